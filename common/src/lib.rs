@@ -5,7 +5,7 @@ Shared configuration types and DB helper functions for MyNewsLens.
 
 This file provides:
 - Config data structures (deserialized from TOML)
-- An async loader for a TOML config file
+- An async loader for a TOML config file, and semantic validation of the result
 - Helpers to initialize and migrate an SQLite database
 */
 
@@ -17,10 +17,13 @@ use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
 
+mod validation;
+pub use validation::ConfigError;
+
 /// Database configuration section
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
-    /// Path to the sqlite database file (e.g. "data/mynewslens.db")
+    /// Path to the sqlite database file (e.g. "data/newscope.db")
     pub path: String,
 }
 
@@ -29,6 +32,17 @@ pub struct DatabaseConfig {
 pub struct SchedulerConfig {
     /// List of wall-clock times in "HH:MM" 24h format when ingestion should run
     pub times: Vec<String>,
+    /// Feeds fetched at once across all domains (default 8)
+    pub max_concurrent_fetches: Option<usize>,
+    /// "cadence" (per-feed poll intervals only), "times" (full passes at `times` only)
+    /// or "both" (default)
+    pub mode: Option<String>,
+    /// Clock `times` are read against: "local" (default), "UTC" or a fixed offset like "+02:00"
+    pub timezone: Option<String>,
+    /// Longest interval (minutes) a failing feed's poll interval doubles up to (default 1440)
+    pub max_backoff_minutes: Option<i64>,
+    /// Consecutive permanent (4xx) failures after which a feed is disabled (default 5, 0 = never)
+    pub max_consecutive_failures: Option<u32>,
 }
 
 /// Politeness / fetching configuration
@@ -39,13 +53,33 @@ pub struct PolitenessConfig {
     pub max_response_bytes: Option<u64>,
     pub fetch_timeout_seconds: Option<u64>,
     pub respect_robots_txt: Option<bool>,
+    /// How long a site's robots.txt is cached, in seconds (default 86400)
+    pub robots_ttl_seconds: Option<u64>,
+    /// Retry failed feed parses on a sanitized copy (opt-in: alters bytes)
+    pub lenient_parsing: Option<bool>,
+    /// Seconds an idle pooled connection is kept for reuse
+    pub pool_idle_timeout_seconds: Option<u64>,
+    /// Idle connections kept per host (0 disables connection reuse)
+    pub pool_max_idle_per_host: Option<usize>,
+    /// TCP keep-alive interval in seconds (0 disables it)
+    pub tcp_keepalive_seconds: Option<u64>,
 }
 
 /// Local LLM config (used if `llm.adapter = "local"`)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocalLlmConfig {
+    /// GGUF model file
     pub model_path: Option<String>,
     pub max_threads: Option<u32>,
+    /// Context window in tokens (default 4096)
+    pub context_size: Option<u32>,
+    /// llama.cpp completion binary (default "llama-cli", looked up in PATH)
+    pub llama_cli: Option<String>,
+    /// llama.cpp embedding binary (default "llama-embedding")
+    pub llama_embedding: Option<String>,
+    /// Per-call timeout (default 300)
+    pub timeout_seconds: Option<u64>,
+    pub max_tokens: Option<usize>,
 }
 
 /// Remote LLM config (used if `llm.adapter = "remote"`)
@@ -56,6 +90,18 @@ pub struct RemoteLlmConfig {
     pub model: Option<String>,
     pub timeout_seconds: Option<u64>,
     pub max_tokens: Option<usize>,
+    /// Max in-flight requests for batch passes (currently used by embeddings, default 4)
+    pub concurrency: Option<usize>,
+    /// Max items handled per worker pass (currently used by embeddings)
+    pub batch_size: Option<usize>,
+    /// Relative share of traffic when listed under `background_endpoints` (default 1.0)
+    pub weight: Option<f64>,
+    /// Retries after a connection error, 5xx or 429 (default 3, 0 disables)
+    pub max_retries: Option<u32>,
+    /// First retry delay in milliseconds, doubled on each retry (default 1000)
+    pub retry_base_delay_ms: Option<u64>,
+    /// Size of the vectors returned by the embedding model (default 384)
+    pub embedding_dim: Option<usize>,
 }
 
 /// LLM top-level config grouping local/remote specifics
@@ -73,6 +119,50 @@ pub struct LlmConfig {
     // Compatibility redirects
     pub background: Option<RemoteLlmConfig>,
     pub interactive: Option<RemoteLlmConfig>,
+    // Weighted pool shared by summarization/personalization (overrides `background`)
+    #[serde(default)]
+    pub background_endpoints: Vec<RemoteLlmConfig>,
+    // Cap on article text sent for summarization
+    pub summarization_input: Option<SummarizationInputConfig>,
+    pub embedding_text: Option<EmbeddingTextConfig>,
+    // Per-user limit on chat messages sent to the LLM
+    pub chat_rate_limit: Option<ChatRateLimitConfig>,
+}
+
+/// Article deduplication settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupConfig {
+    /// Merge entries with different URLs but identical normalized content (default false)
+    pub merge_by_content_hash: Option<bool>,
+    /// Query parameters removed from article URLs before matching URL variants;
+    /// a trailing `*` matches a prefix (default: utm_*, fbclid, gclid, ...)
+    pub strip_query_params: Option<Vec<String>>,
+}
+
+/// Truncation of article text before summarization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummarizationInputConfig {
+    /// Max characters sent to the LLM (default 12000, 0 = unlimited)
+    pub max_chars: Option<usize>,
+    /// Characters kept from the end of long articles (default 0)
+    pub keep_tail_chars: Option<usize>,
+}
+
+/// Composition of the text embedded for each article
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingTextConfig {
+    /// Template with {title}, {headline}, {bullets}, {details}, {categories},
+    /// {content} and {summary} placeholders (default "{title}\n{summary}")
+    pub template: Option<String>,
+    /// Max characters embedded (default 0 = unlimited)
+    pub max_chars: Option<usize>,
+}
+
+/// Token-bucket limit on websocket chat messages (per user)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatRateLimitConfig {
+    pub messages_per_minute: Option<f64>,
+    pub burst: Option<u32>,
 }
 
 /// Simple feed descriptor used in per-user initial feed lists
@@ -102,6 +192,127 @@ pub struct ScoringConfig {
     pub w_src: Option<f64>,
     pub w_novel: Option<f64>,
     pub serendipity: Option<f64>,
+    /// Age (hours) at which the recency boost has halved (default 24)
+    pub recency_half_life_hours: Option<f64>,
+    /// How far each pass moves category weights toward what the user's ratings
+    /// imply (0.0-1.0, default 0.2; 0 disables learning from ratings)
+    pub rating_learning_rate: Option<f64>,
+}
+
+/// Regex replacement applied to a single entry field before storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegexReplacement {
+    pub field: String, // "title" or "content"
+    pub pattern: String,
+    #[serde(default)]
+    pub replacement: String,
+}
+
+/// Per-feed transform rules applied to entries before dedup/insert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedTransformConfig {
+    pub feed_url: String,
+    pub title_field: Option<String>,   // "title" (default) or "summary"
+    pub content_field: Option<String>, // "content" (default), "summary" or "media_description"
+    #[serde(default)]
+    pub replacements: Vec<RegexReplacement>,
+}
+
+/// Notification delivery settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    /// URL receiving JSON notification events via POST
+    pub webhook_url: Option<String>,
+    /// Personalized relevance (0.0-1.0) at which an article triggers a notification
+    pub min_relevance_to_notify: Option<f64>,
+    /// Only notify users who started a session within this many days (default 7)
+    pub active_within_days: Option<i64>,
+}
+
+/// Press review generation settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressReviewConfig {
+    /// Within this many minutes of a user's last review, new sessions replay it
+    /// instead of generating a new one (0 = disabled)
+    pub min_interval_minutes: Option<i64>,
+    /// Articles first seen longer ago than this never reach a review (0 = no limit)
+    pub catch_up_max_age_hours: Option<i64>,
+    /// Users whose last review is older than this are catching up
+    pub catch_up_after_hours: Option<i64>,
+    /// Maximum articles in a catch-up review
+    pub catch_up_max_articles: Option<i64>,
+    /// Cosine distance between embeddings under which two articles are the
+    /// same story and only the best-ranked one is shown (0 = disabled)
+    pub near_duplicate_distance: Option<f64>,
+}
+
+/// Article summarization and classification settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingConfig {
+    /// Categories the classifier chooses from (default: general news categories)
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Summarization prompt: `{content}` (required) is replaced by the article
+    /// text, `{language}` by its language name (empty when unknown)
+    pub summarization_prompt_template: Option<String>,
+    /// Classification prompt: `{content}` (required) is replaced by the
+    /// headline and key points, `{categories}` by the category list
+    pub classification_prompt_template: Option<String>,
+    /// Users personalized at once for each article (default 4)
+    pub personalization_concurrency: Option<usize>,
+    /// Reuse relevance scores across articles for unchanged profiles (default true)
+    pub relevance_cache: Option<bool>,
+    /// Cosine distance between summary embeddings under which a cached score
+    /// is reused (default 0.05; 0 = identical summaries only)
+    pub relevance_cache_max_distance: Option<f64>,
+}
+
+/// HTTPS termination by the built-in server (PEM files)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Certificate chain
+    pub cert_path: String,
+    /// Private key (PKCS#8, PKCS#1 or SEC1)
+    pub key_path: String,
+}
+
+/// HTTP server settings. `bind`/`port` are also read directly by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerConfig {
+    pub bind: Option<String>,
+    pub port: Option<u16>,
+    pub read_only: Option<bool>,
+    pub tls: Option<TlsConfig>,
+    /// Seconds between pings sent on chat websockets (default 30)
+    pub ws_ping_interval_seconds: Option<u64>,
+    /// Seconds without a pong or message after which a chat websocket is
+    /// closed (default 90)
+    pub ws_idle_timeout_seconds: Option<u64>,
+    /// Origins (e.g. "https://news.example.com", or "*") allowed to call the
+    /// API from a browser; empty or unset sends no CORS headers
+    pub cors_allowed_origins: Option<Vec<String>>,
+}
+
+/// Outbound network settings (feeds, scraping, LLM APIs, webhooks)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// Proxy for http:// URLs (falls back to HTTP_PROXY)
+    pub http_proxy: Option<String>,
+    /// Proxy for https:// URLs (falls back to HTTPS_PROXY)
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts/domains/CIDRs reached directly (falls back to NO_PROXY)
+    pub no_proxy: Option<String>,
+}
+
+/// Authentication settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Lifetime of issued JWTs, in hours (default 24)
+    pub token_ttl_hours: Option<u64>,
+    /// Failed logins (per client IP or per username) before answering 429 (default 5)
+    pub max_failed_logins: Option<u32>,
+    /// Window in which failed logins are counted, in seconds (default 900)
+    pub failed_login_window_seconds: Option<u64>,
 }
 
 /// Admin / maintenance config
@@ -109,12 +320,18 @@ pub struct ScoringConfig {
 pub struct AdminConfig {
     pub auto_migrate: Option<bool>,
     pub diagnostics_dir: Option<String>,
+    /// OPML file whose feeds are added to the catalog (unsubscribed) at startup
+    pub bootstrap_opml: Option<String>,
+    /// Delete articles first seen more than this many days ago (unset or 0: keep forever)
+    pub retention_days: Option<u32>,
 }
 
 /// Top-level application configuration (deserialized from config.toml)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub database: DatabaseConfig,
+    pub server: Option<ServerConfig>,
+    pub auth: Option<AuthConfig>,
     pub scheduler: SchedulerConfig,
     pub politeness: Option<PolitenessConfig>,
     pub llm: Option<LlmConfig>,
@@ -122,6 +339,13 @@ pub struct Config {
     pub users: Vec<UserConfig>,
     pub scoring: Option<ScoringConfig>,
     pub admin: Option<AdminConfig>,
+    pub notifications: Option<NotificationsConfig>,
+    pub dedup: Option<DedupConfig>,
+    pub press_review: Option<PressReviewConfig>,
+    pub processing: Option<ProcessingConfig>,
+    pub network: Option<NetworkConfig>,
+    #[serde(default)]
+    pub feed_transforms: Vec<FeedTransformConfig>,
 }
 
 impl Config {
@@ -210,7 +434,7 @@ pub async fn run_migrations(pool: &SqlitePool) -> Result<()> {
 /// - connection timeout default provided by `sqlx`
 ///
 /// Example:
-///   let pool = init_db_pool("data/mynewslens.db").await?;
+///   let pool = init_db_pool("data/newscope.db").await?;
 pub async fn init_db_pool(path: &str) -> Result<SqlitePool> {
     // Ensure parent directory exists
     if let Some(parent) = Path::new(path).parent() {
@@ -224,6 +448,7 @@ pub async fn init_db_pool(path: &str) -> Result<SqlitePool> {
     // SQLite connection attempt.
     tokio::fs::OpenOptions::new()
         .create(true)
+        .truncate(false)
         .write(true)
         .open(path)
         .await
@@ -250,6 +475,20 @@ pub async fn init_db_pool(path: &str) -> Result<SqlitePool> {
     Ok(pool)
 }
 
+/// Checkpoint the WAL into the main database file and close the pool.
+///
+/// Call once on shutdown, after background tasks have stopped writing: closing
+/// waits for checked-out connections to be returned, and the checkpoint
+/// truncates the `-wal` file so the database is left self-contained.
+pub async fn close_db_pool(pool: &SqlitePool) -> Result<()> {
+    let checkpoint = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(pool)
+        .await
+        .context("Failed to checkpoint WAL");
+    pool.close().await;
+    checkpoint.map(|_| ())
+}
+
 /// Convenience: sleep helper used by implementations (kept public for tests)
 pub async fn sleep_millis(ms: u64) {
     tokio::time::sleep(Duration::from_millis(ms)).await;
@@ -259,17 +498,17 @@ pub async fn sleep_millis(ms: u64) {
 /// This function will:
 ///  - INSERT OR IGNORE a row for each configured user (so it is safe to call multiple times)
 ///  - UPDATE the `display_name` and `password_hash` if those fields are provided in the config
+///
 /// Usage: call this once after running migrations so the `users` table contains the configured users.
 pub async fn sync_users(config: &Config, pool: &SqlitePool) -> Result<()> {
     for u in &config.users {
-        // Insert if missing (prefs_json left NULL for now)
+        // Insert if missing (preferences live in user_preferences, not on the users row)
         sqlx::query(
-            "INSERT OR IGNORE INTO users (username, display_name, password_hash, prefs_json) VALUES (?, ?, ?, ?)"
+            "INSERT OR IGNORE INTO users (username, display_name, password_hash) VALUES (?, ?, ?)"
         )
         .bind(&u.username)
         .bind(u.display_name.clone())
         .bind(u.password_hash.clone())
-        .bind(None::<String>)
         .execute(pool)
         .await
         .with_context(|| format!("failed to insert or ignore user {}", u.username))?;
@@ -330,5 +569,13 @@ mod tests {
         // Simple sanity: acquire a connection
         let conn = pool.acquire().await.expect("acquire conn");
         drop(conn);
+
+        // Closing checkpoints the WAL back into the database file
+        sqlx::query("CREATE TABLE t (x INTEGER)").execute(&pool).await.expect("create");
+        sqlx::query("INSERT INTO t VALUES (1)").execute(&pool).await.expect("insert");
+        close_db_pool(&pool).await.expect("close pool");
+        assert!(pool.is_closed());
+        let wal = dir.join("mynews.db-wal");
+        assert!(!wal.exists() || fs::metadata(&wal).expect("wal metadata").len() == 0);
     }
 }
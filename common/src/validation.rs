@@ -0,0 +1,575 @@
+//! Semantic checks on a loaded [`Config`].
+//!
+//! Parsing only proves the TOML has the right shape; [`Config::validate`]
+//! catches values that would otherwise fail much later (a scheduler time like
+//! "25:99", a remote adapter without any endpoint, a weight out of range, an
+//! unwritable database path), so the process can stop at startup with the full
+//! list of problems.
+
+use crate::{Config, RemoteLlmConfig};
+use chrono::NaiveTime;
+use std::fmt;
+use std::path::Path;
+
+/// One problem found in the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// Dotted path of the offending setting, e.g. `scheduler.times[1]`
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Check the configuration for values that cannot work, returning every
+    /// problem found rather than stopping at the first.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+        self.validate_database(&mut errors);
+        self.validate_scheduler(&mut errors);
+        self.validate_llm(&mut errors);
+        self.validate_ranges(&mut errors);
+        self.validate_users(&mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_database(&self, errors: &mut Vec<ConfigError>) {
+        let path = self.database.path.trim();
+        if path.is_empty() {
+            errors.push(ConfigError::new("database.path", "must not be empty"));
+            return;
+        }
+        if let Err(message) = check_writable(Path::new(path)) {
+            errors.push(ConfigError::new("database.path", message));
+        }
+    }
+
+    fn validate_scheduler(&self, errors: &mut Vec<ConfigError>) {
+        let scheduler = &self.scheduler;
+        for (i, time) in scheduler.times.iter().enumerate() {
+            if NaiveTime::parse_from_str(time.trim(), "%H:%M").is_err() {
+                errors.push(ConfigError::new(
+                    format!("scheduler.times[{}]", i),
+                    format!("invalid time {:?} (expected HH:MM, 00:00 to 23:59)", time),
+                ));
+            }
+        }
+        let mode = scheduler.mode.as_deref().map(|m| m.trim().to_ascii_lowercase());
+        match mode.as_deref() {
+            None | Some("cadence") | Some("both") => {}
+            Some("times") if scheduler.times.is_empty() => errors.push(ConfigError::new(
+                "scheduler.mode",
+                "\"times\" needs at least one entry in scheduler.times",
+            )),
+            Some("times") => {}
+            Some(other) => errors.push(ConfigError::new(
+                "scheduler.mode",
+                format!("unknown mode {:?} (expected cadence, times or both)", other),
+            )),
+        }
+        if let Some(tz) = &scheduler.timezone {
+            if !valid_timezone(tz) {
+                errors.push(ConfigError::new(
+                    "scheduler.timezone",
+                    format!("invalid timezone {:?} (expected local, UTC or +HH:MM)", tz),
+                ));
+            }
+        }
+        if scheduler.max_concurrent_fetches == Some(0) {
+            errors.push(ConfigError::new("scheduler.max_concurrent_fetches", "must be at least 1"));
+        }
+        if scheduler.max_backoff_minutes.is_some_and(|m| m < 1) {
+            errors.push(ConfigError::new("scheduler.max_backoff_minutes", "must be at least 1"));
+        }
+    }
+
+    fn validate_llm(&self, errors: &mut Vec<ConfigError>) {
+        let Some(llm) = &self.llm else {
+            return;
+        };
+        match llm.adapter.as_deref() {
+            None | Some("none") => {}
+            Some("local") => match &llm.local {
+                Some(local) if local.model_path.as_deref().is_some_and(|p| !p.trim().is_empty()) => {}
+                Some(_) => errors.push(ConfigError::new(
+                    "llm.local.model_path",
+                    "required when llm.adapter = \"local\"",
+                )),
+                None => errors.push(ConfigError::new("llm.local", "required when llm.adapter = \"local\"")),
+            },
+            Some("remote") => {
+                let has_endpoint = [
+                    &llm.remote,
+                    &llm.summarization,
+                    &llm.personalization,
+                    &llm.embedding,
+                    &llm.interaction,
+                    &llm.background,
+                    &llm.interactive,
+                ]
+                .iter()
+                .any(|c| c.is_some())
+                    || !llm.background_endpoints.is_empty();
+                if !has_endpoint {
+                    errors.push(ConfigError::new(
+                        "llm.remote",
+                        "llm.adapter = \"remote\" needs an endpoint: [llm.remote] or a task section \
+                         ([llm.summarization], [llm.interaction], ...)",
+                    ));
+                }
+            }
+            Some(other) => errors.push(ConfigError::new(
+                "llm.adapter",
+                format!("unknown adapter {:?} (expected local, remote or none)", other),
+            )),
+        }
+
+        let named = [
+            ("llm.remote", &llm.remote),
+            ("llm.summarization", &llm.summarization),
+            ("llm.personalization", &llm.personalization),
+            ("llm.embedding", &llm.embedding),
+            ("llm.interaction", &llm.interaction),
+            ("llm.background", &llm.background),
+            ("llm.interactive", &llm.interactive),
+        ];
+        for (name, remote) in named {
+            if let Some(remote) = remote {
+                validate_remote(name, remote, errors);
+            }
+        }
+        for (i, remote) in llm.background_endpoints.iter().enumerate() {
+            validate_remote(&format!("llm.background_endpoints[{}]", i), remote, errors);
+        }
+        if let Some(limit) = &llm.chat_rate_limit {
+            if limit.messages_per_minute.is_some_and(|m| !(m > 0.0 && m.is_finite())) {
+                errors.push(ConfigError::new(
+                    "llm.chat_rate_limit.messages_per_minute",
+                    "must be a positive number",
+                ));
+            }
+        }
+    }
+
+    fn validate_ranges(&self, errors: &mut Vec<ConfigError>) {
+        if let Some(server) = &self.server {
+            for (name, value) in [
+                ("ws_ping_interval_seconds", server.ws_ping_interval_seconds),
+                ("ws_idle_timeout_seconds", server.ws_idle_timeout_seconds),
+            ] {
+                if value == Some(0) {
+                    errors.push(ConfigError::new(format!("server.{}", name), "must be at least 1"));
+                }
+            }
+            for (i, origin) in server.cors_allowed_origins.iter().flatten().enumerate() {
+                check_origin(errors, &format!("server.cors_allowed_origins[{}]", i), origin);
+            }
+            if let (Some(ping), Some(idle)) = (server.ws_ping_interval_seconds, server.ws_idle_timeout_seconds) {
+                if idle > 0 && idle <= ping {
+                    errors.push(ConfigError::new(
+                        "server.ws_idle_timeout_seconds",
+                        "must be longer than ws_ping_interval_seconds",
+                    ));
+                }
+            }
+        }
+        if let Some(auth) = &self.auth {
+            if auth.max_failed_logins == Some(0) {
+                errors.push(ConfigError::new("auth.max_failed_logins", "must be at least 1"));
+            }
+            if auth.failed_login_window_seconds == Some(0) {
+                errors.push(ConfigError::new("auth.failed_login_window_seconds", "must be at least 1"));
+            }
+        }
+        if let Some(scoring) = &self.scoring {
+            for (name, value) in [
+                ("w_pref", scoring.w_pref),
+                ("w_red", scoring.w_red),
+                ("w_recency", scoring.w_recency),
+                ("w_src", scoring.w_src),
+                ("w_novel", scoring.w_novel),
+                ("recency_half_life_hours", scoring.recency_half_life_hours),
+            ] {
+                check_range(errors, &format!("scoring.{}", name), value, 0.0, f64::MAX);
+            }
+            check_range(errors, "scoring.serendipity", scoring.serendipity, 0.0, 1.0);
+            check_range(errors, "scoring.rating_learning_rate", scoring.rating_learning_rate, 0.0, 1.0);
+        }
+        if let Some(notifications) = &self.notifications {
+            check_range(
+                errors,
+                "notifications.min_relevance_to_notify",
+                notifications.min_relevance_to_notify,
+                0.0,
+                1.0,
+            );
+            if let Some(url) = &notifications.webhook_url {
+                check_url(errors, "notifications.webhook_url", url);
+            }
+        }
+        if let Some(review) = &self.press_review {
+            check_range(
+                errors,
+                "press_review.near_duplicate_distance",
+                review.near_duplicate_distance,
+                0.0,
+                2.0,
+            );
+            for (name, value) in [
+                ("min_interval_minutes", review.min_interval_minutes),
+                ("catch_up_max_age_hours", review.catch_up_max_age_hours),
+                ("catch_up_after_hours", review.catch_up_after_hours),
+                ("catch_up_max_articles", review.catch_up_max_articles),
+            ] {
+                if value.is_some_and(|v| v < 0) {
+                    errors.push(ConfigError::new(format!("press_review.{}", name), "must not be negative"));
+                }
+            }
+        }
+        if let Some(processing) = &self.processing {
+            let mut seen = std::collections::HashSet::new();
+            for (i, category) in processing.categories.iter().enumerate() {
+                let field = format!("processing.categories[{}]", i);
+                let category = category.trim();
+                if category.is_empty() {
+                    errors.push(ConfigError::new(field, "must not be empty"));
+                } else if category.contains(',') {
+                    // The classifier's answer is a comma-separated list
+                    errors.push(ConfigError::new(field, "must not contain a comma"));
+                } else if !seen.insert(category.to_lowercase()) {
+                    errors.push(ConfigError::new(field, format!("{:?} is listed more than once", category)));
+                }
+            }
+            for (name, template) in [
+                ("summarization_prompt_template", &processing.summarization_prompt_template),
+                ("classification_prompt_template", &processing.classification_prompt_template),
+            ] {
+                if template.as_deref().is_some_and(|t| !t.contains("{content}")) {
+                    errors.push(ConfigError::new(
+                        format!("processing.{}", name),
+                        "must contain the {content} placeholder",
+                    ));
+                }
+            }
+            if processing.personalization_concurrency == Some(0) {
+                errors.push(ConfigError::new("processing.personalization_concurrency", "must be at least 1"));
+            }
+            if processing.relevance_cache_max_distance.is_some_and(|d| !(0.0..=1.0).contains(&d)) {
+                errors.push(ConfigError::new("processing.relevance_cache_max_distance", "must be between 0.0 and 1.0"));
+            }
+        }
+        if let Some(network) = &self.network {
+            for (name, proxy) in [("http_proxy", &network.http_proxy), ("https_proxy", &network.https_proxy)] {
+                if let Some(proxy) = proxy.as_deref().filter(|p| !p.trim().is_empty()) {
+                    check_url(errors, &format!("network.{}", name), proxy);
+                }
+            }
+        }
+    }
+
+    fn validate_users(&self, errors: &mut Vec<ConfigError>) {
+        let mut seen = std::collections::HashSet::new();
+        for (i, user) in self.users.iter().enumerate() {
+            let username = user.username.trim();
+            if username.is_empty() {
+                errors.push(ConfigError::new(format!("users[{}].username", i), "must not be empty"));
+            } else if !seen.insert(username.to_string()) {
+                errors.push(ConfigError::new(
+                    format!("users[{}].username", i),
+                    format!("{:?} is defined more than once", username),
+                ));
+            }
+        }
+    }
+}
+
+fn validate_remote(name: &str, remote: &RemoteLlmConfig, errors: &mut Vec<ConfigError>) {
+    if let Some(url) = &remote.api_url {
+        check_url(errors, &format!("{}.api_url", name), url);
+    }
+    if remote.api_key_env.as_deref().is_some_and(|v| v.trim().is_empty()) {
+        errors.push(ConfigError::new(format!("{}.api_key_env", name), "must not be empty"));
+    }
+    if remote.weight.is_some_and(|w| !(w > 0.0 && w.is_finite())) {
+        errors.push(ConfigError::new(format!("{}.weight", name), "must be a positive number"));
+    }
+    for (field, value) in [
+        ("embedding_dim", remote.embedding_dim),
+        ("concurrency", remote.concurrency),
+        ("batch_size", remote.batch_size),
+    ] {
+        if value == Some(0) {
+            errors.push(ConfigError::new(format!("{}.{}", name, field), "must be at least 1"));
+        }
+    }
+}
+
+fn check_range(errors: &mut Vec<ConfigError>, field: &str, value: Option<f64>, min: f64, max: f64) {
+    let Some(value) = value else {
+        return;
+    };
+    if !value.is_finite() || value < min || value > max {
+        let message = if max == f64::MAX {
+            format!("{} is out of range (expected {} or more)", value, min)
+        } else {
+            format!("{} is out of range (expected {} to {})", value, min, max)
+        };
+        errors.push(ConfigError::new(field, message));
+    }
+}
+
+fn check_url(errors: &mut Vec<ConfigError>, field: &str, value: &str) {
+    match url::Url::parse(value.trim()) {
+        Ok(url) if matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") => {}
+        Ok(url) => errors.push(ConfigError::new(field, format!("unsupported URL scheme {:?}", url.scheme()))),
+        Err(e) => errors.push(ConfigError::new(field, format!("invalid URL {:?}: {}", value, e))),
+    }
+}
+
+/// `"*"`, or a browser origin: http(s) scheme, host and optional port only.
+fn check_origin(errors: &mut Vec<ConfigError>, field: &str, value: &str) {
+    let value = value.trim();
+    if value == "*" {
+        return;
+    }
+    match url::Url::parse(value) {
+        Ok(url) if !matches!(url.scheme(), "http" | "https") => {
+            errors.push(ConfigError::new(field, format!("unsupported origin scheme {:?}", url.scheme())))
+        }
+        Ok(url) if url.path() != "/" || url.query().is_some() || url.fragment().is_some() => {
+            errors.push(ConfigError::new(field, format!("{:?} is not an origin (no path allowed)", value)))
+        }
+        Ok(_) => {}
+        Err(e) => errors.push(ConfigError::new(field, format!("invalid origin {:?}: {}", value, e))),
+    }
+}
+
+/// Same forms as the scheduler accepts: "local", "UTC", "Z" or "+HH:MM".
+fn valid_timezone(value: &str) -> bool {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("local") || value.eq_ignore_ascii_case("utc") || value == "Z" {
+        return true;
+    }
+    value
+        .strip_prefix('+')
+        .or_else(|| value.strip_prefix('-'))
+        .is_some_and(|rest| NaiveTime::parse_from_str(rest, "%H:%M").is_ok())
+}
+
+/// Whether the database at `path` can be opened for writing, or created: an
+/// existing file is opened without being modified, otherwise the nearest
+/// existing ancestor directory must accept new files.
+fn check_writable(path: &Path) -> Result<(), String> {
+    if path.is_dir() {
+        return Err(format!("{} is a directory", path.display()));
+    }
+    if path.exists() {
+        return std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map(|_| ())
+            .map_err(|e| format!("{} is not writable: {}", path.display(), e));
+    }
+    let mut dir = path.parent();
+    while let Some(candidate) = dir {
+        let candidate = if candidate.as_os_str().is_empty() { Path::new(".") } else { candidate };
+        if candidate.exists() {
+            if !candidate.is_dir() {
+                return Err(format!("{} is not a directory", candidate.display()));
+            }
+            let probe = candidate.join(format!(".newscope-write-test-{}", std::process::id()));
+            return match std::fs::File::create(&probe) {
+                Ok(_) => {
+                    let _ = std::fs::remove_file(&probe);
+                    Ok(())
+                }
+                Err(e) => Err(format!("cannot create files in {}: {}", candidate.display(), e)),
+            };
+        }
+        dir = candidate.parent();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(extra: &str) -> Config {
+        let dir = std::env::temp_dir();
+        toml::from_str(&format!(
+            "[database]\npath = {:?}\n{}",
+            dir.join("validate-test.db").to_string_lossy(),
+            extra
+        ))
+        .expect("parse config")
+    }
+
+    fn fields(config: &Config) -> Vec<String> {
+        config
+            .validate()
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| e.field)
+            .collect()
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        let mut config: Config = toml::from_str(include_str!("../../config.default.toml")).expect("parse defaults");
+        config.database.path = std::env::temp_dir().join("validate-test.db").to_string_lossy().to_string();
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let config = config(
+            r#"
+            [scheduler]
+            times = ["07:30", "25:99"]
+            mode = "hourly"
+            timezone = "Europe/Paris"
+
+            [llm]
+            adapter = "remote"
+
+            [scoring]
+            w_pref = -1.0
+            serendipity = 1.5
+
+            [[users]]
+            username = "alice"
+            [[users]]
+            username = "alice"
+            "#,
+        );
+        let errors = config.validate().unwrap_err();
+        assert_eq!(
+            errors.iter().map(|e| e.field.as_str()).collect::<Vec<_>>(),
+            vec![
+                "scheduler.times[1]",
+                "scheduler.mode",
+                "scheduler.timezone",
+                "llm.remote",
+                "scoring.w_pref",
+                "scoring.serendipity",
+                "users[1].username",
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "scheduler.times[1]: invalid time \"25:99\" (expected HH:MM, 00:00 to 23:59)"
+        );
+    }
+
+    #[test]
+    fn test_llm_adapter_consistency() {
+        let remote = config("[scheduler]\ntimes = []\n[llm]\nadapter = \"remote\"\n[llm.interaction]\nmodel = \"m\"");
+        assert!(fields(&remote).is_empty());
+        let local = config("[scheduler]\ntimes = []\n[llm]\nadapter = \"local\"\n[llm.local]\nmax_threads = 2");
+        assert_eq!(fields(&local), vec!["llm.local.model_path"]);
+        let bad = config(
+            "[scheduler]\ntimes = []\n[llm]\nadapter = \"cloud\"\n[llm.remote]\napi_url = \"not a url\"\nweight = 0.0",
+        );
+        assert_eq!(fields(&bad), vec!["llm.adapter", "llm.remote.api_url", "llm.remote.weight"]);
+        let times_only = config("[scheduler]\ntimes = []\nmode = \"times\"");
+        assert_eq!(fields(&times_only), vec!["scheduler.mode"]);
+    }
+
+    #[test]
+    fn test_processing_templates_and_categories() {
+        let valid = config(
+            "[scheduler]\ntimes = []\n[processing]\ncategories = [\"physics\", \"biology\"]\n\
+             classification_prompt_template = \"Topics of {content} among {categories}\"",
+        );
+        assert!(fields(&valid).is_empty());
+        let invalid = config(
+            "[scheduler]\ntimes = []\n[processing]\ncategories = [\"physics\", \" \", \"a, b\", \"Physics\"]\n\
+             summarization_prompt_template = \"Summarize this\"\npersonalization_concurrency = 0\nrelevance_cache_max_distance = 1.5",
+        );
+        assert_eq!(
+            fields(&invalid),
+            vec![
+                "processing.categories[1]",
+                "processing.categories[2]",
+                "processing.categories[3]",
+                "processing.summarization_prompt_template",
+                "processing.personalization_concurrency",
+                "processing.relevance_cache_max_distance",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_websocket_heartbeat() {
+        let valid = config("[scheduler]\ntimes = []\n[server]\nws_ping_interval_seconds = 10\nws_idle_timeout_seconds = 25");
+        assert!(fields(&valid).is_empty());
+        let zero = config("[scheduler]\ntimes = []\n[server]\nws_ping_interval_seconds = 0");
+        assert_eq!(fields(&zero), vec!["server.ws_ping_interval_seconds"]);
+        let shorter = config("[scheduler]\ntimes = []\n[server]\nws_ping_interval_seconds = 30\nws_idle_timeout_seconds = 30");
+        assert_eq!(fields(&shorter), vec!["server.ws_idle_timeout_seconds"]);
+    }
+
+    #[test]
+    fn test_cors_origins() {
+        let valid = config(
+            "[scheduler]\ntimes = []\n[server]\ncors_allowed_origins = [\"https://news.example.com\", \"http://localhost:5173/\", \"*\"]",
+        );
+        assert!(fields(&valid).is_empty());
+        let invalid = config(
+            "[scheduler]\ntimes = []\n[server]\ncors_allowed_origins = [\"news.example.com\", \"https://a.example/app\", \"ftp://a.example\"]",
+        );
+        assert_eq!(
+            fields(&invalid),
+            vec![
+                "server.cors_allowed_origins[0]",
+                "server.cors_allowed_origins[1]",
+                "server.cors_allowed_origins[2]"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_login_throttling() {
+        let valid = config("[scheduler]\ntimes = []\n[auth]\nmax_failed_logins = 3\nfailed_login_window_seconds = 60");
+        assert!(fields(&valid).is_empty());
+        let zero = config("[scheduler]\ntimes = []\n[auth]\nmax_failed_logins = 0\nfailed_login_window_seconds = 0");
+        assert_eq!(fields(&zero), vec!["auth.max_failed_logins", "auth.failed_login_window_seconds"]);
+    }
+
+    #[test]
+    fn test_database_path_must_be_writable() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = config("[scheduler]\ntimes = []");
+        // Missing parent directories are created at startup
+        config.database.path = dir.path().join("nested/dir/app.db").to_string_lossy().to_string();
+        assert!(fields(&config).is_empty());
+        config.database.path = dir.path().to_string_lossy().to_string();
+        assert_eq!(fields(&config), vec!["database.path"]);
+        let file = dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        config.database.path = file.join("app.db").to_string_lossy().to_string();
+        assert_eq!(fields(&config), vec!["database.path"]);
+    }
+}
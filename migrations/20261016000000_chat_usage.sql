@@ -0,0 +1,8 @@
+-- Track model and token usage for assistant chat turns
+ALTER TABLE chat_messages ADD COLUMN model TEXT;
+ALTER TABLE chat_messages ADD COLUMN prompt_tokens INTEGER;
+ALTER TABLE chat_messages ADD COLUMN completion_tokens INTEGER;
+
+-- Link usage log entries to the chat session that caused them (NULL for background work)
+ALTER TABLE llm_usage_log ADD COLUMN session_id INTEGER;
+CREATE INDEX IF NOT EXISTS idx_llm_usage_log_created ON llm_usage_log(created_at);
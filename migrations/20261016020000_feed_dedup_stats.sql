@@ -0,0 +1,13 @@
+-- Cumulative per-feed deduplication counters, updated after each worker sweep
+CREATE TABLE IF NOT EXISTS feed_dedup_stats (
+    feed_id INTEGER PRIMARY KEY,
+    new_articles INTEGER NOT NULL DEFAULT 0,
+    already_seen INTEGER NOT NULL DEFAULT 0,
+    merged_by_url INTEGER NOT NULL DEFAULT 0,
+    merged_by_hash INTEGER NOT NULL DEFAULT 0,
+    hash_duplicates_unmerged INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    FOREIGN KEY(feed_id) REFERENCES feeds(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_articles_canonical_hash ON articles(canonical_hash);
@@ -0,0 +1,2 @@
+-- Feeds that deliver complete articles: skip the scrape fallback for them
+ALTER TABLE feeds ADD COLUMN full_content BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,15 @@
+-- Recent fetch latency/size samples per feed (pruned to the last 50 per feed)
+CREATE TABLE IF NOT EXISTS feed_fetch_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    feed_id INTEGER NOT NULL,
+    fetched_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    latency_ms INTEGER NOT NULL,
+    response_bytes INTEGER,
+    http_status INTEGER,
+    attempts INTEGER NOT NULL DEFAULT 1,
+    success BOOLEAN NOT NULL,
+    error TEXT,
+    FOREIGN KEY(feed_id) REFERENCES feeds(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_feed_fetch_log_feed ON feed_fetch_log(feed_id, id);
@@ -0,0 +1,6 @@
+-- Instance-wide key/value settings (e.g. fingerprint of the embedding text composition)
+CREATE TABLE IF NOT EXISTS app_settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
@@ -0,0 +1,2 @@
+-- Poll interval (minutes) suggested by the feed itself via <ttl> or sy:updatePeriod
+ALTER TABLE feeds ADD COLUMN declared_update_minutes INTEGER;
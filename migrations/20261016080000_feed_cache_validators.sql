@@ -0,0 +1,3 @@
+-- HTTP validators from the last successful fetch, for conditional GETs
+ALTER TABLE feeds ADD COLUMN etag TEXT;
+ALTER TABLE feeds ADD COLUMN last_modified TEXT;
@@ -0,0 +1,4 @@
+-- Hash of title + normalized URL, matching tracking/mobile variants of a known article URL
+ALTER TABLE articles ADD COLUMN url_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_articles_url_hash ON articles(url_hash);
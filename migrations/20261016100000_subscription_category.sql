@@ -0,0 +1,2 @@
+-- Folder a subscription was filed under in an imported OPML document
+ALTER TABLE subscriptions ADD COLUMN category TEXT;
@@ -0,0 +1,6 @@
+-- Full-text index over article title/content and summary headline/bullets
+-- (rowid = articles.id). Filled by the server at startup when empty.
+CREATE VIRTUAL TABLE IF NOT EXISTS articles_fts USING fts5(
+    title, content, headline, bullets,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
@@ -0,0 +1,2 @@
+-- Language the article is written in (ISO 639-1), detected at store time
+ALTER TABLE articles ADD COLUMN language TEXT;
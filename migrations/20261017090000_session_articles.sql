@@ -0,0 +1,15 @@
+-- Articles shown in a chat session, as the user saw them (refined title and
+-- summary), so chat context survives reconnects and server restarts.
+-- No foreign key to articles: the row keeps its own text past retention.
+CREATE TABLE IF NOT EXISTS session_articles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id INTEGER NOT NULL,
+    article_id INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    details TEXT,
+    also_covered_by TEXT,
+    created_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    UNIQUE(session_id, article_id)
+);
@@ -0,0 +1,6 @@
+-- processing_jobs becomes a queue consumed by the worker: a job is claimed by
+-- setting claimed_at and bumping attempts, so one left 'running' by a crash
+-- can be put back to 'pending' and retried a bounded number of times.
+ALTER TABLE processing_jobs ADD COLUMN claimed_at TIMESTAMP;
+ALTER TABLE processing_jobs ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+CREATE INDEX IF NOT EXISTS idx_processing_jobs_status_id ON processing_jobs(status, id);
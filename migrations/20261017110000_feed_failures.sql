@@ -0,0 +1,3 @@
+-- Consecutive permanent (4xx) fetch failures; past `scheduler.max_consecutive_failures`
+-- the feed gets status 'disabled' and is no longer polled until re-enabled.
+ALTER TABLE feeds ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0;
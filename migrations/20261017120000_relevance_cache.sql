@@ -0,0 +1,15 @@
+-- Relevance evaluations reused across articles for an unchanged profile
+-- (profile_hash covers the interests and preferred categories the prompt shows)
+CREATE TABLE IF NOT EXISTS relevance_cache (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    profile_hash TEXT NOT NULL,
+    article_id INTEGER NOT NULL,
+    content_hash TEXT NOT NULL,
+    embedding BLOB,
+    score REAL NOT NULL,
+    reasons TEXT,
+    created_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    FOREIGN KEY(article_id) REFERENCES articles(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_relevance_cache_profile ON relevance_cache(profile_hash, content_hash);
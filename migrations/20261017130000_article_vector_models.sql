@@ -0,0 +1,8 @@
+-- Embedding model that produced each vec_articles vector, so vectors of
+-- another model (or of the local hashing fallback) are never compared
+CREATE TABLE IF NOT EXISTS article_vector_models (
+    article_id INTEGER PRIMARY KEY,
+    model TEXT NOT NULL,
+    embedded_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    FOREIGN KEY(article_id) REFERENCES articles(id) ON DELETE CASCADE
+);
@@ -0,0 +1,312 @@
+use anyhow::{anyhow, Context, Result};
+use argon2::password_hash::{PasswordHasher, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+
+use crate::server::AppState;
+
+/// Prefix identifying newscope API keys in logs and config files.
+const API_KEY_PREFIX: &str = "nsk_";
+/// Characters of the key stored in clear to help users tell keys apart.
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// Metadata of an API key (the secret itself is only returned once, on creation).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyInfo {
+    pub id: i64,
+    pub name: Option<String>,
+    /// First characters of the key, e.g. "nsk_1a2b3c4d"
+    pub prefix: String,
+    pub created_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+    pub revoked: bool,
+}
+
+/// Generate a new random API key.
+pub fn generate_api_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", API_KEY_PREFIX, hex)
+}
+
+/// SHA-256 of the key. Keys are 256-bit random values, so a fast hash is
+/// sufficient (unlike passwords, which go through Argon2).
+pub fn hash_api_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// Create a key for `user_id`. Returns its metadata and the clear-text key.
+pub async fn create_api_key(
+    pool: &SqlitePool,
+    user_id: i64,
+    name: Option<&str>,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<(ApiKeyInfo, String)> {
+    let key = generate_api_key();
+    let prefix: String = key.chars().take(DISPLAY_PREFIX_LEN).collect();
+
+    let id = sqlx::query(
+        "INSERT INTO api_keys (user_id, name, key_hash, prefix, expires_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(hash_api_key(&key))
+    .bind(&prefix)
+    .bind(expires_at)
+    .execute(pool)
+    .await
+    .context("failed to insert api key")?
+    .last_insert_rowid();
+
+    let info = list_api_keys(pool, user_id)
+        .await?
+        .into_iter()
+        .find(|k| k.id == id)
+        .context("created api key not found")?;
+    Ok((info, key))
+}
+
+/// List a user's keys, newest first.
+pub async fn list_api_keys(pool: &SqlitePool, user_id: i64) -> Result<Vec<ApiKeyInfo>> {
+    let rows = sqlx::query(
+        "SELECT id, name, prefix, created_at, expires_at, last_used_at, revoked_at
+         FROM api_keys WHERE user_id = ? ORDER BY id DESC",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .context("failed to list api keys")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| ApiKeyInfo {
+            id: r.get("id"),
+            name: r.get("name"),
+            prefix: r.get("prefix"),
+            created_at: r.get("created_at"),
+            expires_at: r.get("expires_at"),
+            last_used_at: r.get("last_used_at"),
+            revoked: r.get::<Option<String>, _>("revoked_at").is_some(),
+        })
+        .collect())
+}
+
+/// Revoke one of the user's keys. Returns false if no such active key exists.
+pub async fn revoke_api_key(pool: &SqlitePool, user_id: i64, key_id: i64) -> Result<bool> {
+    let res = sqlx::query(
+        "UPDATE api_keys SET revoked_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+         WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(key_id)
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .context("failed to revoke api key")?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// Resolve a clear-text key to its user, if the key is active (not revoked or expired).
+pub async fn verify_api_key(pool: &SqlitePool, key: &str) -> Result<Option<i64>> {
+    let row = sqlx::query("SELECT id, user_id, expires_at FROM api_keys WHERE key_hash = ? AND revoked_at IS NULL")
+        .bind(hash_api_key(key))
+        .fetch_optional(pool)
+        .await
+        .context("failed to look up api key")?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let expires_at: Option<DateTime<Utc>> = row.get("expires_at");
+    if expires_at.is_some_and(|t| t <= Utc::now()) {
+        return Ok(None);
+    }
+
+    let id: i64 = row.get("id");
+    sqlx::query("UPDATE api_keys SET last_used_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .context("failed to update api key usage")?;
+    Ok(Some(row.get("user_id")))
+}
+
+/// Resolve a JWT to its user id, rejecting tokens revoked via logout.
+pub async fn verify_jwt(pool: &SqlitePool, token: &str) -> Option<i64> {
+    let user_id = crate::server::decode_jwt(token)?;
+    let revoked = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM revoked_tokens WHERE token = ?")
+        .bind(token)
+        .fetch_one(pool)
+        .await
+        .unwrap_or(0)
+        > 0;
+    (!revoked).then_some(user_id)
+}
+
+/// Authenticated user, from `Authorization: Bearer <jwt>` or `X-API-Key: <key>`.
+/// Browsers can't set headers on a WebSocket handshake, so an upgrade request
+/// may carry the JWT as `?token=<jwt>` instead.
+///
+/// Routes that require it answer 401 on a missing or invalid credential.
+pub struct AuthUser(pub i64);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthUser {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(state) = req.rocket().state::<AppState>() else {
+            return Outcome::Error((Status::InternalServerError, ()));
+        };
+
+        if let Some(key) = req.headers().get_one("X-API-Key") {
+            return match verify_api_key(&state.db, key).await {
+                Ok(Some(user_id)) => Outcome::Success(AuthUser(user_id)),
+                Ok(None) => Outcome::Error((Status::Unauthorized, ())),
+                Err(e) => {
+                    tracing::error!("api key check failed: {:?}", e);
+                    Outcome::Error((Status::InternalServerError, ()))
+                }
+            };
+        }
+
+        if let Some(token) = req
+            .headers()
+            .get_one("Authorization")
+            .and_then(|h| h.strip_prefix("Bearer "))
+        {
+            return match verify_jwt(&state.db, token.trim()).await {
+                Some(user_id) => Outcome::Success(AuthUser(user_id)),
+                None => Outcome::Error((Status::Unauthorized, ())),
+            };
+        }
+
+        let upgrade = req.headers().get_one("Upgrade");
+        if upgrade.is_some_and(|u| u.eq_ignore_ascii_case("websocket")) {
+            if let Some(Ok(token)) = req.query_value::<&str>("token") {
+                return match verify_jwt(&state.db, token).await {
+                    Some(user_id) => Outcome::Success(AuthUser(user_id)),
+                    None => Outcome::Error((Status::Unauthorized, ())),
+                };
+            }
+        }
+
+        Outcome::Forward(Status::Unauthorized)
+    }
+}
+
+/// Environment variable holding the token of the admin-only endpoints.
+pub const ADMIN_TOKEN_ENV: &str = "MYNEWSLENS_ADMIN_TOKEN";
+
+/// Caller of an admin-only endpoint, holding `X-Admin-Token: <token>` equal to
+/// [`ADMIN_TOKEN_ENV`]. Without that variable these endpoints are disabled
+/// (403); a missing or wrong token is 401.
+pub struct AdminToken;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminToken {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(expected) = std::env::var(ADMIN_TOKEN_ENV).ok().filter(|t| !t.is_empty()) else {
+            return Outcome::Error((Status::Forbidden, ()));
+        };
+        // Compare digests so the time taken doesn't depend on where they differ
+        let given = req.headers().get_one("X-Admin-Token").unwrap_or_default();
+        if Sha256::digest(given.as_bytes()) == Sha256::digest(expected.as_bytes()) {
+            Outcome::Success(AdminToken)
+        } else {
+            Outcome::Error((Status::Unauthorized, ()))
+        }
+    }
+}
+
+/// A user account, as listed by `newscope user list`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserInfo {
+    pub id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// Argon2 hash of `password` with a random salt, as stored in `users.password_hash`.
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow!("failed to hash password: {}", e))
+}
+
+/// Whether `e` is a UNIQUE constraint violation (SQLITE_CONSTRAINT_UNIQUE).
+fn is_unique_violation(e: &sqlx::Error) -> bool {
+    matches!(e, sqlx::Error::Database(db) if db.code().as_deref() == Some("2067"))
+}
+
+/// Create a user with a hashed password, a profile in `language` and the
+/// default preferences. Returns the new user id, None if `username` is taken.
+pub async fn create_user(
+    pool: &SqlitePool,
+    username: &str,
+    display_name: Option<&str>,
+    password: &str,
+    language: &str,
+) -> Result<Option<i64>> {
+    let password_hash = hash_password(password)?;
+    let user_id = match sqlx::query("INSERT INTO users (username, display_name, password_hash) VALUES (?, ?, ?)")
+        .bind(username)
+        .bind(display_name)
+        .bind(&password_hash)
+        .execute(pool)
+        .await
+    {
+        Ok(res) => res.last_insert_rowid(),
+        Err(e) if is_unique_violation(&e) => return Ok(None),
+        Err(e) => return Err(e).context("failed to insert user"),
+    };
+
+    // Profile and preferences are conveniences: the account works without them
+    let default_interests = serde_json::json!(["technology", "science", "news"]).to_string();
+    if let Err(e) = sqlx::query(
+        "INSERT INTO user_profiles
+         (user_id, language, complexity_level, interests)
+         VALUES (?, ?, 'medium', ?)",
+    )
+    .bind(user_id)
+    .bind(language)
+    .bind(default_interests)
+    .execute(pool)
+    .await
+    {
+        tracing::warn!("failed to create user profile (non-critical): {}", e);
+    }
+    let _ = sqlx::query(
+        "INSERT INTO user_preferences
+         (user_id, preference_type, preference_key, preference_value)
+         VALUES (?, 'category_filter', 'technology', 1.0)",
+    )
+    .bind(user_id)
+    .execute(pool)
+    .await;
+
+    Ok(Some(user_id))
+}
+
+/// All users, oldest first.
+pub async fn list_users(pool: &SqlitePool) -> Result<Vec<UserInfo>> {
+    sqlx::query_as::<_, UserInfo>(
+        "SELECT id, username, display_name, CAST(created_at AS TEXT) AS created_at FROM users ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .context("failed to list users")
+}
@@ -1,19 +1,9 @@
-use argon2::password_hash::{PasswordHasher, SaltString};
-use argon2::Argon2;
-use rand::rngs::OsRng;
-
 fn main() {
     let password = std::env::args()
         .nth(1)
         .expect("Usage: hash_password <password>");
 
-    let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
-    
-    let password_hash = argon2
-        .hash_password(password.as_bytes(), &salt)
-        .expect("Failed to hash password")
-        .to_string();
+    let password_hash = newscope::auth::hash_password(&password).expect("Failed to hash password");
 
     println!("{}", password_hash);
 }
@@ -1,5 +1,4 @@
-#[path = "../ingestion.rs"]
-mod ingestion;
+use newscope::ingestion;
 
 #[tokio::main]
 async fn main() {
@@ -1,8 +1,5 @@
-#[path = "../llm/mod.rs"]
-mod llm;
-
-use llm::remote::RemoteLlmProvider;
-use llm::LlmProvider;
+use newscope::llm::remote::RemoteLlmProvider;
+use newscope::llm::LlmProvider;
 
 #[tokio::main]
 async fn main() {
@@ -48,7 +45,7 @@ guarantees make it ideal for operating systems, web servers, and embedded system
     "#;
 
     println!("\n[Test 1] Summarizing article...");
-    match provider.summarize(test_article, 300).await {
+    match provider.summarize(test_article, None, 300).await {
         Ok(summary) => {
             println!("✓ Success!");
             println!("  Headline: {}", summary.headline);
@@ -74,7 +71,7 @@ guarantees make it ideal for operating systems, web servers, and embedded system
     let short_article = "Rust 1.70 was released today with new features.";
     
     println!("\n[Test 2] Summarizing short article...");
-    match provider.summarize(short_article, 200).await {
+    match provider.summarize(short_article, None, 200).await {
         Ok(summary) => {
             println!("✓ Success!");
             println!("  Headline: {}", summary.headline);
@@ -0,0 +1,142 @@
+//! Typed HTTP client for the Newscope API (enable with the `client` feature).
+//!
+//! Request and response bodies reuse the server's own serde types so both sides
+//! stay in sync.
+//!
+//! Example:
+//!   let client = Client::new("http://localhost:8000");
+//!   let login = client.login("alice", "secret").await?;
+//!   let page = client.list_feeds(login.user_id, 50, 0).await?;
+//!   let review = client.press_review(Some(600), None).await?;
+//!
+//! Scripts can skip the login flow with an API key:
+//!   let client = Client::new("http://localhost:8000").with_api_key("nsk_...");
+
+use anyhow::{Context, Result};
+use reqwest::{RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+
+use crate::server::{
+    CreateSessionRequest, FeedCreate, FeedCreated, FeedPage, LoginRequest, LoginResponse, PressReviewRequest,
+    PressReviewResponse,
+};
+use crate::sessions::Session;
+use crate::timeline::TimelineBucket;
+
+/// Thin wrapper around `reqwest::Client` bound to a server base URL.
+#[derive(Clone)]
+pub struct Client {
+    base_url: String,
+    http: reqwest::Client,
+    token: Option<String>,
+    api_key: Option<String>,
+}
+
+impl Client {
+    /// Create a client for `base_url` (e.g. "http://localhost:8000").
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            token: None,
+            api_key: None,
+        }
+    }
+
+    /// Attach a JWT sent as a bearer token on subsequent requests.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Authenticate with an API key (sent as `X-API-Key`) instead of a JWT.
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// POST /api/v1/login. Stores the returned token on the client.
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<LoginResponse> {
+        let body = LoginRequest {
+            username: username.to_string(),
+            password: password.to_string(),
+        };
+        let resp: LoginResponse = self.send(self.http.post(self.url("/api/v1/login")).json(&body)).await?;
+        self.token = Some(resp.token.clone());
+        Ok(resp)
+    }
+
+    /// GET /api/v1/feeds?user_id=&limit=&offset=
+    pub async fn list_feeds(&self, user_id: i64, limit: i64, offset: i64) -> Result<FeedPage> {
+        self.send(
+            self.http
+                .get(self.url("/api/v1/feeds"))
+                .query(&[("user_id", user_id), ("limit", limit), ("offset", offset)]),
+        )
+        .await
+    }
+
+    /// POST /api/v1/feeds, subscribing the authenticated user.
+    pub async fn create_feed(&self, url: &str, title: Option<&str>) -> Result<FeedCreated> {
+        let body = FeedCreate {
+            url: url.to_string(),
+            title: title.map(str::to_string),
+            full_content: None,
+        };
+        self.send(self.http.post(self.url("/api/v1/feeds")).json(&body)).await
+    }
+
+    /// POST /api/v1/sessions for the authenticated user.
+    pub async fn create_session(&self, duration_seconds: Option<i32>) -> Result<Session> {
+        let body = CreateSessionRequest { duration_seconds };
+        self.send(self.http.post(self.url("/api/v1/sessions")).json(&body)).await
+    }
+
+    /// POST /api/v1/press-review for the authenticated user. `group_by` is
+    /// "source" (the server default) or "topic".
+    pub async fn press_review(
+        &self,
+        duration_seconds: Option<i64>,
+        group_by: Option<&str>,
+    ) -> Result<PressReviewResponse> {
+        let body = PressReviewRequest {
+            user_id: None,
+            duration_seconds,
+            group_by: group_by.map(str::to_string),
+        };
+        self.send(self.http.post(self.url("/api/v1/press-review")).json(&body)).await
+    }
+
+    /// GET /api/v1/timeline — embedding search over the user's articles, bucketed by day.
+    pub async fn search(&self, user_id: i64, query: &str) -> Result<Vec<TimelineBucket>> {
+        self.send(
+            self.http
+                .get(self.url("/api/v1/timeline"))
+                .query(&[("q", query), ("user_id", &user_id.to_string())]),
+        )
+        .await
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, req: RequestBuilder) -> Result<T> {
+        let req = match (&self.api_key, &self.token) {
+            (Some(key), _) => req.header("X-API-Key", key),
+            (None, Some(token)) => req.bearer_auth(token),
+            (None, None) => req,
+        };
+        let resp = req.send().await.context("request failed")?;
+        parse(resp).await
+    }
+}
+
+async fn parse<T: DeserializeOwned>(resp: Response) -> Result<T> {
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        anyhow::bail!("API error {}: {}", status, body);
+    }
+    resp.json::<T>().await.context("failed to parse API response")
+}
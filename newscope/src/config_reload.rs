@@ -0,0 +1,191 @@
+//! Reloading the configuration without restarting the process.
+//!
+//! The loaded [`Config`] lives in a [`SharedConfig`] read by both the HTTP
+//! handlers and the worker. On SIGHUP the files it came from are read again,
+//! validated and swapped in atomically; a config that fails to load or validate
+//! is rejected and the running one is kept. Handlers see the new values on
+//! their next request, the worker on its next loop. Settings read once at
+//! startup (database, server, LLM endpoints, network) still need a restart.
+
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use common::Config;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+/// The live configuration, shared between Rocket state and the worker.
+pub type SharedConfig = Arc<ArcSwap<Config>>;
+
+pub fn shared(config: Config) -> SharedConfig {
+    Arc::new(ArcSwap::from_pointee(config))
+}
+
+/// Top-level sections only read at startup.
+const RESTART_SECTIONS: &[&str] = &["database", "server", "llm", "network"];
+
+/// Where the configuration is loaded from, kept to reload the same files.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSource {
+    pub default_path: Option<PathBuf>,
+    pub override_path: Option<PathBuf>,
+}
+
+impl ConfigSource {
+    /// Load and validate the configuration.
+    pub async fn load(&self) -> Result<Config> {
+        let config = Config::load_with_defaults(self.default_path.as_deref(), self.override_path.as_deref()).await?;
+        if let Err(problems) = config.validate() {
+            let list: Vec<String> = problems.iter().map(|p| format!("  - {}", p)).collect();
+            anyhow::bail!("invalid configuration ({} problem(s)):\n{}", problems.len(), list.join("\n"));
+        }
+        Ok(config)
+    }
+}
+
+/// Reload `shared` from `source`, returning the settings that changed (see
+/// [`changed_settings`]). On error the running configuration is left as is.
+pub async fn reload(shared: &SharedConfig, source: &ConfigSource) -> Result<Vec<String>> {
+    let config = source.load().await.context("configuration not reloaded")?;
+    let changes = changed_settings(&shared.load(), &config);
+    if !changes.is_empty() {
+        shared.store(Arc::new(config));
+    }
+    Ok(changes)
+}
+
+/// Settings that differ between `old` and `new`, as `path: old -> new` lines
+/// (`path: changed` for password hashes).
+pub fn changed_settings(old: &Config, new: &Config) -> Vec<String> {
+    let (old, new) = (flatten(old), flatten(new));
+    let mut paths: Vec<&String> = old.keys().chain(new.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+        .into_iter()
+        .filter(|p| old.get(*p) != new.get(*p))
+        .map(|p| {
+            if p.contains("password") {
+                return format!("{}: changed", p);
+            }
+            let show = |v: Option<&String>| v.cloned().unwrap_or_else(|| "(unset)".to_string());
+            format!("{}: {} -> {}", p, show(old.get(p)), show(new.get(p)))
+        })
+        .collect()
+}
+
+/// Every leaf setting as dotted path -> TOML value.
+fn flatten(config: &Config) -> BTreeMap<String, String> {
+    fn walk(prefix: String, value: &toml::Value, out: &mut BTreeMap<String, String>) {
+        match value {
+            toml::Value::Table(table) => {
+                for (key, value) in table {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    walk(path, value, out);
+                }
+            }
+            toml::Value::Array(items) if items.iter().any(|v| v.is_table()) => {
+                for (i, item) in items.iter().enumerate() {
+                    walk(format!("{}[{}]", prefix, i), item, out);
+                }
+            }
+            leaf => {
+                out.insert(prefix, leaf.to_string());
+            }
+        }
+    }
+    let mut out = BTreeMap::new();
+    match toml::Value::try_from(config) {
+        Ok(value) => walk(String::new(), &value, &mut out),
+        Err(e) => error!("Failed to compare configurations: {}", e),
+    }
+    out
+}
+
+/// Reload the configuration on every SIGHUP, logging what changed.
+#[cfg(unix)]
+pub fn spawn_sighup_reload(shared: SharedConfig, source: ConfigSource) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(stream) => stream,
+        Err(e) => {
+            error!("Cannot listen for SIGHUP, configuration reload disabled: {}", e);
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            info!("SIGHUP received, reloading configuration");
+            match reload(&shared, &source).await {
+                Ok(changes) if changes.is_empty() => info!("Configuration reloaded: nothing changed"),
+                Ok(changes) => {
+                    for change in &changes {
+                        let section = change.split(['.', '[', ':']).next().unwrap_or_default();
+                        if RESTART_SECTIONS.contains(&section) {
+                            warn!("Configuration changed: {} (takes effect after a restart)", change);
+                        } else {
+                            info!("Configuration changed: {}", change);
+                        }
+                    }
+                }
+                Err(e) => error!("{:#}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(extra: &str) -> Config {
+        toml::from_str(&format!("[database]\npath = \"x.db\"\n[scheduler]\ntimes = [\"07:00\"]\n{}", extra)).unwrap()
+    }
+
+    #[test]
+    fn test_changed_settings() {
+        let old = config("[scoring]\nw_src = 0.5\n[[users]]\nusername = \"alice\"\npassword_hash = \"a\"");
+        let new = config(
+            "[scoring]\nw_src = 1.0\nw_novel = 0.2\n[[users]]\nusername = \"alice\"\npassword_hash = \"b\"",
+        );
+        assert_eq!(
+            changed_settings(&old, &new),
+            vec![
+                "scoring.w_novel: (unset) -> 0.2".to_string(),
+                "scoring.w_src: 0.5 -> 1.0".to_string(),
+                "users[0].password_hash: changed".to_string(),
+            ]
+        );
+        assert!(changed_settings(&old, &old.clone()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reload_swaps_only_valid_configs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let db = dir.path().join("app.db");
+        let write = |times: &str| {
+            std::fs::write(
+                &path,
+                format!("[database]\npath = {:?}\n[scheduler]\ntimes = [{}]\n", db.to_string_lossy(), times),
+            )
+            .unwrap()
+        };
+        write("\"07:00\"");
+        let source = ConfigSource { default_path: None, override_path: Some(path.clone()) };
+        let shared = shared(source.load().await.unwrap());
+
+        write("\"07:00\", \"19:30\"");
+        let changes = reload(&shared, &source).await.unwrap();
+        assert_eq!(changes, vec!["scheduler.times: [\"07:00\"] -> [\"07:00\", \"19:30\"]".to_string()]);
+        assert_eq!(shared.load().scheduler.times, vec!["07:00".to_string(), "19:30".to_string()]);
+
+        // A broken edit is rejected and the running configuration kept
+        write("\"25:99\"");
+        let err = reload(&shared, &source).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("scheduler.times[0]"), "{:#}", err);
+        assert_eq!(shared.load().scheduler.times.len(), 2);
+    }
+}
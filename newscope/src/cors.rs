@@ -0,0 +1,99 @@
+//! Cross-origin requests, for a frontend served from another origin than the API.
+//!
+//! Only origins listed in `[server] cors_allowed_origins` get CORS headers on
+//! `/api/v1/*` and `/ws/*`; `"*"` allows any origin. Preflight `OPTIONS`
+//! requests have no route of their own: the fairing answers them with 204
+//! instead of the 404 Rocket would send. With an empty list the fairing is not
+//! attached and browsers keep the same-origin policy.
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Header, Method, Status};
+use rocket::{Request, Response};
+use std::io::Cursor;
+
+/// Methods announced in preflight responses.
+const ALLOWED_METHODS: &str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+/// Headers allowed when the preflight request doesn't list any.
+const DEFAULT_ALLOWED_HEADERS: &str = "Authorization, Content-Type, X-API-Key";
+/// Response headers scripts may read besides the CORS-safelisted ones.
+const EXPOSED_HEADERS: &str = "Retry-After";
+/// How long browsers may cache a preflight response, in seconds.
+const PREFLIGHT_MAX_AGE: &str = "3600";
+
+/// CORS fairing for the origins allowed by configuration.
+pub struct Cors {
+    allowed_origins: Vec<String>,
+}
+
+impl Cors {
+    /// The fairing for `[server] cors_allowed_origins`, `None` when no origin is allowed.
+    pub fn from_config(config: Option<&common::Config>) -> Option<Self> {
+        let origins = config
+            .and_then(|c| c.server.as_ref())
+            .and_then(|s| s.cors_allowed_origins.as_ref())?;
+        Self::new(origins)
+    }
+
+    pub fn new(origins: &[String]) -> Option<Self> {
+        let allowed_origins: Vec<String> = origins
+            .iter()
+            .map(|o| o.trim().trim_end_matches('/').to_string())
+            .filter(|o| !o.is_empty())
+            .collect();
+        (!allowed_origins.is_empty()).then_some(Self { allowed_origins })
+    }
+
+    /// Whether `origin` (an `Origin` request header) is allowed.
+    pub fn allows(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+}
+
+/// Whether CORS applies to requests for `path`.
+fn covered(path: &str) -> bool {
+    path.starts_with("/api/v1/") || path.starts_with("/ws/")
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        if !covered(req.uri().path().as_str()) {
+            return;
+        }
+        let Some(origin) = req.headers().get_one("Origin") else {
+            return;
+        };
+        if !self.allows(origin) {
+            return;
+        }
+        res.set_header(Header::new("Access-Control-Allow-Origin", origin.to_string()));
+        res.adjoin_header(Header::new("Vary", "Origin"));
+
+        let preflight = req.method() == Method::Options
+            && req.headers().get_one("Access-Control-Request-Method").is_some();
+        if preflight {
+            let headers = req
+                .headers()
+                .get_one("Access-Control-Request-Headers")
+                .unwrap_or(DEFAULT_ALLOWED_HEADERS)
+                .to_string();
+            res.set_status(Status::NoContent);
+            res.set_sized_body(0, Cursor::new(""));
+            res.remove_header("Content-Type");
+            res.set_header(Header::new("Access-Control-Allow-Methods", ALLOWED_METHODS));
+            res.set_header(Header::new("Access-Control-Allow-Headers", headers));
+            res.set_header(Header::new("Access-Control-Max-Age", PREFLIGHT_MAX_AGE));
+        } else {
+            res.set_header(Header::new("Access-Control-Expose-Headers", EXPOSED_HEADERS));
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use anyhow::{Context, Result};
+use common::Config;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+
+/// Entries shorter than this (after normalization) are not content-hashed:
+/// short teasers collide too easily to be safe merge keys.
+const MIN_HASHABLE_CHARS: usize = 200;
+
+/// Dedup outcome counts for a batch of feed entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupStats {
+    /// Entries stored as new articles
+    pub new_articles: i64,
+    /// Entries this feed had already delivered (plain re-fetch, not a duplicate)
+    pub already_seen: i64,
+    /// Entries whose URL, or a variant of it differing only by tracking
+    /// parameters, fragment or mobile host, matched an article first seen via
+    /// another feed
+    pub merged_by_url: i64,
+    /// Entries with a new URL merged into an article with the same content hash
+    pub merged_by_hash: i64,
+    /// Entries with a new URL whose content hash matched an existing article,
+    /// stored separately because content-hash merging is disabled
+    pub hash_duplicates_unmerged: i64,
+}
+
+impl DedupStats {
+    pub fn add(&mut self, other: &DedupStats) {
+        self.new_articles += other.new_articles;
+        self.already_seen += other.already_seen;
+        self.merged_by_url += other.merged_by_url;
+        self.merged_by_hash += other.merged_by_hash;
+        self.hash_duplicates_unmerged += other.hash_duplicates_unmerged;
+    }
+
+    /// Entries that duplicated an article from elsewhere (merged or mergeable).
+    pub fn duplicates(&self) -> i64 {
+        self.merged_by_url + self.merged_by_hash + self.hash_duplicates_unmerged
+    }
+
+    /// Share of this feed's distinct entries that were duplicates, in [0, 1].
+    pub fn redundancy(&self) -> f64 {
+        let total = self.new_articles + self.duplicates();
+        if total == 0 {
+            0.0
+        } else {
+            self.duplicates() as f64 / total as f64
+        }
+    }
+}
+
+/// Whether `[dedup] merge_by_content_hash` is enabled.
+pub fn merge_by_content_hash(config: Option<&Config>) -> bool {
+    config
+        .and_then(|c| c.dedup.as_ref())
+        .and_then(|d| d.merge_by_content_hash)
+        .unwrap_or(false)
+}
+
+/// Hash of the normalized title and content (lowercased, whitespace collapsed),
+/// or None when the text is too short to be a reliable merge key.
+pub fn content_hash(title: &str, content: &str) -> Option<String> {
+    let normalized: String = format!("{} {}", title, content)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    if normalized.chars().count() < MIN_HASHABLE_CHARS {
+        return None;
+    }
+    Some(format!("{:x}", Sha256::digest(normalized.as_bytes())))
+}
+
+/// Query parameters dropped from article URLs when `[dedup] strip_query_params`
+/// is unset. A trailing `*` matches every parameter with that prefix.
+pub const DEFAULT_STRIP_QUERY_PARAMS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "igshid", "xtor", "at_medium",
+    "at_campaign", "cmpid", "ref", "ref_src",
+];
+
+/// Host prefixes of a site's mobile/alternate front ends, dropped when normalizing.
+const ALTERNATE_HOST_PREFIXES: &[&str] = &["www.", "m.", "mobile.", "amp."];
+
+/// Reduces the URL variants of one article (tracking parameters, fragments,
+/// mobile subdomains) to a single form for [`url_hash`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlNormalizer {
+    strip_params: Vec<String>,
+}
+
+impl Default for UrlNormalizer {
+    fn default() -> Self {
+        Self::new(DEFAULT_STRIP_QUERY_PARAMS.iter().map(|p| p.to_string()).collect())
+    }
+}
+
+impl UrlNormalizer {
+    pub fn new(strip_params: Vec<String>) -> Self {
+        Self {
+            strip_params: strip_params.into_iter().map(|p| p.to_ascii_lowercase()).collect(),
+        }
+    }
+
+    /// Denylist from `[dedup] strip_query_params`, or the default one.
+    pub fn from_config(config: Option<&Config>) -> Self {
+        match config.and_then(|c| c.dedup.as_ref()).and_then(|d| d.strip_query_params.clone()) {
+            Some(params) => Self::new(params),
+            None => Self::default(),
+        }
+    }
+
+    fn strips(&self, param: &str) -> bool {
+        let param = param.to_ascii_lowercase();
+        self.strip_params.iter().any(|p| match p.strip_suffix('*') {
+            Some(prefix) => param.starts_with(prefix),
+            None => param == *p,
+        })
+    }
+
+    /// Lowercase host without alternate prefixes, no fragment, no denylisted
+    /// query parameters. Unparsable URLs are returned trimmed.
+    pub fn normalize(&self, url: &str) -> String {
+        let Ok(mut parsed) = url::Url::parse(url.trim()) else {
+            return url.trim().to_string();
+        };
+        parsed.set_fragment(None);
+        if let Some(host) = parsed.host_str().map(|h| h.to_ascii_lowercase()) {
+            let bare = ALTERNATE_HOST_PREFIXES
+                .iter()
+                .find_map(|p| host.strip_prefix(p))
+                .filter(|rest| rest.contains('.'))
+                .unwrap_or(&host)
+                .to_string();
+            let _ = parsed.set_host(Some(&bare));
+        }
+        let kept: Vec<(String, String)> = parsed
+            .query_pairs()
+            .filter(|(k, _)| !self.strips(k))
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        if kept.is_empty() {
+            parsed.set_query(None);
+        } else {
+            parsed.query_pairs_mut().clear().extend_pairs(kept);
+        }
+        parsed.to_string()
+    }
+}
+
+/// Hash of the normalized title and a [`UrlNormalizer::normalize`]d URL:
+/// the same article reached through another URL variant gets the same hash.
+pub fn url_hash(title: &str, normalized_url: &str) -> String {
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    format!("{:x}", Sha256::digest(format!("{}\n{}", title, normalized_url).as_bytes()))
+}
+
+/// Add a sweep's counts to the cumulative per-feed totals.
+pub async fn record_feed_stats(pool: &SqlitePool, feed_id: i64, stats: &DedupStats) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO feed_dedup_stats
+            (feed_id, new_articles, already_seen, merged_by_url, merged_by_hash, hash_duplicates_unmerged, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        ON CONFLICT(feed_id) DO UPDATE SET
+            new_articles = new_articles + excluded.new_articles,
+            already_seen = already_seen + excluded.already_seen,
+            merged_by_url = merged_by_url + excluded.merged_by_url,
+            merged_by_hash = merged_by_hash + excluded.merged_by_hash,
+            hash_duplicates_unmerged = hash_duplicates_unmerged + excluded.hash_duplicates_unmerged,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(feed_id)
+    .bind(stats.new_articles)
+    .bind(stats.already_seen)
+    .bind(stats.merged_by_url)
+    .bind(stats.merged_by_hash)
+    .bind(stats.hash_duplicates_unmerged)
+    .execute(pool)
+    .await
+    .context("failed to record dedup stats")?;
+    Ok(())
+}
+
+/// Cumulative dedup counts for one feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedDedupStats {
+    pub feed_id: i64,
+    pub url: String,
+    pub title: Option<String>,
+    #[serde(flatten)]
+    pub stats: DedupStats,
+    pub redundancy: f64,
+}
+
+/// Cumulative dedup stats, with feeds ordered from most to least redundant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupReport {
+    pub content_hash_merging: bool,
+    pub totals: DedupStats,
+    pub feeds: Vec<FeedDedupStats>,
+}
+
+pub async fn dedup_report(pool: &SqlitePool, content_hash_merging: bool) -> Result<DedupReport> {
+    let rows = sqlx::query(
+        r#"
+        SELECT d.feed_id, f.url, f.title, d.new_articles, d.already_seen,
+               d.merged_by_url, d.merged_by_hash, d.hash_duplicates_unmerged
+        FROM feed_dedup_stats d
+        JOIN feeds f ON f.id = d.feed_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("failed to load dedup stats")?;
+
+    let mut totals = DedupStats::default();
+    let mut feeds: Vec<FeedDedupStats> = rows
+        .into_iter()
+        .map(|r| {
+            let stats = DedupStats {
+                new_articles: r.get("new_articles"),
+                already_seen: r.get("already_seen"),
+                merged_by_url: r.get("merged_by_url"),
+                merged_by_hash: r.get("merged_by_hash"),
+                hash_duplicates_unmerged: r.get("hash_duplicates_unmerged"),
+            };
+            totals.add(&stats);
+            FeedDedupStats {
+                feed_id: r.get("feed_id"),
+                url: r.get("url"),
+                title: r.get("title"),
+                redundancy: stats.redundancy(),
+                stats,
+            }
+        })
+        .collect();
+
+    feeds.sort_by(|a, b| {
+        b.redundancy
+            .partial_cmp(&a.redundancy)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.feed_id.cmp(&b.feed_id))
+    });
+
+    Ok(DedupReport {
+        content_hash_merging,
+        totals,
+        feeds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash_normalizes_and_skips_short_text() {
+        let body = "word ".repeat(60);
+        let a = content_hash("Big News", &body).unwrap();
+        let b = content_hash("big   news", &format!("  {}\n", body.to_uppercase())).unwrap();
+        assert_eq!(a, b);
+        assert!(content_hash("Teaser", "Read more").is_none());
+    }
+
+    #[test]
+    fn test_url_normalization() {
+        let urls = UrlNormalizer::default();
+        assert_eq!(
+            urls.normalize("https://M.Example.com/news/1?utm_source=rss&id=7&fbclid=x#comments"),
+            "https://example.com/news/1?id=7"
+        );
+        assert_eq!(urls.normalize("https://www.example.com/a?utm_medium=feed"), "https://example.com/a");
+        // A bare two-label host keeps its first label
+        assert_eq!(urls.normalize("https://m.com/a"), "https://m.com/a");
+        assert_eq!(urls.normalize(" not a url "), "not a url");
+
+        let custom = UrlNormalizer::new(vec!["Session".into()]);
+        assert_eq!(custom.normalize("http://x.org/a?session=1&utm_source=rss"), "http://x.org/a?utm_source=rss");
+
+        assert_eq!(
+            url_hash("Same  Title", &urls.normalize("https://example.com/a?utm_source=x")),
+            url_hash("same title", &urls.normalize("https://www.example.com/a#top"))
+        );
+        assert_ne!(url_hash("Title", "https://example.com/a"), url_hash("Other", "https://example.com/a"));
+    }
+
+    #[test]
+    fn test_redundancy() {
+        let stats = DedupStats {
+            new_articles: 3,
+            already_seen: 10,
+            merged_by_url: 1,
+            merged_by_hash: 0,
+            hash_duplicates_unmerged: 0,
+        };
+        assert!((stats.redundancy() - 0.25).abs() < 1e-9);
+        assert_eq!(DedupStats::default().redundancy(), 0.0);
+    }
+}
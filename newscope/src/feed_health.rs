@@ -0,0 +1,265 @@
+use anyhow::{Context, Result};
+use common::Config;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+
+use crate::ingestion::{FetchMetrics, MAX_POLL_INTERVAL_MINUTES};
+use crate::storage::FEED_STATUS_DISABLED;
+
+/// Fetch samples kept per feed; older rows are pruned on insert.
+pub const MAX_SAMPLES_PER_FEED: i64 = 50;
+/// Default for `scheduler.max_consecutive_failures`.
+pub const DEFAULT_MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+/// Append a fetch sample to the feed's log and drop samples beyond the last
+/// [`MAX_SAMPLES_PER_FEED`].
+pub async fn record_fetch(
+    pool: &SqlitePool,
+    feed_id: i64,
+    metrics: &FetchMetrics,
+    error: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO feed_fetch_log (feed_id, latency_ms, response_bytes, http_status, attempts, success, error)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(feed_id)
+    .bind(metrics.latency_ms)
+    .bind(metrics.response_bytes)
+    .bind(metrics.http_status.map(i64::from))
+    .bind(metrics.attempts as i64)
+    .bind(error.is_none())
+    .bind(error)
+    .execute(pool)
+    .await
+    .context("failed to record fetch sample")?;
+
+    sqlx::query(
+        "DELETE FROM feed_fetch_log WHERE feed_id = ? AND id NOT IN
+           (SELECT id FROM feed_fetch_log WHERE feed_id = ? ORDER BY id DESC LIMIT ?)",
+    )
+    .bind(feed_id)
+    .bind(feed_id)
+    .bind(MAX_SAMPLES_PER_FEED)
+    .execute(pool)
+    .await
+    .context("failed to prune fetch log")?;
+    Ok(())
+}
+
+/// Fetch latency and size over a feed's recent samples.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedHealth {
+    pub feed_id: i64,
+    pub url: String,
+    pub title: Option<String>,
+    pub samples: i64,
+    pub failures: i64,
+    pub avg_latency_ms: f64,
+    pub max_latency_ms: i64,
+    pub last_latency_ms: i64,
+    /// Average body size over successful fetches
+    pub avg_response_bytes: Option<f64>,
+    pub last_fetched_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// Health of every feed with fetch samples, slowest (by average latency) first.
+pub async fn feed_health_report(pool: &SqlitePool) -> Result<Vec<FeedHealth>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT f.id AS feed_id, f.url, f.title,
+               COUNT(*) AS samples,
+               SUM(CASE WHEN l.success THEN 0 ELSE 1 END) AS failures,
+               AVG(l.latency_ms) AS avg_latency_ms,
+               MAX(l.latency_ms) AS max_latency_ms,
+               AVG(CASE WHEN l.success THEN l.response_bytes END) AS avg_response_bytes,
+               MAX(l.id) AS last_id
+        FROM feed_fetch_log l
+        JOIN feeds f ON f.id = l.feed_id
+        GROUP BY f.id
+        ORDER BY avg_latency_ms DESC, f.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("failed to load feed health")?;
+
+    let mut report = Vec::with_capacity(rows.len());
+    for r in rows {
+        let last = sqlx::query("SELECT latency_ms, fetched_at, error FROM feed_fetch_log WHERE id = ?")
+            .bind(r.get::<i64, _>("last_id"))
+            .fetch_one(pool)
+            .await
+            .context("failed to load last fetch sample")?;
+        report.push(FeedHealth {
+            feed_id: r.get("feed_id"),
+            url: r.get("url"),
+            title: r.get("title"),
+            samples: r.get("samples"),
+            failures: r.get("failures"),
+            avg_latency_ms: r.get("avg_latency_ms"),
+            max_latency_ms: r.get("max_latency_ms"),
+            last_latency_ms: last.get("latency_ms"),
+            avg_response_bytes: r.get("avg_response_bytes"),
+            last_fetched_at: last.get("fetched_at"),
+            last_error: last.get("error"),
+        });
+    }
+    Ok(report)
+}
+
+/// Longest poll interval of a failing feed (`scheduler.max_backoff_minutes`).
+pub fn max_backoff_minutes(config: Option<&Config>) -> i64 {
+    config
+        .and_then(|c| c.scheduler.max_backoff_minutes)
+        .unwrap_or(MAX_POLL_INTERVAL_MINUTES)
+        .max(1)
+}
+
+/// Consecutive permanent failures before a feed is disabled
+/// (`scheduler.max_consecutive_failures`); 0 never disables.
+pub fn max_consecutive_failures(config: Option<&Config>) -> u32 {
+    config
+        .and_then(|c| c.scheduler.max_consecutive_failures)
+        .unwrap_or(DEFAULT_MAX_CONSECUTIVE_FAILURES)
+}
+
+/// Poll interval after a failed fetch: doubled, up to `max_minutes`.
+pub fn failure_backoff(interval: i64, max_minutes: i64) -> i64 {
+    interval.saturating_mul(2).min(max_minutes).max(1)
+}
+
+/// Whether a failed fetch will keep failing: the server answered with a 4xx
+/// other than 408 Request Timeout or 429 Too Many Requests. Network errors,
+/// timeouts and 5xx are transient.
+pub fn is_permanent_failure(http_status: Option<u16>) -> bool {
+    matches!(http_status, Some(status) if (400..500).contains(&status) && status != 408 && status != 429)
+}
+
+/// Count a failed fetch towards disabling the feed. Only permanent failures
+/// count; once `max_failures` of them happened in a row (and `max_failures` is
+/// not 0) the feed is marked disabled. Returns true when this call disabled it.
+pub async fn record_failure(pool: &SqlitePool, feed_id: i64, permanent: bool, max_failures: u32) -> Result<bool> {
+    if !permanent {
+        return Ok(false);
+    }
+    sqlx::query("UPDATE feeds SET consecutive_failures = consecutive_failures + 1 WHERE id = ?")
+        .bind(feed_id)
+        .execute(pool)
+        .await
+        .context("failed to count feed failure")?;
+    let failures: i64 = sqlx::query_scalar("SELECT consecutive_failures FROM feeds WHERE id = ?")
+        .bind(feed_id)
+        .fetch_one(pool)
+        .await
+        .context("failed to count feed failure")?;
+    if max_failures == 0 || failures < i64::from(max_failures) {
+        return Ok(false);
+    }
+    let disabled = sqlx::query("UPDATE feeds SET status = ? WHERE id = ? AND status IS NULL")
+        .bind(FEED_STATUS_DISABLED)
+        .bind(feed_id)
+        .execute(pool)
+        .await
+        .context("failed to disable feed")?
+        .rows_affected();
+    Ok(disabled > 0)
+}
+
+/// Reset the failure count after a successful fetch, re-enabling the feed if
+/// it was disabled.
+pub async fn record_success(pool: &SqlitePool, feed_id: i64) -> Result<()> {
+    sqlx::query(
+        "UPDATE feeds SET consecutive_failures = 0,
+                status = CASE WHEN status = ? THEN NULL ELSE status END
+         WHERE id = ?",
+    )
+    .bind(FEED_STATUS_DISABLED)
+    .bind(feed_id)
+    .execute(pool)
+    .await
+    .context("failed to reset feed failures")?;
+    Ok(())
+}
+
+/// Re-enable a disabled feed: its failure count is reset and it is polled on
+/// the worker's next loop. Returns false when the feed was not disabled.
+pub async fn enable_feed(pool: &SqlitePool, feed_id: i64) -> Result<bool> {
+    let enabled = sqlx::query(
+        "UPDATE feeds SET status = NULL, consecutive_failures = 0, next_poll_at = NULL WHERE id = ? AND status = ?",
+    )
+    .bind(feed_id)
+    .bind(FEED_STATUS_DISABLED)
+    .execute(pool)
+    .await
+    .context("failed to enable feed")?
+    .rows_affected();
+    Ok(enabled > 0)
+}
+
+/// A subscribed feed disabled after repeated failures.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisabledFeed {
+    pub feed_id: i64,
+    pub subscription_id: i64,
+    pub url: String,
+    pub title: Option<String>,
+    pub consecutive_failures: i64,
+    pub last_checked: Option<String>,
+    /// HTTP status and error of the last fetch
+    pub last_http_status: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+/// `user_id`'s subscriptions whose feed is disabled.
+pub async fn disabled_feeds(pool: &SqlitePool, user_id: i64) -> Result<Vec<DisabledFeed>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT f.id AS feed_id, s.id AS subscription_id, f.url, COALESCE(s.title, f.title) AS title,
+               f.consecutive_failures, f.last_checked, l.http_status, l.error
+        FROM subscriptions s
+        JOIN feeds f ON f.id = s.feed_id
+        LEFT JOIN feed_fetch_log l ON l.id = (SELECT MAX(id) FROM feed_fetch_log WHERE feed_id = f.id)
+        WHERE s.user_id = ? AND f.status = ?
+        ORDER BY s.id
+        "#,
+    )
+    .bind(user_id)
+    .bind(FEED_STATUS_DISABLED)
+    .fetch_all(pool)
+    .await
+    .context("failed to load disabled feeds")?;
+    Ok(rows
+        .into_iter()
+        .map(|r| DisabledFeed {
+            feed_id: r.get("feed_id"),
+            subscription_id: r.get("subscription_id"),
+            url: r.get("url"),
+            title: r.get("title"),
+            consecutive_failures: r.get("consecutive_failures"),
+            last_checked: r.get("last_checked"),
+            last_http_status: r.get("http_status"),
+            last_error: r.get("error"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permanent_failures_and_backoff() {
+        assert!(is_permanent_failure(Some(404)));
+        assert!(is_permanent_failure(Some(410)));
+        assert!(!is_permanent_failure(Some(429)));
+        assert!(!is_permanent_failure(Some(408)));
+        assert!(!is_permanent_failure(Some(503)));
+        assert!(!is_permanent_failure(None));
+
+        assert_eq!(failure_backoff(60, 1440), 120);
+        assert_eq!(failure_backoff(1000, 1440), 1440);
+        assert_eq!(failure_backoff(60, 90), 90);
+    }
+}
@@ -0,0 +1,216 @@
+//! Health of the service and of the subsystems it depends on, for
+//! `GET /api/v1/health` and `GET /health`.
+//!
+//! The database is critical: when it does not answer the service is `down` and
+//! both endpoints return 503, so an orchestrator can restart it. An unreachable
+//! LLM or a stalled worker only make it `degraded`; an LLM role that is not
+//! configured, or a worker not run by this process (`serve --no-worker`), is
+//! `disabled` and does not count.
+//!
+//! The report is public, so messages stay generic: error details, which may
+//! name endpoints, paths or keys, only go to the log. LLM pings are cached for
+//! [`LLM_CHECK_TTL`] so polling the endpoint doesn't turn into provider traffic.
+
+use chrono::{DateTime, Utc};
+use rocket::futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::llm::LlmProvider;
+use crate::server::AppState;
+use crate::worker_status::{self, SharedWorkerStatus};
+
+/// The worker loops at least once a minute; past this it is considered stalled.
+pub const WORKER_STALL_MINUTES: i64 = 15;
+/// Time allowed for the database to answer `SELECT 1`.
+const DB_PING_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long an LLM ping result is reused.
+pub const LLM_CHECK_TTL: Duration = Duration::from_secs(30);
+/// Checks whose failure takes the whole service down.
+const CRITICAL_CHECKS: &[&str] = &["database"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    Degraded,
+    Down,
+    /// Not configured or not run by this process; only used for checks
+    Disabled,
+}
+
+/// Outcome of one subsystem check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Check {
+    pub status: HealthStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+}
+
+impl Check {
+    fn new(status: HealthStatus, message: Option<String>) -> Self {
+        Self { status, message, latency_ms: None }
+    }
+}
+
+/// Response of `GET /api/v1/health`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// `ok`, `degraded` or `down`
+    pub status: HealthStatus,
+    pub checks: BTreeMap<String, Check>,
+    /// Start of the worker's last loop, when it runs in this process
+    pub worker_last_loop_at: Option<DateTime<Utc>>,
+}
+
+/// `down` when a critical check is down, `degraded` when any other check is
+/// not ok, `ok` otherwise.
+pub fn overall(checks: &BTreeMap<String, Check>) -> HealthStatus {
+    let critical_down = checks
+        .iter()
+        .any(|(name, c)| c.status == HealthStatus::Down && CRITICAL_CHECKS.contains(&name.as_str()));
+    if critical_down {
+        HealthStatus::Down
+    } else if checks.values().any(|c| matches!(c.status, HealthStatus::Degraded | HealthStatus::Down)) {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Ok
+    }
+}
+
+/// Ping the database with `SELECT 1`.
+pub async fn check_database(pool: &SqlitePool) -> Check {
+    let started = Instant::now();
+    let ping = tokio::time::timeout(DB_PING_TIMEOUT, sqlx::query_scalar::<_, i64>("SELECT 1").fetch_one(pool)).await;
+    let mut check = match ping {
+        Ok(Ok(_)) => Check::new(HealthStatus::Ok, None),
+        Ok(Err(e)) => {
+            tracing::warn!("health: database check failed: {}", e);
+            Check::new(HealthStatus::Down, Some("query failed".to_string()))
+        }
+        Err(_) => Check::new(HealthStatus::Down, Some(format!("no answer within {:?}", DB_PING_TIMEOUT))),
+    };
+    check.latency_ms = Some(started.elapsed().as_millis() as u64);
+    check
+}
+
+/// Last ping of each provider, keyed by its address.
+static LLM_CHECKS: OnceLock<Mutex<HashMap<usize, (Instant, Check)>>> = OnceLock::new();
+
+/// Whether an LLM role is configured and its provider reachable (see
+/// [`LlmProvider::ping`]). A provider is pinged at most once per [`LLM_CHECK_TTL`].
+pub async fn check_llm(provider: Option<&Arc<dyn LlmProvider>>) -> Check {
+    let Some(provider) = provider else {
+        return Check::new(HealthStatus::Disabled, Some("not configured".to_string()));
+    };
+    let key = Arc::as_ptr(provider) as *const () as usize;
+    let cache = LLM_CHECKS.get_or_init(Default::default);
+    if let Some((at, check)) = cache.lock().unwrap().get(&key) {
+        if at.elapsed() < LLM_CHECK_TTL {
+            return check.clone();
+        }
+    }
+
+    let started = Instant::now();
+    let mut check = match provider.ping().await {
+        Ok(()) => Check::new(HealthStatus::Ok, None),
+        Err(e) => {
+            tracing::warn!("health: LLM check failed: {:#}", e);
+            Check::new(HealthStatus::Degraded, Some("unreachable".to_string()))
+        }
+    };
+    check.latency_ms = Some(started.elapsed().as_millis() as u64);
+    cache.lock().unwrap().insert(key, (Instant::now(), check.clone()));
+    check
+}
+
+/// Whether the worker has looped in the last [`WORKER_STALL_MINUTES`]. A
+/// worker that has not looped yet gets that long from `started_at`.
+pub fn check_worker(worker: Option<&SharedWorkerStatus>, started_at: DateTime<Utc>, now: DateTime<Utc>) -> Check {
+    let Some(worker) = worker else {
+        return Check::new(HealthStatus::Disabled, Some("not running in this process".to_string()));
+    };
+    let stall = chrono::Duration::minutes(WORKER_STALL_MINUTES);
+    match worker_status::snapshot(worker).last_loop_at {
+        Some(at) if now - at > stall => Check::new(
+            HealthStatus::Degraded,
+            Some(format!("no loop for {} minutes", (now - at).num_minutes())),
+        ),
+        None if now - started_at > stall => Check::new(
+            HealthStatus::Degraded,
+            Some(format!("no loop since startup {} minutes ago", (now - started_at).num_minutes())),
+        ),
+        _ => Check::new(HealthStatus::Ok, None),
+    }
+}
+
+/// Run every check, the database and LLM ones concurrently.
+pub async fn report(state: &AppState) -> HealthReport {
+    let llms = [
+        ("summarization_llm", &state.summarization_llm),
+        ("personalization_llm", &state.personalization_llm),
+        ("interaction_llm", &state.interaction_llm),
+        ("embedding_llm", &state.embedding_llm),
+    ];
+    let (database, llm_checks) = tokio::join!(
+        check_database(&state.db),
+        join_all(llms.iter().map(|(_, provider)| check_llm(provider.as_ref()))),
+    );
+
+    let mut checks = BTreeMap::new();
+    checks.insert("database".to_string(), database);
+    for ((name, _), check) in llms.iter().zip(llm_checks) {
+        checks.insert(name.to_string(), check);
+    }
+    checks.insert(
+        "worker".to_string(),
+        check_worker(state.worker.as_ref(), state.started_at, Utc::now()),
+    );
+
+    HealthReport {
+        status: overall(&checks),
+        checks,
+        worker_last_loop_at: state.worker.as_ref().and_then(|w| worker_status::snapshot(w).last_loop_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_staleness() {
+        let now = Utc::now();
+        let worker = worker_status::shared();
+        assert_eq!(check_worker(None, now, now).status, HealthStatus::Disabled);
+        // Freshly started, no loop yet
+        assert_eq!(check_worker(Some(&worker), now, now).status, HealthStatus::Ok);
+        let long_ago = now - chrono::Duration::minutes(WORKER_STALL_MINUTES + 1);
+        assert_eq!(check_worker(Some(&worker), long_ago, now).status, HealthStatus::Degraded);
+
+        worker_status::update(&worker, |w| w.last_loop_at = Some(now - chrono::Duration::minutes(1)));
+        assert_eq!(check_worker(Some(&worker), long_ago, now).status, HealthStatus::Ok);
+        worker_status::update(&worker, |w| w.last_loop_at = Some(long_ago));
+        assert_eq!(check_worker(Some(&worker), long_ago, now).status, HealthStatus::Degraded);
+    }
+
+    #[test]
+    fn test_only_critical_checks_take_the_service_down() {
+        let checks = |llm: HealthStatus, database: HealthStatus| {
+            BTreeMap::from([
+                ("database".to_string(), Check::new(database, None)),
+                ("interaction_llm".to_string(), Check::new(llm, None)),
+            ])
+        };
+        assert_eq!(overall(&checks(HealthStatus::Disabled, HealthStatus::Ok)), HealthStatus::Ok);
+        assert_eq!(overall(&checks(HealthStatus::Degraded, HealthStatus::Ok)), HealthStatus::Degraded);
+        assert_eq!(overall(&checks(HealthStatus::Down, HealthStatus::Ok)), HealthStatus::Degraded);
+        assert_eq!(overall(&checks(HealthStatus::Ok, HealthStatus::Down)), HealthStatus::Down);
+    }
+}
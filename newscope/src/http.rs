@@ -0,0 +1,240 @@
+//! Shared outbound HTTP client for feed fetches, article scraping, LLM APIs and
+//! webhooks.
+//!
+//! Building a `reqwest::Client` per request throws away its connection pool,
+//! so every poll paid a new TCP + TLS handshake. One client is built per
+//! process instead; per-call timeouts are set on each request. Proxies come
+//! from `[network]`, falling back to the usual environment variables.
+//! Response bodies are read through [`read_body_limited`] so an oversized or
+//! endless response cannot exhaust memory.
+
+use anyhow::{Context, Result};
+use common::Config;
+use reqwest::{Client, ClientBuilder, NoProxy, Proxy};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Sent with every outbound request; its product token is what robots.txt rules match.
+pub const USER_AGENT: &str = "Newscope/0.1.0";
+
+/// Body size limit when `politeness.max_response_bytes` is unset (10 MiB).
+pub const DEFAULT_MAX_RESPONSE_BYTES: u64 = 10 * 1024 * 1024;
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+static MAX_RESPONSE_BYTES: OnceLock<u64> = OnceLock::new();
+
+/// Proxy settings, resolved from `[network]` and the environment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyOptions {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub no_proxy: Option<String>,
+}
+
+impl ProxyOptions {
+    /// `[network]` settings, each falling back to HTTP_PROXY / HTTPS_PROXY /
+    /// NO_PROXY (or their lowercase forms).
+    pub fn from_config(config: Option<&Config>) -> Self {
+        Self::resolve(config, |name| std::env::var(name).ok())
+    }
+
+    fn resolve(config: Option<&Config>, env: impl Fn(&str) -> Option<String>) -> Self {
+        let network = config.and_then(|c| c.network.as_ref());
+        let pick = |configured: Option<&String>, var: &str| {
+            configured
+                .cloned()
+                .or_else(|| env(var))
+                .or_else(|| env(&var.to_lowercase()))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            http: pick(network.and_then(|n| n.http_proxy.as_ref()), "HTTP_PROXY"),
+            https: pick(network.and_then(|n| n.https_proxy.as_ref()), "HTTPS_PROXY"),
+            no_proxy: pick(network.and_then(|n| n.no_proxy.as_ref()), "NO_PROXY"),
+        }
+    }
+
+    fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder> {
+        let no_proxy = || self.no_proxy.as_deref().and_then(NoProxy::from_string);
+        if let Some(url) = &self.http {
+            let proxy = Proxy::http(url).with_context(|| format!("invalid http proxy {}", url))?;
+            builder = builder.proxy(proxy.no_proxy(no_proxy()));
+        }
+        if let Some(url) = &self.https {
+            let proxy = Proxy::https(url).with_context(|| format!("invalid https proxy {}", url))?;
+            builder = builder.proxy(proxy.no_proxy(no_proxy()));
+        }
+        Ok(builder)
+    }
+}
+
+/// Connection reuse settings from the `[politeness]` section, and proxies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpClientOptions {
+    /// How long an idle pooled connection is kept open
+    pub pool_idle_timeout_secs: u64,
+    /// Idle connections kept per host (0 disables connection reuse)
+    pub pool_max_idle_per_host: usize,
+    /// TCP keep-alive interval (0 disables it)
+    pub tcp_keepalive_secs: u64,
+    /// Largest response body read from feeds and article pages
+    pub max_response_bytes: u64,
+    pub proxy: ProxyOptions,
+}
+
+impl Default for HttpClientOptions {
+    fn default() -> Self {
+        Self {
+            pool_idle_timeout_secs: 90,
+            pool_max_idle_per_host: 8,
+            tcp_keepalive_secs: 60,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            proxy: ProxyOptions::default(),
+        }
+    }
+}
+
+impl HttpClientOptions {
+    pub fn from_config(config: Option<&Config>) -> Self {
+        let politeness = config.and_then(|c| c.politeness.as_ref());
+        let defaults = Self::default();
+        Self {
+            pool_idle_timeout_secs: politeness
+                .and_then(|p| p.pool_idle_timeout_seconds)
+                .unwrap_or(defaults.pool_idle_timeout_secs),
+            pool_max_idle_per_host: politeness
+                .and_then(|p| p.pool_max_idle_per_host)
+                .unwrap_or(defaults.pool_max_idle_per_host),
+            tcp_keepalive_secs: politeness
+                .and_then(|p| p.tcp_keepalive_seconds)
+                .unwrap_or(defaults.tcp_keepalive_secs),
+            max_response_bytes: politeness
+                .and_then(|p| p.max_response_bytes)
+                .unwrap_or(defaults.max_response_bytes),
+            proxy: ProxyOptions::from_config(config),
+        }
+    }
+}
+
+pub fn build_client(opts: &HttpClientOptions) -> Result<Client> {
+    let builder = Client::builder()
+        .user_agent(USER_AGENT)
+        // Transparently decompress `Content-Encoding: gzip`, `deflate` and `br` bodies
+        .gzip(true)
+        .deflate(true)
+        .brotli(true)
+        .pool_idle_timeout(Duration::from_secs(opts.pool_idle_timeout_secs))
+        .pool_max_idle_per_host(opts.pool_max_idle_per_host)
+        .tcp_keepalive((opts.tcp_keepalive_secs > 0).then(|| Duration::from_secs(opts.tcp_keepalive_secs)));
+    opts.proxy
+        .apply(builder)?
+        .build()
+        .context("failed to build reqwest client")
+}
+
+/// Configure the shared client. Call once at startup, before any fetch;
+/// later calls (or calls after [`client`] was first used) are ignored.
+pub fn init(opts: &HttpClientOptions) -> Result<()> {
+    let client = build_client(opts)?;
+    if CLIENT.set(client).is_err() || MAX_RESPONSE_BYTES.set(opts.max_response_bytes).is_err() {
+        tracing::warn!("HTTP client already initialized, keeping existing settings");
+    }
+    Ok(())
+}
+
+/// The configured body size limit, [`DEFAULT_MAX_RESPONSE_BYTES`] if [`init`] was not called.
+pub fn max_response_bytes() -> u64 {
+    MAX_RESPONSE_BYTES.get().copied().unwrap_or(DEFAULT_MAX_RESPONSE_BYTES)
+}
+
+/// Read a response body, failing once it grows past `max_bytes`. A declared
+/// `Content-Length` over the limit is rejected before anything is read.
+pub async fn read_body_limited(mut response: reqwest::Response, max_bytes: u64) -> Result<Vec<u8>> {
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        anyhow::bail!("response exceeded {} bytes", max_bytes);
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.context("failed to read response body")? {
+        if (body.len() + chunk.len()) as u64 > max_bytes {
+            anyhow::bail!("response exceeded {} bytes", max_bytes);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// The shared client, built with default settings if [`init`] was not called.
+pub fn client() -> &'static Client {
+    CLIENT.get_or_init(|| build_client(&HttpClientOptions::default()).expect("failed to build default HTTP client"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with(network: &str) -> Config {
+        toml::from_str(&format!(
+            "[database]\npath = \"x.db\"\n[scheduler]\ntimes = []\n[network]\n{}",
+            network
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_proxy_config_overrides_env_per_scheme() {
+        let env = |name: &str| match name {
+            "HTTP_PROXY" => Some("http://env-http:3128".to_string()),
+            "https_proxy" => Some("http://env-https:3128".to_string()),
+            _ => None,
+        };
+        let cfg = config_with("http_proxy = \"http://cfg:8080\"\nno_proxy = \"localhost,.corp\"");
+        let proxy = ProxyOptions::resolve(Some(&cfg), env);
+        assert_eq!(proxy.http.as_deref(), Some("http://cfg:8080"));
+        assert_eq!(proxy.https.as_deref(), Some("http://env-https:3128"));
+        assert_eq!(proxy.no_proxy.as_deref(), Some("localhost,.corp"));
+
+        assert_eq!(ProxyOptions::resolve(None, |_| None), ProxyOptions::default());
+        assert!(build_client(&HttpClientOptions { proxy, ..Default::default() }).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_proxy_is_reported() {
+        let proxy = ProxyOptions {
+            http: Some("not a url".into()),
+            ..Default::default()
+        };
+        assert!(build_client(&HttpClientOptions { proxy, ..Default::default() }).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_body_limited() {
+        let mut server = mockito::Server::new_async().await;
+        let declared = server.mock("GET", "/declared").with_body(vec![b'a'; 64]).expect(2).create_async().await;
+        let chunked = server
+            .mock("GET", "/chunked")
+            .with_chunked_body(|w| {
+                for _ in 0..8 {
+                    w.write_all(&[b'b'; 16])?;
+                }
+                Ok(())
+            })
+            .create_async()
+            .await;
+
+        let get = |path: &str| client().get(format!("{}{}", server.url(), path)).send();
+        let body = read_body_limited(get("/declared").await.unwrap(), 64).await.unwrap();
+        assert_eq!(body.len(), 64);
+
+        let err = read_body_limited(get("/declared").await.unwrap(), 63).await.unwrap_err();
+        assert_eq!(err.to_string(), "response exceeded 63 bytes");
+
+        let response = get("/chunked").await.unwrap();
+        assert_eq!(response.content_length(), None);
+        let err = read_body_limited(response, 100).await.unwrap_err();
+        assert_eq!(err.to_string(), "response exceeded 100 bytes");
+
+        declared.assert_async().await;
+        chunked.assert_async().await;
+    }
+}
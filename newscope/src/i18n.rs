@@ -0,0 +1,110 @@
+//! Minimal message catalog for user-facing strings outside the LLM prompts.
+//!
+//! Supported languages match the rest of the UI (en, fr, es, de, it); anything
+//! else falls back to English. Also detects the language articles are written in.
+
+use whatlang::Lang;
+
+/// Keys for localized messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    /// Body of the "press review ready" notification.
+    ReviewReady,
+    /// Title of the out-of-session "high relevance article" notification.
+    HighRelevanceArticle,
+    /// Prefix of the feeds whose copies of a story were collapsed into a card.
+    AlsoCoveredBy,
+}
+
+/// Localized text for `msg` in `lang` (ISO 639-1 primary tag, e.g. "fr").
+pub fn t(lang: &str, msg: Msg) -> &'static str {
+    match (msg, lang) {
+        (Msg::ReviewReady, "fr") => "Votre revue de presse est prête !",
+        (Msg::ReviewReady, "es") => "¡Su resumen de prensa está listo!",
+        (Msg::ReviewReady, "de") => "Ihr Pressespiegel ist fertig!",
+        (Msg::ReviewReady, "it") => "La tua rassegna stampa è pronta!",
+        (Msg::ReviewReady, _) => "Your press review is ready!",
+
+        (Msg::HighRelevanceArticle, "fr") => "Un article important pour vous",
+        (Msg::HighRelevanceArticle, "es") => "Un artículo importante para usted",
+        (Msg::HighRelevanceArticle, "de") => "Ein wichtiger Artikel für Sie",
+        (Msg::HighRelevanceArticle, "it") => "Un articolo importante per te",
+        (Msg::HighRelevanceArticle, _) => "An important article for you",
+
+        (Msg::AlsoCoveredBy, "fr") => "Aussi couvert par :",
+        (Msg::AlsoCoveredBy, "es") => "También cubierto por:",
+        (Msg::AlsoCoveredBy, "de") => "Auch berichtet von:",
+        (Msg::AlsoCoveredBy, "it") => "Riportato anche da:",
+        (Msg::AlsoCoveredBy, _) => "Also covered by:",
+    }
+}
+
+/// ISO 639-1 codes for the languages the detector knows that have one.
+const ISO_639_1: &[(Lang, &str)] = &[
+    (Lang::Eng, "en"), (Lang::Fra, "fr"), (Lang::Spa, "es"), (Lang::Deu, "de"),
+    (Lang::Ita, "it"), (Lang::Por, "pt"), (Lang::Nld, "nl"), (Lang::Rus, "ru"),
+    (Lang::Ukr, "uk"), (Lang::Pol, "pl"), (Lang::Ces, "cs"), (Lang::Swe, "sv"),
+    (Lang::Dan, "da"), (Lang::Nob, "nb"), (Lang::Fin, "fi"), (Lang::Ell, "el"),
+    (Lang::Tur, "tr"), (Lang::Ara, "ar"), (Lang::Heb, "he"), (Lang::Hin, "hi"),
+    (Lang::Cmn, "zh"), (Lang::Jpn, "ja"), (Lang::Kor, "ko"), (Lang::Ron, "ro"),
+    (Lang::Hun, "hu"), (Lang::Cat, "ca"), (Lang::Vie, "vi"), (Lang::Ind, "id"),
+];
+
+/// Detected language of `text`: an ISO 639-1 code such as "fr" (ISO 639-3 for
+/// languages without one), or `None` when the detector is not confident.
+pub fn detect_language(text: &str) -> Option<String> {
+    let info = whatlang::detect(text).filter(|i| i.is_reliable())?;
+    let code = ISO_639_1
+        .iter()
+        .find(|(lang, _)| *lang == info.lang())
+        .map_or(info.lang().code(), |(_, code)| code);
+    Some(code.to_string())
+}
+
+/// English name of a language code from [`detect_language`] or a user profile,
+/// for LLM prompts ("fr" -> "French").
+pub fn language_name(code: &str) -> Option<&'static str> {
+    ISO_639_1
+        .iter()
+        .find(|(_, c)| *c == code)
+        .map(|(lang, _)| *lang)
+        .or_else(|| Lang::from_code(code))
+        .map(|lang| lang.eng_name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_ready_is_only_french_for_french() {
+        let french = t("fr", Msg::ReviewReady);
+        for lang in ["en", "es", "de", "it"] {
+            assert_ne!(t(lang, Msg::ReviewReady), french, "{} got the French string", lang);
+        }
+    }
+
+    #[test]
+    fn test_unknown_language_falls_back_to_english() {
+        assert_eq!(t("pt", Msg::ReviewReady), t("en", Msg::ReviewReady));
+        assert_ne!(t("en", Msg::ReviewReady), t("fr", Msg::ReviewReady));
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let fr = "Le gouvernement a présenté mercredi son projet de budget pour l'année prochaine, \
+                  qui prévoit une baisse des dépenses publiques.";
+        let en = "The government presented its budget for next year on Wednesday, \
+                  which plans a reduction in public spending.";
+        assert_eq!(detect_language(fr).as_deref(), Some("fr"));
+        assert_eq!(detect_language(en).as_deref(), Some("en"));
+        assert_eq!(detect_language(""), None);
+    }
+
+    #[test]
+    fn test_language_name() {
+        assert_eq!(language_name("fr"), Some("French"));
+        assert_eq!(language_name("de"), Some("German"));
+        assert_eq!(language_name("xx"), None);
+    }
+}
@@ -1,20 +1,145 @@
 use anyhow::{Context, Result};
+use common::Config;
 use feed_rs::parser;
 use feed_rs::model::Feed;
-use reqwest::Client;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
+/// Options controlling how feeds are fetched and parsed.
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    pub timeout_secs: u64,
+    /// Retry with a sanitized body (control chars stripped, bare `&` escaped)
+    /// when the first parse fails.
+    pub lenient_parsing: bool,
+    /// Abort the download once the body grows past this many bytes.
+    pub max_response_bytes: u64,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 10,
+            lenient_parsing: false,
+            max_response_bytes: crate::http::DEFAULT_MAX_RESPONSE_BYTES,
+        }
+    }
+}
+
+impl FetchOptions {
+    /// Build fetch options from the `[politeness]` section.
+    pub fn from_config(config: Option<&Config>) -> Self {
+        let politeness = config.and_then(|c| c.politeness.as_ref());
+        let defaults = Self::default();
+        Self {
+            timeout_secs: politeness
+                .and_then(|p| p.fetch_timeout_seconds)
+                .unwrap_or(defaults.timeout_secs),
+            lenient_parsing: politeness
+                .and_then(|p| p.lenient_parsing)
+                .unwrap_or(defaults.lenient_parsing),
+            max_response_bytes: politeness
+                .and_then(|p| p.max_response_bytes)
+                .unwrap_or(defaults.max_response_bytes),
+        }
+    }
+}
 
 /// Fetches a feed from the given URL and parses it.
-/// Enforces a timeout and size limit (though size limit is tricky with streaming, 
-/// we'll rely on timeout and simple content-length check for now).
+/// Enforces a timeout and the configured response size limit.
 pub async fn fetch_and_parse_feed(url: &str, timeout_secs: u64) -> Result<Feed> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .user_agent("Newscope/0.1.0")
-        .build()
-        .context("failed to build reqwest client")?;
+    let opts = FetchOptions {
+        timeout_secs,
+        max_response_bytes: crate::http::max_response_bytes(),
+        ..FetchOptions::default()
+    };
+    fetch_and_parse_feed_with(url, &opts).await
+}
+
+/// Timing and size of one feed fetch, retries included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FetchMetrics {
+    /// Wall-clock time from the first request to the parsed feed (or the final error)
+    pub latency_ms: i64,
+    /// Size of the response body, when one was received
+    pub response_bytes: Option<i64>,
+    /// HTTP status of the last response, if any
+    pub http_status: Option<u16>,
+    pub attempts: u32,
+}
+
+/// Same as [`fetch_and_parse_feed`] with explicit options.
+pub async fn fetch_and_parse_feed_with(url: &str, opts: &FetchOptions) -> Result<Feed> {
+    fetch_and_parse_feed_measured(url, opts).await.0
+}
+
+/// Same as [`fetch_and_parse_feed_with`], also reporting how long the fetch took
+/// and how large the response was.
+pub async fn fetch_and_parse_feed_measured(url: &str, opts: &FetchOptions) -> (Result<Feed>, FetchMetrics) {
+    let (result, metrics) = fetch_feed_conditional(url, opts, &CacheValidators::default()).await;
+    let result = result.and_then(|outcome| match outcome {
+        FetchOutcome::Fetched { feed, .. } => Ok(*feed),
+        FetchOutcome::NotModified => Err(anyhow::anyhow!("unexpected 304 Not Modified")),
+    });
+    (result, metrics)
+}
+
+/// HTTP cache validators from a previous fetch, sent back as
+/// `If-None-Match` / `If-Modified-Since`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let get = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            etag: get(reqwest::header::ETAG),
+            last_modified: get(reqwest::header::LAST_MODIFIED),
+        }
+    }
+}
+
+/// Result of a conditional feed fetch.
+#[derive(Debug)]
+pub enum FetchOutcome {
+    /// The server answered 304: the feed is unchanged since the stored validators.
+    NotModified,
+    /// A fresh copy of the feed, with the validators to send next time.
+    Fetched { feed: Box<Feed>, validators: CacheValidators },
+}
 
+/// Fetch a feed, sending the validators from the previous fetch so an
+/// unchanged feed costs a 304 instead of a full download.
+pub async fn fetch_feed_conditional(
+    url: &str,
+    opts: &FetchOptions,
+    validators: &CacheValidators,
+) -> (Result<FetchOutcome>, FetchMetrics) {
+    let started = Instant::now();
+    let mut metrics = FetchMetrics::default();
+    let result = fetch_with_retries(url, opts, validators, &mut metrics).await;
+    metrics.latency_ms = started.elapsed().as_millis() as i64;
+    (result, metrics)
+}
+
+async fn fetch_with_retries(
+    url: &str,
+    opts: &FetchOptions,
+    validators: &CacheValidators,
+    metrics: &mut FetchMetrics,
+) -> Result<FetchOutcome> {
+    check_robots(url).await?;
+    let client = crate::http::client();
     let max_retries = 3;
     let mut last_error = None;
 
@@ -25,13 +150,29 @@ pub async fn fetch_and_parse_feed(url: &str, timeout_secs: u64) -> Result<Feed>
             tokio::time::sleep(backoff).await;
         }
 
-        match client.get(url).send().await {
+        metrics.attempts = attempt;
+        let mut request = client.get(url).timeout(Duration::from_secs(opts.timeout_secs));
+        if let Some(etag) = &validators.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        match request.send().await {
             Ok(response) => {
                 let status = response.status();
-                if status.is_success() {
-                    let bytes = response.bytes().await.context("failed to read response body")?;
-                    let feed = parser::parse(bytes.as_ref()).context("failed to parse feed")?;
-                    return Ok(feed);
+                metrics.http_status = Some(status.as_u16());
+                if status == reqwest::StatusCode::NOT_MODIFIED {
+                    metrics.response_bytes = Some(0);
+                    return Ok(FetchOutcome::NotModified);
+                } else if status.is_success() {
+                    let validators = CacheValidators::from_headers(response.headers());
+                    let content_type = content_type(&response);
+                    let bytes = crate::http::read_body_limited(response, opts.max_response_bytes).await?;
+                    metrics.response_bytes = Some(bytes.len() as i64);
+                    let bytes = decode_to_utf8(bytes, content_type.as_deref());
+                    let feed = parse_feed_bytes(url, bytes.as_ref(), opts.lenient_parsing)?;
+                    return Ok(FetchOutcome::Fetched { feed: Box::new(feed), validators });
                 } else if status.is_server_error() { // 5xx
                     last_error = Some(anyhow::anyhow!("server error: {}", status));
                     continue; // Retry
@@ -52,3 +193,802 @@ pub async fn fetch_and_parse_feed(url: &str, timeout_secs: u64) -> Result<Feed>
 
     Err(last_error.unwrap_or_else(|| anyhow::anyhow!("unknown error after retries")))
 }
+
+/// Default for `politeness.robots_ttl_seconds`.
+pub const DEFAULT_ROBOTS_TTL_SECS: u64 = 86_400;
+
+/// A fetch refused because the site's robots.txt disallows the URL for us.
+/// Not a feed failure: callers should not back off on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RobotsBlocked {
+    pub url: String,
+}
+
+impl std::fmt::Display for RobotsBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is disallowed by robots.txt", self.url)
+    }
+}
+
+impl std::error::Error for RobotsBlocked {}
+
+/// Allow/Disallow rules of the robots.txt group that applies to us.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsRules {
+    /// (allow, path pattern)
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Parse `content`, keeping the group(s) naming `agent` (case-insensitive
+    /// product token, e.g. "newscope") or, failing that, the `*` group.
+    pub fn parse(content: &str, agent: &str) -> Self {
+        let agent = agent.to_lowercase();
+        let mut specific = Vec::new();
+        let mut wildcard = Vec::new();
+        let mut found_specific = false;
+
+        // User-agent lines in a row open one group; a rule line closes the list
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    group_agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty Disallow allows everything: no rule needed
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (key == "allow", value.to_string());
+                    if group_agents.iter().any(|a| a.split('/').next() == Some(agent.as_str())) {
+                        found_specific = true;
+                        specific.push(rule);
+                    } else if group_agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self {
+            rules: if found_specific { specific } else { wildcard },
+        }
+    }
+
+    /// Whether `path` (path and query) may be fetched: the longest matching
+    /// pattern wins, Allow winning ties; no match means allowed.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if robots_pattern_matches(pattern, path) {
+                let len = pattern.len();
+                best = match best {
+                    Some((l, a)) if l > len || (l == len && a) => Some((l, a)),
+                    _ => Some((len, *allow)),
+                };
+            }
+        }
+        best.is_none_or(|(_, allow)| allow)
+    }
+}
+
+/// robots.txt path matching: prefix match, `*` matches any run of characters
+/// and a trailing `$` anchors the end.
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i + 1 == parts.len();
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// robots.txt rules per site (scheme + host + port), refreshed after `ttl`.
+pub struct RobotsCache {
+    ttl: Duration,
+    timeout: Duration,
+    entries: Mutex<HashMap<String, (Instant, Arc<RobotsRules>)>>,
+}
+
+impl RobotsCache {
+    pub fn new(ttl: Duration, timeout: Duration) -> Self {
+        Self {
+            ttl,
+            timeout,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether our user agent may fetch `url`, downloading the site's
+    /// robots.txt when it is not cached or has expired.
+    pub async fn is_allowed(&self, url: &str) -> Result<bool> {
+        let parsed = url::Url::parse(url).with_context(|| format!("invalid URL {}", url))?;
+        let origin = parsed.origin().ascii_serialization();
+        let mut path = parsed.path().to_string();
+        if let Some(query) = parsed.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+
+        let cached = self
+            .lock()
+            .get(&origin)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < self.ttl)
+            .map(|(_, rules)| rules.clone());
+        let rules = match cached {
+            Some(rules) => rules,
+            None => {
+                let rules = Arc::new(self.download(&origin).await);
+                self.lock().insert(origin, (Instant::now(), rules.clone()));
+                rules
+            }
+        };
+        Ok(rules.is_allowed(&path))
+    }
+
+    /// A missing robots.txt (4xx) allows everything. So does an unreachable
+    /// one: a personal reader should not go dark because a site hiccuped.
+    async fn download(&self, origin: &str) -> RobotsRules {
+        let robots_url = format!("{}/robots.txt", origin);
+        let agent = crate::http::USER_AGENT.split('/').next().unwrap_or("newscope");
+        match crate::http::client().get(&robots_url).timeout(self.timeout).send().await {
+            Ok(resp) if resp.status().is_success() => match resp.text().await {
+                Ok(body) => RobotsRules::parse(&body, agent),
+                Err(e) => {
+                    tracing::debug!("failed to read {}: {}", robots_url, e);
+                    RobotsRules::default()
+                }
+            },
+            Ok(resp) => {
+                tracing::debug!("{} returned {}, treating as allow-all", robots_url, resp.status());
+                RobotsRules::default()
+            }
+            Err(e) => {
+                tracing::debug!("failed to fetch {}: {}", robots_url, e);
+                RobotsRules::default()
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Instant, Arc<RobotsRules>)>> {
+        match self.entries.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+static ROBOTS: OnceLock<RobotsCache> = OnceLock::new();
+
+/// Enable robots.txt checks when `politeness.respect_robots_txt` is set.
+/// Call once at startup; without it no check is made.
+pub fn init_robots(config: Option<&Config>) {
+    let politeness = config.and_then(|c| c.politeness.as_ref());
+    if !politeness.and_then(|p| p.respect_robots_txt).unwrap_or(false) {
+        return;
+    }
+    let ttl = politeness
+        .and_then(|p| p.robots_ttl_seconds)
+        .unwrap_or(DEFAULT_ROBOTS_TTL_SECS);
+    let timeout = FetchOptions::from_config(config).timeout_secs;
+    let _ = ROBOTS.set(RobotsCache::new(Duration::from_secs(ttl), Duration::from_secs(timeout)));
+}
+
+/// Fail with [`RobotsBlocked`] when robots checks are enabled and disallow `url`.
+pub async fn check_robots(url: &str) -> Result<()> {
+    if let Some(cache) = ROBOTS.get() {
+        if !cache.is_allowed(url).await? {
+            return Err(RobotsBlocked { url: url.to_string() }.into());
+        }
+    }
+    Ok(())
+}
+
+/// `type` values of `<link rel="alternate">` tags that point at a feed.
+const FEED_LINK_TYPES: &[&str] = &["application/rss+xml", "application/atom+xml"];
+
+/// Feed URLs advertised by an HTML page through `<link rel="alternate"
+/// type="application/rss+xml|atom+xml" href="...">`, resolved against
+/// `base_url`, in document order and without duplicates.
+pub fn find_feed_links(html: &str, base_url: &str) -> Vec<String> {
+    let document = scraper::Html::parse_document(html);
+    let selector = scraper::Selector::parse("link[href]").expect("static selector");
+    let base = url::Url::parse(base_url).ok();
+
+    let mut links: Vec<String> = Vec::new();
+    for link in document.select(&selector) {
+        let attrs = link.value();
+        let is_alternate = attrs
+            .attr("rel")
+            .is_some_and(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("alternate")));
+        let is_feed = attrs
+            .attr("type")
+            .is_some_and(|t| FEED_LINK_TYPES.iter().any(|f| t.trim().eq_ignore_ascii_case(f)));
+        if !(is_alternate && is_feed) {
+            continue;
+        }
+        let href = attrs.attr("href").unwrap_or_default().trim();
+        let resolved = match &base {
+            Some(base) => base.join(href).map(|u| u.to_string()).ok(),
+            None => url::Url::parse(href).map(|u| u.to_string()).ok(),
+        };
+        if let Some(url) = resolved.filter(|u| !links.contains(u)) {
+            links.push(url);
+        }
+    }
+    links
+}
+
+/// One GET, no retries: used on URLs typed in by a user, who is waiting.
+async fn fetch_page(url: &str, timeout_secs: u64) -> Result<Vec<u8>> {
+    check_robots(url).await?;
+    let response = crate::http::client()
+        .get(url)
+        .timeout(Duration::from_secs(timeout_secs))
+        .send()
+        .await
+        .with_context(|| format!("failed to fetch {}", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!("fetching {} failed with status: {}", url, response.status());
+    }
+    let content_type = content_type(&response);
+    let body = crate::http::read_body_limited(response, crate::http::max_response_bytes()).await?;
+    Ok(decode_to_utf8(body, content_type.as_deref()))
+}
+
+/// Candidate feed URLs advertised by the page at `url` (see [`find_feed_links`]).
+pub async fn discover_feed_url(url: &str, timeout_secs: u64) -> Result<Vec<String>> {
+    let page = fetch_page(url, timeout_secs).await?;
+    Ok(find_feed_links(&String::from_utf8_lossy(&page), url))
+}
+
+/// A feed found for a URL submitted by a user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedFeed {
+    pub feed_url: String,
+    pub title: Option<String>,
+    /// The submitted page, when the feed was discovered from it
+    pub site_url: Option<String>,
+}
+
+/// Resolve a user-submitted URL to a feed: the URL itself when it serves one,
+/// otherwise the first feed advertised by its HTML page that parses.
+pub async fn resolve_feed_url(url: &str, timeout_secs: u64) -> Result<ResolvedFeed> {
+    Ok(fetch_resolved_feed(url, timeout_secs).await?.0)
+}
+
+/// A user-submitted URL serving neither a feed nor a page advertising one that
+/// parses. Unlike fetch errors, retrying will not help.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotAFeed {
+    pub url: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for NotAFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no feed found at {}: {}", self.url, self.reason)
+    }
+}
+
+impl std::error::Error for NotAFeed {}
+
+/// Same as [`resolve_feed_url`], also returning the parsed feed. Fails with
+/// [`NotAFeed`] when the URL could be fetched but no feed was found.
+pub async fn fetch_resolved_feed(url: &str, timeout_secs: u64) -> Result<(ResolvedFeed, Feed)> {
+    let page = fetch_page(url, timeout_secs).await?;
+    let parse_error = match parse_feed_bytes(url, &page, false) {
+        Ok(feed) => {
+            let resolved = ResolvedFeed {
+                feed_url: url.to_string(),
+                title: feed.title.as_ref().map(|t| t.content.clone()),
+                site_url: None,
+            };
+            return Ok((resolved, feed));
+        }
+        Err(e) => e,
+    };
+
+    let candidates = find_feed_links(&String::from_utf8_lossy(&page), url);
+    for candidate in &candidates {
+        match fetch_and_parse_feed(candidate, timeout_secs).await {
+            Ok(feed) => {
+                tracing::info!("Discovered feed {} from {}", candidate, url);
+                let resolved = ResolvedFeed {
+                    feed_url: candidate.clone(),
+                    title: feed.title.as_ref().map(|t| t.content.clone()),
+                    site_url: Some(url.to_string()),
+                };
+                return Ok((resolved, feed));
+            }
+            Err(e) => tracing::debug!("Advertised feed {} of {} failed: {:?}", candidate, url, e),
+        }
+    }
+    let reason = if candidates.is_empty() {
+        format!("{:#}", parse_error)
+    } else {
+        format!("none of the {} advertised feeds could be loaded", candidates.len())
+    };
+    Err(NotAFeed { url: url.to_string(), reason }.into())
+}
+
+/// Longest entry snippet in a [`FeedPreview`], in characters.
+pub const PREVIEW_SNIPPET_CHARS: usize = 280;
+
+/// What subscribing to a URL would bring in, see [`preview_feed`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FeedPreview {
+    /// The feed that would be subscribed to
+    pub feed_url: String,
+    /// Whether `feed_url` was discovered from the submitted page
+    pub discovered: bool,
+    pub site_url: Option<String>,
+    pub title: Option<String>,
+    pub item_count: usize,
+    /// The first entries, in feed order
+    pub entries: Vec<PreviewEntry>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PreviewEntry {
+    pub title: Option<String>,
+    pub link: Option<String>,
+    pub published: Option<chrono::DateTime<chrono::Utc>>,
+    /// Start of the entry's text, markup removed
+    pub snippet: Option<String>,
+}
+
+/// Fetch and resolve `url` like a subscription would, without storing anything,
+/// keeping the first `limit` entries.
+pub async fn preview_feed(url: &str, timeout_secs: u64, limit: usize) -> Result<FeedPreview> {
+    let (resolved, feed) = fetch_resolved_feed(url, timeout_secs).await?;
+    let entries = feed
+        .entries
+        .iter()
+        .take(limit)
+        .map(|entry| {
+            let text = crate::storage::entry_text(entry);
+            let snippet = match text.char_indices().nth(PREVIEW_SNIPPET_CHARS) {
+                Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+                None => text,
+            };
+            PreviewEntry {
+                title: entry.title.as_ref().map(|t| t.content.clone()),
+                link: entry.links.first().map(|l| l.href.clone()),
+                published: entry.published.or(entry.updated),
+                snippet: Some(snippet).filter(|s| !s.is_empty()),
+            }
+        })
+        .collect();
+    Ok(FeedPreview {
+        discovered: resolved.site_url.is_some(),
+        feed_url: resolved.feed_url,
+        site_url: resolved.site_url,
+        title: resolved.title,
+        item_count: feed.entries.len(),
+        entries,
+    })
+}
+
+fn content_type(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Character set of a document: its byte order mark, else the `charset` of
+/// its `Content-Type`, else the `encoding` of its XML declaration (the order
+/// RFC 7303 gives them precedence in). `None` when none is declared.
+pub fn detect_encoding(bytes: &[u8], content_type: Option<&str>) -> Option<&'static encoding_rs::Encoding> {
+    if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(bytes) {
+        return Some(encoding);
+    }
+    let from_header = content_type.and_then(|ct| {
+        ct.split(';')
+            .skip(1)
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+            .and_then(|(_, value)| encoding_rs::Encoding::for_label(value.trim().trim_matches('"').as_bytes()))
+    });
+    from_header.or_else(|| xml_declared_encoding(bytes))
+}
+
+/// `encoding="..."` of the `<?xml ...?>` declaration opening the document.
+fn xml_declared_encoding(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    let head = &bytes[..bytes.len().min(1024)];
+    let text = String::from_utf8_lossy(head);
+    let declaration = text.trim_start().strip_prefix("<?xml")?;
+    let declaration = &declaration[..declaration.find("?>")?];
+    let value = declaration.split("encoding").nth(1)?.trim_start().strip_prefix('=')?.trim_start();
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let label = &value[1..value[1..].find(quote)? + 1];
+    encoding_rs::Encoding::for_label(label.as_bytes())
+}
+
+/// Transcode a fetched document to UTF-8 (see [`detect_encoding`]), so that
+/// feeds in ISO-8859-1, Windows-1252 and the like parse without mojibake. A
+/// document declaring nothing that is not valid UTF-8 is read as
+/// Windows-1252, the usual culprit. The XML declaration of a transcoded
+/// document is rewritten to say UTF-8.
+pub fn decode_to_utf8(bytes: Vec<u8>, content_type: Option<&str>) -> Vec<u8> {
+    let encoding = match detect_encoding(&bytes, content_type) {
+        Some(encoding) => encoding,
+        None if std::str::from_utf8(&bytes).is_ok() => return bytes,
+        None => encoding_rs::WINDOWS_1252,
+    };
+    if encoding == encoding_rs::UTF_8 && !bytes.starts_with(b"\xEF\xBB\xBF") {
+        return bytes;
+    }
+    let (text, _, had_errors) = encoding.decode(&bytes);
+    if had_errors {
+        tracing::debug!("Some bytes were not valid {} and were replaced", encoding.name());
+    }
+    match xml_declared_encoding(text.as_bytes()).filter(|declared| *declared != encoding_rs::UTF_8) {
+        Some(_) => rewrite_xml_encoding(&text).into_bytes(),
+        None => text.into_owned().into_bytes(),
+    }
+}
+
+/// Replace the `encoding` of the leading XML declaration with UTF-8.
+fn rewrite_xml_encoding(text: &str) -> String {
+    let Some(end) = text.find("?>") else {
+        return text.to_string();
+    };
+    let (declaration, rest) = text.split_at(end);
+    let re = regex::Regex::new(r#"encoding\s*=\s*("[^"]*"|'[^']*')"#).expect("valid regex");
+    format!("{}{}", re.replace(declaration, r#"encoding="UTF-8""#), rest)
+}
+
+/// Parse raw feed bytes. With `lenient`, a failed parse is retried once on a
+/// sanitized copy of the document.
+///
+/// feed_rs ignores the RSS syndication module, so when the feed has no `<ttl>`
+/// its `sy:updatePeriod`/`sy:updateFrequency` cadence is stored in `ttl` instead.
+pub fn parse_feed_bytes(url: &str, bytes: &[u8], lenient: bool) -> Result<Feed> {
+    let mut feed = parse_feed_document(url, bytes, lenient)?;
+    if feed.ttl.is_none() {
+        feed.ttl = declared_update_minutes(bytes);
+    }
+    Ok(feed)
+}
+
+fn parse_feed_document(url: &str, bytes: &[u8], lenient: bool) -> Result<Feed> {
+    match parser::parse(bytes) {
+        Ok(feed) => Ok(feed),
+        Err(e) if lenient => {
+            // feed_rs tolerates bare `&` and unknown entities by silently dropping the
+            // affected text, but stray `<` and similar break the parse outright.
+            tracing::debug!("Parse failed for {} ({}), retrying with sanitized XML", url, e);
+            let cleaned = sanitize_xml(bytes);
+            match parser::parse(cleaned.as_bytes()) {
+                Ok(feed) => {
+                    tracing::info!("Recovered malformed feed {} after sanitization", url);
+                    Ok(feed)
+                }
+                Err(_) => Err(anyhow::Error::new(e).context("failed to parse feed (sanitization did not help)")),
+            }
+        }
+        Err(e) => Err(anyhow::Error::new(e).context("failed to parse feed")),
+    }
+}
+
+/// Shortest and longest poll interval (minutes) the scheduler works with.
+pub const MIN_POLL_INTERVAL_MINUTES: i64 = 15;
+pub const MAX_POLL_INTERVAL_MINUTES: i64 = 1440;
+
+/// Update cadence declared by the channel through the RSS syndication module
+/// (`<sy:updatePeriod>` times `<sy:updateFrequency>`), in minutes.
+/// Item-level elements are ignored.
+pub fn declared_update_minutes(bytes: &[u8]) -> Option<u32> {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let mut reader = Reader::from_reader(bytes);
+    reader.trim_text(true);
+
+    let mut period = None;
+    let mut frequency = None;
+    let mut current: Option<Vec<u8>> = None;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let name = e.local_name().as_ref().to_vec();
+                if name == b"item" || name == b"entry" {
+                    break;
+                }
+                current = Some(name);
+            }
+            Ok(Event::Text(t)) => {
+                let text = t.unescape().map(|v| v.trim().to_lowercase()).unwrap_or_default();
+                match current.as_deref() {
+                    Some(b"updatePeriod") => period = Some(text),
+                    Some(b"updateFrequency") => frequency = text.parse::<u32>().ok(),
+                    _ => {}
+                }
+            }
+            Ok(Event::End(_)) => current = None,
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let period_minutes: u32 = match period?.as_str() {
+        "hourly" => 60,
+        "daily" => 1440,
+        "weekly" => 7 * 1440,
+        "monthly" => 30 * 1440,
+        "yearly" => 365 * 1440,
+        _ => return None,
+    };
+    // updateFrequency is the number of updates per period (default 1)
+    Some(period_minutes / frequency.filter(|&f| f > 0).unwrap_or(1).min(period_minutes))
+}
+
+/// Poll interval suggested by the feed itself (`<ttl>` or `sy:updatePeriod`),
+/// clamped to the scheduler's bounds.
+pub fn declared_poll_interval(feed: &Feed) -> Option<i64> {
+    feed.ttl
+        .filter(|&ttl| ttl > 0)
+        .map(|ttl| (ttl as i64).clamp(MIN_POLL_INTERVAL_MINUTES, MAX_POLL_INTERVAL_MINUTES))
+}
+
+/// Best-effort repair of common XML breakage in the wild:
+/// - strips control characters that XML 1.0 forbids
+/// - escapes `<` that can't start a tag (e.g. "a < b" in a title)
+/// - escapes `&` that doesn't start a valid entity reference
+/// - rewrites common HTML named entities to numeric references
+///
+/// CDATA sections are copied untouched.
+pub fn sanitize_xml(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let text: String = text
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while !rest.is_empty() {
+        if rest.starts_with("<![CDATA[") {
+            let end = rest.find("]]>").map(|i| i + 3).unwrap_or(rest.len());
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if let Some(after) = rest.strip_prefix('<') {
+            let starts_tag = after
+                .chars()
+                .next()
+                .map(|c| c.is_alphabetic() || matches!(c, '/' | '!' | '?' | '_'))
+                .unwrap_or(false);
+            out.push_str(if starts_tag { "<" } else { "&lt;" });
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('&') {
+            let entity_len = after
+                .find(';')
+                .filter(|&i| i > 0 && i <= 10)
+                .filter(|&i| after[..i].chars().all(|c| c.is_ascii_alphanumeric() || c == '#'));
+            match entity_len {
+                Some(i) => {
+                    let name = &after[..i];
+                    match html_entity(name) {
+                        Some(code) => out.push_str(&format!("&#{};", code)),
+                        None if is_valid_xml_entity(name) => {
+                            out.push('&');
+                            out.push_str(name);
+                            out.push(';');
+                        }
+                        None => {
+                            out.push_str("&amp;");
+                            out.push_str(name);
+                            out.push(';');
+                        }
+                    }
+                    rest = &after[i + 1..];
+                }
+                None => {
+                    out.push_str("&amp;");
+                    rest = after;
+                }
+            }
+        } else {
+            // Past the first char, which may be multi-byte (`&é`, `<é`)
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            let next = rest[first..]
+                .find(['&', '<'])
+                .map(|i| i + first)
+                .unwrap_or(rest.len());
+            out.push_str(&rest[..next]);
+            rest = &rest[next..];
+        }
+    }
+    out
+}
+
+fn is_valid_xml_entity(name: &str) -> bool {
+    if let Some(num) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+        return !num.is_empty() && num.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    if let Some(num) = name.strip_prefix('#') {
+        return !num.is_empty() && num.chars().all(|c| c.is_ascii_digit());
+    }
+    matches!(name, "amp" | "lt" | "gt" | "quot" | "apos")
+}
+
+/// Code points for HTML entities commonly (and illegally) found in feeds.
+fn html_entity(name: &str) -> Option<u32> {
+    Some(match name {
+        "nbsp" => 160,
+        "copy" => 169,
+        "reg" => 174,
+        "laquo" => 171,
+        "raquo" => 187,
+        "eacute" => 233,
+        "egrave" => 232,
+        "agrave" => 224,
+        "ccedil" => 231,
+        "ndash" => 8211,
+        "mdash" => 8212,
+        "lsquo" => 8216,
+        "rsquo" => 8217,
+        "ldquo" => 8220,
+        "rdquo" => 8221,
+        "hellip" => 8230,
+        "euro" => 8364,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Real-world breakage: stray `<` in a title, bare `&`, a control char.
+    const BROKEN_FEED: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<rss version=\"2.0\"><channel><title>Tom & Jerry News</title>
+<item>
+  <title>Rates < 2% for \u{0001}savers</title>
+  <link>https://example.com/a?x=1&y=2</link>
+  <description><![CDATA[Raw & fine]]></description>
+</item>
+</channel></rss>";
+
+    #[test]
+    fn strict_parse_rejects_broken_feed() {
+        assert!(parse_feed_bytes("test", BROKEN_FEED.as_bytes(), false).is_err());
+    }
+
+    #[test]
+    fn lenient_parse_recovers_broken_feed() {
+        let feed = parse_feed_bytes("test", BROKEN_FEED.as_bytes(), true).expect("recovered");
+        assert_eq!(feed.title.unwrap().content, "Tom & Jerry News");
+        let entry = &feed.entries[0];
+        assert_eq!(entry.links[0].href, "https://example.com/a?x=1&y=2");
+        assert_eq!(entry.title.as_ref().unwrap().content, "Rates < 2% for savers");
+    }
+
+    #[test]
+    fn syndication_period_fills_missing_ttl() {
+        let rss = |channel: &str| {
+            format!(
+                "<rss version=\"2.0\" xmlns:sy=\"http://purl.org/rss/1.0/modules/syndication/\">\
+                 <channel><title>Blog</title>{}<item><title>A</title>\
+                 <sy:updatePeriod>hourly</sy:updatePeriod></item></channel></rss>",
+                channel
+            )
+        };
+
+        let weekly = rss("<sy:updatePeriod>weekly</sy:updatePeriod>");
+        assert_eq!(declared_update_minutes(weekly.as_bytes()), Some(10080));
+        let feed = parse_feed_bytes("test", weekly.as_bytes(), false).unwrap();
+        assert_eq!(declared_poll_interval(&feed), Some(MAX_POLL_INTERVAL_MINUTES));
+
+        let twice_hourly = rss("<sy:updatePeriod> Hourly </sy:updatePeriod><sy:updateFrequency>2</sy:updateFrequency>");
+        let feed = parse_feed_bytes("test", twice_hourly.as_bytes(), false).unwrap();
+        assert_eq!(feed.ttl, Some(30));
+        assert_eq!(declared_poll_interval(&feed), Some(30));
+
+        // <ttl> wins; item-level declarations are ignored
+        let with_ttl = rss("<ttl>120</ttl><sy:updatePeriod>daily</sy:updatePeriod>");
+        let feed = parse_feed_bytes("test", with_ttl.as_bytes(), false).unwrap();
+        assert_eq!(feed.ttl, Some(120));
+        let undeclared = rss("");
+        assert_eq!(declared_update_minutes(undeclared.as_bytes()), None);
+        let feed = parse_feed_bytes("test", undeclared.as_bytes(), false).unwrap();
+        assert_eq!(declared_poll_interval(&feed), None);
+    }
+
+    #[test]
+    fn robots_rules_pick_our_group_and_longest_match() {
+        let robots = "User-agent: *\nDisallow: /\n\n\
+                      User-agent: GoogleBot\nUser-agent: newscope\n\
+                      Disallow: /private/\nAllow: /private/feed.xml$\nDisallow: /*.pdf$\n";
+        let rules = RobotsRules::parse(robots, "Newscope");
+        assert!(rules.is_allowed("/feed.xml"));
+        assert!(!rules.is_allowed("/private/notes"));
+        assert!(rules.is_allowed("/private/feed.xml"));
+        assert!(!rules.is_allowed("/private/feed.xml?page=2"));
+        assert!(!rules.is_allowed("/docs/report.pdf"));
+        assert!(rules.is_allowed("/docs/report.pdf.html"));
+
+        // Not named: the wildcard group applies
+        let rules = RobotsRules::parse(robots, "OtherBot");
+        assert!(!rules.is_allowed("/feed.xml"));
+        // Empty Disallow allows everything
+        assert!(RobotsRules::parse("User-agent: *\nDisallow:\n", "newscope").is_allowed("/a"));
+    }
+
+    #[test]
+    fn feed_links_are_found_and_resolved() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" type="text/css" href="/style.css">
+            <link rel="alternate" type="application/rss+xml" title="News" href="/feed.xml">
+            <link rel="Alternate home" type="application/atom+xml" href="https://cdn.example/atom">
+            <link rel="alternate" type="text/html" hreflang="fr" href="/fr/">
+            <link rel="alternate" type="application/rss+xml" href="feed.xml">
+        </head><body><a href="/other.rss">not a link tag</a></body></html>"#;
+        assert_eq!(
+            find_feed_links(html, "https://example.com/"),
+            vec!["https://example.com/feed.xml".to_string(), "https://cdn.example/atom".to_string()]
+        );
+        assert!(find_feed_links("<p>nothing</p>", "https://example.com/").is_empty());
+    }
+
+    #[test]
+    fn sanitize_keeps_valid_entities_and_cdata() {
+        let out = sanitize_xml(b"<a>&lt;&#38;&#x26;&amp;<![CDATA[a & b]]></a>");
+        assert_eq!(out, "<a>&lt;&#38;&#x26;&amp;<![CDATA[a & b]]></a>");
+    }
+
+    #[test]
+    fn sanitize_handles_multibyte_text_after_markup() {
+        assert_eq!(sanitize_xml("<t>R&é</t>".as_bytes()), "<t>R&amp;é</t>");
+        assert_eq!(sanitize_xml("<t>a <é & ü</t>".as_bytes()), "<t>a <é &amp; ü</t>");
+        assert_eq!(sanitize_xml("<t>1 < 2 &</t>".as_bytes()), "<t>1 &lt; 2 &amp;</t>");
+    }
+
+    #[test]
+    fn charset_precedence_and_transcoding() {
+        let latin1 = b"<?xml version='1.0' encoding='ISO-8859-1'?><t>\xE9t\xE9</t>";
+        assert_eq!(detect_encoding(latin1, None), Some(encoding_rs::WINDOWS_1252));
+        // The header wins over the declaration
+        assert_eq!(detect_encoding(latin1, Some("text/xml; charset=\"utf-8\"")), Some(encoding_rs::UTF_8));
+        assert_eq!(detect_encoding(b"<t/>", Some("text/xml")), None);
+
+        let out = String::from_utf8(decode_to_utf8(latin1.to_vec(), None)).unwrap();
+        assert_eq!(out, "<?xml version='1.0' encoding=\"UTF-8\"?><t>été</t>");
+        // Undeclared and not UTF-8: read as Windows-1252
+        assert_eq!(decode_to_utf8(b"<t>\x93ok\x94</t>".to_vec(), None), "<t>\u{201C}ok\u{201D}</t>".as_bytes());
+        assert_eq!(decode_to_utf8("<t>été</t>".as_bytes().to_vec(), None), "<t>été</t>".as_bytes());
+    }
+}
@@ -0,0 +1,385 @@
+//! Queue of background LLM work, persisted in `processing_jobs`.
+//!
+//! Ingestion only enqueues `pending` jobs (summary of each new article); the
+//! worker's [`JobRunner`] claims them one at a time, runs them and marks them
+//! `completed`, or `failed` once [`MAX_ATTEMPTS`] claims have failed. A summary
+//! job enqueues the personalization and embedding jobs of its article.
+//!
+//! A claim sets `claimed_at` and bumps `attempts` in a single `UPDATE`, so two
+//! consumers never run the same job. Jobs left `running` for longer than
+//! [`STALE_AFTER_MINUTES`], by a process that died, are put back to `pending`
+//! ([`requeue_stale`]) when the runner starts and between jobs, and are thus
+//! retried instead of lost. Younger claims may belong to another live runner
+//! sharing the database, and are left alone.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tracing::{error, info, warn, Instrument};
+
+use crate::config_reload::SharedConfig;
+use crate::llm::{LlmProvider, LlmTask};
+use crate::worker_status::{self, SharedWorkerStatus};
+
+/// Summarize, classify and index an article (`entity_id` is the article).
+pub const JOB_SUMMARY: &str = "article_summary";
+/// Personalize a summarized article for every user.
+pub const JOB_PERSONALIZATION: &str = "article_personalization";
+/// Embed an article into `vec_articles`.
+pub const JOB_EMBEDDING: &str = "article_embedding";
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_RUNNING: &str = "running";
+pub const STATUS_COMPLETED: &str = "completed";
+pub const STATUS_FAILED: &str = "failed";
+pub const STATUSES: &[&str] = &[STATUS_PENDING, STATUS_RUNNING, STATUS_COMPLETED, STATUS_FAILED];
+
+/// Claims of a job before it is marked failed.
+pub const MAX_ATTEMPTS: i64 = 3;
+/// Delay before a failed job may be claimed again.
+pub const RETRY_DELAY_SECONDS: i64 = 60;
+/// A job `running` for this long is assumed abandoned by another process.
+pub const STALE_AFTER_MINUTES: i64 = 30;
+/// Pause of the runner when the queue is empty.
+const IDLE_POLL: Duration = Duration::from_secs(5);
+
+/// A row of `processing_jobs`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Job {
+    pub id: i64,
+    pub job_type: String,
+    pub entity_id: Option<i64>,
+    pub status: String,
+    pub attempts: i64,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub error_message: Option<String>,
+    pub llm_model: Option<String>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub processing_time_ms: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Enqueue a `job_type` job for each entity that has no pending or running
+/// one already. Returns the number of jobs added.
+pub async fn enqueue(pool: &SqlitePool, job_type: &str, entity_ids: &[i64]) -> Result<usize> {
+    let mut tx = pool.begin().await?;
+    let mut added = 0;
+    for &entity_id in entity_ids {
+        added += sqlx::query(
+            "INSERT INTO processing_jobs (job_type, entity_id, status, created_at)
+             SELECT ?, ?, 'pending', ?
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM processing_jobs
+                 WHERE job_type = ? AND entity_id = ? AND status IN ('pending', 'running')
+             )",
+        )
+        .bind(job_type)
+        .bind(entity_id)
+        .bind(Utc::now())
+        .bind(job_type)
+        .bind(entity_id)
+        .execute(&mut tx)
+        .await
+        .with_context(|| format!("failed to enqueue {} job for {}", job_type, entity_id))?
+        .rows_affected() as usize;
+    }
+    tx.commit().await?;
+    Ok(added)
+}
+
+/// Enqueue summary jobs for up to `limit` articles still pending processing,
+/// newest first.
+pub async fn enqueue_pending_articles(pool: &SqlitePool, limit: i64) -> Result<usize> {
+    let ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM articles WHERE COALESCE(processing_status, 'pending') = 'pending' ORDER BY id DESC LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("failed to fetch pending articles")?;
+    enqueue(pool, JOB_SUMMARY, &ids).await
+}
+
+/// Enqueue embedding jobs for up to `limit` articles without a vector, newest
+/// first: new articles when no summary job will do it, and vectors dropped by
+/// a change of embedding text or model. With `summarized_only`, articles still
+/// waiting for their summary are left to the summary job. Articles whose
+/// embedding job failed for good are not retried.
+pub async fn enqueue_missing_embeddings(pool: &SqlitePool, summarized_only: bool, limit: i64) -> Result<usize> {
+    let ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT a.id FROM articles a
+         WHERE NOT EXISTS (SELECT 1 FROM vec_articles v WHERE v.article_id = a.id)
+           AND (? = 0 OR a.processing_status = 'completed')
+           AND NOT EXISTS (
+               SELECT 1 FROM processing_jobs j
+               WHERE j.job_type = ? AND j.entity_id = a.id AND j.status = 'failed'
+           )
+         ORDER BY a.id DESC LIMIT ?",
+    )
+    .bind(summarized_only)
+    .bind(JOB_EMBEDDING)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("failed to fetch articles without embeddings")?;
+    enqueue(pool, JOB_EMBEDDING, &ids).await
+}
+
+/// Claim the oldest pending job that is not waiting out its retry delay, marking
+/// it running. Atomic: concurrent consumers never get the same job.
+pub async fn claim(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Option<Job>> {
+    // fetch_all: the RETURNING statement must run to completion, or sqlx leaves
+    // its write uncommitted
+    let claimed = sqlx::query_as::<_, Job>(
+        "UPDATE processing_jobs
+         SET status = 'running', claimed_at = ?, started_at = ?, attempts = attempts + 1
+         WHERE id = (
+             SELECT id FROM processing_jobs
+             WHERE status = 'pending' AND (claimed_at IS NULL OR claimed_at <= ?)
+             ORDER BY id
+             LIMIT 1
+         )
+         RETURNING *",
+    )
+    .bind(now)
+    .bind(now)
+    .bind(now - chrono::Duration::seconds(RETRY_DELAY_SECONDS))
+    .fetch_all(pool)
+    .await
+    .context("failed to claim a job")?;
+    Ok(claimed.into_iter().next())
+}
+
+/// Mark a job completed, with the model and tokens it used.
+pub async fn complete(pool: &SqlitePool, id: i64, outcome: &JobOutcome, elapsed: Duration) -> Result<()> {
+    sqlx::query(
+        "UPDATE processing_jobs
+         SET status = 'completed', completed_at = ?, error_message = NULL, llm_model = ?,
+             prompt_tokens = ?, completion_tokens = ?, processing_time_ms = ?
+         WHERE id = ?",
+    )
+    .bind(Utc::now())
+    .bind(&outcome.model)
+    .bind(outcome.prompt_tokens)
+    .bind(outcome.completion_tokens)
+    .bind(elapsed.as_millis() as i64)
+    .bind(id)
+    .execute(pool)
+    .await
+    .context("failed to complete job")?;
+    Ok(())
+}
+
+/// Record a failed attempt: the job goes back to pending (claimable again after
+/// [`RETRY_DELAY_SECONDS`]) while it has attempts left, and is marked failed
+/// otherwise. Returns the new status.
+pub async fn fail(pool: &SqlitePool, job: &Job, error: &str) -> Result<&'static str> {
+    let status = if job.attempts < MAX_ATTEMPTS { STATUS_PENDING } else { STATUS_FAILED };
+    let now = Utc::now();
+    sqlx::query(
+        "UPDATE processing_jobs SET status = ?, error_message = ?, claimed_at = ?, completed_at = ? WHERE id = ?",
+    )
+    .bind(status)
+    .bind(error)
+    .bind(now)
+    .bind((status == STATUS_FAILED).then_some(now))
+    .bind(job.id)
+    .execute(pool)
+    .await
+    .context("failed to record job failure")?;
+    Ok(status)
+}
+
+/// Put back to pending the jobs running since before `cutoff`, or mark them
+/// failed when they used up their attempts. Returns the number of jobs reset.
+pub async fn requeue_stale(pool: &SqlitePool, cutoff: DateTime<Utc>) -> Result<u64> {
+    let result = sqlx::query(
+        "UPDATE processing_jobs
+         SET status = CASE WHEN attempts < ? THEN 'pending' ELSE 'failed' END,
+             completed_at = CASE WHEN attempts < ? THEN NULL ELSE ? END,
+             error_message = 'abandoned while running'
+         WHERE status = 'running' AND (claimed_at IS NULL OR claimed_at <= ?)",
+    )
+    .bind(MAX_ATTEMPTS)
+    .bind(MAX_ATTEMPTS)
+    .bind(Utc::now())
+    .bind(cutoff)
+    .execute(pool)
+    .await
+    .context("failed to requeue stale jobs")?;
+    Ok(result.rows_affected())
+}
+
+/// Most recent jobs, optionally only those with `status`.
+pub async fn list(pool: &SqlitePool, status: Option<&str>, limit: i64) -> Result<Vec<Job>> {
+    sqlx::query_as::<_, Job>(
+        "SELECT * FROM processing_jobs WHERE (? IS NULL OR status = ?) ORDER BY id DESC LIMIT ?",
+    )
+    .bind(status)
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("failed to list jobs")
+}
+
+/// What a successful job used, stored on its row.
+#[derive(Debug, Clone, Default)]
+pub struct JobOutcome {
+    pub model: Option<String>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+}
+
+/// Consumer of the queue, run by the worker.
+pub struct JobRunner {
+    pub pool: SqlitePool,
+    /// Read for each job, so a reloaded configuration applies to the next one
+    pub config: SharedConfig,
+    pub summarization_llm: Option<Arc<dyn LlmProvider>>,
+    pub personalization_llm: Option<Arc<dyn LlmProvider>>,
+    pub embedding_llm: Option<Arc<dyn LlmProvider>>,
+    pub embedding_model: String,
+    pub status: Option<SharedWorkerStatus>,
+}
+
+impl JobRunner {
+    /// Requeue the stale jobs a previous run left running, then run jobs until
+    /// `shutdown` turns true. The flag is checked before every claim, so a busy
+    /// runner stops after the job in hand rather than draining the queue.
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) {
+        let cutoff = Utc::now() - chrono::Duration::minutes(STALE_AFTER_MINUTES);
+        match requeue_stale(&self.pool, cutoff).await {
+            Ok(0) => {}
+            Ok(n) => info!("jobs: requeued {} jobs interrupted by the last shutdown", n),
+            Err(e) => error!("jobs: {:#}", e),
+        }
+
+        while !*shutdown.borrow() {
+            match self.run_next().await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => error!("jobs: {:#}", e),
+            }
+            let cutoff = Utc::now() - chrono::Duration::minutes(STALE_AFTER_MINUTES);
+            if let Err(e) = requeue_stale(&self.pool, cutoff).await {
+                error!("jobs: {:#}", e);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(IDLE_POLL) => {}
+                // A dropped sender means nobody is left to wait for
+                res = shutdown.changed() => if res.is_err() { break },
+            }
+        }
+        info!("jobs: runner stopped");
+    }
+
+    /// Claim and run one job. Returns false when the queue is empty.
+    pub async fn run_next(&self) -> Result<bool> {
+        let Some(job) = claim(&self.pool, Utc::now()).await? else {
+            return Ok(false);
+        };
+        let article_id = job.entity_id.unwrap_or_default();
+        if let Some(status) = &self.status {
+            worker_status::update(status, |s| {
+                s.processing_article_ids.insert(article_id);
+            });
+        }
+
+        let started = Instant::now();
+        let span = tracing::info_span!("job", id = job.id, kind = %job.job_type, article_id);
+        let result = self.process(&job, article_id).instrument(span).await;
+        if let Some(status) = &self.status {
+            worker_status::update(status, |s| {
+                s.processing_article_ids.remove(&article_id);
+            });
+        }
+        match result {
+            Ok(outcome) => complete(&self.pool, job.id, &outcome, started.elapsed()).await?,
+            Err(e) => {
+                let status = fail(&self.pool, &job, &format!("{:#}", e)).await?;
+                warn!(
+                    "jobs: {} job {} for article {} failed (attempt {}/{}, now {}): {:#}",
+                    job.job_type, job.id, article_id, job.attempts, MAX_ATTEMPTS, status, e
+                );
+            }
+        }
+        Ok(true)
+    }
+
+    async fn process(&self, job: &Job, article_id: i64) -> Result<JobOutcome> {
+        let config = self.config.load_full();
+        match job.job_type.as_str() {
+            JOB_SUMMARY => {
+                let provider = self.summarization_llm.as_ref().context("no summarization LLM configured")?;
+                let model = LlmTask::Summarization.model(Some(&config));
+                let options = crate::processing::ProcessingOptions::from_config(Some(&config));
+                let Some(summary) =
+                    crate::processing::summarize_article(&self.pool, article_id, provider.as_ref(), &model, &options)
+                        .await?
+                else {
+                    return Ok(JobOutcome { model: Some(model), ..JobOutcome::default() });
+                };
+                if self.personalization_llm.is_some() {
+                    enqueue(&self.pool, JOB_PERSONALIZATION, &[article_id]).await?;
+                }
+                if self.embedding_llm.is_some() {
+                    enqueue(&self.pool, JOB_EMBEDDING, &[article_id]).await?;
+                }
+                Ok(JobOutcome {
+                    model: Some(model),
+                    prompt_tokens: Some(summary.usage.prompt_tokens as i64),
+                    completion_tokens: Some(summary.usage.completion_tokens as i64),
+                })
+            }
+            JOB_PERSONALIZATION => {
+                let provider = self.personalization_llm.clone().context("no personalization LLM configured")?;
+                let model = LlmTask::Personalization.model(Some(&config));
+                let summary = crate::processing::load_summary(&self.pool, article_id)
+                    .await?
+                    .with_context(|| format!("article {} has no summary", article_id))?;
+                let cache = crate::relevance_cache::RelevanceCache::for_article(
+                    &self.pool,
+                    Some(&config),
+                    article_id,
+                    &summary,
+                    self.embedding_llm.as_deref(),
+                )
+                .await;
+                let users = crate::personalize_worker::personalize_for_users(
+                    &self.pool,
+                    article_id,
+                    &summary,
+                    provider,
+                    &model,
+                    crate::personalize_worker::personalization_concurrency(Some(&config)),
+                    cache.as_ref(),
+                )
+                .await?;
+                info!("jobs: personalized article {} for {} users", article_id, users);
+                Ok(JobOutcome { model: Some(model), ..JobOutcome::default() })
+            }
+            JOB_EMBEDDING => {
+                let provider = self.embedding_llm.as_ref().context("no embedding LLM configured")?;
+                crate::processing::embed_article(
+                    &self.pool,
+                    provider.as_ref(),
+                    &self.embedding_model,
+                    article_id,
+                    crate::processing::embedding_dim(Some(&config)),
+                    &crate::processing::EmbeddingText::from_config(Some(&config)),
+                )
+                .await?;
+                Ok(JobOutcome { model: Some(self.embedding_model.clone()), ..JobOutcome::default() })
+            }
+            other => anyhow::bail!("unknown job type {:?}", other),
+        }
+    }
+}
@@ -1,13 +1,38 @@
 // Library interface for newscope modules
 // This allows tests and other binaries to import modules
 
+pub mod config_reload;
+pub mod cors;
+pub mod health;
+pub mod jobs;
 pub mod llm;
+pub mod http;
 pub mod ingestion;
+pub mod politeness;
+pub mod scheduler;
 pub mod storage;
+pub mod dedup;
+pub mod diagnostics;
+pub mod feed_health;
 pub mod scraping;
+pub mod opml;
 pub mod sessions;
 pub mod server;
 pub mod processing;
 pub mod press_review;
 pub mod personalization;
 pub mod personalize_worker;
+pub mod relevance_cache;
+pub mod reading_speed;
+pub mod timeline;
+pub mod search;
+pub mod views;
+pub mod rate_limit;
+pub mod request_id;
+pub mod maintenance;
+pub mod auth;
+pub mod i18n;
+pub mod notifications;
+pub mod worker_status;
+#[cfg(feature = "client")]
+pub mod client;
@@ -0,0 +1,271 @@
+use anyhow::Result;
+use rand::Rng;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use super::{LlmProvider, LlmRequest, LlmResponse, LlmStream, Summary};
+
+/// Smoothing factor for the per-endpoint success and latency averages.
+const EWMA_ALPHA: f64 = 0.2;
+/// Floor on the health factor so a degraded endpoint still gets probed now and then.
+const MIN_HEALTH: f64 = 0.05;
+
+/// Observed health of one endpoint.
+#[derive(Debug, Clone)]
+pub struct EndpointStats {
+    pub successes: u64,
+    pub failures: u64,
+    /// Exponentially weighted success rate in [0, 1]
+    pub success_rate: f64,
+    /// Exponentially weighted latency of successful calls
+    pub latency: Option<Duration>,
+}
+
+impl Default for EndpointStats {
+    fn default() -> Self {
+        Self {
+            successes: 0,
+            failures: 0,
+            success_rate: 1.0,
+            latency: None,
+        }
+    }
+}
+
+struct Endpoint {
+    name: String,
+    provider: Arc<dyn LlmProvider>,
+    weight: f64,
+    stats: Mutex<EndpointStats>,
+}
+
+/// Spreads calls over several equivalent providers by weight, shifting traffic
+/// away from endpoints that fail or respond slowly. A failed call is retried once
+/// on each remaining endpoint before the error is returned.
+pub struct LoadBalancedLlmProvider {
+    endpoints: Vec<Endpoint>,
+}
+
+impl LoadBalancedLlmProvider {
+    /// Build from `(name, provider, weight)` triples. Weights <= 0 are treated as 1.
+    pub fn new(providers: Vec<(String, Arc<dyn LlmProvider>, f64)>) -> Self {
+        Self {
+            endpoints: providers
+                .into_iter()
+                .map(|(name, provider, weight)| Endpoint {
+                    name,
+                    provider,
+                    weight: if weight > 0.0 { weight } else { 1.0 },
+                    stats: Mutex::new(EndpointStats::default()),
+                })
+                .collect(),
+        }
+    }
+
+    /// Snapshot of per-endpoint stats, by endpoint name.
+    pub fn stats(&self) -> Vec<(String, EndpointStats)> {
+        self.endpoints
+            .iter()
+            .map(|e| (e.name.clone(), lock(&e.stats).clone()))
+            .collect()
+    }
+
+    /// Effective weight: configured weight scaled by health and relative latency.
+    fn effective_weight(&self, idx: usize, fastest: Option<Duration>) -> f64 {
+        let e = &self.endpoints[idx];
+        let stats = lock(&e.stats);
+        let health = stats.success_rate.max(MIN_HEALTH);
+        let speed = match (fastest, stats.latency) {
+            (Some(f), Some(l)) if l > Duration::ZERO => (f.as_secs_f64() / l.as_secs_f64()).max(MIN_HEALTH),
+            _ => 1.0,
+        };
+        e.weight * health * speed
+    }
+
+    /// Endpoint indices in the order they should be tried: a weighted random
+    /// pick first, then the others by decreasing effective weight.
+    fn pick_order(&self) -> Vec<usize> {
+        let fastest = self
+            .endpoints
+            .iter()
+            .filter_map(|e| lock(&e.stats).latency)
+            .min();
+        let mut weighted: Vec<(usize, f64)> = (0..self.endpoints.len())
+            .map(|i| (i, self.effective_weight(i, fastest)))
+            .collect();
+
+        let total: f64 = weighted.iter().map(|(_, w)| w).sum();
+        let mut target = rand::thread_rng().gen_range(0.0..total.max(f64::EPSILON));
+        let mut first = weighted.len().saturating_sub(1);
+        for (pos, (_, w)) in weighted.iter().enumerate() {
+            if target < *w {
+                first = pos;
+                break;
+            }
+            target -= w;
+        }
+
+        let (head, _) = weighted.remove(first);
+        weighted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        std::iter::once(head).chain(weighted.into_iter().map(|(i, _)| i)).collect()
+    }
+
+    fn record(&self, idx: usize, ok: bool, elapsed: Duration) {
+        let mut stats = lock(&self.endpoints[idx].stats);
+        let sample = if ok { 1.0 } else { 0.0 };
+        stats.success_rate = (1.0 - EWMA_ALPHA) * stats.success_rate + EWMA_ALPHA * sample;
+        if ok {
+            stats.successes += 1;
+            stats.latency = Some(match stats.latency {
+                Some(prev) => prev.mul_f64(1.0 - EWMA_ALPHA) + elapsed.mul_f64(EWMA_ALPHA),
+                None => elapsed,
+            });
+        } else {
+            stats.failures += 1;
+        }
+    }
+
+    /// Run `call` against endpoints in pick order until one succeeds.
+    async fn dispatch<T, F, Fut>(&self, call: F) -> Result<T>
+    where
+        F: Fn(Arc<dyn LlmProvider>) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        if self.endpoints.is_empty() {
+            anyhow::bail!("load balancer has no LLM endpoints configured");
+        }
+
+        let mut last_err = None;
+        for idx in self.pick_order() {
+            let started = Instant::now();
+            match call(self.endpoints[idx].provider.clone()).await {
+                Ok(v) => {
+                    self.record(idx, true, started.elapsed());
+                    return Ok(v);
+                }
+                Err(e) => {
+                    self.record(idx, false, started.elapsed());
+                    warn!("LLM endpoint '{}' failed, trying next: {}", self.endpoints[idx].name, e);
+                    last_err = Some(e);
+                }
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("all LLM endpoints failed")))
+    }
+}
+
+fn lock<T>(m: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    match m.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for LoadBalancedLlmProvider {
+    async fn generate(&self, request: LlmRequest) -> Result<LlmResponse> {
+        self.dispatch(|p| {
+            let request = request.clone();
+            async move { p.generate(request).await }
+        })
+        .await
+    }
+
+    /// Failover only covers opening the stream, not errors once tokens flow.
+    async fn generate_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        self.dispatch(|p| {
+            let request = request.clone();
+            async move { p.generate_stream(request).await }
+        })
+        .await
+    }
+
+    async fn summarize(&self, content: &str, language: Option<&str>, max_tokens: usize) -> Result<Summary> {
+        self.dispatch(|p| async move { p.summarize(content, language, max_tokens).await }).await
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.dispatch(|p| async move { p.embed(text).await }).await
+    }
+
+    /// Usable as long as one endpoint answers.
+    async fn ping(&self) -> Result<()> {
+        let mut failures = Vec::new();
+        for endpoint in &self.endpoints {
+            match endpoint.provider.ping().await {
+                Ok(()) => return Ok(()),
+                Err(e) => failures.push(format!("{}: {:#}", endpoint.name, e)),
+            }
+        }
+        anyhow::bail!("no endpoint reachable ({})", failures.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::UsageMetadata;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Fake {
+        fail: bool,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for Fake {
+        async fn generate(&self, _request: LlmRequest) -> Result<LlmResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                anyhow::bail!("down");
+            }
+            Ok(LlmResponse {
+                content: "ok".into(),
+                usage: UsageMetadata::default(),
+                model: "fake".into(),
+            })
+        }
+
+        async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> Result<Summary> {
+            anyhow::bail!("unused")
+        }
+
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            anyhow::bail!("unused")
+        }
+    }
+
+    fn request() -> LlmRequest {
+        LlmRequest {
+            prompt: "hi".into(),
+            max_tokens: None,
+            temperature: None,
+            timeout_seconds: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_and_degraded_endpoint_gets_less_traffic() {
+        let bad = Arc::new(Fake { fail: true, calls: AtomicUsize::new(0) });
+        let good = Arc::new(Fake { fail: false, calls: AtomicUsize::new(0) });
+        let lb = LoadBalancedLlmProvider::new(vec![
+            ("bad".into(), bad.clone() as Arc<dyn LlmProvider>, 1.0),
+            ("good".into(), good.clone() as Arc<dyn LlmProvider>, 1.0),
+        ]);
+
+        // Every call succeeds thanks to failover
+        for _ in 0..200 {
+            assert!(lb.generate(request()).await.is_ok());
+        }
+        assert_eq!(good.calls.load(Ordering::SeqCst), 200);
+
+        // The failing endpoint was probed far less than an even split
+        let bad_calls = bad.calls.load(Ordering::SeqCst);
+        assert!(bad_calls < 50, "bad endpoint got {} calls", bad_calls);
+
+        let stats = lb.stats();
+        assert!(stats[0].1.success_rate < 0.5);
+        assert_eq!(stats[1].1.failures, 0);
+    }
+}
@@ -0,0 +1,329 @@
+//! Offline provider running a GGUF model through the llama.cpp command-line
+//! tools (`llama-cli` for completions, `llama-embedding` for vectors).
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+use super::{LlmProvider, LlmRequest, LlmResponse, Summary, UsageMetadata};
+
+/// Defaults for the optional `[llm.local]` settings.
+pub const DEFAULT_LLAMA_CLI: &str = "llama-cli";
+pub const DEFAULT_LLAMA_EMBEDDING: &str = "llama-embedding";
+pub const DEFAULT_CONTEXT_SIZE: u32 = 4096;
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Local LLM provider. One model invocation runs at a time across every
+/// instance (each task builds its own provider), so concurrent callers queue
+/// instead of competing for the device's cores and memory.
+pub struct LocalLlmProvider {
+    model_path: PathBuf,
+    threads: u32,
+    context_size: u32,
+    llama_cli: String,
+    llama_embedding: String,
+    default_timeout: Duration,
+    default_max_tokens: usize,
+    default_temperature: f32,
+    slot: Arc<Semaphore>,
+}
+
+/// The single slot shared by every [`LocalLlmProvider`] in the process.
+fn shared_slot() -> Arc<Semaphore> {
+    static SLOT: OnceLock<Arc<Semaphore>> = OnceLock::new();
+    SLOT.get_or_init(|| Arc::new(Semaphore::new(1))).clone()
+}
+
+impl LocalLlmProvider {
+    /// Fails when the model file does not exist; the llama.cpp binaries are
+    /// only looked up on first use.
+    pub fn new(model_path: impl Into<PathBuf>, threads: u32) -> Result<Self> {
+        let model_path = model_path.into();
+        if !model_path.is_file() {
+            anyhow::bail!("local model not found: {}", model_path.display());
+        }
+        Ok(Self {
+            model_path,
+            threads: threads.max(1),
+            context_size: DEFAULT_CONTEXT_SIZE,
+            llama_cli: DEFAULT_LLAMA_CLI.to_string(),
+            llama_embedding: DEFAULT_LLAMA_EMBEDDING.to_string(),
+            default_timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            default_max_tokens: 500,
+            default_temperature: 0.7,
+            slot: shared_slot(),
+        })
+    }
+
+    /// Build from `[llm.local]`.
+    pub fn from_config(config: &common::LocalLlmConfig) -> Result<Self> {
+        let model_path = config
+            .model_path
+            .as_deref()
+            .context("Missing model_path in [llm.local]")?;
+        let mut provider = Self::new(model_path, config.max_threads.unwrap_or(1))?;
+        if let Some(size) = config.context_size {
+            provider.context_size = size;
+        }
+        if let Some(cli) = &config.llama_cli {
+            provider.llama_cli = cli.clone();
+        }
+        if let Some(embedding) = &config.llama_embedding {
+            provider.llama_embedding = embedding.clone();
+        }
+        if let Some(secs) = config.timeout_seconds {
+            provider.default_timeout = Duration::from_secs(secs);
+        }
+        if let Some(max_tokens) = config.max_tokens {
+            provider.default_max_tokens = max_tokens;
+        }
+        Ok(provider)
+    }
+
+    pub fn model_path(&self) -> &Path {
+        &self.model_path
+    }
+
+    fn model_name(&self) -> String {
+        self.model_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "local".to_string())
+    }
+
+    /// Run `program` with `args` and return its standard output.
+    async fn run(&self, program: &str, args: Vec<String>, timeout: Duration) -> Result<String> {
+        let _slot = self.slot.acquire().await.context("local LLM provider closed")?;
+
+        let child = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to run {} (is llama.cpp installed?)", program))?;
+
+        let output = tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .with_context(|| format!("{} timed out after {:?}", program, timeout))?
+            .with_context(|| format!("failed to wait for {}", program))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+            anyhow::bail!(
+                "{} exited with {}: {}",
+                program,
+                output.status,
+                tail.into_iter().rev().collect::<Vec<_>>().join(" | ")
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn common_args(&self) -> Vec<String> {
+        vec![
+            "--model".to_string(),
+            self.model_path.to_string_lossy().into_owned(),
+            "--threads".to_string(),
+            self.threads.to_string(),
+            "--ctx-size".to_string(),
+            self.context_size.to_string(),
+        ]
+    }
+}
+
+/// Rough token count (~4 characters per token) for usage accounting, as the
+/// command-line tools do not report it on stdout.
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for LocalLlmProvider {
+    async fn generate(&self, request: LlmRequest) -> Result<LlmResponse> {
+        let timeout = request
+            .timeout_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(self.default_timeout);
+        let max_tokens = request.max_tokens.unwrap_or(self.default_max_tokens);
+        let temperature = request.temperature.unwrap_or(self.default_temperature);
+
+        // Prompts can exceed the size of a single command-line argument
+        let prompt_file = std::env::temp_dir().join(format!("newscope-prompt-{}.txt", uuid::Uuid::new_v4()));
+        tokio::fs::write(&prompt_file, &request.prompt)
+            .await
+            .context("failed to write prompt file")?;
+
+        let mut args = self.common_args();
+        args.extend([
+            "--file".to_string(),
+            prompt_file.to_string_lossy().into_owned(),
+            "--n-predict".to_string(),
+            max_tokens.to_string(),
+            "--temp".to_string(),
+            temperature.to_string(),
+            "--no-display-prompt".to_string(),
+            "--no-conversation".to_string(),
+        ]);
+        let result = self.run(&self.llama_cli, args, timeout).await;
+        tokio::fs::remove_file(&prompt_file).await.ok();
+
+        let content = result?.trim().to_string();
+        let prompt_tokens = estimate_tokens(&request.prompt);
+        let completion_tokens = estimate_tokens(&content);
+        Ok(LlmResponse {
+            content,
+            usage: UsageMetadata {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+            },
+            model: self.model_name(),
+        })
+    }
+
+    async fn summarize(&self, content: &str, language: Option<&str>, max_tokens: usize) -> Result<Summary> {
+        let request = LlmRequest {
+            prompt: super::summary_prompt(content, language),
+            max_tokens: Some(max_tokens),
+            temperature: Some(0.5),
+            timeout_seconds: None,
+        };
+
+        let response = self.generate(request).await?;
+        super::parse_summary(&response.content, response.usage)
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut args = self.common_args();
+        args.extend([
+            "--prompt".to_string(),
+            text.to_string(),
+            "--embd-output-format".to_string(),
+            "json".to_string(),
+        ]);
+        let stdout = self.run(&self.llama_embedding, args, self.default_timeout).await?;
+        parse_embedding_output(&stdout)
+    }
+
+    async fn ping(&self) -> Result<()> {
+        if !self.model_path.is_file() {
+            anyhow::bail!("local model not found: {}", self.model_path.display());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingOutput {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+}
+
+/// Extract the vector from `llama-embedding --embd-output-format json`.
+fn parse_embedding_output(stdout: &str) -> Result<Vec<f32>> {
+    let json = super::extract_json_from_text(stdout).context("no JSON in llama-embedding output")?;
+    let output: EmbeddingOutput =
+        serde_json::from_str(&json).context("failed to parse llama-embedding output")?;
+    let embedding = output
+        .data
+        .into_iter()
+        .next()
+        .map(|d| d.embedding)
+        .context("llama-embedding returned no embedding")?;
+    if embedding.is_empty() {
+        anyhow::bail!("llama-embedding returned an empty vector");
+    }
+    Ok(embedding)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// A stand-in for a llama.cpp binary: records its arguments, prints `output`.
+    fn fake_tool(dir: &Path, name: &str, output: &str) -> String {
+        let path = dir.join(name);
+        let script = format!(
+            "#!/bin/sh\nprintf '%s\\n' \"$@\" > \"{}.args\"\ncat <<'EOF'\n{}\nEOF\n",
+            path.display(),
+            output
+        );
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[tokio::test]
+    async fn runs_llama_cpp_tools() {
+        let dir = std::env::temp_dir().join(format!("newscope-local-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let model = dir.join("model.gguf");
+        std::fs::write(&model, b"GGUF").unwrap();
+
+        let config = common::LocalLlmConfig {
+            model_path: Some(model.to_string_lossy().into_owned()),
+            max_threads: Some(2),
+            context_size: None,
+            llama_cli: Some(fake_tool(
+                &dir,
+                "llama-cli",
+                r#"Sure! {"headline": "Local", "bullets": ["a", "b"], "details": null}"#,
+            )),
+            llama_embedding: Some(fake_tool(
+                &dir,
+                "llama-embedding",
+                r#"{"object": "list", "data": [{"object": "embedding", "index": 0, "embedding": [0.25, -0.5]}]}"#,
+            )),
+            timeout_seconds: Some(10),
+            max_tokens: None,
+        };
+        let provider = LocalLlmProvider::from_config(&config).unwrap();
+
+        let summary = provider.summarize("Some article", None, 200).await.unwrap();
+        assert_eq!(summary.headline, "Local");
+        assert_eq!(summary.bullets, vec!["a", "b"]);
+        assert!(summary.usage.prompt_tokens > 0);
+        let args = std::fs::read_to_string(dir.join("llama-cli.args")).unwrap();
+        let args: Vec<&str> = args.lines().collect();
+        assert!(args.windows(2).any(|w| w == ["--threads", "2"]));
+        assert!(args.windows(2).any(|w| w == ["--n-predict", "200"]));
+        assert!(args.windows(2).any(|w| w[0] == "--model" && w[1].ends_with("model.gguf")));
+
+        assert_eq!(provider.embed("text").await.unwrap(), vec![0.25, -0.5]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn providers_share_one_slot() {
+        let dir = std::env::temp_dir().join(format!("newscope-local-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let model = dir.join("model.gguf");
+        std::fs::write(&model, b"GGUF").unwrap();
+
+        let summarization = LocalLlmProvider::new(&model, 1).unwrap();
+        let embedding = LocalLlmProvider::new(&model, 1).unwrap();
+        assert!(Arc::ptr_eq(&summarization.slot, &embedding.slot));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn rejects_missing_model() {
+        let err = LocalLlmProvider::new("/nonexistent/model.gguf", 1).err().unwrap();
+        assert!(err.to_string().contains("/nonexistent/model.gguf"));
+    }
+}
@@ -1,18 +1,40 @@
 use anyhow::Result;
+use rocket::futures::Stream;
 use serde::{Deserialize, Serialize};
+use std::pin::Pin;
 
 /// Core trait for LLM providers (local or remote)
 #[async_trait::async_trait]
 pub trait LlmProvider: Send + Sync {
     /// Generate completion for a given prompt
     async fn generate(&self, request: LlmRequest) -> Result<LlmResponse>;
+
+    /// Stream the completion as it is generated, ending with [`LlmChunk::Done`].
+    /// Providers without native streaming yield the whole `generate` response
+    /// as a single token.
+    async fn generate_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        let response = self.generate(request).await?;
+        Ok(Box::pin(rocket::futures::stream::iter([
+            Ok(LlmChunk::Token(response.content)),
+            Ok(LlmChunk::Done {
+                model: response.model,
+                usage: response.usage,
+            }),
+        ])))
+    }
     
-    /// Generate hierarchical summary for article content
-    /// Generate hierarchical summary for article content
-    async fn summarize(&self, content: &str, max_tokens: usize) -> Result<Summary>;
+    /// Generate hierarchical summary for article content, written in
+    /// `language` (ISO 639-1) when it is known
+    async fn summarize(&self, content: &str, language: Option<&str>, max_tokens: usize) -> Result<Summary>;
 
     /// Generate vector embedding for text
     async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Cheap check that the provider is usable, without generating anything
+    /// (used by the health check).
+    async fn ping(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Request structure for LLM generation
@@ -32,6 +54,18 @@ pub struct LlmResponse {
     pub model: String,
 }
 
+/// Piece of a streamed completion.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LlmChunk {
+    /// Next piece of generated text
+    Token(String),
+    /// Sent once, after the last token
+    Done { model: String, usage: UsageMetadata },
+}
+
+/// Stream returned by [`LlmProvider::generate_stream`].
+pub type LlmStream = Pin<Box<dyn Stream<Item = Result<LlmChunk>> + Send>>;
+
 /// Hierarchical summary structure (FR-LLM-02)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Summary {
@@ -47,15 +81,116 @@ pub struct Summary {
 }
 
 /// Token usage metadata (FR-LLM-06)
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UsageMetadata {
     pub prompt_tokens: usize,
     pub completion_tokens: usize,
     pub total_tokens: usize,
 }
 
+/// Job an LLM provider is built for. Each task may point at its own endpoint
+/// and model, e.g. a cheap model for embeddings and a strong one for
+/// personalization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmTask {
+    Summarization,
+    Personalization,
+    Embedding,
+    Interaction,
+}
+
+impl LlmTask {
+    /// Endpoint for this task: its own `[llm.<task>]` section, then the legacy
+    /// `[llm.background]` / `[llm.interactive]` one, then `[llm.remote]`.
+    pub fn endpoint_config(self, llm: &common::LlmConfig) -> Option<&common::RemoteLlmConfig> {
+        let specific = match self {
+            Self::Summarization => llm.summarization.as_ref(),
+            Self::Personalization => llm.personalization.as_ref(),
+            Self::Embedding => llm.embedding.as_ref(),
+            Self::Interaction => llm.interaction.as_ref(),
+        };
+        let legacy = match self {
+            Self::Summarization | Self::Personalization => llm.background.as_ref(),
+            Self::Interaction => llm.interactive.as_ref(),
+            Self::Embedding => None,
+        };
+        specific.or(legacy).or(llm.remote.as_ref())
+    }
+
+    /// Model name this task's calls are recorded under ("unknown" if unset).
+    pub fn model(self, config: Option<&common::Config>) -> String {
+        config
+            .and_then(|c| c.llm.as_ref())
+            .and_then(|l| self.endpoint_config(l))
+            .and_then(|r| r.model.clone())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+pub mod balanced;
+pub mod local;
 pub mod remote;
 pub mod summarizer;
+pub mod usage;
+
+/// Prompt asking for a hierarchical summary of `content` as strict JSON.
+/// A known `language` is named so the model does not drift into English.
+pub(crate) fn summary_prompt(content: &str, language: Option<&str>) -> String {
+    let language_hint = language
+        .and_then(crate::i18n::language_name)
+        .map(|name| format!("5. The article is written in {name}: write the summary in {name}\n"))
+        .unwrap_or_default();
+    format!(
+        r#"You are a news article summarizer. Create a concise, informative summary.
+
+IMPORTANT INSTRUCTIONS:
+1. IGNORE all markdown formatting (###, **, __, etc.) - extract only text content
+2. Create a REAL summary of the key points (not just the first few lines)
+3. Be concise but capture the essential information from the ENTIRE article
+4. KEEP THE ORIGINAL LANGUAGE - do not translate (translation happens later)
+{}
+OUTPUT FORMAT (strict JSON):
+{{
+  "headline": "one-line summary in original language (max 100 chars)",
+  "bullets": ["key point 1", "key point 2", "key point 3"],
+  "details": "optional additional context"
+}}
+
+Use 3-7 bullet points that capture the most important information.
+
+ARTICLE TO SUMMARIZE:
+{}
+"#,
+        language_hint, content
+    )
+}
+
+// Internal structure for parsing summary JSON
+#[derive(Debug, Deserialize)]
+struct SummaryJson {
+    headline: String,
+    bullets: Vec<String>,
+    details: Option<String>,
+}
+
+/// Parse the model's answer to [`summary_prompt`].
+pub(crate) fn parse_summary(content: &str, usage: UsageMetadata) -> Result<Summary> {
+    use anyhow::Context;
+
+    // Robust JSON extraction: handle markdown backticks, preamble, etc.
+    let cleaned_json = extract_json_from_text(content)
+        .context("No valid JSON found in LLM summary response")?;
+
+    let summary_data: SummaryJson = serde_json::from_str(&cleaned_json)
+        .context(format!("Failed to parse LLM summary as JSON. Input was: {}", cleaned_json))?;
+
+    Ok(Summary {
+        headline: summary_data.headline,
+        bullets: summary_data.bullets,
+        details: summary_data.details,
+        usage,
+    })
+}
 
 /// Helper to extract JSON from text that might contain markdown backticks or preamble
 pub fn extract_json_from_text(text: &str) -> Option<String> {
@@ -82,3 +217,40 @@ pub fn extract_json_from_text(text: &str) -> Option<String> {
     
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_endpoints_fall_back_to_remote() {
+        let config: common::Config = toml::from_str(
+            r#"
+            [database]
+            path = "x.db"
+            [scheduler]
+            times = []
+            [llm]
+            adapter = "remote"
+            [llm.remote]
+            model = "default"
+            [llm.embedding]
+            model = "cheap"
+            [llm.background]
+            model = "legacy"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(LlmTask::Embedding.model(Some(&config)), "cheap");
+        assert_eq!(LlmTask::Summarization.model(Some(&config)), "legacy");
+        assert_eq!(LlmTask::Interaction.model(Some(&config)), "default");
+        assert_eq!(LlmTask::Personalization.model(None), "unknown");
+    }
+
+    #[test]
+    fn summary_prompt_names_known_language() {
+        assert!(summary_prompt("Texte", Some("fr")).contains("written in French"));
+        assert!(!summary_prompt("Text", None).contains("written in"));
+        assert!(!summary_prompt("Text", Some("zz")).contains("written in"));
+    }
+}
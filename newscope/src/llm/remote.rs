@@ -1,8 +1,20 @@
 use anyhow::{Context, Result};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
 use std::time::Duration;
 
-use super::{LlmProvider, LlmRequest, LlmResponse, Summary, UsageMetadata};
+use super::{LlmChunk, LlmProvider, LlmRequest, LlmResponse, LlmStream, Summary, UsageMetadata};
+
+/// Default for `max_retries` in a remote LLM config.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+/// Default for `retry_base_delay_ms` in a remote LLM config.
+pub const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 1000;
+/// Longest `Retry-After` honored; longer waits are cut to this.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+/// Time allowed for a health check to reach the endpoint.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Remote LLM provider using OpenAI-compatible HTTP API
 pub struct RemoteLlmProvider {
@@ -12,9 +24,27 @@ pub struct RemoteLlmProvider {
     default_timeout: Duration,
     default_max_tokens: usize,
     default_temperature: f32,
+    max_retries: u32,
+    retry_base_delay: Duration,
     client: reqwest::Client,
 }
 
+/// A failed attempt; `Retry` ones are worth another try.
+enum AttemptError {
+    Retry {
+        error: anyhow::Error,
+        /// Wait asked for by the server (`Retry-After` on a 429)
+        retry_after: Option<Duration>,
+    },
+    Fatal(anyhow::Error),
+}
+
+impl From<anyhow::Error> for AttemptError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::Fatal(error)
+    }
+}
+
 impl RemoteLlmProvider {
     pub fn new(
         base_url: impl Into<String>,
@@ -28,7 +58,9 @@ impl RemoteLlmProvider {
             default_timeout: Duration::from_secs(30),
             default_max_tokens: 500,
             default_temperature: 0.7,
-            client: reqwest::Client::new(),
+            max_retries: 0,
+            retry_base_delay: Duration::from_millis(DEFAULT_RETRY_BASE_DELAY_MS),
+            client: crate::http::client().clone(),
         }
     }
 
@@ -43,6 +75,72 @@ impl RemoteLlmProvider {
         self.default_temperature = temperature;
         self
     }
+
+    /// Retry `generate`, `summarize` and `embed` up to `max_retries` times on
+    /// connection errors, 5xx and 429 responses, waiting `base_delay`, then
+    /// twice as long each time (or the 429's `Retry-After`). Other 4xx and
+    /// timeouts fail at once. Streaming is never retried.
+    pub fn with_retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_base_delay = base_delay;
+        self
+    }
+
+    /// Run `attempt` until it succeeds, fails for good or retries run out.
+    async fn retrying<T, F, Fut>(&self, what: &str, mut attempt: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = std::result::Result<T, AttemptError>>,
+    {
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(AttemptError::Fatal(error)) => return Err(error),
+                Err(AttemptError::Retry { error, .. }) if retries >= self.max_retries => return Err(error),
+                Err(AttemptError::Retry { error, retry_after }) => {
+                    let delay = retry_after
+                        .unwrap_or_else(|| self.retry_base_delay.saturating_mul(1 << retries.min(16)));
+                    retries += 1;
+                    tracing::warn!(
+                        "{} failed ({:#}), retry {}/{} in {:?}",
+                        what, error, retries, self.max_retries, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+/// Error for a non-success response; 429 and 5xx are retried, other statuses are not.
+async fn status_error(response: reqwest::Response, describe: impl FnOnce(StatusCode, String) -> anyhow::Error) -> AttemptError {
+    let status = response.status();
+    let retry_after = (status == StatusCode::TOO_MANY_REQUESTS)
+        .then(|| retry_after(response.headers()))
+        .flatten();
+    let body = response.text().await.unwrap_or_default();
+    let error = describe(status, body);
+    if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        AttemptError::Retry { error, retry_after }
+    } else {
+        AttemptError::Fatal(error)
+    }
+}
+
+/// Parse `Retry-After`, given either as seconds or as an HTTP date.
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    let wait = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+            (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+                .to_std()
+                .unwrap_or(Duration::ZERO)
+        }
+    };
+    Some(wait.min(MAX_RETRY_AFTER))
 }
 
 #[async_trait::async_trait]
@@ -65,32 +163,43 @@ impl LlmProvider for RemoteLlmProvider {
             }],
             max_tokens: Some(max_tokens),
             temperature: Some(temperature),
+            stream: None,
+            stream_options: None,
         };
 
-        // Make HTTP request with timeout
-        let response = tokio::time::timeout(
-            timeout,
-            self.client
-                .post(&self.base_url)
-                .header("Authorization", format!("Bearer {}", self.api_key))
-                .header("Content-Type", "application/json")
-                .json(&req_body)
-                .send(),
-        )
-        .await
-        .context("LLM request timed out")?
-        .context("LLM HTTP request failed")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("LLM API error {}: {}", status, body);
-        }
-
-        let resp_body: OpenAiResponse = response
-            .json()
-            .await
-            .context("Failed to parse LLM response")?;
+        // Make HTTP request with timeout (covers both the request and reading the body)
+        let resp_body: OpenAiResponse = self
+            .retrying("LLM request", || async {
+                tokio::time::timeout(timeout, async {
+                    let response = self
+                        .client
+                        .post(&self.base_url)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                        .header("Content-Type", "application/json")
+                        .json(&req_body)
+                        .send()
+                        .await
+                        .map_err(|e| AttemptError::Retry {
+                            error: anyhow::Error::new(e).context("LLM HTTP request failed"),
+                            retry_after: None,
+                        })?;
+
+                    if !response.status().is_success() {
+                        return Err(status_error(response, |status, body| {
+                            anyhow::anyhow!("LLM API error {}: {}", status, body)
+                        })
+                        .await);
+                    }
+
+                    Ok(response
+                        .json::<OpenAiResponse>()
+                        .await
+                        .context("Failed to parse LLM response")?)
+                })
+                .await
+                .context("LLM request timed out")?
+            })
+            .await?;
 
         let choice = resp_body
             .choices
@@ -110,53 +219,93 @@ impl LlmProvider for RemoteLlmProvider {
         })
     }
 
-    async fn summarize(&self, content: &str, max_tokens: usize) -> Result<Summary> {
-        let prompt = format!(
-            r#"You are a news article summarizer. Create a concise, informative summary.
+    /// OpenAI `stream: true` completion, read as server-sent events. The timeout
+    /// applies to the response headers and then to each gap between chunks.
+    async fn generate_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        let timeout = request
+            .timeout_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(self.default_timeout);
 
-IMPORTANT INSTRUCTIONS:
-1. IGNORE all markdown formatting (###, **, __, etc.) - extract only text content
-2. Create a REAL summary of the key points (not just the first few lines)
-3. Be concise but capture the essential information from the ENTIRE article
-4. KEEP THE ORIGINAL LANGUAGE - do not translate (translation happens later)
+        let req_body = OpenAiRequest {
+            model: self.model.clone(),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: request.prompt,
+            }],
+            max_tokens: Some(request.max_tokens.unwrap_or(self.default_max_tokens)),
+            temperature: Some(request.temperature.unwrap_or(self.default_temperature)),
+            stream: Some(true),
+            stream_options: Some(StreamOptions { include_usage: true }),
+        };
 
-OUTPUT FORMAT (strict JSON):
-{{
-  "headline": "one-line summary in original language (max 100 chars)",
-  "bullets": ["key point 1", "key point 2", "key point 3"],
-  "details": "optional additional context"
-}}
+        let response = tokio::time::timeout(
+            timeout,
+            self.client
+                .post(&self.base_url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .header("Content-Type", "application/json")
+                .header("Accept", "text/event-stream")
+                .json(&req_body)
+                .send(),
+        )
+        .await
+        .context("LLM request timed out")?
+        .context("LLM HTTP request failed")?;
 
-Use 3-7 bullet points that capture the most important information.
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("LLM API error {}: {}", status, body);
+        }
 
-ARTICLE TO SUMMARIZE:
-{}
-"#,
-            content
-        );
+        let state = SseState {
+            response,
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+            model: self.model.clone(),
+            usage: UsageMetadata::default(),
+            finished: false,
+            idle_timeout: timeout,
+        };
+        Ok(Box::pin(rocket::futures::stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(chunk) = state.pending.pop_front() {
+                    return Some((Ok(chunk), state));
+                }
+                if state.finished {
+                    return None;
+                }
+                match tokio::time::timeout(state.idle_timeout, state.response.chunk()).await {
+                    Err(_) => {
+                        state.finished = true;
+                        return Some((Err(anyhow::anyhow!("LLM stream stalled for {:?}", state.idle_timeout)), state));
+                    }
+                    Ok(Err(e)) => {
+                        state.finished = true;
+                        return Some((Err(anyhow::Error::new(e).context("LLM stream failed")), state));
+                    }
+                    // Closed without [DONE]: keep what was received
+                    Ok(Ok(None)) => state.finish(),
+                    Ok(Ok(Some(bytes))) => {
+                        state.buffer.extend_from_slice(&bytes);
+                        state.drain_lines();
+                    }
+                }
+            }
+        })))
+    }
 
+    async fn summarize(&self, content: &str, language: Option<&str>, max_tokens: usize) -> Result<Summary> {
         let request = LlmRequest {
-            prompt,
+            prompt: super::summary_prompt(content, language),
             max_tokens: Some(max_tokens),
             temperature: Some(0.5), // Lower temperature for more consistent summarization
             timeout_seconds: None,
         };
 
         let response = self.generate(request).await?;
-
-        // Robust JSON extraction: handle markdown backticks, preamble, etc.
-        let cleaned_json = super::extract_json_from_text(&response.content)
-            .context("No valid JSON found in LLM summary response")?;
-
-        let summary_data: SummaryJson = serde_json::from_str(&cleaned_json)
-            .context(format!("Failed to parse LLM summary as JSON. Input was: {}", cleaned_json))?;
-
-        Ok(Summary {
-            headline: summary_data.headline,
-            bullets: summary_data.bullets,
-            details: summary_data.details,
-            usage: response.usage,
-        })
+        super::parse_summary(&response.content, response.usage)
     }
 
     async fn embed(&self, text: &str) -> Result<Vec<f32>> {
@@ -184,24 +333,33 @@ ARTICLE TO SUMMARIZE:
             input: text.to_string(),
         };
 
-        let response = tokio::time::timeout(
-            self.default_timeout,
-            self.client
-                .post(&embedding_url)
-                .header("Authorization", format!("Bearer {}", self.api_key))
-                .header("Content-Type", "application/json")
-                .json(&req_body)
-                .send(),
-        )
-        .await
-        .context("Embedding request timed out")?
-        .context("Embedding HTTP request failed")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Embedding API error {}: {} (URL: {})", status, body, embedding_url);
-        }
+        let response = self
+            .retrying("Embedding request", || async {
+                let response = tokio::time::timeout(
+                    self.default_timeout,
+                    self.client
+                        .post(&embedding_url)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                        .header("Content-Type", "application/json")
+                        .json(&req_body)
+                        .send(),
+                )
+                .await
+                .context("Embedding request timed out")?
+                .map_err(|e| AttemptError::Retry {
+                    error: anyhow::Error::new(e).context("Embedding HTTP request failed"),
+                    retry_after: None,
+                })?;
+
+                if !response.status().is_success() {
+                    return Err(status_error(response, |status, body| {
+                        anyhow::anyhow!("Embedding API error {}: {} (URL: {})", status, body, embedding_url)
+                    })
+                    .await);
+                }
+                Ok(response)
+            })
+            .await?;
 
         let body_text = response.text().await.context("Failed to read embedding response body")?;
         
@@ -229,6 +387,16 @@ ARTICLE TO SUMMARIZE:
 
         anyhow::bail!("Embedding response has no data: {}", body_text);
     }
+
+    /// Any HTTP response counts: a 404 or 401 on the base URL still proves the
+    /// server is up, and nothing is billed.
+    async fn ping(&self) -> Result<()> {
+        tokio::time::timeout(PING_TIMEOUT, self.client.get(&self.base_url).send())
+            .await
+            .with_context(|| format!("no answer from {} within {:?}", self.base_url, PING_TIMEOUT))?
+            .with_context(|| format!("cannot reach {}", self.base_url))?;
+        Ok(())
+    }
 }
 
 // OpenAI API request/response structures
@@ -240,6 +408,114 @@ struct OpenAiRequest {
     max_tokens: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+}
+
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    /// Ask for a final chunk carrying token usage
+    include_usage: bool,
+}
+
+/// Progress through a server-sent event stream.
+struct SseState {
+    response: reqwest::Response,
+    /// Bytes of the current, incomplete line
+    buffer: Vec<u8>,
+    pending: VecDeque<LlmChunk>,
+    model: String,
+    usage: UsageMetadata,
+    finished: bool,
+    idle_timeout: Duration,
+}
+
+impl SseState {
+    /// Queue the tokens of every complete line received so far.
+    fn drain_lines(&mut self) {
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            match parse_sse_line(&String::from_utf8_lossy(&line)) {
+                Some(SseEvent::Done) => {
+                    self.finish();
+                    return;
+                }
+                Some(SseEvent::Chunk(chunk)) => {
+                    if let Some(model) = chunk.model {
+                        self.model = model;
+                    }
+                    if let Some(usage) = chunk.usage {
+                        let prompt_tokens = usage.prompt_tokens.unwrap_or(0);
+                        let completion_tokens = usage.completion_tokens.unwrap_or(0);
+                        self.usage = UsageMetadata {
+                            prompt_tokens,
+                            completion_tokens,
+                            total_tokens: usage.total_tokens.unwrap_or(prompt_tokens + completion_tokens),
+                        };
+                    }
+                    let text: String = chunk
+                        .choices
+                        .into_iter()
+                        .filter_map(|c| c.delta.content)
+                        .collect();
+                    if !text.is_empty() {
+                        self.pending.push_back(LlmChunk::Token(text));
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            self.pending.push_back(LlmChunk::Done {
+                model: self.model.clone(),
+                usage: self.usage.clone(),
+            });
+        }
+    }
+}
+
+#[derive(Debug)]
+enum SseEvent {
+    Chunk(StreamChunk),
+    Done,
+}
+
+/// Parse one line of an OpenAI event stream. Comments, blank lines and
+/// unparseable payloads yield None.
+fn parse_sse_line(line: &str) -> Option<SseEvent> {
+    let data = line.trim_end_matches(['\r', '\n']).strip_prefix("data:")?.trim();
+    if data == "[DONE]" {
+        return Some(SseEvent::Done);
+    }
+    serde_json::from_str(data).ok().map(SseEvent::Chunk)
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    #[serde(default)]
+    delta: StreamDelta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StreamDelta {
+    #[serde(default)]
+    content: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -270,14 +546,6 @@ struct Usage {
     total_tokens: Option<usize>,
 }
 
-// Internal structure for parsing summary JSON
-#[derive(Debug, Deserialize)]
-struct SummaryJson {
-    headline: String,
-    bullets: Vec<String>,
-    details: Option<String>,
-}
-
 #[derive(Debug, Serialize)]
 struct EmbeddingRequest {
     model: String,
@@ -285,6 +553,7 @@ struct EmbeddingRequest {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct EmbeddingResponse {
     data: Vec<EmbeddingData>,
     model: Option<String>,
@@ -292,6 +561,7 @@ struct EmbeddingResponse {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct EmbeddingData {
     #[serde(default)]
     object: Option<String>, // "embedding"
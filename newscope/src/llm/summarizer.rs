@@ -7,9 +7,16 @@ use super::{LlmProvider, Summary, UsageMetadata};
 pub async fn summarize_article<P: LlmProvider + ?Sized>(
     provider: &P,
     article_text: &str,
+    language: Option<&str>,
     max_tokens: usize,
 ) -> Summary {
-    match provider.summarize(article_text, max_tokens).await {
+    with_fallback(provider.summarize(article_text, language, max_tokens).await, article_text)
+}
+
+/// The LLM summary if summarization succeeded, else an extractive summary of
+/// `article_text` (FR-LLM-04).
+pub fn with_fallback(result: anyhow::Result<Summary>, article_text: &str) -> Summary {
+    match result {
         Ok(summary) => {
             info!(
                 "LLM summarization successful: {} bullets, {} tokens",
@@ -27,11 +34,7 @@ pub async fn summarize_article<P: LlmProvider + ?Sized>(
 
 /// Fallback extractive summary when LLM fails
 fn extractive_summary(text: &str) -> Summary {
-    let sentences: Vec<&str> = text
-        .split(['.', '!', '?'])
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
+    let sentences = split_sentences(text);
 
     let headline = sentences
         .first()
@@ -53,11 +56,93 @@ fn extractive_summary(text: &str) -> Summary {
     }
 }
 
+/// Sentence terminators, including full-width CJK punctuation.
+const TERMINATORS: &[char] = &['.', '!', '?', '…', '。', '！', '？'];
+/// Closing marks that may follow a terminator and belong to the same sentence.
+const CLOSING_MARKS: &[char] = &['"', '\'', '”', '’', '»', ')', '」', '』'];
+/// Lowercase abbreviations (without their final dot) that do not end a sentence.
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "st", "jr", "sr", "vs", "etc", "e.g", "i.e", "cf", "fig", "no",
+    "vol", "approx", "inc", "ltd", "co", "corp", "dept", "gen", "gov", "sen", "rep", "jan", "feb",
+    "mar", "apr", "aug", "sept", "oct", "nov", "dec", "mm", "mme", "mmes", "mlle", "av", "bd",
+    "env", "hr", "nr", "bzw", "usw", "z.b", "sra", "dra",
+];
+/// Split `text` into sentences with their terminal punctuation removed.
+///
+/// Latin terminators only end a sentence when followed by whitespace (so
+/// "3.5" or "example.com" stay whole) and a dot after a known abbreviation or
+/// an initial ("M. Dupont", "J. Smith") does not count. Closing quotes after a
+/// terminator stay with its sentence, including French-spaced ones (". »").
+/// CJK terminators end a sentence immediately, as those scripts do not
+/// separate sentences by spaces, unless a closing quote shows they are quoted.
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in text.char_indices() {
+        if i < start || !TERMINATORS.contains(&c) {
+            continue;
+        }
+        let after = &text[i + c.len_utf8()..];
+        let rest = skip_closing_marks(after);
+        let followed_by_space = rest.chars().next().is_none_or(char::is_whitespace);
+        let boundary = match c {
+            '。' | '！' | '？' => !after.starts_with(CLOSING_MARKS),
+            '.' => followed_by_space && !ends_with_abbreviation(&text[start..i]),
+            _ => followed_by_space,
+        };
+        if !boundary {
+            continue;
+        }
+        let end = text.len() - rest.len();
+        push_sentence(&mut sentences, &text[start..end]);
+        start = end;
+    }
+    push_sentence(&mut sentences, &text[start..]);
+    sentences
+}
+
+/// Skip closing marks (optionally preceded by spaces) following a terminator.
+fn skip_closing_marks(mut rest: &str) -> &str {
+    loop {
+        let trimmed = rest.trim_start();
+        match trimmed.strip_prefix(CLOSING_MARKS) {
+            Some(next) => rest = next,
+            None => return rest,
+        }
+    }
+}
+
+fn push_sentence(sentences: &mut Vec<String>, raw: &str) {
+    let sentence = raw.trim().trim_end_matches(TERMINATORS).trim_end();
+    if !sentence.is_empty() {
+        sentences.push(sentence.to_string());
+    }
+}
+
+/// Whether the text before a dot ends with an abbreviation or a single-letter initial.
+fn ends_with_abbreviation(before: &str) -> bool {
+    let word = before
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or("")
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => c.is_alphabetic(),
+        (Some(_), Some(_)) => ABBREVIATIONS.contains(&word.as_str()),
+        _ => false,
+    }
+}
+
+/// Truncate to at most `max_len` characters (not bytes), ending with "...".
 fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len - 3])
+        let kept: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", kept)
     }
 }
 
@@ -89,4 +174,49 @@ mod tests {
         assert!(summary.headline.len() <= 103); // 100 + "..."
         assert!(summary.headline.ends_with("..."));
     }
+
+    #[test]
+    fn test_truncate_is_char_boundary_safe() {
+        let accented = "é".repeat(150);
+        let truncated = truncate(&accented, 100);
+        assert_eq!(truncated.chars().count(), 100);
+        assert!(truncated.ends_with("..."));
+
+        assert_eq!(truncate("日本語のテキスト", 5), "日本...");
+        assert_eq!(truncate("court", 100), "court");
+    }
+
+    #[test]
+    fn test_extractive_summary_french() {
+        let text = "M. Dupont a présenté le budget à l'Assemblée. La croissance prévue est de 1.5 % \
+                    selon l'Insee, etc. mais les économistes restent prudents ! Qu'en pensent les \
+                    élus ? « C'est irréaliste. » Le débat reprendra mardi.";
+
+        let summary = extractive_summary(text);
+
+        assert_eq!(summary.headline, "M. Dupont a présenté le budget à l'Assemblée");
+        assert_eq!(
+            summary.bullets,
+            vec![
+                "La croissance prévue est de 1.5 % selon l'Insee, etc. mais les économistes restent prudents",
+                "Qu'en pensent les élus",
+                "« C'est irréaliste. »",
+                "Le débat reprendra mardi",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extractive_summary_japanese() {
+        let text = "東京で新しい技術展示会が開幕した。来場者は初日だけで一万人を超えた！\
+                    主催者は「大成功だ。」と語った。本当に？！";
+
+        let summary = extractive_summary(text);
+
+        assert_eq!(summary.headline, "東京で新しい技術展示会が開幕した");
+        assert_eq!(
+            summary.bullets,
+            vec!["来場者は初日だけで一万人を超えた", "主催者は「大成功だ。」と語った", "本当に"]
+        );
+    }
 }
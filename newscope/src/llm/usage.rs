@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use super::UsageMetadata;
+
+/// One LLM call to record in `llm_usage_log`.
+pub struct UsageEntry<'a> {
+    pub operation: &'a str,
+    pub model: &'a str,
+    pub usage: Option<&'a UsageMetadata>,
+    pub error_message: Option<&'a str>,
+    pub session_id: Option<i64>,
+}
+
+/// Aggregated usage for one (operation, model) pair.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UsageAggregate {
+    pub operation: Option<String>,
+    pub model: Option<String>,
+    pub calls: i64,
+    pub failures: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+}
+
+/// Append an entry to `llm_usage_log`. A missing `usage` is recorded as zero tokens.
+pub async fn log_usage(pool: &SqlitePool, entry: UsageEntry<'_>) -> Result<()> {
+    let (prompt, completion) = entry
+        .usage
+        .map(|u| (u.prompt_tokens as i64, u.completion_tokens as i64))
+        .unwrap_or((0, 0));
+
+    sqlx::query(
+        r#"
+        INSERT INTO llm_usage_log
+            (operation, model, prompt_tokens, completion_tokens, success, error_message, session_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(entry.operation)
+    .bind(entry.model)
+    .bind(prompt)
+    .bind(completion)
+    .bind(entry.error_message.is_none())
+    .bind(entry.error_message)
+    .bind(entry.session_id)
+    .execute(pool)
+    .await
+    .context("failed to insert llm usage log")?;
+
+    Ok(())
+}
+
+/// Record one background LLM call (summarize, classify, embed, ...). A failed
+/// call is recorded with its error message and no tokens. Failing to write the
+/// entry is only logged, so accounting never breaks the call being accounted.
+pub async fn log_llm_usage(
+    pool: &SqlitePool,
+    operation: &str,
+    model: &str,
+    usage: Option<&UsageMetadata>,
+    success: bool,
+    error: Option<&str>,
+) {
+    let error_message = if success { None } else { Some(error.unwrap_or("unknown error")) };
+    let entry = UsageEntry {
+        operation,
+        model,
+        usage,
+        error_message,
+        session_id: None,
+    };
+    if let Err(e) = log_usage(pool, entry).await {
+        tracing::error!("Failed to log {} usage: {:?}", operation, e);
+    }
+}
+
+/// [`log_llm_usage`] for the result of a provider call; `usage` picks the
+/// token counts out of a successful response, if it has any.
+pub async fn log_llm_result<T>(
+    pool: &SqlitePool,
+    operation: &str,
+    model: &str,
+    result: &Result<T>,
+    usage: impl FnOnce(&T) -> Option<&UsageMetadata>,
+) {
+    match result {
+        Ok(value) => log_llm_usage(pool, operation, model, usage(value), true, None).await,
+        Err(e) => log_llm_usage(pool, operation, model, None, false, Some(&format!("{:#}", e))).await,
+    }
+}
+
+/// Totals per operation and model over the last `days` days.
+pub async fn aggregate_usage(pool: &SqlitePool, days: i64) -> Result<Vec<UsageAggregate>> {
+    aggregate_usage_since(pool, Utc::now() - chrono::Duration::days(days)).await
+}
+
+/// Totals per operation and model for calls made at or after `since`.
+pub async fn aggregate_usage_since(pool: &SqlitePool, since: DateTime<Utc>) -> Result<Vec<UsageAggregate>> {
+    sqlx::query_as::<_, UsageAggregate>(
+        r#"
+        SELECT
+            operation,
+            model,
+            COUNT(*) as calls,
+            COALESCE(SUM(CASE WHEN success THEN 0 ELSE 1 END), 0) as failures,
+            COALESCE(SUM(prompt_tokens), 0) as prompt_tokens,
+            COALESCE(SUM(completion_tokens), 0) as completion_tokens,
+            COALESCE(SUM(prompt_tokens), 0) + COALESCE(SUM(completion_tokens), 0) as total_tokens
+        FROM llm_usage_log
+        WHERE created_at >= ?
+        GROUP BY operation, model
+        ORDER BY operation, model
+        "#,
+    )
+    .bind(since.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+    .fetch_all(pool)
+    .await
+    .context("failed to aggregate llm usage")
+}
@@ -5,56 +5,127 @@ This binary starts the Rocket HTTP server and runs the background worker inside
 
 use anyhow::Context;
 use chrono::Utc;
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand};
 use common::Config;
+use rocket::futures::{stream, StreamExt};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::select;
-use tokio::sync::Notify;
+use tokio::sync::watch;
 use tokio::time::Duration;
-use tracing::{error, info};
+use tracing::{error, info, warn, Instrument};
 use tracing_subscriber::{fmt, EnvFilter};
 
 use common::init_db_pool;
 use sqlx::Row;
 
 // Import modules from the lib
+use newscope::config_reload::{self, ConfigSource, SharedConfig};
+use newscope::llm::LlmTask;
 use newscope::server;
+use newscope::worker_status::{self, SharedWorkerStatus};
 use server::launch_rocket;
 
 #[derive(Parser, Debug)]
 #[command(name = "newscope", about = "Newscope single-binary server + worker")]
 struct Args {
     /// Path to config.toml
-    #[arg(long, value_name = "FILE")]
+    #[arg(long, value_name = "FILE", global = true)]
     config: Option<PathBuf>,
 
-    /// Disable background worker (run server only)
-    #[arg(long)]
+    /// Override log level (info, debug, warn, error)
+    #[arg(long, default_value = "info", global = true)]
+    log_level: String,
+
+    /// Same as `serve --no-worker`, kept for existing deployments
+    #[arg(long, hide = true)]
     no_worker: bool,
 
-    /// Run worker only (do not bind HTTP server)
-    #[arg(long)]
+    /// Same as `worker`, kept for existing deployments
+    #[arg(long, hide = true, conflicts_with = "no_worker")]
     worker_only: bool,
 
-    /// Override log level (info, debug, warn, error)
-    #[arg(long, default_value = "info")]
-    log_level: String,
+    /// What to run; `serve` when omitted
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Run the HTTP server and the background worker (default)
+    Serve {
+        /// Disable background worker (run server only)
+        #[arg(long)]
+        no_worker: bool,
+    },
+    /// Run migrations, create missing tables, sync configured users, then exit
+    Migrate,
+    /// Run the background worker only (do not bind HTTP server)
+    Worker,
+    /// Manage user accounts in a migrated database
+    User {
+        #[command(subcommand)]
+        command: UserCommand,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum UserCommand {
+    /// Create a user and print a login token for it
+    Add {
+        #[arg(long)]
+        username: String,
+        #[arg(long)]
+        display_name: Option<String>,
+        /// Read from the first line of stdin when omitted
+        #[arg(long)]
+        password: Option<String>,
+        /// Language of the user's profile
+        #[arg(long, default_value = "en")]
+        language: String,
+    },
+    /// List users: id, username, display name and creation date, tab-separated
+    List,
+}
+
+impl Args {
+    /// The subcommand to run, with the legacy top-level flags mapped onto it.
+    fn resolved_command(&self) -> Command {
+        if self.command.is_some() && (self.no_worker || self.worker_only) {
+            Args::command()
+                .error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    "--no-worker and --worker-only cannot be combined with a subcommand",
+                )
+                .exit();
+        }
+        match self.command.clone() {
+            Some(command) => command,
+            None if self.worker_only => Command::Worker,
+            None => Command::Serve { no_worker: self.no_worker },
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Parse CLI args
     let args = Args::parse();
+    let command = args.resolved_command();
 
     // Initialize logging
     let filter = EnvFilter::try_new(&args.log_level).unwrap_or_else(|_| EnvFilter::new("info"));
-    fmt().with_env_filter(filter).init();
+    if matches!(command, Command::User { .. }) {
+        // Keep stdout for the command's output (e.g. the token), so it can be scripted
+        fmt().with_env_filter(filter).with_writer(std::io::stderr).init();
+    } else {
+        fmt().with_env_filter(filter).init();
+    }
 
     // Resolve config paths
     let default_path = PathBuf::from("config.default.toml");
     
-    let override_path = if let Some(p) = args.config {
+    let override_path = if let Some(p) = args.config.clone() {
         if !p.exists() {
              error!(path = ?p, "specified config file not found");
              return Err(anyhow::anyhow!("Config file not found: {}", p.display()));
@@ -65,91 +136,119 @@ async fn main() -> anyhow::Result<()> {
         if p.exists() { Some(p) } else { None }
     };
 
-    // Load configuration with defaults
-    let config = match Config::load_with_defaults(
-        if default_path.exists() { Some(&default_path) } else { None },
-        override_path.as_deref()
-    ).await {
+    // Load and validate configuration with defaults; SIGHUP reloads the same files
+    let config_source = ConfigSource {
+        default_path: default_path.exists().then(|| default_path.clone()),
+        override_path: override_path.clone(),
+    };
+    let config = match config_source.load().await {
         Ok(cfg) => cfg,
         Err(e) => {
-            error!(%e, "failed to load configuration");
-            return Err(e.into());
+            error!("failed to load configuration: {:#}", e);
+            return Err(e);
         }
     };
     info!(default = ?default_path, override = ?override_path, "configuration loaded");
 
-    // Initialize DB pool - resolve and log the absolute DB path before connecting
-    let db_path_abs = match tokio::fs::canonicalize(&config.database.path).await {
-        Ok(p) => p.to_string_lossy().to_string(),
-        Err(_) => config.database.path.clone(),
-    };
-    info!(db_path = %db_path_abs, "resolved DB path");
-
-    let db_pool = match init_db_pool(&db_path_abs).await {
-        Ok(p) => p,
-        Err(e) => {
-            error!(%e, db_path = %db_path_abs, "failed to initialize database pool");
-            return Err(e.into());
+    if matches!(command, Command::Migrate) {
+        let db_pool = open_db(&config).await?;
+        let result = migrate(&config, &db_pool).await;
+        close_db(&db_pool).await;
+        if let Err(e) = &result {
+            error!("migration failed: {:#}", e);
         }
-    };
+        return result;
+    }
+    if let Command::User { command } = command {
+        let db_pool = open_db(&config).await?;
+        let result = run_user_command(command, &config, &db_pool).await;
+        close_db(&db_pool).await;
+        if let Err(e) = &result {
+            error!("user command failed: {:#}", e);
+        }
+        return result;
+    }
+
+    let shared_config = config_reload::shared(config.clone());
+    #[cfg(unix)]
+    config_reload::spawn_sighup_reload(shared_config.clone(), config_source);
+
+    newscope::http::init(&newscope::http::HttpClientOptions::from_config(Some(&config)))?;
+    newscope::ingestion::init_robots(Some(&config));
+
+    let db_pool = open_db(&config).await?;
     let db_pool = Arc::new(db_pool);
 
-    // Prepare a shutdown notifier to signal worker tasks
-    let shutdown_notify = Arc::new(Notify::new());
+    // Shutdown flag for the worker tasks, set once and checked between steps
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
     // Initialize LLM providers for specific tasks
-    let summarization_llm = config.llm.as_ref().and_then(|l| create_llm_provider(l, LlmMode::Summarization).ok().map(Arc::from));
-    let personalization_llm = config.llm.as_ref().and_then(|l| create_llm_provider(l, LlmMode::Personalization).ok().map(Arc::from));
-    let interaction_llm = config.llm.as_ref().and_then(|l| create_llm_provider(l, LlmMode::Interaction).ok().map(Arc::from));
-    let embedding_llm = config.llm.as_ref().and_then(|l| create_llm_provider(l, LlmMode::Embedding).ok().map(Arc::from));
+    let summarization_llm = init_llm_provider(&config, LlmTask::Summarization);
+    let personalization_llm = init_llm_provider(&config, LlmTask::Personalization);
+    let interaction_llm = init_llm_provider(&config, LlmTask::Interaction);
+    let (embedding_llm, embedding_model) = init_embedding_provider(&config)?;
+    let embedding_llm = Some(embedding_llm);
 
     if let Some(ref _l) = summarization_llm { info!("Summarization LLM initialized"); }
     if let Some(ref _l) = personalization_llm { info!("Personalization LLM initialized"); }
     if let Some(ref _l) = interaction_llm { info!("Interaction LLM initialized"); }
-    if let Some(ref _l) = embedding_llm { info!("Embedding LLM initialized"); }
+    info!("Embedding provider initialized ({})", embedding_model);
 
-    // If worker_only, run the worker tasks (without HTTP) and exit when shutdown requested
-    if args.worker_only {
+    // In worker mode, run the worker tasks (without HTTP) and exit when shutdown requested
+    if matches!(command, Command::Worker) {
         info!("Starting in worker-only mode");
+        bootstrap_catalog(&config, &db_pool).await;
         let worker = run_worker(
             db_pool.clone(), 
-            config.clone(), 
-            shutdown_notify.clone(), 
+            shared_config.clone(), 
+            shutdown_rx.clone(), 
             summarization_llm.clone(), 
             personalization_llm.clone(),
-            embedding_llm.clone()
+            embedding_llm.clone(),
+            embedding_model.clone(),
+            worker_status::shared(),
         );
 
         // Wait for CTRL-C or worker completion (worker runs until notified)
-        tokio::select! {
+        tokio::pin!(worker);
+        let res = tokio::select! {
             _ = tokio::signal::ctrl_c() => {
                 info!("ctrl-c received, notifying worker to shutdown");
-                shutdown_notify.notify_waiters();
-                // give worker a small grace period
-                tokio::time::sleep(Duration::from_secs(5)).await;
-            }
-            res = worker => {
-                if let Err(e) = res {
-                    error!(%e, "worker encountered an error");
-                }
+                shutdown_tx.send_replace(true);
+                // let the job in hand finish before closing the pool under it
+                tokio::time::timeout(Duration::from_secs(20), &mut worker)
+                    .await
+                    .unwrap_or_else(|_| {
+                        info!("Timed out waiting for worker to exit; continuing shutdown");
+                        Ok(())
+                    })
             }
+            res = &mut worker => res,
+        };
+        if let Err(e) = res {
+            error!(%e, "worker encountered an error");
         }
         info!("worker-only run finished");
+        close_db(&db_pool).await;
         return Ok(());
     }
 
     // Otherwise, start worker (unless disabled) and then start HTTP server.
     let mut worker_handle = None;
-    if !args.no_worker {
+    let mut worker_status = None;
+    if matches!(command, Command::Serve { no_worker: false }) {
         info!("Spawning background worker task");
         let w_db = db_pool.clone();
-        let w_cfg = config.clone();
-        let w_shutdown = shutdown_notify.clone();
+        let w_cfg = shared_config.clone();
+        let w_shutdown = shutdown_rx.clone();
         let w_summarize = summarization_llm.clone();
         let w_personalize = personalization_llm.clone();
         let w_embed = embedding_llm.clone();
+        let w_embed_model = embedding_model.clone();
+        let w_status = worker_status::shared();
+        worker_status = Some(w_status.clone());
         worker_handle = Some(tokio::spawn(async move {
-            if let Err(e) = run_worker(w_db, w_cfg, w_shutdown, w_summarize, w_personalize, w_embed).await {
+            if let Err(e) = run_worker(w_db, w_cfg, w_shutdown, w_summarize, w_personalize, w_embed, w_embed_model, w_status).await {
                 error!(%e, "background worker failed");
                 Err(e)
             } else {
@@ -157,7 +256,7 @@ async fn main() -> anyhow::Result<()> {
             }
         }));
     } else {
-        info!("Background worker disabled via CLI (--no-worker)");
+        info!("Background worker disabled via CLI (serve --no-worker)");
     }
 
     // Before launching the HTTP server, optionally run automatic DB migrations
@@ -170,22 +269,11 @@ async fn main() -> anyhow::Result<()> {
         .unwrap_or(false)
     {
         info!("Auto-migrate enabled: running DB migrations");
-        // Run sqlx migrations against the existing pool. The migrations directory may be empty
-        // in some packaging scenarios; to be robust we also ensure the core schema exists.
-        sqlx::migrate!("../migrations").run(&*db_pool).await?;
-        info!("DB migrations completed");
-        // Ensure core schema exists even if migrations didn't create tables (defensive).
-        server::ensure_schema(&*db_pool).await?;
-    // Start worker loop
-    info!("Newscope worker starting...");
-    
-    // Initial fetch
-    info!("Performing initial feed fetch...");
-        // Ensure users defined in config are present in the DB users table
-        common::sync_users(&config, &*db_pool).await?;
-        info!("Configuration users synchronized into database");
+        migrate(&config, &db_pool).await?;
     }
 
+    bootstrap_catalog(&config, &db_pool).await;
+
     // Launch the Rocket server (blocking until Rocket shuts down)
     info!("Launching Rocket HTTP server");
     if let Err(e) = launch_rocket(
@@ -194,16 +282,17 @@ async fn main() -> anyhow::Result<()> {
         personalization_llm.clone(),
         interaction_llm.clone(),
         embedding_llm.clone(),
-        Some(Arc::new(config.clone()))
+        Some(shared_config.clone()),
+        worker_status,
     ).await {
         error!(%e, "Rocket server failed");
         // Signal worker to stop if running
-        shutdown_notify.notify_waiters();
+        shutdown_tx.send_replace(true);
     }
 
     // When the server shuts down, notify worker and wait a bit for graceful termination.
     info!("HTTP server stopped; notifying worker to shutdown");
-    shutdown_notify.notify_waiters();
+    shutdown_tx.send_replace(true);
 
     // Optionally wait for the worker to finish or timeout
     if let Some(handle) = worker_handle {
@@ -219,66 +308,191 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    close_db(&db_pool).await;
     info!("Shutdown complete");
     Ok(())
 }
 
-/// LLM mode for selecting appropriate configuration
-#[derive(Debug, Clone, Copy)]
-enum LlmMode {
-    Summarization,
-    Personalization,
-    Embedding,
-    Interaction,
+/// Open the configured database, creating the file and its directory if needed.
+async fn open_db(config: &Config) -> anyhow::Result<sqlx::SqlitePool> {
+    // Resolve and log the absolute DB path before connecting
+    let db_path_abs = match tokio::fs::canonicalize(&config.database.path).await {
+        Ok(p) => p.to_string_lossy().to_string(),
+        Err(_) => config.database.path.clone(),
+    };
+    info!(db_path = %db_path_abs, "resolved DB path");
+
+    init_db_pool(&db_path_abs).await.map_err(|e| {
+        error!(%e, db_path = %db_path_abs, "failed to initialize database pool");
+        e
+    })
+}
+
+/// Bring the database up to date: sqlx migrations, then the core schema (in case
+/// the packaged migrations directory is empty), then the users from the config.
+/// Run by `newscope migrate`, and before serving when `admin.auto_migrate = true`.
+async fn migrate(config: &Config, pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
+    common::run_migrations(pool).await?;
+    info!("DB migrations completed");
+    server::ensure_schema(pool).await?;
+    common::sync_users(config, pool).await?;
+    info!("Configuration users synchronized into database");
+    Ok(())
+}
+
+/// `newscope user ...`: prints to stdout so the output can be scripted.
+async fn run_user_command(command: UserCommand, config: &Config, pool: &sqlx::SqlitePool) -> anyhow::Result<()> {
+    match command {
+        UserCommand::Add { username, display_name, password, language } => {
+            server::check_jwt_secret()?;
+            let password = match password {
+                Some(p) => p,
+                None => {
+                    let mut line = String::new();
+                    std::io::stdin().read_line(&mut line).context("failed to read the password from stdin")?;
+                    line.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+            if password.is_empty() {
+                anyhow::bail!("the password must not be empty");
+            }
+            let user_id = newscope::auth::create_user(pool, &username, display_name.as_deref(), &password, &language)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("username {:?} is taken", username))?;
+            info!(user_id, username = %username, "user created");
+            println!("{}", server::issue_token(user_id, Some(config))?);
+        }
+        UserCommand::List => {
+            for user in newscope::auth::list_users(pool).await? {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    user.id,
+                    user.username,
+                    user.display_name.unwrap_or_default(),
+                    user.created_at.unwrap_or_default()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Flush the WAL and close the pool once workers are drained.
+async fn close_db(pool: &sqlx::SqlitePool) {
+    match common::close_db_pool(pool).await {
+        Ok(()) => info!("Database checkpointed and closed"),
+        Err(e) => error!("Failed to close database cleanly: {:?}", e),
+    }
+}
+
+/// An endpoint's `api_key_env` variable is not set.
+#[derive(Debug)]
+struct MissingApiKey(String);
+
+impl std::fmt::Display for MissingApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LLM API key env var '{}' not set", self.0)
+    }
+}
+
+impl std::error::Error for MissingApiKey {}
+
+/// Build the provider for `mode`, or None when it cannot be built. None disables
+/// the LLM features for that mode; feed aggregation keeps running. A missing API
+/// key (e.g. a secret not mounted yet) is reported loudly rather than aborting.
+fn init_llm_provider(config: &Config, mode: LlmTask) -> Option<Arc<dyn newscope::llm::LlmProvider>> {
+    let llm_config = config.llm.as_ref()?;
+    if llm_config.adapter.as_deref().unwrap_or("none") == "none" {
+        return None;
+    }
+    match create_llm_provider(llm_config, mode) {
+        Ok(provider) => Some(Arc::from(provider)),
+        Err(e) if e.downcast_ref::<MissingApiKey>().is_some() => {
+            tracing::warn!(
+                "!!! {:?} LLM DISABLED: {}. Feeds are still aggregated, but summaries, \
+                 personalization and chat are unavailable until the key is provided and newscope is restarted.",
+                mode,
+                e
+            );
+            None
+        }
+        Err(e) => {
+            error!("{:?} LLM disabled: {:#}", mode, e);
+            None
+        }
+    }
+}
+
+/// Embedding provider and the model its vectors are recorded under. The local
+/// hashing embedder stands in only when no embedding endpoint is configured
+/// (`adapter = "none"`, or a remote adapter without `[llm.embedding]` nor
+/// `[llm.remote]`); a configured one that can't be built (missing key, bad
+/// section) is an error, since silently mixing fallback vectors with the
+/// model's would corrupt similarity search.
+fn init_embedding_provider(config: &Config) -> anyhow::Result<(Arc<dyn newscope::llm::LlmProvider>, String)> {
+    let model = newscope::processing::embedding_model(Some(config));
+    if let Some(llm_config) = config.llm.as_ref().filter(|_| newscope::processing::embedding_configured(Some(config))) {
+        let provider = create_llm_provider(llm_config, LlmTask::Embedding).context(
+            "the configured embedding provider is unusable; fix it, or remove the embedding endpoint \
+             to use the local hashing embedder",
+        )?;
+        return Ok((Arc::from(provider), model));
+    }
+    tracing::warn!(
+        "No embedding model configured: using the local n-gram hashing fallback ({}). \
+         Similarity search and personalization only see lexical overlap until an embedding model is configured.",
+        newscope::processing::LOCAL_EMBEDDING_MODEL
+    );
+    let fallback: Arc<dyn newscope::llm::LlmProvider> = Arc::new(newscope::processing::HashingEmbedder::new(
+        newscope::processing::embedding_dim(Some(config)),
+    ));
+    Ok((fallback, model))
 }
 
 /// Create an LLM provider based on configuration and mode
-fn create_llm_provider(llm_config: &common::LlmConfig, mode: LlmMode) -> anyhow::Result<Box<dyn newscope::llm::LlmProvider>> {
+fn create_llm_provider(llm_config: &common::LlmConfig, mode: LlmTask) -> anyhow::Result<Box<dyn newscope::llm::LlmProvider>> {
     let adapter = llm_config.adapter.as_deref().unwrap_or("none");
     match adapter {
         "local" => {
-            // Placeholder for local provider
-            anyhow::bail!("Local LLM adapter not yet implemented in main.rs factory")
+            // One model serves every mode
+            let local_config = llm_config.local.as_ref()
+                .ok_or_else(|| anyhow::anyhow!("Local adapter selected but [llm.local] is missing"))?;
+            Ok(Box::new(newscope::llm::local::LocalLlmProvider::from_config(local_config)?))
         }
         "remote" => {
-            // Choose config based on mode with fallback ladder
-            let endpoint_config = match mode {
-                LlmMode::Summarization => llm_config.summarization.as_ref()
-                    .or(llm_config.background.as_ref())
-                    .or(llm_config.remote.as_ref()),
-                LlmMode::Personalization => llm_config.personalization.as_ref()
-                    .or(llm_config.background.as_ref())
-                    .or(llm_config.remote.as_ref()),
-                LlmMode::Interaction => llm_config.interaction.as_ref()
-                    .or(llm_config.interactive.as_ref())
-                    .or(llm_config.remote.as_ref()),
-                LlmMode::Embedding => llm_config.embedding.as_ref()
-                    .or(llm_config.remote.as_ref()),
+            // Background tasks spread load over the weighted pool when one is configured,
+            // unless a task-specific endpoint overrides it
+            let task_specific = match mode {
+                LlmTask::Summarization => llm_config.summarization.is_some(),
+                LlmTask::Personalization => llm_config.personalization.is_some(),
+                _ => true,
             };
+            if !task_specific && !llm_config.background_endpoints.is_empty() {
+                let mut endpoints: Vec<(String, Arc<dyn newscope::llm::LlmProvider>, f64)> = Vec::new();
+                let mut last_error = None;
+                for remote_config in &llm_config.background_endpoints {
+                    let name = remote_config.api_url.clone().unwrap_or_default();
+                    // Endpoints whose key is missing are left out of the pool
+                    match build_remote_provider(remote_config) {
+                        Ok(provider) => endpoints.push((name, Arc::new(provider), remote_config.weight.unwrap_or(1.0))),
+                        Err(e) if e.downcast_ref::<MissingApiKey>().is_some() => {
+                            tracing::warn!("LLM endpoint {} skipped: {}", name, e);
+                            last_error = Some(e);
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                if endpoints.is_empty() {
+                    return Err(last_error.unwrap_or_else(|| anyhow::anyhow!("no usable background endpoint")));
+                }
+                return Ok(Box::new(newscope::llm::balanced::LoadBalancedLlmProvider::new(endpoints)));
+            }
+
+            // Task-specific endpoint, with fallback ladder
+            let endpoint_config = mode.endpoint_config(llm_config);
 
             if let Some(remote_config) = endpoint_config {
-                // Fetch API key from env var
-                let api_key_env = remote_config.api_key_env.as_deref()
-                    .ok_or_else(|| anyhow::anyhow!("Missing api_key_env in remote config"))?;
-                
-                let api_key = std::env::var(api_key_env)
-                    .with_context(|| format!("LLM API key env var '{}' not set", api_key_env))?;
-                
-                let model = remote_config.model.clone().unwrap_or_else(|| "gpt-4o-mini".to_string());
-                let api_url = remote_config.api_url.clone().unwrap_or_else(|| "http://localhost:11434/v1/chat/completions".to_string());
-                let timeout_secs = remote_config.timeout_seconds.unwrap_or(30);
-                let max_tokens = remote_config.max_tokens.unwrap_or(500);
-
-                let provider = newscope::llm::remote::RemoteLlmProvider::new(
-                    api_url,
-                    api_key,
-                    model,
-                ).with_defaults(
-                    timeout_secs,
-                    max_tokens,
-                    0.7,
-                );
-                Ok(Box::new(provider))
+                Ok(Box::new(build_remote_provider(remote_config)?))
             } else {
                 anyhow::bail!("Remote adapter selected but no LLM config found for mode {:?}", mode)
             }
@@ -290,192 +504,453 @@ fn create_llm_provider(llm_config: &common::LlmConfig, mode: LlmMode) -> anyhow:
     }
 }
 
-/// run_worker is the top-level background worker entrypoint. It runs until `shutdown_notify`
-/// is signalled. The function encapsulates scheduling logic, politeness and ingestion loops.
-/// For now it runs a placeholder schedule loop. Replace the TODO sections with the real logic.
+/// Build a remote provider from one endpoint config, reading the API key from the environment.
+fn build_remote_provider(remote_config: &common::RemoteLlmConfig) -> anyhow::Result<newscope::llm::remote::RemoteLlmProvider> {
+    // Fetch API key from env var
+    let api_key_env = remote_config.api_key_env.as_deref()
+        .ok_or_else(|| anyhow::anyhow!("Missing api_key_env in remote config"))?;
+
+    let api_key = std::env::var(api_key_env)
+        .map_err(|_| MissingApiKey(api_key_env.to_string()))?;
+
+    let model = remote_config.model.clone().unwrap_or_else(|| "gpt-4o-mini".to_string());
+    let api_url = remote_config.api_url.clone().unwrap_or_else(|| "http://localhost:11434/v1/chat/completions".to_string());
+    let timeout_secs = remote_config.timeout_seconds.unwrap_or(30);
+    let max_tokens = remote_config.max_tokens.unwrap_or(500);
+    let max_retries = remote_config.max_retries.unwrap_or(newscope::llm::remote::DEFAULT_MAX_RETRIES);
+    let retry_base_delay_ms = remote_config
+        .retry_base_delay_ms
+        .unwrap_or(newscope::llm::remote::DEFAULT_RETRY_BASE_DELAY_MS);
+
+    Ok(newscope::llm::remote::RemoteLlmProvider::new(
+        api_url,
+        api_key,
+        model,
+    ).with_defaults(
+        timeout_secs,
+        max_tokens,
+        0.7,
+    ).with_retries(
+        max_retries,
+        Duration::from_millis(retry_base_delay_ms),
+    ))
+}
+
+/// Seed the feed catalog from `admin.bootstrap_opml`, if configured.
+async fn bootstrap_catalog(config: &Config, pool: &sqlx::SqlitePool) {
+    let Some(path) = config.admin.as_ref().and_then(|a| a.bootstrap_opml.as_deref()) else {
+        return;
+    };
+    if let Err(e) = newscope::opml::bootstrap_feeds(pool, std::path::Path::new(path)).await {
+        error!("Failed to bootstrap feeds from {}: {:?}", path, e);
+    }
+}
+
+/// Poll one due feed: fetch it within its domain's politeness limits, store
+/// new items (queuing their summary when `summarize`) and schedule the next
+/// poll. Returns the feed's dedup counts.
+async fn poll_feed(
+    pool: &sqlx::SqlitePool,
+    config: &common::Config,
+    limiter: &newscope::politeness::DomainLimiter,
+    row: sqlx::sqlite::SqliteRow,
+    merge_by_hash: bool,
+    summarize: bool,
+    status: &SharedWorkerStatus,
+) -> newscope::dedup::DedupStats {
+    let mut feed_dedup = newscope::dedup::DedupStats::default();
+    let feed_id: i64 = row.get("id");
+    let url: String = row.get("url");
+    let mut interval: i64 = row.get("poll_interval_minutes");
+    let adaptive: bool = row.get("adaptive_scheduling");
+    let first_fetch = row.get::<Option<String>, _>("last_checked").is_none();
+    let validators = newscope::ingestion::CacheValidators {
+        etag: row.get("etag"),
+        last_modified: row.get("last_modified"),
+    };
+    
+    info!("worker: processing feed {} ({})", feed_id, url);
+    
+    // Fetch feed
+    let fetch_opts = newscope::ingestion::FetchOptions::from_config(Some(config));
+    // 2. Fetch and parse, holding the domain's permit only for the request itself
+    let permit = limiter.acquire(&url).await;
+    let (fetch_result, fetch_metrics) = newscope::ingestion::fetch_feed_conditional(&url, &fetch_opts, &validators).await;
+    drop(permit);
+    let fetch_error = fetch_result.as_ref().err().map(|e| e.to_string());
+    if let Err(e) = newscope::feed_health::record_fetch(pool, feed_id, &fetch_metrics, fetch_error.as_deref()).await {
+        error!("worker: {}", e);
+    }
+    if fetch_result.is_ok() {
+        worker_status::update(status, |s| s.last_successful_poll_at = Some(Utc::now()));
+        if let Err(e) = newscope::feed_health::record_success(pool, feed_id).await {
+            error!("worker: {}", e);
+        }
+    }
+    match fetch_result {
+        Ok(newscope::ingestion::FetchOutcome::NotModified) => {
+            // Unchanged since the last fetch: nothing to parse or store
+            info!("Feed '{}' not modified", url);
+            if adaptive {
+                interval = (interval + (interval / 2)).min(1440);
+            }
+            let _ = sqlx::query(
+                "UPDATE feeds SET next_poll_at = ?, poll_interval_minutes = ?, last_checked = ? WHERE id = ?"
+            )
+            .bind(Utc::now() + chrono::Duration::minutes(interval))
+            .bind(interval)
+            .bind(Utc::now())
+            .bind(feed_id)
+            .execute(pool)
+            .await;
+        }
+        Ok(newscope::ingestion::FetchOutcome::Fetched { feed, validators }) => {
+            info!("Fetched feed '{}': {} items", url, feed.entries.len());
+            if let Err(e) = newscope::storage::set_cache_validators(pool, feed_id, &validators).await {
+                error!("worker: {}", e);
+            }
+            let declared_interval = newscope::ingestion::declared_poll_interval(&feed);
+            if let Err(e) = newscope::storage::set_declared_update_minutes(pool, feed_id, declared_interval).await {
+                error!("worker: {}", e);
+            }
+            let mut new_items_found = false;
+            let transform = newscope::storage::EntryTransform::for_feed(Some(config), &url);
+            let urls = newscope::dedup::UrlNormalizer::from_config(Some(config));
+            match newscope::storage::store_feed_items_with_stats(pool, feed_id, &feed.entries, transform.as_ref(), &urls, merge_by_hash).await {
+                Ok((article_ids, dedup_stats)) => {
+                    info!("Stored {} items for feed '{}'", article_ids.len(), url);
+                    feed_dedup.add(&dedup_stats);
+                    if let Err(e) = newscope::dedup::record_feed_stats(pool, feed_id, &dedup_stats).await {
+                        error!("worker: {}", e);
+                    }
+                    
+                    // 3. Process new articles with LLM if configured
+                    if !article_ids.is_empty() {
+                        new_items_found = true;
+                        
+                        // Queued for the job runner
+                        if summarize {
+                            match newscope::jobs::enqueue(pool, newscope::jobs::JOB_SUMMARY, &article_ids).await {
+                                Ok(n) => info!("Queued {} new articles for summarization", n),
+                                Err(e) => error!("worker: {:#}", e),
+                            }
+                        }
+                    }
+                }
+                Err(e) => error!("worker: failed to store items for feed {}: {}", feed_id, e),
+            }
+            
+            // A new feed starts from its declared cadence instead of the default
+            if let (true, Some(declared)) = (first_fetch, declared_interval) {
+                info!("worker: feed {} declares a {}-minute update period", feed_id, declared);
+                interval = declared;
+            } else if adaptive {
+                if new_items_found {
+                    interval = (interval / 2).max(15);
+                } else {
+                    interval = (interval + (interval / 2)).min(1440);
+                }
+            }
+            
+            // Update next_poll_at
+            let next_poll = Utc::now() + chrono::Duration::minutes(interval);
+            let _ = sqlx::query(
+                "UPDATE feeds SET next_poll_at = ?, poll_interval_minutes = ?, last_checked = ? WHERE id = ?"
+            )
+            .bind(next_poll)
+            .bind(interval)
+            .bind(Utc::now())
+            .bind(feed_id)
+            .execute(pool)
+            .await;
+        }
+        Err(e) if e.downcast_ref::<newscope::ingestion::RobotsBlocked>().is_some() => {
+            // Not a failure: check again at the usual pace in case the rules change
+            info!("worker: skipping feed {}: {}", feed_id, e);
+            let _ = sqlx::query("UPDATE feeds SET next_poll_at = ? WHERE id = ?")
+                .bind(Utc::now() + chrono::Duration::minutes(interval))
+                .bind(feed_id)
+                .execute(pool)
+                .await;
+        }
+        Err(e) => {
+            error!("worker: failed to fetch feed {}: {}", feed_id, e);
+
+            // Permanent (4xx) failures in a row dead-letter the feed
+            let permanent = newscope::feed_health::is_permanent_failure(fetch_metrics.http_status);
+            let max_failures = newscope::feed_health::max_consecutive_failures(Some(config));
+            match newscope::feed_health::record_failure(pool, feed_id, permanent, max_failures).await {
+                Ok(true) => warn!(
+                    "worker: feed {} disabled after {} consecutive failures, re-enable it with POST /api/v1/feeds/{}/enable",
+                    feed_id, max_failures, feed_id
+                ),
+                Ok(false) => {}
+                Err(e) => error!("worker: {}", e),
+            }
+
+            // Scheduler Backoff: Double the interval to avoid spamming a failing feed,
+            // up to `scheduler.max_backoff_minutes`
+            let new_interval = newscope::feed_health::failure_backoff(interval, newscope::feed_health::max_backoff_minutes(Some(config)));
+            info!("worker: feed {} failed, backing off interval from {} to {} minutes", feed_id, interval, new_interval);
+            
+            let next_poll = Utc::now() + chrono::Duration::minutes(new_interval);
+            let _ = sqlx::query(
+                "UPDATE feeds SET next_poll_at = ?, poll_interval_minutes = ? WHERE id = ?"
+            )
+                .bind(next_poll)
+                .bind(new_interval)
+                .bind(feed_id)
+                .execute(pool)
+                .await;
+        }
+    }
+    feed_dedup
+}
+
+/// Articles queued for embedding per worker pass.
+const EMBEDDING_BACKFILL_LIMIT: i64 = 100;
+
+/// run_worker is the top-level background worker entrypoint. It runs until `shutdown`
+/// turns true, and waits for the job runner to stop before returning. Every minute it polls the feeds whose own cadence is due and, at the
+/// `scheduler.times` wall-clock times, every active feed (see [`newscope::scheduler`]).
+/// A reloaded configuration is picked up at the start of the next loop.
+#[allow(clippy::too_many_arguments)]
 async fn run_worker(
     _db_pool: Arc<sqlx::SqlitePool>,
-    config: common::Config,
-    shutdown_notify: Arc<Notify>,
+    shared_config: SharedConfig,
+    mut shutdown: watch::Receiver<bool>,
     summarization_llm: Option<Arc<dyn newscope::llm::LlmProvider>>,
     personalization_llm: Option<Arc<dyn newscope::llm::LlmProvider>>,
     embedding_llm: Option<Arc<dyn newscope::llm::LlmProvider>>,
+    embedding_model: String,
+    status: SharedWorkerStatus,
 ) -> anyhow::Result<()> {
+    let mut config = shared_config.load_full();
+    let mut schedule = newscope::scheduler::Schedule::from_config(&config)?;
     info!(
-        "worker: initializing scheduler with times: {:?}",
-        config.scheduler.times
+        "worker: initializing scheduler in {:?} mode with times {:?} ({:?})",
+        schedule.mode, config.scheduler.times, schedule.timezone
     );
+    let mut next_full_pass = schedule.next_run_after(Utc::now());
+    if let Some(at) = next_full_pass {
+        info!("worker: next scheduled full pass at {}", at);
+    }
+
+    if embedding_llm.is_some() {
+        let text = newscope::processing::EmbeddingText::from_config(Some(&config));
+        if let Err(e) = newscope::processing::sync_embedding_text(&_db_pool, &text).await {
+            error!("Error checking embedding text composition: {:?}", e);
+        }
+        if let Err(e) = newscope::processing::sync_embedding_model(&_db_pool, &embedding_model).await {
+            error!("Error checking embedding model: {:?}", e);
+        }
+        let dim = newscope::processing::embedding_dim(Some(&config));
+        match newscope::maintenance::vector_table_dim(&_db_pool, "vec_articles").await {
+            Ok(Some(declared)) if declared != dim => error!(
+                "vec_articles holds {}-dimensional vectors but embedding_dim is {}: \
+                 POST /api/v1/admin/reembed to rebuild the embeddings",
+                declared, dim
+            ),
+            Ok(_) => {}
+            Err(e) => error!("Error checking embedding dimension: {:?}", e),
+        }
+    }
+
+    // Summaries, personalization and embeddings of new articles
+    let runner = newscope::jobs::JobRunner {
+        pool: (*_db_pool).clone(),
+        config: shared_config.clone(),
+        summarization_llm: summarization_llm.clone(),
+        personalization_llm,
+        embedding_llm: embedding_llm.clone(),
+        embedding_model: embedding_model.clone(),
+        status: Some(status.clone()),
+    };
+    let runner_handle = tokio::spawn(runner.run(shutdown.clone()));
 
-    // Example: convert times to a vector for scheduling; real implementation should parse times
-    // and schedule ingestion windows precisely at wall-clock times.
-    // Placeholder loop: tick every hour and respond to shutdown.
+    let mut retention_days = newscope::maintenance::retention_days(Some(&config));
+    let mut next_prune = Utc::now();
+    let mut learning_rate = newscope::personalize_worker::rating_learning_rate(Some(&config));
+    let mut next_rating_pass = Utc::now();
 
     loop {
-        info!("worker: checking for feeds to update");
-        
-        // 1. Find feeds due for update
         let now = Utc::now();
-        let feeds = sqlx::query(
-            "SELECT id, url, poll_interval_minutes, adaptive_scheduling FROM feeds WHERE next_poll_at <= ? OR next_poll_at IS NULL"
-        )
-        .bind(now)
-        .fetch_all(&*_db_pool)
-        .await;
-
-        match feeds {
-            Ok(rows) => {
-                if rows.is_empty() {
-                    info!("worker: no feeds due for update");
-                } else {
-                    info!("worker: found {} feeds to update", rows.len());
-                    
-                    for row in rows {
-                        let feed_id: i64 = row.get("id");
-                        let url: String = row.get("url");
-                        let mut interval: i64 = row.get("poll_interval_minutes");
-                        let adaptive: bool = row.get("adaptive_scheduling");
-                        
-                        info!("worker: processing feed {} ({})", feed_id, url);
-                        
-                        // Fetch feed
-                        let timeout = config.politeness.as_ref()
-                            .and_then(|p| p.fetch_timeout_seconds)
-                            .unwrap_or(10);
-                        // 2. Fetch and parse
-                        match newscope::ingestion::fetch_and_parse_feed(&url, timeout).await {
-                            Ok(feed) => {
-                                info!("Fetched feed '{}': {} items", url, feed.entries.len());
-                                let mut new_items_found = false;
-                                match newscope::storage::store_feed_items(&_db_pool, feed_id, &feed.entries).await {
-                                    Ok(article_ids) => {
-                                        info!("Stored {} items for feed '{}'", article_ids.len(), url);
-                                        
-                                        // 3. Process new articles with LLM if configured
-                                        if !article_ids.is_empty() {
-                                            new_items_found = true;
-                                            
-                                            // Handle Summarization
-                                            if let Some(provider) = &summarization_llm {
-                                                info!("Summarizing {} new articles...", article_ids.len());
-                                                let provider = provider.clone();
-                                                let pool = _db_pool.clone();
-                                                let model = config.llm.as_ref()
-                                                    .and_then(|l| l.summarization.as_ref().or(l.background.as_ref()).or(l.remote.as_ref()))
-                                                    .and_then(|r| r.model.as_deref())
-                                                    .unwrap_or("summarizer")
-                                                    .to_string();
-
-                                                let pers_llm = personalization_llm.clone();
-                                                tokio::spawn(async move {
-                                                    if let Err(e) = newscope::processing::batch_process_articles(
-                                                        &pool,
-                                                        &article_ids,
-                                                        provider,
-                                                        pers_llm,
-                                                        &model,
-                                                    )
-                                                    .await {
-                                                        error!("Error summarizing articles: {:?}", e);
-                                                    }
-                                                });
-                                            }
-                                            
-                                        }
-                                    }
-                                    Err(e) => error!("worker: failed to store items for feed {}: {}", feed_id, e),
-                                }
-                                
-                                // Adaptive scheduling update
-                                if adaptive {
-                                    if new_items_found {
-                                        interval = (interval / 2).max(15);
-                                    } else {
-                                        interval = (interval + (interval / 2)).min(1440);
-                                    }
-                                }
-                                
-                                // Update next_poll_at
-                                let next_poll = Utc::now() + chrono::Duration::minutes(interval);
-                                let _ = sqlx::query(
-                                    "UPDATE feeds SET next_poll_at = ?, poll_interval_minutes = ?, last_checked = ? WHERE id = ?"
-                                )
-                                .bind(next_poll)
-                                .bind(interval)
-                                .bind(Utc::now())
-                                .bind(feed_id)
-                                .execute(&*_db_pool)
-                                .await;
-                            }
-                            Err(e) => {
-                                error!("worker: failed to fetch feed {}: {}", feed_id, e);
-                                
-                                // Scheduler Backoff: Double the interval to avoid spamming a failing feed
-                                // Cap at 24 hours (1440 minutes)
-                                let new_interval = (interval * 2).min(1440);
-                                info!("worker: feed {} failed, backing off interval from {} to {} minutes", feed_id, interval, new_interval);
-                                
-                                let next_poll = Utc::now() + chrono::Duration::minutes(new_interval);
-                                let _ = sqlx::query(
-                                    "UPDATE feeds SET next_poll_at = ?, poll_interval_minutes = ? WHERE id = ?"
-                                )
-                                    .bind(next_poll)
-                                    .bind(new_interval)
-                                    .bind(feed_id)
-                                    .execute(&*_db_pool)
-                                    .await;
-                            }
-                        }
-                    }
+        worker_status::update(&status, |s| s.last_loop_at = Some(now));
+
+        let latest = shared_config.load_full();
+        if !Arc::ptr_eq(&latest, &config) {
+            config = latest;
+            match newscope::scheduler::Schedule::from_config(&config) {
+                Ok(reloaded) => {
+                    schedule = reloaded;
+                    next_full_pass = schedule.next_run_after(now);
+                    info!(
+                        "worker: configuration reloaded, {:?} mode with times {:?}, next full pass at {:?}",
+                        schedule.mode, config.scheduler.times, next_full_pass
+                    );
                 }
+                Err(e) => error!("worker: keeping the previous schedule: {:#}", e),
             }
-            Err(e) => error!("worker: failed to query feeds: {}", e),
+            retention_days = newscope::maintenance::retention_days(Some(&config));
+            learning_rate = newscope::personalize_worker::rating_learning_rate(Some(&config));
         }
 
-        // 4. Process missing article embeddings
-        if let Some(provider) = &embedding_llm {
-            let provider = provider.clone();
+        // Article retention, every PRUNE_INTERVAL_HOURS whatever the scheduler mode
+        if let Some(days) = retention_days.filter(|_| next_prune <= now) {
+            next_prune = now + chrono::Duration::hours(newscope::maintenance::PRUNE_INTERVAL_HOURS);
             let pool = _db_pool.clone();
-            let model = config.llm.as_ref()
-                .and_then(|l| l.embedding.as_ref())
-                .or(config.llm.as_ref().and_then(|l| l.remote.as_ref()))
-                .and_then(|r| r.model.as_deref())
-                .unwrap_or("unknown")
-                .to_string();
-
             tokio::spawn(async move {
-                if let Err(e) = newscope::processing::process_missing_embeddings(
-                    &pool,
-                    provider,
-                    &model, 
-                    20
-                ).await {
-                     error!("Error processing embeddings: {:?}", e);
+                if let Err(e) = newscope::maintenance::prune_articles(&pool, days).await {
+                    error!("Error pruning old articles: {:?}", e);
                 }
             });
         }
-        
-        // 5. Initialize user vectors
-        if let Some(provider) = &embedding_llm {
-            let provider = provider.clone();
+
+        // Learn category weights from ratings, every RATING_LEARNING_INTERVAL_HOURS
+        if learning_rate > 0.0 && next_rating_pass <= now {
+            next_rating_pass = now + chrono::Duration::hours(newscope::personalize_worker::RATING_LEARNING_INTERVAL_HOURS);
             let pool = _db_pool.clone();
             tokio::spawn(async move {
-                if let Err(e) = newscope::personalize_worker::initialize_user_vectors(&pool, provider).await {
-                    error!("Error initializing user vectors: {:?}", e);
+                if let Err(e) = newscope::personalize_worker::learn_from_ratings(&pool, learning_rate).await {
+                    error!("Error learning from ratings: {:?}", e);
                 }
             });
         }
 
-        select! {
-            _ = tokio::time::sleep(Duration::from_secs(60)) => {
-                // Loop again
-            },
-            _ = shutdown_notify.notified() => {
-                info!("worker: shutdown requested, exiting loop");
+        let full_pass = next_full_pass.is_some_and(|at| at <= now);
+        if full_pass {
+            next_full_pass = schedule.next_run_after(now);
+            info!("worker: scheduled full pass, next one at {:?}", next_full_pass);
+        } else if !schedule.mode.uses_cadence() {
+            if wait_for_next_tick(next_full_pass, &mut shutdown).await {
                 break;
             }
+            continue;
+        }
+
+        // Everything logged during this pass carries its run id
+        let run_span = tracing::info_span!("run", id = %newscope::request_id::new_id(), full_pass);
+        async {
+            info!("worker: checking for feeds to update");
+
+            // 1. Find feeds due for update (all active feeds on a scheduled full pass)
+            let feeds = sqlx::query(
+                "SELECT id, url, poll_interval_minutes, adaptive_scheduling, last_checked, etag, last_modified FROM feeds WHERE (? OR next_poll_at <= ? OR next_poll_at IS NULL) AND (status IS NULL OR status NOT IN (?, ?))"
+            )
+            .bind(full_pass)
+            .bind(now)
+            .bind(newscope::storage::FEED_STATUS_INACTIVE)
+            .bind(newscope::storage::FEED_STATUS_DISABLED)
+            .fetch_all(&*_db_pool)
+            .await;
+
+            match feeds {
+                Ok(rows) => {
+                    worker_status::update(&status, |s| s.feeds_due = rows.len());
+                    if rows.is_empty() {
+                        info!("worker: no feeds due for update");
+                    } else {
+                        info!("worker: found {} feeds to update", rows.len());
+                        let merge_by_hash = newscope::dedup::merge_by_content_hash(Some(&config));
+                        let mut sweep_dedup = newscope::dedup::DedupStats::default();
+                    
+                        let limiter = newscope::politeness::DomainLimiter::from_config(Some(&config));
+                        let max_concurrent = newscope::politeness::max_concurrent_fetches(Some(&config));
+                        let config_ref = &*config;
+                        let limiter_ref = &limiter;
+                        let mut polls = stream::iter(rows)
+                            .map(|row| {
+                                poll_feed(
+                                    &_db_pool,
+                                    config_ref,
+                                    limiter_ref,
+                                    row,
+                                    merge_by_hash,
+                                    summarization_llm.is_some(),
+                                    &status,
+                                )
+                            })
+                            .buffer_unordered(max_concurrent);
+                        while let Some(feed_dedup) = polls.next().await {
+                            sweep_dedup.add(&feed_dedup);
+                        }
+
+                        info!(
+                            "worker: dedup summary: {} new, {} merged by URL, {} merged by content hash, {} unmerged content-hash matches, {} already seen",
+                            sweep_dedup.new_articles,
+                            sweep_dedup.merged_by_url,
+                            sweep_dedup.merged_by_hash,
+                            sweep_dedup.hash_duplicates_unmerged,
+                            sweep_dedup.already_seen
+                        );
+                    }
+                }
+                Err(e) => error!("worker: failed to query feeds: {}", e),
+            }
+
+            // 4. Queue embedding jobs for articles without a vector
+            if embedding_llm.is_some() {
+                let summarized_only = summarization_llm.is_some();
+                match newscope::jobs::enqueue_missing_embeddings(&_db_pool, summarized_only, EMBEDDING_BACKFILL_LIMIT).await {
+                    Ok(0) => {}
+                    Ok(n) => info!("worker: queued {} articles for embedding", n),
+                    Err(e) => error!("worker: {:#}", e),
+                }
+            }
+
+            // 5. Initialize user vectors
+            if let Some(provider) = &embedding_llm {
+                let provider = provider.clone();
+                let pool = _db_pool.clone();
+                let model = embedding_model.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = newscope::personalize_worker::initialize_user_vectors(&pool, provider, &model).await {
+                        error!("Error initializing user vectors: {:?}", e);
+                    }
+                }.in_current_span());
+            }
+
+            // 6. Out-of-session notifications for high-relevance articles
+            if let Some(notify_cfg) = config.notifications.clone() {
+                let pool = _db_pool.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = newscope::notifications::notify_high_relevance(&pool, &notify_cfg).await {
+                        error!("Error sending notifications: {:?}", e);
+                    }
+                }.in_current_span());
+            }
+        }
+        .instrument(run_span)
+        .await;
+
+        if wait_for_next_tick(next_full_pass, &mut shutdown).await {
+            break;
         }
     }
 
+    if let Err(e) = runner_handle.await {
+        error!(%e, "job runner panicked");
+    }
     info!("worker: cleanup complete");
     Ok(())
 }
+
+/// Sleep for a minute, or until the next scheduled full pass if that comes first.
+/// Returns true when shutdown was requested.
+async fn wait_for_next_tick(next_full_pass: Option<chrono::DateTime<Utc>>, shutdown: &mut watch::Receiver<bool>) -> bool {
+    let mut sleep_for = Duration::from_secs(60);
+    if let Some(at) = next_full_pass {
+        let until = (at - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+        sleep_for = sleep_for.min(until);
+    }
+    // wait_for sees a flag set while the pass was running, and a dropped sender
+    // also ends the loop
+    let stop = select! {
+        _ = tokio::time::sleep(sleep_for) => false,
+        _ = shutdown.wait_for(|stop| *stop) => true,
+    };
+    if stop {
+        info!("worker: shutdown requested, exiting loop");
+    }
+    stop
+}
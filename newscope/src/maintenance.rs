@@ -0,0 +1,488 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::llm::LlmProvider;
+
+/// Result of an integrity pass over derived data (embeddings, summaries).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub orphan_vec_articles_removed: u64,
+    pub orphan_vec_users_removed: u64,
+    pub orphan_article_summaries_removed: u64,
+    pub orphan_user_article_summaries_removed: u64,
+    pub articles_missing_embeddings: i64,
+    pub reembedded: usize,
+}
+
+/// Options for re-embedding articles found without a vector.
+pub struct ReembedOptions {
+    pub provider: Arc<dyn LlmProvider>,
+    pub model: String,
+    pub limit: usize,
+    pub concurrency: usize,
+    pub dim: usize,
+    pub text: crate::processing::EmbeddingText,
+}
+
+/// "fsck" for derived data: drop rows that reference deleted articles/users,
+/// count articles without embeddings and optionally re-embed them.
+pub async fn check_integrity(pool: &SqlitePool, reembed: Option<ReembedOptions>) -> Result<IntegrityReport> {
+    let mut report = IntegrityReport::default();
+
+    if table_exists(pool, "vec_articles").await? {
+        report.orphan_vec_articles_removed = sqlx::query(
+            "DELETE FROM vec_articles WHERE article_id NOT IN (SELECT id FROM articles)",
+        )
+        .execute(pool)
+        .await
+        .context("failed to remove orphaned vec_articles rows")?
+        .rows_affected();
+
+        report.articles_missing_embeddings = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM articles a WHERE NOT EXISTS (SELECT 1 FROM vec_articles v WHERE v.article_id = a.id)",
+        )
+        .fetch_one(pool)
+        .await
+        .context("failed to count articles missing embeddings")?;
+    }
+
+    if table_exists(pool, "vec_users").await? {
+        report.orphan_vec_users_removed = sqlx::query(
+            "DELETE FROM vec_users WHERE user_id NOT IN (SELECT id FROM users)",
+        )
+        .execute(pool)
+        .await
+        .context("failed to remove orphaned vec_users rows")?
+        .rows_affected();
+    }
+
+    report.orphan_article_summaries_removed = sqlx::query(
+        "DELETE FROM article_summaries WHERE article_id NOT IN (SELECT id FROM articles)",
+    )
+    .execute(pool)
+    .await
+    .context("failed to remove orphaned article_summaries rows")?
+    .rows_affected();
+
+    if table_exists(pool, "user_article_summaries").await? {
+        report.orphan_user_article_summaries_removed = sqlx::query(
+            "DELETE FROM user_article_summaries
+             WHERE article_id NOT IN (SELECT id FROM articles)
+                OR user_id NOT IN (SELECT id FROM users)",
+        )
+        .execute(pool)
+        .await
+        .context("failed to remove orphaned user_article_summaries rows")?
+        .rows_affected();
+    }
+
+    if let Some(opts) = reembed {
+        if report.articles_missing_embeddings > 0 {
+            report.reembedded = crate::processing::process_missing_embeddings(
+                pool,
+                opts.provider,
+                &opts.model,
+                opts.limit,
+                opts.concurrency,
+                opts.dim,
+                &opts.text,
+            )
+            .await?;
+        }
+    }
+
+    info!("Integrity check complete: {:?}", report);
+    Ok(report)
+}
+
+/// How often the worker prunes articles past `admin.retention_days`.
+pub const PRUNE_INTERVAL_HOURS: i64 = 6;
+/// Articles deleted per statement while pruning.
+const PRUNE_BATCH_SIZE: usize = 500;
+
+/// Rows deleted by [`prune_articles`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PruneReport {
+    pub articles: u64,
+    pub article_occurrences: u64,
+    pub article_summaries: u64,
+    pub user_article_summaries: u64,
+    pub vec_articles: u64,
+}
+
+/// `admin.retention_days`; `None` (or 0) keeps articles forever.
+pub fn retention_days(config: Option<&common::Config>) -> Option<u32> {
+    config
+        .and_then(|c| c.admin.as_ref())
+        .and_then(|a| a.retention_days)
+        .filter(|days| *days > 0)
+}
+
+/// Delete articles first seen more than `retention_days` ago, with everything
+/// derived from them. Articles are kept while a user has a personalized
+/// summary of them they have not viewed yet, or while they appear in a
+/// session started within the retention period.
+pub async fn prune_articles(pool: &SqlitePool, retention_days: u32) -> Result<PruneReport> {
+    let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
+    let has_user_summaries = table_exists(pool, "user_article_summaries").await?;
+    let has_views = table_exists(pool, "user_article_views").await?;
+
+    let mut query = String::from("SELECT a.id FROM articles a WHERE a.first_seen_at < ?");
+    if has_user_summaries {
+        query.push_str(if has_views {
+            " AND NOT EXISTS (
+                SELECT 1 FROM user_article_summaries uas
+                WHERE uas.article_id = a.id
+                  AND NOT EXISTS (
+                      SELECT 1 FROM user_article_views v
+                      WHERE v.user_id = uas.user_id AND v.article_id = a.id
+                  )
+            )"
+        } else {
+            " AND NOT EXISTS (SELECT 1 FROM user_article_summaries uas WHERE uas.article_id = a.id)"
+        });
+    }
+    if has_views {
+        query.push_str(
+            " AND NOT EXISTS (
+                SELECT 1 FROM user_article_views v
+                JOIN sessions s ON s.id = v.session_id
+                WHERE v.article_id = a.id AND s.start_at >= ?
+            )",
+        );
+    }
+    let mut select = sqlx::query_scalar::<_, i64>(&query).bind(cutoff);
+    if has_views {
+        select = select.bind(cutoff);
+    }
+    let ids = select.fetch_all(pool).await.context("failed to select articles to prune")?;
+
+    let mut report = PruneReport::default();
+    if ids.is_empty() {
+        return Ok(report);
+    }
+    let has_vec_articles = table_exists(pool, "vec_articles").await?;
+    let has_fts = table_exists(pool, "articles_fts").await?;
+    let has_notifications = table_exists(pool, "user_notifications").await?;
+    let has_relevance_cache = table_exists(pool, "relevance_cache").await?;
+
+    for batch in ids.chunks(PRUNE_BATCH_SIZE) {
+        let batch = serde_json::to_string(batch)?;
+        let mut tx = pool.begin().await?;
+        report.article_occurrences += delete_batch(&mut tx, "article_occurrences", "article_id", &batch).await?;
+        report.article_summaries += delete_batch(&mut tx, "article_summaries", "article_id", &batch).await?;
+        if has_user_summaries {
+            report.user_article_summaries += delete_batch(&mut tx, "user_article_summaries", "article_id", &batch).await?;
+        }
+        if has_vec_articles {
+            report.vec_articles += delete_batch(&mut tx, "vec_articles", "article_id", &batch).await?;
+        }
+        // Not reported: search index, views, notification history and cached relevance
+        if has_fts {
+            delete_batch(&mut tx, "articles_fts", "rowid", &batch).await?;
+        }
+        if has_views {
+            delete_batch(&mut tx, "user_article_views", "article_id", &batch).await?;
+        }
+        if has_notifications {
+            delete_batch(&mut tx, "user_notifications", "article_id", &batch).await?;
+        }
+        if has_relevance_cache {
+            delete_batch(&mut tx, "relevance_cache", "article_id", &batch).await?;
+        }
+        report.articles += delete_batch(&mut tx, "articles", "id", &batch).await?;
+        tx.commit().await?;
+    }
+
+    info!("Pruned articles older than {} days: {:?}", retention_days, report);
+    Ok(report)
+}
+
+/// Delete the rows of `table` whose `column` is in `ids` (a JSON array).
+async fn delete_batch(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    table: &str,
+    column: &str,
+    ids: &str,
+) -> Result<u64> {
+    let sql = format!("DELETE FROM {} WHERE {} IN (SELECT value FROM json_each(?))", table, column);
+    Ok(sqlx::query(&sql)
+        .bind(ids)
+        .execute(&mut *tx)
+        .await
+        .with_context(|| format!("failed to prune {}", table))?
+        .rows_affected())
+}
+
+/// Articles shown per processing status in `ProcessingReport::samples`.
+const PROCESSING_SAMPLE_SIZE: i64 = 5;
+
+/// An article as listed in the processing backlog report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingSample {
+    pub article_id: i64,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub first_seen_at: Option<String>,
+}
+
+/// A failed summarization job, with the error the LLM call returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingFailure {
+    pub article_id: i64,
+    pub title: Option<String>,
+    pub error_message: Option<String>,
+    pub failed_at: Option<String>,
+}
+
+/// Snapshot of the LLM processing backlog.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessingReport {
+    /// Article counts by `processing_status` (NULL counted as "pending")
+    pub counts: BTreeMap<String, i64>,
+    /// Most recently seen articles per status
+    pub samples: BTreeMap<String, Vec<ProcessingSample>>,
+    /// Age of the oldest pending article, in seconds
+    pub oldest_pending_age_seconds: Option<i64>,
+    /// Latest failed summarization jobs
+    pub recent_failures: Vec<ProcessingFailure>,
+}
+
+/// Summarize how far LLM processing lags behind ingestion.
+pub async fn processing_report(pool: &SqlitePool) -> Result<ProcessingReport> {
+    let mut report = ProcessingReport::default();
+
+    let rows = sqlx::query(
+        "SELECT COALESCE(processing_status, 'pending') as status, COUNT(*) as count
+         FROM articles GROUP BY 1",
+    )
+    .fetch_all(pool)
+    .await
+    .context("failed to count articles by processing status")?;
+    for row in rows {
+        report.counts.insert(row.get("status"), row.get("count"));
+    }
+
+    for status in report.counts.keys() {
+        let samples = sqlx::query(
+            "SELECT id, title, canonical_url, first_seen_at FROM articles
+             WHERE COALESCE(processing_status, 'pending') = ?
+             ORDER BY first_seen_at DESC, id DESC
+             LIMIT ?",
+        )
+        .bind(status)
+        .bind(PROCESSING_SAMPLE_SIZE)
+        .fetch_all(pool)
+        .await
+        .context("failed to sample articles by processing status")?
+        .into_iter()
+        .map(|r| ProcessingSample {
+            article_id: r.get("id"),
+            title: r.get("title"),
+            url: r.get("canonical_url"),
+            first_seen_at: r.get("first_seen_at"),
+        })
+        .collect();
+        report.samples.insert(status.clone(), samples);
+    }
+
+    let oldest_pending: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT MIN(first_seen_at) FROM articles WHERE COALESCE(processing_status, 'pending') = 'pending'",
+    )
+    .fetch_one(pool)
+    .await
+    .context("failed to find oldest pending article")?;
+    report.oldest_pending_age_seconds = oldest_pending.map(|t| (Utc::now() - t).num_seconds());
+
+    if table_exists(pool, "processing_jobs").await? {
+        report.recent_failures = sqlx::query(
+            "SELECT j.entity_id, a.title, j.error_message, j.completed_at
+             FROM processing_jobs j
+             LEFT JOIN articles a ON a.id = j.entity_id
+             WHERE j.job_type = 'article_summary' AND j.status = 'failed'
+             ORDER BY j.id DESC
+             LIMIT ?",
+        )
+        .bind(PROCESSING_SAMPLE_SIZE * 2)
+        .fetch_all(pool)
+        .await
+        .context("failed to load failed processing jobs")?
+        .into_iter()
+        .map(|r| ProcessingFailure {
+            article_id: r.get("entity_id"),
+            title: r.get("title"),
+            error_message: r.get("error_message"),
+            failed_at: r.get("completed_at"),
+        })
+        .collect();
+    }
+
+    Ok(report)
+}
+
+/// Instance-wide counters for a status page.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstanceStats {
+    pub users: i64,
+    pub feeds: i64,
+    pub subscriptions: i64,
+    pub articles: i64,
+    pub articles_with_summaries: i64,
+    /// None when the vector tables are not available
+    pub embeddings: Option<i64>,
+    /// None when the processing job queue does not exist
+    pub pending_jobs: Option<i64>,
+    /// Most recent feed poll by the worker
+    pub last_worker_run: Option<String>,
+    pub llm_prompt_tokens_24h: i64,
+    pub llm_completion_tokens_24h: i64,
+}
+
+/// Gather `InstanceStats` with one COUNT per table.
+pub async fn instance_stats(pool: &SqlitePool) -> Result<InstanceStats> {
+    let row = sqlx::query(
+        "SELECT (SELECT COUNT(*) FROM users) AS users,
+                (SELECT COUNT(*) FROM feeds) AS feeds,
+                (SELECT COUNT(*) FROM subscriptions) AS subscriptions,
+                (SELECT COUNT(*) FROM articles) AS articles,
+                (SELECT COUNT(*) FROM article_summaries) AS articles_with_summaries,
+                (SELECT MAX(last_checked) FROM feeds) AS last_worker_run",
+    )
+    .fetch_one(pool)
+    .await
+    .context("failed to count instance rows")?;
+
+    let usage = sqlx::query(
+        "SELECT COALESCE(SUM(prompt_tokens), 0) AS prompt, COALESCE(SUM(completion_tokens), 0) AS completion
+         FROM llm_usage_log
+         WHERE created_at >= strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-24 hours')",
+    )
+    .fetch_one(pool)
+    .await
+    .context("failed to sum LLM usage")?;
+
+    let mut stats = InstanceStats {
+        users: row.get("users"),
+        feeds: row.get("feeds"),
+        subscriptions: row.get("subscriptions"),
+        articles: row.get("articles"),
+        articles_with_summaries: row.get("articles_with_summaries"),
+        last_worker_run: row.get("last_worker_run"),
+        llm_prompt_tokens_24h: usage.get("prompt"),
+        llm_completion_tokens_24h: usage.get("completion"),
+        ..InstanceStats::default()
+    };
+
+    if table_exists(pool, "vec_articles").await? {
+        stats.embeddings = Some(
+            sqlx::query_scalar("SELECT COUNT(*) FROM vec_articles")
+                .fetch_one(pool)
+                .await
+                .context("failed to count embeddings")?,
+        );
+    }
+    if table_exists(pool, "processing_jobs").await? {
+        stats.pending_jobs = Some(
+            sqlx::query_scalar("SELECT COUNT(*) FROM processing_jobs WHERE status = 'pending'")
+                .fetch_one(pool)
+                .await
+                .context("failed to count pending jobs")?,
+        );
+    }
+
+    Ok(stats)
+}
+
+/// Size declared for the `embedding` column of a vec0 table (`FLOAT[384]`),
+/// `None` when the table is missing or isn't a vec0 table.
+pub async fn vector_table_dim(pool: &SqlitePool, table: &str) -> Result<Option<usize>> {
+    let sql: Option<String> = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE name = ?")
+        .bind(table)
+        .fetch_optional(pool)
+        .await
+        .context("failed to inspect sqlite_master")?
+        .flatten();
+    Ok(sql.and_then(|sql| {
+        let start = sql.to_ascii_uppercase().find("FLOAT[")? + "FLOAT[".len();
+        let end = start + sql[start..].find(']')?;
+        sql[start..end].trim().parse().ok()
+    }))
+}
+
+/// Drop every article vector so they are recomputed with the current
+/// embedding model; `vec_articles` is recreated when it was declared with
+/// another size than `dim`. User vectors are left alone, see
+/// [`resize_user_vectors`]. Returns the number of article vectors dropped.
+pub async fn reset_embeddings(pool: &SqlitePool, dim: usize) -> Result<u64> {
+    if !table_exists(pool, "vec_articles").await? {
+        return Ok(0);
+    }
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vec_articles")
+        .fetch_one(pool)
+        .await
+        .context("failed to count vec_articles rows")?;
+    if !recreate_vector_table(pool, "vec_articles", "article_id", dim).await? {
+        sqlx::query("DELETE FROM vec_articles")
+            .execute(pool)
+            .await
+            .context("failed to clear vec_articles")?;
+    }
+    sqlx::query("DELETE FROM article_vector_models")
+        .execute(pool)
+        .await
+        .context("failed to clear embedding model records")?;
+    Ok(rows as u64)
+}
+
+/// Recreate `vec_users` when it was declared with another size than `dim`:
+/// its vectors can't be compared with articles embedded at `dim` any more.
+/// Returns the number of user vectors dropped, 0 when the size matched.
+pub async fn resize_user_vectors(pool: &SqlitePool, dim: usize) -> Result<u64> {
+    if !table_exists(pool, "vec_users").await? {
+        return Ok(0);
+    }
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vec_users")
+        .fetch_one(pool)
+        .await
+        .context("failed to count vec_users rows")?;
+    Ok(if recreate_vector_table(pool, "vec_users", "user_id", dim).await? {
+        rows as u64
+    } else {
+        0
+    })
+}
+
+/// Recreate the vec0 `table` (keyed by `key`) with `dim` dimensions if it was
+/// declared with another size. Returns whether it was recreated.
+async fn recreate_vector_table(pool: &SqlitePool, table: &str, key: &str, dim: usize) -> Result<bool> {
+    let declared = match vector_table_dim(pool, table).await? {
+        Some(declared) if declared != dim => declared,
+        _ => return Ok(false),
+    };
+    let mut tx = pool.begin().await?;
+    sqlx::query(&format!("DROP TABLE {}", table)).execute(&mut tx).await?;
+    sqlx::query(&format!(
+        "CREATE VIRTUAL TABLE {} USING vec0({} INTEGER PRIMARY KEY, embedding FLOAT[{}])",
+        table, key, dim
+    ))
+    .execute(&mut tx)
+    .await
+    .with_context(|| format!("failed to recreate {} with {} dimensions", table, dim))?;
+    tx.commit().await?;
+    info!("Recreated {} with {} dimensions (was {})", table, dim, declared);
+    Ok(true)
+}
+
+pub(crate) async fn table_exists(pool: &SqlitePool, name: &str) -> Result<bool> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE name = ?")
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .context("failed to inspect sqlite_master")?;
+    Ok(count > 0)
+}
@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use common::NotificationsConfig;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::i18n::{self, Msg};
+
+/// Structured notification, sent over the chat websocket or POSTed to the
+/// delivery webhook.
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationEvent {
+    /// "review_ready" or "high_relevance_article"
+    pub event: &'static str,
+    pub user_id: i64,
+    pub lang: String,
+    pub title: String,
+    pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub article_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relevance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl NotificationEvent {
+    /// "Your press review is ready" in the user's language.
+    pub fn review_ready(user_id: i64, lang: &str) -> Self {
+        Self {
+            event: "review_ready",
+            user_id,
+            lang: lang.to_string(),
+            title: "Newscope".to_string(),
+            body: i18n::t(lang, Msg::ReviewReady).to_string(),
+            article_id: None,
+            relevance: None,
+            url: None,
+        }
+    }
+}
+
+/// POST a notification as JSON to the configured webhook.
+pub async fn deliver_webhook(url: &str, event: &NotificationEvent) -> Result<()> {
+    let resp = crate::http::client()
+        .post(url)
+        .timeout(Duration::from_secs(10))
+        .json(event)
+        .send()
+        .await
+        .context("webhook request failed")?;
+    if !resp.status().is_success() {
+        anyhow::bail!("webhook returned {}", resp.status());
+    }
+    Ok(())
+}
+
+/// Notify active users, outside of a running session, about articles whose
+/// personalized relevance is at or above `min_relevance_to_notify`.
+///
+/// A user is active if they started a session in the last `active_within_days`
+/// days; they are "in session" while a session's requested duration hasn't elapsed.
+/// Each (user, article) pair is notified at most once.
+pub async fn notify_high_relevance(pool: &SqlitePool, cfg: &NotificationsConfig) -> Result<usize> {
+    let (Some(webhook_url), Some(threshold)) = (cfg.webhook_url.as_deref(), cfg.min_relevance_to_notify) else {
+        return Ok(0);
+    };
+    let active_days = cfg.active_within_days.unwrap_or(7);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT uas.user_id, uas.article_id, uas.relevance_score, uas.personalized_headline,
+               uas.language, a.canonical_url
+        FROM user_article_summaries uas
+        JOIN articles a ON a.id = uas.article_id
+        LEFT JOIN user_notifications un ON un.user_id = uas.user_id AND un.article_id = uas.article_id
+        LEFT JOIN user_article_views uav ON uav.user_id = uas.user_id AND uav.article_id = uas.article_id
+        WHERE uas.relevance_score >= ?
+          AND un.id IS NULL
+          AND uav.id IS NULL
+          AND EXISTS (
+              SELECT 1 FROM sessions s
+              WHERE s.user_id = uas.user_id
+                AND s.start_at >= strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?)
+          )
+          AND NOT EXISTS (
+              SELECT 1 FROM sessions s
+              WHERE s.user_id = uas.user_id
+                AND datetime(s.start_at, '+' || COALESCE(s.duration_requested_seconds, 0) || ' seconds') > datetime('now')
+          )
+        ORDER BY uas.relevance_score DESC, uas.article_id ASC
+        LIMIT 50
+        "#,
+    )
+    .bind(threshold)
+    .bind(format!("-{} days", active_days))
+    .fetch_all(pool)
+    .await
+    .context("failed to query high-relevance articles")?;
+
+    let mut sent = 0;
+    for row in rows {
+        let user_id: i64 = row.get("user_id");
+        let article_id: i64 = row.get("article_id");
+        let lang: String = row.get("language");
+        let event = NotificationEvent {
+            event: "high_relevance_article",
+            user_id,
+            title: i18n::t(&lang, Msg::HighRelevanceArticle).to_string(),
+            body: row.get("personalized_headline"),
+            lang,
+            article_id: Some(article_id),
+            relevance: Some(row.get("relevance_score")),
+            url: Some(row.get("canonical_url")),
+        };
+
+        match deliver_webhook(webhook_url, &event).await {
+            Ok(()) => {
+                sqlx::query("INSERT OR IGNORE INTO user_notifications (user_id, article_id, event) VALUES (?, ?, ?)")
+                    .bind(user_id)
+                    .bind(article_id)
+                    .bind(event.event)
+                    .execute(pool)
+                    .await
+                    .context("failed to record notification")?;
+                sent += 1;
+            }
+            Err(e) => warn!("Failed to deliver notification for user {} article {}: {}", user_id, article_id, e),
+        }
+    }
+
+    if sent > 0 {
+        info!("Sent {} high-relevance notifications", sent);
+    }
+    Ok(sent)
+}
@@ -0,0 +1,235 @@
+//! OPML feed lists: parsing, writing and seeding the shared feed catalog.
+
+use anyhow::{Context, Result};
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use sqlx::SqlitePool;
+use std::path::Path;
+use tracing::info;
+
+/// A feed listed in an OPML document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpmlFeed {
+    pub url: String,
+    pub title: Option<String>,
+    /// The feed's website (`htmlUrl`)
+    pub html_url: Option<String>,
+    /// Enclosing folder outlines, outermost first, joined with `/`
+    pub category: Option<String>,
+}
+
+/// Separator between nested folder names in [`OpmlFeed::category`].
+pub const CATEGORY_SEPARATOR: &str = "/";
+
+/// Extract every `<outline xmlUrl="...">` from an OPML document, nested
+/// outlines (categories) included.
+pub fn parse_opml(content: &[u8]) -> Result<Vec<OpmlFeed>> {
+    let mut reader = Reader::from_reader(content);
+    reader.trim_text(true);
+
+    let mut feeds = Vec::new();
+    // One entry per open <outline>: its folder name, None for a feed
+    let mut folders: Vec<Option<String>> = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        let (e, has_children) = match reader.read_event_into(&mut buf).context("invalid OPML")? {
+            Event::Empty(e) if e.name().as_ref() == b"outline" => (e, false),
+            Event::Start(e) if e.name().as_ref() == b"outline" => (e, true),
+            Event::End(e) if e.name().as_ref() == b"outline" => {
+                folders.pop();
+                buf.clear();
+                continue;
+            }
+            Event::Eof => break,
+            _ => {
+                buf.clear();
+                continue;
+            }
+        };
+
+        let mut url = None;
+        let mut title = None;
+        let mut html_url = None;
+        for attr in e.attributes().flatten() {
+            let value = attr.unescape_value().ok().map(|v| v.trim().to_string());
+            match attr.key.as_ref() {
+                b"xmlUrl" => url = value,
+                b"htmlUrl" => html_url = value,
+                b"title" | b"text" if title.is_none() => title = value,
+                _ => {}
+            }
+        }
+        let title = title.filter(|t| !t.is_empty());
+        let url = url.filter(|u| !u.is_empty());
+        if let Some(url) = &url {
+            let path: Vec<&str> = folders.iter().flatten().map(String::as_str).collect();
+            feeds.push(OpmlFeed {
+                url: url.clone(),
+                title: title.clone(),
+                html_url: html_url.filter(|u| !u.is_empty()),
+                category: (!path.is_empty()).then(|| path.join(CATEGORY_SEPARATOR)),
+            });
+        }
+        if has_children {
+            folders.push(if url.is_none() { title } else { None });
+        }
+        buf.clear();
+    }
+    Ok(feeds)
+}
+
+/// Render `feeds` as an OPML 2.0 document. Feeds are grouped into nested
+/// folder outlines by `category`, so [`parse_opml`] reads the same list back.
+pub fn write_opml(title: &str, feeds: &[OpmlFeed]) -> String {
+    let path_of = |feed: &OpmlFeed| -> Vec<String> {
+        feed.category
+            .as_deref()
+            .map(|c| c.split(CATEGORY_SEPARATOR).map(str::trim).filter(|p| !p.is_empty()).map(String::from).collect())
+            .unwrap_or_default()
+    };
+    // Stable: feeds keep their order within a folder; subfolders follow their parent
+    let mut sorted: Vec<(Vec<String>, &OpmlFeed)> = feeds.iter().map(|f| (path_of(f), f)).collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n");
+    out.push_str(&format!(
+        "  <head>\n    <title>{}</title>\n    <dateCreated>{}</dateCreated>\n  </head>\n  <body>\n",
+        escape(title),
+        chrono::Utc::now().to_rfc2822()
+    ));
+    let indent = |depth: usize| "  ".repeat(depth + 2);
+    let mut open: Vec<String> = Vec::new();
+    for (path, feed) in sorted {
+        let shared = open.iter().zip(&path).take_while(|(a, b)| a == b).count();
+        while open.len() > shared {
+            open.pop();
+            out.push_str(&format!("{}</outline>\n", indent(open.len())));
+        }
+        for folder in &path[shared..] {
+            out.push_str(&format!(
+                "{}<outline text=\"{}\" title=\"{}\">\n",
+                indent(open.len()),
+                escape(folder),
+                escape(folder)
+            ));
+            open.push(folder.clone());
+        }
+        let name = feed.title.as_deref().unwrap_or(&feed.url);
+        out.push_str(&format!(
+            "{}<outline type=\"rss\" text=\"{}\" title=\"{}\" xmlUrl=\"{}\"",
+            indent(open.len()),
+            escape(name),
+            escape(name),
+            escape(&feed.url)
+        ));
+        if let Some(html_url) = &feed.html_url {
+            out.push_str(&format!(" htmlUrl=\"{}\"", escape(html_url)));
+        }
+        out.push_str("/>\n");
+    }
+    while !open.is_empty() {
+        open.pop();
+        out.push_str(&format!("{}</outline>\n", indent(open.len())));
+    }
+    out.push_str("  </body>\n</opml>\n");
+    out
+}
+
+/// Ensure every feed of the OPML file at `path` exists in the catalog (without
+/// subscribing anyone). Feeds already known are left untouched. Returns the
+/// number of feeds created.
+pub async fn bootstrap_feeds(pool: &SqlitePool, path: &Path) -> Result<usize> {
+    let content = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let feeds = parse_opml(&content).with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let mut created = 0;
+    for feed in &feeds {
+        let res = sqlx::query("INSERT OR IGNORE INTO feeds (url, title, site_url, next_poll_at) VALUES (?, ?, ?, NULL)")
+            .bind(&feed.url)
+            .bind(feed.title.as_deref())
+            .bind(feed.html_url.as_deref())
+            .execute(pool)
+            .await
+            .with_context(|| format!("failed to create feed {}", feed.url))?;
+        created += res.rows_affected() as usize;
+    }
+    info!(
+        "Bootstrap OPML {}: {} feeds listed, {} added to the catalog",
+        path.display(),
+        feeds.len(),
+        created
+    );
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_opml_nested_outlines() {
+        let opml = br#"<?xml version="1.0"?>
+<opml version="2.0"><head><title>Family</title></head><body>
+  <outline text="Tech">
+    <outline text="Ars &amp; Co" xmlUrl="https://ars.example/rss" htmlUrl="https://ars.example/"/>
+    <outline text="Linux">
+      <outline title="LWN" text="ignored" xmlUrl="https://lwn.example/rss"></outline>
+    </outline>
+  </outline>
+  <outline text="No feed here" htmlUrl="https://site.example"/>
+  <outline text="Top level" xmlUrl="https://top.example/rss"/>
+</body></opml>"#;
+
+        let feeds = parse_opml(opml).unwrap();
+        assert_eq!(
+            feeds,
+            vec![
+                OpmlFeed {
+                    url: "https://ars.example/rss".into(),
+                    title: Some("Ars & Co".into()),
+                    html_url: Some("https://ars.example/".into()),
+                    category: Some("Tech".into()),
+                },
+                OpmlFeed {
+                    url: "https://lwn.example/rss".into(),
+                    title: Some("LWN".into()),
+                    html_url: None,
+                    category: Some("Tech/Linux".into()),
+                },
+                OpmlFeed {
+                    url: "https://top.example/rss".into(),
+                    title: Some("Top level".into()),
+                    html_url: None,
+                    category: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_opml_round_trips() {
+        let feed = |url: &str, title: Option<&str>, category: Option<&str>| OpmlFeed {
+            url: url.into(),
+            title: title.map(String::from),
+            html_url: Some(format!("{}/", url.trim_end_matches("/rss"))),
+            category: category.map(String::from),
+        };
+        let feeds = vec![
+            feed("https://lwn.example/rss", Some("LWN"), Some("Tech/Linux")),
+            feed("https://top.example/rss?a=1&b=2", Some("\"Top\" <news>"), None),
+            feed("https://ars.example/rss", None, Some("Tech")),
+            feed("https://other.example/rss", Some("Other"), Some("World")),
+        ];
+
+        let xml = write_opml("Alice's feeds", &feeds);
+        assert!(xml.contains("xmlUrl=\"https://top.example/rss?a=1&amp;b=2\""));
+        let parsed = parse_opml(xml.as_bytes()).unwrap();
+        // Untitled feeds are written with their URL as text
+        let mut expected = vec![feeds[1].clone(), feeds[2].clone(), feeds[0].clone(), feeds[3].clone()];
+        expected[1].title = Some("https://ars.example/rss".into());
+        assert_eq!(parsed, expected);
+    }
+}
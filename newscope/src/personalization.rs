@@ -9,14 +9,43 @@ pub struct UserProfile {
     pub reading_speed: i32, // Words per minute
     pub interests: Vec<String>,
     pub preferred_categories: Vec<String>,
+    /// Category weights: the user's own `category_filter` ones, else those
+    /// learned from ratings (`category_learned`)
+    pub category_weights: std::collections::HashMap<String, f32>,
     pub keyword_boosts: std::collections::HashMap<String, f32>,
 }
 
+/// Category weight that neither boosts nor penalizes relevance, on the
+/// `user_preferences` scale: -1.0 blocks, 0.0 is neutral, 1.0 boosts.
+/// Categories weighted above it are the user's preferred ones.
+pub const NEUTRAL_CATEGORY_WEIGHT: f32 = 0.0;
+
+/// Relevance `score` adjusted for the article's `categories`: the mean weight
+/// `w` of those the user has a weight for scales it by `1 + w / 2`, so 1.0
+/// boosts it by half and -1.0 halves it. Clamped to 0.0-1.0.
+pub fn apply_category_weights(
+    score: f32,
+    categories: &[String],
+    weights: &std::collections::HashMap<String, f32>,
+) -> f32 {
+    let weighted: Vec<f32> = categories.iter().filter_map(|c| weights.get(c).copied()).collect();
+    if weighted.is_empty() {
+        return score;
+    }
+    let mean = weighted.iter().sum::<f32>() / weighted.len() as f32;
+    (score * (1.0 + (mean - NEUTRAL_CATEGORY_WEIGHT) / 2.0)).clamp(0.0, 1.0)
+}
+
 /// Relevance evaluation result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelevanceEvaluation {
     pub score: f32,  // 0.0 to 1.0
     pub reasons: Vec<String>,
+    #[serde(skip)]
+    pub usage: crate::llm::UsageMetadata,
+    /// Served from the [`crate::relevance_cache`] rather than the LLM
+    #[serde(skip)]
+    pub cached: bool,
 }
 
 /// Personalized summary for an article
@@ -69,12 +98,24 @@ use anyhow::{Context, Result};
 use sqlx::{SqlitePool, Row};
 use crate::llm::{LlmProvider, LlmRequest};
 
-/// Evaluate article relevance for a specific user
+/// Evaluate article relevance for a specific user. With a `cache`, a previous
+/// evaluation for the same profile version is reused when one matches, and
+/// new evaluations are added to it.
 pub async fn evaluate_article_relevance(
     llm: &dyn LlmProvider,
     summary: &crate::llm::Summary,
     user: &UserProfile,
+    cache: Option<&crate::relevance_cache::RelevanceCache>,
 ) -> Result<RelevanceEvaluation> {
+    let profile_hash = crate::relevance_cache::profile_hash(user);
+    if let Some(cache) = cache {
+        match cache.lookup(&profile_hash).await {
+            Ok(Some(cached)) => return Ok(cached),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Relevance cache lookup failed for user {}: {:#}", user.id, e),
+        }
+    }
+
     let interests_str = if user.interests.is_empty() {
         "general news".to_string()
     } else {
@@ -115,13 +156,22 @@ Return ONLY valid JSON: {{\"score\": 0.8, \"reasons\": [\"matches interest in AI
     // Parse JSON response with robustness
     let cleaned_content = crate::llm::extract_json_from_text(&response.content).unwrap_or(response.content.clone());
     match serde_json::from_str::<RelevanceEvaluation>(&cleaned_content) {
-        Ok(eval) => Ok(eval),
+        Ok(eval) => {
+            if let Some(cache) = cache {
+                if let Err(e) = cache.store(&profile_hash, &eval).await {
+                    tracing::warn!("Failed to cache relevance for user {}: {:#}", user.id, e);
+                }
+            }
+            Ok(RelevanceEvaluation { usage: response.usage, ..eval })
+        }
         Err(_) => {
             // Fallback: default moderate relevance if parsing fails
             tracing::warn!("Failed to parse relevance JSON, using default: {}", response.content);
             Ok(RelevanceEvaluation {
                 score: 0.5,
                 reasons: vec!["Unable to evaluate".to_string()],
+                usage: response.usage,
+                cached: false,
             })
         }
     }
@@ -253,7 +303,8 @@ pub async fn get_user_profile(pool: &SqlitePool, user_id: i64) -> Result<UserPro
     .fetch_all(pool)
     .await?;
 
-    let mut preferred_categories = Vec::new();
+    let mut category_weights = std::collections::HashMap::new();
+    let mut learned_weights = std::collections::HashMap::new();
     let mut keyword_boosts = std::collections::HashMap::new();
 
     for pref in prefs {
@@ -262,11 +313,22 @@ pub async fn get_user_profile(pool: &SqlitePool, user_id: i64) -> Result<UserPro
         let p_val: f32 = pref.get("preference_value");
 
         match p_type.as_str() {
-            "category_filter" if p_val > 0.0 => preferred_categories.push(p_key),
+            "category_filter" => { category_weights.insert(p_key, p_val); },
+            "category_learned" => { learned_weights.insert(p_key, p_val); },
             "keyword_boost" => { keyword_boosts.insert(p_key, p_val); },
             _ => {}
         }
     }
+    // What the user set explicitly wins over what ratings suggest
+    for (category, weight) in learned_weights {
+        category_weights.entry(category).or_insert(weight);
+    }
+    let mut preferred_categories: Vec<String> = category_weights
+        .iter()
+        .filter(|(_, &w)| w > NEUTRAL_CATEGORY_WEIGHT)
+        .map(|(c, _)| c.clone())
+        .collect();
+    preferred_categories.sort();
 
     Ok(UserProfile {
         id,
@@ -275,10 +337,180 @@ pub async fn get_user_profile(pool: &SqlitePool, user_id: i64) -> Result<UserPro
         reading_speed,
         interests,
         preferred_categories,
+        category_weights,
         keyword_boosts,
     })
 }
 
+/// Accepted `complexity_level` values.
+pub const COMPLEXITY_LEVELS: &[&str] = &["simple", "medium", "advanced"];
+/// Accepted `reading_speed` range, in words per minute.
+pub const READING_SPEED_RANGE: std::ops::RangeInclusive<i32> = 50..=1000;
+const MAX_INTERESTS: usize = 50;
+const MAX_TOPIC_CHARS: usize = 100;
+
+/// Personalization inputs a user can edit: the `user_profiles` settings and
+/// their `category_filter` weights from `user_preferences`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserPreferences {
+    pub language: String,
+    pub complexity_level: String,
+    pub reading_speed: i32,
+    pub interests: Vec<String>,
+    /// Categories weighted above [`NEUTRAL_CATEGORY_WEIGHT`], as used for relevance prompts
+    pub preferred_categories: Vec<String>,
+    /// Weight per category set by the user (-1.0 block, 0.0 neutral, 1.0
+    /// boost); weights learned from ratings apply to the other categories
+    pub category_filter: std::collections::BTreeMap<String, f32>,
+}
+
+/// Partial update of [`UserPreferences`]; omitted fields are kept.
+/// `preferred_categories` and `category_filter` each replace the stored
+/// weights; listed categories get weight 1.0 unless `category_filter` says otherwise.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UserPreferencesUpdate {
+    pub language: Option<String>,
+    pub complexity_level: Option<String>,
+    pub reading_speed: Option<i32>,
+    pub interests: Option<Vec<String>>,
+    pub preferred_categories: Option<Vec<String>>,
+    pub category_filter: Option<std::collections::BTreeMap<String, f32>>,
+}
+
+impl UserPreferencesUpdate {
+    /// Check ranges and formats; the error says which field is wrong.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if let Some(language) = &self.language {
+            let valid = (2..=10).contains(&language.len())
+                && language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if !valid {
+                return Err(format!("invalid language {:?}", language));
+            }
+        }
+        if let Some(level) = &self.complexity_level {
+            if !COMPLEXITY_LEVELS.contains(&level.as_str()) {
+                return Err(format!("complexity_level must be one of {}", COMPLEXITY_LEVELS.join(", ")));
+            }
+        }
+        if let Some(speed) = self.reading_speed {
+            if !READING_SPEED_RANGE.contains(&speed) {
+                return Err(format!(
+                    "reading_speed must be between {} and {}",
+                    READING_SPEED_RANGE.start(),
+                    READING_SPEED_RANGE.end()
+                ));
+            }
+        }
+        if let Some(interests) = &self.interests {
+            if interests.len() > MAX_INTERESTS {
+                return Err(format!("at most {} interests", MAX_INTERESTS));
+            }
+        }
+        let topics = self.interests.iter().flatten()
+            .chain(self.preferred_categories.iter().flatten())
+            .chain(self.category_filter.iter().flat_map(|m| m.keys()));
+        for topic in topics {
+            if topic.trim().is_empty() || topic.chars().count() > MAX_TOPIC_CHARS {
+                return Err(format!("interests and categories must be 1-{} characters", MAX_TOPIC_CHARS));
+            }
+        }
+        if let Some(weights) = &self.category_filter {
+            if weights.values().any(|w| !(-1.0..=1.0).contains(w)) {
+                return Err("category_filter weights must be between -1.0 and 1.0".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read a user's editable preferences (defaults when no profile row exists).
+pub async fn get_user_preferences(pool: &SqlitePool, user_id: i64) -> Result<UserPreferences> {
+    let profile = get_user_profile(pool, user_id).await?;
+    let weights: Vec<(String, f64)> = sqlx::query_as(
+        "SELECT preference_key, preference_value FROM user_preferences
+         WHERE user_id = ? AND preference_type = 'category_filter'",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch category weights")?;
+
+    Ok(UserPreferences {
+        language: profile.language,
+        complexity_level: profile.complexity_level,
+        reading_speed: profile.reading_speed,
+        interests: profile.interests,
+        preferred_categories: profile.preferred_categories,
+        category_filter: weights.into_iter().map(|(k, v)| (k, v as f32)).collect(),
+    })
+}
+
+/// Apply a validated [`UserPreferencesUpdate`] and return the stored result.
+pub async fn update_user_preferences(
+    pool: &SqlitePool,
+    user_id: i64,
+    update: &UserPreferencesUpdate,
+) -> Result<UserPreferences> {
+    let current = get_user_preferences(pool, user_id).await?;
+    let interests = update.interests.as_ref().map(|list| {
+        let mut seen = std::collections::HashSet::new();
+        list.iter()
+            .map(|i| i.trim().to_string())
+            .filter(|i| seen.insert(i.to_lowercase()))
+            .collect::<Vec<_>>()
+    });
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "INSERT INTO user_profiles (user_id, language, complexity_level, reading_speed, interests, updated_at)
+         VALUES (?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+         ON CONFLICT(user_id) DO UPDATE SET
+             language = excluded.language,
+             complexity_level = excluded.complexity_level,
+             reading_speed = excluded.reading_speed,
+             interests = excluded.interests,
+             updated_at = excluded.updated_at",
+    )
+    .bind(user_id)
+    .bind(update.language.as_ref().unwrap_or(&current.language))
+    .bind(update.complexity_level.as_ref().unwrap_or(&current.complexity_level))
+    .bind(update.reading_speed.unwrap_or(current.reading_speed))
+    .bind(serde_json::to_string(interests.as_ref().unwrap_or(&current.interests))?)
+    .execute(&mut tx)
+    .await
+    .context("Failed to store user profile")?;
+
+    if update.preferred_categories.is_some() || update.category_filter.is_some() {
+        let mut weights = std::collections::BTreeMap::new();
+        for category in update.preferred_categories.iter().flatten() {
+            weights.insert(category.trim().to_string(), 1.0);
+        }
+        for (category, weight) in update.category_filter.iter().flatten() {
+            weights.insert(category.trim().to_string(), *weight);
+        }
+
+        sqlx::query("DELETE FROM user_preferences WHERE user_id = ? AND preference_type = 'category_filter'")
+            .bind(user_id)
+            .execute(&mut tx)
+            .await?;
+        for (category, weight) in &weights {
+            sqlx::query(
+                "INSERT INTO user_preferences (user_id, preference_type, preference_key, preference_value)
+                 VALUES (?, 'category_filter', ?, ?)",
+            )
+            .bind(user_id)
+            .bind(category)
+            .bind(*weight as f64)
+            .execute(&mut tx)
+            .await
+            .context("Failed to store category weights")?;
+        }
+    }
+    tx.commit().await?;
+
+    get_user_preferences(pool, user_id).await
+}
+
 /// Fetch user interest vector from vec_users table
 pub async fn get_user_vector(pool: &SqlitePool, user_id: i64) -> Result<Option<Vec<f32>>> {
     let row = sqlx::query(
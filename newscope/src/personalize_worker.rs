@@ -1,26 +1,52 @@
 use anyhow::{Context, Result};
+use rocket::futures::{stream, StreamExt};
 use sqlx::{Row, SqlitePool};
 use std::sync::Arc;
 use tracing::{info, warn};
 
+use crate::llm::usage::log_llm_result;
 use crate::llm::{LlmProvider, Summary};
 use crate::personalization::{
-    evaluate_article_relevance, generate_personalized_summary, get_user_profile,
+    apply_category_weights, evaluate_article_relevance, generate_personalized_summary, get_user_profile,
+    UserProfile, NEUTRAL_CATEGORY_WEIGHT,
 };
+use crate::relevance_cache::RelevanceCache;
 
-/// Personalize article for all active users after generic summary generated
+/// Default `processing.personalization_concurrency`.
+pub const DEFAULT_PERSONALIZATION_CONCURRENCY: usize = 4;
+
+/// Relevance under which an article is skipped for a user, without asking for
+/// a personalized summary.
+pub const RELEVANCE_THRESHOLD: f32 = 0.3;
+
+/// Users personalized at once for an article
+/// (`processing.personalization_concurrency`).
+pub fn personalization_concurrency(config: Option<&common::Config>) -> usize {
+    config
+        .and_then(|c| c.processing.as_ref())
+        .and_then(|p| p.personalization_concurrency)
+        .unwrap_or(DEFAULT_PERSONALIZATION_CONCURRENCY)
+        .max(1)
+}
+
+/// Personalize article for all active users after generic summary generated,
+/// `concurrency` users at a time, reusing relevance scores from `cache`. A
+/// failure for one user is logged and doesn't stop the others; returns how
+/// many users got a personalized summary, or an error when every user failed.
 pub async fn personalize_for_users(
     pool: &SqlitePool,
     article_id: i64,
     generic_summary: &Summary,
     llm_provider: Arc<dyn LlmProvider>,
     model: &str,
+    concurrency: usize,
+    cache: Option<&RelevanceCache>,
 ) -> Result<usize> {
     // Get all active users (include users without explicit preferences)
     info!(
         "Fetching users for article personalization (including users without explicit preferences)"
     );
-    let users = sqlx::query("SELECT DISTINCT u.id FROM users u")
+    let users: Vec<i64> = sqlx::query_scalar("SELECT DISTINCT u.id FROM users u")
         .fetch_all(pool)
         .await
         .context("Failed to fetch active users")?;
@@ -36,120 +62,335 @@ pub async fn personalize_for_users(
     }
 
     let total_users = users.len();
-    let mut personalized_count = 0;
-
-    for user_row in users {
-        let user_id: i64 = user_row.get("id");
+    let llm_provider = llm_provider.as_ref();
+    // Each user's summary is its own (user_id, article_id) row, so concurrent
+    // users never write to the same row
+    let outcomes: Vec<Result<bool>> = stream::iter(users)
+        .map(|user_id| async move {
+            let user_profile = get_user_profile(pool, user_id)
+                .await
+                .with_context(|| format!("failed to fetch profile for user {}", user_id))?;
+            personalize_article_for_user(pool, article_id, generic_summary, llm_provider, model, &user_profile, cache)
+                .await
+                .with_context(|| format!("failed to personalize for user {}", user_id))
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
 
-        // Fetch user profile
-        let user_profile = match get_user_profile(pool, user_id).await {
-            Ok(profile) => profile,
+    let mut personalized_count = 0;
+    let mut last_error = None;
+    let mut failed = 0;
+    for outcome in outcomes {
+        match outcome {
+            Ok(stored) => personalized_count += usize::from(stored),
             Err(e) => {
-                warn!("Failed to fetch profile for user {}: {}", user_id, e);
-                continue;
+                warn!("Article {}: {:#}", article_id, e);
+                failed += 1;
+                last_error = Some(e);
             }
-        };
-
-        // 1. Evaluate relevance
-        let relevance =
-            match evaluate_article_relevance(llm_provider.as_ref(), generic_summary, &user_profile)
-                .await
-            {
-                Ok(eval) => eval,
-                Err(e) => {
-                    warn!(
-                        "Failed to evaluate relevance for user {} article {}: {}",
-                        user_id, article_id, e
-                    );
-                    continue;
-                }
-            };
-
-        // Skip if not relevant (score < 0.3)
-        if relevance.score < 0.3 {
-            info!(
-                "Article {} not relevant for user {} (score: {})",
-                article_id, user_id, relevance.score
-            );
-            continue;
         }
+    }
+    // Nobody succeeded: most likely the LLM is down, so let the job be retried
+    if failed == total_users {
+        if let Some(e) = last_error {
+            return Err(e.context(format!("personalization failed for all {} users", total_users)));
+        }
+    }
 
-        // 2. Generate personalized summary
-        let personalized = match generate_personalized_summary(
-            llm_provider.as_ref(),
-            generic_summary,
-            &user_profile,
-            relevance.score,
-        )
+    info!(
+        "Personalized article {} for {}/{} active users",
+        article_id, personalized_count, total_users
+    );
+    if let Some(cache) = cache {
+        cache.log_hit_rate();
+    }
+
+    Ok(personalized_count)
+}
+
+/// Evaluate one article for one user and, if relevant, store the personalized
+/// summary. Returns whether a summary was stored.
+pub async fn personalize_article_for_user(
+    pool: &SqlitePool,
+    article_id: i64,
+    generic_summary: &Summary,
+    llm_provider: &dyn LlmProvider,
+    model: &str,
+    user_profile: &UserProfile,
+    cache: Option<&RelevanceCache>,
+) -> Result<bool> {
+    let user_id = user_profile.id;
+
+    // 1. Evaluate relevance, then weigh in the user's category weights
+    let relevance = evaluate_article_relevance(llm_provider, generic_summary, user_profile, cache).await;
+    if !relevance.as_ref().is_ok_and(|r| r.cached) {
+        log_llm_result(pool, "relevance", model, &relevance, |r| Some(&r.usage)).await;
+    }
+    let mut relevance = relevance.context("relevance evaluation failed")?;
+    if !user_profile.category_weights.is_empty() {
+        let categories = article_categories(pool, article_id).await?;
+        relevance.score = apply_category_weights(relevance.score, &categories, &user_profile.category_weights);
+    }
+
+    // Skip if not relevant, before the summary call
+    if relevance.score < RELEVANCE_THRESHOLD {
+        info!(
+            "Article {} not relevant for user {} (score: {})",
+            article_id, user_id, relevance.score
+        );
+        return Ok(false);
+    }
+
+    // 2. Generate personalized summary
+    let personalized = generate_personalized_summary(llm_provider, generic_summary, user_profile, relevance.score).await;
+    log_llm_result(pool, "personalize", model, &personalized, |p| Some(&p.usage)).await;
+    let personalized = personalized.context("personalized summary failed")?;
+
+    // 3. Store in database
+    let relevance_reasons_json = serde_json::to_string(&relevance.reasons)?;
+    let bullets_json = serde_json::to_string(&personalized.bullets)?;
+
+    sqlx::query(
+        "INSERT OR REPLACE INTO user_article_summaries
+         (user_id, article_id, relevance_score, relevance_reasons, is_relevant,
+          personalized_headline, personalized_bullets, personalized_details,
+          language, complexity_level, summary_length, llm_model,
+          prompt_tokens, completion_tokens)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(article_id)
+    .bind(relevance.score)
+    .bind(relevance_reasons_json)
+    .bind(true)
+    .bind(&personalized.headline)
+    .bind(bullets_json)
+    .bind(&personalized.details)
+    .bind(&user_profile.language)
+    .bind(&user_profile.complexity_level)
+    .bind(&personalized.length)
+    .bind(model)
+    .bind(personalized.usage.prompt_tokens as i64)
+    .bind(personalized.usage.completion_tokens as i64)
+    .execute(pool)
+    .await
+    .context("failed to store personalized summary")?;
+
+    info!(
+        "Personalized article {} for user {} (relevance: {:.2})",
+        article_id, user_id, relevance.score
+    );
+    Ok(true)
+}
+
+/// Categories `classify_article` stored for an article.
+async fn article_categories(pool: &SqlitePool, article_id: i64) -> Result<Vec<String>> {
+    let categories = sqlx::query_scalar::<_, Option<String>>("SELECT categories FROM article_summaries WHERE article_id = ?")
+        .bind(article_id)
+        .fetch_optional(pool)
         .await
-        {
-            Ok(summary) => summary,
-            Err(e) => {
-                warn!(
-                    "Failed to personalize for user {} article {}: {}",
-                    user_id, article_id, e
-                );
-                continue;
-            }
-        };
+        .context("failed to load article categories")?
+        .flatten();
+    Ok(categories.and_then(|c| serde_json::from_str(&c).ok()).unwrap_or_default())
+}
+
+/// Default `scoring.rating_learning_rate`.
+pub const DEFAULT_RATING_LEARNING_RATE: f32 = 0.2;
+/// Hours between two [`learn_from_ratings`] passes.
+pub const RATING_LEARNING_INTERVAL_HOURS: i64 = 6;
 
-        // 3. Store in database
-        let relevance_reasons_json = serde_json::to_string(&relevance.reasons)?;
-        let bullets_json = serde_json::to_string(&personalized.bullets)?;
-
-        match sqlx::query(
-            "INSERT OR REPLACE INTO user_article_summaries
-             (user_id, article_id, relevance_score, relevance_reasons, is_relevant,
-              personalized_headline, personalized_bullets, personalized_details,
-              language, complexity_level, summary_length, llm_model,
-              prompt_tokens, completion_tokens)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+/// `scoring.rating_learning_rate`, clamped to 0.0-1.0; 0.0 disables learning.
+pub fn rating_learning_rate(config: Option<&common::Config>) -> f32 {
+    config
+        .and_then(|c| c.scoring.as_ref())
+        .and_then(|s| s.rating_learning_rate)
+        .map(|r| r as f32)
+        .unwrap_or(DEFAULT_RATING_LEARNING_RATE)
+        .clamp(0.0, 1.0)
+}
+
+/// Category weight implied by an average star rating: 1 star = -1.0,
+/// 3 stars = 0.0 (neutral), 5 stars = 1.0.
+fn rating_target_weight(average_rating: f64) -> f32 {
+    ((average_rating as f32 - 3.0) / 2.0).clamp(-1.0, 1.0)
+}
+
+/// Move the user's learned weight (`category_learned`) for every category they
+/// have rated articles in `learning_rate` of the way toward the weight their
+/// average rating implies (unlearned categories start from neutral).
+/// Categories the user weighted themselves (`category_filter`) are left alone.
+/// Returns the updated weights.
+pub async fn learn_category_weights(
+    pool: &SqlitePool,
+    user_id: i64,
+    learning_rate: f32,
+) -> Result<std::collections::BTreeMap<String, f32>> {
+    let averages: Vec<(String, f64)> = sqlx::query_as(
+        "SELECT c.value, AVG(v.rating)
+         FROM user_article_views v
+         JOIN article_summaries s ON s.article_id = v.article_id
+         JOIN json_each(s.categories) c
+         WHERE v.user_id = ? AND v.rating IS NOT NULL AND s.categories IS NOT NULL
+           AND c.value NOT IN (SELECT preference_key FROM user_preferences
+                               WHERE user_id = v.user_id AND preference_type = 'category_filter')
+         GROUP BY c.value",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .context("failed to aggregate ratings by category")?;
+    if averages.is_empty() {
+        return Ok(Default::default());
+    }
+
+    let learned: std::collections::HashMap<String, f64> = sqlx::query_as(
+        "SELECT preference_key, preference_value FROM user_preferences
+         WHERE user_id = ? AND preference_type = 'category_learned'",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .context("failed to fetch learned category weights")?
+    .into_iter()
+    .collect();
+    let mut tx = pool.begin().await?;
+    let mut updated = std::collections::BTreeMap::new();
+    for (category, average) in averages {
+        let old = learned.get(&category).map(|&w| w as f32).unwrap_or(NEUTRAL_CATEGORY_WEIGHT);
+        let new = old + learning_rate * (rating_target_weight(average) - old);
+        sqlx::query(
+            "INSERT INTO user_preferences (user_id, preference_type, preference_key, preference_value)
+             VALUES (?, 'category_learned', ?, ?)
+             ON CONFLICT(user_id, preference_type, preference_key) DO UPDATE SET preference_value = excluded.preference_value",
         )
         .bind(user_id)
-        .bind(article_id)
-        .bind(relevance.score)
-        .bind(relevance_reasons_json)
-        .bind(true)
-        .bind(&personalized.headline)
-        .bind(bullets_json)
-        .bind(&personalized.details)
-        .bind(&user_profile.language)
-        .bind(&user_profile.complexity_level)
-        .bind(&personalized.length)
-        .bind(model)
-        .bind(personalized.usage.prompt_tokens as i64)
-        .bind(personalized.usage.completion_tokens as i64)
-        .execute(pool)
+        .bind(&category)
+        .bind(new as f64)
+        .execute(&mut tx)
         .await
-        {
-            Ok(_) => {
-                info!(
-                    "Personalized article {} for user {} (relevance: {:.2})",
-                    article_id, user_id, relevance.score
-                );
-                personalized_count += 1;
-            }
-            Err(e) => {
-                warn!(
-                    "Failed to store personalized summary for user {} article {}: {}",
-                    user_id, article_id, e
-                );
+        .context("failed to store learned category weight")?;
+        updated.insert(category, new);
+    }
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Run [`learn_category_weights`] for every user who has rated an article.
+/// Returns how many users had weights updated.
+pub async fn learn_from_ratings(pool: &SqlitePool, learning_rate: f32) -> Result<usize> {
+    let users: Vec<i64> = sqlx::query_scalar("SELECT DISTINCT user_id FROM user_article_views WHERE rating IS NOT NULL")
+        .fetch_all(pool)
+        .await
+        .context("failed to list users with ratings")?;
+
+    let mut learned = 0;
+    for user_id in users {
+        match learn_category_weights(pool, user_id, learning_rate).await {
+            Ok(weights) if !weights.is_empty() => {
+                info!("Learned {} category weights from ratings for user {}", weights.len(), user_id);
+                learned += 1;
             }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to learn category weights for user {}: {:#}", user_id, e),
         }
     }
+    Ok(learned)
+}
+
+/// Articles considered when re-personalizing: the most recent ones from the
+/// user's subscriptions that already have a generic summary.
+pub const REPERSONALIZE_ARTICLE_LIMIT: i64 = 100;
+
+/// Outcome of [`repersonalize_user`].
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct RepersonalizeReport {
+    /// Stale personalized summaries deleted
+    pub removed: u64,
+    /// Articles re-evaluated against the current profile
+    pub evaluated: usize,
+    /// Articles that were relevant and got a new personalized summary
+    pub personalized: usize,
+}
 
+/// Delete a user's personalized summaries (stale after a profile change).
+async fn clear_user_summaries(pool: &SqlitePool, user_id: i64) -> Result<u64> {
+    let res = sqlx::query("DELETE FROM user_article_summaries WHERE user_id = ?")
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .context("failed to delete user summaries")?;
+    Ok(res.rows_affected())
+}
+
+/// Ids of the user's recent articles that have a generic summary, newest first.
+async fn repersonalize_candidates(pool: &SqlitePool, user_id: i64, limit: i64) -> Result<Vec<i64>> {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT a.id
+         FROM articles a
+         JOIN article_summaries s ON s.article_id = a.id
+         WHERE EXISTS (
+             SELECT 1 FROM article_occurrences ao
+             JOIN subscriptions sub ON sub.feed_id = ao.feed_id
+             WHERE ao.article_id = a.id AND sub.user_id = ?
+         )
+         ORDER BY a.published_at DESC, a.id DESC
+         LIMIT ?",
+    )
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("failed to select articles to re-personalize")
+}
+
+/// Drop the user's personalized summaries and rebuild them for their recent
+/// articles with the current profile (language, interests, complexity).
+pub async fn repersonalize_user(
+    pool: &SqlitePool,
+    user_id: i64,
+    llm_provider: Arc<dyn LlmProvider>,
+    model: &str,
+) -> Result<RepersonalizeReport> {
+    let user_profile = get_user_profile(pool, user_id).await?;
+    let removed = clear_user_summaries(pool, user_id).await?;
+    let article_ids = repersonalize_candidates(pool, user_id, REPERSONALIZE_ARTICLE_LIMIT).await?;
     info!(
-        "Personalized article {} for {}/{} active users",
-        article_id, personalized_count, total_users
+        "Re-personalizing {} articles for user {} ({} stale summaries removed)",
+        article_ids.len(),
+        user_id,
+        removed
     );
 
-    Ok(personalized_count)
+    let mut report = RepersonalizeReport {
+        removed,
+        ..RepersonalizeReport::default()
+    };
+    for article_id in article_ids {
+        let Some(generic) = crate::processing::load_summary(pool, article_id).await? else {
+            continue;
+        };
+
+        report.evaluated += 1;
+        match personalize_article_for_user(pool, article_id, &generic, llm_provider.as_ref(), model, &user_profile, None).await {
+            Ok(true) => report.personalized += 1,
+            Ok(false) => {}
+            Err(e) => warn!("Failed to personalize for user {} article {}: {:#}", user_id, article_id, e),
+        }
+    }
+
+    info!(
+        "Re-personalized user {}: {}/{} articles relevant",
+        user_id, report.personalized, report.evaluated
+    );
+    Ok(report)
 }
 
 /// Initialize user vectors from their interest keywords if they don't have one
 pub async fn initialize_user_vectors(
     pool: &SqlitePool,
     llm_provider: Arc<dyn LlmProvider>,
+    model: &str,
 ) -> Result<usize> {
     let users = sqlx::query(
         "SELECT u.id FROM users u LEFT JOIN vec_users vu ON u.id = vu.user_id WHERE vu.user_id IS NULL"
@@ -167,7 +408,9 @@ pub async fn initialize_user_vectors(
         }
 
         let interests_text = profile.interests.join(" ");
-        match llm_provider.embed(&interests_text).await {
+        let result = llm_provider.embed(&interests_text).await;
+        log_llm_result(pool, "embed", model, &result, |_| None).await;
+        match result {
             Ok(embedding) => {
                 crate::personalization::update_user_vector(pool, user_id, &embedding).await?;
                 count += 1;
@@ -0,0 +1,143 @@
+//! Per-domain request limits for the ingestion worker.
+//!
+//! Feeds are fetched concurrently, but requests to one host are capped at
+//! `politeness.concurrency_per_domain` in flight and started at least
+//! `politeness.delay_seconds` apart.
+
+use common::Config;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Default for `politeness.concurrency_per_domain`.
+pub const DEFAULT_CONCURRENCY_PER_DOMAIN: usize = 1;
+/// Default for `politeness.delay_seconds`.
+pub const DEFAULT_DELAY_SECS: u64 = 1;
+/// Default for `scheduler.max_concurrent_fetches`.
+pub const DEFAULT_MAX_CONCURRENT_FETCHES: usize = 8;
+
+struct DomainSlot {
+    permits: Arc<Semaphore>,
+    last_start: tokio::sync::Mutex<Option<Instant>>,
+}
+
+/// Hands out per-host permits; hosts are tracked for the limiter's lifetime
+/// (one worker sweep).
+pub struct DomainLimiter {
+    per_domain: usize,
+    delay: Duration,
+    domains: Mutex<HashMap<String, Arc<DomainSlot>>>,
+}
+
+/// Held while a request to a host is in flight.
+pub struct DomainPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl DomainLimiter {
+    pub fn new(per_domain: usize, delay: Duration) -> Self {
+        Self {
+            per_domain: per_domain.max(1),
+            delay,
+            domains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build from the `[politeness]` section.
+    pub fn from_config(config: Option<&Config>) -> Self {
+        let politeness = config.and_then(|c| c.politeness.as_ref());
+        let per_domain = politeness
+            .and_then(|p| p.concurrency_per_domain)
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_CONCURRENCY_PER_DOMAIN);
+        let delay = politeness.and_then(|p| p.delay_seconds).unwrap_or(DEFAULT_DELAY_SECS);
+        Self::new(per_domain, Duration::from_secs(delay))
+    }
+
+    /// Wait until a request to `url`'s host may start.
+    pub async fn acquire(&self, url: &str) -> DomainPermit {
+        let slot = {
+            let mut domains = self.domains.lock().unwrap();
+            domains
+                .entry(domain_key(url))
+                .or_insert_with(|| {
+                    Arc::new(DomainSlot {
+                        permits: Arc::new(Semaphore::new(self.per_domain)),
+                        last_start: tokio::sync::Mutex::new(None),
+                    })
+                })
+                .clone()
+        };
+        let permit = slot
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("domain semaphore is never closed");
+
+        let mut last_start = slot.last_start.lock().await;
+        if let Some(elapsed) = last_start.map(|t| t.elapsed()) {
+            if elapsed < self.delay {
+                tokio::time::sleep(self.delay - elapsed).await;
+            }
+        }
+        *last_start = Some(Instant::now());
+
+        DomainPermit { _permit: permit }
+    }
+}
+
+/// Requests are grouped by host; unparsable URLs each get their own group.
+fn domain_key(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_ascii_lowercase()))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Overall number of feeds fetched at once (`scheduler.max_concurrent_fetches`).
+pub fn max_concurrent_fetches(config: Option<&Config>) -> usize {
+    config
+        .and_then(|c| c.scheduler.max_concurrent_fetches)
+        .unwrap_or(DEFAULT_MAX_CONCURRENT_FETCHES)
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domain_key_uses_host() {
+        assert_eq!(domain_key("https://Example.com/a.xml"), "example.com");
+        assert_eq!(domain_key("http://example.com:8080/b"), "example.com");
+        assert_eq!(domain_key("not a url"), "not a url");
+    }
+
+    #[tokio::test]
+    async fn limits_concurrency_and_spaces_requests_per_domain() {
+        let limiter = Arc::new(DomainLimiter::new(1, Duration::from_millis(100)));
+        let started = Instant::now();
+
+        let first = limiter.acquire("https://a.example/1").await;
+        // Another host is not held back
+        let _other = limiter.acquire("https://b.example/1").await;
+        assert!(started.elapsed() < Duration::from_millis(100));
+
+        let waiter = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                let _permit = limiter.acquire("https://a.example/2").await;
+                Instant::now()
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(!waiter.is_finished());
+        let released = Instant::now();
+        drop(first);
+        let second_start = waiter.await.unwrap();
+        assert!(second_start >= released);
+        assert!(second_start.duration_since(started) >= Duration::from_millis(100));
+    }
+}
@@ -1,301 +1,948 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
-use sqlx::{SqlitePool, Row};
-use std::sync::Arc;
-use tracing::info;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::{Arguments, Row, SqlitePool};
+use std::collections::HashMap;
+use tracing::{debug, error, info};
 
-use crate::llm::LlmProvider;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+/// An article picked for a press review, with the user's personalized summary.
 #[derive(Debug, Clone, Serialize)]
 pub struct ScoredArticle {
     pub id: i64,
     pub headline: String,
     pub bullets: Vec<String>,
-    pub feed_title: String,
-    pub article_title: String,
+    pub details: Option<String>,
+    /// Language the personalized summary was written in
+    pub language: String,
+    /// Language the article itself is written in, when it was detected
+    pub source_language: Option<String>,
+    pub feed_title: Option<String>,
+    /// Topics from `classify_article` (stored in `article_summaries.categories`)
+    pub categories: Vec<String>,
     pub url: String,
+    /// Stored relevance score for this user, plus any keyword boosts
     pub score: f64,
-    pub categories: Vec<String>,
-    pub published_at: DateTime<Utc>,
+    /// Low-relevance "something different" pick
+    pub serendipity: bool,
+    /// Near-identical articles collapsed into this one (see [`dedup_articles`])
+    pub duplicates: Vec<DuplicateArticle>,
+    /// The user's `keyword_boost` keywords found in the headline or bullets
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub matched_keywords: Vec<String>,
 }
 
-/// Fetch and score articles based on user preferences
-/// Returns ALL articles with summaries, regardless of publication date
-pub async fn fetch_and_score_articles(
-    pool: &SqlitePool,
-    user_id: i64,
-) -> Result<Vec<ScoredArticle>> {
-    // 1. Fetch user preferences
-    let prefs = sqlx::query(
-        "SELECT preference_type, preference_key, preference_value FROM user_preferences WHERE user_id = ?"
-    )
-    .bind(user_id)
-    .fetch_all(pool)
-    .await?;
-
-    let mut category_weights = std::collections::HashMap::new();
-    
-    for row in prefs {
-        let p_type: String = row.get("preference_type");
-        let key: String = row.get("preference_key");
-        let val: f64 = row.get("preference_value");
-        
-        if p_type == "category_filter" {
-            category_weights.insert(key.to_lowercase(), val);
+/// Another copy of a review article's story, left out of the review.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateArticle {
+    pub id: i64,
+    pub feed_title: Option<String>,
+}
+
+/// How review articles are grouped: by the feed they came from, or by topic.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewGroupBy {
+    #[default]
+    Source,
+    Topic,
+}
+
+impl ReviewGroupBy {
+    /// Parse a `group_by=source|topic` option; anything else means `Source`.
+    pub fn parse(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            Some("topic") => Self::Topic,
+            _ => Self::Source,
         }
     }
+}
 
-    // 2. Fetch articles with summaries (all articles, not just recent ones)
-    // This ensures we don't miss articles from newly added feeds or failed processing
-    // BUT we exclude articles the user has already seen
-    let rows = sqlx::query(
-        r#"
-        SELECT 
-            a.id,
-            s.headline, 
-            s.bullets_json, 
-            s.categories,
-            f.title as feed_title,
-            a.title as article_title,
-            a.canonical_url,
-            a.first_seen_at
-        FROM article_summaries s
-        JOIN articles a ON s.article_id = a.id
-        JOIN article_occurrences ao ON a.id = ao.article_id
-        JOIN subscriptions sub ON ao.feed_id = sub.feed_id
-        JOIN feeds f ON sub.feed_id = f.id
-        LEFT JOIN user_article_views uav ON uav.user_id = ? AND uav.article_id = a.id
-        WHERE sub.user_id = ?
-        AND uav.id IS NULL  -- Exclude articles already viewed
-        "#
-    )
-    .bind(user_id)
-    .bind(user_id)
-    .fetch_all(pool)
-    .await
-    .context("Failed to fetch article summaries")?;
-
-    let mut scored_articles = Vec::new();
-
-    for row in rows {
-        let id: i64 = row.get("id");
-        let headline: String = row.get("headline");
-        let bullets_json: String = row.get("bullets_json");
-        let categories_json: Option<String> = row.get("categories");
-        let feed_title: String = row.get("feed_title");
-        let article_title: String = row.get("article_title");
-        let url: String = row.get("canonical_url");
-        let published_at: DateTime<Utc> = row.get("first_seen_at");
-
-        let bullets: Vec<String> = serde_json::from_str(&bullets_json).unwrap_or_default();
-        let categories: Vec<String> = categories_json
-            .and_then(|j| serde_json::from_str(&j).ok())
-            .unwrap_or_default();
-
-        // Scoring logic
-        let mut score = 1.0;
-        
-        // Recency boost (newer is better)
-        let age_hours = (Utc::now() - published_at).num_hours() as f64;
-        score += (24.0 - age_hours).max(0.0) * 0.05; // Up to +1.2 for very new
-
-        // Category weights
-        for cat in &categories {
-            if let Some(weight) = category_weights.get(cat) {
-                if *weight < 0.0 {
-                    score = -1.0; // Blocked
-                    break;
-                }
-                score += weight;
+/// Display form of a stored category: "faits_divers" -> "Faits divers".
+pub fn topic_label(category: &str) -> String {
+    let words = category.trim().replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl ScoredArticle {
+    /// Heading the article is grouped under, if it has one: its feed title, or
+    /// its first category when grouping by topic.
+    pub fn group_label(&self, group_by: ReviewGroupBy) -> Option<String> {
+        match group_by {
+            ReviewGroupBy::Source => self.feed_title.clone(),
+            ReviewGroupBy::Topic => self
+                .categories
+                .iter()
+                .map(|c| topic_label(c))
+                .find(|l| !l.is_empty()),
+        }
+    }
+
+    /// Other feeds carrying the same story, for "also covered by ..." mentions.
+    pub fn also_covered_by(&self) -> Vec<String> {
+        let mut feeds: Vec<String> = Vec::new();
+        for title in self.duplicates.iter().filter_map(|d| d.feed_title.as_ref()) {
+            if Some(title) != self.feed_title.as_ref() && !feeds.contains(title) {
+                feeds.push(title.clone());
             }
         }
+        feeds
+    }
+}
+
+/// Default `scoring.recency_half_life_hours`.
+pub const DEFAULT_RECENCY_HALF_LIFE_HOURS: f64 = 24.0;
+/// With scoring weights or keyword boosts, relevant candidates fetched per
+/// review slot before re-ranking.
+const RERANK_CANDIDATES_PER_SLOT: i64 = 4;
+/// With near-duplicate collapsing, extra candidates fetched per review slot.
+const DEDUP_CANDIDATES_PER_SLOT: i64 = 2;
+/// Default `press_review.near_duplicate_distance`.
+pub const DEFAULT_NEAR_DUPLICATE_DISTANCE: f64 = 0.15;
+
+/// Cosine distance under which two review candidates count as the same story
+/// (`[press_review] near_duplicate_distance`); None when set to 0.
+pub fn near_duplicate_distance(config: Option<&common::Config>) -> Option<f64> {
+    let distance = config
+        .and_then(|c| c.press_review.as_ref())
+        .and_then(|p| p.near_duplicate_distance)
+        .unwrap_or(DEFAULT_NEAR_DUPLICATE_DISTANCE);
+    Some(distance).filter(|&d| d > 0.0)
+}
+
+/// Share of the recency boost left after `age_hours`: 1.0 when new, 0.5 after
+/// one half-life, 0.25 after two... A non-positive half-life disables decay.
+pub fn recency_decay(age_hours: f64, half_life_hours: f64) -> f64 {
+    if half_life_hours <= 0.0 {
+        return 1.0;
+    }
+    0.5_f64.powf(age_hours.max(0.0) / half_life_hours)
+}
+
+/// Defaults for the `[scoring]` weights used in review ranking.
+pub const DEFAULT_W_PREF: f64 = 1.0;
+pub const DEFAULT_W_RECENCY: f64 = 1.0;
+pub const DEFAULT_W_SRC: f64 = 0.5;
+pub const DEFAULT_W_NOVEL: f64 = 0.75;
+/// Jitter amplitude at `scoring.serendipity = 1.0`.
+const SERENDIPITY_JITTER: f64 = 0.01;
+/// Rank lost, times `w_src` (same feed) or `w_novel` (same topic), per earlier
+/// review article sharing the feed or topic (see [`diversify`]).
+const REPEAT_PENALTY: f64 = 0.5;
+/// Views counted when measuring which categories the user read recently.
+const NOVELTY_WINDOW_DAYS: i64 = 14;
+
+/// `subscriptions.weight` (see [`crate::server::SUBSCRIPTION_WEIGHT_RANGE`])
+/// scaled to -1.0..=1.0, the range of the other ranking terms, so `w_src` is
+/// comparable with `w_pref` and `w_recency`.
+pub fn normalized_source_weight(weight: i64) -> f64 {
+    let max = *crate::server::SUBSCRIPTION_WEIGHT_RANGE.end() as f64;
+    (weight as f64 / max).clamp(-1.0, 1.0)
+}
+
+/// Weights used when ranking review articles, from the `[scoring]` section:
+///
+/// `rank = w_pref * relevance + w_recency * recency_decay(age) + w_src * source_weight
+///        + w_novel * novelty + jitter`
+///
+/// `relevance` is the stored personalized score plus keyword boosts,
+/// `source_weight` the user's weight for the article's feed
+/// (`subscriptions.weight`, 0 unless set) scaled to -1.0..=1.0 by
+/// [`normalized_source_weight`], and `novelty` how little the user
+/// read of the article's categories lately (see [`category_novelty`]). `jitter`
+/// is drawn from `[0, serendipity * 0.01)`: enough to shuffle equal scores from
+/// one review to the next, too little to reorder articles that really differ.
+/// The ranked articles are then spread across feeds and topics by [`diversify`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoringWeights {
+    pub w_pref: f64,
+    /// Boost of a brand-new article
+    pub w_recency: f64,
+    pub half_life_hours: f64,
+    pub w_src: f64,
+    pub w_novel: f64,
+    /// Upper bound of the random jitter (0 = deterministic ranking)
+    pub jitter: f64,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self {
+            w_pref: DEFAULT_W_PREF,
+            w_recency: DEFAULT_W_RECENCY,
+            half_life_hours: DEFAULT_RECENCY_HALF_LIFE_HOURS,
+            w_src: DEFAULT_W_SRC,
+            w_novel: DEFAULT_W_NOVEL,
+            jitter: 0.0,
+        }
+    }
+}
+
+impl ScoringWeights {
+    /// From the `[scoring]` section; None when it is absent.
+    pub fn from_config(config: Option<&common::Config>) -> Option<Self> {
+        let scoring = config.and_then(|c| c.scoring.as_ref())?;
+        let defaults = Self::default();
+        Some(Self {
+            w_pref: scoring.w_pref.unwrap_or(defaults.w_pref),
+            w_recency: scoring.w_recency.unwrap_or(defaults.w_recency),
+            half_life_hours: scoring.recency_half_life_hours.unwrap_or(defaults.half_life_hours),
+            w_src: scoring.w_src.unwrap_or(defaults.w_src),
+            w_novel: scoring.w_novel.unwrap_or(defaults.w_novel),
+            jitter: scoring
+                .serendipity
+                .filter(|s| s.is_finite())
+                .map_or(0.0, |s| s.clamp(0.0, 1.0) * SERENDIPITY_JITTER),
+        })
+    }
+
+    /// Ranking score, without jitter, for an article with `relevance` and
+    /// `novelty`, first seen `age_hours` ago in a feed the user weighs
+    /// `source_weight` (normalized, see [`normalized_source_weight`]).
+    pub fn rank(&self, relevance: f64, age_hours: f64, source_weight: f64, novelty: f64) -> f64 {
+        self.w_pref * relevance
+            + self.w_recency * recency_decay(age_hours, self.half_life_hours)
+            + self.w_src * source_weight
+            + self.w_novel * novelty
+    }
+
+    /// Random tie-breaker added to [`Self::rank`].
+    pub fn jitter<R: rand::Rng>(&self, rng: &mut R) -> f64 {
+        if self.jitter > 0.0 {
+            rng.gen_range(0.0..self.jitter)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// How many articles to select, and which share of them go to serendipity picks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReviewSelectionOptions {
+    pub limit: i64,
+    /// From `scoring.serendipity`, clamped to [0, 1]
+    pub serendipity_fraction: f64,
+    /// Rank relevant articles with these weights (see [`ScoringWeights`])
+    /// instead of by relevance alone
+    pub scoring: Option<ScoringWeights>,
+    /// Leave out articles first seen longer ago than this
+    pub max_age_hours: Option<i64>,
+    /// Collapse relevant articles whose embeddings are closer than this
+    /// cosine distance, keeping the best-ranked one
+    pub near_duplicate_distance: Option<f64>,
+}
+
+impl ReviewSelectionOptions {
+    /// Size the review for a reading budget: half the session is spent reading,
+    /// at ~150 words per article, with between 3 and 15 articles.
+    pub fn for_budget(duration_seconds: i64, reading_speed: i32, serendipity_fraction: f64) -> Self {
+        let reading_minutes = (duration_seconds as f64 / 60.0).ceil();
+        let total_words_budget = (reading_minutes / 2.0) * reading_speed as f64;
+        let limit = ((total_words_budget / 150.0).ceil() as i64).clamp(3, 15);
+        Self {
+            limit,
+            serendipity_fraction,
+            scoring: None,
+            max_age_hours: None,
+            near_duplicate_distance: None,
+        }
+    }
+
+    pub fn with_scoring(mut self, scoring: Option<ScoringWeights>) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    pub fn with_near_duplicates(mut self, distance: Option<f64>) -> Self {
+        self.near_duplicate_distance = distance;
+        self
+    }
 
-        if score > 0.0 {
-            scored_articles.push(ScoredArticle {
-                id,
-                headline,
-                bullets,
-                feed_title,
-                article_title,
-                url,
-                score,
-                categories,
-                published_at,
-            });
+    /// Restrict the selection to the catch-up window and, for a user coming
+    /// back from a long absence, to the top `max_articles`.
+    pub fn with_catch_up(mut self, limit: &CatchUpLimit, catch_up: Option<&CatchUp>) -> Self {
+        self.max_age_hours = limit.max_age_hours;
+        if catch_up.is_some() {
+            self.limit = self.limit.min(limit.max_articles);
         }
+        self
     }
+}
 
-    // Sort by score descending
-    scored_articles.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+/// Defaults for the `[press_review] catch_up_*` settings.
+pub const DEFAULT_CATCH_UP_MAX_AGE_HOURS: i64 = 168;
+pub const DEFAULT_CATCH_UP_AFTER_HOURS: i64 = 48;
+pub const DEFAULT_CATCH_UP_MAX_ARTICLES: i64 = 10;
 
-    Ok(scored_articles)
+/// How much of a backlog a review may surface, so a long absence does not
+/// produce a giant review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatchUpLimit {
+    /// Articles first seen longer ago are never selected (None = no limit)
+    pub max_age_hours: Option<i64>,
+    /// A user whose last review is older than this is catching up
+    pub after_hours: i64,
+    /// Review size cap while catching up
+    pub max_articles: i64,
 }
 
-/// Generate a personalized press review for a user (Advanced Half-Life Selection)
-pub async fn generate_press_review(
+impl CatchUpLimit {
+    pub fn from_config(config: Option<&common::Config>) -> Self {
+        let review = config.and_then(|c| c.press_review.as_ref());
+        Self {
+            max_age_hours: Some(
+                review
+                    .and_then(|p| p.catch_up_max_age_hours)
+                    .unwrap_or(DEFAULT_CATCH_UP_MAX_AGE_HOURS),
+            )
+            .filter(|&h| h > 0),
+            after_hours: review
+                .and_then(|p| p.catch_up_after_hours)
+                .unwrap_or(DEFAULT_CATCH_UP_AFTER_HOURS)
+                .max(0),
+            max_articles: review
+                .and_then(|p| p.catch_up_max_articles)
+                .unwrap_or(DEFAULT_CATCH_UP_MAX_ARTICLES)
+                .max(1),
+        }
+    }
+}
+
+/// A user returning after a long absence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatchUp {
+    /// Hours since the user's last review
+    pub away_hours: i64,
+    /// Unseen relevant articles within the catch-up window
+    pub backlog: i64,
+}
+
+/// Whether `user_id` is catching up: they had a review before, but not within
+/// `limit.after_hours`. Users who never had a review are not catching up.
+pub async fn catch_up_status(pool: &SqlitePool, user_id: i64, limit: &CatchUpLimit) -> Result<Option<CatchUp>> {
+    let away_hours: Option<f64> = sqlx::query_scalar(
+        "SELECT (julianday('now') - julianday(MAX(su.created_at))) * 24.0
+         FROM summaries su
+         JOIN sessions s ON s.id = su.session_id
+         WHERE s.user_id = ? AND su.summary_text IS NOT NULL",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .context("failed to look up last press review")?;
+    let away_hours = match away_hours {
+        Some(h) if h >= limit.after_hours as f64 => h as i64,
+        _ => return Ok(None),
+    };
+
+    let sql = personalized_summaries_sql("COUNT(*)", "");
+    let backlog: i64 = sqlx::query_scalar_with(&sql, SummaryFilter::review(limit.max_age_hours).arguments(user_id))
+        .fetch_one(pool)
+        .await
+        .context("failed to count unseen articles")?;
+    Ok(Some(CatchUp { away_hours, backlog }))
+}
+
+/// Which of a user's personalized summaries to select. Press reviews, catch-up
+/// counts and `GET /api/v1/feed` all go through [`personalized_summaries_sql`]
+/// with one of these, so they agree on what a user may see.
+#[derive(Debug, Clone, Default)]
+pub struct SummaryFilter {
+    /// Required `is_relevant` flag (`None` = either)
+    pub relevant: Option<bool>,
+    /// Leave out articles the user already viewed
+    pub unread_only: bool,
+    /// Catch-up window on `first_seen_at` (`None` = unlimited)
+    pub max_age_hours: Option<i64>,
+    /// Only articles classified in this category (case-insensitive)
+    pub category: Option<String>,
+    /// Minimum stored relevance score
+    pub min_relevance: Option<f64>,
+}
+
+impl SummaryFilter {
+    /// The unseen, relevant articles a press review picks from.
+    pub fn review(max_age_hours: Option<i64>) -> Self {
+        Self { relevant: Some(true), unread_only: true, max_age_hours, ..Default::default() }
+    }
+
+    /// Arguments for the placeholders of [`personalized_summaries_sql`];
+    /// those of the query's tail (limit, offset) are added after these.
+    pub fn arguments(&self, user_id: i64) -> SqliteArguments<'static> {
+        let mut args = SqliteArguments::default();
+        args.add(user_id);
+        args.add(self.relevant);
+        args.add(self.relevant);
+        args.add(self.unread_only);
+        args.add(self.max_age_hours);
+        args.add(self.max_age_hours);
+        args.add(self.category.clone());
+        args.add(self.category.clone());
+        args.add(self.min_relevance);
+        args.add(self.min_relevance);
+        args
+    }
+}
+
+/// Columns of a selected personalized summary, read by `scored_article_from_row`.
+///
+/// Articles must occur in at least one feed the user subscribes to; when several
+/// do, the feed with the lowest id provides `feed_title` and the highest
+/// subscription weight is the `source_weight`.
+const PERSONALIZED_COLUMNS: &str = "uas.article_id,
+        uas.personalized_headline,
+        uas.personalized_bullets,
+        uas.personalized_details,
+        uas.language,
+        uas.relevance_score,
+        a.canonical_url,
+        a.language as source_language,
+        (julianday('now') - julianday(a.first_seen_at)) * 24.0 as age_hours,
+        (SELECT s.categories FROM article_summaries s WHERE s.article_id = uas.article_id) as categories,
+        (SELECT f.title
+           FROM article_occurrences ao
+           JOIN subscriptions s ON s.feed_id = ao.feed_id AND s.user_id = uas.user_id
+           JOIN feeds f ON f.id = ao.feed_id
+          WHERE ao.article_id = uas.article_id
+          ORDER BY f.id ASC
+          LIMIT 1) as feed_title,
+        (SELECT MAX(s.weight)
+           FROM article_occurrences ao
+           JOIN subscriptions s ON s.feed_id = ao.feed_id AND s.user_id = uas.user_id
+          WHERE ao.article_id = uas.article_id) as source_weight";
+
+/// Most relevant first, then most recent. Ending on `article_id` makes ties
+/// resolve the same way across runs.
+const RELEVANCE_ORDER: &str = "ORDER BY uas.relevance_score DESC, a.first_seen_at DESC, uas.article_id ASC";
+
+/// `SELECT <columns>` over a user's personalized summaries of articles from
+/// their subscribed feeds, filtered as in [`SummaryFilter::arguments`], then
+/// `tail` (ordering and limit).
+pub fn personalized_summaries_sql(columns: &str, tail: &str) -> String {
+    format!(
+        "SELECT {}
+     FROM user_article_summaries uas
+     JOIN articles a ON uas.article_id = a.id
+     WHERE uas.user_id = ?
+       AND (? IS NULL OR uas.is_relevant = ?)
+       -- Exclude articles already viewed by the user in ANY session
+       AND (NOT ? OR NOT EXISTS (
+           SELECT 1 FROM user_article_views uav
+           WHERE uav.user_id = uas.user_id AND uav.article_id = uas.article_id
+       ))
+       -- Require that the article appears in at least one feed the user is subscribed to.
+       AND EXISTS (
+           SELECT 1 FROM article_occurrences ao
+           JOIN subscriptions s ON s.feed_id = ao.feed_id
+           WHERE ao.article_id = uas.article_id AND s.user_id = uas.user_id
+       )
+       -- Catch-up window (max age in hours, NULL = unlimited)
+       AND (? IS NULL OR (julianday('now') - julianday(a.first_seen_at)) * 24.0 <= ?)
+       AND (? IS NULL OR EXISTS (
+           SELECT 1 FROM article_summaries s, json_each(s.categories) c
+           WHERE s.article_id = uas.article_id AND c.value = ? COLLATE NOCASE
+       ))
+       AND (? IS NULL OR uas.relevance_score >= ?)
+     {}",
+        columns, tail
+    )
+}
+
+/// Select the articles for a user's press review: the most relevant unseen
+/// articles, followed by serendipity picks. Both the REST digest and the
+/// websocket session use this, so they always agree on the selection.
+///
+/// With `opts.scoring`, a wider pool of the most relevant candidates is
+/// re-ranked by [`ScoringWeights::rank`], then spread across feeds and topics
+/// (see [`diversify`]). With
+/// `opts.near_duplicate_distance`, copies of the same story are collapsed into
+/// their best-ranked article before the review is cut to size. The user's
+/// keyword boosts (see [`apply_keyword_boosts`]) are added to every score first.
+pub async fn select_review_articles(
     pool: &SqlitePool,
     user_id: i64,
-    _llm_provider: Arc<dyn LlmProvider>,
-    _model: &str,
-    duration_seconds: i64,
-) -> Result<String> {
-    // 1. Fetch user profile
-    let user = crate::personalization::get_user_profile(pool, user_id).await?;
-    let reading_speed = user.reading_speed as f64; // wpm
-    
-    info!("Generating half-life press review for user {} (speed: {} wpm, budget: {}s)", 
-          user_id, reading_speed, duration_seconds);
-
-    // 2. Calculate average publication interval per feed (Frequency Analysis)
-    // We look at the last 20 articles per feed to determine their "cadence"
-    let feed_stats_rows = sqlx::query(
-        r#"
-        WITH lead_times AS (
-            SELECT 
-                ao.feed_id, 
-                a.first_seen_at,
-                LAG(a.first_seen_at) OVER (PARTITION BY ao.feed_id ORDER BY a.first_seen_at) as prev_seen
-            FROM article_occurrences ao
-            JOIN articles a ON ao.article_id = a.id
-            WHERE ao.feed_id IN (SELECT feed_id FROM subscriptions WHERE user_id = ?)
-        )
-        SELECT 
-            feed_id,
-            AVG(unixepoch(first_seen_at) - unixepoch(prev_seen)) as avg_interval_seconds
-        FROM lead_times
-        WHERE prev_seen IS NOT NULL
-        GROUP BY feed_id
-        "#
+    opts: &ReviewSelectionOptions,
+) -> Result<Vec<ScoredArticle>> {
+    let serendipity_count = serendipity_slots(opts.limit, opts.serendipity_fraction);
+    let relevant_count = opts.limit - serendipity_count;
+    let keyword_boosts = load_keyword_boosts(pool, user_id).await?;
+    let reranked = opts.scoring.is_some() || !keyword_boosts.is_empty();
+    let mut candidate_count = if reranked {
+        relevant_count * RERANK_CANDIDATES_PER_SLOT
+    } else {
+        relevant_count
+    };
+    if opts.near_duplicate_distance.is_some() {
+        candidate_count *= DEDUP_CANDIDATES_PER_SLOT;
+    }
+
+    let seen_categories = match opts.scoring {
+        Some(scoring) if scoring.w_novel != 0.0 => recent_category_views(pool, user_id).await?,
+        _ => HashMap::new(),
+    };
+
+    let sql = personalized_summaries_sql(PERSONALIZED_COLUMNS, &format!("{} LIMIT ?", RELEVANCE_ORDER));
+    let mut args = SummaryFilter::review(opts.max_age_hours).arguments(user_id);
+    args.add(candidate_count);
+    let rows = sqlx::query_with(&sql, args)
+        .fetch_all(pool)
+        .await
+        .context("Failed to select review articles")?;
+
+    let mut ranked: Vec<(f64, ScoredArticle)> = {
+        let mut rng = rand::thread_rng();
+        rows.iter()
+            .filter_map(|r| {
+                let mut article = scored_article_from_row(r, false);
+                if !apply_keyword_boosts(&mut article, &keyword_boosts) {
+                    return None;
+                }
+                let rank = match opts.scoring {
+                    Some(scoring) => {
+                        let age_hours = r.get::<Option<f64>, _>("age_hours").unwrap_or(0.0);
+                        let source_weight = r.get::<Option<i64>, _>("source_weight").unwrap_or(0);
+                        let novelty = category_novelty(&article.categories, &seen_categories);
+                        scoring.rank(article.score, age_hours, normalized_source_weight(source_weight), novelty)
+                            + scoring.jitter(&mut rng)
+                    }
+                    None => article.score,
+                };
+                Some((rank, article))
+            })
+            .collect()
+    };
+    // Stable: equal ranks keep the SQL order
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let ranks: HashMap<i64, f64> = ranked.iter().map(|(rank, a)| (a.id, *rank)).collect();
+    let mut articles: Vec<ScoredArticle> = ranked.into_iter().map(|(_, a)| a).collect();
+    if let Some(max_distance) = opts.near_duplicate_distance {
+        articles = dedup_articles(pool, articles, max_distance).await?;
+    }
+    if let Some(scoring) = opts.scoring {
+        let ranked = articles.into_iter().map(|a| (ranks[&a.id], a)).collect();
+        articles = diversify(ranked, &scoring);
+    }
+    articles.truncate(relevant_count.max(0) as usize);
+    let mut serendipity = fetch_serendipity_articles(pool, user_id, serendipity_count, opts.max_age_hours).await;
+    serendipity.retain_mut(|article| apply_keyword_boosts(article, &keyword_boosts));
+    articles.extend(serendipity);
+    for article in articles.iter().filter(|a| !a.matched_keywords.is_empty()) {
+        debug!(
+            "Press review for user {}: article {} boosted to {:.2} by {:?}",
+            user_id, article.id, article.score, article.matched_keywords
+        );
+    }
+    Ok(articles)
+}
+
+/// How many articles of each category `user_id` read over the last
+/// `NOVELTY_WINDOW_DAYS` days.
+pub async fn recent_category_views(pool: &SqlitePool, user_id: i64) -> Result<HashMap<String, usize>> {
+    let rows: Vec<Option<String>> = sqlx::query_scalar(
+        "SELECT s.categories
+         FROM user_article_views v
+         JOIN article_summaries s ON s.article_id = v.article_id
+         WHERE v.user_id = ? AND v.viewed_at >= strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?)",
     )
     .bind(user_id)
+    .bind(format!("-{} days", NOVELTY_WINDOW_DAYS))
     .fetch_all(pool)
     .await
-    .context("Failed to calculate feed publication frequencies")?;
-
-    let mut feed_half_lives = std::collections::HashMap::new();
-    for row in feed_stats_rows {
-        let feed_id: i64 = row.get("feed_id");
-        let avg_interval: f64 = row.get::<Option<f64>, _>("avg_interval_seconds").unwrap_or(86400.0); // Default 1 day
-        
-        // Half-life concept: T1/2 = 10 * average interval
-        // An article loses 50% value after 10 "expected" publication intervals
-        let half_life_secs = (avg_interval * 10.0).clamp(3600.0, 31536000.0); // 1h to 1 year
-        feed_half_lives.insert(feed_id, half_life_secs);
-    }
-
-    // 3. Fetch candidate articles: last 30 unread articles per feed (relative window)
-    let rows = sqlx::query(
-        r#"
-        WITH ranked_articles AS (
-            SELECT 
-                uas.id as summary_id,
-                uas.user_id,
-                uas.article_id,
-                uas.relevance_score,
-                uas.relevance_reasons,
-                uas.is_relevant,
-                uas.personalized_headline,
-                uas.personalized_bullets,
-                uas.personalized_details,
-                uas.language,
-                uas.complexity_level,
-                uas.summary_length,
-                uas.created_at,
-                uas.llm_model,
-                uas.prompt_tokens,
-                uas.completion_tokens,
-                ao.feed_id,
-                ROW_NUMBER() OVER (PARTITION BY ao.feed_id ORDER BY a.first_seen_at DESC) as rank
-            FROM user_article_summaries uas
-            JOIN articles a ON uas.article_id = a.id
-            JOIN article_occurrences ao ON a.id = ao.article_id
-            JOIN subscriptions sub ON ao.feed_id = sub.feed_id AND sub.user_id = uas.user_id
-            LEFT JOIN user_article_views uav ON uav.user_id = uas.user_id AND uav.article_id = uas.article_id
-            WHERE uas.user_id = ?
-            AND uav.id IS NULL
-        )
-        SELECT * FROM ranked_articles WHERE rank <= 30
-        "#
+    .context("Failed to load recently read categories")?;
+
+    let mut counts = HashMap::new();
+    for categories in rows.into_iter().flatten() {
+        let categories: Vec<String> = serde_json::from_str(&categories).unwrap_or_default();
+        for category in categories {
+            *counts.entry(category).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Novelty of an article for a user who recently read `seen` articles per
+/// category: for each of its categories, 1 - reads / reads of the most-read
+/// category, averaged. Unread categories score 1, the user's staple 0.
+/// Uncategorized articles, and users without recent reads, get 0.
+pub fn category_novelty(categories: &[String], seen: &HashMap<String, usize>) -> f64 {
+    let most_read = seen.values().copied().max().unwrap_or(0);
+    if categories.is_empty() || most_read == 0 {
+        return 0.0;
+    }
+    let total: f64 = categories
+        .iter()
+        .map(|c| 1.0 - *seen.get(c).unwrap_or(&0) as f64 / most_read as f64)
+        .sum();
+    total / categories.len() as f64
+}
+
+/// Order ranked articles so one feed or topic does not take over the review.
+///
+/// Positions are filled greedily with the best remaining rank after a penalty
+/// of `w_src * REPEAT_PENALTY` per article already placed from the same feed,
+/// and `w_novel * REPEAT_PENALTY` per article already placed with the same
+/// first category. Equal penalized ranks keep the input order.
+pub fn diversify(mut ranked: Vec<(f64, ScoredArticle)>, scoring: &ScoringWeights) -> Vec<ScoredArticle> {
+    let mut placed_feeds: HashMap<String, usize> = HashMap::new();
+    let mut placed_topics: HashMap<String, usize> = HashMap::new();
+    let mut ordered = Vec::with_capacity(ranked.len());
+    while !ranked.is_empty() {
+        let penalized = |(rank, article): &(f64, ScoredArticle)| {
+            let feed_repeats = article.feed_title.as_ref().and_then(|f| placed_feeds.get(f)).copied().unwrap_or(0);
+            let topic_repeats = article.categories.first().and_then(|c| placed_topics.get(c)).copied().unwrap_or(0);
+            rank - REPEAT_PENALTY
+                * (scoring.w_src * feed_repeats as f64 + scoring.w_novel * topic_repeats as f64)
+        };
+        let mut best = 0;
+        for i in 1..ranked.len() {
+            if penalized(&ranked[i]) > penalized(&ranked[best]) {
+                best = i;
+            }
+        }
+        let (_, article) = ranked.remove(best);
+        if let Some(feed) = &article.feed_title {
+            *placed_feeds.entry(feed.clone()).or_insert(0) += 1;
+        }
+        if let Some(topic) = article.categories.first() {
+            *placed_topics.entry(topic.clone()).or_insert(0) += 1;
+        }
+        ordered.push(article);
+    }
+    ordered
+}
+
+/// The user's `keyword_boost` preferences, keyword -> boost.
+pub async fn load_keyword_boosts(pool: &SqlitePool, user_id: i64) -> Result<HashMap<String, f32>> {
+    if !crate::maintenance::table_exists(pool, "user_preferences").await? {
+        return Ok(HashMap::new());
+    }
+    let rows: Vec<(String, f64)> = sqlx::query_as(
+        "SELECT preference_key, preference_value FROM user_preferences
+         WHERE user_id = ? AND preference_type = 'keyword_boost'",
     )
     .bind(user_id)
     .fetch_all(pool)
     .await
-    .context("Failed to fetch top articles per feed")?;
+    .context("Failed to load keyword boosts")?;
+    Ok(rows.into_iter().map(|(k, v)| (k, v as f32)).collect())
+}
 
-    if rows.is_empty() {
-        return Ok(if user.language == "fr" { 
-            "Pas de nouveaux articles trouvés.".to_string() 
-        } else { 
-            "No new articles found.".to_string() 
+/// Add the boost of every keyword found in the article's headline or bullets
+/// (case-insensitive, whole words) to its score, recording the matches in
+/// `matched_keywords`. Returns false when negative boosts bring the score down
+/// to zero or below: the article is suppressed.
+pub fn apply_keyword_boosts(article: &mut ScoredArticle, boosts: &HashMap<String, f32>) -> bool {
+    if boosts.is_empty() {
+        return true;
+    }
+    let text = std::iter::once(&article.headline)
+        .chain(&article.bullets)
+        .map(|t| t.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut total = 0.0;
+    let mut matched: Vec<String> = boosts
+        .keys()
+        .filter(|keyword| contains_word(&text, &keyword.trim().to_lowercase()))
+        .cloned()
+        .collect();
+    matched.sort();
+    for keyword in &matched {
+        total += boosts[keyword] as f64;
+    }
+    article.score += total;
+    article.matched_keywords = matched;
+    total >= 0.0 || article.score > 0.0
+}
+
+/// Whether `needle` occurs in `haystack` with no letter or digit on either side.
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return false;
+    }
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Collapse near-identical articles (wire stories carried by several feeds)
+/// using their `vec_articles` embeddings. `articles` must be in rank order:
+/// each article whose cosine distance to an earlier kept one is below
+/// `max_distance` is dropped and recorded in that article's `duplicates`.
+/// Articles without an embedding are always kept.
+pub async fn dedup_articles(
+    pool: &SqlitePool,
+    articles: Vec<ScoredArticle>,
+    max_distance: f64,
+) -> Result<Vec<ScoredArticle>> {
+    if articles.len() < 2 || !crate::maintenance::table_exists(pool, "vec_articles").await? {
+        return Ok(articles);
+    }
+    let mut embeddings = std::collections::HashMap::new();
+    for article in &articles {
+        let embedding: Option<Vec<u8>> = sqlx::query_scalar("SELECT embedding FROM vec_articles WHERE article_id = ?")
+            .bind(article.id)
+            .fetch_optional(pool)
+            .await
+            .context("failed to load article embedding")?;
+        if let Some(bytes) = embedding {
+            let vector: Vec<f32> = bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+                .collect();
+            embeddings.insert(article.id, vector);
+        }
+    }
+    Ok(collapse_near_duplicates(articles, &embeddings, max_distance))
+}
+
+/// Greedy clustering behind [`dedup_articles`], on already loaded embeddings.
+pub fn collapse_near_duplicates(
+    articles: Vec<ScoredArticle>,
+    embeddings: &std::collections::HashMap<i64, Vec<f32>>,
+    max_distance: f64,
+) -> Vec<ScoredArticle> {
+    let mut kept: Vec<ScoredArticle> = Vec::with_capacity(articles.len());
+    for article in articles {
+        let representative = embeddings.get(&article.id).and_then(|embedding| {
+            kept.iter().position(|k| {
+                embeddings
+                    .get(&k.id)
+                    .is_some_and(|other| cosine_distance(embedding, other) < max_distance)
+            })
         });
+        match representative {
+            Some(i) => kept[i].duplicates.push(DuplicateArticle {
+                id: article.id,
+                feed_title: article.feed_title,
+            }),
+            None => kept.push(article),
+        }
+    }
+    kept
+}
+
+/// 1 - cosine similarity; 1.0 when either vector is zero or sizes differ.
+pub fn cosine_distance(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 1.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0_f64, 0.0_f64, 0.0_f64);
+    for (&x, &y) in a.iter().zip(b) {
+        dot += x as f64 * y as f64;
+        norm_a += x as f64 * x as f64;
+        norm_b += y as f64 * y as f64;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 1.0;
+    }
+    1.0 - dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Pick `count` low-relevance articles, weighted by novelty (1 - relevance).
+async fn fetch_serendipity_articles(
+    pool: &SqlitePool,
+    user_id: i64,
+    count: i64,
+    max_age_hours: Option<i64>,
+) -> Vec<ScoredArticle> {
+    if count <= 0 {
+        return Vec::new();
     }
 
-    // 4. Calculate Final Score with Semantic Similarity & Exponential Half-Life Decay
-    // Fetch user vector
-    let user_vector = crate::personalization::get_user_vector(pool, user_id).await.unwrap_or(None);
-    
-    let mut scored_articles = Vec::new();
-    for row in rows {
-        let feed_id: i64 = row.get("feed_id");
-        let article_id: i64 = row.get("article_id");
-        let relevance_score: f64 = row.get("relevance_score");
-        let created_at_str: String = row.get("created_at");
-        
-        // Semantic Boost
-        let mut semantic_similarity = 0.5; // Default neutral if no vectors
-        if let Some(uv) = &user_vector {
-             // Fetch article vector
-             let article_vec_row = sqlx::query(
-                 "SELECT vec_distance_cosine(v.embedding, ?) as distance 
-                  FROM vec_articles v 
-                  WHERE v.article_id = ?"
-             )
-             .bind(f32_vec_to_bytes(uv))
-             .bind(article_id)
-             .fetch_optional(pool)
-             .await
-             .unwrap_or(None);
-             
-             if let Some(avr) = article_vec_row {
-                  let distance: f64 = avr.get("distance");
-                  semantic_similarity = (1.0 - distance).max(0.0);
-             }
+    // Unseen articles that fell below the relevance threshold
+    let filter = SummaryFilter { relevant: Some(false), unread_only: true, max_age_hours, ..Default::default() };
+    let sql = personalized_summaries_sql(
+        PERSONALIZED_COLUMNS,
+        "ORDER BY a.first_seen_at DESC, uas.article_id ASC LIMIT 50",
+    );
+    let candidates = match sqlx::query_with(&sql, filter.arguments(user_id)).fetch_all(pool).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            error!("Failed to fetch serendipity candidates for user {}: {:?}", user_id, e);
+            return Vec::new();
         }
+    };
+
+    let weights: Vec<(i64, f64)> = candidates
+        .iter()
+        .map(|r| {
+            let relevance: f64 = r.get("relevance_score");
+            (r.get("article_id"), (1.0 - relevance).max(0.05))
+        })
+        .collect();
+    let picked = weighted_sample(&weights, count as usize, &mut rand::thread_rng());
 
-        let created_at = match DateTime::parse_from_rfc3339(&created_at_str) {
-            Ok(dt) => dt.with_timezone(&Utc),
-            Err(_) => {
-                // Try SQLite format: YYYY-MM-DD HH:MM:SS
-                chrono::NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
-                    .map(|ndt| ndt.and_utc())
-                    .unwrap_or_else(|_| Utc::now())
+    let mut by_id: std::collections::HashMap<i64, ScoredArticle> = candidates
+        .iter()
+        .map(|r| (r.get("article_id"), scored_article_from_row(r, true)))
+        .collect();
+    picked.into_iter().filter_map(|id| by_id.remove(&id)).collect()
+}
+
+/// An entry of a user's personalized feed (`GET /api/v1/feed`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonalizedFeedItem {
+    pub id: i64,
+    pub url: String,
+    pub headline: String,
+    pub bullets: Vec<String>,
+    pub details: Option<String>,
+    /// Language the personalized summary was written in
+    pub language: String,
+    pub feed_title: Option<String>,
+    pub categories: Vec<String>,
+    pub relevance_score: f64,
+    pub is_relevant: bool,
+    pub published_at: Option<String>,
+    pub first_seen_at: Option<String>,
+    /// Whether the user already read it
+    pub viewed: bool,
+}
+
+/// A page of a user's personalized summaries matching `filter`, most relevant
+/// first, then most recent: the press review's selection, as a list.
+pub async fn list_personalized_feed(
+    pool: &SqlitePool,
+    user_id: i64,
+    filter: &SummaryFilter,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<PersonalizedFeedItem>> {
+    let columns = format!(
+        "{}, uas.is_relevant, a.published_at, a.first_seen_at,
+        EXISTS (SELECT 1 FROM user_article_views uav
+                WHERE uav.user_id = uas.user_id AND uav.article_id = uas.article_id) as viewed",
+        PERSONALIZED_COLUMNS
+    );
+    let sql = personalized_summaries_sql(&columns, &format!("{} LIMIT ? OFFSET ?", RELEVANCE_ORDER));
+    let mut args = filter.arguments(user_id);
+    args.add(limit);
+    args.add(offset);
+    let rows = sqlx::query_with(&sql, args)
+        .fetch_all(pool)
+        .await
+        .context("Failed to list personalized feed")?;
+
+    Ok(rows
+        .iter()
+        .map(|r| {
+            let article = scored_article_from_row(r, false);
+            PersonalizedFeedItem {
+                id: article.id,
+                url: article.url,
+                headline: article.headline,
+                bullets: article.bullets,
+                details: article.details,
+                language: article.language,
+                feed_title: article.feed_title,
+                categories: article.categories,
+                relevance_score: article.score,
+                is_relevant: r.get("is_relevant"),
+                published_at: r.get("published_at"),
+                first_seen_at: r.get("first_seen_at"),
+                viewed: r.get("viewed"),
             }
+        })
+        .collect())
+}
+
+fn scored_article_from_row(row: &sqlx::sqlite::SqliteRow, serendipity: bool) -> ScoredArticle {
+    let bullets_json: Option<String> = row.get("personalized_bullets");
+    ScoredArticle {
+        id: row.get("article_id"),
+        headline: row.get::<Option<String>, _>("personalized_headline").unwrap_or_default(),
+        bullets: bullets_json
+            .and_then(|j| serde_json::from_str(&j).ok())
+            .unwrap_or_default(),
+        details: row.get("personalized_details"),
+        language: row.get::<Option<String>, _>("language").unwrap_or_else(|| "en".to_string()),
+        source_language: row.get("source_language"),
+        feed_title: row.get("feed_title"),
+        categories: row
+            .get::<Option<String>, _>("categories")
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default(),
+        url: row.get("canonical_url"),
+        score: row.get::<Option<f64>, _>("relevance_score").unwrap_or(0.0),
+        serendipity,
+        duplicates: Vec::new(),
+        matched_keywords: Vec::new(),
+    }
+}
+
+/// Selection options for a `duration_seconds` review, from the `[scoring]` and
+/// `[press_review]` settings, with the user's catch-up status. Shared by the
+/// websocket and REST press reviews so both pick the same articles.
+pub async fn review_selection_options(
+    pool: &SqlitePool,
+    user_id: i64,
+    duration_seconds: i64,
+    reading_speed: i32,
+    config: Option<&common::Config>,
+) -> (ReviewSelectionOptions, Option<CatchUp>) {
+    let serendipity = config
+        .and_then(|c| c.scoring.as_ref())
+        .and_then(|s| s.serendipity)
+        .unwrap_or(0.0);
+    let catch_up_limit = CatchUpLimit::from_config(config);
+    let catch_up = match catch_up_status(pool, user_id, &catch_up_limit).await {
+        Ok(status) => status,
+        Err(e) => {
+            error!("Catch-up status for user {}: {:?}", user_id, e);
+            None
+        }
+    };
+    let opts = ReviewSelectionOptions::for_budget(duration_seconds, reading_speed, serendipity)
+        .with_scoring(ScoringWeights::from_config(config))
+        .with_catch_up(&catch_up_limit, catch_up.as_ref())
+        .with_near_duplicates(near_duplicate_distance(config));
+    (opts, catch_up)
+}
+
+/// A press review rendered as Markdown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressReview {
+    pub summary: String,
+    pub article_count: usize,
+    /// Articles in the review, followed by the near-duplicates collapsed into them
+    #[serde(skip)]
+    pub viewed_ids: Vec<i64>,
+}
+
+/// Generate a personalized press review for a user as Markdown.
+/// Uses the same selection as websocket sessions (see [`review_selection_options`]).
+/// With [`ReviewGroupBy::Topic`], articles are gathered under one heading per topic.
+/// No LLM is called: the review is assembled from the stored personalized summaries.
+pub async fn generate_press_review(
+    pool: &SqlitePool,
+    user_id: i64,
+    duration_seconds: i64,
+    config: Option<&common::Config>,
+    group_by: ReviewGroupBy,
+) -> Result<PressReview> {
+    let user = crate::personalization::get_user_profile(pool, user_id).await?;
+    let (opts, _) = review_selection_options(pool, user_id, duration_seconds, user.reading_speed, config).await;
+
+    info!("Generating press review for user {} (speed: {} wpm, budget: {}s, {} articles)",
+          user_id, user.reading_speed, duration_seconds, opts.limit);
+
+    let articles = select_review_articles(pool, user_id, &opts).await?;
+
+    if articles.is_empty() {
+        let summary = if user.language == "fr" {
+            "Pas de nouveaux articles trouvés.".to_string()
+        } else {
+            "No new articles found.".to_string()
         };
-        
-        let age_secs = (Utc::now() - created_at).num_seconds() as f64;
-        let t_half = feed_half_lives.get(&feed_id).cloned().unwrap_or(864000.0); // Default 10 days
-        
-        let decay_exponent = age_secs / t_half;
-        let freshness_boost = 2.0_f64.powf(-decay_exponent);
-        
-        // Final Score blend: (LLM Relevance * 0.4) + (Semantic Similarity * 0.6)
-        // Then apply freshness decay
-        let blended_score = (relevance_score * 0.4) + (semantic_similarity * 0.6);
-        let final_score = blended_score * freshness_boost;
-        
-        let headline: String = row.get("personalized_headline");
-        let bullets_json: String = row.get("personalized_bullets");
-        
-        scored_articles.push((final_score, article_id, headline, bullets_json));
-    }
-
-    // Sort by final score
-    scored_articles.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-
-    // 5. Budgeting & Formatting
-    let target_words = ((duration_seconds as f64 / 60.0) / 2.0 * reading_speed) as usize;
-    let target_words = target_words.clamp(100, 3000);
+        return Ok(PressReview { summary, article_count: 0, viewed_ids: Vec::new() });
+    }
 
     let mut digest = String::new();
     if user.language == "fr" {
@@ -304,46 +951,370 @@ pub async fn generate_press_review(
         digest.push_str("# Press Review: Dynamic Selection\n\n");
     }
 
-    let mut current_words = 0;
-    let mut article_count = 0;
+    let write_article = |digest: &mut String, article: &ScoredArticle, level: &str| {
+        digest.push_str(&format!(
+            "{} {}\n{}\n\n*Source: {} • [Lire l'article]({})*\n\n", 
+            level,
+            article.headline,
+            article.bullets.iter().map(|b| format!("- {}", b)).collect::<Vec<_>>().join("\n"),
+            article.feed_title.as_deref().unwrap_or("Source"),
+            article.url
+        ));
+    };
 
-    for (_score, article_id, headline, bullets_json) in scored_articles {
-        if current_words >= target_words && article_count >= 3 {
-             break;
+    match group_by {
+        ReviewGroupBy::Source => {
+            for article in &articles {
+                write_article(&mut digest, article, "##");
+            }
+        }
+        ReviewGroupBy::Topic => {
+            // Topics in order of their most relevant article
+            let other = if user.language == "fr" { "Autres sujets" } else { "Other topics" };
+            let mut groups: Vec<(String, Vec<&ScoredArticle>)> = Vec::new();
+            for article in &articles {
+                let label = article.group_label(group_by).unwrap_or_else(|| other.to_string());
+                match groups.iter_mut().find(|(l, _)| *l == label) {
+                    Some((_, members)) => members.push(article),
+                    None => groups.push((label, vec![article])),
+                }
+            }
+            for (label, members) in groups {
+                digest.push_str(&format!("## {}\n\n", label));
+                for article in members {
+                    write_article(&mut digest, article, "###");
+                }
+            }
         }
+    }
 
-        let (feed_title, url): (String, String) = sqlx::query_as(
-            "SELECT f.title, a.canonical_url FROM articles a JOIN article_occurrences ao ON a.id = ao.article_id JOIN feeds f ON ao.feed_id = f.id WHERE a.id = ? LIMIT 1"
-        )
-        .bind(article_id)
-        .fetch_one(pool)
+    info!("Digest generated: {} articles", articles.len());
+    let viewed_ids = articles
+        .iter()
+        .map(|a| a.id)
+        .chain(articles.iter().flat_map(|a| a.duplicates.iter().map(|d| d.id)))
+        .collect();
+    Ok(PressReview { summary: digest, article_count: articles.len(), viewed_ids })
+}
+
+/// Minimum minutes between two generated press reviews for the same user
+/// (`[press_review] min_interval_minutes`, 0 = no cooldown).
+pub fn review_cooldown_minutes(config: Option<&common::Config>) -> i64 {
+    config
+        .and_then(|c| c.press_review.as_ref())
+        .and_then(|p| p.min_interval_minutes)
+        .unwrap_or(0)
+        .max(0)
+}
+
+/// A press review stored in `summaries` after generation.
+#[derive(Debug, Clone)]
+pub struct StoredReview {
+    pub id: i64,
+    pub session_id: i64,
+    pub summary_text: String,
+    pub created_at: Option<String>,
+}
+
+/// `by_model` of the reviews [`generate_press_review`] assembles without an LLM.
+pub const DIGEST_MODEL: &str = "digest";
+
+/// Store a generated review for `session_id` and make it the session's digest.
+pub async fn store_review(pool: &SqlitePool, session_id: i64, summary_text: &str, model: &str) -> Result<i64> {
+    let id = sqlx::query("INSERT INTO summaries (session_id, summary_text, by_model) VALUES (?, ?, ?)")
+        .bind(session_id)
+        .bind(summary_text)
+        .bind(model)
+        .execute(pool)
+        .await
+        .context("failed to store press review")?
+        .last_insert_rowid();
+    attach_review(pool, session_id, id).await?;
+    Ok(id)
+}
+
+/// Point a session's digest at an existing review (used when a review is reused).
+pub async fn attach_review(pool: &SqlitePool, session_id: i64, summary_id: i64) -> Result<()> {
+    sqlx::query("UPDATE sessions SET digest_summary_id = ? WHERE id = ?")
+        .bind(summary_id)
+        .bind(session_id)
+        .execute(pool)
         .await
-        .unwrap_or(("Source".to_string(), "#".to_string()));
-
-        let bullets: Vec<String> = serde_json::from_str(&bullets_json).unwrap_or_default();
-        let article_text = format!(
-            "## {}\n{}\n\n*Source: {} • [Lire l'article]({})*\n\n", 
-            headline,
-            bullets.iter().map(|b| format!("- {}", b)).collect::<Vec<_>>().join("\n"),
-            feed_title,
-            url
+        .context("failed to attach press review to session")?;
+    Ok(())
+}
+
+/// The user's most recent stored review, if generated less than
+/// `cooldown_minutes` ago. Always None when the cooldown is 0. Only websocket
+/// reviews (JSON cards) can be replayed, not Markdown digests.
+pub async fn recent_review(pool: &SqlitePool, user_id: i64, cooldown_minutes: i64) -> Result<Option<StoredReview>> {
+    if cooldown_minutes <= 0 {
+        return Ok(None);
+    }
+    let row = sqlx::query(
+        "SELECT su.id, su.session_id, su.summary_text, su.created_at
+         FROM summaries su
+         JOIN sessions s ON s.id = su.session_id
+         WHERE s.user_id = ?
+           AND su.summary_text IS NOT NULL
+           AND json_valid(su.summary_text) AND json_type(su.summary_text) = 'array'
+           AND su.created_at >= strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?)
+         ORDER BY su.created_at DESC, su.id DESC
+         LIMIT 1",
+    )
+    .bind(user_id)
+    .bind(format!("-{} minutes", cooldown_minutes))
+    .fetch_optional(pool)
+    .await
+    .context("failed to look up recent press review")?;
+
+    Ok(row.map(|r| StoredReview {
+        id: r.get("id"),
+        session_id: r.get("session_id"),
+        summary_text: r.get("summary_text"),
+        created_at: r.get("created_at"),
+    }))
+}
+
+/// Number of press-review slots reserved for serendipity picks.
+/// `fraction` comes from `scoring.serendipity` and is clamped to [0, 1].
+pub fn serendipity_slots(total: i64, fraction: f64) -> i64 {
+    if total <= 0 || !fraction.is_finite() {
+        return 0;
+    }
+    ((total as f64) * fraction.clamp(0.0, 1.0)).round() as i64
+}
+
+/// Weighted random sample without replacement (Efraimidis–Spirakis).
+/// `candidates` are `(id, weight)` pairs; higher weight means more likely to be picked.
+pub fn weighted_sample<R: rand::Rng>(candidates: &[(i64, f64)], n: usize, rng: &mut R) -> Vec<i64> {
+    let mut keyed: Vec<(f64, i64)> = candidates
+        .iter()
+        .map(|&(id, w)| {
+            let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+            (u.powf(1.0 / w.max(1e-6)), id)
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    keyed.into_iter().take(n).map(|(_, id)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recency_decay_curve() {
+        assert_eq!(recency_decay(0.0, 24.0), 1.0);
+        assert!((recency_decay(24.0, 24.0) - 0.5).abs() < 1e-12);
+        assert!((recency_decay(48.0, 24.0) - 0.25).abs() < 1e-12);
+        assert!((recency_decay(6.0, 12.0) - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-12);
+        // Still decreasing well past a day, never negative
+        assert!(recency_decay(25.0, 24.0) > recency_decay(24.0 * 30.0, 24.0));
+        assert!(recency_decay(24.0 * 30.0, 24.0) > 0.0);
+        // Future timestamps (clock skew) count as new; non-positive half-life disables decay
+        assert_eq!(recency_decay(-5.0, 24.0), 1.0);
+        assert_eq!(recency_decay(100.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_recency_rank_breaks_relevance_ties() {
+        let scoring = ScoringWeights { w_src: 0.0, ..Default::default() };
+        // A 25h-old important article still outranks a month-old one
+        assert!(scoring.rank(0.8, 25.0, 0.0, 0.0) > scoring.rank(0.8, 24.0 * 30.0, 0.0, 0.0));
+        // A much more relevant day-old article beats a fresh marginal one
+        assert!(scoring.rank(0.9, 24.0, 0.0, 0.0) > scoring.rank(0.3, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_scoring_weights_from_config() {
+        assert_eq!(ScoringWeights::from_config(None), None);
+        let config: common::Config = toml::from_str(
+            "[database]\npath = \"x.db\"\n[scheduler]\ntimes = []\n[scoring]\nw_pref = 2.0\nw_src = 0.25\nserendipity = 0.5\nrecency_half_life_hours = 12.0",
+        )
+        .unwrap();
+        let scoring = ScoringWeights::from_config(Some(&config)).unwrap();
+        assert_eq!(
+            scoring,
+            ScoringWeights {
+                w_pref: 2.0,
+                w_recency: 1.0,
+                half_life_hours: 12.0,
+                w_src: 0.25,
+                w_novel: 0.75,
+                jitter: 0.005
+            }
         );
+        // 2 * 0.5 + 1 * 0.5 + 0.25 * 2 + 0.75 * 0.4
+        assert!((scoring.rank(0.5, 12.0, 2.0, 0.4) - 2.3).abs() < 1e-12);
+
+        let mut rng = rand::thread_rng();
+        assert!((0..100).map(|_| scoring.jitter(&mut rng)).all(|j| (0.0..0.005).contains(&j)));
+        assert_eq!(ScoringWeights::default().jitter(&mut rng), 0.0);
+    }
 
-        let word_count = article_text.split_whitespace().count();
-        if current_words + word_count > target_words + 200 && article_count >= 3 {
-            break;
+    #[test]
+    fn test_weights_change_the_order() {
+        // (relevance, age in hours, normalized source weight)
+        let fresh_minor_feed = (0.6, 1.0, 0.0);
+        let old_favourite_feed = (0.7, 72.0, normalized_source_weight(10));
+        let order = |scoring: ScoringWeights| {
+            let rank = |(relevance, age, source): (f64, f64, f64)| scoring.rank(relevance, age, source, 0.0);
+            rank(fresh_minor_feed) > rank(old_favourite_feed)
+        };
+        let base = ScoringWeights { w_src: 0.0, w_novel: 0.0, ..Default::default() };
+        assert!(order(base));
+        assert!(!order(ScoringWeights { w_recency: 0.0, ..base }));
+        assert!(order(ScoringWeights { w_src: 0.5, ..base }));
+        assert!(!order(ScoringWeights { w_src: 1.0, ..base }));
+        assert!(!order(ScoringWeights { w_pref: 10.0, ..base }));
+    }
+
+    fn article(id: i64, feed_title: &str) -> ScoredArticle {
+        ScoredArticle {
+            id,
+            headline: format!("Article {}", id),
+            bullets: Vec::new(),
+            details: None,
+            language: "en".to_string(),
+            source_language: None,
+            feed_title: Some(feed_title.to_string()),
+            categories: Vec::new(),
+            url: format!("https://example.com/{}", id),
+            score: 1.0 - id as f64 / 10.0,
+            serendipity: false,
+            duplicates: Vec::new(),
+            matched_keywords: Vec::new(),
         }
+    }
 
-        digest.push_str(&article_text);
-        current_words += word_count;
-        article_count += 1;
+    #[test]
+    fn test_category_novelty() {
+        let seen: HashMap<String, usize> = [("tech".to_string(), 8), ("science".to_string(), 2)].into_iter().collect();
+        let novelty = |categories: &[&str]| {
+            let categories: Vec<String> = categories.iter().map(|c| c.to_string()).collect();
+            category_novelty(&categories, &seen)
+        };
+        assert_eq!(novelty(&["tech"]), 0.0);
+        assert_eq!(novelty(&["science"]), 0.75);
+        assert_eq!(novelty(&["sports"]), 1.0);
+        assert_eq!(novelty(&["tech", "sports"]), 0.5);
+        assert_eq!(novelty(&[]), 0.0);
+        assert_eq!(category_novelty(&["sports".to_string()], &HashMap::new()), 0.0);
     }
 
-    info!("Digest generated: {} articles, ~{} words", article_count, current_words);
-    Ok(digest)
-}
+    #[test]
+    fn test_diversify_spreads_feeds_and_topics() {
+        let ranked = |specs: &[(i64, &str, &str, f64)]| {
+            specs
+                .iter()
+                .map(|&(id, feed, topic, rank)| {
+                    let mut a = article(id, feed);
+                    a.categories = vec![topic.to_string()];
+                    (rank, a)
+                })
+                .collect::<Vec<_>>()
+        };
+        let ids = |articles: Vec<ScoredArticle>| articles.iter().map(|a| a.id).collect::<Vec<_>>();
+        // Three tech stories from one feed ahead of a slightly weaker science one
+        let specs = [(1, "Tech", "tech", 2.0), (2, "Tech", "tech", 1.9), (3, "Tech", "tech", 1.8), (4, "Lab", "science", 1.7)];
 
-/// Helper to convert Vec<f32> to bytes for BLOB storage (bind parameter)
-fn f32_vec_to_bytes(v: &[f32]) -> Vec<u8> {
-    v.iter().flat_map(|f| f.to_le_bytes()).collect()
+        let none = ScoringWeights { w_src: 0.0, w_novel: 0.0, ..Default::default() };
+        assert_eq!(ids(diversify(ranked(&specs), &none)), vec![1, 2, 3, 4]);
+        // 0.25 per repeated feed: the science story comes second
+        let by_feed = ScoringWeights { w_src: 0.5, w_novel: 0.0, ..Default::default() };
+        assert_eq!(ids(diversify(ranked(&specs), &by_feed)), vec![1, 4, 2, 3]);
+        // Same topic from another feed is only spread by w_novel
+        let specs = [(1, "Tech", "tech", 2.0), (2, "Wire", "tech", 1.9), (3, "Lab", "science", 1.7)];
+        assert_eq!(ids(diversify(ranked(&specs), &by_feed)), vec![1, 2, 3]);
+        let by_topic = ScoringWeights { w_src: 0.0, w_novel: 0.75, ..Default::default() };
+        assert_eq!(ids(diversify(ranked(&specs), &by_topic)), vec![1, 3, 2]);
+        // A much stronger repeat still wins its place
+        let specs = [(1, "Tech", "tech", 2.0), (2, "Tech", "tech", 1.9), (3, "Lab", "science", 0.5)];
+        assert_eq!(ids(diversify(ranked(&specs), &by_feed)), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_keyword_boosts_match_whole_words() {
+        let boosts: HashMap<String, f32> =
+            [("Rust".to_string(), 0.3), ("climate change".to_string(), 0.2), ("crypto".to_string(), -1.0)]
+                .into_iter()
+                .collect();
+
+        let mut boosted = article(1, "Tech");
+        boosted.headline = "Rust 2.0 released".to_string();
+        boosted.bullets = vec!["Fights Climate Change, somehow.".to_string()];
+        assert!(apply_keyword_boosts(&mut boosted, &boosts));
+        assert!((boosted.score - 1.4).abs() < 1e-6);
+        assert_eq!(boosted.matched_keywords, vec!["Rust".to_string(), "climate change".to_string()]);
+
+        // Substrings of other words do not count
+        let mut plain = article(2, "Tech");
+        plain.headline = "Trust and cryptography".to_string();
+        assert!(apply_keyword_boosts(&mut plain, &boosts));
+        assert!(plain.matched_keywords.is_empty());
+        assert!((plain.score - 0.8).abs() < 1e-6);
+
+        let mut suppressed = article(3, "Tech");
+        suppressed.headline = "Crypto markets rally".to_string();
+        assert!(!apply_keyword_boosts(&mut suppressed, &boosts));
+    }
+
+    #[test]
+    fn test_collapse_near_duplicates_keeps_best_ranked() {
+        let embeddings: std::collections::HashMap<i64, Vec<f32>> = [
+            (1, vec![1.0, 0.0, 0.0]),
+            (2, vec![0.0, 1.0, 0.0]),
+            (3, vec![0.99, 0.05, 0.0]),
+            (4, vec![1.0, 0.02, 0.0]),
+        ]
+        .into_iter()
+        .collect();
+        let articles = vec![
+            article(1, "Wire"),
+            article(2, "Local"),
+            article(3, "Daily"),
+            article(4, "Wire"),
+            article(5, "Unembedded"),
+        ];
+
+        let kept = collapse_near_duplicates(articles, &embeddings, 0.1);
+        assert_eq!(kept.iter().map(|a| a.id).collect::<Vec<_>>(), vec![1, 2, 5]);
+        assert_eq!(kept[0].duplicates.iter().map(|d| d.id).collect::<Vec<_>>(), vec![3, 4]);
+        // Same feed twice, or the representative's own feed, is named once at most
+        assert_eq!(kept[0].also_covered_by(), vec!["Daily".to_string()]);
+        assert!(kept[1].duplicates.is_empty());
+
+        assert!(cosine_distance(&[1.0, 0.0], &[1.0, 0.0]).abs() < 1e-9);
+        assert!((cosine_distance(&[1.0, 0.0], &[0.0, 1.0]) - 1.0).abs() < 1e-9);
+        assert_eq!(cosine_distance(&[0.0, 0.0], &[1.0, 0.0]), 1.0);
+    }
+
+    #[test]
+    fn test_review_group_by() {
+        assert_eq!(ReviewGroupBy::parse(Some("Topic")), ReviewGroupBy::Topic);
+        assert_eq!(ReviewGroupBy::parse(Some("source")), ReviewGroupBy::Source);
+        assert_eq!(ReviewGroupBy::parse(None), ReviewGroupBy::Source);
+        assert_eq!(topic_label("faits_divers"), "Faits divers");
+        assert_eq!(topic_label("économie"), "Économie");
+    }
+
+    #[test]
+    fn test_serendipity_slot_count() {
+        assert_eq!(serendipity_slots(10, 0.2), 2);
+        assert_eq!(serendipity_slots(15, 0.05), 1);
+        assert_eq!(serendipity_slots(3, 0.05), 0);
+        assert_eq!(serendipity_slots(5, 2.0), 5);
+        assert_eq!(serendipity_slots(5, -1.0), 0);
+    }
+
+    #[test]
+    fn test_weighted_sample_is_distinct_and_bounded() {
+        let mut rng = rand::thread_rng();
+        let candidates = vec![(1, 0.9), (2, 0.1), (3, 0.5), (4, 0.7)];
+        let picked = weighted_sample(&candidates, 2, &mut rng);
+        assert_eq!(picked.len(), 2);
+        assert_ne!(picked[0], picked[1]);
+        assert_eq!(weighted_sample(&candidates, 10, &mut rng).len(), 4);
+    }
 }
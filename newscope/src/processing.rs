@@ -1,100 +1,32 @@
 use anyhow::{Context, Result};
-use sqlx::{SqlitePool, Row};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Connection, SqlitePool, Row};
 use tracing::{info, warn, error};
 use std::sync::Arc;
+use rocket::futures::{stream, StreamExt};
 
 use crate::llm::{LlmProvider, summarizer, LlmRequest};
+use crate::llm::usage::log_llm_result;
 
-/// Helper to create a processing job
-async fn create_processing_job(
-    pool: &SqlitePool,
-    job_type: &str,
-    entity_id: i64,
-    model: &str,
-) -> Result<i64> {
-    let job_id = sqlx::query(
-        "INSERT INTO processing_jobs (job_type, entity_id, status, llm_model, created_at) VALUES (?, ?, 'pending', ?, datetime('now')) RETURNING id"
-    )
-    .bind(job_type)
-    .bind(entity_id)
-    .bind(model)
-    .fetch_one(pool)
-    .await?
-    .get(0);
-    Ok(job_id)
-}
-
-/// Helper to update job status
-async fn update_job_status(
-    pool: &SqlitePool,
-    job_id: i64,
-    status: &str,
-    error: Option<&str>,
-) -> Result<()> {
-    let now = if status == "completed" || status == "failed" {
-        Some(chrono::Utc::now())
-    } else {
-        None
-    };
-
-    let query = if let Some(completed_at) = now {
-        sqlx::query("UPDATE processing_jobs SET status = ?, error_message = ?, completed_at = ? WHERE id = ?")
-            .bind(status)
-            .bind(error)
-            .bind(completed_at)
-            .bind(job_id)
-    } else {
-        sqlx::query("UPDATE processing_jobs SET status = ?, error_message = ?, started_at = datetime('now') WHERE id = ?")
-            .bind(status)
-            .bind(error)
-            .bind(job_id)
-    };
-    
-    query.execute(pool).await?;
-    Ok(())
-}
-
-/// Helper to complete job with stats
-async fn complete_processing_job(
-    pool: &SqlitePool,
-    job_id: i64,
-    prompt_tokens: usize,
-    completion_tokens: usize,
-    processing_time_ms: i64,
-) -> Result<()> {
-    sqlx::query(
-        "UPDATE processing_jobs SET status = 'completed', completed_at = datetime('now'), prompt_tokens = ?, completion_tokens = ?, processing_time_ms = ? WHERE id = ?"
-    )
-    .bind(prompt_tokens as i64)
-    .bind(completion_tokens as i64)
-    .bind(processing_time_ms)
-    .bind(job_id)
-    .execute(pool)
-    .await?;
-    Ok(())
-}
-
-/// Classify article using LLM
+/// Classify an article among `prompts.categories` using the LLM.
 async fn classify_article(
+    pool: &SqlitePool,
     llm_provider: &dyn LlmProvider,
+    model: &str,
+    prompts: &ProcessingPrompts,
     headline: &str,
     summary_bullets: &[String],
 ) -> Result<Vec<String>> {
-    let prompt = format!(
-        "Classify this article into categories (max 3): {}\n\nKey points: {}\n\n\
-         Categories: politics, economy, technology, sports, culture, science, \
-         local_news, international, faits_divers, health, environment\n\n\
-         Return only category names, comma-separated.",
-        headline,
-        summary_bullets.join(", ")
-    );
+    let prompt = prompts.classification_prompt(headline, summary_bullets);
     
     let response = llm_provider.generate(LlmRequest {
         prompt,
         max_tokens: Some(50),
         temperature: Some(0.3),
         timeout_seconds: Some(10),
-    }).await?;
+    }).await;
+    log_llm_result(pool, "classify", model, &response, |r| Some(&r.usage)).await;
+    let response = response?;
     
     Ok(response.content
         .split(',')
@@ -102,226 +34,564 @@ async fn classify_article(
         .collect())
 }
 
-/// Process multiple articles in batch with rate limiting
-pub async fn batch_process_articles(
-    pool: &SqlitePool,
-    article_ids: &[i64],
-    summarization_provider: Arc<dyn LlmProvider>,
-    personalization_provider: Option<Arc<dyn LlmProvider>>,
-    model: &str,
-) -> Result<usize> {
-    if article_ids.is_empty() {
-        return Ok(0);
+/// Summarize with a prompt from `[processing] summarization_prompt_template`
+/// instead of the provider's built-in one.
+async fn summarize_with_prompt(provider: &dyn LlmProvider, prompt: String, max_tokens: usize) -> Result<crate::llm::Summary> {
+    let response = provider
+        .generate(LlmRequest {
+            prompt,
+            max_tokens: Some(max_tokens),
+            temperature: Some(0.5),
+            timeout_seconds: None,
+        })
+        .await?;
+    crate::llm::parse_summary(&response.content, response.usage)
+}
+
+/// Categories offered to the classifier when `[processing] categories` is unset.
+pub const DEFAULT_CATEGORIES: &[&str] = &[
+    "politics",
+    "economy",
+    "technology",
+    "sports",
+    "culture",
+    "science",
+    "local_news",
+    "international",
+    "faits_divers",
+    "health",
+    "environment",
+];
+
+const DEFAULT_CLASSIFICATION_PROMPT: &str = "Classify this article into categories (max 3): {content}\n\n\
+     Categories: {categories}\n\n\
+     Return only category names, comma-separated.";
+
+/// Prompts used to summarize and classify articles, from `[processing]`.
+///
+/// Placeholders: `{content}` (article text, or headline and key points for
+/// classification), `{language}` (summarization only: the article's language
+/// name, empty when unknown) and `{categories}` (classification only).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessingPrompts {
+    pub categories: Vec<String>,
+    /// `None`: the provider's built-in summarization prompt
+    pub summarization_template: Option<String>,
+    pub classification_template: String,
+}
+
+impl Default for ProcessingPrompts {
+    fn default() -> Self {
+        Self {
+            categories: DEFAULT_CATEGORIES.iter().map(|c| c.to_string()).collect(),
+            summarization_template: None,
+            classification_template: DEFAULT_CLASSIFICATION_PROMPT.to_string(),
+        }
     }
-    
-    info!("Processing {} articles with LLM", article_ids.len());
-    let mut processed_count = 0;
-    
-    // Process in batches of 5 to avoid overwhelming the LLM API
-    const BATCH_SIZE: usize = 5;
-    
-    for chunk in article_ids.chunks(BATCH_SIZE) {
-        for &article_id in chunk {
-            match process_single_article(pool, article_id, summarization_provider.clone(), personalization_provider.clone(), model).await {
-                Ok(_) => {
-                    processed_count += 1;
-                }
-                Err(e) => {
-                    error!("Failed to process article {}: {}", article_id, e);
-                    // Continue processing other articles despite error
-                }
-            }
+}
+
+impl ProcessingPrompts {
+    /// Read `[processing]`, falling back to defaults.
+    pub fn from_config(config: Option<&common::Config>) -> Self {
+        let defaults = Self::default();
+        let Some(cfg) = config.and_then(|c| c.processing.as_ref()) else {
+            return defaults;
+        };
+        let categories: Vec<String> = cfg
+            .categories
+            .iter()
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect();
+        Self {
+            categories: if categories.is_empty() { defaults.categories } else { categories },
+            summarization_template: cfg.summarization_prompt_template.clone(),
+            classification_template: cfg
+                .classification_prompt_template
+                .clone()
+                .unwrap_or(defaults.classification_template),
         }
-        
-        // Rate limit: wait 2 seconds between batches
-        if article_ids.len() > BATCH_SIZE {
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+    }
+
+    /// The configured summarization prompt for `content`, `None` to use the
+    /// provider's own.
+    pub fn summarization_prompt(&self, content: &str, language: Option<&str>) -> Option<String> {
+        let language = language.and_then(crate::i18n::language_name).unwrap_or_default();
+        self.summarization_template
+            .as_ref()
+            .map(|t| t.replace("{language}", language).replace("{content}", content))
+    }
+
+    pub fn classification_prompt(&self, headline: &str, bullets: &[String]) -> String {
+        let content = format!("{}\n\nKey points: {}", headline, bullets.join(", "));
+        self.classification_template
+            .replace("{categories}", &self.categories.join(", "))
+            .replace("{content}", &content)
+    }
+}
+
+/// How articles are summarized and classified.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessingOptions {
+    pub truncation: InputTruncation,
+    pub prompts: ProcessingPrompts,
+}
+
+impl ProcessingOptions {
+    pub fn from_config(config: Option<&common::Config>) -> Self {
+        Self {
+            truncation: InputTruncation::from_config(config),
+            prompts: ProcessingPrompts::from_config(config),
         }
     }
-    
-    info!("Processed {}/{} articles successfully", processed_count, article_ids.len());
-    Ok(processed_count)
 }
 
-/// Process a single article: fetch content, summarize, store summary
-async fn process_single_article(
-    pool: &SqlitePool,
-    article_id: i64,
-    summarization_provider: Arc<dyn LlmProvider>,
-    personalization_provider: Option<Arc<dyn LlmProvider>>,
-    model: &str,
-) -> Result<()> {
-    // 1. Create job
-    let job_id = create_processing_job(pool, "article_summary", article_id, model).await?;
-    
-    // 2. Mark running
-    update_job_status(pool, job_id, "running", None).await?;
-    let start_time = std::time::Instant::now();
-
-    let result = async {
-        // Fetch article content from database
-        let row = sqlx::query(
-            "SELECT content, canonical_url FROM articles WHERE id = ?"
-        )
-        .bind(article_id)
-        .fetch_optional(pool)
-        .await
-        .context("Failed to fetch article")?;
+/// Cap on the article text sent for summarization, so long-form pieces don't
+/// overflow the model context or inflate cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputTruncation {
+    /// Maximum characters sent to the LLM (0 disables truncation)
+    pub max_chars: usize,
+    /// Characters kept from the end of the text, within `max_chars`
+    pub keep_tail_chars: usize,
+}
 
-        let Some(row) = row else {
-            warn!("Article {} not found, skipping", article_id);
-            return Ok((0, 0));
-        };
+impl Default for InputTruncation {
+    fn default() -> Self {
+        Self {
+            max_chars: 12_000,
+            keep_tail_chars: 0,
+        }
+    }
+}
 
-        let content: String = row.get("content");
-        let url: String = row.get("canonical_url");
-        
-        // If content is too short (< 100 chars), try scraping the full article
-        let final_content = if content.len() < 100 {
-            info!("Article {} has short content ({}), attempting to scrape from {}", 
-                  article_id, content.len(), url);
-            
-            match crate::scraping::scrape_article_content(&url, 10).await {
-                Ok(scraped) => {
-                    info!("Successfully scraped article {}, got {} chars", article_id, scraped.len());
-                    scraped
-                }
-                Err(e) => {
-                    warn!("Failed to scrape article {}: {}, using original content", article_id, e);
-                    content
-                }
-            }
-        } else {
-            content
+impl InputTruncation {
+    /// Read `[llm.summarization_input]`, falling back to defaults.
+    pub fn from_config(config: Option<&common::Config>) -> Self {
+        let defaults = Self::default();
+        let Some(cfg) = config.and_then(|c| c.llm.as_ref()).and_then(|l| l.summarization_input.as_ref()) else {
+            return defaults;
         };
+        Self {
+            max_chars: cfg.max_chars.unwrap_or(defaults.max_chars),
+            keep_tail_chars: cfg.keep_tail_chars.unwrap_or(defaults.keep_tail_chars),
+        }
+    }
 
-        // Skip if still too short after scraping attempt
-        if final_content.len() < 50 {
-            info!("Article {} content too short even after scraping ({}), skipping summarization", 
-                  article_id, final_content.len());
-            return Ok((0, 0));
+    /// Truncate `text` to `max_chars`, keeping the beginning and, if configured,
+    /// the last `keep_tail_chars`. Returns None when the text already fits.
+    pub fn apply(&self, text: &str) -> Option<String> {
+        let total = text.chars().count();
+        if self.max_chars == 0 || total <= self.max_chars {
+            return None;
         }
-        
-        // Convert HTML to Markdown for cleaner LLM input
-        let markdown_content = html2text::from_read(final_content.as_bytes(), 80)
-            .context("Failed to convert HTML to Markdown")?;
-        
-        // Summarize
-        let summary = summarizer::summarize_article(summarization_provider.as_ref(), &markdown_content, 500).await;
-        
-        // Classify
-        let categories = classify_article(
-            summarization_provider.as_ref(),
-            &summary.headline,
-            &summary.bullets
-        ).await.unwrap_or_default();
-        
-        let bullets_json = serde_json::to_string(&summary.bullets)?;
-        let categories_json = serde_json::to_string(&categories)?;
-
-        // Store summary
-        sqlx::query(
-            "INSERT OR REPLACE INTO article_summaries \
-             (article_id, headline, bullets_json, details, model, categories, \
-              prompt_tokens, completion_tokens) \
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
-        )
-        .bind(article_id)
-        .bind(&summary.headline)
-        .bind(&bullets_json)
-        .bind(&summary.details)
-        .bind(model)
-        .bind(&categories_json)
-        .bind(summary.usage.prompt_tokens as i32)
-        .bind(summary.usage.completion_tokens as i32)
-        .execute(pool)
-        .await?;
-        
-        // Mark article as processed
-        sqlx::query(
-            "UPDATE articles SET processing_status = 'completed', processed_at = ? WHERE id = ?"
-        )
-        .bind(chrono::Utc::now())
-        .bind(article_id)
-        .execute(pool)
-        .await?;
-        
-        // 4. Personalize for all active users (Phase 8: NEW!)
-        if let Some(personalization_llm) = personalization_provider {
-            info!("Starting personalization for article {} for active users", article_id);
-            match crate::personalize_worker::personalize_for_users(
-                pool,
-                article_id,
-                &summary,
-                personalization_llm,
-                model,
-            )
-            .await
-            {
-                Ok(count) => {
-                    info!(
-                        "Successfully personalized article {} for {} users",
-                        article_id, count
-                    );
-                }
-                Err(e) => {
-                    // Don't fail the whole job if personalization fails
-                    warn!(
-                        "Failed to personalize article {} for users: {}",
-                        article_id, e
-                    );
-                }
-            }
+        let tail = self.keep_tail_chars.min(self.max_chars / 2);
+        let head: String = text.chars().take(self.max_chars - tail).collect();
+        if tail == 0 {
+            return Some(format!("{}\n\n[…]", head));
         }
-        
-        Ok::<_, anyhow::Error>((summary.usage.prompt_tokens, summary.usage.completion_tokens))
-    }.await;
+        let tail_text: String = text.chars().skip(total - tail).collect();
+        Some(format!("{}\n\n[…]\n\n{}", head, tail_text))
+    }
+}
 
-    let processing_time = start_time.elapsed().as_millis() as i64;
+/// Article fields available to an [`EmbeddingText`] template.
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingSource {
+    pub title: String,
+    pub headline: Option<String>,
+    pub bullets: Option<Vec<String>>,
+    pub details: Option<String>,
+    pub categories: Vec<String>,
+    pub content: String,
+}
 
-    match result {
-        Ok((prompt_tokens, completion_tokens)) => {
-            complete_processing_job(pool, job_id, prompt_tokens, completion_tokens, processing_time).await?;
+/// How the text sent to the embedding model is composed, from
+/// `[llm.embedding_text]`.
+///
+/// Placeholders: `{title}`, `{headline}`, `{bullets}`, `{details}`,
+/// `{categories}`, `{content}` and `{summary}` (headline and bullets, or the
+/// first 500 characters of content when the article has no summary yet).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddingText {
+    pub template: String,
+    /// Maximum characters embedded (0 = no cap)
+    pub max_chars: usize,
+}
+
+impl Default for EmbeddingText {
+    fn default() -> Self {
+        Self {
+            template: "{title}\n{summary}".to_string(),
+            max_chars: 0,
         }
-        Err(e) => {
-            update_job_status(pool, job_id, "failed", Some(&e.to_string())).await?;
-            return Err(e);
+    }
+}
+
+impl EmbeddingText {
+    /// Read `[llm.embedding_text]`, falling back to defaults.
+    pub fn from_config(config: Option<&common::Config>) -> Self {
+        let defaults = Self::default();
+        let Some(cfg) = config.and_then(|c| c.llm.as_ref()).and_then(|l| l.embedding_text.as_ref()) else {
+            return defaults;
+        };
+        Self {
+            template: cfg.template.clone().unwrap_or(defaults.template),
+            max_chars: cfg.max_chars.unwrap_or(defaults.max_chars),
         }
     }
 
-    Ok(())
+    pub fn render(&self, source: &EmbeddingSource) -> String {
+        let bullets = source.bullets.as_ref().map(|b| b.join(" ")).unwrap_or_default();
+        let summary = match (&source.headline, &source.bullets) {
+            (Some(h), Some(_)) => format!("{}\n{}", h, bullets),
+            _ => source.content.chars().take(500).collect(),
+        };
+        let categories = source.categories.join(", ");
+        let text = fill_placeholders(
+            &self.template,
+            &[
+                ("title", &source.title),
+                ("headline", source.headline.as_deref().unwrap_or("")),
+                ("bullets", &bullets),
+                ("details", source.details.as_deref().unwrap_or("")),
+                ("categories", &categories),
+                ("content", &source.content),
+                ("summary", &summary),
+            ],
+        );
+        if self.max_chars == 0 {
+            text
+        } else {
+            text.chars().take(self.max_chars).collect()
+        }
+    }
+
+    /// Identifies the composition; stored embeddings built with a different
+    /// fingerprint are recomputed (see [`sync_embedding_text`]).
+    pub fn fingerprint(&self) -> String {
+        use sha2::{Digest, Sha256};
+        format!("{:x}", Sha256::digest(format!("{}\0{}", self.template, self.max_chars).as_bytes()))
+    }
+}
+
+/// Replace each `{name}` of `template` with its value in a single pass, so
+/// placeholders appearing inside the values (an article quoting `{title}`)
+/// stay as written. Unknown placeholders are kept.
+fn fill_placeholders(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let value = tail.find('}').and_then(|end| {
+            values
+                .iter()
+                .find(|(name, _)| *name == &tail[1..end])
+                .map(|(_, value)| (end, *value))
+        });
+        match value {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
 }
 
+/// `app_settings` key holding the fingerprint of the embedding text composition.
+const EMBEDDING_TEXT_SETTING: &str = "embedding_text_fingerprint";
 
-/// Process all pending articles (those with processing_status = 'pending')
-pub async fn process_pending_articles(
+/// Keep article embeddings consistent with the configured composition: when it
+/// changed since the embeddings were computed, all article vectors are dropped
+/// so the embedding pass recomputes them. Returns the number of vectors dropped.
+/// A database without a stored fingerprint is assumed to use the default.
+pub async fn sync_embedding_text(pool: &SqlitePool, text: &EmbeddingText) -> Result<u64> {
+    let stored = sqlx::query_scalar::<_, String>("SELECT value FROM app_settings WHERE key = ?")
+        .bind(EMBEDDING_TEXT_SETTING)
+        .fetch_optional(pool)
+        .await
+        .context("failed to read embedding text fingerprint")?
+        .unwrap_or_else(|| EmbeddingText::default().fingerprint());
+
+    let current = text.fingerprint();
+    let mut cleared = 0;
+    if stored != current && crate::maintenance::table_exists(pool, "vec_articles").await? {
+        cleared = sqlx::query("DELETE FROM vec_articles")
+            .execute(pool)
+            .await
+            .context("failed to clear article embeddings")?
+            .rows_affected();
+        sqlx::query("DELETE FROM article_vector_models")
+            .execute(pool)
+            .await
+            .context("failed to clear embedding model records")?;
+        info!("Embedding text composition changed: {} article embeddings will be recomputed", cleared);
+    }
+    sqlx::query(
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value,
+             updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+    )
+    .bind(EMBEDDING_TEXT_SETTING)
+    .bind(&current)
+    .execute(pool)
+    .await
+    .context("failed to store embedding text fingerprint")?;
+    Ok(cleared)
+}
+
+/// Summarize and classify an article, store the summary, index it for search
+/// and mark the article completed. Returns `None` when the article is gone or
+/// has too little content to summarize (it then stays pending).
+pub async fn summarize_article(
     pool: &SqlitePool,
-    summarization_provider: Arc<dyn LlmProvider>,
-    personalization_provider: Option<Arc<dyn LlmProvider>>,
+    article_id: i64,
+    summarization_provider: &dyn LlmProvider,
     model: &str,
-    limit: Option<usize>,
-) -> Result<usize> {
-    // Find pending articles
-    let limit_clause = limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default();
-    let query = format!(
-        "SELECT id FROM articles WHERE processing_status = 'pending' ORDER BY first_seen_at DESC {}",
-        limit_clause
-    );
+    options: &ProcessingOptions,
+) -> Result<Option<crate::llm::Summary>> {
+    // Fetch article content from database
+    let row = sqlx::query(
+        "SELECT content, canonical_url, language FROM articles WHERE id = ?"
+    )
+    .bind(article_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to fetch article")?;
+
+    let Some(row) = row else {
+        warn!("Article {} not found, skipping", article_id);
+        return Ok(None);
+    };
+
+    let content: String = row.get("content");
+    let url: String = row.get("canonical_url");
+    let language: Option<String> = row.get("language");
     
-    let rows = sqlx::query(&query)
-        .fetch_all(pool)
-        .await
-        .context("Failed to fetch pending articles")?;
+    // If content is too short (< 100 chars), try scraping the full article
+    let final_content = if content.len() < 100 {
+        info!("Article {} has short content ({}), attempting to scrape from {}", 
+              article_id, content.len(), url);
+        
+        match crate::scraping::scrape_article_content(&url, 10).await {
+            Ok(scraped) => {
+                info!("Successfully scraped article {}, got {} chars", article_id, scraped.len());
+                scraped
+            }
+            Err(e) => {
+                warn!("Failed to scrape article {}: {}, using original content", article_id, e);
+                content
+            }
+        }
+    } else {
+        content
+    };
+
+    // Skip if still too short after scraping attempt
+    if final_content.len() < 50 {
+        info!("Article {} content too short even after scraping ({}), skipping summarization", 
+              article_id, final_content.len());
+        return Ok(None);
+    }
     
-    let article_ids: Vec<i64> = rows.iter().map(|r| r.get("id")).collect();
+    // Convert HTML to Markdown for cleaner LLM input
+    let mut markdown_content = html2text::from_read(final_content.as_bytes(), 80)
+        .context("Failed to convert HTML to Markdown")?;
+
+    if let Some(truncated) = options.truncation.apply(&markdown_content) {
+        info!("Article {} truncated for summarization ({} -> {} chars)",
+              article_id, markdown_content.chars().count(), truncated.chars().count());
+        markdown_content = truncated;
+    }
     
-    if article_ids.is_empty() {
-        info!("No pending articles to process");
-        return Ok(0);
+    // Summarize
+    let result = match options.prompts.summarization_prompt(&markdown_content, language.as_deref()) {
+        Some(prompt) => summarize_with_prompt(summarization_provider, prompt, 500).await,
+        None => summarization_provider.summarize(&markdown_content, language.as_deref(), 500).await,
+    };
+    log_llm_result(pool, "summarize", model, &result, |s| Some(&s.usage)).await;
+    let summary = summarizer::with_fallback(result, &markdown_content);
+    
+    // Classify
+    let categories = classify_article(
+        pool,
+        summarization_provider,
+        model,
+        &options.prompts,
+        &summary.headline,
+        &summary.bullets
+    ).await.unwrap_or_default();
+    
+    let bullets_json = serde_json::to_string(&summary.bullets)?;
+    let categories_json = serde_json::to_string(&categories)?;
+
+    // Store summary
+    sqlx::query(
+        "INSERT OR REPLACE INTO article_summaries \
+         (article_id, headline, bullets_json, details, model, categories, \
+          prompt_tokens, completion_tokens) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(article_id)
+    .bind(&summary.headline)
+    .bind(&bullets_json)
+    .bind(&summary.details)
+    .bind(model)
+    .bind(&categories_json)
+    .bind(summary.usage.prompt_tokens as i32)
+    .bind(summary.usage.completion_tokens as i32)
+    .execute(pool)
+    .await?;
+    if let Err(e) = crate::search::index_article(pool, article_id).await {
+        warn!("Failed to index article {} for search: {:?}", article_id, e);
     }
     
-    info!("Found {} pending articles to process", article_ids.len());
-    batch_process_articles(pool, &article_ids, summarization_provider, personalization_provider, model).await
+    // Mark article as processed
+    sqlx::query(
+        "UPDATE articles SET processing_status = 'completed', processed_at = ? WHERE id = ?"
+    )
+    .bind(chrono::Utc::now())
+    .bind(article_id)
+    .execute(pool)
+    .await?;
+
+    Ok(Some(summary))
+}
+
+/// The stored generic summary of an article, if it has one.
+pub async fn load_summary(pool: &SqlitePool, article_id: i64) -> Result<Option<crate::llm::Summary>> {
+    let row = sqlx::query("SELECT headline, bullets_json, details FROM article_summaries WHERE article_id = ?")
+        .bind(article_id)
+        .fetch_optional(pool)
+        .await
+        .context("failed to load article summary")?;
+    Ok(row.map(|row| crate::llm::Summary {
+        headline: row.get::<Option<String>, _>("headline").unwrap_or_default(),
+        bullets: row
+            .get::<Option<String>, _>("bullets_json")
+            .and_then(|b| serde_json::from_str(&b).ok())
+            .unwrap_or_default(),
+        details: row.get("details"),
+        usage: Default::default(),
+    }))
+}
+
+/// Dimension of the vectors stored in `vec_articles` and `vec_users`.
+pub const EMBEDDING_DIM: usize = 384;
+
+/// Default `concurrency` of `[llm.embedding]`: embed requests in flight during
+/// bulk embedding.
+pub const DEFAULT_EMBEDDING_CONCURRENCY: usize = 4;
+
+/// Expected embedding size: `embedding_dim` of `[llm.embedding]` (or
+/// `[llm.remote]`), [`EMBEDDING_DIM`] by default.
+pub fn embedding_dim(config: Option<&common::Config>) -> usize {
+    config
+        .and_then(|c| c.llm.as_ref())
+        .and_then(|l| l.embedding.as_ref().or(l.remote.as_ref()))
+        .and_then(|r| r.embedding_dim)
+        .unwrap_or(EMBEDDING_DIM)
+}
+
+/// Whether an embedding endpoint is configured: an adapter other than
+/// `none` and, for `remote`, an `[llm.embedding]` or `[llm.remote]` section.
+/// Without one, vectors come from the [`HashingEmbedder`].
+pub fn embedding_configured(config: Option<&common::Config>) -> bool {
+    config.and_then(|c| c.llm.as_ref()).is_some_and(|llm| match llm.adapter.as_deref().unwrap_or("none") {
+        "none" => false,
+        "remote" => crate::llm::LlmTask::Embedding.endpoint_config(llm).is_some(),
+        _ => true,
+    })
+}
+
+/// Model name article vectors are recorded under: the configured embedding
+/// model, or [`LOCAL_EMBEDDING_MODEL`] when [`embedding_configured`] is false.
+pub fn embedding_model(config: Option<&common::Config>) -> String {
+    if embedding_configured(config) {
+        crate::llm::LlmTask::Embedding.model(config)
+    } else {
+        LOCAL_EMBEDDING_MODEL.to_string()
+    }
+}
+
+/// Model name reported for [`HashingEmbedder`] vectors.
+pub const LOCAL_EMBEDDING_MODEL: &str = "local-ngram-hash";
+
+/// Deterministic embedder used when no embedding-capable LLM is configured:
+/// word and character-trigram counts hashed into a fixed-size vector, then
+/// L2-normalized. It only captures lexical overlap, but keeps similarity
+/// search and personalization working without a model.
+pub struct HashingEmbedder {
+    dim: usize,
+}
+
+impl HashingEmbedder {
+    pub fn new(dim: usize) -> Self {
+        Self { dim: dim.max(1) }
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(EMBEDDING_DIM)
+    }
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for HashingEmbedder {
+    async fn generate(&self, _request: LlmRequest) -> Result<crate::llm::LlmResponse> {
+        anyhow::bail!("{} only computes embeddings", LOCAL_EMBEDDING_MODEL)
+    }
+
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> Result<crate::llm::Summary> {
+        anyhow::bail!("{} only computes embeddings", LOCAL_EMBEDDING_MODEL)
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(hashed_embedding(text, self.dim))
+    }
+}
+
+/// 64-bit FNV-1a, stable across runs and platforms (unlike `DefaultHasher`).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Hashed term-frequency vector of the lowercased words of `text` and of their
+/// character trigrams (so inflections still overlap), L2-normalized. The hash
+/// also picks each feature's sign, which keeps collisions from biasing the
+/// vector. Text without any word gives the zero vector.
+pub fn hashed_embedding(text: &str, dim: usize) -> Vec<f32> {
+    let mut vector = vec![0f32; dim];
+    let mut add = |feature: &str, weight: f32| {
+        let hash = fnv1a(feature.as_bytes());
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % dim as u64) as usize] += sign * weight;
+    };
+
+    for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        let word = word.to_lowercase();
+        add(&format!("w:{}", word), 1.0);
+        let padded: Vec<char> = format!(" {} ", word).chars().collect();
+        for trigram in padded.windows(3) {
+            add(&format!("c:{}", trigram.iter().collect::<String>()), 0.5);
+        }
+    }
+
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
 }
 
 /// Convert Vec<f32> to Vec<u8> (Little Endian bytes) for BLOB storage
@@ -329,12 +599,19 @@ fn f32_vec_to_bytes(v: &[f32]) -> Vec<u8> {
     v.iter().flat_map(|f| f.to_le_bytes()).collect()
 }
 
-/// Process articles missing embeddings
+/// Process articles missing embeddings.
+/// Up to `concurrency` embed requests run in parallel; results are inserted in
+/// batches and a failure on one article doesn't abort the pass. A vector that
+/// is not `dim` long does: the model doesn't match the configured
+/// `embedding_dim`, so the rest of the pass would fail the same way.
 pub async fn process_missing_embeddings(
     pool: &SqlitePool,
     provider: Arc<dyn LlmProvider>,
-    _model: &str,
+    model: &str,
     limit: usize,
+    concurrency: usize,
+    dim: usize,
+    text: &EmbeddingText,
 ) -> Result<usize> {
     // 1. Find articles needing embeddings
     let rows = sqlx::query(
@@ -344,6 +621,8 @@ pub async fn process_missing_embeddings(
             a.title, 
             s.headline, 
             s.bullets_json, 
+            s.details,
+            s.categories,
             a.content
         FROM articles a
         LEFT JOIN article_summaries s ON a.id = s.article_id
@@ -362,65 +641,462 @@ pub async fn process_missing_embeddings(
         return Ok(0);
     }
 
-    info!("Found {} articles missing embeddings", rows.len());
+    info!("Found {} articles missing embeddings (model: {})", rows.len(), model);
+
+    let inputs: Vec<(i64, String)> = rows
+        .iter()
+        .map(|article| (article.get("id"), text.render(&embedding_source(article))))
+        .collect();
+
+    // 2. Embed concurrently
+    let mut results = stream::iter(inputs)
+        .map(|(article_id, text)| {
+            let provider = provider.clone();
+            async move { (article_id, provider.embed(&text).await) }
+        })
+        .buffer_unordered(concurrency.max(1));
+
+    // 3. Insert in batches
     let mut count = 0;
+    let mut batch: Vec<(i64, Vec<u8>)> = Vec::with_capacity(EMBEDDING_INSERT_BATCH);
+    while let Some((article_id, result)) = results.next().await {
+        log_llm_result(pool, "embed", model, &result, |_| None).await;
+        match result {
+            Ok(embedding) if embedding.len() != dim => {
+                if !batch.is_empty() {
+                    insert_embeddings(pool, model, batch).await;
+                }
+                return Err(dimension_mismatch(model, embedding.len(), dim));
+            }
+            Ok(embedding) => batch.push((article_id, f32_vec_to_bytes(&embedding))),
+            Err(e) => error!("Failed to embed article {}: {}", article_id, e),
+        }
+        if batch.len() >= EMBEDDING_INSERT_BATCH {
+            count += insert_embeddings(pool, model, std::mem::take(&mut batch)).await;
+        }
+    }
+    if !batch.is_empty() {
+        count += insert_embeddings(pool, model, batch).await;
+    }
 
-    for article in rows {
-        // Construct text to embed: Title + Summary (or truncated content)
-        let article_id: i64 = article.get("id");
-        let title: String = article.get("title");
-        let headline: Option<String> = article.get("headline");
-        let bullets_json: Option<String> = article.get("bullets_json");
-        let content: String = article.get("content");
-        
-        let mut summary_text = String::new();
-        let has_summary = headline.is_some() && bullets_json.is_some();
-        
-        if has_summary {
-             let h = headline.unwrap();
-             let b_json = bullets_json.unwrap();
-             if let Ok(bullets) = serde_json::from_str::<Vec<String>>(&b_json) {
-                 summary_text = format!("{}\n{}", h, bullets.join(" "));
-             }
+    Ok(count)
+}
+
+/// Article fields for an [`EmbeddingText`], from a row of `articles a LEFT
+/// JOIN article_summaries s` selecting `a.title, s.headline, s.bullets_json,
+/// s.details, s.categories, a.content`.
+fn embedding_source(row: &SqliteRow) -> EmbeddingSource {
+    EmbeddingSource {
+        title: row.get("title"),
+        headline: row.get("headline"),
+        bullets: row
+            .get::<Option<String>, _>("bullets_json")
+            .and_then(|b| serde_json::from_str(&b).ok()),
+        details: row.get("details"),
+        categories: row
+            .get::<Option<String>, _>("categories")
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default(),
+        content: row.get("content"),
+    }
+}
+
+fn dimension_mismatch(model: &str, len: usize, dim: usize) -> anyhow::Error {
+    anyhow::anyhow!(
+        "embedding model {} returned a {}-dimensional vector but embedding_dim is {}; \
+         set [llm.embedding] embedding_dim to the model's size and POST /api/v1/admin/reembed",
+        model,
+        len,
+        dim
+    )
+}
+
+/// Embed one article unless it already has a vector (or no longer exists).
+/// Returns whether a vector was written.
+pub async fn embed_article(
+    pool: &SqlitePool,
+    provider: &dyn LlmProvider,
+    model: &str,
+    article_id: i64,
+    dim: usize,
+    text: &EmbeddingText,
+) -> Result<bool> {
+    let row = sqlx::query(
+        r#"
+        SELECT a.title, s.headline, s.bullets_json, s.details, s.categories, a.content
+        FROM articles a
+        LEFT JOIN article_summaries s ON a.id = s.article_id
+        LEFT JOIN vec_articles v ON a.id = v.article_id
+        WHERE a.id = ? AND v.article_id IS NULL
+        "#,
+    )
+    .bind(article_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to fetch article for embedding")?;
+    let Some(row) = row else {
+        return Ok(false);
+    };
+
+    let result = provider.embed(&text.render(&embedding_source(&row))).await;
+    log_llm_result(pool, "embed", model, &result, |_| None).await;
+    let embedding = result?;
+    if embedding.len() != dim {
+        return Err(dimension_mismatch(model, embedding.len(), dim));
+    }
+    let mut tx = pool.begin().await?;
+    sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (?, ?)")
+        .bind(article_id)
+        .bind(f32_vec_to_bytes(&embedding))
+        .execute(&mut tx)
+        .await
+        .context("Failed to store embedding")?;
+    record_vector_model(&mut tx, article_id, model).await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Record that `model` produced the `vec_articles` vector of `article_id`.
+async fn record_vector_model(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    article_id: i64,
+    model: &str,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO article_vector_models (article_id, model) VALUES (?, ?)
+         ON CONFLICT(article_id) DO UPDATE SET model = excluded.model, embedded_at = excluded.embedded_at",
+    )
+    .bind(article_id)
+    .bind(model)
+    .execute(&mut *tx)
+    .await
+    .context("Failed to record embedding model")?;
+    Ok(())
+}
+
+/// Drop the article vectors computed by another model than `model`, so they
+/// are recomputed instead of compared with incompatible ones. Vectors stored
+/// before models were recorded are assumed to come from `model`. Returns the
+/// number of vectors dropped.
+pub async fn sync_embedding_model(pool: &SqlitePool, model: &str) -> Result<u64> {
+    if !crate::maintenance::table_exists(pool, "vec_articles").await? {
+        return Ok(0);
+    }
+    let mut tx = pool.begin().await?;
+    let dropped = sqlx::query(
+        "DELETE FROM vec_articles WHERE article_id IN
+         (SELECT article_id FROM article_vector_models WHERE model != ?)",
+    )
+    .bind(model)
+    .execute(&mut tx)
+    .await
+    .context("failed to drop embeddings of another model")?
+    .rows_affected();
+    sqlx::query("DELETE FROM article_vector_models WHERE model != ?")
+        .bind(model)
+        .execute(&mut tx)
+        .await
+        .context("failed to drop embedding model records")?;
+    sqlx::query(
+        "INSERT INTO article_vector_models (article_id, model)
+         SELECT v.article_id, ? FROM vec_articles v
+         WHERE v.article_id NOT IN (SELECT article_id FROM article_vector_models)
+           AND v.article_id IN (SELECT id FROM articles)",
+    )
+    .bind(model)
+    .execute(&mut tx)
+    .await
+    .context("failed to record embedding models")?;
+    tx.commit().await?;
+    if dropped > 0 {
+        info!("Embedding model is now {}: {} article embeddings will be recomputed", model, dropped);
+    }
+    Ok(dropped)
+}
+
+/// Articles handled per pass by [`embed_missing_until`].
+const BACKFILL_PASS_SIZE: usize = 100;
+
+/// Backfill embeddings in successive passes until `limit` articles were
+/// embedded or, without a limit, until a pass makes no progress (nothing left,
+/// or only articles the provider keeps failing on). Returns the number embedded.
+pub async fn embed_missing_until(
+    pool: &SqlitePool,
+    provider: Arc<dyn LlmProvider>,
+    model: &str,
+    limit: Option<usize>,
+    concurrency: usize,
+    dim: usize,
+    text: &EmbeddingText,
+) -> Result<usize> {
+    let mut total = 0;
+    loop {
+        let pass = match limit {
+            Some(limit) => (limit - total).min(BACKFILL_PASS_SIZE),
+            None => BACKFILL_PASS_SIZE,
+        };
+        if pass == 0 {
+            break;
         }
-        
-        if summary_text.is_empty() {
-             // Fallback to first 500 chars of content
-             summary_text = content.chars().take(500).collect();
+        let embedded = process_missing_embeddings(pool, provider.clone(), model, pass, concurrency, dim, text).await?;
+        total += embedded;
+        if embedded == 0 {
+            break;
         }
+    }
+    Ok(total)
+}
 
-        let text_to_embed = format!("{}\n{}", title, summary_text);
-        
-        // Call LLM Embed
-        match provider.embed(&text_to_embed).await {
-            Ok(embedding) => {
-                let bytes = f32_vec_to_bytes(&embedding);
-                
-                sqlx::query(
-                    "INSERT INTO vec_articles (article_id, embedding) VALUES (?, ?)"
-                )
-                .bind(article_id)
-                .bind(bytes)
-                .execute(pool)
-                .await?;
-                
-                count += 1;
+/// Number of articles without a vector in `vec_articles`.
+pub async fn count_missing_embeddings(pool: &SqlitePool) -> Result<i64> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM articles a WHERE NOT EXISTS (SELECT 1 FROM vec_articles v WHERE v.article_id = a.id)",
+    )
+    .fetch_one(pool)
+    .await
+    .context("failed to count articles missing embeddings")
+}
+
+/// An article close to another one in embedding space.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SimilarArticle {
+    pub article_id: i64,
+    pub headline: Option<String>,
+    pub url: String,
+    /// Cosine distance to the source article (0 = same direction)
+    pub distance: f64,
+}
+
+/// Nearest neighbours fetched from `vec_articles` per article returned by
+/// [`find_similar_articles`]: the KNN search can't see subscriptions, so it
+/// over-fetches before the other users' articles are filtered out.
+const SIMILAR_CANDIDATES_PER_RESULT: i64 = 10;
+
+/// Upper bound on the KNN candidates of one [`find_similar_articles`] call.
+const MAX_SIMILAR_CANDIDATES: i64 = 1000;
+
+/// The `k` articles nearest to `article_id` among those `user_id` can see (an
+/// occurrence in one of their subscribed feeds), closest first. `None` when the
+/// user can't see `article_id` itself; empty when it has no embedding yet.
+///
+/// Candidates come from the vec0 KNN index (L2 distance, which ranks
+/// unit-length embeddings like cosine distance); the reported distance is the
+/// cosine one.
+pub async fn find_similar_articles(
+    pool: &SqlitePool,
+    user_id: i64,
+    article_id: i64,
+    k: i64,
+) -> Result<Option<Vec<SimilarArticle>>> {
+    let visible: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM article_occurrences ao
+            JOIN subscriptions sub ON sub.feed_id = ao.feed_id
+            WHERE ao.article_id = ? AND sub.user_id = ?
+        )
+        "#,
+    )
+    .bind(article_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await
+    .context("failed to check article visibility")?;
+    if !visible {
+        return Ok(None);
+    }
+
+    if !crate::maintenance::table_exists(pool, "vec_articles").await? {
+        return Ok(Some(Vec::new()));
+    }
+    let embedding: Option<Vec<u8>> = sqlx::query_scalar("SELECT embedding FROM vec_articles WHERE article_id = ?")
+        .bind(article_id)
+        .fetch_optional(pool)
+        .await
+        .context("failed to load article embedding")?;
+    let Some(embedding) = embedding else {
+        return Ok(Some(Vec::new()));
+    };
+
+    let candidates = (k.saturating_mul(SIMILAR_CANDIDATES_PER_RESULT) + 1).min(MAX_SIMILAR_CANDIDATES);
+    let rows = sqlx::query(
+        r#"
+        WITH knn AS (
+            SELECT article_id, embedding
+            FROM vec_articles
+            WHERE embedding MATCH ? AND k = ?
+        )
+        SELECT knn.article_id, COALESCE(s.headline, a.title) as headline, a.canonical_url,
+               vec_distance_cosine(knn.embedding, ?) as distance
+        FROM knn
+        JOIN articles a ON a.id = knn.article_id
+        LEFT JOIN article_summaries s ON s.article_id = a.id
+        WHERE knn.article_id != ?
+          AND EXISTS (
+              SELECT 1 FROM article_occurrences ao
+              JOIN subscriptions sub ON sub.feed_id = ao.feed_id
+              WHERE ao.article_id = a.id AND sub.user_id = ?
+          )
+        ORDER BY distance ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(&embedding)
+    .bind(candidates)
+    .bind(&embedding)
+    .bind(article_id)
+    .bind(user_id)
+    .bind(k)
+    .fetch_all(pool)
+    .await
+    .context("failed to query similar articles")?;
+
+    Ok(Some(
+        rows.into_iter()
+            .map(|r| SimilarArticle {
+                article_id: r.get("article_id"),
+                headline: r.get("headline"),
+                url: r.get("canonical_url"),
+                distance: r.get("distance"),
+            })
+            .collect(),
+    ))
+}
+
+/// Number of embeddings written per transaction.
+const EMBEDDING_INSERT_BATCH: usize = 50;
+
+/// Insert a batch of embeddings in a single transaction.
+/// Returns the number of rows written; failures are logged, not propagated.
+async fn insert_embeddings(pool: &SqlitePool, model: &str, batch: Vec<(i64, Vec<u8>)>) -> usize {
+    let result: Result<usize> = async {
+        let mut tx = pool.begin().await?;
+        let mut stored = 0;
+        for (article_id, bytes) in &batch {
+            // A savepoint per row: one bad row (e.g. a vector written meanwhile
+            // by an embedding job) is skipped without losing the rest
+            let row: Result<()> = async {
+                let mut row_tx = tx.begin().await?;
+                sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (?, ?)")
+                    .bind(article_id)
+                    .bind(bytes)
+                    .execute(&mut row_tx)
+                    .await?;
+                record_vector_model(&mut row_tx, *article_id, model).await?;
+                row_tx.commit().await?;
+                Ok(())
             }
-            Err(e) => {
-                error!("Failed to embed article {}: {}", article_id, e);
-                // Continue with next
+            .await;
+            match row {
+                Ok(()) => stored += 1,
+                Err(e) => error!("Failed to store the embedding of article {}: {:?}", article_id, e),
             }
         }
+        tx.commit().await?;
+        Ok(stored)
     }
-    
-    Ok(count)
-}
+    .await;
 
+    match result {
+        Ok(n) => n,
+        Err(e) => {
+            error!("Failed to insert batch of {} embeddings: {:?}", batch.len(), e);
+            0
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    
+    use super::{hashed_embedding, EmbeddingSource, EmbeddingText, InputTruncation, ProcessingPrompts};
+
+    #[test]
+    fn test_input_truncation_keeps_head_and_tail() {
+        let text = format!("{}{}", "a".repeat(50), "z".repeat(50));
+        let head_only = InputTruncation { max_chars: 20, keep_tail_chars: 0 };
+        assert_eq!(head_only.apply(&text).unwrap(), format!("{}\n\n[…]", "a".repeat(20)));
+
+        let head_tail = InputTruncation { max_chars: 20, keep_tail_chars: 5 };
+        assert_eq!(
+            head_tail.apply(&text).unwrap(),
+            format!("{}\n\n[…]\n\n{}", "a".repeat(15), "z".repeat(5))
+        );
+
+        assert!(head_only.apply("short").is_none());
+        assert!(InputTruncation { max_chars: 0, keep_tail_chars: 0 }.apply(&text).is_none());
+    }
+
+    #[test]
+    fn test_embedding_text_prefers_summary() {
+        let mut source = EmbeddingSource {
+            title: "T".into(),
+            headline: Some("H".into()),
+            bullets: Some(vec!["a".into(), "b".into()]),
+            content: "body".into(),
+            ..Default::default()
+        };
+        let default = EmbeddingText::default();
+        assert_eq!(default.render(&source), "T\nH\na b");
+
+        source.headline = None;
+        assert_eq!(default.render(&source), "T\nbody");
+    }
+
+    #[test]
+    fn test_embedding_text_template() {
+        let source = EmbeddingSource {
+            title: "Title".into(),
+            headline: Some("Headline".into()),
+            bullets: Some(vec!["one".into()]),
+            categories: vec!["economy".into(), "europe".into()],
+            content: "body".into(),
+            ..Default::default()
+        };
+        let custom = EmbeddingText {
+            template: "[{categories}] {headline}: {bullets}".into(),
+            max_chars: 0,
+        };
+        assert_eq!(custom.render(&source), "[economy, europe] Headline: one");
+
+        // Placeholders inside the article are not expanded
+        let quoting = EmbeddingSource {
+            content: "use {title} and {summary}".into(),
+            ..source.clone()
+        };
+        let content = EmbeddingText { template: "{title}: {content} {unknown}".into(), max_chars: 0 };
+        assert_eq!(content.render(&quoting), "Title: use {title} and {summary} {unknown}");
+
+        let capped = EmbeddingText { max_chars: 10, ..custom.clone() };
+        assert_eq!(capped.render(&source), "[economy, ");
+        assert_ne!(capped.fingerprint(), custom.fingerprint());
+    }
+
+    #[test]
+    fn test_processing_prompts() {
+        let bullets = vec!["a".to_string(), "b".to_string()];
+        let defaults = ProcessingPrompts::from_config(None);
+        assert_eq!(
+            defaults.classification_prompt("Headline", &bullets),
+            "Classify this article into categories (max 3): Headline\n\nKey points: a, b\n\n\
+             Categories: politics, economy, technology, sports, culture, science, \
+             local_news, international, faits_divers, health, environment\n\n\
+             Return only category names, comma-separated."
+        );
+        assert_eq!(defaults.summarization_prompt("Text", Some("fr")), None);
+
+        let config: common::Config = toml::from_str(
+            "[database]\npath = \"x.db\"\n[scheduler]\ntimes = []\n[processing]\n\
+             categories = [\"physics\", \"biology\"]\n\
+             summarization_prompt_template = \"Abstract ({language}): {content}\"\n\
+             classification_prompt_template = \"{categories} | {content}\"",
+        )
+        .unwrap();
+        let custom = ProcessingPrompts::from_config(Some(&config));
+        assert_eq!(custom.classification_prompt("H", &bullets), "physics, biology | H\n\nKey points: a, b");
+        assert_eq!(custom.summarization_prompt("Paper", Some("fr")).as_deref(), Some("Abstract (French): Paper"));
+        assert_eq!(custom.summarization_prompt("Paper", None).as_deref(), Some("Abstract (): Paper"));
+    }
+
     #[test]
     fn test_batch_chunking() {
         let ids: Vec<i64> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
@@ -431,4 +1107,20 @@ mod tests {
         assert_eq!(chunks[1].len(), 5);
         assert_eq!(chunks[2].len(), 2);
     }
+
+    #[test]
+    fn test_hashed_embedding_is_deterministic_and_normalized() {
+        let a = hashed_embedding("Central bank raises interest rates", 384);
+        assert_eq!(a.len(), 384);
+        assert_eq!(a, hashed_embedding("central bank raises interest rates", 384));
+        let norm: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+
+        let cosine = |x: &[f32], y: &[f32]| x.iter().zip(y).map(|(a, b)| a * b).sum::<f32>();
+        let close = hashed_embedding("The central bank raised its interest rate", 384);
+        let far = hashed_embedding("Football team wins the championship final", 384);
+        assert!(cosine(&a, &close) > cosine(&a, &far) + 0.2);
+
+        assert!(hashed_embedding(" -- ", 16).iter().all(|x| *x == 0.0));
+    }
 }
@@ -0,0 +1,197 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// In-memory token-bucket rate limiter keyed by an integer id (e.g. user id).
+///
+/// Each key starts with `capacity` tokens and regains `refill_per_sec` tokens per
+/// second up to `capacity`. A bucket that has refilled is dropped, as a new
+/// one would start full anyway.
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<i64, (f64, Instant)>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `burst` immediate calls and `per_minute` sustained calls.
+    pub fn new(burst: u32, per_minute: f64) -> Self {
+        Self {
+            capacity: burst.max(1) as f64,
+            refill_per_sec: per_minute.max(0.0) / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take one token for `key`. Returns false if the bucket is empty.
+    pub fn try_acquire(&self, key: i64) -> bool {
+        self.try_acquire_at(key, Instant::now())
+    }
+
+    fn try_acquire_at(&self, key: i64, now: Instant) -> bool {
+        let mut buckets = match self.buckets.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        self.expire(&mut buckets, now);
+        let (tokens, last) = buckets.entry(key).or_insert((self.capacity, now));
+
+        let elapsed = now.saturating_duration_since(*last).as_secs_f64();
+        *tokens = (*tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        *last = now;
+
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expire(&self, buckets: &mut HashMap<i64, (f64, Instant)>, now: Instant) {
+        // Without refill an emptied bucket stays empty, so it must be kept
+        if self.refill_per_sec <= 0.0 {
+            return;
+        }
+        buckets.retain(|_, (tokens, last)| {
+            let elapsed = now.saturating_duration_since(*last).as_secs_f64();
+            *tokens + elapsed * self.refill_per_sec < self.capacity
+        });
+    }
+}
+
+/// Default `auth.max_failed_logins`.
+pub const DEFAULT_MAX_FAILED_LOGINS: u32 = 5;
+/// Default `auth.failed_login_window_seconds`.
+pub const DEFAULT_FAILED_LOGIN_WINDOW_SECONDS: u64 = 900;
+
+/// In-memory limiter of failed authentication attempts, keyed by strings such
+/// as `ip:<addr>` or `user:<name>`.
+///
+/// A key is blocked once it has `max_failures` failures within `window`, until
+/// the oldest of them leaves the window. A success clears the key.
+pub struct FailureLimiter {
+    max_failures: usize,
+    window: Duration,
+    failures: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl FailureLimiter {
+    pub fn new(max_failures: u32, window: Duration) -> Self {
+        Self {
+            max_failures: max_failures.max(1) as usize,
+            window,
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// How long until any of `keys` may try again, `None` if none is blocked.
+    pub fn retry_after(&self, keys: &[String]) -> Option<Duration> {
+        self.retry_after_at(keys, Instant::now())
+    }
+
+    /// Count a failure against each of `keys`.
+    pub fn record_failure(&self, keys: &[String]) {
+        self.record_failure_at(keys, Instant::now())
+    }
+
+    /// Forget the failures of `keys`.
+    pub fn reset(&self, keys: &[String]) {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        for key in keys {
+            failures.remove(key);
+        }
+    }
+
+    fn expire(&self, failures: &mut HashMap<String, VecDeque<Instant>>, now: Instant) {
+        failures.retain(|_, times| {
+            while times.front().is_some_and(|t| now.saturating_duration_since(*t) >= self.window) {
+                times.pop_front();
+            }
+            !times.is_empty()
+        });
+    }
+
+    fn retry_after_at(&self, keys: &[String], now: Instant) -> Option<Duration> {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        self.expire(&mut failures, now);
+        keys.iter()
+            .filter_map(|key| failures.get(key))
+            .filter(|times| times.len() >= self.max_failures)
+            .filter_map(|times| {
+                // Unblocked once enough of the oldest failures have expired
+                let unblocking = times[times.len() - self.max_failures];
+                (unblocking + self.window).checked_duration_since(now)
+            })
+            .max()
+    }
+
+    fn record_failure_at(&self, keys: &[String], now: Instant) {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        self.expire(&mut failures, now);
+        for key in keys {
+            failures.entry(key.clone()).or_default().push_back(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = RateLimiter::new(2, 60.0); // 1 token per second
+        let t0 = Instant::now();
+        assert!(limiter.try_acquire_at(1, t0));
+        assert!(limiter.try_acquire_at(1, t0));
+        assert!(!limiter.try_acquire_at(1, t0));
+        // Other keys are independent
+        assert!(limiter.try_acquire_at(2, t0));
+        // One second later a single token is back
+        let t1 = t0 + Duration::from_secs(1);
+        assert!(limiter.try_acquire_at(1, t1));
+        assert!(!limiter.try_acquire_at(1, t1));
+    }
+
+    #[test]
+    fn test_refilled_buckets_are_dropped() {
+        let limiter = RateLimiter::new(2, 60.0);
+        let t0 = Instant::now();
+        assert!(limiter.try_acquire_at(1, t0));
+        assert!(limiter.try_acquire_at(2, t0 + Duration::from_secs(1)));
+        // Key 1 is full again a second later; key 2 is not yet
+        assert!(limiter.try_acquire_at(3, t0 + Duration::from_millis(1500)));
+        let buckets = limiter.buckets.lock().unwrap();
+        let mut keys: Vec<i64> = buckets.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, vec![2, 3]);
+        drop(buckets);
+
+        // Without refill an empty bucket is never forgotten
+        let limiter = RateLimiter::new(1, 0.0);
+        assert!(limiter.try_acquire_at(1, t0));
+        assert!(!limiter.try_acquire_at(1, t0 + Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn test_failures_block_until_they_expire() {
+        let limiter = FailureLimiter::new(2, Duration::from_secs(60));
+        let keys = |names: &[&str]| names.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        let t0 = Instant::now();
+        limiter.record_failure_at(&keys(&["ip:a", "user:x"]), t0);
+        assert_eq!(limiter.retry_after_at(&keys(&["ip:a"]), t0), None);
+        limiter.record_failure_at(&keys(&["ip:a", "user:y"]), t0 + Duration::from_secs(10));
+        // Blocked until the first failure is a minute old; other keys aren't
+        let t1 = t0 + Duration::from_secs(20);
+        assert_eq!(limiter.retry_after_at(&keys(&["ip:a"]), t1), Some(Duration::from_secs(40)));
+        assert_eq!(limiter.retry_after_at(&keys(&["ip:b", "user:x"]), t1), None);
+        assert_eq!(limiter.retry_after_at(&keys(&["ip:a"]), t0 + Duration::from_secs(60)), None);
+        // A success clears the key
+        limiter.record_failure_at(&keys(&["ip:a"]), t1);
+        assert!(limiter.retry_after_at(&keys(&["ip:a"]), t1).is_some());
+        limiter.reset(&keys(&["ip:a"]));
+        assert_eq!(limiter.retry_after_at(&keys(&["ip:a"]), t1), None);
+    }
+}
@@ -0,0 +1,134 @@
+//! Reading-speed auto-calibration from time spent on press-review cards.
+//!
+//! Opt-in per user (`user_preferences` setting `auto_reading_speed`). Each
+//! open/close of a card yields an observed words-per-minute rate; the stored
+//! `user_profiles.reading_speed` moves toward it by exponential smoothing, so a
+//! single skim or a card left open does not swing the review budget.
+
+use anyhow::{Context, Result};
+use sqlx::SqlitePool;
+
+/// `user_preferences.preference_type` for per-user settings.
+const SETTING_PREFERENCE_TYPE: &str = "setting";
+/// `user_preferences.preference_key` enabling calibration.
+const AUTO_READING_SPEED_KEY: &str = "auto_reading_speed";
+
+/// Cards shorter than this are too short to time reliably.
+pub const MIN_WORDS: usize = 20;
+/// Observations outside this window (seconds) are glances or abandoned cards.
+pub const MIN_SECONDS: f64 = 3.0;
+pub const MAX_SECONDS: f64 = 600.0;
+/// Plausible reading rates; anything outside is discarded rather than clamped.
+pub const MIN_WPM: f64 = 60.0;
+pub const MAX_WPM: f64 = 1000.0;
+/// Weight of a new observation in the smoothed speed.
+pub const SMOOTHING: f64 = 0.2;
+
+/// Words-per-minute observed for `words` read in `seconds`, or None when the
+/// sample is not trustworthy.
+pub fn observed_wpm(words: usize, seconds: f64) -> Option<f64> {
+    if words < MIN_WORDS || !(MIN_SECONDS..=MAX_SECONDS).contains(&seconds) {
+        return None;
+    }
+    let wpm = words as f64 * 60.0 / seconds;
+    (MIN_WPM..=MAX_WPM).contains(&wpm).then_some(wpm)
+}
+
+/// Move `current` toward `observed` by [`SMOOTHING`].
+pub fn calibrated_speed(current: i32, observed: f64) -> i32 {
+    (current as f64 + SMOOTHING * (observed - current as f64)).round() as i32
+}
+
+/// Whether the user opted into reading-speed calibration.
+pub async fn is_enabled(pool: &SqlitePool, user_id: i64) -> Result<bool> {
+    let value = sqlx::query_scalar::<_, f64>(
+        "SELECT preference_value FROM user_preferences
+         WHERE user_id = ? AND preference_type = ? AND preference_key = ?
+         ORDER BY id DESC LIMIT 1",
+    )
+    .bind(user_id)
+    .bind(SETTING_PREFERENCE_TYPE)
+    .bind(AUTO_READING_SPEED_KEY)
+    .fetch_optional(pool)
+    .await
+    .context("failed to read reading-speed calibration setting")?;
+    Ok(value.is_some_and(|v| v > 0.0))
+}
+
+pub async fn set_enabled(pool: &SqlitePool, user_id: i64, enabled: bool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM user_preferences WHERE user_id = ? AND preference_type = ? AND preference_key = ?")
+        .bind(user_id)
+        .bind(SETTING_PREFERENCE_TYPE)
+        .bind(AUTO_READING_SPEED_KEY)
+        .execute(&mut tx)
+        .await?;
+    sqlx::query(
+        "INSERT INTO user_preferences (user_id, preference_type, preference_key, preference_value)
+         VALUES (?, ?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(SETTING_PREFERENCE_TYPE)
+    .bind(AUTO_READING_SPEED_KEY)
+    .bind(if enabled { 1.0 } else { 0.0 })
+    .execute(&mut tx)
+    .await?;
+    tx.commit().await.context("failed to store reading-speed calibration setting")?;
+    Ok(())
+}
+
+/// Feed one reading observation into the user's stored speed. Returns the new
+/// speed, or None when calibration is off or the sample was discarded.
+pub async fn record_reading(pool: &SqlitePool, user_id: i64, words: usize, seconds: f64) -> Result<Option<i32>> {
+    if !is_enabled(pool, user_id).await? {
+        return Ok(None);
+    }
+    let Some(observed) = observed_wpm(words, seconds) else {
+        return Ok(None);
+    };
+
+    let current = crate::personalization::get_user_profile(pool, user_id).await?.reading_speed;
+    let speed = calibrated_speed(current, observed);
+    sqlx::query(
+        "INSERT INTO user_profiles (user_id, reading_speed) VALUES (?, ?)
+         ON CONFLICT(user_id) DO UPDATE SET
+             reading_speed = excluded.reading_speed,
+             updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+    )
+    .bind(user_id)
+    .bind(speed)
+    .execute(pool)
+    .await
+    .context("failed to update reading speed")?;
+
+    tracing::debug!(
+        "User {} read {} words in {:.0}s ({:.0} wpm): reading speed {} -> {}",
+        user_id, words, seconds, observed, current, speed
+    );
+    Ok(Some(speed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observed_wpm_discards_outliers() {
+        assert_eq!(observed_wpm(100, 30.0), Some(200.0));
+        assert_eq!(observed_wpm(10, 30.0), None);
+        assert_eq!(observed_wpm(100, 1.0), None);
+        assert_eq!(observed_wpm(100, 3600.0), None);
+        // 2000 wpm: skimmed, not read
+        assert_eq!(observed_wpm(500, 15.0), None);
+    }
+
+    #[test]
+    fn test_calibration_converges_gradually() {
+        assert_eq!(calibrated_speed(250, 150.0), 230);
+        let mut speed = 250;
+        for _ in 0..30 {
+            speed = calibrated_speed(speed, 150.0);
+        }
+        assert!((150..=152).contains(&speed));
+    }
+}
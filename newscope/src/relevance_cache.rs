@@ -0,0 +1,208 @@
+//! Reuse of relevance evaluations across articles and users.
+//!
+//! A relevance score only depends on the article's summary and on the profile
+//! fields the prompt shows (interests and preferred categories), so each
+//! evaluation is stored under a hash of those fields, the profile version. An
+//! article then reuses the score given to an identical summary, or to the
+//! nearest previously-scored summary within `[processing]
+//! relevance_cache_max_distance`, for the same profile version instead of
+//! asking the LLM again. Changing one's interests or category weights changes
+//! the version, so older scores stop matching.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{info, warn};
+
+use crate::llm::{LlmProvider, Summary};
+use crate::personalization::{RelevanceEvaluation, UserProfile};
+
+/// Default `processing.relevance_cache_max_distance` (cosine distance).
+pub const DEFAULT_MAX_DISTANCE: f64 = 0.05;
+/// Most recent evaluations of a profile version compared by embedding.
+pub const SCAN_LIMIT: i64 = 500;
+
+/// `processing.relevance_cache` (default on).
+pub fn enabled(config: Option<&common::Config>) -> bool {
+    config
+        .and_then(|c| c.processing.as_ref())
+        .and_then(|p| p.relevance_cache)
+        .unwrap_or(true)
+}
+
+/// `processing.relevance_cache_max_distance`; 0 only reuses identical summaries.
+pub fn max_distance(config: Option<&common::Config>) -> f64 {
+    config
+        .and_then(|c| c.processing.as_ref())
+        .and_then(|p| p.relevance_cache_max_distance)
+        .unwrap_or(DEFAULT_MAX_DISTANCE)
+}
+
+/// Version of the profile as the relevance prompt sees it: interests and
+/// preferred categories, regardless of order and case.
+pub fn profile_hash(profile: &UserProfile) -> String {
+    let normalized = |list: &[String]| {
+        let mut list: Vec<String> = list.iter().map(|s| s.trim().to_lowercase()).collect();
+        list.sort();
+        list.dedup();
+        list.join("\n")
+    };
+    let key = format!("{}\0{}", normalized(&profile.interests), normalized(&profile.preferred_categories));
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// Hash of the summary fields the relevance prompt shows.
+pub fn content_hash(summary: &Summary) -> String {
+    format!("{:x}", Sha256::digest(format!("{}\0{}", summary.headline, summary.bullets.join("\n")).as_bytes()))
+}
+
+fn f32_vec_to_bytes(v: &[f32]) -> Vec<u8> {
+    v.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+fn bytes_to_f32_vec(bytes: &[u8]) -> Vec<f32> {
+    bytes.chunks_exact(4).map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap())).collect()
+}
+
+/// Cached relevance evaluations for one article, with lookup statistics.
+pub struct RelevanceCache {
+    pool: SqlitePool,
+    article_id: i64,
+    content_hash: String,
+    /// Embedding of the summary, when an embedding provider is available
+    embedding: Option<Vec<f32>>,
+    max_distance: f64,
+    lookups: AtomicUsize,
+    hits: AtomicUsize,
+}
+
+impl RelevanceCache {
+    /// Cache for evaluating `summary` of `article_id`, embedding the summary
+    /// with `embedder` for similarity lookups. `None` when disabled by config.
+    pub async fn for_article(
+        pool: &SqlitePool,
+        config: Option<&common::Config>,
+        article_id: i64,
+        summary: &Summary,
+        embedder: Option<&dyn LlmProvider>,
+    ) -> Option<Self> {
+        if !enabled(config) {
+            return None;
+        }
+        let max_distance = max_distance(config);
+        let embedding = match embedder.filter(|_| max_distance > 0.0) {
+            Some(embedder) => {
+                let text = format!("{}\n{}", summary.headline, summary.bullets.join("\n"));
+                match embedder.embed(&text).await {
+                    Ok(embedding) => Some(embedding),
+                    Err(e) => {
+                        warn!("Relevance cache: failed to embed summary of article {}: {:#}", article_id, e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        Some(Self {
+            pool: pool.clone(),
+            article_id,
+            content_hash: content_hash(summary),
+            embedding,
+            max_distance,
+            lookups: AtomicUsize::new(0),
+            hits: AtomicUsize::new(0),
+        })
+    }
+
+    /// A previous evaluation for `profile_hash`: of an identical summary, else
+    /// of the nearest one within the configured distance.
+    pub async fn lookup(&self, profile_hash: &str) -> Result<Option<RelevanceEvaluation>> {
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+        let exact = sqlx::query(
+            "SELECT score, reasons FROM relevance_cache
+             WHERE profile_hash = ? AND content_hash = ?
+             ORDER BY id DESC LIMIT 1",
+        )
+        .bind(profile_hash)
+        .bind(&self.content_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to look up cached relevance")?;
+
+        let hit = match (exact, &self.embedding) {
+            (Some(row), _) => Some(row),
+            (None, Some(embedding)) => {
+                let rows = sqlx::query(
+                    "SELECT score, reasons, embedding FROM relevance_cache
+                     WHERE profile_hash = ? AND embedding IS NOT NULL
+                     ORDER BY id DESC LIMIT ?",
+                )
+                .bind(profile_hash)
+                .bind(SCAN_LIMIT)
+                .fetch_all(&self.pool)
+                .await
+                .context("failed to load cached relevance embeddings")?;
+                rows.into_iter()
+                    .map(|row| {
+                        let other = bytes_to_f32_vec(&row.get::<Vec<u8>, _>("embedding"));
+                        (crate::press_review::cosine_distance(embedding, &other), row)
+                    })
+                    .filter(|(distance, _)| *distance <= self.max_distance)
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+                    .map(|(_, row)| row)
+            }
+            (None, None) => None,
+        };
+
+        Ok(hit.map(|row| {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            RelevanceEvaluation {
+                score: row.get::<f64, _>("score") as f32,
+                reasons: row
+                    .get::<Option<String>, _>("reasons")
+                    .and_then(|r| serde_json::from_str(&r).ok())
+                    .unwrap_or_default(),
+                usage: Default::default(),
+                cached: true,
+            }
+        }))
+    }
+
+    /// Remember an LLM evaluation of this article for `profile_hash`.
+    pub async fn store(&self, profile_hash: &str, evaluation: &RelevanceEvaluation) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO relevance_cache (profile_hash, article_id, content_hash, embedding, score, reasons)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(profile_hash)
+        .bind(self.article_id)
+        .bind(&self.content_hash)
+        .bind(self.embedding.as_deref().map(f32_vec_to_bytes))
+        .bind(evaluation.score as f64)
+        .bind(serde_json::to_string(&evaluation.reasons)?)
+        .execute(&self.pool)
+        .await
+        .context("failed to cache relevance evaluation")?;
+        Ok(())
+    }
+
+    /// (hits, lookups) so far.
+    pub fn stats(&self) -> (usize, usize) {
+        (self.hits.load(Ordering::Relaxed), self.lookups.load(Ordering::Relaxed))
+    }
+
+    /// Log how many evaluations of this article were served from the cache.
+    pub fn log_hit_rate(&self) {
+        let (hits, lookups) = self.stats();
+        if lookups > 0 {
+            info!(
+                "Relevance cache for article {}: {}/{} hits ({:.0}%)",
+                self.article_id,
+                hits,
+                lookups,
+                100.0 * hits as f64 / lookups as f64
+            );
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Correlation ids for logs.
+//!
+//! Every HTTP request gets an id, taken from its `X-Request-Id` header when the
+//! client (or a proxy in front) sent a usable one, generated otherwise. Routes
+//! wrapped with [`traced`] run in a `request{id=...}` span, so everything their
+//! handler logs carries the id, and the [`RequestIds`] fairing echoes it in the
+//! response's `X-Request-Id`. The worker runs each pass in a `run{id=...}` span
+//! the same way.
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::route::{Handler, Outcome};
+use rocket::{Data, Request, Response, Route};
+use tracing::Instrument;
+
+/// Header carrying the request id, both ways.
+pub const HEADER: &str = "X-Request-Id";
+/// Longest incoming id kept; longer ones are replaced.
+const MAX_LEN: usize = 128;
+
+/// A new random id, short enough to grep for.
+pub fn new_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+/// Whether a client-supplied id is safe to log and echo.
+fn usable(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_LEN
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+/// Id of the request being handled, cached in the request.
+pub struct RequestId(String);
+
+impl RequestId {
+    pub fn of<'r>(req: &'r Request<'_>) -> &'r str {
+        &req.local_cache(|| {
+            RequestId(
+                req.headers()
+                    .get_one(HEADER)
+                    .filter(|id| usable(id))
+                    .map_or_else(new_id, str::to_string),
+            )
+        })
+        .0
+    }
+}
+
+/// Fairing sending the request id back in `X-Request-Id`.
+pub struct RequestIds;
+
+#[rocket::async_trait]
+impl Fairing for RequestIds {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request ids",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        res.set_header(Header::new(HEADER, RequestId::of(req).to_string()));
+    }
+}
+
+/// Route handler running the wrapped one in the request's span.
+#[derive(Clone)]
+struct Traced(Box<dyn Handler>);
+
+#[rocket::async_trait]
+impl Handler for Traced {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let span = tracing::info_span!("request", id = %RequestId::of(req));
+        self.0.handle(req, data).instrument(span).await
+    }
+}
+
+/// `routes` with their handlers run in a `request{id=...}` span.
+pub fn traced(routes: Vec<Route>) -> Vec<Route> {
+    routes
+        .into_iter()
+        .map(|mut route| {
+            route.handler = Box::new(Traced(route.handler));
+            route
+        })
+        .collect()
+}
@@ -0,0 +1,207 @@
+//! Wall-clock ingestion schedule for the worker.
+//!
+//! `scheduler.times` lists "HH:MM" times of day at which every active feed is
+//! polled, regardless of its own `next_poll_at`. `scheduler.mode` decides
+//! whether those runs replace the per-feed cadence or come on top of it, and
+//! `scheduler.timezone` which clock the times are read against.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use common::Config;
+
+/// How the worker decides which feeds to poll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleMode {
+    /// Only poll feeds whose `next_poll_at` is due
+    Cadence,
+    /// Only poll, all feeds at once, at the configured times
+    Times,
+    /// Per-feed cadence, plus a full pass at the configured times
+    Both,
+}
+
+impl ScheduleMode {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "cadence" => Ok(Self::Cadence),
+            "times" => Ok(Self::Times),
+            "both" => Ok(Self::Both),
+            other => bail!("unknown scheduler mode {:?} (expected cadence, times or both)", other),
+        }
+    }
+
+    pub fn uses_cadence(self) -> bool {
+        self != Self::Times
+    }
+
+    pub fn uses_times(self) -> bool {
+        self != Self::Cadence
+    }
+}
+
+/// Clock against which `scheduler.times` are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleTimezone {
+    /// The host's local time zone, including its DST changes
+    Local,
+    /// A fixed offset from UTC ("UTC", "+02:00", "-05:30")
+    Fixed(FixedOffset),
+}
+
+impl ScheduleTimezone {
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("local") {
+            return Ok(Self::Local);
+        }
+        if value.eq_ignore_ascii_case("utc") || value == "Z" {
+            return Ok(Self::Fixed(FixedOffset::east_opt(0).unwrap()));
+        }
+        let (sign, rest) = if let Some(rest) = value.strip_prefix('+') {
+            (1, rest)
+        } else if let Some(rest) = value.strip_prefix('-') {
+            (-1, rest)
+        } else {
+            bail!("invalid scheduler timezone {:?} (expected local, UTC or +HH:MM)", value);
+        };
+        let offset = NaiveTime::parse_from_str(rest, "%H:%M")
+            .ok()
+            .and_then(|t| {
+                let secs = t.signed_duration_since(NaiveTime::MIN).num_seconds() as i32;
+                FixedOffset::east_opt(sign * secs)
+            })
+            .with_context(|| format!("invalid scheduler timezone {:?} (expected local, UTC or +HH:MM)", value))?;
+        Ok(Self::Fixed(offset))
+    }
+}
+
+/// Parse "HH:MM" times of day, sorted and without duplicates.
+pub fn parse_times(times: &[String]) -> Result<Vec<NaiveTime>> {
+    let mut parsed = times
+        .iter()
+        .map(|t| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .with_context(|| format!("invalid scheduler time {:?} (expected HH:MM)", t))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    parsed.sort();
+    parsed.dedup();
+    Ok(parsed)
+}
+
+/// First of `times` strictly after `now`, in `now`'s time zone. Times that have
+/// already passed today roll over to tomorrow. A time skipped by a DST jump has
+/// no occurrence that day; one repeated by a DST fall-back fires once.
+pub fn next_occurrence<Tz: TimeZone>(now: &DateTime<Tz>, times: &[NaiveTime]) -> Option<DateTime<Utc>> {
+    let tz = now.timezone();
+    let today: NaiveDate = now.date_naive();
+    (0..=2)
+        .filter_map(|days| today.checked_add_signed(Duration::days(days)))
+        .flat_map(|date| times.iter().map(move |t| date.and_time(*t)))
+        .filter_map(|local| tz.from_local_datetime(&local).earliest())
+        .find(|at| at > now)
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// The parsed `[scheduler]` settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    pub mode: ScheduleMode,
+    pub timezone: ScheduleTimezone,
+    pub times: Vec<NaiveTime>,
+}
+
+impl Schedule {
+    /// Build from the `[scheduler]` section; `mode` defaults to `both` and
+    /// `timezone` to `local`.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let scheduler = &config.scheduler;
+        let mode = match scheduler.mode.as_deref() {
+            Some(mode) => ScheduleMode::parse(mode)?,
+            None => ScheduleMode::Both,
+        };
+        let timezone = match scheduler.timezone.as_deref() {
+            Some(tz) => ScheduleTimezone::parse(tz)?,
+            None => ScheduleTimezone::Local,
+        };
+        let times = parse_times(&scheduler.times)?;
+        if mode == ScheduleMode::Times && times.is_empty() {
+            bail!("scheduler.mode = \"times\" needs at least one entry in scheduler.times");
+        }
+        Ok(Self { mode, timezone, times })
+    }
+
+    /// Next wall-clock run strictly after `now`, if runs at fixed times are enabled.
+    pub fn next_run_after(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if !self.mode.uses_times() {
+            return None;
+        }
+        match self.timezone {
+            ScheduleTimezone::Local => next_occurrence(&now.with_timezone(&Local), &self.times),
+            ScheduleTimezone::Fixed(offset) => next_occurrence(&now.with_timezone(&offset), &self.times),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hm(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_and_sorts_times() {
+        let times = parse_times(&["17:00".into(), " 05:30".into(), "17:00".into()]).unwrap();
+        assert_eq!(times, vec![hm(5, 30), hm(17, 0)]);
+        assert!(parse_times(&["25:00".into()]).is_err());
+        assert!(parse_times(&["noon".into()]).is_err());
+    }
+
+    #[test]
+    fn next_occurrence_rolls_over_to_tomorrow() {
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let times = vec![hm(5, 0), hm(17, 0)];
+        let at = |d: u32, h: u32, m: u32| tz.with_ymd_and_hms(2026, 3, d, h, m, 0).unwrap();
+
+        assert_eq!(next_occurrence(&at(10, 4, 0), &times), Some(at(10, 5, 0).with_timezone(&Utc)));
+        // A time equal to now has already run
+        assert_eq!(next_occurrence(&at(10, 5, 0), &times), Some(at(10, 17, 0).with_timezone(&Utc)));
+        assert_eq!(next_occurrence(&at(10, 23, 0), &times), Some(at(11, 5, 0).with_timezone(&Utc)));
+        assert_eq!(next_occurrence(&at(10, 23, 0), &[]), None);
+    }
+
+    #[test]
+    fn parses_mode_and_timezone() {
+        assert_eq!(ScheduleMode::parse("Times").unwrap(), ScheduleMode::Times);
+        assert!(ScheduleMode::parse("hourly").is_err());
+        assert_eq!(ScheduleTimezone::parse("local").unwrap(), ScheduleTimezone::Local);
+        assert_eq!(
+            ScheduleTimezone::parse("UTC").unwrap(),
+            ScheduleTimezone::Fixed(FixedOffset::east_opt(0).unwrap())
+        );
+        assert_eq!(
+            ScheduleTimezone::parse("-05:30").unwrap(),
+            ScheduleTimezone::Fixed(FixedOffset::west_opt(5 * 3600 + 1800).unwrap())
+        );
+        assert!(ScheduleTimezone::parse("Europe/Paris").is_err());
+    }
+
+    #[test]
+    fn cadence_mode_has_no_wall_clock_runs() {
+        let schedule = Schedule {
+            mode: ScheduleMode::Cadence,
+            timezone: ScheduleTimezone::Fixed(FixedOffset::east_opt(0).unwrap()),
+            times: vec![hm(5, 0)],
+        };
+        assert_eq!(schedule.next_run_after(Utc::now()), None);
+
+        let schedule = Schedule { mode: ScheduleMode::Both, ..schedule };
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 6, 0, 0).unwrap();
+        assert_eq!(
+            schedule.next_run_after(now),
+            Some(Utc.with_ymd_and_hms(2026, 3, 11, 5, 0, 0).unwrap())
+        );
+    }
+}
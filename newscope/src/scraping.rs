@@ -1,5 +1,4 @@
 use anyhow::{Context, Result};
-use reqwest::Client;
 use std::time::Duration;
 use tracing::{info, warn};
 use std::io::Cursor;
@@ -7,13 +6,13 @@ use std::io::Cursor;
 /// Scrapes the content of an article from the given URL.
 /// Returns the extracted text content.
 pub async fn scrape_article_content(url: &str, timeout_secs: u64) -> Result<String> {
-    let client = Client::builder()
+    crate::ingestion::check_robots(url).await?;
+    let response = crate::http::client()
+        .get(url)
         .timeout(Duration::from_secs(timeout_secs))
-        .user_agent("Newscope/0.1.0")
-        .build()
-        .context("failed to build reqwest client")?;
-
-    let response = client.get(url).send().await.context("failed to fetch article page")?;
+        .send()
+        .await
+        .context("failed to fetch article page")?;
 
     let status = response.status();
     if !status.is_success() {
@@ -21,7 +20,7 @@ pub async fn scrape_article_content(url: &str, timeout_secs: u64) -> Result<Stri
     }
 
     // Readability requires a Reader, so we fetch bytes
-    let bytes = response.bytes().await.context("failed to read response body")?;
+    let bytes = crate::http::read_body_limited(response, crate::http::max_response_bytes()).await?;
     let mut reader = Cursor::new(bytes);
 
     // Use readability to extract the main content
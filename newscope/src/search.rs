@@ -0,0 +1,174 @@
+//! Full-text article search.
+//!
+//! `articles_fts` is an FTS5 table keyed by article id (its rowid) holding the
+//! article title and content plus the summary headline and bullets. It is
+//! updated when articles are stored and when their summaries are written, and
+//! filled from existing rows at startup when empty.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tracing::info;
+
+/// Columns: title, content, headline, bullets (space-joined).
+const CREATE_INDEX_SQL: &str = "CREATE VIRTUAL TABLE IF NOT EXISTS articles_fts USING fts5(
+        title, content, headline, bullets,
+        tokenize = 'unicode61 remove_diacritics 2'
+    )";
+
+/// Index rows for the articles selected by the trailing WHERE clause.
+const INDEX_ROWS_SQL: &str = "INSERT INTO articles_fts (rowid, title, content, headline, bullets)
+     SELECT a.id, a.title, a.content, s.headline,
+            (SELECT group_concat(b.value, ' ') FROM json_each(s.bullets_json) b)
+     FROM articles a
+     LEFT JOIN article_summaries s ON s.article_id = a.id";
+
+/// One search match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub article_id: i64,
+    pub title: Option<String>,
+    pub headline: Option<String>,
+    pub url: String,
+    pub feed_title: Option<String>,
+    pub first_seen_at: Option<String>,
+    /// Matching excerpt, query terms wrapped in `<mark>` tags
+    pub snippet: String,
+    /// BM25 rank; lower is better
+    pub rank: f64,
+}
+
+/// Create `articles_fts` and, when it is empty, index every stored article.
+pub async fn ensure_index(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(CREATE_INDEX_SQL)
+        .execute(pool)
+        .await
+        .context("failed to create articles_fts")?;
+
+    let indexed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM articles_fts")
+        .fetch_one(pool)
+        .await
+        .context("failed to count indexed articles")?;
+    if indexed == 0 {
+        let backfilled = sqlx::query(INDEX_ROWS_SQL)
+            .execute(pool)
+            .await
+            .context("failed to backfill articles_fts")?
+            .rows_affected();
+        if backfilled > 0 {
+            info!("Indexed {} existing articles for full-text search", backfilled);
+        }
+    }
+    Ok(())
+}
+
+/// (Re)index one article with its current title, content and summary.
+pub async fn index_article(pool: &SqlitePool, article_id: i64) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM articles_fts WHERE rowid = ?")
+        .bind(article_id)
+        .execute(&mut tx)
+        .await
+        .context("failed to remove article from articles_fts")?;
+    sqlx::query(&format!("{} WHERE a.id = ?", INDEX_ROWS_SQL))
+        .bind(article_id)
+        .execute(&mut tx)
+        .await
+        .context("failed to index article")?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Turn user input into an FTS5 query: "quoted text" is kept as a phrase,
+/// every other word is matched literally, and all of them must appear.
+/// `None` when there is nothing to search for.
+pub fn fts_query(input: &str) -> Option<String> {
+    let mut terms = Vec::new();
+    for (i, part) in input.split('"').enumerate() {
+        if i % 2 == 1 {
+            // Inside quotes (an unbalanced trailing quote runs to the end)
+            let phrase = part.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                terms.push(format!("\"{}\"", phrase));
+            }
+        } else {
+            terms.extend(part.split_whitespace().map(|word| format!("\"{}\"", word)));
+        }
+    }
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Articles matching `query` from the feeds `user_id` subscribes to, best first.
+pub async fn search_articles(pool: &SqlitePool, user_id: i64, query: &str, limit: i64) -> Result<Vec<SearchHit>> {
+    let Some(fts) = fts_query(query) else {
+        return Ok(Vec::new());
+    };
+    let rows = sqlx::query(
+        r#"
+        SELECT a.id, a.title, a.canonical_url, a.first_seen_at, s.headline,
+               snippet(articles_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+               bm25(articles_fts, 4.0, 1.0, 4.0, 2.0) AS rank,
+               (SELECT f.title
+                  FROM article_occurrences ao
+                  JOIN subscriptions sub ON sub.feed_id = ao.feed_id AND sub.user_id = ?
+                  JOIN feeds f ON f.id = ao.feed_id
+                 WHERE ao.article_id = a.id
+                 ORDER BY f.id ASC
+                 LIMIT 1) AS feed_title
+        FROM articles_fts
+        JOIN articles a ON a.id = articles_fts.rowid
+        LEFT JOIN article_summaries s ON s.article_id = a.id
+        WHERE articles_fts MATCH ?
+          AND EXISTS (
+              SELECT 1 FROM article_occurrences ao
+              JOIN subscriptions sub ON sub.feed_id = ao.feed_id
+              WHERE ao.article_id = a.id AND sub.user_id = ?
+          )
+        ORDER BY rank ASC, a.id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(user_id)
+    .bind(&fts)
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("failed to search articles")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| SearchHit {
+            article_id: r.get("id"),
+            title: r.get("title"),
+            headline: r.get("headline"),
+            url: r.get("canonical_url"),
+            feed_title: r.get("feed_title"),
+            first_seen_at: r.get("first_seen_at"),
+            snippet: r.get::<Option<String>, _>("snippet").unwrap_or_default(),
+            rank: r.get("rank"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_literal_and_phrase_terms() {
+        assert_eq!(fts_query("rust climate").as_deref(), Some("\"rust\" \"climate\""));
+        assert_eq!(
+            fts_query("\"climate  change\" policy").as_deref(),
+            Some("\"climate change\" \"policy\"")
+        );
+        // FTS5 operators and punctuation are searched for, not interpreted
+        assert_eq!(fts_query("c++ OR title:x").as_deref(), Some("\"c++\" \"OR\" \"title:x\""));
+        assert_eq!(fts_query("\"unbalanced phrase").as_deref(), Some("\"unbalanced phrase\""));
+        assert_eq!(fts_query("  \"\" "), None);
+    }
+}
@@ -5,9 +5,9 @@ use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use rocket::data::{Data, ToByteUnit};
 use rocket::fs::FileServer;
-use rocket::http::Status;
+use rocket::http::{ContentType, Status};
 use rocket::serde::json::Json;
-use rocket::{get, post, put, routes, State};
+use rocket::{delete, get, patch, post, put, routes, State};
 use serde::{Deserialize, Serialize};
 
 use sqlx::{Row, SqlitePool};
@@ -18,21 +18,83 @@ use common::Config;
 // Ingestion and storage for feed refresh
 use crate::{ingestion, storage};
 
-use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::password_hash::{PasswordHash, PasswordVerifier};
 use argon2::Argon2;
 use jsonwebtoken::{encode, EncodingKey, Header as JwtHeader};
-use rand::rngs::OsRng;
 
 /// Application state stored inside Rocket managed state.
 #[derive(Clone)]
 pub struct AppState {
     pub started_at: DateTime<Utc>,
-    pub config: Option<Arc<Config>>,
+    /// Live configuration, replaced when it is reloaded (see [`crate::config_reload`])
+    pub config: Option<crate::config_reload::SharedConfig>,
     pub db: SqlitePool,
     pub summarization_llm: Option<Arc<dyn crate::llm::LlmProvider>>,
     pub personalization_llm: Option<Arc<dyn crate::llm::LlmProvider>>,
     pub interaction_llm: Option<Arc<dyn crate::llm::LlmProvider>>,
     pub embedding_llm: Option<Arc<dyn crate::llm::LlmProvider>>,
+    /// Per-user limiter for chat messages that reach the LLM.
+    pub chat_limiter: Arc<crate::rate_limit::RateLimiter>,
+    /// Failed logins and registrations per client IP and per username.
+    pub auth_limiter: Arc<crate::rate_limit::FailureLimiter>,
+    /// Status of the background worker, `None` when it does not run in this process
+    pub worker: Option<crate::worker_status::SharedWorkerStatus>,
+}
+
+impl AppState {
+    /// State over `db` without LLMs or worker, with the chat and login limits
+    /// read from `config` (or their defaults). Set the other fields with
+    /// `AppState { field, ..AppState::new(db, config) }`.
+    pub fn new(db: SqlitePool, config: Option<crate::config_reload::SharedConfig>) -> Self {
+        let startup_config = config.as_ref().map(|c| c.load_full());
+        let chat_limit = startup_config
+            .as_deref()
+            .and_then(|c| c.llm.as_ref())
+            .and_then(|l| l.chat_rate_limit.as_ref());
+        let chat_limiter = Arc::new(crate::rate_limit::RateLimiter::new(
+            chat_limit.and_then(|r| r.burst).unwrap_or(5),
+            chat_limit.and_then(|r| r.messages_per_minute).unwrap_or(10.0),
+        ));
+
+        let auth_config = startup_config.as_deref().and_then(|c| c.auth.as_ref());
+        let auth_limiter = Arc::new(crate::rate_limit::FailureLimiter::new(
+            auth_config
+                .and_then(|a| a.max_failed_logins)
+                .unwrap_or(crate::rate_limit::DEFAULT_MAX_FAILED_LOGINS),
+            std::time::Duration::from_secs(
+                auth_config
+                    .and_then(|a| a.failed_login_window_seconds)
+                    .unwrap_or(crate::rate_limit::DEFAULT_FAILED_LOGIN_WINDOW_SECONDS),
+            ),
+        ));
+
+        AppState {
+            started_at: Utc::now(),
+            config,
+            db,
+            summarization_llm: None,
+            personalization_llm: None,
+            interaction_llm: None,
+            embedding_llm: None,
+            chat_limiter,
+            auth_limiter,
+            worker: None,
+        }
+    }
+
+    /// Snapshot of the current configuration.
+    pub fn config(&self) -> Option<Arc<Config>> {
+        self.config.as_ref().map(|c| c.load_full())
+    }
+}
+
+/// Response of `GET /api/v1/worker/status`.
+#[derive(Serialize)]
+struct WorkerStatusResponse {
+    /// `false` when the worker does not run in this process (`serve --no-worker`)
+    enabled: bool,
+    #[serde(flatten)]
+    status: crate::worker_status::WorkerStatus,
 }
 
 /// Response structure for `/api/v1/status`.
@@ -44,56 +106,89 @@ struct StatusResponse {
     scheduler_times: Vec<String>,
 }
 
-/// Representation of feed row returned by the API.
 /// Representation of feed row returned by the API (joined with subscription).
-#[derive(Serialize)]
-struct FeedRow {
-    id: i64,
-    subscription_id: i64,
-    user_id: i64,
-    url: String,
-    title: Option<String>,
-    last_checked: Option<String>,
-    status: Option<String>,
-    weight: i64,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedRow {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub user_id: i64,
+    pub url: String,
+    pub title: Option<String>,
+    pub last_checked: Option<String>,
+    pub status: Option<String>,
+    pub weight: i64,
+    /// Feed delivers complete articles; the scrape fallback is skipped
+    #[serde(default)]
+    pub full_content: bool,
+    /// Current polling interval, as adapted by the scheduler
+    #[serde(default)]
+    pub poll_interval_minutes: Option<i64>,
+    /// Update cadence declared by the feed (`<ttl>` or `sy:updatePeriod`)
+    #[serde(default)]
+    pub declared_update_minutes: Option<i64>,
+    /// Folder the subscription was filed under (OPML import), `/`-separated
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Permanent fetch failures in a row; the feed is `disabled` past
+    /// `scheduler.max_consecutive_failures`
+    #[serde(default)]
+    pub consecutive_failures: i64,
 }
 
-/// Request body for creating a feed. `user_id` or `token` (JWT) may be provided.
-/// If `user_id` is omitted, a `token` may be provided and the server will extract
-/// the subject (`sub`) from the token to identify the user.
-#[derive(Deserialize)]
-struct FeedCreate {
-    user_id: Option<i64>,
-    /// Optional JWT token that can contain the subject user id.
-    token: Option<String>,
-    url: String,
-    title: Option<String>,
+/// One page of a user's subscriptions, with the total for pagination controls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedPage {
+    pub feeds: Vec<FeedRow>,
+    /// Subscriptions of the user across all pages
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
 }
 
-/// Response for OPDS import
-#[derive(Serialize)]
-struct OpdsImportResponse {
-    added: usize,
-    duplicates: usize,
-    errors: Vec<String>,
-    total_processed: usize,
+/// Request body for creating a feed; the subscriber is the authenticated user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedCreate {
+    pub url: String,
+    pub title: Option<String>,
+    /// Mark a new feed as delivering complete articles (disables scraping);
+    /// ignored when the feed already exists
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_content: Option<bool>,
 }
 
-/// Job row for API
-#[derive(Serialize, sqlx::FromRow)]
-struct JobRow {
-    id: i64,
-    job_type: String,
-    entity_id: i64,
-    status: String,
-    llm_model: String,
-    created_at: DateTime<Utc>,
-    started_at: Option<DateTime<Utc>>,
-    completed_at: Option<DateTime<Utc>>,
-    prompt_tokens: i64,
-    completion_tokens: i64,
-    processing_time_ms: Option<i64>,
-    error_message: Option<String>,
+/// Request body for `PATCH /api/v1/feeds/<id>`. `user_id` may be omitted; if
+/// given it must be the authenticated user.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeedUpdate {
+    pub user_id: Option<i64>,
+    pub full_content: Option<bool>,
+    /// The user's weight for the feed in press review ranking (`[scoring] w_src`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<i64>,
+}
+
+/// Valid subscription weights.
+pub const SUBSCRIPTION_WEIGHT_RANGE: std::ops::RangeInclusive<i64> = -10..=10;
+
+/// Response for feed creation / subscription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedCreated {
+    pub id: i64,
+    pub subscription_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Summary of an OPML import.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpmlImportResponse {
+    /// New subscriptions
+    pub added: usize,
+    /// Feeds the user was already subscribed to (or listed twice)
+    pub skipped: usize,
+    /// Outlines with an invalid feed URL, or that could not be stored
+    pub failed: usize,
+    pub errors: Vec<String>,
 }
 
 /// Stats response
@@ -113,9 +208,24 @@ async fn index_redirect() -> Redirect {
     Redirect::to("/static/index.html")
 }
 
+/// Liveness probe: only pings the database, 503 when it does not answer.
 #[get("/health")]
-async fn health() -> &'static str {
-    "OK"
+async fn health(state: &State<AppState>) -> (Status, &'static str) {
+    match crate::health::check_database(&state.db).await.status {
+        crate::health::HealthStatus::Down => (Status::ServiceUnavailable, "DOWN"),
+        _ => (Status::Ok, "OK"),
+    }
+}
+
+/// Status of every subsystem (see [`crate::health`]); 503 when the service is down.
+#[get("/api/v1/health")]
+async fn health_report(state: &State<AppState>) -> (Status, Json<crate::health::HealthReport>) {
+    let report = crate::health::report(state).await;
+    let status = match report.status {
+        crate::health::HealthStatus::Down => Status::ServiceUnavailable,
+        _ => Status::Ok,
+    };
+    (status, Json(report))
 }
 
 /// Status endpoint returning simple JSON with uptime and basic config info.
@@ -124,7 +234,7 @@ async fn status(state: &State<AppState>) -> Json<StatusResponse> {
     let now = Utc::now();
     let uptime = (now - state.started_at).num_seconds();
 
-    let (users_count, scheduler_times) = match &state.config {
+    let (users_count, scheduler_times) = match state.config().as_deref() {
         Some(cfg) => (cfg.users.len(), cfg.scheduler.times.clone()),
         None => (0usize, Vec::new()),
     };
@@ -137,22 +247,46 @@ async fn status(state: &State<AppState>) -> Json<StatusResponse> {
     })
 }
 
-/// Get recent processing jobs
+/// Get recent processing jobs. Requires the admin token.
 #[get("/api/jobs")]
-async fn list_jobs(state: &State<AppState>) -> std::result::Result<Json<Vec<JobRow>>, Status> {
-    let jobs = sqlx::query_as::<_, JobRow>(
-        "SELECT * FROM processing_jobs ORDER BY created_at DESC LIMIT 50",
-    )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e| {
-        error!("Failed to fetch jobs: {}", e);
+async fn list_jobs(
+    state: &State<AppState>,
+    _admin: crate::auth::AdminToken,
+) -> std::result::Result<Json<Vec<crate::jobs::Job>>, Status> {
+    let jobs = crate::jobs::list(&state.db, None, 50).await.map_err(|e| {
+        error!("Failed to fetch jobs: {:#}", e);
         Status::InternalServerError
     })?;
 
     Ok(Json(jobs))
 }
 
+/// Most recent jobs of the processing queue, optionally filtered by `status`
+/// (`pending`, `running`, `completed` or `failed`); `limit` defaults to 50, at
+/// most 500. Requires the admin token.
+#[get("/api/v1/jobs?<status>&<limit>")]
+async fn list_queue_jobs(
+    state: &State<AppState>,
+    _admin: crate::auth::AdminToken,
+    status: Option<String>,
+    limit: Option<i64>,
+) -> Result<Json<Vec<crate::jobs::Job>>, (Status, String)> {
+    if let Some(status) = status.as_deref().filter(|s| !crate::jobs::STATUSES.contains(s)) {
+        return Err((
+            Status::BadRequest,
+            format!("unknown status {:?}, expected one of {}", status, crate::jobs::STATUSES.join(", ")),
+        ));
+    }
+    let limit = limit.unwrap_or(50).clamp(1, 500);
+    crate::jobs::list(&state.db, status.as_deref(), limit)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to list jobs: {:#}", e);
+            (Status::InternalServerError, "failed to list jobs".to_string())
+        })
+}
+
 /// Get processing stats
 #[get("/api/stats")]
 async fn get_stats(state: &State<AppState>) -> std::result::Result<Json<StatsResponse>, Status> {
@@ -192,55 +326,71 @@ async fn get_stats(state: &State<AppState>) -> std::result::Result<Json<StatsRes
 #[get("/api/v1/users")]
 async fn list_users(state: &State<AppState>) -> Json<serde_json::Value> {
     let users = state
-        .config
-        .as_ref()
+        .config()
         .map(|c| c.users.clone())
         .unwrap_or_default();
     Json(serde_json::json!(users))
 }
 
-/// List feeds stored in the database for the current user.
-#[get("/api/v1/feeds?<user_id>")]
+/// List the current user's subscribed feeds, a page at a time
+/// (`limit` defaults to 50, at most 200). `user_id` other than the caller is 403.
+#[get("/api/v1/feeds?<user_id>&<limit>&<offset>")]
 async fn list_feeds(
     state: &State<AppState>,
+    auth: crate::auth::AuthUser,
     user_id: Option<i64>,
-) -> Result<Json<Vec<FeedRow>>, Status> {
-    // Require a user_id to avoid exposing all subscriptions to unauthenticated callers.
-    // In a real deployment we'd extract the user from an auth guard; for now we
-    // use the optional query param and refuse to return everything when it's missing.
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Json<FeedPage>, Status> {
     let pool = &state.db;
+    let uid = auth.0;
+    if user_id.is_some_and(|id| id != uid) {
+        return Err(Status::Forbidden);
+    }
+    let limit = limit.unwrap_or(50).clamp(1, 200);
+    let offset = offset.unwrap_or(0).max(0);
 
-    // If user_id is provided, return subscriptions for that user only.
-    // If missing, return an empty list to avoid exposing other users' subscriptions.
-    let rows = if let Some(uid) = user_id {
-        // Query subscriptions joined with feeds for a specific user
-        sqlx::query(
-            r#"
-            SELECT
-                f.id as feed_id,
-                s.id as sub_id,
-                s.user_id,
-                f.url,
-                s.title,
-                f.last_checked,
-                f.status,
-                s.weight
-            FROM subscriptions s
-            JOIN feeds f ON s.feed_id = f.id
-            WHERE s.user_id = ?
-            "#,
-        )
+    let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM subscriptions WHERE user_id = ?")
         .bind(uid)
-        .fetch_all(pool)
+        .fetch_one(pool)
         .await
         .map_err(|e| {
-            tracing::error!("failed to query feeds for user {}: {}", uid, e);
+            tracing::error!("failed to count feeds for user {}: {}", uid, e);
             Status::InternalServerError
-        })?
-    } else {
-        // No user id provided: return empty list (do not leak all subscriptions)
-        Vec::new()
-    };
+        })?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            f.id as feed_id,
+            s.id as sub_id,
+            s.user_id,
+            f.url,
+            s.title,
+            f.last_checked,
+            f.status,
+            s.weight,
+            f.full_content,
+            f.poll_interval_minutes,
+            f.declared_update_minutes,
+            s.category,
+            f.consecutive_failures
+        FROM subscriptions s
+        JOIN feeds f ON s.feed_id = f.id
+        WHERE s.user_id = ?
+        ORDER BY s.id
+        LIMIT ? OFFSET ?
+        "#,
+    )
+    .bind(uid)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("failed to query feeds for user {}: {}", uid, e);
+        Status::InternalServerError
+    })?;
 
     let feeds = rows
         .into_iter()
@@ -253,10 +403,20 @@ async fn list_feeds(
             last_checked: r.get::<Option<String>, _>("last_checked"),
             status: r.get::<Option<String>, _>("status"),
             weight: r.get::<Option<i64>, _>("weight").unwrap_or(0),
+            full_content: r.get::<Option<bool>, _>("full_content").unwrap_or(false),
+            poll_interval_minutes: r.get("poll_interval_minutes"),
+            declared_update_minutes: r.get("declared_update_minutes"),
+            category: r.get("category"),
+            consecutive_failures: r.get("consecutive_failures"),
         })
         .collect();
 
-    Ok(Json(feeds))
+    Ok(Json(FeedPage {
+        feeds,
+        total,
+        limit,
+        offset,
+    }))
 }
 
 /// Request body for user registration.
@@ -268,10 +428,17 @@ struct RegisterRequest {
 }
 
 /// Request body for user login.
-#[derive(Deserialize)]
-struct LoginRequest {
-    username: String,
-    password: String,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+/// Response body for a successful login.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginResponse {
+    pub token: String,
+    pub user_id: i64,
 }
 
 /// JWT claims we encode (subject = user id)
@@ -281,25 +448,54 @@ struct Claims {
     exp: usize,
 }
 
-/// Authentication note: for backwards compatibility with the current Rocket version in this
-/// repository, we do not use a FromRequest request-guard implementation here. Instead, routes
-/// that accept a token will decode it from the request body (`token` field) when necessary.
-/// If you later upgrade Rocket and reintroduce a request guard, implement `FromRequest` that
-/// returns the correct `Outcome` type for your Rocket version.
-///
-/// The design decision keeps handler signatures simple and avoids compatibility issues
-/// with differing Rocket versions' Outcome generics.
+/// Default for `auth.token_ttl_hours`.
+pub const DEFAULT_TOKEN_TTL_HOURS: u64 = 24;
+
+/// Environment variable holding the JWT signing secret.
+pub const JWT_SECRET_ENV: &str = "MYNEWSLENS_JWT_SECRET";
+
+/// Lifetime of newly issued tokens, from `[auth]`.
+fn token_ttl_hours(config: Option<&Config>) -> u64 {
+    config
+        .and_then(|c| c.auth.as_ref())
+        .and_then(|a| a.token_ttl_hours)
+        .unwrap_or(DEFAULT_TOKEN_TTL_HOURS)
+}
+
+/// The JWT signing secret; debug builds fall back to a fixed development value.
+fn jwt_secret() -> String {
+    std::env::var(JWT_SECRET_ENV).unwrap_or_else(|_| "dev-secret".into())
+}
+
+/// Refuse to run a release build without a JWT secret: tokens signed with the
+/// public development value could be forged by anyone. Debug builds only warn.
+pub fn check_jwt_secret() -> Result<()> {
+    if std::env::var(JWT_SECRET_ENV).is_ok_and(|s| !s.is_empty()) {
+        return Ok(());
+    }
+    if cfg!(debug_assertions) {
+        tracing::warn!("{} is not set, signing tokens with the development secret", JWT_SECRET_ENV);
+        return Ok(());
+    }
+    Err(anyhow!("{} must be set to a random secret to sign login tokens", JWT_SECRET_ENV))
+}
+
+/// A login token for `user_id`, valid for `[auth] token_ttl_hours`.
+pub fn issue_token(user_id: i64, config: Option<&Config>) -> Result<String> {
+    create_jwt_for_user(user_id, token_ttl_hours(config)).context("failed to create jwt")
+}
 
-/// Create a signed JWT for a user id.
-/// Expiration is configurable; default 24h.
-fn create_jwt_for_user(user_id: i64) -> Result<String, jsonwebtoken::errors::Error> {
-    let secret = std::env::var("MYNEWSLENS_JWT_SECRET").unwrap_or_else(|_| "dev-secret".into());
+/// Authenticated routes take the `auth::AuthUser` request guard, which reads
+/// these tokens from `Authorization: Bearer <jwt>`.
+///
+/// Create a signed JWT for a user id, valid for `ttl_hours`.
+fn create_jwt_for_user(user_id: i64, ttl_hours: u64) -> Result<String, jsonwebtoken::errors::Error> {
+    let secret = jwt_secret();
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
         .as_secs() as usize;
-    // 24h expiry
-    let exp = now + (24 * 3600);
+    let exp = now + (ttl_hours as usize * 3600);
     let claims = Claims { sub: user_id, exp };
     encode(
         &JwtHeader::default(),
@@ -308,83 +504,190 @@ fn create_jwt_for_user(user_id: i64) -> Result<String, jsonwebtoken::errors::Err
     )
 }
 
+/// Decode and validate a JWT, returning its subject user id.
+/// Does not check `revoked_tokens`; see `auth::verify_jwt` for that.
+pub(crate) fn decode_jwt(token: &str) -> Option<i64> {
+    let secret = jwt_secret();
+    let decoding_key = jsonwebtoken::DecodingKey::from_secret(secret.as_bytes());
+    let validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256);
+    match jsonwebtoken::decode::<Claims>(token, &decoding_key, &validation) {
+        Ok(token_data) => Some(token_data.claims.sub),
+        Err(e) => {
+            tracing::warn!("failed to decode token: {}", e);
+            None
+        }
+    }
+}
+
+/// Fixed wait before every failed login answer, on top of the argon2 work, so
+/// a 401 is neither fast enough to brute-force nor timed by how it failed.
+pub const FAILED_LOGIN_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Hash checked against when the username is unknown, so a failed login costs
+/// one argon2 verification whether the user exists or not.
+fn dummy_password_hash() -> &'static str {
+    static HASH: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    HASH.get_or_init(|| crate::auth::hash_password("not a password").expect("hash dummy password"))
+}
+
+/// Error response of the login and registration endpoints.
+#[derive(Debug, rocket::Responder)]
+enum AuthFailure {
+    Status(Status),
+    /// The username is already registered: `{"error": "username_taken"}`
+    #[response(status = 409)]
+    Conflict(Json<serde_json::Value>),
+    /// Too many failed attempts, see [`crate::rate_limit::FailureLimiter`]
+    #[response(status = 429)]
+    Throttled((), rocket::http::Header<'static>),
+}
+
+impl From<Status> for AuthFailure {
+    fn from(status: Status) -> Self {
+        AuthFailure::Status(status)
+    }
+}
+
+impl AuthFailure {
+    fn throttled(wait: std::time::Duration) -> Self {
+        let seconds = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+        AuthFailure::Throttled((), rocket::http::Header::new("Retry-After", seconds.to_string()))
+    }
+}
+
+/// Limiter keys of an authentication attempt. `remote` is the peer address,
+/// not a client-supplied header such as `X-Real-IP`, which would let each
+/// attempt pick a fresh key.
+fn auth_limit_keys(remote: Option<std::net::SocketAddr>, username: Option<&str>) -> Vec<String> {
+    remote
+        .map(|addr| format!("ip:{}", addr.ip()))
+        .into_iter()
+        .chain(username.map(|u| format!("user:{}", u)))
+        .collect()
+}
+
 /// Register endpoint: create a user with hashed password and return a JWT.
+/// Failed registrations count against the client IP like failed logins.
 #[post("/api/v1/register", data = "<body>")]
 async fn register(
     state: &State<AppState>,
+    remote: Option<std::net::SocketAddr>,
     accept_lang: crate::sessions::websocket::AcceptLanguage,
     body: Json<RegisterRequest>,
-) -> Result<Json<serde_json::Value>, Status> {
-    let pool = &state.db;
-
-    // Hash password with Argon2 + random salt
-    let salt = SaltString::generate(&mut OsRng);
-    let argon = Argon2::default();
-    let password_hash = argon
-        .hash_password(body.password.as_bytes(), &salt)
-        .map_err(|e| {
-            tracing::error!("failed to hash password: {}", e);
-            Status::InternalServerError
-        })?
-        .to_string();
-
-    // Insert user
-    let res =
-        sqlx::query("INSERT INTO users (username, display_name, password_hash) VALUES (?, ?, ?)")
-            .bind(&body.username)
-            .bind(body.display_name.clone())
-            .bind(&password_hash)
-            .execute(pool)
-            .await
-            .map_err(|e| {
-                tracing::error!("failed to insert user: {}", e);
-                // If constraint violation (username exists) return conflict
-                Status::InternalServerError
-            })?;
-
-    let user_id = res.last_insert_rowid();
-
-    // Auto-create user profile with browser language
-    let browser_lang = &accept_lang.0;
-    let default_interests = serde_json::json!(["technology", "science", "news"]).to_string();
-
-    let _ = sqlx::query(
-        "INSERT INTO user_profiles
-         (user_id, language, complexity_level, interests)
-         VALUES (?, ?, 'medium', ?)"
-    )
-    .bind(user_id)
-    .bind(browser_lang)
-    .bind(default_interests)
-    .execute(pool)
-    .await
-    .map_err(|e| {
-        tracing::warn!("failed to create user profile (non-critical): {}", e);
-    });
+) -> Result<Json<LoginResponse>, AuthFailure> {
+    let keys = auth_limit_keys(remote, None);
+    if let Some(wait) = state.auth_limiter.retry_after(&keys) {
+        tracing::warn!("registration throttled for {:?}", remote);
+        return Err(AuthFailure::throttled(wait));
+    }
+    let res = register_user(state, accept_lang, body).await;
+    if res.is_err() {
+        state.auth_limiter.record_failure(&keys);
+    }
+    res
+}
 
-    // Create a default granular preference (example)
-    let _ = sqlx::query(
-        "INSERT INTO user_preferences
-         (user_id, preference_type, preference_key, preference_value)
-         VALUES (?, 'category_filter', 'technology', 1.0)"
+async fn register_user(
+    state: &State<AppState>,
+    accept_lang: crate::sessions::websocket::AcceptLanguage,
+    body: Json<RegisterRequest>,
+) -> Result<Json<LoginResponse>, AuthFailure> {
+    let created = crate::auth::create_user(
+        &state.db,
+        &body.username,
+        body.display_name.as_deref(),
+        &body.password,
+        &accept_lang.0,
     )
-    .bind(user_id)
-    .execute(pool)
     .await;
+    let user_id = match created {
+        Ok(Some(user_id)) => user_id,
+        Ok(None) => {
+            tracing::info!("registration refused, username {:?} is taken", body.username);
+            return Err(AuthFailure::Conflict(Json(serde_json::json!({ "error": "username_taken" }))));
+        }
+        Err(e) => {
+            tracing::error!("failed to register user: {:#}", e);
+            return Err(Status::InternalServerError.into());
+        }
+    };
 
     // Create JWT for the new user
-    match create_jwt_for_user(user_id) {
-        Ok(token) => Ok(Json(
-            serde_json::json!({ "token": token, "user_id": user_id }),
-        )),
+    match create_jwt_for_user(user_id, token_ttl_hours(state.config().as_deref())) {
+        Ok(token) => Ok(Json(LoginResponse { token, user_id })),
         Err(e) => {
             tracing::error!("failed to create jwt: {}", e);
-            Err(Status::InternalServerError)
+            Err(Status::InternalServerError.into())
         }
     }
 }
 
-/// Login endpoint: verify password and return JWT.
+/// Request body for `POST /api/v1/api-keys`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiKeyCreate {
+    pub name: Option<String>,
+    /// Key lifetime; omit for a key that never expires
+    pub expires_in_days: Option<i64>,
+}
+
+/// Response for a newly created API key. `key` is only ever shown here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyCreated {
+    pub key: String,
+    #[serde(flatten)]
+    pub info: crate::auth::ApiKeyInfo,
+}
+
+/// Create an API key for the authenticated user (JWT or existing key).
+#[post("/api/v1/api-keys", data = "<body>")]
+async fn create_api_key(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    body: Option<Json<ApiKeyCreate>>,
+) -> Result<Json<ApiKeyCreated>, Status> {
+    let req = body.map(|b| b.into_inner()).unwrap_or_default();
+    let expires_at = match req.expires_in_days {
+        Some(days) if days <= 0 => return Err(Status::BadRequest),
+        Some(days) => Some(Utc::now() + chrono::Duration::days(days)),
+        None => None,
+    };
+
+    crate::auth::create_api_key(&state.db, auth.0, req.name.as_deref(), expires_at)
+        .await
+        .map(|(info, key)| Json(ApiKeyCreated { key, info }))
+        .map_err(|e| {
+            tracing::error!("failed to create api key: {:?}", e);
+            Status::InternalServerError
+        })
+}
+
+/// List the authenticated user's API keys (without secrets).
+#[get("/api/v1/api-keys")]
+async fn list_api_keys(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+) -> Result<Json<Vec<crate::auth::ApiKeyInfo>>, Status> {
+    crate::auth::list_api_keys(&state.db, auth.0)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to list api keys: {:?}", e);
+            Status::InternalServerError
+        })
+}
+
+/// Revoke one of the authenticated user's API keys.
+#[delete("/api/v1/api-keys/<id>")]
+async fn revoke_api_key(state: &State<AppState>, auth: crate::auth::AuthUser, id: i64) -> Status {
+    match crate::auth::revoke_api_key(&state.db, auth.0, id).await {
+        Ok(true) => Status::NoContent,
+        Ok(false) => Status::NotFound,
+        Err(e) => {
+            tracing::error!("failed to revoke api key: {:?}", e);
+            Status::InternalServerError
+        }
+    }
+}
 
 /// Request body for logout (soft logout / token revocation)
 #[derive(Deserialize)]
@@ -416,12 +719,44 @@ async fn logout(state: &State<AppState>, body: Json<LogoutRequest>) -> Result<St
         }
     }
 }
+
+/// Login endpoint: verify password and return JWT. After too many failures
+/// from the client IP or for the username, answers 429 until the window passes.
 #[post("/api/v1/login", data = "<body>")]
 async fn login(
     state: &State<AppState>,
+    remote: Option<std::net::SocketAddr>,
     body: Json<LoginRequest>,
-) -> Result<Json<serde_json::Value>, Status> {
-    let pool = &state.db;
+) -> Result<Json<LoginResponse>, AuthFailure> {
+    let keys = auth_limit_keys(remote, Some(&body.username));
+    if let Some(wait) = state.auth_limiter.retry_after(&keys) {
+        tracing::warn!("login throttled for {:?} / {}", remote, body.username);
+        return Err(AuthFailure::throttled(wait));
+    }
+    let user_id = match verify_login(&state.db, &body).await {
+        Ok(user_id) => user_id,
+        Err(status) if status == Status::Unauthorized => {
+            state.auth_limiter.record_failure(&keys);
+            tokio::time::sleep(FAILED_LOGIN_DELAY).await;
+            return Err(Status::Unauthorized.into());
+        }
+        Err(status) => return Err(status.into()),
+    };
+    state.auth_limiter.reset(&keys);
+
+    // Create JWT
+    match create_jwt_for_user(user_id, token_ttl_hours(state.config().as_deref())) {
+        Ok(token) => Ok(Json(LoginResponse { token, user_id })),
+        Err(e) => {
+            tracing::error!("failed to create jwt: {}", e);
+            Err(Status::InternalServerError.into())
+        }
+    }
+}
+
+/// The id of the user `body` logs in as; `Unauthorized` for an unknown user
+/// or a wrong password.
+async fn verify_login(pool: &SqlitePool, body: &LoginRequest) -> Result<i64, Status> {
 
     // Fetch user by username
     let row = sqlx::query("SELECT id, password_hash FROM users WHERE username = ?")
@@ -433,33 +768,58 @@ async fn login(
             Status::InternalServerError
         })?;
 
-    let row = match row {
-        Some(r) => r,
-        None => return Err(Status::Unauthorized),
+    // An unknown user gets the same work as a wrong password, so timing
+    // doesn't tell whether the user exists
+    let (user_id, stored_hash) = match row {
+        Some(r) => (Some(r.get::<i64, _>("id")), Some(r.get::<String, _>("password_hash"))),
+        None => (None, None),
     };
+    let verified = password_matches(body.password.clone(), stored_hash).await?;
+    match user_id {
+        Some(user_id) if verified => Ok(user_id),
+        _ => Err(Status::Unauthorized),
+    }
+}
 
-    let user_id = row.get::<i64, _>("id");
-    let stored_hash: String = row.get::<String, _>("password_hash");
-
-    // Verify password using PasswordHash parser
-    let parsed_hash = PasswordHash::new(&stored_hash).map_err(|e| {
-        tracing::error!("invalid password hash in db: {}", e);
+/// Check `password` against the PHC string `hash` (the dummy hash when None)
+/// on the blocking pool, as an argon2 verification holds a core for tens of
+/// milliseconds.
+async fn password_matches(password: String, hash: Option<String>) -> Result<bool, Status> {
+    tokio::task::spawn_blocking(move || {
+        let hash = hash.as_deref().unwrap_or_else(|| dummy_password_hash());
+        let parsed_hash = PasswordHash::new(hash).map_err(|e| {
+            tracing::error!("invalid password hash in db: {}", e);
+            Status::InternalServerError
+        })?;
+        match Argon2::default().verify_password(password.as_bytes(), &parsed_hash) {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                tracing::warn!("password verify failed: {}", e);
+                Ok(false)
+            }
+        }
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("password verification task failed: {}", e);
         Status::InternalServerError
-    })?;
+    })?
+}
 
-    let argon = Argon2::default();
-    argon
-        .verify_password(body.password.as_bytes(), &parsed_hash)
-        .map_err(|e| {
-            tracing::warn!("password verify failed: {}", e);
-            Status::Unauthorized
-        })?;
+/// Request body for `POST /api/v1/refresh`.
+#[derive(Deserialize)]
+struct RefreshRequest {
+    token: String,
+}
 
-    // Create JWT
-    match create_jwt_for_user(user_id) {
-        Ok(token) => Ok(Json(
-            serde_json::json!({ "token": token, "user_id": user_id }),
-        )),
+/// Exchange a still-valid (unexpired, not revoked) JWT for a fresh one.
+#[post("/api/v1/refresh", data = "<body>")]
+async fn refresh_token(state: &State<AppState>, body: Json<RefreshRequest>) -> Result<Json<LoginResponse>, Status> {
+    let user_id = crate::auth::verify_jwt(&state.db, &body.token)
+        .await
+        .ok_or(Status::Unauthorized)?;
+    match create_jwt_for_user(user_id, token_ttl_hours(state.config().as_deref())) {
+        Ok(token) => Ok(Json(LoginResponse { token, user_id })),
         Err(e) => {
             tracing::error!("failed to create jwt: {}", e);
             Err(Status::InternalServerError)
@@ -478,45 +838,16 @@ async fn auto_extract_feed_title(url: &str) -> Option<String> {
     }
 }
 
-/// Create a new feed and subscribe to it. If token is provided in body, it will be used to identify user;
-/// explicit `user_id` takes precedence.
+/// Create a new feed and subscribe the authenticated user to it. A website URL
+/// is replaced by the feed its page advertises, and kept as the feed's `site_url`.
 #[post("/api/v1/feeds", data = "<body>")]
 async fn create_feed(
     state: &State<AppState>,
+    auth: crate::auth::AuthUser,
     body: Json<FeedCreate>,
-) -> Result<Json<serde_json::Value>, Status> {
+) -> Result<Json<FeedCreated>, Status> {
     let pool = &state.db;
-
-    // Determine user id: prefer explicit user_id, otherwise attempt to decode token.
-    let mut user_id_opt = body.user_id;
-
-    if user_id_opt.is_none() {
-        if let Some(ref token) = body.token {
-            // Use env secret (fallback to dev-secret for local dev)
-            let secret =
-                std::env::var("MYNEWSLENS_JWT_SECRET").unwrap_or_else(|_| "dev-secret".into());
-            let decoding_key = jsonwebtoken::DecodingKey::from_secret(secret.as_bytes());
-            let validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256);
-
-            match jsonwebtoken::decode::<Claims>(token, &decoding_key, &validation) {
-                Ok(token_data) => {
-                    user_id_opt = Some(token_data.claims.sub);
-                }
-                Err(e) => {
-                    tracing::warn!("create_feed: failed to decode token: {}", e);
-                    return Err(Status::Unauthorized);
-                }
-            }
-        }
-    }
-
-    let user_id = match user_id_opt {
-        Some(uid) => uid,
-        None => {
-            tracing::error!("create_feed: missing user_id and no valid token provided");
-            return Err(Status::BadRequest);
-        }
-    };
+    let user_id = auth.0;
 
     // Verify that the user exists
     let exists = sqlx::query_scalar::<_, i64>("SELECT id FROM users WHERE id = ?")
@@ -532,9 +863,105 @@ async fn create_feed(
         return Err(Status::Unauthorized);
     }
 
+    // A URL we don't know yet may be a homepage: look for the feed it advertises
+    let known = sqlx::query_scalar::<_, i64>("SELECT id FROM feeds WHERE url = ?")
+        .bind(&body.url)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("db error checking feed: {}", e);
+            Status::InternalServerError
+        })?
+        .is_some();
+    let resolved = if known {
+        None
+    } else {
+        let timeout_secs = ingestion::FetchOptions::from_config(state.config().as_deref()).timeout_secs;
+        match ingestion::resolve_feed_url(&body.url, timeout_secs).await {
+            Ok(resolved) => Some(resolved),
+            Err(e) => {
+                // Subscribe as given; the worker reports the feed's errors
+                tracing::warn!("create_feed: {:?}", e);
+                None
+            }
+        }
+    };
+
+    let subscribed = subscribe_to_feed(
+        pool,
+        user_id,
+        &NewSubscription {
+            url: resolved.as_ref().map_or(&body.url, |r| &r.feed_url),
+            title: body.title.as_deref(),
+            feed_title: resolved.as_ref().and_then(|r| r.title.as_deref()),
+            full_content: body.full_content,
+            site_url: resolved.as_ref().and_then(|r| r.site_url.as_deref()),
+            ..Default::default()
+        },
+    )
+    .await?;
+    Ok(Json(FeedCreated {
+        id: subscribed.feed_id,
+        subscription_id: subscribed.subscription_id,
+        message: (!subscribed.created).then(|| "Already subscribed".into()),
+    }))
+}
+
+/// Request body for `POST /api/v1/feeds/preview`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedPreviewRequest {
+    pub url: String,
+    /// Entries to return, 10 by default
+    pub limit: Option<usize>,
+}
+
+/// Show what subscribing to a URL would bring in, without storing anything.
+/// 422 when the URL serves no feed, 502 when it cannot be fetched.
+#[post("/api/v1/feeds/preview", data = "<body>")]
+async fn preview_feed(
+    state: &State<AppState>,
+    _auth: crate::auth::AuthUser,
+    body: Json<FeedPreviewRequest>,
+) -> Result<Json<ingestion::FeedPreview>, (Status, String)> {
+    let limit = body.limit.unwrap_or(10).clamp(1, 50);
+    // Same timeout as the worker's fetches (`politeness.fetch_timeout_seconds`)
+    let timeout_secs = ingestion::FetchOptions::from_config(state.config().as_deref()).timeout_secs;
+    ingestion::preview_feed(&body.url, timeout_secs, limit).await.map(Json).map_err(|e| {
+        tracing::warn!("preview_feed: {:#}", e);
+        if e.downcast_ref::<ingestion::NotAFeed>().is_some() {
+            (Status::UnprocessableEntity, e.to_string())
+        } else {
+            (Status::BadGateway, format!("{:#}", e))
+        }
+    })
+}
+
+/// A feed to subscribe a user to; the feed is created if its URL is unknown.
+#[derive(Debug, Default)]
+struct NewSubscription<'a> {
+    url: &'a str,
+    title: Option<&'a str>,
+    /// Title of a new feed when already known; otherwise `title`, or fetched
+    feed_title: Option<&'a str>,
+    full_content: Option<bool>,
+    /// Website of a new feed (`feeds.site_url`)
+    site_url: Option<&'a str>,
+    category: Option<&'a str>,
+}
+
+struct Subscribed {
+    feed_id: i64,
+    subscription_id: i64,
+    /// False when the user was already subscribed
+    created: bool,
+}
+
+/// Find or create the feed for `new.url` and subscribe `user_id` to it.
+/// Idempotent: an existing subscription is returned unchanged.
+async fn subscribe_to_feed(pool: &SqlitePool, user_id: i64, new: &NewSubscription<'_>) -> Result<Subscribed, Status> {
     // 1. Check if feed exists (by URL)
     let feed_id_opt = sqlx::query_scalar::<_, i64>("SELECT id FROM feeds WHERE url = ?")
-        .bind(&body.url)
+        .bind(new.url)
         .fetch_optional(pool)
         .await
         .map_err(|e| {
@@ -542,25 +969,30 @@ async fn create_feed(
             Status::InternalServerError
         })?;
 
+    // `full_content` only applies to a new feed: an existing one is shared with other subscribers
     let feed_id = if let Some(id) = feed_id_opt {
         id
     } else {
         // Determine title: use provided, or auto-extract from feed
-        let title = match body.title.as_deref() {
-            Some(t) if !t.is_empty() => Some(t.to_string()),
-            _ => auto_extract_feed_title(&body.url).await,
+        let title = match new.title.filter(|t| !t.is_empty()).or(new.feed_title) {
+            Some(t) => Some(t.to_string()),
+            None => auto_extract_feed_title(new.url).await,
         };
 
         // Create new feed with next_poll_at = NULL to trigger immediate polling
-        let res = sqlx::query("INSERT INTO feeds (url, title, next_poll_at) VALUES (?, ?, NULL)")
-            .bind(&body.url)
-            .bind(title.as_deref())
-            .execute(pool)
-            .await
-            .map_err(|e| {
-                tracing::error!("failed to insert feed: {}", e);
-                Status::InternalServerError
-            })?;
+        let res = sqlx::query(
+            "INSERT INTO feeds (url, title, site_url, next_poll_at, full_content) VALUES (?, ?, ?, NULL, ?)",
+        )
+        .bind(new.url)
+        .bind(title.as_deref())
+        .bind(new.site_url)
+        .bind(new.full_content.unwrap_or(false))
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to insert feed: {}", e);
+            Status::InternalServerError
+        })?;
         res.last_insert_rowid()
     };
 
@@ -578,17 +1010,20 @@ async fn create_feed(
         Status::InternalServerError
     })?;
 
-    if sub_exists.is_some() {
+    if let Some(sub_id) = sub_exists {
         // Already subscribed, return success (idempotent-ish)
-        return Ok(Json(
-            serde_json::json!({ "id": feed_id, "subscription_id": sub_exists.unwrap(), "message": "Already subscribed" }),
-        ));
+        return Ok(Subscribed {
+            feed_id,
+            subscription_id: sub_id,
+            created: false,
+        });
     }
 
-    let res = sqlx::query("INSERT INTO subscriptions (user_id, feed_id, title) VALUES (?, ?, ?)")
+    let res = sqlx::query("INSERT INTO subscriptions (user_id, feed_id, title, category) VALUES (?, ?, ?, ?)")
         .bind(user_id)
         .bind(feed_id)
-        .bind(body.title.as_deref())
+        .bind(new.title)
+        .bind(new.category)
         .execute(pool)
         .await
         .map_err(|e| {
@@ -597,162 +1032,311 @@ async fn create_feed(
         })?;
 
     let sub_id = res.last_insert_rowid();
-    Ok(Json(
-        serde_json::json!({ "id": feed_id, "subscription_id": sub_id }),
-    ))
+    if let Err(e) = crate::storage::reactivate_feed(pool, feed_id).await {
+        tracing::warn!("{:?}", e);
+    }
+    Ok(Subscribed {
+        feed_id,
+        subscription_id: sub_id,
+        created: true,
+    })
+}
+
+async fn set_feed_full_content(pool: &SqlitePool, feed_id: i64, full_content: bool) -> Result<(), Status> {
+    sqlx::query("UPDATE feeds SET full_content = ? WHERE id = ?")
+        .bind(full_content)
+        .bind(feed_id)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to update feed {}: {}", feed_id, e);
+            Status::InternalServerError
+        })?;
+    Ok(())
 }
 
-/// Import feeds from OPML file
-#[post("/api/v1/feeds/import/opml?<user_id>", data = "<data>")]
-async fn import_opds(
+/// Update the caller's subscription to a feed; 404 unless subscribed.
+/// `full_content` is shared by every subscriber of the feed, so changing it
+/// also requires the admin token (403 otherwise).
+#[patch("/api/v1/feeds/<id>", data = "<body>")]
+async fn update_feed(
     state: &State<AppState>,
-    user_id: i64,
-    data: Data<'_>,
-) -> Result<Json<OpdsImportResponse>, Status> {
+    auth: crate::auth::AuthUser,
+    admin: Option<crate::auth::AdminToken>,
+    id: i64,
+    body: Json<FeedUpdate>,
+) -> Status {
     let pool = &state.db;
+    let user_id = auth.0;
+    if body.user_id.is_some_and(|id| id != user_id) || (body.full_content.is_some() && admin.is_none()) {
+        return Status::Forbidden;
+    }
+    if body.weight.is_some_and(|w| !SUBSCRIPTION_WEIGHT_RANGE.contains(&w)) {
+        return Status::BadRequest;
+    }
 
-    // Read uploaded file (limit to 10MB)
+    let subscribed = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM subscriptions WHERE user_id = ? AND feed_id = ?",
+    )
+    .bind(user_id)
+    .bind(id)
+    .fetch_one(pool)
+    .await;
+    match subscribed {
+        Ok(0) => return Status::NotFound,
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("db error checking subscription: {}", e);
+            return Status::InternalServerError;
+        }
+    }
+
+    if let Some(full_content) = body.full_content {
+        if let Err(status) = set_feed_full_content(pool, id, full_content).await {
+            return status;
+        }
+    }
+    if let Some(weight) = body.weight {
+        let res = sqlx::query("UPDATE subscriptions SET weight = ? WHERE user_id = ? AND feed_id = ?")
+            .bind(weight)
+            .bind(user_id)
+            .bind(id)
+            .execute(pool)
+            .await;
+        if let Err(e) = res {
+            tracing::error!("failed to update subscription weight for feed {}: {}", id, e);
+            return Status::InternalServerError;
+        }
+    }
+    Status::NoContent
+}
+
+/// The caller's feeds disabled after repeated permanent fetch failures.
+#[get("/api/v1/feeds/disabled")]
+async fn list_disabled_feeds(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+) -> Result<Json<Vec<crate::feed_health::DisabledFeed>>, Status> {
+    crate::feed_health::disabled_feeds(&state.db, auth.0).await.map(Json).map_err(|e| {
+        tracing::error!("failed to list disabled feeds: {:#}", e);
+        Status::InternalServerError
+    })
+}
+
+/// Re-enable a disabled feed; only its subscribers may. Idempotent.
+#[post("/api/v1/feeds/<id>/enable")]
+async fn enable_feed(state: &State<AppState>, auth: crate::auth::AuthUser, id: i64) -> Status {
+    let subscribed = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM subscriptions WHERE user_id = ? AND feed_id = ?")
+        .bind(auth.0)
+        .bind(id)
+        .fetch_one(&state.db)
+        .await;
+    match subscribed {
+        Ok(0) => return Status::NotFound,
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("db error checking subscription: {}", e);
+            return Status::InternalServerError;
+        }
+    }
+    match crate::feed_health::enable_feed(&state.db, id).await {
+        Ok(true) => {
+            tracing::info!("user {} re-enabled feed {}", auth.0, id);
+            Status::NoContent
+        }
+        Ok(false) => Status::NoContent,
+        Err(e) => {
+            tracing::error!("failed to enable feed {}: {:#}", id, e);
+            Status::InternalServerError
+        }
+    }
+}
+
+/// Unsubscribe the authenticated user from a feed.
+#[delete("/api/v1/feeds/<subscription_id>")]
+async fn delete_subscription(state: &State<AppState>, auth: crate::auth::AuthUser, subscription_id: i64) -> Status {
+    let user_id = auth.0;
+
+    match crate::storage::unsubscribe(&state.db, user_id, subscription_id).await {
+        Ok(Some(feed_id)) => {
+            tracing::info!("user {} unsubscribed from feed {}", user_id, feed_id);
+            Status::NoContent
+        }
+        Ok(None) => Status::NotFound,
+        Err(e) => {
+            tracing::error!("failed to unsubscribe: {:?}", e);
+            Status::InternalServerError
+        }
+    }
+}
+
+/// Articles seen in a feed, with their summaries, newest first. Only
+/// subscribers of the feed may browse it (404 otherwise); `user_id` other
+/// than the caller is 403.
+#[get("/api/v1/feeds/<id>/articles?<user_id>&<limit>&<offset>")]
+async fn list_feed_articles(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    id: i64,
+    user_id: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Json<Vec<crate::storage::FeedArticle>>, Status> {
+    let pool = &state.db;
+    if user_id.is_some_and(|uid| uid != auth.0) {
+        return Err(Status::Forbidden);
+    }
+    let user_id = auth.0;
+
+    let subscribed = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM subscriptions WHERE user_id = ? AND feed_id = ?",
+    )
+    .bind(user_id)
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("db error checking subscription: {}", e);
+        Status::InternalServerError
+    })?;
+    if subscribed == 0 {
+        return Err(Status::NotFound);
+    }
+
+    let limit = limit.unwrap_or(50).clamp(1, 200);
+    let offset = offset.unwrap_or(0).max(0);
+    crate::storage::list_feed_articles(pool, id, limit, offset)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to list articles of feed {}: {:?}", id, e);
+            Status::InternalServerError
+        })
+}
+
+/// Feeds subscribed at once during an OPML import.
+const OPML_IMPORT_CONCURRENCY: usize = 8;
+
+/// Subscribe the authenticated user to every feed of an OPML document (request
+/// body), with the same find-or-create logic as `POST /api/v1/feeds`. Folder
+/// outlines are kept as the subscriptions' `category`.
+#[post("/api/v1/feeds/import", data = "<data>")]
+async fn import_opml(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    data: Data<'_>,
+) -> Result<Json<OpmlImportResponse>, Status> {
+    use rocket::futures::StreamExt;
+
+    let pool = &state.db;
+    let user_id = auth.0;
+
+    // Read uploaded file (limit to 10MB)
     let bytes = data.open(10.megabytes()).into_bytes().await.map_err(|e| {
         tracing::error!("Failed to read upload: {}", e);
         Status::BadRequest
     })?;
-
     if !bytes.is_complete() {
         tracing::error!("Upload too large");
         return Err(Status::PayloadTooLarge);
     }
+    let feeds = crate::opml::parse_opml(&bytes.into_inner()).map_err(|e| {
+        tracing::warn!("OPML import for user {}: {:?}", user_id, e);
+        Status::BadRequest
+    })?;
 
-    let content = bytes.into_inner();
-
-    // Parse OPML using quick-xml
-    use quick_xml::events::Event;
-    use quick_xml::Reader;
-
-    let mut reader = Reader::from_reader(&content[..]);
-    reader.trim_text(true);
-
-    let mut added = 0;
-    let mut duplicates = 0;
-    let mut errors = Vec::new();
-
-    // Parse <outline> elements
-    let mut buf = Vec::new();
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Empty(e)) if e.name().as_ref() == b"outline" => {
-                // Extract attributes
-                let mut xml_url: Option<String> = None;
-                let mut title: Option<String> = None;
-
-                for attr in e.attributes() {
-                    if let Ok(attr) = attr {
-                        match attr.key.as_ref() {
-                            b"xmlUrl" => {
-                                xml_url = String::from_utf8(attr.value.to_vec()).ok();
-                            }
-                            b"text" | b"title" => {
-                                if title.is_none() {
-                                    title = String::from_utf8(attr.value.to_vec()).ok();
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                }
+    let mut summary = OpmlImportResponse::default();
+    let mut seen = std::collections::HashSet::new();
+    let mut valid = Vec::new();
+    for feed in feeds {
+        let is_http = url::Url::parse(&feed.url)
+            .map(|u| matches!(u.scheme(), "http" | "https"))
+            .unwrap_or(false);
+        if !is_http {
+            summary.failed += 1;
+            summary.errors.push(format!("Invalid feed URL: {}", feed.url));
+        } else if !seen.insert(feed.url.clone()) {
+            summary.skipped += 1;
+        } else {
+            valid.push(feed);
+        }
+    }
 
-                // Process feed if xmlUrl found
-                if let Some(url) = xml_url {
-                    // Auto-extract title if not in OPML
-                    let final_title = match title {
-                        Some(t) if !t.is_empty() => Some(t),
-                        _ => auto_extract_feed_title(&url).await,
-                    };
-
-                    // Check if feed exists
-                    match sqlx::query_scalar::<_, i64>("SELECT id FROM feeds WHERE url = ?")
-                        .bind(&url)
-                        .fetch_optional(pool)
-                        .await
-                    {
-                        Ok(feed_id_opt) => {
-                            let feed_id = if let Some(id) = feed_id_opt {
-                                id
-                            } else {
-                                // Create new feed
-                                match sqlx::query("INSERT INTO feeds (url, title, next_poll_at) VALUES (?, ?, NULL)")
-                                    .bind(&url)
-                                    .bind(final_title.as_deref())
-                                    .execute(pool)
-                                    .await
-                                {
-                                    Ok(res) => res.last_insert_rowid(),
-                                    Err(e) => {
-                                        errors.push(format!("Failed to create feed {}: {}", url, e));
-                                        continue;
-                                    }
-                                }
-                            };
-
-                            // Create subscription if not exists
-                            match sqlx::query_scalar::<_, i64>(
-                                "SELECT id FROM subscriptions WHERE user_id = ? AND feed_id = ?",
-                            )
-                            .bind(user_id)
-                            .bind(feed_id)
-                            .fetch_optional(pool)
-                            .await
-                            {
-                                Ok(Some(_)) => {
-                                    duplicates += 1;
-                                }
-                                Ok(None) => {
-                                    // Add subscription
-                                    match sqlx::query(
-                                        "INSERT INTO subscriptions (user_id, feed_id, title) VALUES (?, ?, ?)"
-                                    )
-                                        .bind(user_id)
-                                        .bind(feed_id)
-                                        .bind(final_title.as_deref())
-                                        .execute(pool)
-                                        .await
-                                    {
-                                        Ok(_) => added += 1,
-                                        Err(e) => {
-                                            errors.push(format!("Failed to subscribe to {}: {}", url, e));
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    errors.push(format!(
-                                        "DB error checking subscription for {}: {}",
-                                        url, e
-                                    ));
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            errors.push(format!("DB error checking feed {}: {}", url, e));
-                        }
-                    }
-                }
-            }
-            Ok(Event::Eof) => break,
-            Err(e) => {
-                errors.push(format!("XML parse error: {}", e));
-                break;
+    let results: Vec<(String, Result<Subscribed, Status>)> = rocket::futures::stream::iter(valid)
+        .map(|feed| async move {
+            let new = NewSubscription {
+                url: &feed.url,
+                title: feed.title.as_deref(),
+                site_url: feed.html_url.as_deref(),
+                category: feed.category.as_deref(),
+                ..Default::default()
+            };
+            let result = subscribe_to_feed(pool, user_id, &new).await;
+            (feed.url, result)
+        })
+        .buffer_unordered(OPML_IMPORT_CONCURRENCY)
+        .collect()
+        .await;
+    for (url, result) in results {
+        match result {
+            Ok(s) if s.created => summary.added += 1,
+            Ok(_) => summary.skipped += 1,
+            Err(_) => {
+                summary.failed += 1;
+                summary.errors.push(format!("Failed to subscribe to {}", url));
             }
-            _ => {}
         }
-        buf.clear();
     }
 
-    let errors_count = errors.len();
-    Ok(Json(OpdsImportResponse {
-        added,
-        duplicates,
-        errors,
-        total_processed: added + duplicates + errors_count,
-    }))
+    tracing::info!(
+        "OPML import for user {}: {} added, {} skipped, {} failed",
+        user_id, summary.added, summary.skipped, summary.failed
+    );
+    Ok(Json(summary))
+}
+
+/// Download the authenticated user's subscriptions as OPML 2.0, folders from
+/// their `category`. `user_id`, if given, must be the caller.
+#[get("/api/v1/feeds/export?<user_id>")]
+async fn export_opml(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    user_id: Option<i64>,
+) -> Result<(ContentType, String), Status> {
+    let uid = auth.0;
+    if user_id.is_some_and(|id| id != uid) {
+        return Err(Status::Forbidden);
+    }
+    let rows = sqlx::query(
+        r#"
+        SELECT f.url, COALESCE(s.title, f.title) as title, f.site_url, s.category
+        FROM subscriptions s
+        JOIN feeds f ON s.feed_id = f.id
+        WHERE s.user_id = ?
+        ORDER BY s.id
+        "#,
+    )
+    .bind(uid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("failed to query feeds for export, user {}: {}", uid, e);
+        Status::InternalServerError
+    })?;
+
+    let feeds: Vec<crate::opml::OpmlFeed> = rows
+        .into_iter()
+        .map(|r| crate::opml::OpmlFeed {
+            url: r.get("url"),
+            title: r.get("title"),
+            html_url: r.get("site_url"),
+            category: r.get("category"),
+        })
+        .collect();
+    Ok((
+        ContentType::new("text", "x-opml"),
+        crate::opml::write_opml("Newscope subscriptions", &feeds),
+    ))
 }
 
 /// Minimal fetch trigger for a feed: enqueues a background task that will perform the fetch.
@@ -762,32 +1346,52 @@ struct FetchRequest {
     feed_id: i64,
 }
 
+/// Fetch one of the authenticated user's feeds now. 404 unless subscribed.
 #[post("/api/v1/fetch", data = "<req>")]
-async fn trigger_fetch(state: &State<AppState>, req: Json<FetchRequest>) -> Result<Status, Status> {
+async fn trigger_fetch(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    req: Json<FetchRequest>,
+) -> Result<Status, Status> {
     let feed_id = req.feed_id;
+    let subscribed = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM subscriptions WHERE user_id = ? AND feed_id = ?",
+    )
+    .bind(auth.0)
+    .bind(feed_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("db error checking subscription: {}", e);
+        Status::InternalServerError
+    })?;
+    if subscribed == 0 {
+        return Err(Status::NotFound);
+    }
+
     let pool = state.db.clone();
-    let llm_provider = state.summarization_llm.clone();
-    let config = state.config.clone();
+    let summarize = state.summarization_llm.is_some();
+    let config = state.config();
 
-    let personalization_llm = state.personalization_llm.clone();
     // Spawn a background task to fetch and parse the feed
     tokio::spawn(async move {
         tracing::info!("manual fetch: triggered for feed id {}", feed_id);
 
         // Get feed URL
         let feed_row = sqlx::query(
-            "SELECT url, poll_interval_minutes, adaptive_scheduling FROM feeds WHERE id = ?",
+            "SELECT url, poll_interval_minutes, adaptive_scheduling, last_checked FROM feeds WHERE id = ?",
         )
         .bind(feed_id)
         .fetch_optional(&pool)
         .await;
 
-        let (url, mut interval, adaptive) = match feed_row {
+        let (url, mut interval, adaptive, first_fetch) = match feed_row {
             Ok(Some(row)) => {
                 let url: String = row.try_get("url").unwrap_or_default();
                 let interval: i64 = row.try_get("poll_interval_minutes").unwrap_or(60);
                 let adaptive: bool = row.try_get("adaptive_scheduling").unwrap_or(false);
-                (url, interval, adaptive)
+                let last_checked: Option<String> = row.try_get("last_checked").unwrap_or(None);
+                (url, interval, adaptive, last_checked.is_none())
             }
             Ok(None) => {
                 tracing::error!("manual fetch: feed {} not found", feed_id);
@@ -800,27 +1404,51 @@ async fn trigger_fetch(state: &State<AppState>, req: Json<FetchRequest>) -> Resu
         };
 
         // Fetch and parse feed
-        let timeout = config
-            .as_ref()
-            .and_then(|c| c.politeness.as_ref())
-            .and_then(|p| p.fetch_timeout_seconds)
-            .unwrap_or(10);
-
-        let fetch_result = ingestion::fetch_and_parse_feed(&url, timeout).await;
+        let fetch_opts = ingestion::FetchOptions::from_config(config.as_deref());
+        // A manual fetch always downloads the feed, but keeps its validators for the worker
+        let (fetch_result, fetch_metrics) =
+            ingestion::fetch_feed_conditional(&url, &fetch_opts, &ingestion::CacheValidators::default()).await;
+        let fetch_error = fetch_result.as_ref().err().map(|e| e.to_string());
+        if let Err(e) = crate::feed_health::record_fetch(&pool, feed_id, &fetch_metrics, fetch_error.as_deref()).await {
+            tracing::warn!("manual fetch: {}", e);
+        }
+        if fetch_result.is_ok() {
+            if let Err(e) = crate::feed_health::record_success(&pool, feed_id).await {
+                tracing::warn!("manual fetch: {}", e);
+            }
+        }
 
         let mut new_items_found = false;
         let fetch_success = fetch_result.is_ok();
+        let mut declared_interval = None;
 
         match fetch_result {
-            Ok(feed) => {
+            Ok(ingestion::FetchOutcome::NotModified) => {
+                tracing::info!("manual fetch: feed {} not modified", feed_id);
+            }
+            Ok(ingestion::FetchOutcome::Fetched { feed, validators }) => {
                 tracing::info!(
                     "manual fetch: successfully fetched feed {}, found {} items",
                     feed_id,
                     feed.entries.len()
                 );
+                if let Err(e) = storage::set_cache_validators(&pool, feed_id, &validators).await {
+                    tracing::warn!("manual fetch: {}", e);
+                }
+
+                declared_interval = ingestion::declared_poll_interval(&feed);
+                if let Err(e) = storage::set_declared_update_minutes(&pool, feed_id, declared_interval).await {
+                    tracing::warn!("manual fetch: {}", e);
+                }
 
-                match storage::store_feed_items(&pool, feed_id, &feed.entries).await {
-                    Ok(new_article_ids) => {
+                let transform = storage::EntryTransform::for_feed(config.as_deref(), &url);
+                let urls = crate::dedup::UrlNormalizer::from_config(config.as_deref());
+                let merge_by_hash = crate::dedup::merge_by_content_hash(config.as_deref());
+                match storage::store_feed_items_with_stats(&pool, feed_id, &feed.entries, transform.as_ref(), &urls, merge_by_hash).await {
+                    Ok((new_article_ids, dedup_stats)) => {
+                        if let Err(e) = crate::dedup::record_feed_stats(&pool, feed_id, &dedup_stats).await {
+                            tracing::warn!("manual fetch: {}", e);
+                        }
                         let new_count = new_article_ids.len();
                         if new_count > 0 {
                             new_items_found = true;
@@ -830,35 +1458,11 @@ async fn trigger_fetch(state: &State<AppState>, req: Json<FetchRequest>) -> Resu
                                 feed_id
                             );
 
-                            // Process articles with LLM if available
-                            if let Some(llm_prov) = llm_provider.clone() {
-                                let pool_clone = pool.clone();
-                                let model = config
-                                    .as_ref()
-                                    .and_then(|c| c.llm.as_ref())
-                                    .and_then(|l| l.remote.as_ref())
-                                    .and_then(|r| r.model.as_deref())
-                                    .unwrap_or("unknown")
-                                    .to_string();
-                                let ids = new_article_ids.clone();
-
-                                let pers_llm_inner = personalization_llm.clone();
-                                tokio::spawn(async move {
-                                    if let Err(e) = crate::processing::batch_process_articles(
-                                        &pool_clone,
-                                        &ids,
-                                        llm_prov,
-                                        pers_llm_inner,
-                                        &model,
-                                    )
-                                    .await
-                                    {
-                                        tracing::error!(
-                                            "manual fetch: failed to process articles: {}",
-                                            e
-                                        );
-                                    }
-                                });
+                            // Queued for the worker's job runner
+                            if summarize {
+                                if let Err(e) = crate::jobs::enqueue(&pool, crate::jobs::JOB_SUMMARY, &new_article_ids).await {
+                                    tracing::error!("manual fetch: {:#}", e);
+                                }
                             }
                         } else {
                             tracing::info!("manual fetch: no new articles for feed {}", feed_id);
@@ -873,13 +1477,25 @@ async fn trigger_fetch(state: &State<AppState>, req: Json<FetchRequest>) -> Resu
                     }
                 }
             }
+            Err(e) if e.downcast_ref::<ingestion::RobotsBlocked>().is_some() => {
+                tracing::info!("manual fetch: skipping feed {}: {}", feed_id, e);
+            }
             Err(e) => {
                 tracing::error!("manual fetch: failed to fetch feed {}: {}", feed_id, e);
+                let permanent = crate::feed_health::is_permanent_failure(fetch_metrics.http_status);
+                let max_failures = crate::feed_health::max_consecutive_failures(config.as_deref());
+                match crate::feed_health::record_failure(&pool, feed_id, permanent, max_failures).await {
+                    Ok(true) => tracing::warn!("manual fetch: feed {} disabled after {} failures", feed_id, max_failures),
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("manual fetch: {}", e),
+                }
             }
         }
 
-        // Adaptive logic (same as worker)
-        if adaptive && fetch_success {
+        // A new feed starts from its declared cadence, then adapts (same as worker)
+        if let (true, Some(declared)) = (first_fetch, declared_interval) {
+            interval = declared;
+        } else if adaptive && fetch_success {
             if new_items_found {
                 interval = (interval / 2).max(15);
             } else {
@@ -906,152 +1522,936 @@ async fn trigger_fetch(state: &State<AppState>, req: Json<FetchRequest>) -> Resu
         }
     });
 
-    Ok(Status::Accepted)
+    Ok(Status::Accepted)
+}
+
+// ============================================================================
+// Session Management Endpoints
+// ============================================================================
+
+/// Request body for `POST /api/v1/sessions`; the session belongs to the authenticated user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSessionRequest {
+    pub duration_seconds: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct SessionWithMessages {
+    session: crate::sessions::Session,
+    messages: Vec<crate::sessions::ChatMessage>,
+}
+
+#[post("/api/v1/sessions", data = "<body>")]
+async fn create_session(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    body: Json<CreateSessionRequest>,
+) -> Result<Json<crate::sessions::Session>, Status> {
+    let pool = &state.db;
+    let user_id = auth.0;
+
+    // Prevent creating a session for a user who has no subscriptions.
+    // New users should not see other users' feeds and must add at least one feed before starting a session.
+    let subs_count_res =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM subscriptions WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await;
+
+    let subs_count = match subs_count_res {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!(
+                "db error while checking subscriptions for user {}: {}",
+                user_id,
+                e
+            );
+            return Err(Status::InternalServerError);
+        }
+    };
+
+    if subs_count == 0 {
+        tracing::warn!(
+            "user {} attempted to create a session but has no subscriptions",
+            user_id
+        );
+        // Bad request: user must add subscriptions before creating a session
+        return Err(Status::BadRequest);
+    }
+
+    match crate::sessions::create_session(&state.db, user_id, body.duration_seconds).await {
+        Ok(session) => Ok(Json(session)),
+        Err(e) => {
+            tracing::error!(
+                "create_session failed for user_id={} duration_seconds={:?}: {:?}",
+                user_id,
+                body.duration_seconds,
+                e
+            );
+            Err(Status::InternalServerError)
+        }
+    }
+}
+
+/// Status for a data-layer error: 404 when the requested row does not exist,
+/// 500 for genuine failures.
+pub(crate) fn error_status(e: &anyhow::Error) -> Status {
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::RowNotFound) => Status::NotFound,
+        _ => Status::InternalServerError,
+    }
+}
+
+#[get("/api/v1/sessions")]
+async fn list_sessions(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+) -> Result<Json<Vec<crate::sessions::Session>>, Status> {
+    let user_id = auth.0;
+    crate::sessions::list_sessions(&state.db, user_id)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to list sessions for user {}: {:?}", user_id, e);
+            Status::InternalServerError
+        })
+}
+
+/// Load one of the authenticated user's sessions. Other users' sessions are 404.
+#[get("/api/v1/sessions/<session_id>")]
+async fn get_session(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    session_id: i64,
+) -> Result<Json<SessionWithMessages>, Status> {
+    crate::sessions::get_session_with_messages(&state.db, session_id)
+        .await
+        .and_then(|(session, messages)| {
+            if session.user_id != auth.0 {
+                return Err(sqlx::Error::RowNotFound.into());
+            }
+            Ok(Json(SessionWithMessages { session, messages }))
+        })
+        .map_err(|e| {
+            let status = error_status(&e);
+            if status == Status::InternalServerError {
+                tracing::error!("failed to load session {}: {:?}", session_id, e);
+            }
+            status
+        })
+}
+
+#[derive(Deserialize)]
+struct UpdateSessionRequest {
+    title: String,
+}
+
+/// Rename one of the authenticated user's sessions.
+#[put("/api/v1/sessions/<session_id>", data = "<body>")]
+async fn update_session(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    session_id: i64,
+    body: Json<UpdateSessionRequest>,
+) -> Result<Status, Status> {
+    rename_session(state, auth, session_id, &body.title).await
+}
+
+/// Same as `PUT /api/v1/sessions/<id>`.
+#[patch("/api/v1/sessions/<session_id>", data = "<body>")]
+async fn patch_session(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    session_id: i64,
+    body: Json<UpdateSessionRequest>,
+) -> Result<Status, Status> {
+    rename_session(state, auth, session_id, &body.title).await
+}
+
+async fn rename_session(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    session_id: i64,
+    title: &str,
+) -> Result<Status, Status> {
+    let owned = async {
+        let session = crate::sessions::get_session(&state.db, session_id).await?;
+        if session.user_id != auth.0 {
+            return Err(sqlx::Error::RowNotFound.into());
+        }
+        crate::sessions::update_session_title(&state.db, session_id, title).await
+    };
+    owned
+        .await
+        .map(|_| Status::Ok)
+        .map_err(|e| {
+            let status = error_status(&e);
+            if status == Status::InternalServerError {
+                tracing::error!("failed to update session {}: {:?}", session_id, e);
+            }
+            status
+        })
+}
+
+/// Delete one of the authenticated user's sessions and its history. Other
+/// users' sessions are 404.
+#[delete("/api/v1/sessions/<session_id>")]
+async fn delete_session(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    session_id: i64,
+) -> Status {
+    match crate::sessions::delete_session(&state.db, auth.0, session_id).await {
+        Ok(true) => Status::NoContent,
+        Ok(false) => Status::NotFound,
+        Err(e) => {
+            tracing::error!("failed to delete session {}: {:?}", session_id, e);
+            Status::InternalServerError
+        }
+    }
+}
+
+/// Topic timeline: the caller's articles matching `q` (via embeddings)
+/// bucketed by day. `user_id` other than the caller is 403.
+#[get("/api/v1/timeline?<q>&<user_id>&<limit>")]
+async fn timeline(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    q: String,
+    user_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Json<Vec<crate::timeline::TimelineBucket>>, Status> {
+    if user_id.is_some_and(|id| id != auth.0) {
+        return Err(Status::Forbidden);
+    }
+    let user_id = auth.0;
+    if q.trim().is_empty() {
+        return Err(Status::BadRequest);
+    }
+    let provider = state.embedding_llm.clone().ok_or(Status::ServiceUnavailable)?;
+    let limit = limit.unwrap_or(200).clamp(1, 1000);
+
+    crate::timeline::build_timeline(&state.db, provider, user_id, q.trim(), limit)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to build timeline for user {}: {:?}", user_id, e);
+            Status::InternalServerError
+        })
+}
+
+/// Full-text search over the articles of the caller's subscribed feeds, best
+/// matches first. `"quoted text"` searches for a phrase. `user_id` other than
+/// the caller is 403.
+#[get("/api/v1/search?<q>&<user_id>&<limit>")]
+async fn search(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    q: String,
+    user_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Json<Vec<crate::search::SearchHit>>, Status> {
+    if user_id.is_some_and(|id| id != auth.0) {
+        return Err(Status::Forbidden);
+    }
+    let user_id = auth.0;
+    if crate::search::fts_query(&q).is_none() {
+        return Err(Status::BadRequest);
+    }
+    let limit = limit.unwrap_or(20).clamp(1, 100);
+
+    crate::search::search_articles(&state.db, user_id, &q, limit)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to search articles for user {}: {:?}", user_id, e);
+            Status::InternalServerError
+        })
+}
+
+/// A single article with its content, stored summary, categories and the
+/// caller's feeds it appeared in; 404 unless it appeared in one of them.
+#[get("/api/v1/articles/<id>")]
+async fn get_article(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    id: i64,
+) -> Result<Json<crate::storage::ArticleDetail>, Status> {
+    match crate::storage::get_article_detail(&state.db, auth.0, id).await {
+        Ok(Some(article)) => Ok(Json(article)),
+        Ok(None) => Err(Status::NotFound),
+        Err(e) => {
+            tracing::error!("failed to load article {} for user {}: {:?}", id, auth.0, e);
+            Err(Status::InternalServerError)
+        }
+    }
+}
+
+/// Related stories: the `k` articles (default 5, at most 50) from the caller's
+/// feeds whose embeddings are closest to this one's. Empty until the article
+/// has been embedded; 404 when the caller doesn't see the article.
+#[get("/api/v1/articles/<id>/similar?<k>")]
+async fn similar_articles(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    id: i64,
+    k: Option<i64>,
+) -> Result<Json<Vec<crate::processing::SimilarArticle>>, Status> {
+    let k = k.unwrap_or(5).clamp(1, 50);
+    match crate::processing::find_similar_articles(&state.db, auth.0, id, k).await {
+        Ok(Some(similar)) => Ok(Json(similar)),
+        Ok(None) => Err(Status::NotFound),
+        Err(e) => {
+            tracing::error!("failed to find articles similar to {} for user {}: {:?}", id, auth.0, e);
+            Err(Status::InternalServerError)
+        }
+    }
+}
+
+/// Mark an article as read by the authenticated user.
+#[post("/api/v1/articles/<id>/view")]
+async fn mark_article_viewed(state: &State<AppState>, auth: crate::auth::AuthUser, id: i64) -> Status {
+    match crate::views::mark_viewed(&state.db, auth.0, id).await {
+        Ok(true) => Status::NoContent,
+        Ok(false) => Status::NotFound,
+        Err(e) => {
+            tracing::error!("failed to mark article {} viewed for user {}: {:?}", id, auth.0, e);
+            Status::InternalServerError
+        }
+    }
+}
+
+/// Mark an article as unread again for the authenticated user.
+#[delete("/api/v1/articles/<id>/view")]
+async fn mark_article_unread(state: &State<AppState>, auth: crate::auth::AuthUser, id: i64) -> Status {
+    match crate::views::mark_unread(&state.db, auth.0, id).await {
+        Ok(_) => Status::NoContent,
+        Err(e) => {
+            tracing::error!("failed to mark article {} unread for user {}: {:?}", id, auth.0, e);
+            Status::InternalServerError
+        }
+    }
+}
+
+/// Request body for `POST /api/v1/articles/<id>/rating`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingUpdate {
+    /// 1 to 5 stars
+    pub rating: i64,
+}
+
+/// The authenticated user's rating of an article (`null` until rated).
+#[get("/api/v1/articles/<id>/rating")]
+async fn get_article_rating(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    id: i64,
+) -> Result<Json<crate::views::ArticleRating>, Status> {
+    crate::views::get_rating(&state.db, auth.0, id)
+        .await
+        .map(|rating| Json(crate::views::ArticleRating { article_id: id, rating }))
+        .map_err(|e| {
+            tracing::error!("failed to load rating of article {} for user {}: {:?}", id, auth.0, e);
+            Status::InternalServerError
+        })
+}
+
+/// Rate an article 1-5 stars, marking it read; 400 outside that range. Like a
+/// websocket rating, it also nudges the user's interest vector.
+#[post("/api/v1/articles/<id>/rating", data = "<body>")]
+async fn rate_article(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    id: i64,
+    body: Json<RatingUpdate>,
+) -> Result<Json<crate::views::ArticleRating>, Status> {
+    let (user_id, rating) = (auth.0, body.rating);
+    if !crate::views::RATING_RANGE.contains(&rating) {
+        return Err(Status::BadRequest);
+    }
+    match crate::views::rate_article(&state.db, user_id, id, rating).await {
+        Ok(true) => {}
+        Ok(false) => return Err(Status::NotFound),
+        Err(e) => {
+            tracing::error!("failed to rate article {} for user {}: {:?}", id, user_id, e);
+            return Err(Status::InternalServerError);
+        }
+    }
+
+    let pool = state.db.clone();
+    tokio::spawn(async move {
+        let weight = crate::views::rating_weight(rating);
+        if let Err(e) = crate::personalize_worker::update_user_vector_from_interaction(&pool, user_id, id, weight).await {
+            tracing::error!("failed to update user vector from rating: {:?}", e);
+        }
+    });
+    Ok(Json(crate::views::ArticleRating { article_id: id, rating: Some(rating) }))
+}
+
+/// Articles the user has read, most recent first, with when and any rating
+/// (`limit` defaults to 50, at most 500). `user_id` must be the caller's own.
+#[get("/api/v1/history?<user_id>&<limit>")]
+async fn reading_history(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    user_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Json<Vec<crate::views::HistoryEntry>>, Status> {
+    if user_id.is_some_and(|id| id != auth.0) {
+        return Err(Status::Forbidden);
+    }
+    let limit = limit.unwrap_or(50).clamp(1, 500);
+    crate::views::reading_history(&state.db, auth.0, limit)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to load reading history for user {}: {:?}", auth.0, e);
+            Status::InternalServerError
+        })
+}
+
+/// The caller's personalized feed, a page at a time (`limit` defaults to 50,
+/// at most 200): their summaries of articles from subscribed feeds, most
+/// relevant first, optionally narrowed to a `category`, a `min_relevance` or
+/// unread articles. `user_id` must be the caller's own.
+#[get("/api/v1/feed?<user_id>&<category>&<min_relevance>&<unread_only>&<limit>&<offset>")]
+#[allow(clippy::too_many_arguments)]
+async fn personalized_feed(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    user_id: Option<i64>,
+    category: Option<String>,
+    min_relevance: Option<f64>,
+    unread_only: Option<bool>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Json<Vec<crate::press_review::PersonalizedFeedItem>>, Status> {
+    if user_id.is_some_and(|id| id != auth.0) {
+        return Err(Status::Forbidden);
+    }
+    let filter = crate::press_review::SummaryFilter {
+        unread_only: unread_only.unwrap_or(false),
+        category: category.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()),
+        min_relevance,
+        ..Default::default()
+    };
+    let limit = limit.unwrap_or(50).clamp(1, 200);
+    let offset = offset.unwrap_or(0).max(0);
+    crate::press_review::list_personalized_feed(&state.db, auth.0, &filter, limit, offset)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to list personalized feed for user {}: {:?}", auth.0, e);
+            Status::InternalServerError
+        })
+}
+
+/// Request body for `POST /api/v1/press-review`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PressReviewRequest {
+    /// Must be the caller's own id when given
+    pub user_id: Option<i64>,
+    /// Reading budget (default 1200 seconds)
+    pub duration_seconds: Option<i64>,
+    /// "source" (default) or "topic"
+    pub group_by: Option<String>,
+}
+
+/// Response of `POST /api/v1/press-review`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressReviewResponse {
+    /// Session created for the review, which stores it as its digest
+    pub session_id: i64,
+    /// The review as Markdown
+    pub summary: String,
+    pub article_count: usize,
+}
+
+/// Generate a press review as Markdown without a websocket session. The
+/// articles are picked like in a chat session, marked read, and the review is
+/// stored as the digest of a new session. The review is assembled from the
+/// stored personalized summaries without an LLM call, so unlike the original
+/// proposal the response has no `tokens_used`.
+#[post("/api/v1/press-review", data = "<body>")]
+async fn create_press_review(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    body: Option<Json<PressReviewRequest>>,
+) -> Result<Json<PressReviewResponse>, Status> {
+    let req = body.map(|b| b.into_inner()).unwrap_or_default();
+    if req.user_id.is_some_and(|id| id != auth.0) {
+        return Err(Status::Forbidden);
+    }
+    let duration = req.duration_seconds.unwrap_or(1200).clamp(60, 86_400);
+    let group_by = crate::press_review::ReviewGroupBy::parse(req.group_by.as_deref());
+
+    let result = async {
+        let review = crate::press_review::generate_press_review(
+            &state.db,
+            auth.0,
+            duration,
+            state.config().as_deref(),
+            group_by,
+        )
+        .await?;
+        let session = crate::sessions::create_session(&state.db, auth.0, Some(duration as i32)).await?;
+        crate::press_review::store_review(&state.db, session.id, &review.summary, crate::press_review::DIGEST_MODEL)
+            .await?;
+        if crate::maintenance::table_exists(&state.db, "user_article_views").await? {
+            crate::views::mark_shown(&state.db, auth.0, session.id, &review.viewed_ids).await?;
+        }
+        anyhow::Ok(PressReviewResponse {
+            session_id: session.id,
+            summary: review.summary,
+            article_count: review.article_count,
+        })
+    }
+    .await;
+    result.map(Json).map_err(|e| {
+        tracing::error!("press review for user {} failed: {:?}", auth.0, e);
+        Status::InternalServerError
+    })
+}
+
+/// LLM usage totals per operation (summarize, chat, ...) and model, since
+/// `since` (RFC 3339 timestamp or YYYY-MM-DD date) or over the last `days`
+/// days (default 30). Requires the admin token.
+#[get("/api/v1/usage?<since>&<days>")]
+async fn usage(
+    state: &State<AppState>,
+    _admin: crate::auth::AdminToken,
+    since: Option<&str>,
+    days: Option<i64>,
+) -> Result<Json<Vec<crate::llm::usage::UsageAggregate>>, Status> {
+    let aggregated = match since {
+        Some(since) => {
+            let since = parse_since(since).ok_or(Status::BadRequest)?;
+            crate::llm::usage::aggregate_usage_since(&state.db, since).await
+        }
+        None => {
+            let days = days.unwrap_or(30).clamp(1, 3650);
+            crate::llm::usage::aggregate_usage(&state.db, days).await
+        }
+    };
+    aggregated.map(Json).map_err(|e| {
+        tracing::error!("failed to aggregate usage: {:?}", e);
+        Status::InternalServerError
+    })
+}
+
+/// Parse a `since` query value: an RFC 3339 timestamp, or a date meaning its
+/// midnight UTC.
+fn parse_since(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(at) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(|d| d.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
+/// Response of `/api/v1/admin/embed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbedReport {
+    pub embedded: usize,
+    /// Articles still without a vector afterwards
+    pub remaining: i64,
+}
+
+/// Generate missing article embeddings now instead of waiting for worker passes
+/// (e.g. right after a large import). Embeds up to `limit` articles, or all of
+/// them when omitted. Requires the admin token.
+#[post("/api/v1/admin/embed?<limit>")]
+async fn admin_embed(
+    state: &State<AppState>,
+    _admin: crate::auth::AdminToken,
+    limit: Option<usize>,
+) -> Result<Json<EmbedReport>, Status> {
+    let provider = state.embedding_llm.clone().ok_or(Status::ServiceUnavailable)?;
+    let config = state.config();
+    let (model, concurrency) = embedding_settings(config.as_deref());
+    let dim = crate::processing::embedding_dim(config.as_deref());
+    let text = crate::processing::EmbeddingText::from_config(config.as_deref());
+
+    let result = async {
+        let embedded =
+            crate::processing::embed_missing_until(&state.db, provider, &model, limit, concurrency, dim, &text).await?;
+        let remaining = crate::processing::count_missing_embeddings(&state.db).await?;
+        anyhow::Ok(EmbedReport { embedded, remaining })
+    }
+    .await;
+    result.map(Json).map_err(|e| {
+        tracing::error!("embedding backfill failed: {:?}", e);
+        Status::InternalServerError
+    })
+}
+
+/// Response of `/api/v1/admin/reembed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReembedReport {
+    /// Article vectors dropped
+    pub cleared: u64,
+    /// `embedding_dim` the vectors were recomputed with
+    pub dimension: usize,
+    pub embedded: usize,
+    /// Articles still without a vector afterwards
+    pub remaining: i64,
+    /// User vectors dropped because they had another dimension
+    pub users_cleared: u64,
+    /// User vectors rebuilt from their interests afterwards
+    pub users_embedded: usize,
 }
 
-// ============================================================================
-// Session Management Endpoints
-// ============================================================================
+/// Drop all article vectors and recompute them with the current embedding
+/// model, e.g. after switching to a model with another `embedding_dim` (the
+/// vector table is recreated with the new size). User vectors are kept unless
+/// their size changed too; they are then rebuilt from the users' interests.
+/// Re-embeds up to `limit` articles now; the worker handles the rest.
+/// Requires the admin token.
+#[post("/api/v1/admin/reembed?<limit>")]
+async fn admin_reembed(
+    state: &State<AppState>,
+    _admin: crate::auth::AdminToken,
+    limit: Option<usize>,
+) -> Result<Json<ReembedReport>, Status> {
+    let provider = state.embedding_llm.clone().ok_or(Status::ServiceUnavailable)?;
+    let config = state.config();
+    let (model, concurrency) = embedding_settings(config.as_deref());
+    let dim = crate::processing::embedding_dim(config.as_deref());
+    let text = crate::processing::EmbeddingText::from_config(config.as_deref());
+
+    let result = async {
+        let cleared = crate::maintenance::reset_embeddings(&state.db, dim).await?;
+        let users_cleared = crate::maintenance::resize_user_vectors(&state.db, dim).await?;
+        let embedded =
+            crate::processing::embed_missing_until(&state.db, provider.clone(), &model, limit, concurrency, dim, &text)
+                .await?;
+        let remaining = crate::processing::count_missing_embeddings(&state.db).await?;
+        let users_embedded = if users_cleared > 0 {
+            crate::personalize_worker::initialize_user_vectors(&state.db, provider, &model).await?
+        } else {
+            0
+        };
+        anyhow::Ok(ReembedReport { cleared, dimension: dim, embedded, remaining, users_cleared, users_embedded })
+    }
+    .await;
+    result.map(Json).map_err(|e| {
+        tracing::error!("re-embedding failed: {:?}", e);
+        Status::InternalServerError
+    })
+}
 
-#[derive(Deserialize)]
-struct CreateSessionRequest {
-    user_id: i64,
-    duration_seconds: Option<i32>,
+/// Model name vectors are recorded under (see
+/// [`crate::processing::embedding_model`]) and concurrency of embedding calls,
+/// from the endpoint the embedding provider is built for.
+fn embedding_settings(config: Option<&Config>) -> (String, usize) {
+    let concurrency = config
+        .and_then(|c| c.llm.as_ref())
+        .and_then(|l| crate::llm::LlmTask::Embedding.endpoint_config(l))
+        .and_then(|r| r.concurrency)
+        .unwrap_or(crate::processing::DEFAULT_EMBEDDING_CONCURRENCY);
+    (crate::processing::embedding_model(config), concurrency)
 }
 
-#[derive(Serialize)]
-struct SessionWithMessages {
-    session: crate::sessions::Session,
-    messages: Vec<crate::sessions::ChatMessage>,
+/// Request body for `/api/v1/admin/reindex`.
+#[derive(Deserialize, Default)]
+struct ReindexRequest {
+    /// Re-embed articles found without a vector (requires an embedding LLM).
+    #[serde(default)]
+    reembed: bool,
+    limit: Option<usize>,
 }
 
-#[post("/api/v1/sessions", data = "<body>")]
-async fn create_session(
+/// Integrity check for derived data: removes orphaned vector/summary rows,
+/// reports articles missing embeddings and optionally re-embeds them.
+/// Requires the admin token.
+#[post("/api/v1/admin/reindex", data = "<body>")]
+async fn admin_reindex(
     state: &State<AppState>,
-    body: Json<CreateSessionRequest>,
-) -> Result<Json<crate::sessions::Session>, Status> {
-    let pool = &state.db;
-    let user_id = body.user_id;
+    _admin: crate::auth::AdminToken,
+    body: Option<Json<ReindexRequest>>,
+) -> Result<Json<crate::maintenance::IntegrityReport>, Status> {
+    let req = body.map(|b| b.into_inner()).unwrap_or_default();
+
+    let reembed = if req.reembed {
+        let provider = state.embedding_llm.clone().ok_or(Status::ServiceUnavailable)?;
+        let config = state.config();
+        let (model, concurrency) = embedding_settings(config.as_deref());
+        Some(crate::maintenance::ReembedOptions {
+            provider,
+            model,
+            limit: req.limit.unwrap_or(500),
+            concurrency,
+            dim: crate::processing::embedding_dim(config.as_deref()),
+            text: crate::processing::EmbeddingText::from_config(config.as_deref()),
+        })
+    } else {
+        None
+    };
 
-    // Prevent creating a session for a user who has no subscriptions.
-    // New users should not see other users' feeds and must add at least one feed before starting a session.
-    let subs_count_res =
-        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM subscriptions WHERE user_id = ?")
-            .bind(user_id)
-            .fetch_one(pool)
-            .await;
+    crate::maintenance::check_integrity(&state.db, reembed)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("integrity check failed: {:?}", e);
+            Status::InternalServerError
+        })
+}
 
-    let subs_count = match subs_count_res {
-        Ok(c) => c,
-        Err(e) => {
-            tracing::error!(
-                "db error while checking subscriptions for user {}: {}",
-                user_id,
-                e
-            );
-            return Err(Status::InternalServerError);
-        }
-    };
+/// LLM processing backlog: article counts and samples by processing status,
+/// the oldest pending article's age and recent summarization failures.
+/// Requires the admin token.
+#[get("/api/v1/admin/processing")]
+async fn admin_processing(
+    state: &State<AppState>,
+    _admin: crate::auth::AdminToken,
+) -> Result<Json<crate::maintenance::ProcessingReport>, Status> {
+    crate::maintenance::processing_report(&state.db)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to build processing report: {:?}", e);
+            Status::InternalServerError
+        })
+}
 
-    if subs_count == 0 {
-        tracing::warn!(
-            "user {} attempted to create a session but has no subscriptions",
-            user_id
-        );
-        // Bad request: user must add subscriptions before creating a session
+/// Prune articles past `[admin] retention_days` now, or past `days` when
+/// given, and report how many rows were deleted. 400 when neither is set or
+/// `days` is 0. Requires the admin token.
+#[post("/api/v1/admin/prune?<days>")]
+async fn admin_prune(
+    state: &State<AppState>,
+    _admin: crate::auth::AdminToken,
+    days: Option<u32>,
+) -> Result<Json<crate::maintenance::PruneReport>, Status> {
+    if days == Some(0) {
         return Err(Status::BadRequest);
     }
+    let Some(days) = days.or_else(|| crate::maintenance::retention_days(state.config().as_deref())) else {
+        tracing::warn!("prune requested without days and no [admin] retention_days configured");
+        return Err(Status::BadRequest);
+    };
+    crate::maintenance::prune_articles(&state.db, days)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to prune articles: {:?}", e);
+            Status::InternalServerError
+        })
+}
 
-    match crate::sessions::create_session(&state.db, user_id, body.duration_seconds).await {
-        Ok(session) => Ok(Json(session)),
-        Err(e) => {
-            tracing::error!(
-                "create_session failed for user_id={} duration_seconds={:?}: {:?}",
-                user_id,
-                body.duration_seconds,
-                e
-            );
-            Err(Status::InternalServerError)
-        }
-    }
+/// Cumulative deduplication stats per feed, most redundant feeds first.
+/// Requires the admin token.
+#[get("/api/v1/admin/dedup")]
+async fn admin_dedup(
+    state: &State<AppState>,
+    _admin: crate::auth::AdminToken,
+) -> Result<Json<crate::dedup::DedupReport>, Status> {
+    let merging = crate::dedup::merge_by_content_hash(state.config().as_deref());
+    crate::dedup::dedup_report(&state.db, merging)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to load dedup stats: {:?}", e);
+            Status::InternalServerError
+        })
 }
 
-#[get("/api/v1/sessions?<user_id>")]
-async fn list_sessions(
+/// Instance-wide counts (users, feeds, articles, embeddings, jobs) and the
+/// last 24 hours of LLM token usage. Requires the admin token.
+#[get("/api/v1/admin/stats")]
+async fn admin_stats(
     state: &State<AppState>,
-    user_id: i64,
-) -> Result<Json<Vec<crate::sessions::Session>>, Status> {
-    crate::sessions::list_sessions(&state.db, user_id)
+    _admin: crate::auth::AdminToken,
+) -> Result<Json<crate::maintenance::InstanceStats>, Status> {
+    crate::maintenance::instance_stats(&state.db)
         .await
         .map(Json)
-        .map_err(|_| Status::InternalServerError)
+        .map_err(|e| {
+            tracing::error!("failed to gather instance stats: {:?}", e);
+            Status::InternalServerError
+        })
 }
 
-#[get("/api/v1/sessions/<session_id>")]
-async fn get_session(
+/// Response of `POST /api/v1/admin/diagnostics`.
+#[derive(Serialize)]
+struct DiagnosticsResponse {
+    path: String,
+}
+
+/// Write a diagnostics snapshot (see [`crate::diagnostics`]) into
+/// `[admin] diagnostics_dir` and return its path. Requires the admin token.
+#[post("/api/v1/admin/diagnostics")]
+async fn admin_diagnostics(
     state: &State<AppState>,
-    session_id: i64,
-) -> Result<Json<SessionWithMessages>, Status> {
-    crate::sessions::get_session_with_messages(&state.db, session_id)
+    _admin: crate::auth::AdminToken,
+) -> Result<Json<DiagnosticsResponse>, Status> {
+    let config = state.config();
+    let result = async {
+        let diagnostics = crate::diagnostics::collect(&state.db, config.as_deref(), state.worker.as_ref()).await?;
+        crate::diagnostics::write(&crate::diagnostics::diagnostics_dir(config.as_deref()), &diagnostics).await
+    }
+    .await;
+    result
+        .map(|path| {
+            tracing::info!("diagnostics written to {}", path.display());
+            Json(DiagnosticsResponse { path: path.display().to_string() })
+        })
+        .map_err(|e| {
+            tracing::error!("failed to write diagnostics: {:?}", e);
+            Status::InternalServerError
+        })
+}
+
+/// What the background worker is doing and when it last ran.
+#[get("/api/v1/worker/status")]
+async fn worker_status(state: &State<AppState>) -> Json<WorkerStatusResponse> {
+    Json(WorkerStatusResponse {
+        enabled: state.worker.is_some(),
+        status: state.worker.as_ref().map(crate::worker_status::snapshot).unwrap_or_default(),
+    })
+}
+
+/// Fetch latency and response size per feed over its recent fetches, slowest
+/// first. Requires the admin token: it lists every user's feed URLs and errors.
+#[get("/api/v1/admin/feed-health")]
+async fn admin_feed_health(
+    state: &State<AppState>,
+    _admin: crate::auth::AdminToken,
+) -> Result<Json<Vec<crate::feed_health::FeedHealth>>, Status> {
+    crate::feed_health::feed_health_report(&state.db)
         .await
-        .map(|(session, messages)| Json(SessionWithMessages { session, messages }))
-        .map_err(|_| Status::InternalServerError)
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to load feed health: {:?}", e);
+            Status::InternalServerError
+        })
 }
 
-#[derive(Deserialize)]
-struct UpdateSessionRequest {
-    title: String,
+/// Per-user settings (`GET`/`PUT /api/v1/me/settings`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSettings {
+    /// Adjust `reading_speed` from time spent on press-review cards
+    pub auto_reading_speed: bool,
+    /// Current reading speed (words per minute)
+    pub reading_speed: i32,
 }
 
-#[put("/api/v1/sessions/<session_id>", data = "<body>")]
-async fn update_session(
+/// Partial update of [`UserSettings`]; omitted fields are left unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserSettingsUpdate {
+    pub auto_reading_speed: Option<bool>,
+}
+
+async fn load_user_settings(pool: &SqlitePool, user_id: i64) -> anyhow::Result<UserSettings> {
+    Ok(UserSettings {
+        auto_reading_speed: crate::reading_speed::is_enabled(pool, user_id).await?,
+        reading_speed: crate::personalization::get_user_profile(pool, user_id).await?.reading_speed,
+    })
+}
+
+#[get("/api/v1/me/settings")]
+async fn get_settings(state: &State<AppState>, auth: crate::auth::AuthUser) -> Result<Json<UserSettings>, Status> {
+    load_user_settings(&state.db, auth.0).await.map(Json).map_err(|e| {
+        tracing::error!("failed to load settings for user {}: {:?}", auth.0, e);
+        Status::InternalServerError
+    })
+}
+
+#[put("/api/v1/me/settings", data = "<body>")]
+async fn update_settings(
     state: &State<AppState>,
-    session_id: i64,
-    body: Json<UpdateSessionRequest>,
-) -> Result<Status, Status> {
-    crate::sessions::update_session_title(&state.db, session_id, &body.title)
+    auth: crate::auth::AuthUser,
+    body: Json<UserSettingsUpdate>,
+) -> Result<Json<UserSettings>, Status> {
+    let pool = &state.db;
+    let result = async {
+        if let Some(enabled) = body.auto_reading_speed {
+            crate::reading_speed::set_enabled(pool, auth.0, enabled).await?;
+        }
+        load_user_settings(pool, auth.0).await
+    }
+    .await;
+    result.map(Json).map_err(|e| {
+        tracing::error!("failed to update settings for user {}: {:?}", auth.0, e);
+        Status::InternalServerError
+    })
+}
+
+/// Personalization inputs of user `id`, who must be the caller.
+#[get("/api/v1/users/<id>/preferences")]
+async fn get_preferences(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    id: i64,
+) -> Result<Json<crate::personalization::UserPreferences>, Status> {
+    if id != auth.0 {
+        return Err(Status::Forbidden);
+    }
+    crate::personalization::get_user_preferences(&state.db, id)
         .await
-        .map(|_| Status::Ok)
-        .map_err(|_| Status::InternalServerError)
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to load preferences for user {}: {:?}", id, e);
+            Status::InternalServerError
+        })
 }
 
-/// Trigger processing of pending articles
-#[post("/api/v1/process-pending")]
-async fn process_pending(state: &State<AppState>) -> Status {
+/// Update some of user `id`'s personalization inputs; 400 on out-of-range values.
+#[put("/api/v1/users/<id>/preferences", data = "<body>")]
+async fn update_preferences(
+    state: &State<AppState>,
+    auth: crate::auth::AuthUser,
+    id: i64,
+    body: Json<crate::personalization::UserPreferencesUpdate>,
+) -> Result<Json<crate::personalization::UserPreferences>, Status> {
+    if id != auth.0 {
+        return Err(Status::Forbidden);
+    }
+    if let Err(reason) = body.validate() {
+        tracing::warn!("rejected preferences for user {}: {}", id, reason);
+        return Err(Status::BadRequest);
+    }
+    crate::personalization::update_user_preferences(&state.db, id, &body)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("failed to update preferences for user {}: {:?}", id, e);
+            Status::InternalServerError
+        })
+}
+
+/// Rebuild the authenticated user's personalized summaries with their current
+/// profile (e.g. after a language or interest change). Stale summaries are
+/// dropped and recent articles re-personalized in the background.
+#[post("/api/v1/me/repersonalize")]
+async fn repersonalize_me(state: &State<AppState>, auth: crate::auth::AuthUser) -> Status {
+    let Some(llm_provider) = state.personalization_llm.clone() else {
+        return Status::ServiceUnavailable;
+    };
     let pool = state.db.clone();
-    let llm_provider = state.summarization_llm.clone();
-    let config = state.config.clone();
+    let user_id = auth.0;
+    let model = crate::llm::LlmTask::Personalization.model(state.config().as_deref());
 
-    let personalization_llm = state.personalization_llm.clone();
     tokio::spawn(async move {
-        tracing::info!("Manual trigger: processing pending articles");
-
-        if let Some(llm_prov) = llm_provider {
-            let model = config
-                .as_ref()
-                .and_then(|c| c.llm.as_ref())
-                .and_then(|l| l.remote.as_ref())
-                .and_then(|r| r.model.as_deref())
-                .unwrap_or("unknown")
-                .to_string();
-
-            match crate::processing::process_pending_articles(
-                &pool,
-                llm_prov,
-                personalization_llm,
-                &model,
-                Some(50),
-            )
-            .await
-            {
-                Ok(count) => tracing::info!("Processed {} pending articles", count),
-                Err(e) => tracing::error!("Failed to process pending articles: {:?}", e),
-            }
-        } else {
-            tracing::warn!("No LLM provider configured, cannot process articles");
+        if let Err(e) = crate::personalize_worker::repersonalize_user(&pool, user_id, llm_provider, &model).await {
+            tracing::error!("failed to re-personalize user {}: {:?}", user_id, e);
         }
     });
+    Status::Accepted
+}
 
+/// Queue summary jobs for up to 50 pending articles, for the worker to process
+#[post("/api/v1/process-pending")]
+async fn process_pending(state: &State<AppState>) -> Status {
+    if state.summarization_llm.is_none() {
+        tracing::warn!("No LLM provider configured, cannot process articles");
+        return Status::Accepted;
+    }
+    match crate::jobs::enqueue_pending_articles(&state.db, 50).await {
+        Ok(count) => tracing::info!("Manual trigger: queued {} pending articles", count),
+        Err(e) => tracing::error!("Failed to queue pending articles: {:#}", e),
+    }
     Status::Accepted
 }
 
@@ -1271,6 +2671,20 @@ pub async fn ensure_schema(pool: &SqlitePool) -> Result<()> {
         );
         "#,
         r#"
+        CREATE TABLE IF NOT EXISTS session_articles (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id INTEGER NOT NULL,
+            article_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            details TEXT,
+            also_covered_by TEXT,
+            created_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+            UNIQUE(session_id, article_id)
+        );
+        "#,
+        r#"
         CREATE TABLE IF NOT EXISTS summaries (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             session_id INTEGER,
@@ -1281,6 +2695,106 @@ pub async fn ensure_schema(pool: &SqlitePool) -> Result<()> {
             FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
         );
         "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS user_notifications (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL,
+            article_id INTEGER NOT NULL,
+            event TEXT NOT NULL,
+            sent_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+            FOREIGN KEY(article_id) REFERENCES articles(id) ON DELETE CASCADE,
+            UNIQUE(user_id, article_id)
+        );
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS feed_dedup_stats (
+            feed_id INTEGER PRIMARY KEY,
+            new_articles INTEGER NOT NULL DEFAULT 0,
+            already_seen INTEGER NOT NULL DEFAULT 0,
+            merged_by_url INTEGER NOT NULL DEFAULT 0,
+            merged_by_hash INTEGER NOT NULL DEFAULT 0,
+            hash_duplicates_unmerged INTEGER NOT NULL DEFAULT 0,
+            updated_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            FOREIGN KEY(feed_id) REFERENCES feeds(id) ON DELETE CASCADE
+        );
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_articles_canonical_hash ON articles(canonical_hash);",
+        r#"
+        CREATE TABLE IF NOT EXISTS feed_fetch_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            feed_id INTEGER NOT NULL,
+            fetched_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            latency_ms INTEGER NOT NULL,
+            response_bytes INTEGER,
+            http_status INTEGER,
+            attempts INTEGER NOT NULL DEFAULT 1,
+            success BOOLEAN NOT NULL,
+            error TEXT,
+            FOREIGN KEY(feed_id) REFERENCES feeds(id) ON DELETE CASCADE
+        );
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_feed_fetch_log_feed ON feed_fetch_log(feed_id, id);",
+        r#"
+        CREATE TABLE IF NOT EXISTS app_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS api_keys (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL,
+            name TEXT,
+            key_hash TEXT NOT NULL UNIQUE,
+            prefix TEXT NOT NULL,
+            created_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            expires_at TIMESTAMP,
+            last_used_at TIMESTAMP,
+            revoked_at TIMESTAMP,
+            FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+        );
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS processing_jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_type TEXT NOT NULL,
+            entity_id INTEGER,
+            status TEXT NOT NULL,
+            started_at TIMESTAMP,
+            completed_at TIMESTAMP,
+            error_message TEXT,
+            llm_model TEXT,
+            prompt_tokens INTEGER,
+            completion_tokens INTEGER,
+            processing_time_ms INTEGER,
+            created_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        );
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_processing_jobs_type_entity ON processing_jobs(job_type, entity_id);",
+        r#"
+        CREATE TABLE IF NOT EXISTS relevance_cache (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            profile_hash TEXT NOT NULL,
+            article_id INTEGER NOT NULL,
+            content_hash TEXT NOT NULL,
+            embedding BLOB,
+            score REAL NOT NULL,
+            reasons TEXT,
+            created_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            FOREIGN KEY(article_id) REFERENCES articles(id) ON DELETE CASCADE
+        );
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_relevance_cache_profile ON relevance_cache(profile_hash, content_hash);",
+        r#"
+        CREATE TABLE IF NOT EXISTS article_vector_models (
+            article_id INTEGER PRIMARY KEY,
+            model TEXT NOT NULL,
+            embedded_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            FOREIGN KEY(article_id) REFERENCES articles(id) ON DELETE CASCADE
+        );
+        "#,
     ];
 
     for s in &stmts {
@@ -1306,6 +2820,51 @@ pub async fn ensure_schema(pool: &SqlitePool) -> Result<()> {
         tracing::error!("failed to ensure revoked_tokens table: {}", e);
     }
 
+    // Chat usage accounting columns (see migrations/20261016000000_chat_usage.sql)
+    for (table, column, ddl) in [
+        ("chat_messages", "model", "ALTER TABLE chat_messages ADD COLUMN model TEXT"),
+        ("chat_messages", "prompt_tokens", "ALTER TABLE chat_messages ADD COLUMN prompt_tokens INTEGER"),
+        ("chat_messages", "completion_tokens", "ALTER TABLE chat_messages ADD COLUMN completion_tokens INTEGER"),
+        ("llm_usage_log", "session_id", "ALTER TABLE llm_usage_log ADD COLUMN session_id INTEGER"),
+        ("feeds", "full_content", "ALTER TABLE feeds ADD COLUMN full_content BOOLEAN NOT NULL DEFAULT 0"),
+        ("feeds", "declared_update_minutes", "ALTER TABLE feeds ADD COLUMN declared_update_minutes INTEGER"),
+        ("feeds", "etag", "ALTER TABLE feeds ADD COLUMN etag TEXT"),
+        ("feeds", "last_modified", "ALTER TABLE feeds ADD COLUMN last_modified TEXT"),
+        ("feeds", "consecutive_failures", "ALTER TABLE feeds ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0"),
+        ("article_summaries", "categories", "ALTER TABLE article_summaries ADD COLUMN categories TEXT"),
+        ("articles", "url_hash", "ALTER TABLE articles ADD COLUMN url_hash TEXT"),
+        ("articles", "language", "ALTER TABLE articles ADD COLUMN language TEXT"),
+        ("subscriptions", "category", "ALTER TABLE subscriptions ADD COLUMN category TEXT"),
+        ("processing_jobs", "claimed_at", "ALTER TABLE processing_jobs ADD COLUMN claimed_at TIMESTAMP"),
+        ("processing_jobs", "attempts", "ALTER TABLE processing_jobs ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0"),
+    ] {
+        let exists = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?",
+        )
+        .bind(table)
+        .bind(column)
+        .fetch_one(pool)
+        .await
+        .unwrap_or(0)
+            > 0;
+        if !exists {
+            tracing::info!("Adding {} column to {} table", column, table);
+            sqlx::query(ddl)
+                .execute(pool)
+                .await
+                .with_context(|| format!("Failed to add {}.{} column", table, column))?;
+        }
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_articles_url_hash ON articles(url_hash)")
+        .execute(pool)
+        .await
+        .context("Failed to create url_hash index")?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_processing_jobs_status_id ON processing_jobs(status, id)")
+        .execute(pool)
+        .await
+        .context("Failed to create processing_jobs status index")?;
+
     // Add processing_status to articles if it doesn't exist
     let has_processing_status = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM pragma_table_info('articles') WHERE name='processing_status'",
@@ -1328,10 +2887,94 @@ pub async fn ensure_schema(pool: &SqlitePool) -> Result<()> {
             .context("Failed to add processed_at column")?;
     }
 
+    // Full-text search index (see migrations/20261016110000_articles_fts.sql)
+    crate::search::ensure_index(pool).await?;
+
     tracing::info!("server: DB schema ensured");
     Ok(())
 }
 
+/// HTTP API routes (everything mounted at `/`).
+pub fn api_routes() -> Vec<rocket::Route> {
+    routes![
+        index_redirect,
+        health,
+        health_report,
+        status,
+        list_jobs,
+        list_queue_jobs,
+        get_stats,
+        list_users,
+        list_feeds,
+        create_feed,
+        preview_feed,
+        update_feed,
+        list_disabled_feeds,
+        enable_feed,
+        delete_subscription,
+        list_feed_articles,
+        import_opml,
+        export_opml,
+        trigger_fetch,
+        process_pending,
+        register,
+        login,
+        // Logout endpoint for token revocation (soft logout)
+        logout,
+        refresh_token,
+        // Session routes
+        create_session,
+        list_sessions,
+        get_session,
+        update_session,
+        patch_session,
+        delete_session,
+        timeline,
+        search,
+        get_article,
+        similar_articles,
+        mark_article_viewed,
+        mark_article_unread,
+        reading_history,
+        personalized_feed,
+        create_press_review,
+        get_article_rating,
+        rate_article,
+        usage,
+        repersonalize_me,
+        get_settings,
+        update_settings,
+        get_preferences,
+        update_preferences,
+        admin_reindex,
+        admin_embed,
+        admin_reembed,
+        admin_dedup,
+        admin_prune,
+        admin_feed_health,
+        worker_status,
+        admin_processing,
+        admin_stats,
+        admin_diagnostics,
+        create_api_key,
+        list_api_keys,
+        revoke_api_key,
+    ]
+}
+
+/// Check that `[server.tls]` points to readable PEM files, so a typo fails at
+/// startup with the offending path rather than deep inside the TLS listener.
+pub fn validate_tls_config(tls: &common::TlsConfig) -> anyhow::Result<()> {
+    for (what, path) in [("certificate", &tls.cert_path), ("private key", &tls.key_path)] {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("[server.tls] {} '{}' cannot be read: {}", what, path, e))?;
+        if !contents.contains("-----BEGIN ") {
+            return Err(anyhow!("[server.tls] {} '{}' is not a PEM file", what, path));
+        }
+    }
+    Ok(())
+}
+
 /// Build and launch a Rocket server.
 ///
 /// The server will attempt to load configuration from the path specified in the `CONFIG_PATH`
@@ -1347,16 +2990,19 @@ pub async fn launch_rocket(
     personalization_llm: Option<Arc<dyn crate::llm::LlmProvider>>,
     interaction_llm: Option<Arc<dyn crate::llm::LlmProvider>>,
     embedding_llm: Option<Arc<dyn crate::llm::LlmProvider>>,
-    config: Option<Arc<Config>>,
+    config: Option<crate::config_reload::SharedConfig>,
+    worker: Option<crate::worker_status::SharedWorkerStatus>,
 ) -> Result<()> {
+    check_jwt_secret()?;
+
+    let startup_config = config.as_ref().map(|c| c.load_full());
     let state = AppState {
-        started_at: Utc::now(),
-        config,
-        db,
         summarization_llm,
         personalization_llm,
         interaction_llm,
         embedding_llm,
+        worker,
+        ..AppState::new(db, config)
     };
     // The DB pool and optional application config are provided by the caller.
     // The server must not re-init or migrate the database here; migrations and pool
@@ -1392,35 +3038,27 @@ pub async fn launch_rocket(
         }
     }
 
-    let rocket = rocket::custom(fig)
+    if let Some(tls) = startup_config
+        .as_deref()
+        .and_then(|c| c.server.as_ref())
+        .and_then(|s| s.tls.as_ref())
+    {
+        validate_tls_config(tls)?;
+        tracing::info!("TLS enabled (certificate: {})", tls.cert_path);
+        fig = fig
+            .merge(("tls.certs", tls.cert_path.clone()))
+            .merge(("tls.key", tls.key_path.clone()));
+    }
+
+    let mut rocket = rocket::custom(fig)
         .manage(state)
-        .mount(
-            "/",
-            routes![
-                index_redirect,
-                health,
-                status,
-                list_jobs,
-                get_stats,
-                list_users,
-                list_feeds,
-                create_feed,
-                import_opds,
-                trigger_fetch,
-                process_pending,
-                register,
-                login,
-                // Logout endpoint for token revocation (soft logout)
-                logout,
-                // Session routes
-                create_session,
-                list_sessions,
-                get_session,
-                update_session,
-            ],
-        )
-        .mount("/ws", routes![crate::sessions::websocket::chat_websocket,])
-        .mount("/static", FileServer::from("newscope/static"));
+        .mount("/", crate::request_id::traced(api_routes()))
+        .mount("/ws", crate::request_id::traced(routes![crate::sessions::websocket::chat_websocket,]))
+        .mount("/static", FileServer::from("newscope/static"))
+        .attach(crate::request_id::RequestIds);
+    if let Some(cors) = crate::cors::Cors::from_config(startup_config.as_deref()) {
+        rocket = rocket.attach(cors);
+    }
 
     // Launch Rocket - this will run until shutdown (SIGINT/SIGTERM etc.)
     tracing::info!("Starting Rocket HTTP server");
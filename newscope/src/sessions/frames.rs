@@ -0,0 +1,273 @@
+//! Sequenced websocket frames, so a chat client can resume after a dropped
+//! connection.
+//!
+//! Every frame of a session carries the next `seq`. The last [`FRAME_BUFFER`]
+//! published frames are kept in memory: a client reconnecting with
+//! `?since_seq=` gets exactly the frames it missed, and the background press
+//! review keeps publishing here whether or not a client is connected. When the
+//! frames are gone (server restart, long absence) the client starts over from
+//! the persisted chat history.
+
+use rocket_ws::Message;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Published frames kept per session for resumes.
+pub const FRAME_BUFFER: usize = 256;
+
+/// Sessions without a client or a running review are forgotten after this.
+const IDLE_EXPIRY: Duration = Duration::from_secs(3600);
+
+struct SessionFrames {
+    next_seq: u64,
+    buffer: VecDeque<(u64, String)>,
+    clients: Vec<UnboundedSender<Message>>,
+    generating: bool,
+    last_active: Instant,
+}
+
+impl SessionFrames {
+    fn new() -> Self {
+        Self {
+            next_seq: 1,
+            buffer: VecDeque::new(),
+            clients: Vec::new(),
+            generating: false,
+            last_active: Instant::now(),
+        }
+    }
+
+    fn stamp(&mut self, mut frame: Value) -> (u64, String) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        frame["seq"] = json!(seq);
+        (seq, frame.to_string())
+    }
+
+    /// Whether every frame after `seq` is still buffered.
+    fn covers(&self, seq: u64) -> bool {
+        if seq >= self.next_seq {
+            return false;
+        }
+        match self.buffer.front() {
+            Some((first, _)) => *first <= seq + 1,
+            None => true,
+        }
+    }
+}
+
+static SESSIONS: OnceLock<Mutex<HashMap<i64, SessionFrames>>> = OnceLock::new();
+
+fn with_session<T>(session_id: i64, f: impl FnOnce(&mut SessionFrames) -> T) -> T {
+    let mut sessions = SESSIONS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    sessions.retain(|id, s| {
+        *id == session_id || s.generating || !s.clients.is_empty() || now.duration_since(s.last_active) < IDLE_EXPIRY
+    });
+    let session = sessions.entry(session_id).or_insert_with(SessionFrames::new);
+    session.last_active = now;
+    f(session)
+}
+
+/// How a connection picked up its session, see [`FrameLog::attach`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attach {
+    /// The frames after `since_seq` were replayed: the client is up to date.
+    Resumed,
+    /// A press review is being generated: every buffered frame was replayed
+    /// and the rest will follow.
+    Joined,
+    /// Nothing was replayed; the connection sets the session up itself.
+    Fresh,
+}
+
+/// Frame log of one chat session.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameLog {
+    session_id: i64,
+}
+
+impl FrameLog {
+    pub fn new(session_id: i64) -> Self {
+        Self { session_id }
+    }
+
+    /// Attach a connection's sender, first sending it a `sync` frame telling
+    /// whether it resumed, then what it missed (see [`Attach`]).
+    pub fn attach(&self, tx: &UnboundedSender<Message>, since_seq: Option<u64>) -> Attach {
+        with_session(self.session_id, |session| {
+            let attach = match since_seq {
+                Some(seq) if session.covers(seq) => Attach::Resumed,
+                _ if session.generating => Attach::Joined,
+                _ => Attach::Fresh,
+            };
+            let resumed = attach == Attach::Resumed;
+            let _ = tx.send(Message::Text(json!({ "type": "sync", "resumed": resumed }).to_string()));
+            if attach != Attach::Fresh {
+                let after = since_seq.filter(|_| resumed).unwrap_or(0);
+                for (_, frame) in session.buffer.iter().filter(|(seq, _)| *seq > after) {
+                    let _ = tx.send(Message::Text(frame.clone()));
+                }
+            }
+            session.clients.push(tx.clone());
+            attach
+        })
+    }
+
+    /// Stop sending to a connection's sender.
+    pub fn detach(&self, tx: &UnboundedSender<Message>) {
+        with_session(self.session_id, |session| session.clients.retain(|c| !c.same_channel(tx)));
+    }
+
+    /// Send `frame` to every client of the session and keep it for resumes.
+    /// Returns its `seq`.
+    pub fn publish(&self, frame: Value) -> u64 {
+        with_session(self.session_id, |session| {
+            let (seq, text) = session.stamp(frame);
+            session.clients.retain(|c| c.send(Message::Text(text.clone())).is_ok());
+            session.buffer.push_back((seq, text));
+            if session.buffer.len() > FRAME_BUFFER {
+                session.buffer.pop_front();
+            }
+            seq
+        })
+    }
+
+    /// Send `frame` to one connection only (e.g. history replayed on
+    /// connect): it takes a `seq` but is not kept.
+    pub fn send_to(&self, tx: &UnboundedSender<Message>, frame: Value) -> u64 {
+        with_session(self.session_id, |session| {
+            let (seq, text) = session.stamp(frame);
+            let _ = tx.send(Message::Text(text));
+            seq
+        })
+    }
+
+    /// Claim the session's press review generation. `None` when another
+    /// connection is already generating it; the claim ends with the guard.
+    pub fn start_generation(&self) -> Option<GenerationGuard> {
+        with_session(self.session_id, |session| {
+            if session.generating {
+                return None;
+            }
+            session.generating = true;
+            Some(GenerationGuard { log: *self })
+        })
+    }
+}
+
+impl FrameLog {
+    /// Publish `{"type":"typing","state":"start"}`, and the matching `"stop"`
+    /// when the returned guard is dropped, whichever way the work ends.
+    pub fn typing(&self) -> TypingGuard {
+        self.publish(json!({ "type": "typing", "state": "start" }));
+        TypingGuard { log: *self }
+    }
+}
+
+/// Held while the assistant is working on a reply, see [`FrameLog::typing`].
+pub struct TypingGuard {
+    log: FrameLog,
+}
+
+impl Drop for TypingGuard {
+    fn drop(&mut self) {
+        self.log.publish(json!({ "type": "typing", "state": "stop" }));
+    }
+}
+
+/// Held while a press review is generated, see [`FrameLog::start_generation`].
+pub struct GenerationGuard {
+    log: FrameLog,
+}
+
+impl Drop for GenerationGuard {
+    fn drop(&mut self) {
+        with_session(self.log.session_id, |session| session.generating = false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    fn drain(rx: &mut UnboundedReceiver<Message>) -> Vec<Value> {
+        let mut frames = Vec::new();
+        while let Ok(Message::Text(text)) = rx.try_recv() {
+            frames.push(serde_json::from_str(&text).unwrap());
+        }
+        frames
+    }
+
+    #[test]
+    fn test_resume_replays_only_missed_frames() {
+        let log = FrameLog::new(-1);
+        let (tx, mut rx) = unbounded_channel();
+        assert_eq!(log.attach(&tx, None), Attach::Fresh);
+        assert_eq!(log.send_to(&tx, json!({ "type": "history" })), 1);
+        for i in 0..3 {
+            log.publish(json!({ "type": "news_card", "n": i }));
+        }
+        let frames = drain(&mut rx);
+        assert_eq!(frames[0], json!({ "type": "sync", "resumed": false }));
+        assert_eq!(frames.iter().skip(1).map(|f| f["seq"].as_u64().unwrap()).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        // Dropped after seq 2: frames 3 and 4 come back, then live ones
+        log.detach(&tx);
+        log.publish(json!({ "type": "news_card", "n": 3 }));
+        let (tx, mut rx) = unbounded_channel();
+        assert_eq!(log.attach(&tx, Some(2)), Attach::Resumed);
+        log.publish(json!({ "type": "message" }));
+        let frames = drain(&mut rx);
+        assert_eq!(frames[0]["resumed"], json!(true));
+        assert_eq!(frames.iter().skip(1).map(|f| f["seq"].as_u64().unwrap()).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+
+        // A seq this log never issued (e.g. from before a restart) can't resume
+        let (tx, _rx) = unbounded_channel();
+        assert_eq!(log.attach(&tx, Some(99)), Attach::Fresh);
+    }
+
+    #[test]
+    fn test_generation_is_claimed_once() {
+        let log = FrameLog::new(-2);
+        let guard = log.start_generation().expect("first claim");
+        assert!(log.start_generation().is_none());
+        log.publish(json!({ "type": "message", "content": "greeting" }));
+
+        // A new connection joins the running review from its first frame
+        let (tx, mut rx) = unbounded_channel();
+        assert_eq!(log.attach(&tx, None), Attach::Joined);
+        assert_eq!(drain(&mut rx)[1]["content"], json!("greeting"));
+
+        drop(guard);
+        assert!(log.start_generation().is_some());
+    }
+
+    #[test]
+    fn test_typing_stops_when_the_guard_drops() {
+        let log = FrameLog::new(-3);
+        let (tx, mut rx) = unbounded_channel();
+        log.attach(&tx, None);
+        {
+            let _typing = log.typing();
+            log.publish(json!({ "type": "message_end" }));
+        }
+        let frames = drain(&mut rx);
+        let kinds: Vec<_> = frames[1..].iter().map(|f| (f["type"].clone(), f["state"].clone())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (json!("typing"), json!("start")),
+                (json!("message_end"), Value::Null),
+                (json!("typing"), json!("stop")),
+            ]
+        );
+    }
+}
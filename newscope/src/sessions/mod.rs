@@ -26,6 +26,17 @@ pub struct ChatMessage {
     pub created_at: DateTime<Utc>,
 }
 
+/// An article shown in a session, as the user saw it: context for chat
+#[derive(Clone, Debug)]
+pub struct ArticleContext {
+    pub id: i64,
+    pub title: String,
+    pub summary: String,
+    pub content: Option<String>,
+    /// Other feeds carrying the same story
+    pub also_covered_by: Vec<String>,
+}
+
 /// Create a new session
 pub async fn create_session(
     pool: &SqlitePool,
@@ -108,21 +119,64 @@ pub async fn list_sessions(pool: &SqlitePool, user_id: i64) -> Result<Vec<Sessio
         .collect()
 }
 
-/// Update session title
+/// Update session title. Fails with `sqlx::Error::RowNotFound` if the session does not exist.
 pub async fn update_session_title(
     pool: &SqlitePool,
     session_id: i64,
     title: &str,
 ) -> Result<()> {
-    sqlx::query("UPDATE sessions SET title = ? WHERE id = ?")
+    let res = sqlx::query("UPDATE sessions SET title = ? WHERE id = ?")
         .bind(title)
         .bind(session_id)
         .execute(pool)
         .await
         .context("Failed to update session title")?;
+    if res.rows_affected() == 0 {
+        return Err(sqlx::Error::RowNotFound).context("Session not found");
+    }
     Ok(())
 }
 
+/// Delete one of `user_id`'s sessions with its messages, shown articles and
+/// press review. A review another session reuses as its digest is handed over
+/// to that session instead. Returns false if the user has no such session.
+pub async fn delete_session(pool: &SqlitePool, user_id: i64, session_id: i64) -> Result<bool> {
+    let has_views = crate::maintenance::table_exists(pool, "user_article_views").await?;
+    let mut tx = pool.begin().await.context("failed to begin transaction")?;
+    let owned = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM sessions WHERE id = ? AND user_id = ?")
+        .bind(session_id)
+        .bind(user_id)
+        .fetch_one(&mut tx)
+        .await
+        .context("Failed to look up session")?;
+    if owned == 0 {
+        return Ok(false);
+    }
+    let mut stmts = vec![
+        "UPDATE summaries SET session_id = (
+             SELECT MIN(s.id) FROM sessions s WHERE s.digest_summary_id = summaries.id AND s.id != ?1
+         )
+         WHERE session_id = ?1
+           AND EXISTS (SELECT 1 FROM sessions s WHERE s.digest_summary_id = summaries.id AND s.id != ?1)",
+        "DELETE FROM summaries WHERE session_id = ?1",
+        "DELETE FROM chat_messages WHERE session_id = ?1",
+        "DELETE FROM session_articles WHERE session_id = ?1",
+    ];
+    if has_views {
+        stmts.push("UPDATE user_article_views SET session_id = NULL WHERE session_id = ?1");
+    }
+    stmts.push("DELETE FROM sessions WHERE id = ?1");
+    for stmt in stmts {
+        sqlx::query(stmt)
+            .bind(session_id)
+            .execute(&mut tx)
+            .await
+            .context("Failed to delete session")?;
+    }
+    tx.commit().await.context("failed to commit session deletion")?;
+    Ok(true)
+}
+
 /// Get session with full chat message history
 pub async fn get_session_with_messages(
     pool: &SqlitePool,
@@ -209,6 +263,74 @@ pub async fn store_message(
     })
 }
 
+/// Attach model and token usage to a stored (assistant) message
+pub async fn record_message_usage(
+    pool: &SqlitePool,
+    message_id: i64,
+    model: &str,
+    usage: &crate::llm::UsageMetadata,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE chat_messages SET model = ?, prompt_tokens = ?, completion_tokens = ? WHERE id = ?",
+    )
+    .bind(model)
+    .bind(usage.prompt_tokens as i64)
+    .bind(usage.completion_tokens as i64)
+    .bind(message_id)
+    .execute(pool)
+    .await
+    .context("Failed to record message usage")?;
+    Ok(())
+}
+
+/// Record an article shown in a session, as the user saw it, so chat keeps
+/// its context across reconnects and restarts. Sending it again updates it.
+pub async fn store_session_article(pool: &SqlitePool, session_id: i64, article: &ArticleContext) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO session_articles (session_id, article_id, title, summary, details, also_covered_by)
+         VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(session_id, article_id) DO UPDATE SET
+             title = excluded.title, summary = excluded.summary,
+             details = excluded.details, also_covered_by = excluded.also_covered_by",
+    )
+    .bind(session_id)
+    .bind(article.id)
+    .bind(&article.title)
+    .bind(&article.summary)
+    .bind(&article.content)
+    .bind(serde_json::to_string(&article.also_covered_by)?)
+    .execute(pool)
+    .await
+    .context("Failed to store session article")?;
+    Ok(())
+}
+
+/// Articles shown in a session, in the order they were sent
+pub async fn get_session_articles(pool: &SqlitePool, session_id: i64) -> Result<Vec<ArticleContext>> {
+    let rows = sqlx::query_as::<_, SessionArticleRow>(
+        "SELECT article_id, title, summary, details, also_covered_by
+         FROM session_articles WHERE session_id = ? ORDER BY id",
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch session articles")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ArticleContext {
+            id: row.article_id,
+            title: row.title,
+            summary: row.summary,
+            content: row.details,
+            also_covered_by: row
+                .also_covered_by
+                .and_then(|a| serde_json::from_str(&a).ok())
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
 // Internal row types for SQLx mapping
 #[derive(sqlx::FromRow)]
 struct SessionRow {
@@ -229,4 +351,15 @@ struct ChatMessageRow {
     created_at: String,
 }
 
+#[derive(sqlx::FromRow)]
+struct SessionArticleRow {
+    article_id: i64,
+    title: String,
+    summary: String,
+    details: Option<String>,
+    also_covered_by: Option<String>,
+}
+
+pub mod frames;
+pub mod title;
 pub mod websocket;
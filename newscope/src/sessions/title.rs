@@ -0,0 +1,160 @@
+//! Automatic session titles.
+//!
+//! A session gets a title once, from whichever comes first: the press review
+//! or the first chat exchange. The session is claimed with a timestamp title,
+//! which the interaction LLM's 3-6 word title then replaces; the timestamp
+//! stays if the LLM fails, and a title set by the user is never overwritten.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::llm::{LlmProvider, LlmRequest};
+
+/// Longest title kept from the LLM, in words.
+const MAX_TITLE_WORDS: usize = 6;
+
+/// Title used until (or instead of) the generated one.
+pub fn fallback_title(start_at: DateTime<Utc>) -> String {
+    format!("Session of {}", start_at.format("%Y-%m-%d %H:%M"))
+}
+
+/// First line of an LLM answer as a title: without quotes, a "Title:" label or
+/// trailing punctuation, cut to [`MAX_TITLE_WORDS`]. None if nothing is left.
+pub fn clean_title(raw: &str) -> Option<String> {
+    let line = raw.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line
+        .strip_prefix("Title:")
+        .or_else(|| line.strip_prefix("title:"))
+        .unwrap_or(line);
+    let words: Vec<&str> = line
+        .split_whitespace()
+        .map(|w| w.trim_matches(['"', '\'', '*', '«', '»', '“', '”']))
+        .filter(|w| !w.is_empty())
+        .take(MAX_TITLE_WORDS)
+        .collect();
+    let title = words.join(" ");
+    let title = title.trim_end_matches(['.', ':', ';', ',']);
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Give `session_id` a title from `context` (the review headlines or the first
+/// exchange) unless it already has one. Returns the stored title, None if the
+/// session was already titled.
+pub async fn generate_session_title(
+    pool: &SqlitePool,
+    llm: &dyn LlmProvider,
+    session_id: i64,
+    context: &str,
+    language: Option<&str>,
+) -> Result<Option<String>> {
+    let session = super::get_session(pool, session_id).await?;
+    let fallback = fallback_title(session.start_at);
+    let claimed = sqlx::query("UPDATE sessions SET title = ? WHERE id = ? AND title IS NULL")
+        .bind(&fallback)
+        .bind(session_id)
+        .execute(pool)
+        .await
+        .context("Failed to claim session title")?
+        .rows_affected();
+    if claimed == 0 {
+        return Ok(None);
+    }
+
+    let language = language.and_then(crate::i18n::language_name).unwrap_or("the language of the session");
+    let prompt = format!(
+        "Give a title of 3 to 6 words summarizing this news reading session. \
+         Write it in {}. Answer with the title only, without quotes.\n\n{}",
+        language, context
+    );
+    let response = llm
+        .generate(LlmRequest {
+            prompt,
+            max_tokens: Some(30),
+            temperature: Some(0.3),
+            timeout_seconds: Some(30),
+        })
+        .await;
+    let (model, usage, error_message) = match &response {
+        Ok(r) => (r.model.as_str(), Some(&r.usage), None),
+        Err(e) => ("unknown", None, Some(e.to_string())),
+    };
+    if let Err(e) = crate::llm::usage::log_usage(
+        pool,
+        crate::llm::usage::UsageEntry {
+            operation: "session_title",
+            model,
+            usage,
+            error_message: error_message.as_deref(),
+            session_id: Some(session_id),
+        },
+    )
+    .await
+    {
+        error!("Failed to log session title usage: {}", e);
+    }
+
+    let title = match response.as_ref().map(|r| clean_title(&r.content)) {
+        Ok(Some(title)) => title,
+        Ok(None) => {
+            warn!("Session {}: empty title from LLM, keeping \"{}\"", session_id, fallback);
+            return Ok(Some(fallback));
+        }
+        Err(e) => {
+            warn!("Session {}: title generation failed, keeping \"{}\": {:#}", session_id, fallback, e);
+            return Ok(Some(fallback));
+        }
+    };
+    // Unless the user renamed the session meanwhile
+    let updated = sqlx::query("UPDATE sessions SET title = ? WHERE id = ? AND title = ?")
+        .bind(&title)
+        .bind(session_id)
+        .bind(&fallback)
+        .execute(pool)
+        .await
+        .context("Failed to update session title")?
+        .rows_affected();
+    Ok(Some(if updated > 0 { title } else { fallback }))
+}
+
+/// [`generate_session_title`] in the background, so the chat isn't held up.
+pub fn spawn_session_title(
+    pool: SqlitePool,
+    llm: Arc<dyn LlmProvider>,
+    session_id: i64,
+    context: String,
+    language: Option<String>,
+) {
+    tokio::spawn(async move {
+        match generate_session_title(&pool, llm.as_ref(), session_id, &context, language.as_deref()).await {
+            Ok(Some(title)) => info!("Session {} titled \"{}\"", session_id, title),
+            Ok(None) => {}
+            Err(e) => error!("Session {}: failed to title session: {:?}", session_id, e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(clean_title("\"Rust and the Economy\"\n").as_deref(), Some("Rust and the Economy"));
+        assert_eq!(clean_title("\n  Title: Storms hit the coast.").as_deref(), Some("Storms hit the coast"));
+        assert_eq!(
+            clean_title("A very long title that goes on and on").as_deref(),
+            Some("A very long title that goes")
+        );
+        assert_eq!(clean_title("  \n\"\" "), None);
+    }
+
+    #[test]
+    fn test_fallback_title() {
+        let start = Utc.with_ymd_and_hms(2026, 10, 17, 8, 5, 0).unwrap();
+        assert_eq!(fallback_title(start), "Session of 2026-10-17 08:05");
+    }
+}
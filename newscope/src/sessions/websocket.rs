@@ -1,14 +1,16 @@
 use anyhow::Result;
 use rocket::futures::{SinkExt, StreamExt};
 use rocket::request::{FromRequest, Outcome, Request};
+use rocket::http::Status;
 use rocket::{get, State};
 use rocket_ws::{Channel, Message, WebSocket};
 use sqlx::SqlitePool;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{error, info};
 
-use super::{get_messages, store_message};
-use crate::llm::{LlmProvider, LlmRequest};
+use super::{get_messages, store_message, ArticleContext};
+use crate::llm::{LlmChunk, LlmProvider, LlmRequest, LlmResponse, UsageMetadata};
 
 use serde_json::json;
 
@@ -31,20 +33,85 @@ impl<'r> FromRequest<'r> for AcceptLanguage {
     }
 }
 
-/// WebSocket chat endpoint
-#[get("/chat?<session_id>")]
-pub fn chat_websocket(
+/// Default `server.ws_ping_interval_seconds`.
+pub const DEFAULT_PING_INTERVAL_SECONDS: u64 = 30;
+/// Default `server.ws_idle_timeout_seconds`.
+pub const DEFAULT_IDLE_TIMEOUT_SECONDS: u64 = 90;
+/// Time given to the last queued frames (e.g. the close frame) once a
+/// connection ends, before its forwarding task is aborted.
+const FORWARD_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Keep-alive of a chat connection: the server pings every `interval` and
+/// gives up on a client it hasn't heard from (pong or message) for `idle_timeout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Heartbeat {
+    pub interval: Duration,
+    pub idle_timeout: Duration,
+}
+
+impl Heartbeat {
+    /// From `[server] ws_ping_interval_seconds` and `ws_idle_timeout_seconds`.
+    pub fn from_config(config: Option<&common::Config>) -> Self {
+        let server = config.and_then(|c| c.server.as_ref());
+        let seconds = |value: Option<u64>, default: u64| Duration::from_secs(value.unwrap_or(default).max(1));
+        Self {
+            interval: seconds(server.and_then(|s| s.ws_ping_interval_seconds), DEFAULT_PING_INTERVAL_SECONDS),
+            idle_timeout: seconds(server.and_then(|s| s.ws_idle_timeout_seconds), DEFAULT_IDLE_TIMEOUT_SECONDS),
+        }
+    }
+
+    /// Whether a client last heard from at `last_seen` should be dropped.
+    pub fn is_idle(&self, last_seen: Instant, now: Instant) -> bool {
+        now.saturating_duration_since(last_seen) >= self.idle_timeout
+    }
+}
+
+/// WebSocket chat endpoint. A new session replays the user's last press review
+/// when it is within the `[press_review]` cooldown, unless `force_refresh` is set.
+/// `group_by=topic` labels review cards by article category instead of feed.
+/// Frames carry a `seq`; a client reconnecting with `since_seq` gets only the
+/// frames it missed (see [`super::frames`]). The connection is pinged and
+/// closed once idle (see [`Heartbeat`]); a press review being generated keeps
+/// going in the background for a later reconnection. Only the session's owner
+/// may connect; other users' sessions are 404 before the upgrade.
+#[get("/chat?<session_id>&<force_refresh>&<group_by>&<since_seq>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn chat_websocket(
     ws: WebSocket,
+    auth: crate::auth::AuthUser,
     session_id: i64,
+    force_refresh: Option<bool>,
+    group_by: Option<&str>,
+    since_seq: Option<u64>,
     accept_lang: AcceptLanguage,
     state: &State<crate::server::AppState>,
-) -> Channel<'static> {
+) -> Result<Channel<'static>, Status> {
     let pool = state.db.clone();
+    let (session, messages) = super::get_session_with_messages(&pool, session_id)
+        .await
+        .and_then(|(session, messages)| {
+            if session.user_id != auth.0 {
+                return Err(sqlx::Error::RowNotFound.into());
+            }
+            Ok((session, messages))
+        })
+        .map_err(|e| {
+            let status = crate::server::error_status(&e);
+            if status == Status::InternalServerError {
+                error!("Failed to fetch session {}: {:?}", session_id, e);
+            }
+            status
+        })?;
+    let user_id = session.user_id;
+    let duration_seconds = session.duration_requested_seconds.unwrap_or(1200) as i64;
     let llm = state.interaction_llm.clone();
-    let config = state.config.clone();
+    let config = state.config();
+    let chat_limiter = state.chat_limiter.clone();
+    let heartbeat = Heartbeat::from_config(config.as_deref());
     let language = accept_lang.0;
+    let group_by = crate::press_review::ReviewGroupBy::parse(group_by);
 
-    ws.channel(move |stream| {
+    Ok(ws.channel(move |stream| {
         Box::pin(async move {
             info!("WebSocket connected for session {}", session_id);
 
@@ -54,8 +121,9 @@ pub fn chat_websocket(
             // Create MPSC channel for sending messages to the websocket
             let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
 
-            // Spawn task to forward messages from channel to websocket
-            tokio::spawn(async move {
+            // Spawn task to forward messages from channel to websocket. It ends
+            // once every sender is gone (see the end of this connection).
+            let mut forwarder = tokio::spawn(async move {
                 while let Some(msg) = rx.recv().await {
                     if let Err(e) = ws_sink.send(msg).await {
                         error!("Failed to send message to websocket: {}", e);
@@ -64,39 +132,25 @@ pub fn chat_websocket(
                 }
             });
 
-            // Helper to send JSON message
-            let send_json = |tx: &tokio::sync::mpsc::UnboundedSender<Message>, json: serde_json::Value| {
-                let _ = tx.send(Message::Text(json.to_string()));
-            };
-
-            // Fetch session info first
-            let (user_id, messages, duration_seconds) = match crate::sessions::get_session_with_messages(&pool, session_id).await {
-                Ok((session, msgs)) => (
-                    session.user_id,
-                    msgs,
-                    session.duration_requested_seconds.unwrap_or(1200) as i64
-                ),
-                Err(e) => {
-                    error!("Failed to fetch session {}: {}", session_id, e);
-                    return Ok(());
-                }
-            };
-
-            // Shared state for article context (empty for now, populated if new session)
-            let article_context = Arc::new(std::sync::Mutex::new(Vec::<ArticleContext>::new()));
-            let article_context_bg = article_context.clone();
-            let article_context_chat = article_context.clone();
-
-            if messages.is_empty() {
-                // New session: generate press review
-                if let Some(llm_provider) = llm.clone() {
+            // Frames go through the session's log, so a dropped client can resume
+            let frames = super::frames::FrameLog::new(session_id);
+            let attach = frames.attach(&tx, since_seq);
+
+            if attach != super::frames::Attach::Fresh {
+                // Resumed, or joined a review still being generated: the frames
+                // were replayed and the running task keeps publishing
+                info!("Session {}: client {:?} (since seq {:?})", session_id, attach, since_seq);
+            } else if messages.is_empty() {
+                // New session: generate press review, unless another connection already is
+                let generation = frames.start_generation();
+                if let (Some(llm_provider), Some(generation)) = (llm.clone(), generation) {
                     let pool = pool.clone();
-                    let _model = config.as_ref()
-                        .and_then(|c| c.llm.as_ref())
-                        .and_then(|l| l.remote.as_ref())
-                        .and_then(|r| r.model.as_deref())
-                        .unwrap_or("unknown")
-                        .to_string();
+                    let model = crate::llm::LlmTask::Interaction.model(config.as_deref());
+                    let cooldown_minutes = if force_refresh.unwrap_or(false) {
+                        0
+                    } else {
+                        crate::press_review::review_cooldown_minutes(config.as_deref())
+                    };
 
                     let greeting = match language.as_str() {
                         "fr" => "👋 Bonjour ! Je prépare votre revue de presse personnalisée. Je vous enverrai une notification quand elle sera prête...",
@@ -106,23 +160,52 @@ pub fn chat_websocket(
                         _ => "👋 Hello! I'm preparing your personalized press review. I'll send you a notification when it's ready..."
                     };
 
-                    send_json(&tx, json!({
+                    frames.publish(json!({
                         "type": "message",
                         "content": greeting
                     }));
 
-                    // Spawn background task for heavy lifting
-                    let tx_clone = tx.clone(); // Clone sender for background task
+                    // Spawn background task for heavy lifting. It doesn't hold the
+                    // connection: it publishes to the frame log until it is done.
                     let language_clone = language.clone();
                     // Initialize user_profile_lang from Accept-Language; it may be updated after fetching profile
 
                     tokio::spawn(async move {
-                        // Notify when ready
-                        let _ = tx_clone.send(Message::Text(serde_json::to_string(&json!({
-                            "type": "notification",
-                            "title": "Newscope",
-                            "body": "Votre revue de presse est prête !"
-                        })).unwrap()));
+                        let _generation = generation;
+                        let _typing = frames.typing();
+                        // Within the cooldown, replay the last review instead of paying for a new one
+                        match crate::press_review::recent_review(&pool, user_id, cooldown_minutes).await {
+                            Ok(Some(review)) => {
+                                info!("Session {}: reusing press review {} from session {}", session_id, review.id, review.session_id);
+                                if let Err(e) = crate::press_review::attach_review(&pool, session_id, review.id).await {
+                                    error!("Session {}: {:?}", session_id, e);
+                                }
+                                let cards: Vec<serde_json::Value> = serde_json::from_str(&review.summary_text).unwrap_or_default();
+                                frames.publish(json!({ "type": "progress_hide" }));
+                                for mut card in cards {
+                                    let details = card.as_object_mut()
+                                        .and_then(|c| c.remove("details"))
+                                        .and_then(|d| d.as_str().map(str::to_string));
+                                    let article = ArticleContext {
+                                        id: card["id"].as_i64().unwrap_or_default(),
+                                        title: card["title"].as_str().unwrap_or_default().to_string(),
+                                        summary: card["summary"].as_str().unwrap_or_default().to_string(),
+                                        content: details,
+                                        also_covered_by: serde_json::from_value(card["also_covered_by"].clone()).unwrap_or_default(),
+                                    };
+                                    if let Err(e) = crate::sessions::store_session_article(&pool, session_id, &article).await {
+                                        error!("Session {}: {:?}", session_id, e);
+                                    }
+                                    frames.publish(json!({ "type": "news_card", "article": card }));
+                                }
+                                let completion_msg = completion_message(&language_clone);
+                                let _ = crate::sessions::store_message(&pool, session_id, "assistant", completion_msg).await;
+                                frames.publish(json!({ "type": "message", "content": completion_msg }));
+                                return;
+                            }
+                            Ok(None) => {}
+                            Err(e) => error!("Session {}: {:?}", session_id, e),
+                        }
 
                         // PHASE 3: Fetch PRE-COMPUTED personalized summaries
                         let duration = duration_seconds as u64;
@@ -142,74 +225,51 @@ pub fn chat_websocket(
                             Err(_) => None,
                         };
 
-                        // Calculate number of articles
-                        let total_words_budget = (reading_minutes / 2.0) * reading_speed as f64;
-                        let estimated_articles = (total_words_budget / 150.0).ceil() as i64;
-                        // Ensure at least 3 articles, max 15
-                        let estimated_articles = estimated_articles.max(3).min(15);
-
-                        info!("Session {}: duration {}s ({}m), speed {}wpm -> budget {} words -> {} articles",
-                            session_id, duration, reading_minutes, reading_speed, total_words_budget, estimated_articles);
-
-                        match sqlx::query(
-                            "SELECT
-                                uas.article_id,
-                                uas.personalized_headline,
-                                uas.personalized_bullets,
-                                uas.personalized_details,
-                                uas.language,
-                                uas.relevance_score,
-                                a.canonical_url,
-                                f.title as feed_title
-                             FROM user_article_summaries uas
-                             JOIN articles a ON uas.article_id = a.id
-                             -- Require that the article appears in at least one feed the user is subscribed to.
-                             JOIN article_occurrences ao ON a.id = ao.article_id
-                             JOIN subscriptions s ON s.feed_id = ao.feed_id AND s.user_id = ?
-                             LEFT JOIN feeds f ON ao.feed_id = f.id
-                             -- Exclude articles already viewed by the user in ANY session
-                             LEFT JOIN user_article_views uav ON uas.user_id = uav.user_id AND uas.article_id = uav.article_id
-                             WHERE uas.user_id = ?
-                               AND uas.is_relevant = 1
-                               AND uav.id IS NULL
-                             GROUP BY uas.article_id
-                             ORDER BY uas.relevance_score DESC, a.first_seen_at DESC
-                             LIMIT ?"
-                        )
-                        // Bind order corresponds to the ? placeholders above:
-                        // 1: s.user_id, 2: uas.user_id, 3: LIMIT
-                        .bind(user_id)
-                        .bind(user_id)
-                        .bind(estimated_articles)
-                        .fetch_all(&pool)
-                        .await
+                        // Calculate number of articles (shared with the REST press review)
+                        let (opts, catch_up) = crate::press_review::review_selection_options(
+                            &pool, user_id, duration_seconds, reading_speed, config.as_deref(),
+                        ).await;
+
+                        info!("Session {}: duration {}s ({}m), speed {}wpm -> {} articles",
+                            session_id, duration, reading_minutes, reading_speed, opts.limit);
+
+                        match crate::press_review::select_review_articles(&pool, user_id, &opts).await
                         {
                             Ok(articles) => {
+                                let serendipity_ids: std::collections::HashSet<i64> =
+                                    articles.iter().filter(|a| a.serendipity).map(|a| a.id).collect();
+                                // Collapsed copies of each story: named on its card, marked viewed with it
+                                let duplicates: std::collections::HashMap<i64, (Vec<String>, Vec<i64>)> = articles.iter()
+                                    .filter(|a| !a.duplicates.is_empty())
+                                    .map(|a| (a.id, (a.also_covered_by(), a.duplicates.iter().map(|d| d.id).collect())))
+                                    .collect();
+
                                 if articles.is_empty() {
                                     let msg = "I couldn't find any new relevant articles for you right now. Please check back later!";
-                                    let _ = tx_clone.send(Message::Text(serde_json::to_string(&json!({
+                                    frames.publish(json!({
                                         "type": "message",
                                         "content": msg
-                                    })).unwrap()));
+                                    }));
                                 } else {
                                     // Hide progress indicator
-                                    let _ = tx_clone.send(Message::Text(serde_json::to_string(&json!({
+                                    frames.publish(json!({
                                         "type": "progress_hide"
-                                    })).unwrap()));
-
-                                    // Extract article data from rows (include stored summary language)
-                                    use sqlx::Row;
-                                    let article_data: Vec<(i64, String, String, Option<String>, String, f64, String, Option<String>)> = articles.iter()
-                                        .map(|row| {
-                                            let article_id: i64 = row.get("article_id");
-                                            let headline: String = row.get("personalized_headline");
-                                            let bullets: String = row.get("personalized_bullets");
-                                            let details: Option<String> = row.try_get("personalized_details").ok();
-                                            let article_lang: String = row.get("language");
-                                            let relevance: f64 = row.get("relevance_score");
-                                            let url: String = row.get("canonical_url");
-                                            let feed_title: Option<String> = row.try_get("feed_title").ok();
-                                            (article_id, headline, bullets, details, article_lang, relevance, url, feed_title)
+                                    }));
+
+                                    // Returning after a long absence: say the backlog was trimmed
+                                    let shown = articles.iter().filter(|a| !a.serendipity).count() as i64;
+                                    if let Some(catch_up) = catch_up.filter(|c| c.backlog > shown) {
+                                        let msg = catch_up_message(&user_profile_lang, catch_up.away_hours / 24, shown, catch_up.backlog);
+                                        let _ = crate::sessions::store_message(&pool, session_id, "assistant", &msg).await;
+                                        frames.publish(json!({ "type": "message", "content": msg }));
+                                    }
+
+                                    // Extract article data (include stored summary language)
+                                    let article_data: Vec<SelectedArticleRow> = articles.into_iter()
+                                        .map(|a| {
+                                            let bullets = serde_json::to_string(&a.bullets).unwrap_or_else(|_| "[]".to_string());
+                                            let group_label = a.group_label(group_by);
+                                            (a.id, a.headline, bullets, a.details, a.language, a.source_language, a.score, a.url, a.feed_title, group_label)
                                         })
                                         .collect();
 
@@ -218,9 +278,10 @@ pub fn chat_websocket(
                                     // We want to process N articles in parallel to hide LLM latency, 
                                     // but emit them in order to respect relevance sorting.
                                     let stream = rocket::futures::stream::iter(article_data)
-                                        .map(|(article_id, headline, bullets_json, details, article_lang, _relevance, url, feed_title)| {
+                                        .map(|(article_id, headline, bullets_json, details, article_lang, source_lang, _relevance, url, feed_title, group_label)| {
                                             let llm_provider_clone = llm_provider.clone();
                                             let user_profile_lang_clone = user_profile_lang.clone();
+                                            let pool_clone = pool.clone();
                                             
                                             async move {
                                                 // Construct raw summary
@@ -231,8 +292,8 @@ pub fn chat_websocket(
                                                     bullets.join(" ")
                                                 };
 
-                                                let theme = feed_title.clone().unwrap_or_else(|| "Actualité".to_string());
-                                                let source_name = feed_title.unwrap_or_else(|| "Unknown".to_string());
+                                                let theme = group_label.unwrap_or_else(|| "Actualité".to_string());
+                                                let feed_name = feed_title.unwrap_or_else(|| "Unknown".to_string());
 
                                                 // Truncate input
                                                 let input_text = if raw_summary.len() > 2000 {
@@ -241,14 +302,21 @@ pub fn chat_websocket(
                                                     raw_summary.clone()
                                                 };
 
+                                                // Name the article's own language, so items already in the
+                                                // reader's language are refined rather than re-translated
+                                                let source_lang_name = source_lang.as_deref()
+                                                    .and_then(crate::i18n::language_name)
+                                                    .unwrap_or("unknown");
+
                                                 let refine_prompt = format!(
                                                     "Task: Translate and refine this news item for a {} speaker.
                                             
+                                            Source Language: {}
                                             Original Headline: {}
                                             Content Snippet: {}
 
                                             Requirements:
-                                            1. Language: {} ONLY.
+                                            1. Language: {} ONLY. If the source is already in this language, do not translate: only refine.
                                             2. No truncation.
                                             3. No Markdown: Output PLAIN TEXT only.
                                             4. Format: Use the exact format below.
@@ -266,6 +334,7 @@ pub fn chat_websocket(
                                                         "it" => "Italian",
                                                         _ => "English"
                                                     },
+                                                    source_lang_name,
                                                     headline,
                                                     input_text,
                                                     match user_profile_lang_clone.as_str() {
@@ -277,12 +346,15 @@ pub fn chat_websocket(
                                                     }
                                                 );
 
-                                                let (final_title, final_summary, final_context, final_lang) = match llm_provider_clone.generate(crate::llm::LlmRequest {
+                                                let refined = llm_provider_clone.generate(crate::llm::LlmRequest {
                                                     prompt: refine_prompt,
                                                     max_tokens: Some(600),
                                                     temperature: Some(0.3),
                                                     timeout_seconds: Some(45),
-                                                }).await {
+                                                }).await;
+                                                let model = refined.as_ref().map(|r| r.model.as_str()).unwrap_or("unknown");
+                                                crate::llm::usage::log_llm_result(&pool_clone, "press_review", model, &refined, |r| Some(&r.usage)).await;
+                                                let (final_title, final_summary, final_context, final_lang) = match refined {
                                                     Ok(resp) => {
                                                         let content = resp.content.trim();
                                                         let find_marker = |text: &str, markers: &[&str]| -> Option<(usize, usize)> {
@@ -347,31 +419,49 @@ pub fn chat_websocket(
                                                     final_lang, 
                                                     url, 
                                                     theme, 
-                                                    source_name, 
-                                                    article_lang,
+                                                    feed_name,
+                                                    source_lang.unwrap_or(article_lang),
                                                     details
                                                 )
                                             }
                                         })
                                         .buffered(4); // PARALLELISM: 4 concurrent LLM requests
 
+                                    // Cards are kept (with details, for chat context) to be stored as the session's review
+                                    let generated_cards = Arc::new(std::sync::Mutex::new(Vec::<serde_json::Value>::new()));
+
                                     // Consume the stream
                                     stream.for_each(|(article_id, final_title, final_summary, final_context, final_lang, url, theme, source_name, origin_lang, details)| {
-                                        let tx_inner = tx_clone.clone();
                                         let pool_inner = pool.clone();
-                                        let context_bg_inner = article_context_bg.clone();
                                         let session_id_inner = session_id;
                                         let user_id_inner = user_id;
+                                        let is_serendipity = serendipity_ids.contains(&article_id);
+                                        let (also_covered_by, duplicate_ids) = duplicates.get(&article_id).cloned().unwrap_or_default();
+                                        let also_covered_by_label = crate::i18n::t(&language_clone, crate::i18n::Msg::AlsoCoveredBy);
+                                        let generated_cards = generated_cards.clone();
+                                        let theme = if is_serendipity {
+                                            match language_clone.as_str() {
+                                                "fr" => "✨ Autre chose".to_string(),
+                                                "es" => "✨ Algo diferente".to_string(),
+                                                "de" => "✨ Mal etwas anderes".to_string(),
+                                                "it" => "✨ Qualcosa di diverso".to_string(),
+                                                _ => "✨ Something different".to_string(),
+                                            }
+                                        } else {
+                                            theme
+                                        };
 
                                         async move {
-                                            // Update shared context
-                                            if let Ok(mut ctx) = context_bg_inner.lock() {
-                                                ctx.push(ArticleContext {
-                                                    id: article_id,
-                                                    title: final_title.clone(),
-                                                    summary: final_summary.clone(),
-                                                    content: details.clone(),
-                                                });
+                                            // Keep the card as chat context for this session
+                                            let article = ArticleContext {
+                                                id: article_id,
+                                                title: final_title.clone(),
+                                                summary: final_summary.clone(),
+                                                content: details.clone(),
+                                                also_covered_by: also_covered_by.clone(),
+                                            };
+                                            if let Err(e) = crate::sessions::store_session_article(&pool_inner, session_id_inner, &article).await {
+                                                error!("Session {}: {:?}", session_id_inner, e);
                                             }
 
                                             // Send card
@@ -386,20 +476,30 @@ pub fn chat_websocket(
                                                     "theme": theme,
                                                     "lang": final_lang,
                                                     "origin_lang": origin_lang,
-                                                    "context_region": final_context
+                                                    "context_region": final_context,
+                                                    "serendipity": is_serendipity,
+                                                    "also_covered_by": also_covered_by,
+                                                    "also_covered_by_label": also_covered_by_label
                                                 }
                                             });
-                                            let _ = tx_inner.send(Message::Text(serde_json::to_string(&card).unwrap()));
-
-                                            // Mark as viewed
-                                            let _ = sqlx::query(
-                                                "INSERT OR IGNORE INTO user_article_views (user_id, article_id, session_id) VALUES (?, ?, ?)"
-                                            )
-                                            .bind(user_id_inner)
-                                            .bind(article_id)
-                                            .bind(session_id_inner)
-                                            .execute(&pool_inner)
-                                            .await;
+                                            frames.publish(card.clone());
+                                            if let Ok(mut cards) = generated_cards.lock() {
+                                                let mut stored = card["article"].clone();
+                                                stored["details"] = json!(details);
+                                                cards.push(stored);
+                                            }
+
+                                            // Mark as viewed, with its collapsed copies so they don't resurface
+                                            for viewed_id in std::iter::once(article_id).chain(duplicate_ids) {
+                                                let _ = sqlx::query(
+                                                    "INSERT OR IGNORE INTO user_article_views (user_id, article_id, session_id) VALUES (?, ?, ?)"
+                                                )
+                                                .bind(user_id_inner)
+                                                .bind(viewed_id)
+                                                .bind(session_id_inner)
+                                                .execute(&pool_inner)
+                                                .await;
+                                            }
 
                                             // Update User Vector on View (Passive signal)
                                             let pool_v_inner = pool_inner.clone();
@@ -414,29 +514,49 @@ pub fn chat_websocket(
                                         }
                                     }).await;
 
+                                    let cards_json = generated_cards.lock()
+                                        .map(|cards| serde_json::to_string(&*cards).unwrap_or_default())
+                                        .unwrap_or_default();
+                                    if let Err(e) = crate::press_review::store_review(&pool, session_id, &cards_json, &model).await {
+                                        error!("Session {}: {:?}", session_id, e);
+                                    }
+                                    let headlines = generated_cards.lock()
+                                        .map(|cards| cards.iter().filter_map(|c| c["title"].as_str()).map(|t| format!("- {}", t)).collect::<Vec<_>>().join("\n"))
+                                        .unwrap_or_default();
+                                    if !headlines.is_empty() {
+                                        super::title::spawn_session_title(
+                                            pool.clone(),
+                                            llm_provider.clone(),
+                                            session_id,
+                                            format!("Press review headlines:\n{}", headlines),
+                                            Some(user_profile_lang.clone()),
+                                        );
+                                    }
+
                                     // Final message
-                                    let completion_msg = match language_clone.as_str() {
-                                        "fr" => "Voilà pour l'essentiel de l'actualité. Souhaitez-vous approfondir un sujet ?",
-                                        "es" => "Eso es todo por ahora. ¿Desea profundizar en algún tema?",
-                                        "de" => "Das war das Wichtigste. Möchten Sie ein Thema vertiefen?",
-                                        "it" => "Questo è tutto per ora. Vuoi approfondire un argomento?",
-                                        _ => "That's the main news. Would you like to explore any topic further?"
-                                    };
+                                    let completion_msg = completion_message(&language_clone);
 
                                     let _ = crate::sessions::store_message(&pool, session_id, "assistant", completion_msg).await;
-                                    let _ = tx_clone.send(Message::Text(serde_json::to_string(&json!({
+                                    frames.publish(json!({
                                         "type": "message",
                                         "content": completion_msg
-                                    })).unwrap()));
+                                    }));
+
+                                    // Notify that the review is ready, in the same language as the greeting
+                                    // and completion messages above
+                                    let event = crate::notifications::NotificationEvent::review_ready(user_id, &language_clone);
+                                    let mut frame = serde_json::to_value(&event).unwrap_or_default();
+                                    frame["type"] = json!("notification");
+                                    frames.publish(frame);
                                 }
                             }
                             Err(e) => {
                                 error!("Failed to fetch personalized articles for user {}: {:?}", user_id, e);
                                 let msg = "I'm having trouble accessing the latest news. Please try again later.";
-                                let _ = tx_clone.send(Message::Text(serde_json::to_string(&json!({
+                                frames.publish(json!({
                                     "type": "message",
                                     "content": msg
-                                })).unwrap()));
+                                }));
                             }
                         }
                     });
@@ -445,7 +565,7 @@ pub fn chat_websocket(
                 // Existing session: replay history
                 for msg in messages {
                     let role = if msg.author == "user" { "user" } else { "assistant" };
-                    send_json(&tx, json!({
+                    frames.send_to(&tx, json!({
                         "type": "history",
                         "role": role,
                         "content": msg.message
@@ -453,8 +573,33 @@ pub fn chat_websocket(
                 }
             }
 
-            // Handle incoming messages
-            while let Some(message) = ws_stream.next().await {
+            // Cards currently expanded, for reading-speed calibration
+            let mut open_articles: std::collections::HashMap<i64, std::time::Instant> = std::collections::HashMap::new();
+
+            // Handle incoming messages, pinging the client while it's quiet
+            let mut last_seen = Instant::now();
+            let mut ping = tokio::time::interval_at(tokio::time::Instant::now() + heartbeat.interval, heartbeat.interval);
+            loop {
+                let message = tokio::select! {
+                    message = ws_stream.next() => match message {
+                        Some(message) => message,
+                        None => break,
+                    },
+                    _ = ping.tick() => {
+                        if heartbeat.is_idle(last_seen, Instant::now()) {
+                            info!("WebSocket for session {} idle for {:?}, closing", session_id, heartbeat.idle_timeout);
+                            let _ = tx.send(Message::Close(None));
+                            break;
+                        }
+                        if tx.send(Message::Ping(Vec::new())).is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                if message.is_ok() {
+                    last_seen = Instant::now();
+                }
                 match message {
                     Ok(Message::Text(text)) => {
                         info!("Received message for session {}: {}", session_id, text);
@@ -462,21 +607,37 @@ pub fn chat_websocket(
                         // Parse user message
                         let json_msg: serde_json::Value = serde_json::from_str(&text).unwrap_or(json!({"type": "message", "message": text}));
 
+                        if json_msg["type"] == "article_open" || json_msg["type"] == "article_close" {
+                            let Some(article_id) = json_msg["article_id"].as_i64() else { continue };
+                            if json_msg["type"] == "article_open" {
+                                open_articles.insert(article_id, std::time::Instant::now());
+                            } else if let Some(opened) = open_articles.remove(&article_id) {
+                                let words = crate::sessions::get_session_articles(&pool, session_id).await
+                                    .ok()
+                                    .and_then(|ctx| ctx.iter().find(|a| a.id == article_id).map(|a| a.summary.split_whitespace().count()))
+                                    .unwrap_or(0);
+                                let seconds = opened.elapsed().as_secs_f64();
+                                if let Err(e) = crate::reading_speed::record_reading(&pool, user_id, words, seconds).await {
+                                    error!("Error calibrating reading speed for user {}: {:?}", user_id, e);
+                                }
+                            }
+                            continue;
+                        }
+
                         if json_msg["type"] == "rate" {
                             // Handle Rating
                             if let (Some(article_id), Some(rating)) = (json_msg["article_id"].as_i64(), json_msg["rating"].as_i64()) {
+                                if !crate::views::RATING_RANGE.contains(&rating) {
+                                    tracing::warn!("User {} sent out-of-range rating {} for article {}", user_id, rating, article_id);
+                                    continue;
+                                }
                                 info!("User {} rated article {} with {} stars", user_id, article_id, rating);
-                                let _ = sqlx::query(
-                                    "UPDATE user_article_views SET rating = ? WHERE user_id = ? AND article_id = ?"
-                                )
-                                .bind(rating)
-                                .bind(user_id)
-                                .bind(article_id)
-                                .execute(&pool)
-                                .await;
+                                if let Err(e) = crate::views::rate_article(&pool, user_id, article_id, rating).await {
+                                    error!("Error storing rating for article {}: {:?}", article_id, e);
+                                }
 
                                 // Update User Vector (Rating is a strong signal)
-                                let weight = (rating as f32 - 2.0).max(0.1); // 1 star = 0.1, 3 stars = 1.0, 5 stars = 3.0
+                                let weight = crate::views::rating_weight(rating);
                                 let pool_clone = pool.clone();
                                 tokio::spawn(async move {
                                     if let Err(e) = crate::personalize_worker::update_user_vector_from_interaction(&pool_clone, user_id, article_id, weight).await {
@@ -493,6 +654,27 @@ pub fn chat_websocket(
                             text
                         };
 
+                        // Bound LLM cost: refuse (without calling the LLM) when the user is over budget
+                        if !chat_limiter.try_acquire(user_id) {
+                            tracing::warn!("Chat rate limit exceeded for user {} (session {})", user_id, session_id);
+                            let msg = match language.as_str() {
+                                "fr" => "Doucement ! Vous envoyez des messages trop vite. Réessayez dans un instant.",
+                                "es" => "¡Más despacio! Está enviando mensajes demasiado rápido. Inténtelo de nuevo en un momento.",
+                                "de" => "Langsamer bitte! Sie senden Nachrichten zu schnell. Versuchen Sie es gleich noch einmal.",
+                                "it" => "Piano! Stai inviando messaggi troppo velocemente. Riprova tra un momento.",
+                                _ => "Slow down! You're sending messages too quickly. Please try again in a moment.",
+                            };
+                            frames.publish(json!({
+                                "type": "message",
+                                "content": msg,
+                                "rate_limited": true
+                            }));
+                            continue;
+                        }
+
+                        // Shown until the reply (or fallback) is sent, even without token streaming
+                        let _typing = frames.typing();
+
                         // Store user message
                         if let Err(e) = store_message(&pool, session_id, "user", &user_message).await {
                             error!("Failed to store user message: {}", e);
@@ -501,10 +683,14 @@ pub fn chat_websocket(
 
                         // Generate LLM response
                         let response = if let Some(ref provider) = llm {
-                            // Get current articles context
-                            let current_articles = article_context_chat.lock()
-                                .map(|guard| guard.clone())
-                                .unwrap_or_default();
+                            // Articles shown in this session so far, whichever connection sent them
+                            let current_articles = match crate::sessions::get_session_articles(&pool, session_id).await {
+                                Ok(articles) => articles,
+                                Err(e) => {
+                                    error!("Session {}: {:?}", session_id, e);
+                                    Vec::new()
+                                }
+                            };
                             
                             // Update User Vector (Chatting about articles is a signal)
                             for art in &current_articles {
@@ -517,28 +703,80 @@ pub fn chat_websocket(
                                 });
                             }
 
-                            match handle_chat_message(&pool, provider, session_id, &user_message, &current_articles).await {
-                                Ok(resp) => resp,
+                            match handle_chat_message(&pool, provider, session_id, &user_message, &current_articles, &frames).await {
+                                Ok(resp) => Ok(resp),
                                 Err(e) => {
                                     error!("LLM error: {}", e);
-                                    "Sorry, I encountered an error processing your message.".to_string()
+                                    let err = e.to_string();
+                                    let _ = crate::llm::usage::log_usage(&pool, crate::llm::usage::UsageEntry {
+                                        operation: "chat",
+                                        model: "unknown",
+                                        usage: None,
+                                        error_message: Some(&err),
+                                        session_id: Some(session_id),
+                                    }).await;
+                                    Err("Sorry, I encountered an error processing your message.".to_string())
                                 }
                             }
                         } else {
-                            "LLM provider not configured.".to_string()
+                            Err("LLM provider not configured.".to_string())
                         };
 
-                        // Store assistant response
-                        if let Err(e) = store_message(&pool, session_id, "assistant", &response).await {
-                            error!("Failed to store assistant message: {}", e);
+                        // Store assistant response, with model/token usage for LLM-generated turns
+                        let streamed = response.is_ok();
+                        let response = match response {
+                            Ok(llm_resp) => {
+                                match store_message(&pool, session_id, "assistant", &llm_resp.content).await {
+                                    Ok(stored) => {
+                                        if let Err(e) = crate::sessions::record_message_usage(&pool, stored.id, &llm_resp.model, &llm_resp.usage).await {
+                                            error!("Failed to record chat usage: {}", e);
+                                        }
+                                    }
+                                    Err(e) => error!("Failed to store assistant message: {}", e),
+                                }
+                                if let Err(e) = crate::llm::usage::log_usage(&pool, crate::llm::usage::UsageEntry {
+                                    operation: "chat",
+                                    model: &llm_resp.model,
+                                    usage: Some(&llm_resp.usage),
+                                    error_message: None,
+                                    session_id: Some(session_id),
+                                }).await {
+                                    error!("Failed to log chat usage: {}", e);
+                                }
+                                llm_resp.content
+                            }
+                            Err(fallback) => {
+                                if let Err(e) = store_message(&pool, session_id, "assistant", &fallback).await {
+                                    error!("Failed to store assistant message: {}", e);
+                                }
+                                fallback
+                            }
+                        };
+
+                        // The first exchange titles a session that didn't get one from its review
+                        if let (true, Some(provider)) = (streamed, llm.clone()) {
+                            super::title::spawn_session_title(
+                                pool.clone(),
+                                provider,
+                                session_id,
+                                format!("User: {}\nAssistant: {}", user_message, response),
+                                None,
+                            );
                         }
 
-                        // Send response to client
-                        send_json(&tx, json!({
-                            "type": "message",
-                            "author": "assistant",
-                            "message": response,
-                        }));
+                        // Send response to client: close the streamed reply, or send the fallback whole
+                        if streamed {
+                            frames.publish(json!({
+                                "type": "message_end",
+                                "content": response,
+                            }));
+                        } else {
+                            frames.publish(json!({
+                                "type": "message",
+                                "author": "assistant",
+                                "message": response,
+                            }));
+                        }
                     }
                     Ok(Message::Close(_)) => {
                         info!("WebSocket closed for session {}", session_id);
@@ -552,28 +790,54 @@ pub fn chat_websocket(
                 }
             }
 
+            // Nothing writes to this connection any more: the review task
+            // publishes to the frame log, which drops detached senders
+            frames.detach(&tx);
+            drop(tx);
+            if tokio::time::timeout(FORWARD_DRAIN_TIMEOUT, &mut forwarder).await.is_err() {
+                forwarder.abort();
+            }
+            info!("WebSocket for session {} finished", session_id);
             Ok(())
         })
-    })
+    }))
 }
 
-/// Context for an article to be used in chat
-#[derive(Clone, Debug)]
-pub struct ArticleContext {
-    pub id: i64,
-    pub title: String,
-    pub summary: String,
-    pub content: Option<String>,
+/// Row selected for the press review: (article_id, headline, bullets_json, details,
+/// language, source_language, relevance, url, feed_title, group_label)
+type SelectedArticleRow = (i64, String, String, Option<String>, String, Option<String>, f64, String, Option<String>, Option<String>);
+
+/// Closing message of a press review, in the session language.
+fn completion_message(language: &str) -> &'static str {
+    match language {
+        "fr" => "Voilà pour l'essentiel de l'actualité. Souhaitez-vous approfondir un sujet ?",
+        "es" => "Eso es todo por ahora. ¿Desea profundizar en algún tema?",
+        "de" => "Das war das Wichtigste. Möchten Sie ein Thema vertiefen?",
+        "it" => "Questo è tutto per ora. Vuoi approfondire un argomento?",
+        _ => "That's the main news. Would you like to explore any topic further?"
+    }
 }
 
-/// Handle chat message with LLM
+/// Intro of a review capped by the catch-up limit.
+fn catch_up_message(language: &str, away_days: i64, shown: i64, backlog: i64) -> String {
+    match language {
+        "fr" => format!("Vous étiez absent depuis {} jours : voici les {} articles les plus pertinents sur {}.", away_days, shown, backlog),
+        "es" => format!("Ha estado fuera {} días: estos son los {} artículos más relevantes de {}.", away_days, shown, backlog),
+        "de" => format!("Sie waren {} Tage weg: Hier sind die {} wichtigsten von {} Artikeln.", away_days, shown, backlog),
+        "it" => format!("Sei stato via {} giorni: ecco i {} articoli più rilevanti su {}.", away_days, shown, backlog),
+        _ => format!("You've been away for {} days: here are the top {} of {} articles.", away_days, shown, backlog),
+    }
+}
+
+/// Handle chat message with LLM, streaming the reply to the session as `token` frames
 async fn handle_chat_message(
     pool: &SqlitePool,
     llm_provider: &Arc<dyn LlmProvider>,
     session_id: i64,
     user_message: &str,
     articles: &[ArticleContext],
-) -> Result<String> {
+    frames: &super::frames::FrameLog,
+) -> Result<LlmResponse> {
     // Get conversation history
     let messages = get_messages(pool, session_id).await?;
 
@@ -621,7 +885,10 @@ async fn handle_chat_message(
                 };
                 context.push_str(&format!("Content Snippet: {}\n", truncated));
             }
-            context.push_str("\n");
+            if !article.also_covered_by.is_empty() {
+                context.push_str(&format!("Also covered by: {}\n", article.also_covered_by.join(", ")));
+            }
+            context.push('\n');
         }
         context.push_str("Use the above articles to answer the user's questions if relevant.\n\n");
     }
@@ -639,7 +906,44 @@ async fn handle_chat_message(
         timeout_seconds: Some(30),
     };
 
-    let response = llm_provider.generate(request).await?;
+    // Forward tokens to the client as they arrive
+    let mut stream = llm_provider.generate_stream(request).await?;
+    let mut content = String::new();
+    let mut model = String::new();
+    let mut usage = UsageMetadata::default();
+    while let Some(chunk) = stream.next().await {
+        match chunk? {
+            LlmChunk::Token(token) => {
+                content.push_str(&token);
+                frames.publish(json!({ "type": "token", "content": token }));
+            }
+            LlmChunk::Done { model: m, usage: u } => {
+                model = m;
+                usage = u;
+            }
+        }
+    }
+    Ok(LlmResponse { content, usage, model })
+}
 
-    Ok(response.content)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heartbeat_from_config_and_idle() {
+        let defaults = Heartbeat::from_config(None);
+        assert_eq!(defaults.interval, Duration::from_secs(DEFAULT_PING_INTERVAL_SECONDS));
+        assert_eq!(defaults.idle_timeout, Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECONDS));
+
+        let config: common::Config = toml::from_str(
+            "[database]\npath = \"x.db\"\n[scheduler]\ntimes = []\n[server]\nws_ping_interval_seconds = 5\nws_idle_timeout_seconds = 12",
+        )
+        .unwrap();
+        let heartbeat = Heartbeat::from_config(Some(&config));
+        assert_eq!(heartbeat.interval, Duration::from_secs(5));
+        let last_seen = Instant::now();
+        assert!(!heartbeat.is_idle(last_seen, last_seen + Duration::from_secs(11)));
+        assert!(heartbeat.is_idle(last_seen, last_seen + Duration::from_secs(12)));
+    }
 }
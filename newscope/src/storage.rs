@@ -1,23 +1,296 @@
 use anyhow::{Context, Result};
 use chrono::Utc;
+use common::{Config, FeedTransformConfig};
 use feed_rs::model::Entry;
-use sqlx::SqlitePool;
-use tracing::{info, debug};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tracing::{info, debug, warn};
 
+use crate::dedup::{self, DedupStats, UrlNormalizer};
 use crate::scraping;
 
+/// Per-feed transform applied to an entry's title/content before dedup/insert.
+/// Built from a `[[feed_transforms]]` config entry; feeds without one are stored as-is.
+pub struct EntryTransform {
+    title_field: Option<String>,
+    content_field: Option<String>,
+    title_replacements: Vec<(Regex, String)>,
+    content_replacements: Vec<(Regex, String)>,
+}
+
+impl EntryTransform {
+    /// Compile a transform from its config. Fails on invalid regex or unknown field names.
+    pub fn from_config(cfg: &FeedTransformConfig) -> Result<Self> {
+        let mut title_replacements = Vec::new();
+        let mut content_replacements = Vec::new();
+
+        for r in &cfg.replacements {
+            let re = Regex::new(&r.pattern)
+                .with_context(|| format!("invalid regex '{}' for feed {}", r.pattern, cfg.feed_url))?;
+            match r.field.as_str() {
+                "title" => title_replacements.push((re, r.replacement.clone())),
+                "content" => content_replacements.push((re, r.replacement.clone())),
+                other => anyhow::bail!("unknown replacement field '{}' for feed {}", other, cfg.feed_url),
+            }
+        }
+
+        Ok(Self {
+            title_field: cfg.title_field.clone(),
+            content_field: cfg.content_field.clone(),
+            title_replacements,
+            content_replacements,
+        })
+    }
+
+    /// Look up the transform configured for `feed_url`, if any.
+    /// Invalid configs are logged and ignored so a bad rule never blocks ingestion.
+    pub fn for_feed(config: Option<&Config>, feed_url: &str) -> Option<Self> {
+        let cfg = config?.feed_transforms.iter().find(|t| t.feed_url == feed_url)?;
+        match Self::from_config(cfg) {
+            Ok(t) => Some(t),
+            Err(e) => {
+                tracing::warn!("Ignoring feed transform for {}: {}", feed_url, e);
+                None
+            }
+        }
+    }
+
+    /// Title for this entry, honouring `title_field` and title replacements.
+    pub fn title(&self, entry: &Entry) -> String {
+        let raw = match self.title_field.as_deref() {
+            Some("summary") => entry.summary.as_ref().map(|s| s.content.clone()),
+            _ => None,
+        }
+        .unwrap_or_else(|| default_title(entry));
+        apply_replacements(raw, &self.title_replacements)
+    }
+
+    /// Content for this entry, honouring `content_field` and content replacements.
+    pub fn content(&self, entry: &Entry) -> String {
+        let raw = match self.content_field.as_deref() {
+            Some("summary") => entry.summary.as_ref().map(|s| decode_entities(&s.content)),
+            Some("media_description") => entry
+                .media
+                .iter()
+                .find_map(|m| m.description.as_ref().map(|d| decode_entities(&d.content))),
+            _ => None,
+        }
+        .unwrap_or_else(|| default_content(entry));
+        apply_replacements(raw, &self.content_replacements)
+    }
+}
+
+fn default_title(entry: &Entry) -> String {
+    entry.title.as_ref().map(|t| t.content.clone()).unwrap_or_default()
+}
+
+/// The entry's text fields in priority order: `content` (Atom `<content>`,
+/// RSS `content:encoded`, JSON Feed `content_html` or else `content_text`),
+/// `summary` (Atom `<summary>`, RSS `<description>`, JSON Feed `summary` or
+/// the `content_text` left over from `content_html`), then `media:description`.
+fn content_candidates(entry: &Entry) -> Vec<String> {
+    entry
+        .content
+        .as_ref()
+        .and_then(|c| c.body.clone())
+        .into_iter()
+        .chain(entry.summary.as_ref().map(|s| s.content.clone()))
+        .chain(entry.media.iter().filter_map(|m| m.description.as_ref().map(|d| d.content.clone())))
+        .collect()
+}
+
+/// The richest of [`content_candidates`]: the one with the most text once
+/// markup is ignored, the earlier one on a tie. Empty when all are blank.
+fn default_content(entry: &Entry) -> String {
+    let mut best: Option<(usize, String)> = None;
+    for candidate in content_candidates(entry) {
+        let candidate = decode_entities(candidate.trim());
+        let len = text_len(&candidate);
+        if len > 0 && best.as_ref().is_none_or(|(best_len, _)| len > *best_len) {
+            best = Some((len, candidate));
+        }
+    }
+    best.map(|(_, content)| content).unwrap_or_default()
+}
+
+/// Decode the HTML entities `feed_rs` leaves in text without markup: plain text
+/// such as "Q&amp;A", or HTML escaped once more than its `type` says
+/// ("&lt;p&gt;..."). Markup that decoding brings to life goes through
+/// [`sanitize_markup`], so escaped `<script>` or `onclick` stay inert. Text
+/// that already contains tags is returned as is.
+fn decode_entities(text: &str) -> String {
+    static ENTITY: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    let entity = ENTITY.get_or_init(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z][a-zA-Z0-9]*);").unwrap());
+    if text.contains('<') || !entity.is_match(text) {
+        return text.to_string();
+    }
+    let decoded: String = scraper::Html::parse_fragment(text).root_element().text().collect();
+    if decoded.contains('<') {
+        sanitize_markup(&decoded)
+    } else {
+        decoded
+    }
+}
+
+/// Formatting tags kept by [`sanitize_markup`].
+const SAFE_TAGS: &[&str] = &[
+    "a", "b", "blockquote", "br", "code", "div", "em", "figcaption", "figure", "h1", "h2", "h3", "h4", "h5", "h6",
+    "hr", "i", "img", "li", "ol", "p", "pre", "q", "s", "span", "strong", "sub", "sup", "table", "tbody", "td",
+    "th", "thead", "tr", "u", "ul",
+];
+
+/// Tags dropped by [`sanitize_markup`] together with their content.
+const DROPPED_TAGS: &[&str] = &[
+    "embed", "form", "iframe", "math", "noscript", "object", "script", "style", "svg", "template",
+];
+
+/// Rebuild `html` from [`SAFE_TAGS`] only: other tags are unwrapped (their
+/// text kept) or, for [`DROPPED_TAGS`], removed; the only attributes kept are
+/// `href` and `title` on links and `src`, `alt` and `title` on images, with
+/// http(s) or relative URLs.
+fn sanitize_markup(html: &str) -> String {
+    let fragment = scraper::Html::parse_fragment(html);
+    let mut out = String::with_capacity(html.len());
+    write_safe_children(fragment.root_element(), &mut out);
+    out
+}
+
+fn write_safe_children(element: scraper::ElementRef, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            scraper::Node::Text(text) => out.push_str(&escape_html(text)),
+            scraper::Node::Element(el) => {
+                let Some(child) = scraper::ElementRef::wrap(child) else { continue };
+                let name = el.name();
+                if DROPPED_TAGS.contains(&name) {
+                    continue;
+                }
+                if !SAFE_TAGS.contains(&name) {
+                    write_safe_children(child, out);
+                    continue;
+                }
+                out.push('<');
+                out.push_str(name);
+                for (attr, value) in el.attrs() {
+                    if safe_attribute(name, attr, value) {
+                        out.push_str(&format!(" {}=\"{}\"", attr, escape_html(value)));
+                    }
+                }
+                out.push('>');
+                if !matches!(name, "br" | "hr" | "img") {
+                    write_safe_children(child, out);
+                    out.push_str(&format!("</{}>", name));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn safe_attribute(tag: &str, attr: &str, value: &str) -> bool {
+    let is_url = match (tag, attr) {
+        (_, "title") | ("img", "alt") => return true,
+        ("a", "href") | ("img", "src") => true,
+        _ => return false,
+    };
+    // Relative URLs have no scheme before their first path, query or fragment
+    let value = value.trim().to_ascii_lowercase();
+    let scheme_end = value.find(':');
+    let path_start = value.find(['/', '?', '#']);
+    is_url
+        && (value.starts_with("http://")
+            || value.starts_with("https://")
+            || scheme_end.is_none()
+            || path_start.is_some_and(|p| scheme_end.is_some_and(|s| p < s)))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Plain text of the entry's richest content (see [`default_content`]), with
+/// markup removed and whitespace collapsed.
+pub fn entry_text(entry: &Entry) -> String {
+    let content = default_content(entry);
+    let text = if content.contains('<') {
+        scraper::Html::parse_fragment(&content).root_element().text().collect::<Vec<_>>().join(" ")
+    } else {
+        content
+    };
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Length of `html`'s text content, ignoring tags and whitespace.
+fn text_len(html: &str) -> usize {
+    let count = |text: &str| text.chars().filter(|c| !c.is_whitespace()).count();
+    if !html.contains('<') {
+        return count(html);
+    }
+    scraper::Html::parse_fragment(html).root_element().text().map(count).sum()
+}
+
+/// Language of an article, detected from its title and the text of its content.
+fn article_language(title: &str, content: &str) -> Option<String> {
+    let text = if content.contains('<') {
+        scraper::Html::parse_fragment(content).root_element().text().collect::<Vec<_>>().join(" ")
+    } else {
+        content.to_string()
+    };
+    crate::i18n::detect_language(&format!("{}\n{}", title, text))
+}
+
+fn apply_replacements(mut text: String, replacements: &[(Regex, String)]) -> String {
+    for (re, rep) in replacements {
+        text = re.replace_all(&text, rep.as_str()).into_owned();
+    }
+    text
+}
+
 /// Stores a list of feed entries into the database.
+/// An optional `transform` rewrites title/content before dedup/insert.
 /// Returns the IDs of newly inserted articles.
 pub async fn store_feed_items(
     pool: &SqlitePool,
     feed_id: i64,
     entries: &[Entry],
+    transform: Option<&EntryTransform>,
 ) -> Result<Vec<i64>> {
+    let (ids, _) =
+        store_feed_items_with_stats(pool, feed_id, entries, transform, &UrlNormalizer::default(), false).await?;
+    Ok(ids)
+}
+
+/// Like `store_feed_items`, also reporting how each entry was deduplicated.
+/// An entry whose URL differs from an existing article's only by `urls`
+/// normalization (tracking parameters, fragment, mobile host), with the same
+/// title, is attached to that article. With `merge_by_content_hash`, an entry
+/// with an unknown URL but the same normalized content is attached as well.
+pub async fn store_feed_items_with_stats(
+    pool: &SqlitePool,
+    feed_id: i64,
+    entries: &[Entry],
+    transform: Option<&EntryTransform>,
+    urls: &UrlNormalizer,
+    merge_by_content_hash: bool,
+) -> Result<(Vec<i64>, DedupStats)> {
     let mut new_article_ids = Vec::new();
+    let mut stats = DedupStats::default();
+
+    // Feeds flagged `full_content` already deliver complete articles: never scrape them
+    let full_content = sqlx::query_scalar::<_, bool>("SELECT full_content FROM feeds WHERE id = ?")
+        .bind(feed_id)
+        .fetch_optional(pool)
+        .await
+        .context("failed to load feed settings")?
+        .unwrap_or(false);
 
     for entry in entries {
         // 1. Extract basic info
-        let title = entry.title.as_ref().map(|t| t.content.clone()).unwrap_or_default();
+        let title = match transform {
+            Some(t) => t.title(entry),
+            None => default_title(entry),
+        };
         // Use the first link as the URL
         let url = entry.links.first().map(|l| l.href.clone()).unwrap_or_default();
         
@@ -36,56 +309,111 @@ pub async fn store_feed_items(
         .await
         .context("failed to check existing article")?;
 
+        // Then by canonical URL hash, catching tracking/mobile variants of a known URL
+        let url_hash = dedup::url_hash(&title, &urls.normalize(&url));
+        let existing_id = match existing_id {
+            Some(id) => Some(id),
+            None => sqlx::query_scalar::<_, i64>("SELECT id FROM articles WHERE url_hash = ? ORDER BY id LIMIT 1")
+                .bind(&url_hash)
+                .fetch_optional(pool)
+                .await
+                .context("failed to check URL hash")?,
+        };
+
         let article_id = if let Some(id) = existing_id {
+            if occurs_in_feed(pool, id, feed_id).await? {
+                stats.already_seen += 1;
+            } else {
+                stats.merged_by_url += 1;
+            }
             id
         } else {
             // New article: extract content and potentially scrape
-            let published = entry.published.map(|d| d).unwrap_or_else(Utc::now);
-            let mut content = entry.content.as_ref().map(|c| c.body.clone().unwrap_or_default())
-                .or_else(|| entry.summary.as_ref().map(|s| s.content.clone()))
-                .unwrap_or_default();
-
-            // SCRAPING FALLBACK
-            // If content is very short (likely just a summary or empty), try to scrape the page.
-            // Threshold: 500 chars is arbitrary but reasonable for a "full article".
-            if content.len() < 500 {
-                info!("Content short ({}), attempting to scrape: {}", content.len(), url);
-                // We use a default timeout of 10s for scraping for now
-                match scraping::scrape_article_content(&url, 10).await {
-                    Ok(scraped) => {
-                        if scraped.len() > content.len() {
-                            info!("Scraping successful, replaced content ({} -> {} chars)", content.len(), scraped.len());
-                            content = scraped;
-                        } else {
-                            info!("Scraping returned less content, keeping original");
+            let published = entry.published.unwrap_or_else(Utc::now);
+            let mut content = match transform {
+                Some(t) => t.content(entry),
+                None => default_content(entry),
+            };
+
+            // Content-hash dedup uses the feed-provided text, before scraping
+            let hash = dedup::content_hash(&title, &content);
+            let hash_match = match &hash {
+                Some(h) => sqlx::query_scalar::<_, i64>(
+                    "SELECT id FROM articles WHERE canonical_hash = ? ORDER BY id LIMIT 1"
+                )
+                .bind(h)
+                .fetch_optional(pool)
+                .await
+                .context("failed to check content hash")?,
+                None => None,
+            };
+
+            if let (Some(id), true) = (hash_match, merge_by_content_hash) {
+                // Merged on an earlier fetch of this feed: its URL never becomes the article's
+                if occurs_in_feed(pool, id, feed_id).await? {
+                    stats.already_seen += 1;
+                } else {
+                    debug!("Merging {} into article {} by content hash", url, id);
+                    stats.merged_by_hash += 1;
+                }
+                id
+            } else {
+                if hash_match.is_some() {
+                    stats.hash_duplicates_unmerged += 1;
+                } else {
+                    stats.new_articles += 1;
+                }
+
+                // SCRAPING FALLBACK
+                // If content is very short (likely just a summary or empty), try to scrape the page.
+                // Threshold: 500 chars is arbitrary but reasonable for a "full article".
+                if !full_content && content.len() < 500 {
+                    info!("Content short ({}), attempting to scrape: {}", content.len(), url);
+                    // We use a default timeout of 10s for scraping for now
+                    match scraping::scrape_article_content(&url, 10).await {
+                        Ok(scraped) => {
+                            if scraped.len() > content.len() {
+                                info!("Scraping successful, replaced content ({} -> {} chars)", content.len(), scraped.len());
+                                content = scraped;
+                            } else {
+                                info!("Scraping returned less content, keeping original");
+                            }
+                        }
+                        Err(e) => {
+                            // Log but don't fail the whole process
+                            tracing::warn!("Failed to scrape {}: {}", url, e);
                         }
-                    }
-                    Err(e) => {
-                        // Log but don't fail the whole process
-                        tracing::warn!("Failed to scrape {}: {}", url, e);
                     }
                 }
-            }
 
-            // Insert new article
-            let id = sqlx::query_scalar::<_, i64>(
-                r#"
-                INSERT INTO articles (canonical_url, title, content, published_at, first_seen_at)
-                VALUES (?, ?, ?, ?, ?)
-                RETURNING id
-                "#
-            )
-            .bind(&url)
-            .bind(&title)
-            .bind(&content)
-            .bind(published)
-            .bind(Utc::now())
-            .fetch_one(pool)
-            .await
-            .context("failed to insert article")?;
-            
-            new_article_ids.push(id);
-            id
+                let language = article_language(&title, &content);
+
+                // Insert new article
+                let id = sqlx::query_scalar::<_, i64>(
+                    r#"
+                    INSERT INTO articles (canonical_url, title, content, published_at, first_seen_at, canonical_hash, url_hash, language)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    RETURNING id
+                    "#
+                )
+                .bind(&url)
+                .bind(&title)
+                .bind(&content)
+                .bind(published)
+                .bind(Utc::now())
+                .bind(&hash)
+                .bind(&url_hash)
+                .bind(&language)
+                .fetch_one(pool)
+                .await
+                .context("failed to insert article")?;
+
+                if let Err(e) = crate::search::index_article(pool, id).await {
+                    warn!("Failed to index article {} for search: {:?}", id, e);
+                }
+                new_article_ids.push(id);
+                id
+            }
         };
 
         // 3. Record occurrence for this feed
@@ -104,7 +432,20 @@ pub async fn store_feed_items(
         .context("failed to insert occurrence")?;
     }
 
-    Ok(new_article_ids)
+    Ok((new_article_ids, stats))
+}
+
+/// Whether `feed_id` already delivered the article `article_id`.
+async fn occurs_in_feed(pool: &SqlitePool, article_id: i64, feed_id: i64) -> Result<bool> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM article_occurrences WHERE article_id = ? AND feed_id = ?"
+    )
+    .bind(article_id)
+    .bind(feed_id)
+    .fetch_one(pool)
+    .await
+    .context("failed to check existing occurrence")?;
+    Ok(count > 0)
 }
 
 /// Store an article summary in the database
@@ -134,7 +475,391 @@ pub async fn store_article_summary(
     .execute(pool)
     .await
     .context("failed to insert article summary")?;
-    
+
+    if let Err(e) = crate::search::index_article(pool, article_id).await {
+        warn!("Failed to index article {} for search: {:?}", article_id, e);
+    }
     info!("Stored summary for article {}", article_id);
     Ok(())
 }
+
+/// Record the update cadence a feed declares (`None` when it declares none).
+pub async fn set_declared_update_minutes(pool: &SqlitePool, feed_id: i64, minutes: Option<i64>) -> Result<()> {
+    sqlx::query("UPDATE feeds SET declared_update_minutes = ? WHERE id = ?")
+        .bind(minutes)
+        .bind(feed_id)
+        .execute(pool)
+        .await
+        .context("failed to store declared update period")?;
+    Ok(())
+}
+
+/// Remember the `ETag` / `Last-Modified` of the latest successful fetch.
+pub async fn set_cache_validators(
+    pool: &SqlitePool,
+    feed_id: i64,
+    validators: &crate::ingestion::CacheValidators,
+) -> Result<()> {
+    sqlx::query("UPDATE feeds SET etag = ?, last_modified = ? WHERE id = ?")
+        .bind(&validators.etag)
+        .bind(&validators.last_modified)
+        .bind(feed_id)
+        .execute(pool)
+        .await
+        .context("failed to store cache validators")?;
+    Ok(())
+}
+
+/// `feeds.status` of a feed nobody subscribes to any more; the worker skips it.
+pub const FEED_STATUS_INACTIVE: &str = "inactive";
+/// `feeds.status` of a feed that failed too many times in a row (see
+/// [`crate::feed_health::record_failure`]); the worker skips it until re-enabled.
+pub const FEED_STATUS_DISABLED: &str = "disabled";
+
+/// Remove one of `user_id`'s subscriptions. When it was the feed's last
+/// subscription the feed row is kept (articles still reference it) but marked
+/// inactive. Returns the feed id, or `None` if the user has no such subscription.
+pub async fn unsubscribe(pool: &SqlitePool, user_id: i64, subscription_id: i64) -> Result<Option<i64>> {
+    let mut tx = pool.begin().await.context("failed to begin transaction")?;
+    let feed_id = sqlx::query_scalar::<_, i64>("SELECT feed_id FROM subscriptions WHERE id = ? AND user_id = ?")
+        .bind(subscription_id)
+        .bind(user_id)
+        .fetch_optional(&mut tx)
+        .await
+        .context("failed to look up subscription")?;
+    let Some(feed_id) = feed_id else {
+        return Ok(None);
+    };
+
+    sqlx::query("DELETE FROM subscriptions WHERE id = ?")
+        .bind(subscription_id)
+        .execute(&mut tx)
+        .await
+        .context("failed to delete subscription")?;
+    sqlx::query(
+        "UPDATE feeds SET status = ? WHERE id = ? AND NOT EXISTS (SELECT 1 FROM subscriptions WHERE feed_id = ?)",
+    )
+    .bind(FEED_STATUS_INACTIVE)
+    .bind(feed_id)
+    .bind(feed_id)
+    .execute(&mut tx)
+    .await
+    .context("failed to deactivate feed")?;
+    tx.commit().await.context("failed to commit unsubscribe")?;
+    Ok(Some(feed_id))
+}
+
+/// Resume polling an inactive feed that gained a subscriber again.
+pub async fn reactivate_feed(pool: &SqlitePool, feed_id: i64) -> Result<()> {
+    sqlx::query("UPDATE feeds SET status = NULL, next_poll_at = NULL WHERE id = ? AND status = ?")
+        .bind(feed_id)
+        .bind(FEED_STATUS_INACTIVE)
+        .execute(pool)
+        .await
+        .context("failed to reactivate feed")?;
+    Ok(())
+}
+
+/// An article seen in a given feed, with its summary when one exists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedArticle {
+    pub id: i64,
+    pub url: String,
+    pub title: Option<String>,
+    pub published_at: Option<String>,
+    /// When the article was first seen in this feed
+    pub discovered_at: Option<String>,
+    pub headline: Option<String>,
+    pub bullets: Option<Vec<String>>,
+    pub details: Option<String>,
+}
+
+/// Articles with an occurrence in `feed_id`, newest first (publication date,
+/// falling back to when the feed delivered them).
+pub async fn list_feed_articles(
+    pool: &SqlitePool,
+    feed_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<FeedArticle>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT a.id, a.canonical_url, a.title, a.published_at,
+               MIN(ao.discovered_at) AS discovered_at,
+               s.headline, s.bullets_json, s.details
+        FROM article_occurrences ao
+        JOIN articles a ON a.id = ao.article_id
+        LEFT JOIN article_summaries s ON s.article_id = a.id
+        WHERE ao.feed_id = ?
+        GROUP BY a.id
+        ORDER BY COALESCE(a.published_at, MIN(ao.discovered_at)) DESC, a.id DESC
+        LIMIT ? OFFSET ?
+        "#,
+    )
+    .bind(feed_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .context("failed to list feed articles")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| FeedArticle {
+            id: r.get("id"),
+            url: r.get("canonical_url"),
+            title: r.get("title"),
+            published_at: r.get("published_at"),
+            discovered_at: r.get("discovered_at"),
+            headline: r.get("headline"),
+            bullets: r
+                .get::<Option<String>, _>("bullets_json")
+                .and_then(|b| serde_json::from_str(&b).ok()),
+            details: r.get("details"),
+        })
+        .collect())
+}
+
+/// Stored summary of an article, as returned with [`ArticleDetail`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticleSummary {
+    pub headline: Option<String>,
+    pub bullets: Vec<String>,
+    pub details: Option<String>,
+    pub model: Option<String>,
+}
+
+/// A feed an article appeared in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticleOccurrence {
+    pub feed_id: i64,
+    pub feed_title: Option<String>,
+    pub discovered_at: Option<String>,
+}
+
+/// An article with its full content, summary and the feeds it was seen in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticleDetail {
+    pub id: i64,
+    pub canonical_url: String,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub full_content: Option<String>,
+    pub published_at: Option<String>,
+    /// `None` until the article has been summarized
+    pub summary: Option<ArticleSummary>,
+    pub categories: Vec<String>,
+    pub occurrences: Vec<ArticleOccurrence>,
+}
+
+/// Article `article_id` as seen by `user_id`: only its occurrences in feeds the
+/// user subscribes to are listed, and `None` is returned when there are none
+/// (or no such article).
+pub async fn get_article_detail(pool: &SqlitePool, user_id: i64, article_id: i64) -> Result<Option<ArticleDetail>> {
+    let occurrences: Vec<ArticleOccurrence> = sqlx::query(
+        r#"
+        SELECT ao.feed_id, f.title AS feed_title, MIN(ao.discovered_at) AS discovered_at
+        FROM article_occurrences ao
+        JOIN subscriptions s ON s.feed_id = ao.feed_id AND s.user_id = ?
+        JOIN feeds f ON f.id = ao.feed_id
+        WHERE ao.article_id = ?
+        GROUP BY ao.feed_id
+        ORDER BY MIN(ao.discovered_at), ao.feed_id
+        "#,
+    )
+    .bind(user_id)
+    .bind(article_id)
+    .fetch_all(pool)
+    .await
+    .context("failed to load article occurrences")?
+    .into_iter()
+    .map(|r| ArticleOccurrence {
+        feed_id: r.get("feed_id"),
+        feed_title: r.get("feed_title"),
+        discovered_at: r.get("discovered_at"),
+    })
+    .collect();
+    if occurrences.is_empty() {
+        return Ok(None);
+    }
+
+    let row = sqlx::query(
+        r#"
+        SELECT a.id, a.canonical_url, a.title, a.content, a.full_content, a.published_at,
+               s.article_id AS summary_article_id, s.headline, s.bullets_json, s.details,
+               s.model, s.categories
+        FROM articles a
+        LEFT JOIN article_summaries s ON s.article_id = a.id
+        WHERE a.id = ?
+        "#,
+    )
+    .bind(article_id)
+    .fetch_optional(pool)
+    .await
+    .context("failed to load article")?;
+    let Some(r) = row else {
+        return Ok(None);
+    };
+
+    let json_list = |column: &str| -> Vec<String> {
+        r.get::<Option<String>, _>(column)
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_default()
+    };
+    let summary = r.get::<Option<i64>, _>("summary_article_id").map(|_| ArticleSummary {
+        headline: r.get("headline"),
+        bullets: json_list("bullets_json"),
+        details: r.get("details"),
+        model: r.get("model"),
+    });
+    Ok(Some(ArticleDetail {
+        id: r.get("id"),
+        canonical_url: r.get("canonical_url"),
+        title: r.get("title"),
+        content: r.get("content"),
+        full_content: r.get("full_content"),
+        published_at: r.get("published_at"),
+        summary,
+        categories: json_list("categories"),
+        occurrences,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::RegexReplacement;
+
+    fn sample_entry() -> Entry {
+        let xml = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>t</title>
+<item>
+  <title>[Sponsored] Hello world</title>
+  <link>https://example.com/a</link>
+  <description>Real body here</description>
+</item>
+</channel></rss>"#;
+        feed_rs::parser::parse(xml.as_bytes()).unwrap().entries.remove(0)
+    }
+
+    #[test]
+    fn transform_applies_field_selection_and_regex() {
+        let cfg = FeedTransformConfig {
+            feed_url: "https://example.com/rss".into(),
+            title_field: None,
+            content_field: Some("summary".into()),
+            replacements: vec![
+                RegexReplacement {
+                    field: "title".into(),
+                    pattern: r"^\[Sponsored\]\s*".into(),
+                    replacement: String::new(),
+                },
+                RegexReplacement {
+                    field: "content".into(),
+                    pattern: "Real".into(),
+                    replacement: "Actual".into(),
+                },
+            ],
+        };
+        let t = EntryTransform::from_config(&cfg).unwrap();
+        let entry = sample_entry();
+        assert_eq!(t.title(&entry), "Hello world");
+        assert_eq!(t.content(&entry), "Actual body here");
+    }
+
+    fn parse_entry(feed: &str) -> Entry {
+        feed_rs::parser::parse(feed.as_bytes()).unwrap().entries.remove(0)
+    }
+
+    #[test]
+    fn atom_content_prefers_richest_field_and_decodes_entities() {
+        // type="html" content escaped twice, plus a shorter summary
+        let entry = parse_entry(
+            r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom"><title>t</title><id>urn:f</id><updated>2026-01-01T00:00:00Z</updated>
+<entry><title>A</title><id>urn:a</id><updated>2026-01-01T00:00:00Z</updated><link href="https://example.com/a"/>
+  <summary>Short teaser</summary>
+  <content type="html">&amp;lt;p&amp;gt;Full story about Q&amp;amp;amp;A sessions&amp;lt;/p&amp;gt;</content>
+</entry></feed>"#,
+        );
+        assert_eq!(default_content(&entry), "<p>Full story about Q&amp;A sessions</p>");
+
+        // Plain-text content with a leftover entity; the summary is richer
+        let entry = parse_entry(
+            r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom"><title>t</title><id>urn:f</id><updated>2026-01-01T00:00:00Z</updated>
+<entry><title>B</title><id>urn:b</id><updated>2026-01-01T00:00:00Z</updated><link href="https://example.com/b"/>
+  <content type="text">Tom &amp;amp; Jerry</content>
+  <summary type="html">&lt;p&gt;Tom &amp;amp; Jerry, the &lt;b&gt;whole&lt;/b&gt; episode guide&lt;/p&gt;</summary>
+</entry></feed>"#,
+        );
+        assert_eq!(default_content(&entry), "<p>Tom &amp; Jerry, the <b>whole</b> episode guide</p>");
+    }
+
+    #[test]
+    fn decoded_markup_is_sanitized() {
+        let entry = parse_entry(
+            r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom"><title>t</title><id>urn:f</id><updated>2026-01-01T00:00:00Z</updated>
+<entry><title>X</title><id>urn:x</id><updated>2026-01-01T00:00:00Z</updated><link href="https://example.com/x"/>
+  <content type="html">&amp;lt;p onclick="steal()"&amp;gt;Hello&amp;lt;script&amp;gt;alert(1)&amp;lt;/script&amp;gt; &amp;lt;a href="javascript:alert(1)"&amp;gt;there&amp;lt;/a&amp;gt; &amp;lt;a href="/more" target="_blank"&amp;gt;more&amp;lt;/a&amp;gt; &amp;lt;img src="https://example.com/i.png" onerror="steal()"&amp;gt;&amp;amp;lt;b&amp;amp;gt;&amp;lt;/p&amp;gt;</content>
+</entry></feed>"#,
+        );
+        assert_eq!(
+            default_content(&entry),
+            r#"<p>Hello <a>there</a> <a href="/more">more</a> <img src="https://example.com/i.png">&lt;b&gt;</p>"#
+        );
+    }
+
+    #[test]
+    fn media_description_is_used_when_richer() {
+        let entry = parse_entry(
+            r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:media="http://search.yahoo.com/mrss/"><channel><title>t</title>
+<item>
+  <title>Video</title><link>https://example.com/v</link>
+  <description>Watch</description>
+  <media:group><media:description>A long description of the video&#8217;s content</media:description></media:group>
+</item></channel></rss>"#,
+        );
+        assert_eq!(default_content(&entry), "A long description of the video\u{2019}s content");
+    }
+
+    #[test]
+    fn json_feed_uses_content_html_or_content_text() {
+        let entry = parse_entry(
+            r#"{"version": "https://jsonfeed.org/version/1.1", "title": "t", "items": [
+                {"id": "1", "url": "https://example.com/1", "content_html": "<p>Rich <em>HTML</em> body</p>",
+                 "content_text": "Rich HTML body"}]}"#,
+        );
+        assert_eq!(default_content(&entry), "<p>Rich <em>HTML</em> body</p>");
+
+        let entry = parse_entry(
+            r#"{"version": "https://jsonfeed.org/version/1.1", "title": "t", "items": [
+                {"id": "2", "url": "https://example.com/2", "content_text": "Caf&eacute; opening hours",
+                 "summary": "Caf\u00e9"}]}"#,
+        );
+        assert_eq!(default_content(&entry), "Caf\u{e9} opening hours");
+
+        let entry = parse_entry(
+            r#"{"version": "https://jsonfeed.org/version/1.1", "title": "t", "items": [
+                {"id": "3", "url": "https://example.com/3", "content_text": "   "}]}"#,
+        );
+        assert_eq!(default_content(&entry), "");
+    }
+
+    #[test]
+    fn invalid_rule_is_rejected() {
+        let cfg = FeedTransformConfig {
+            feed_url: "https://example.com/rss".into(),
+            title_field: None,
+            content_field: None,
+            replacements: vec![RegexReplacement {
+                field: "author".into(),
+                pattern: "x".into(),
+                replacement: String::new(),
+            }],
+        };
+        assert!(EntryTransform::from_config(&cfg).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::llm::LlmProvider;
+
+/// Maximum cosine distance for an article to be considered on-topic.
+const MAX_DISTANCE: f64 = 0.6;
+/// Number of headlines kept per day bucket.
+const HEADLINES_PER_BUCKET: usize = 3;
+
+/// A single matching article before bucketing.
+#[derive(Debug, Clone)]
+pub struct TimelineHit {
+    pub article_id: i64,
+    pub headline: String,
+    pub first_seen_at: String,
+    pub distance: f64,
+}
+
+/// One day of coverage for a topic.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineBucket {
+    pub day: String,
+    pub count: usize,
+    pub headlines: Vec<String>,
+    pub article_ids: Vec<i64>,
+}
+
+/// Embed `query` and return matching articles from the user's subscriptions,
+/// grouped by the day they were first seen (oldest first).
+pub async fn build_timeline(
+    pool: &SqlitePool,
+    provider: Arc<dyn LlmProvider>,
+    user_id: i64,
+    query: &str,
+    limit: i64,
+) -> Result<Vec<TimelineBucket>> {
+    let embedding = provider.embed(query).await.context("failed to embed timeline query")?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT a.id, COALESCE(s.headline, a.title) as headline, a.first_seen_at,
+               vec_distance_cosine(v.embedding, ?) as distance
+        FROM vec_articles v
+        JOIN articles a ON a.id = v.article_id
+        LEFT JOIN article_summaries s ON s.article_id = a.id
+        WHERE EXISTS (
+            SELECT 1 FROM article_occurrences ao
+            JOIN subscriptions sub ON sub.feed_id = ao.feed_id
+            WHERE ao.article_id = a.id AND sub.user_id = ?
+        )
+        AND distance <= ?
+        ORDER BY distance ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(f32_vec_to_bytes(&embedding))
+    .bind(user_id)
+    .bind(MAX_DISTANCE)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("failed to query timeline articles")?;
+
+    let hits = rows
+        .into_iter()
+        .map(|r| TimelineHit {
+            article_id: r.get("id"),
+            headline: r.get::<Option<String>, _>("headline").unwrap_or_default(),
+            first_seen_at: r.get::<Option<String>, _>("first_seen_at").unwrap_or_default(),
+            distance: r.get("distance"),
+        })
+        .collect();
+
+    Ok(bucket_by_day(hits))
+}
+
+/// Group hits by calendar day (YYYY-MM-DD prefix of `first_seen_at`).
+/// Within a bucket, headlines are ordered by closeness to the query.
+pub fn bucket_by_day(mut hits: Vec<TimelineHit>) -> Vec<TimelineBucket> {
+    hits.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut days: BTreeMap<String, Vec<TimelineHit>> = BTreeMap::new();
+    for hit in hits {
+        let day: String = hit.first_seen_at.chars().take(10).collect();
+        days.entry(day).or_default().push(hit);
+    }
+
+    days.into_iter()
+        .map(|(day, hits)| TimelineBucket {
+            day,
+            count: hits.len(),
+            headlines: hits.iter().take(HEADLINES_PER_BUCKET).map(|h| h.headline.clone()).collect(),
+            article_ids: hits.iter().map(|h| h.article_id).collect(),
+        })
+        .collect()
+}
+
+/// Helper to convert Vec<f32> to bytes for BLOB storage
+fn f32_vec_to_bytes(v: &[f32]) -> Vec<u8> {
+    v.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(id: i64, day: &str, distance: f64) -> TimelineHit {
+        TimelineHit {
+            article_id: id,
+            headline: format!("h{}", id),
+            first_seen_at: format!("{}T08:00:00+00:00", day),
+            distance,
+        }
+    }
+
+    #[test]
+    fn buckets_are_chronological_and_ranked() {
+        let buckets = bucket_by_day(vec![
+            hit(1, "2024-03-02", 0.4),
+            hit(2, "2024-03-01", 0.2),
+            hit(3, "2024-03-02", 0.1),
+        ]);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].day, "2024-03-01");
+        assert_eq!(buckets[1].count, 2);
+        assert_eq!(buckets[1].headlines, vec!["h3", "h1"]);
+    }
+}
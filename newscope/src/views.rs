@@ -0,0 +1,170 @@
+//! Per-user read state.
+//!
+//! A row in `user_article_views` marks an article as read by a user: press
+//! reviews write one for every card they stream, and REST clients can add or
+//! remove them to sync read state. The row also holds the user's 1-5 star
+//! rating, which can be given over the websocket or REST.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+
+/// One article in a user's reading history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub article_id: i64,
+    pub title: Option<String>,
+    pub headline: Option<String>,
+    pub url: String,
+    pub viewed_at: Option<String>,
+    /// Chat session that showed the article, if any
+    pub session_id: Option<i64>,
+    /// 1-5 stars, when the user rated the article
+    pub rating: Option<i64>,
+}
+
+/// Valid star ratings.
+pub const RATING_RANGE: std::ops::RangeInclusive<i64> = 1..=5;
+
+/// The user's rating of one article; `rating` is `None` until rated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticleRating {
+    pub article_id: i64,
+    pub rating: Option<i64>,
+}
+
+/// Strength of a rating as a personalization signal:
+/// 1 star = 0.1, 3 stars = 1.0, 5 stars = 3.0.
+pub fn rating_weight(rating: i64) -> f32 {
+    (rating as f32 - 2.0).max(0.1)
+}
+
+async fn article_exists(pool: &SqlitePool, article_id: i64) -> Result<bool> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM articles WHERE id = ?")
+        .bind(article_id)
+        .fetch_one(pool)
+        .await
+        .context("failed to look up article")?;
+    Ok(count > 0)
+}
+
+/// Mark an article as read by `user_id`. Marking it again keeps the first view
+/// (and its rating). Returns `false` when there is no such article.
+pub async fn mark_viewed(pool: &SqlitePool, user_id: i64, article_id: i64) -> Result<bool> {
+    if !article_exists(pool, article_id).await? {
+        return Ok(false);
+    }
+    sqlx::query("INSERT OR IGNORE INTO user_article_views (user_id, article_id) VALUES (?, ?)")
+        .bind(user_id)
+        .bind(article_id)
+        .execute(pool)
+        .await
+        .context("failed to record article view")?;
+    Ok(true)
+}
+
+/// Mark articles shown in a press review of `session_id` as read; those
+/// already read keep their first view.
+pub async fn mark_shown(pool: &SqlitePool, user_id: i64, session_id: i64, article_ids: &[i64]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    for article_id in article_ids {
+        sqlx::query("INSERT OR IGNORE INTO user_article_views (user_id, article_id, session_id) VALUES (?, ?, ?)")
+            .bind(user_id)
+            .bind(article_id)
+            .bind(session_id)
+            .execute(&mut tx)
+            .await
+            .context("failed to record article view")?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Mark an article as unread again, dropping its rating with the view so it can
+/// come back in a press review. Returns whether it had been read.
+pub async fn mark_unread(pool: &SqlitePool, user_id: i64, article_id: i64) -> Result<bool> {
+    let res = sqlx::query("DELETE FROM user_article_views WHERE user_id = ? AND article_id = ?")
+        .bind(user_id)
+        .bind(article_id)
+        .execute(pool)
+        .await
+        .context("failed to delete article view")?;
+    Ok(res.rows_affected() > 0)
+}
+
+/// The articles `user_id` has read, most recent first.
+pub async fn reading_history(pool: &SqlitePool, user_id: i64, limit: i64) -> Result<Vec<HistoryEntry>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT v.article_id, a.title, s.headline, a.canonical_url, v.viewed_at, v.session_id, v.rating
+        FROM user_article_views v
+        JOIN articles a ON a.id = v.article_id
+        LEFT JOIN article_summaries s ON s.article_id = v.article_id
+        WHERE v.user_id = ?
+        ORDER BY v.viewed_at DESC, v.id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("failed to load reading history")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| HistoryEntry {
+            article_id: r.get("article_id"),
+            title: r.get("title"),
+            headline: r.get("headline"),
+            url: r.get("canonical_url"),
+            viewed_at: r.get("viewed_at"),
+            session_id: r.get("session_id"),
+            rating: r.get("rating"),
+        })
+        .collect())
+}
+
+/// Rate an article for `user_id`, marking it read if it was not. `rating` must
+/// be in [`RATING_RANGE`]. Returns `false` when there is no such article.
+pub async fn rate_article(pool: &SqlitePool, user_id: i64, article_id: i64, rating: i64) -> Result<bool> {
+    if !article_exists(pool, article_id).await? {
+        return Ok(false);
+    }
+    sqlx::query(
+        "INSERT INTO user_article_views (user_id, article_id, rating) VALUES (?, ?, ?)
+         ON CONFLICT(user_id, article_id) DO UPDATE SET rating = excluded.rating",
+    )
+    .bind(user_id)
+    .bind(article_id)
+    .bind(rating)
+    .execute(pool)
+    .await
+    .context("failed to store article rating")?;
+    Ok(true)
+}
+
+/// `user_id`'s rating of an article, if any.
+pub async fn get_rating(pool: &SqlitePool, user_id: i64, article_id: i64) -> Result<Option<i64>> {
+    let rating = sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT rating FROM user_article_views WHERE user_id = ? AND article_id = ?",
+    )
+    .bind(user_id)
+    .bind(article_id)
+    .fetch_optional(pool)
+    .await
+    .context("failed to load article rating")?;
+    Ok(rating.flatten())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rating_weight() {
+        assert_eq!(rating_weight(1), 0.1);
+        assert_eq!(rating_weight(3), 1.0);
+        assert_eq!(rating_weight(5), 3.0);
+    }
+}
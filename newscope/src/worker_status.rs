@@ -0,0 +1,38 @@
+//! State of the background worker, shared with the HTTP server.
+//!
+//! `run_worker` updates a [`SharedWorkerStatus`] as it goes; the server reads it
+//! to tell whether the worker is alive (see [`crate::health`]) and serves it at
+//! `GET /api/v1/worker/status`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkerStatus {
+    /// When the worker last started a loop (at least once a minute)
+    pub last_loop_at: Option<DateTime<Utc>>,
+    /// When a feed was last fetched successfully (including "not modified")
+    pub last_successful_poll_at: Option<DateTime<Utc>>,
+    /// Feeds found due for a poll by the last loop
+    pub feeds_due: usize,
+    /// Articles being summarized and personalized
+    pub processing_article_ids: BTreeSet<i64>,
+}
+
+pub type SharedWorkerStatus = Arc<Mutex<WorkerStatus>>;
+
+pub fn shared() -> SharedWorkerStatus {
+    Arc::new(Mutex::new(WorkerStatus::default()))
+}
+
+/// Update the status, even if a panic poisoned the lock.
+pub fn update(status: &SharedWorkerStatus, f: impl FnOnce(&mut WorkerStatus)) {
+    f(&mut status.lock().unwrap_or_else(|e| e.into_inner()));
+}
+
+/// Copy of the current status.
+pub fn snapshot(status: &SharedWorkerStatus) -> WorkerStatus {
+    status.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
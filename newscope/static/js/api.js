@@ -72,14 +72,23 @@ const API = {
   },
 
   // Feeds
-  async getFeeds(userId) {
-    return this.request(`/api/v1/feeds?user_id=${userId}`);
+  async getFeeds(userId, limit = 200, offset = 0) {
+    return this.request(`/api/v1/feeds?user_id=${userId}&limit=${limit}&offset=${offset}`);
   },
 
-  async createFeed(url, title, userId) {
+  async createFeed(url, title) {
     return this.request("/api/v1/feeds", {
       method: "POST",
-      body: JSON.stringify({ url, title, user_id: userId }),
+      body: JSON.stringify({ url, title }),
+    });
+  },
+
+  // Subscribe to every feed of an OPML file (raw XML body)
+  async importOpml(file) {
+    return this.request("/api/v1/feeds/import", {
+      method: "POST",
+      headers: { "Content-Type": "text/x-opml" },
+      body: file,
     });
   },
 
@@ -94,18 +103,17 @@ const API = {
   },
 
   // Sessions
-  async createSession(userId, durationSeconds) {
+  async createSession(durationSeconds) {
     return this.request("/api/v1/sessions", {
       method: "POST",
       body: JSON.stringify({
-        user_id: userId,
         duration_seconds: durationSeconds,
       }),
     });
   },
 
-  async getSessions(userId) {
-    return this.request(`/api/v1/sessions?user_id=${userId}`);
+  async getSessions() {
+    return this.request("/api/v1/sessions");
   },
 
   async getSession(sessionId) {
@@ -209,8 +209,8 @@ const App = {
 
   async loadFeeds() {
     try {
-      const feeds = await API.getFeeds(this.currentUser.id);
-      this.renderFeeds(feeds);
+      const page = await API.getFeeds(this.currentUser.id);
+      this.renderFeeds(page.feeds);
     } catch (error) {
       console.error("Failed to load feeds:", error);
     }
@@ -272,7 +272,7 @@ const App = {
 
   async loadSessions() {
     try {
-      const sessions = await API.getSessions(this.currentUser.id);
+      const sessions = await API.getSessions();
       this.renderSessions(sessions);
     } catch (error) {
       console.error("Failed to load sessions:", error);
@@ -391,7 +391,7 @@ const App = {
     const title = document.getElementById("feed-title").value;
 
     try {
-      await API.createFeed(url, title, this.currentUser.id);
+      await API.createFeed(url, title);
       this.closeModal("modal-add-feed");
       document.getElementById("form-add-feed").reset();
       this.loadFeeds();
@@ -408,10 +408,7 @@ const App = {
     const durationSeconds = duration * 60;
 
     try {
-      const data = await API.createSession(
-        this.currentUser.id,
-        durationSeconds,
-      );
+      const data = await API.createSession(durationSeconds);
       this.currentSession = data;
       this.closeModal("modal-new-session");
       this.openChat(data.id, durationSeconds);
@@ -455,6 +452,9 @@ const App = {
       // Set handlers before connecting to avoid missing early messages
       this.chatManager.onMessage = (data) => this.handleChatMessage(data);
       this.chatManager.onStatus = (status) => this.updateChatStatus(status);
+      this.chatManager.onReset = () => {
+        document.getElementById("chat-messages").innerHTML = "";
+      };
       // Connect WebSocket
       this.chatManager.connect(sessionId);
     } catch (error) {
@@ -548,6 +548,31 @@ const App = {
         feedContainer.appendChild(updatedCard);
       }
       // Removed auto-scroll
+    } else if (data.type === "token") {
+      // Streamed chat reply: grow a plain-text bubble until message_end
+      this.hideProgress();
+      this.hideThinking();
+      if (!this.streamingMessage) {
+        const container = document.getElementById("chat-messages");
+        const messageDiv = document.createElement("div");
+        messageDiv.className = "message assistant streaming";
+        messageDiv.innerHTML = `
+            <div class="avatar">A</div>
+            <div class="message-content"></div>
+        `;
+        container.appendChild(messageDiv);
+        this.streamingMessage = messageDiv;
+      }
+      this.streamingMessage.querySelector(".message-content").textContent +=
+        data.content;
+    } else if (data.type === "message_end") {
+      // Replace the streamed text with the rendered (Markdown) reply
+      if (this.streamingMessage) {
+        this.streamingMessage.remove();
+        this.streamingMessage = null;
+      }
+      this.hideThinking();
+      this.addMessage("assistant", data.content || "");
     } else if (data.type === "message" && data.content) {
       // Hide progress and show new message from server
       this.hideProgress();
@@ -565,6 +590,15 @@ const App = {
           icon: "/static/favicon.ico", // Assuming you have one, or remove if not
         });
       }
+    } else if (data.type === "notification") {
+      // Structured notification event (already localized server-side)
+      if (
+        document.hidden &&
+        "Notification" in window &&
+        Notification.permission === "granted"
+      ) {
+        new Notification(data.title || "Newscope", { body: data.body });
+      }
     } else if (data.type === "history") {
       // Chat history replay
       this.addMessage(
@@ -576,7 +610,11 @@ const App = {
       data.author === "assistant" &&
       data.message
     ) {
-      // Legacy format support
+      // Legacy format support (also used for chat errors, which may cut a stream short)
+      if (this.streamingMessage) {
+        this.streamingMessage.remove();
+        this.streamingMessage = null;
+      }
       this.hideProgress();
       this.addMessage("assistant", data.message);
     }
@@ -804,22 +842,7 @@ const App = {
       `Uploading ${file.name}...`;
 
     try {
-      const formData = new FormData();
-      formData.append("file", file);
-
-      const response = await fetch(
-        `/api/v1/feeds/import/opml?user_id=${this.currentUser.id}`,
-        {
-          method: "POST",
-          body: file, // Send raw file data
-        },
-      );
-
-      if (!response.ok) {
-        throw new Error(`Import failed: ${response.statusText}`);
-      }
-
-      const result = await response.json();
+      const result = await API.importOpml(file);
 
       // Hide progress, show results
       document.getElementById("opds-progress").classList.add("hidden");
@@ -828,9 +851,9 @@ const App = {
       // Update stats
       document.getElementById("opds-stats-added").textContent = result.added;
       document.getElementById("opds-stats-duplicates").textContent =
-        result.duplicates;
+        result.skipped;
       document.getElementById("opds-stats-errors").textContent =
-        result.errors.length;
+        result.failed;
 
       // Show errors if any
       const errorList = document.getElementById("opds-error-list");
@@ -934,6 +957,11 @@ const App = {
       // Prevent toggle if clicking directly on a link (or its children)
       if (e.target.closest('a')) return;
       card.classList.toggle("collapsed");
+      // Open/close timing lets the server calibrate reading speed (if enabled)
+      if (this.chatManager && article && article.id) {
+        const type = card.classList.contains("collapsed") ? "article_close" : "article_open";
+        this.chatManager.sendEvent({ type, article_id: article.id });
+      }
       const chevron = header.querySelector(".toggle-chevron");
       if (chevron) {
         chevron.style.transform = card.classList.contains("collapsed") ? "rotate(0deg)" : "rotate(180deg)";
@@ -991,6 +1019,16 @@ const App = {
     }
     body.appendChild(content);
 
+    // Same story from other feeds, collapsed into this card
+    if (Array.isArray(article.also_covered_by) && article.also_covered_by.length > 0) {
+      const coveredBy = document.createElement("div");
+      coveredBy.className = "card-covered-by";
+      // Label localized by the server (i18n::Msg::AlsoCoveredBy)
+      const label = article.also_covered_by_label || "Also covered by:";
+      coveredBy.textContent = `${label} ${article.also_covered_by.join(", ")}`;
+      body.appendChild(coveredBy);
+    }
+
     // Footer
     const footer = document.createElement("div");
     footer.className = "card-footer";
@@ -10,6 +10,12 @@ class ChatManager {
         this.sessionStartTime = null;
         this.sessionDuration = 0; // in seconds
         this.isLoading = false;
+        // Last frame seq seen, to resume after a dropped connection
+        this.lastSeq = null;
+        this.reconnectTimer = null;
+        this.reconnectDelay = 1000;
+        // Called when a reconnect could not resume: the server replays the session from scratch
+        this.onReset = null;
     }
 
     setSessionDuration(durationSeconds) {
@@ -78,24 +84,46 @@ class ChatManager {
     }
 
     connect(sessionId) {
+        if (this.sessionId !== sessionId) {
+            this.lastSeq = null;
+        }
         this.sessionId = sessionId;
         const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
-        const url = `${protocol}//${window.location.host}/ws/chat?session_id=${sessionId}`;
+        // WebSocket handshakes can't carry an Authorization header
+        const token = encodeURIComponent(localStorage.getItem('mnl_token') || '');
+        let url = `${protocol}//${window.location.host}/ws/chat?session_id=${sessionId}&token=${token}`;
+        const reconnecting = this.lastSeq !== null;
+        if (reconnecting) {
+            url += `&since_seq=${this.lastSeq}`;
+        }
 
-        this.ws = new WebSocket(url);
+        const ws = new WebSocket(url);
+        this.ws = ws;
 
-        this.ws.onopen = () => {
+        ws.onopen = () => {
             console.log('WebSocket connected');
-            this.showLoading();
-            this.startTimer();
+            this.reconnectDelay = 1000;
+            if (!reconnecting) {
+                this.showLoading();
+                this.startTimer();
+            }
             if (this.onStatus) this.onStatus('connected');
         };
 
-        this.ws.onmessage = (event) => {
+        ws.onmessage = (event) => {
             try {
                 const data = JSON.parse(event.data);
                 console.log('Message received:', data);
 
+                if (data.type === 'sync') {
+                    // Could not resume: drop what we show, the session is replayed
+                    if (reconnecting && !data.resumed && this.onReset) this.onReset();
+                    return;
+                }
+                if (typeof data.seq === 'number') {
+                    this.lastSeq = Math.max(this.lastSeq || 0, data.seq);
+                }
+
                 // Hide loading on first message (press review)
                 if (this.isLoading) {
                     this.hideLoading();
@@ -107,16 +135,20 @@ class ChatManager {
             }
         };
 
-        this.ws.onerror = (error) => {
+        ws.onerror = (error) => {
             console.error('WebSocket error:', error);
             this.hideLoading();
             if (this.onStatus) this.onStatus('error');
         };
 
-        this.ws.onclose = () => {
+        ws.onclose = () => {
             console.log('WebSocket disconnected');
-            this.stopTimer();
             if (this.onStatus) this.onStatus('disconnected');
+            // Dropped (not closed by us): reconnect and resume from the last frame
+            if (this.ws === ws) {
+                this.reconnectTimer = setTimeout(() => this.connect(sessionId), this.reconnectDelay);
+                this.reconnectDelay = Math.min(this.reconnectDelay * 2, 30000);
+            }
         };
     }
 
@@ -128,11 +160,21 @@ class ChatManager {
         }
     }
 
+    // Send a structured event (e.g. article_open/article_close) as-is, not as a chat message
+    sendEvent(event) {
+        if (this.ws && this.ws.readyState === WebSocket.OPEN) {
+            this.ws.send(JSON.stringify(event));
+        }
+    }
+
     disconnect() {
+        clearTimeout(this.reconnectTimer);
         if (this.ws) {
-            this.ws.close();
+            const ws = this.ws;
             this.ws = null;
+            ws.close();
         }
+        this.lastSeq = null;
         this.stopTimer();
     }
 }
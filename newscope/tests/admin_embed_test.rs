@@ -0,0 +1,174 @@
+use newscope::llm::{LlmProvider, LlmRequest, LlmResponse, Summary};
+use newscope::processing::EMBEDDING_DIM;
+use newscope::server::{AppState, EmbedReport, ReembedReport};
+use rocket::http::Status;
+use std::sync::Arc;
+
+mod support;
+
+/// Embeds every text as a fixed vector of the given size.
+struct ConstantEmbedder(usize);
+
+#[async_trait::async_trait]
+impl LlmProvider for ConstantEmbedder {
+    async fn generate(&self, _request: LlmRequest) -> anyhow::Result<LlmResponse> {
+        anyhow::bail!("not used")
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        Ok(vec![0.5; self.0])
+    }
+}
+
+#[tokio::test]
+async fn test_admin_embed_backfills_missing_vectors() {
+    let (pool, _db) = support::migrated_db().await;
+    for i in 0..130 {
+        sqlx::query("INSERT INTO articles (canonical_url, title, content) VALUES (?, 'Title', 'Body')")
+            .bind(format!("https://example.com/{}", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let state = |embedding_llm: Option<Arc<dyn LlmProvider>>| AppState {
+        embedding_llm,
+        ..support::app_state(pool.clone())
+    };
+
+    let client = support::api_client(state(None)).await;
+    let resp = client.post("/api/v1/admin/embed").header(support::admin_token()).dispatch().await;
+    assert_eq!(resp.status(), Status::ServiceUnavailable);
+
+    let client = support::api_client(state(Some(Arc::new(ConstantEmbedder(EMBEDDING_DIM))))).await;
+
+    let resp = client.post("/api/v1/admin/embed?limit=10").header(support::admin_token()).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let report: EmbedReport = resp.into_json().await.unwrap();
+    assert_eq!((report.embedded, report.remaining), (10, 120));
+
+    // Without a limit: everything left, across several passes
+    let resp = client.post("/api/v1/admin/embed").header(support::admin_token()).dispatch().await;
+    let report: EmbedReport = resp.into_json().await.unwrap();
+    assert_eq!((report.embedded, report.remaining), (120, 0));
+}
+
+#[tokio::test]
+async fn test_reembed_rebuilds_vectors_with_configured_dimension() {
+    // Vectors from a previous 2-dimensional model
+    let (pool, _db) = support::migrated_db_with_dim(2).await;
+    for i in 1..=3 {
+        sqlx::query("INSERT INTO articles (id, canonical_url, title, content) VALUES (?, ?, 'Title', 'Body')")
+            .bind(i)
+            .bind(format!("https://example.com/{}", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    for id in [1, 2] {
+        sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (?, '[0.5, 0.5]')")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice')",
+        "INSERT INTO user_profiles (user_id, interests) VALUES (1, '[\"science\"]')",
+        "INSERT INTO vec_users (user_id, embedding) VALUES (1, '[0.5, 0.5]')",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    assert_eq!(newscope::maintenance::vector_table_dim(&pool, "vec_articles").await.unwrap(), Some(2));
+
+    let client = |embedder: ConstantEmbedder| {
+        support::api_client(AppState {
+            embedding_llm: Some(Arc::new(embedder)),
+            ..support::app_state(pool.clone())
+        })
+    };
+
+    // Still the old model: its vectors don't match the default embedding_dim
+    let old = client(ConstantEmbedder(2)).await;
+    let resp = old.post("/api/v1/admin/embed").header(support::admin_token()).dispatch().await;
+    assert_eq!(resp.status(), Status::InternalServerError);
+
+    let new = client(ConstantEmbedder(EMBEDDING_DIM)).await;
+    let reembed = || new.post("/api/v1/admin/reembed").header(support::admin_token()).dispatch();
+    let resp = reembed().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let report: ReembedReport = resp.into_json().await.unwrap();
+    assert_eq!(
+        (report.cleared, report.dimension, report.embedded, report.remaining),
+        (2, EMBEDDING_DIM, 3, 0)
+    );
+    // alice's old vector couldn't be compared with the new ones: rebuilt from her interests
+    assert_eq!((report.users_cleared, report.users_embedded), (1, 1));
+    for table in ["vec_articles", "vec_users"] {
+        assert_eq!(
+            newscope::maintenance::vector_table_dim(&pool, table).await.unwrap(),
+            Some(EMBEDDING_DIM),
+            "{}",
+            table
+        );
+    }
+
+    // Same dimension again: user vectors are left alone
+    let report: ReembedReport = reembed().await.into_json().await.unwrap();
+    assert_eq!((report.cleared, report.embedded), (3, 3));
+    assert_eq!((report.users_cleared, report.users_embedded), (0, 0));
+    let user_vectors: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vec_users").fetch_one(&pool).await.unwrap();
+    assert_eq!(user_vectors, 1);
+}
+
+/// Embeds like [`ConstantEmbedder`], but an embedding job stores a vector for
+/// the "Raced" article while its embed call is in flight.
+struct RacedEmbedder(sqlx::SqlitePool);
+
+#[async_trait::async_trait]
+impl LlmProvider for RacedEmbedder {
+    async fn generate(&self, _request: LlmRequest) -> anyhow::Result<LlmResponse> {
+        anyhow::bail!("not used")
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        if text.contains("Raced") {
+            sqlx::query("INSERT INTO vec_articles (article_id, embedding) SELECT id, '[1, 0]' FROM articles WHERE title = 'Raced'")
+                .execute(&self.0)
+                .await?;
+        }
+        Ok(vec![0.5; 2])
+    }
+}
+
+#[tokio::test]
+async fn test_one_failed_row_does_not_drop_the_batch() {
+    let (pool, _db) = support::migrated_db_with_dim(2).await;
+    for (i, title) in ["First", "Raced", "Last"].iter().enumerate() {
+        sqlx::query("INSERT INTO articles (canonical_url, title, content) VALUES (?, ?, 'Body')")
+            .bind(format!("https://example.com/{}", i))
+            .bind(title)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let text = newscope::processing::EmbeddingText::default();
+    let provider = Arc::new(RacedEmbedder(pool.clone()));
+    let embedded = newscope::processing::process_missing_embeddings(&pool, provider, "embedder", 10, 1, 2, &text)
+        .await
+        .unwrap();
+    assert_eq!(embedded, 2);
+
+    let recorded: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM article_vector_models WHERE model = 'embedder'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(recorded, 2);
+    let vectors: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vec_articles").fetch_one(&pool).await.unwrap();
+    assert_eq!(vectors, 3);
+}
@@ -0,0 +1,42 @@
+use newscope::maintenance::InstanceStats;
+use rocket::http::Status;
+
+mod support;
+
+#[tokio::test]
+async fn test_admin_stats_counts_instance_rows() {
+    let (pool, _db) = support::migrated_db_with_dim(2).await;
+
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice'), ('bob')",
+        "INSERT INTO feeds (url, last_checked) VALUES ('https://a.example/rss', '2026-10-15T08:00:00Z'), ('https://b.example/rss', '2026-10-16T09:30:00Z'), ('https://c.example/rss', NULL)",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1), (1, 2), (2, 1)",
+        "INSERT INTO articles (canonical_url) VALUES ('https://a.example/1'), ('https://a.example/2'), ('https://b.example/1')",
+        "INSERT INTO article_summaries (article_id, headline) VALUES (1, 'One'), (3, 'Three')",
+        "INSERT INTO llm_usage_log (operation, prompt_tokens, completion_tokens) VALUES ('summarize', 100, 20), ('chat', 50, 5)",
+        "INSERT INTO llm_usage_log (operation, prompt_tokens, completion_tokens, created_at) VALUES ('summarize', 1000, 1000, '2020-01-01T00:00:00Z')",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+
+    let resp = client.get("/api/v1/admin/stats").header(support::admin_token()).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let stats: InstanceStats = resp.into_json().await.unwrap();
+    assert_eq!(
+        (stats.users, stats.feeds, stats.subscriptions, stats.articles, stats.articles_with_summaries),
+        (2, 3, 3, 3, 2)
+    );
+    assert_eq!(stats.last_worker_run.as_deref(), Some("2026-10-16T09:30:00Z"));
+    assert_eq!((stats.llm_prompt_tokens_24h, stats.llm_completion_tokens_24h), (150, 25));
+    assert_eq!((stats.embeddings, stats.pending_jobs), (Some(0), Some(0)));
+
+    sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (1, '[0.5, 0.5]')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let resp = client.get("/api/v1/admin/stats").header(support::admin_token()).dispatch().await;
+    let stats: InstanceStats = resp.into_json().await.unwrap();
+    assert_eq!(stats.embeddings, Some(1));
+}
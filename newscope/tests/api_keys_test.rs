@@ -0,0 +1,44 @@
+use newscope::auth::{create_api_key, revoke_api_key, verify_api_key, AuthUser};
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+
+mod support;
+
+#[rocket::get("/whoami")]
+fn whoami(user: AuthUser) -> String {
+    user.0.to_string()
+}
+
+#[tokio::test]
+async fn test_api_key_lifecycle_and_guard() {
+    let (pool, _db) = support::migrated_db().await;
+    sqlx::query("INSERT INTO users (username) VALUES ('cron')").execute(&pool).await.unwrap();
+
+    let (info, key) = create_api_key(&pool, 1, Some("nightly"), None).await.unwrap();
+    assert!(key.starts_with(&info.prefix));
+    assert_eq!(verify_api_key(&pool, &key).await.unwrap(), Some(1));
+    assert_eq!(verify_api_key(&pool, "nsk_wrong").await.unwrap(), None);
+
+    // Expired keys are rejected
+    let past = chrono::Utc::now() - chrono::Duration::days(1);
+    let (_, expired) = create_api_key(&pool, 1, None, Some(past)).await.unwrap();
+    assert_eq!(verify_api_key(&pool, &expired).await.unwrap(), None);
+
+    let state = support::app_state(pool.clone());
+    let rocket = rocket::build().manage(state).mount("/", rocket::routes![whoami]);
+    let client = Client::untracked(rocket).await.expect("rocket client");
+
+    let resp = client.get("/whoami").header(Header::new("X-API-Key", key.clone())).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.into_string().await.unwrap(), "1");
+
+    let resp = client.get("/whoami").dispatch().await;
+    assert_eq!(resp.status(), Status::Unauthorized);
+
+    // Revoked keys stop working
+    assert!(revoke_api_key(&pool, 1, info.id).await.unwrap());
+    assert!(!revoke_api_key(&pool, 1, info.id).await.unwrap());
+    let resp = client.get("/whoami").header(Header::new("X-API-Key", key)).dispatch().await;
+    assert_eq!(resp.status(), Status::Unauthorized);
+
+}
@@ -0,0 +1,60 @@
+use rocket::http::{Header, Status};
+use serde_json::{json, Value};
+
+mod support;
+
+#[tokio::test]
+async fn test_article_detail_endpoint() {
+    let (pool, _db) = support::migrated_db().await;
+
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice'), ('bob')",
+        "INSERT INTO feeds (url, title) VALUES ('https://a.example/rss', 'A'), ('https://b.example/rss', 'B'), ('https://c.example/rss', 'C')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1), (1, 2), (2, 3)",
+        "INSERT INTO articles (canonical_url, title, content, full_content, published_at) VALUES
+            ('https://a.example/1', 'Shared', 'Teaser', 'Full text', '2026-10-01T08:00:00Z'),
+            ('https://c.example/1', 'Elsewhere', NULL, NULL, NULL)",
+        "INSERT INTO article_occurrences (article_id, feed_id, discovered_at) VALUES
+            (1, 2, '2026-10-01T09:00:00Z'), (1, 1, '2026-10-01T10:00:00Z'), (1, 3, '2026-10-01T11:00:00Z'), (2, 3, '2026-10-01T12:00:00Z')",
+        r#"INSERT INTO article_summaries (article_id, headline, bullets_json, details, model, categories)
+            VALUES (1, 'Headline', '["one","two"]', 'More', 'test-model', '["technology"]')"#,
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let (_, alice) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+    let (_, bob) = newscope::auth::create_api_key(&pool, 2, None, None).await.unwrap();
+
+    let state = support::app_state(pool.clone());
+    let client = support::api_client(state).await;
+    let get = |uri: &'static str, key: &str| client.get(uri).header(Header::new("X-API-Key", key.to_string())).dispatch();
+
+    let res = get("/api/v1/articles/1", &alice).await;
+    assert_eq!(res.status(), Status::Ok);
+    let article: Value = res.into_json().await.unwrap();
+    assert_eq!(article["canonical_url"], "https://a.example/1");
+    assert_eq!(article["full_content"], "Full text");
+    assert_eq!(
+        article["summary"],
+        json!({"headline": "Headline", "bullets": ["one", "two"], "details": "More", "model": "test-model"})
+    );
+    assert_eq!(article["categories"], json!(["technology"]));
+    // Only the caller's feeds, earliest first
+    assert_eq!(
+        article["occurrences"],
+        json!([
+            {"feed_id": 2, "feed_title": "B", "discovered_at": "2026-10-01T09:00:00Z"},
+            {"feed_id": 1, "feed_title": "A", "discovered_at": "2026-10-01T10:00:00Z"},
+        ])
+    );
+
+    // Not yet summarized
+    let article: Value = get("/api/v1/articles/2", &bob).await.into_json().await.unwrap();
+    assert_eq!(article["summary"], Value::Null);
+    assert_eq!(article["categories"], json!([]));
+
+    // Not in any subscribed feed / unknown / anonymous
+    assert_eq!(get("/api/v1/articles/2", &alice).await.status(), Status::NotFound);
+    assert_eq!(get("/api/v1/articles/99", &alice).await.status(), Status::NotFound);
+    assert_eq!(client.get("/api/v1/articles/1").dispatch().await.status(), Status::Unauthorized);
+
+}
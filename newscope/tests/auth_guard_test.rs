@@ -0,0 +1,122 @@
+use rocket::http::{ContentType, Header, Method, Status};
+
+mod support;
+
+#[tokio::test]
+async fn test_authenticated_endpoints_require_credentials() {
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice'), ('bob')",
+        "INSERT INTO feeds (url) VALUES ('https://a.example/rss')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let (_, bob) = newscope::auth::create_api_key(&pool, 2, None, None).await.unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+
+    let json_post = |path: &'static str, body: &'static str| client.post(path).header(ContentType::JSON).body(body);
+    let feed = r#"{"url": "https://a.example/rss"}"#;
+    let fetch = r#"{"feed_id": 1}"#;
+    let session = r#"{"duration_seconds": 600}"#;
+
+    // No credentials, or a bad bearer token
+    for req in [
+        json_post("/api/v1/feeds", feed),
+        json_post("/api/v1/fetch", fetch),
+        json_post("/api/v1/sessions", session),
+        json_post("/api/v1/sessions", session).header(Header::new("Authorization", "Bearer not-a-jwt")),
+    ] {
+        assert_eq!(req.dispatch().await.status(), Status::Unauthorized);
+    }
+    assert_eq!(client.get("/api/v1/sessions").dispatch().await.status(), Status::Unauthorized);
+    // A user id in the body no longer identifies the caller
+    let resp = json_post("/api/v1/feeds", r#"{"url": "https://a.example/rss", "user_id": 1}"#).dispatch().await;
+    assert_eq!(resp.status(), Status::Unauthorized);
+
+    // Bob may not trigger fetches of feeds he does not follow, nor start a session without feeds
+    let as_bob = |path: &'static str, body: &'static str| json_post(path, body).header(Header::new("X-API-Key", bob.clone()));
+    assert_eq!(as_bob("/api/v1/fetch", fetch).dispatch().await.status(), Status::NotFound);
+    assert_eq!(as_bob("/api/v1/sessions", session).dispatch().await.status(), Status::BadRequest);
+
+    let resp = as_bob("/api/v1/feeds", feed).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let created: newscope::server::FeedCreated = resp.into_json().await.unwrap();
+    assert_eq!(created.id, 1);
+    let subscriber: i64 = sqlx::query_scalar("SELECT user_id FROM subscriptions WHERE id = ?")
+        .bind(created.subscription_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(subscriber, 2);
+
+    let resp = as_bob("/api/v1/sessions", session).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let created: newscope::sessions::Session = resp.into_json().await.unwrap();
+    assert_eq!(created.user_id, 2);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_admin_endpoints_require_the_admin_token() {
+    let (pool, _db) = support::migrated_db().await;
+    let (_, key) = support::user_with_key(&pool, "alice").await;
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let token = support::admin_token();
+
+    for (method, path) in [
+        (Method::Get, "/api/v1/admin/stats"),
+        (Method::Post, "/api/v1/admin/reindex"),
+        (Method::Post, "/api/v1/admin/embed"),
+        (Method::Post, "/api/v1/admin/reembed"),
+        (Method::Post, "/api/v1/admin/prune?days=30"),
+        (Method::Get, "/api/v1/admin/dedup"),
+        (Method::Get, "/api/v1/admin/processing"),
+        (Method::Get, "/api/v1/admin/feed-health"),
+        (Method::Get, "/api/jobs"),
+    ] {
+        // A user's credentials are not enough
+        let resp = client.req(method, path).header(support::api_key(&key)).dispatch().await;
+        assert_eq!(resp.status(), Status::Unauthorized, "{}", path);
+        let resp = client.req(method, path).header(Header::new("X-Admin-Token", "wrong")).dispatch().await;
+        assert_eq!(resp.status(), Status::Unauthorized, "{}", path);
+        let resp = client.req(method, path).header(token.clone()).dispatch().await;
+        assert_ne!(resp.status(), Status::Unauthorized, "{}", path);
+    }
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_chat_websocket_requires_the_session_owner() {
+    let (pool, _db) = support::migrated_db().await;
+    sqlx::query("INSERT INTO users (username) VALUES ('alice'), ('bob')").execute(&pool).await.unwrap();
+    let session = newscope::sessions::create_session(&pool, 1, Some(600)).await.unwrap();
+    let alice = newscope::server::issue_token(1, None).unwrap();
+    let bob = newscope::server::issue_token(2, None).unwrap();
+
+    let rocket = rocket::build()
+        .manage(support::app_state(pool.clone()))
+        .mount("/ws", rocket::routes![newscope::sessions::websocket::chat_websocket]);
+    let client = rocket::local::asynchronous::Client::untracked(rocket).await.unwrap();
+    let connect = |query: String| {
+        client
+            .get(format!("/ws/chat?session_id={}{}", session.id, query))
+            .header(Header::new("Connection", "Upgrade"))
+            .header(Header::new("Upgrade", "websocket"))
+            .header(Header::new("Sec-WebSocket-Version", "13"))
+            .header(Header::new("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+            .dispatch()
+    };
+
+    assert_eq!(connect(String::new()).await.status(), Status::Unauthorized);
+    assert_eq!(connect("&token=not-a-jwt".to_string()).await.status(), Status::Unauthorized);
+    // Someone else's session, and its frames, are not there for bob
+    assert_eq!(connect(format!("&token={}&since_seq=0", bob)).await.status(), Status::NotFound);
+    // The local client doesn't upgrade: getting past the guards is a 200
+    assert_eq!(connect(format!("&token={}", alice)).await.status(), Status::Ok);
+
+    pool.close().await;
+}
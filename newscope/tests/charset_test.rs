@@ -0,0 +1,72 @@
+use newscope::ingestion::{fetch_feed_conditional, CacheValidators, FetchOptions, FetchOutcome};
+use newscope::storage;
+use std::io::Write;
+
+mod support;
+
+/// "Café à Noël — été" as an RSS item title, the whole document in Windows-1252.
+fn windows_1252_feed(declaration: &str, link: &str) -> Vec<u8> {
+    let xml = format!(
+        "{}<rss version=\"2.0\"><channel><title>Actualités</title>\
+         <item><title>Café à Noël — été</title><link>http://example.com/{}</link></item>\
+         </channel></rss>",
+        declaration, link
+    );
+    encoding_rs::WINDOWS_1252.encode(&xml).0.into_owned()
+}
+
+async fn fetch(url: &str) -> Box<feed_rs::model::Feed> {
+    let (result, _) = fetch_feed_conditional(url, &FetchOptions::default(), &CacheValidators::default()).await;
+    match result.expect("fetch") {
+        FetchOutcome::Fetched { feed, .. } => feed,
+        FetchOutcome::NotModified => panic!("expected a full fetch"),
+    }
+}
+
+#[tokio::test]
+async fn test_legacy_charsets_and_compression_survive_into_articles() {
+    let mut server_mock = mockito::Server::new_async().await;
+    // Charset from the Content-Type header only
+    let _header = server_mock
+        .mock("GET", "/header.rss")
+        .with_header("content-type", "application/rss+xml; charset=windows-1252")
+        .with_body(windows_1252_feed("<?xml version=\"1.0\"?>", "header"))
+        .create_async()
+        .await;
+    // Charset from the XML declaration only
+    let _declared = server_mock
+        .mock("GET", "/declared.rss")
+        .with_header("content-type", "application/rss+xml")
+        .with_body(windows_1252_feed("<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>", "declared"))
+        .create_async()
+        .await;
+    // Gzip-encoded
+    let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    gzip.write_all(&windows_1252_feed("<?xml version=\"1.0\" encoding=\"windows-1252\"?>", "gzipped")).unwrap();
+    let _gzipped = server_mock
+        .mock("GET", "/gzipped.rss")
+        .with_header("content-type", "text/xml")
+        .with_header("content-encoding", "gzip")
+        .with_body(gzip.finish().unwrap())
+        .create_async()
+        .await;
+
+    let (pool, _db) = support::migrated_db().await;
+
+    for (feed_id, path) in [(1i64, "/header.rss"), (2, "/declared.rss"), (3, "/gzipped.rss")] {
+        let url = format!("{}{}", server_mock.url(), path);
+        let feed = fetch(&url).await;
+        assert_eq!(feed.title.as_ref().unwrap().content, "Actualités", "{}", path);
+
+        sqlx::query("INSERT INTO feeds (url) VALUES (?)").bind(&url).execute(&pool).await.unwrap();
+        let ids = storage::store_feed_items(&pool, feed_id, &feed.entries, None).await.unwrap();
+        assert_eq!(ids.len(), 1, "{}", path);
+        let title: String = sqlx::query_scalar("SELECT title FROM articles WHERE id = ?")
+            .bind(ids[0])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(title, "Café à Noël — été", "{}", path);
+    }
+
+}
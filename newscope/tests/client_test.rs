@@ -0,0 +1,43 @@
+//! Typed client against a mock server (`cargo test --features client`).
+#![cfg(feature = "client")]
+
+use mockito::Matcher;
+use newscope::client::Client;
+
+#[tokio::test]
+async fn test_press_review_posts_request_and_parses_review() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/v1/press-review")
+        .match_header("x-api-key", "nsk_test")
+        .match_body(Matcher::PartialJson(serde_json::json!({
+            "duration_seconds": 600,
+            "group_by": "topic"
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"session_id": 7, "summary": "Today in tech", "article_count": 3}"#)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).with_api_key("nsk_test");
+    let review = client.press_review(Some(600), Some("topic")).await.unwrap();
+
+    assert_eq!(review.session_id, 7);
+    assert_eq!(review.summary, "Today in tech");
+    assert_eq!(review.article_count, 3);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_press_review_reports_api_errors() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/api/v1/press-review")
+        .with_status(401)
+        .create_async()
+        .await;
+
+    let err = Client::new(server.url()).press_review(None, None).await.unwrap_err();
+    assert!(err.to_string().contains("401"), "{}", err);
+}
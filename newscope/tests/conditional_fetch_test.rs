@@ -0,0 +1,63 @@
+use newscope::ingestion::{fetch_feed_conditional, CacheValidators, FetchOptions, FetchOutcome};
+use newscope::storage;
+use sqlx::Row;
+
+mod support;
+
+#[tokio::test]
+async fn test_conditional_fetch_uses_stored_validators() {
+    let body = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>t</title>
+<item><title>One</title><link>http://x/one</link></item>
+</channel></rss>"#;
+    let mut server_mock = mockito::Server::new_async().await;
+    let not_modified = server_mock
+        .mock("GET", "/rss")
+        .match_header("if-none-match", "\"v1\"")
+        .match_header("if-modified-since", "Wed, 14 Oct 2026 08:00:00 GMT")
+        .with_status(304)
+        .create_async()
+        .await;
+    let full = server_mock
+        .mock("GET", "/rss")
+        .match_header("if-none-match", mockito::Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_header("last-modified", "Wed, 14 Oct 2026 08:00:00 GMT")
+        .with_body(body)
+        .create_async()
+        .await;
+    let url = format!("{}/rss", server_mock.url());
+    let opts = FetchOptions::default();
+
+    let (result, _) = fetch_feed_conditional(&url, &opts, &CacheValidators::default()).await;
+    let FetchOutcome::Fetched { feed, validators } = result.unwrap() else {
+        panic!("expected a full fetch");
+    };
+    assert_eq!(feed.entries.len(), 1);
+    assert_eq!(validators.etag.as_deref(), Some("\"v1\""));
+
+    // Validators survive a round trip through the feeds table
+    let (pool, _db) = support::migrated_db().await;
+    sqlx::query("INSERT INTO feeds (url) VALUES (?)").bind(&url).execute(&pool).await.unwrap();
+    storage::set_cache_validators(&pool, 1, &validators).await.unwrap();
+    let row = sqlx::query("SELECT etag, last_modified FROM feeds WHERE id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let stored = CacheValidators {
+        etag: row.get("etag"),
+        last_modified: row.get("last_modified"),
+    };
+    assert_eq!(stored, validators);
+
+    let (result, metrics) = fetch_feed_conditional(&url, &opts, &stored).await;
+    assert!(matches!(result.unwrap(), FetchOutcome::NotModified));
+    assert_eq!(metrics.http_status, Some(304));
+    assert_eq!(metrics.attempts, 1);
+
+    full.assert_async().await;
+    not_modified.assert_async().await;
+
+    pool.close().await;
+}
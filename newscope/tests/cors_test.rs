@@ -0,0 +1,66 @@
+use newscope::cors::Cors;
+use newscope::server;
+use rocket::http::{Header, Method, Status};
+use rocket::local::asynchronous::Client;
+
+mod support;
+
+async fn client(pool: sqlx::SqlitePool, origins: &[&str]) -> Client {
+    let state = support::app_state(pool);
+    let mut rocket = rocket::build().manage(state).mount("/", server::api_routes());
+    let origins: Vec<String> = origins.iter().map(|o| o.to_string()).collect();
+    if let Some(cors) = Cors::new(&origins) {
+        rocket = rocket.attach(cors);
+    }
+    Client::untracked(rocket).await.expect("rocket client")
+}
+
+#[tokio::test]
+async fn test_cors_for_allowed_origins() {
+    let (pool, _db) = support::migrated_db().await;
+    let client = client(pool, &["https://app.example/"]).await;
+    let origin = |o: &'static str| Header::new("Origin", o);
+
+    // Preflight
+    let res = client
+        .req(Method::Options, "/api/v1/feeds")
+        .header(origin("https://app.example"))
+        .header(Header::new("Access-Control-Request-Method", "POST"))
+        .header(Header::new("Access-Control-Request-Headers", "authorization, content-type"))
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NoContent);
+    let headers = res.headers();
+    assert_eq!(headers.get_one("Access-Control-Allow-Origin"), Some("https://app.example"));
+    assert!(headers.get_one("Access-Control-Allow-Methods").unwrap().contains("PATCH"));
+    assert_eq!(headers.get_one("Access-Control-Allow-Headers"), Some("authorization, content-type"));
+    assert_eq!(headers.get_one("Vary"), Some("Origin"));
+
+    // Actual request
+    let res = client.get("/api/v1/status").header(origin("https://app.example")).dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(res.headers().get_one("Access-Control-Allow-Origin"), Some("https://app.example"));
+    assert_eq!(res.headers().get_one("Access-Control-Expose-Headers"), Some("Retry-After"));
+
+    // Other origins, and routes outside /api/v1 and /ws, get nothing
+    let res = client
+        .req(Method::Options, "/api/v1/feeds")
+        .header(origin("https://evil.example"))
+        .header(Header::new("Access-Control-Request-Method", "POST"))
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::NotFound);
+    assert_eq!(res.headers().get_one("Access-Control-Allow-Origin"), None);
+    let res = client.get("/health").header(origin("https://app.example")).dispatch().await;
+    assert_eq!(res.headers().get_one("Access-Control-Allow-Origin"), None);
+}
+
+#[tokio::test]
+async fn test_no_cors_headers_by_default() {
+    let (pool, _db) = support::migrated_db().await;
+    assert!(Cors::new(&[" ".to_string()]).is_none());
+    let client = client(pool, &[]).await;
+    let res = client.get("/api/v1/status").header(Header::new("Origin", "https://app.example")).dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(res.headers().get_one("Access-Control-Allow-Origin"), None);
+}
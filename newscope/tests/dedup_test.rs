@@ -0,0 +1,129 @@
+use newscope::dedup::{dedup_report, record_feed_stats, UrlNormalizer};
+use newscope::storage::store_feed_items_with_stats;
+
+mod support;
+
+/// Two-item RSS feed; bodies are long enough to skip the scraping fallback.
+fn entries(first_url: &str, second_url: &str) -> Vec<feed_rs::model::Entry> {
+    let body_a = "Alpha story body. ".repeat(40);
+    let body_b = "Beta story body. ".repeat(40);
+    let xml = format!(
+        r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>t</title>
+<item><title>Alpha</title><link>{}</link><description>{}</description></item>
+<item><title>Beta</title><link>{}</link><description>{}</description></item>
+</channel></rss>"#,
+        first_url, body_a, second_url, body_b
+    );
+    feed_rs::parser::parse(xml.as_bytes()).unwrap().entries
+}
+
+#[tokio::test]
+async fn test_dedup_stats_by_url_and_content_hash() {
+    let (pool, _db) = support::migrated_db().await;
+
+    for url in ["http://a.example/rss", "http://b.example/rss", "http://c.example/rss"] {
+        sqlx::query("INSERT INTO feeds (url) VALUES (?)").bind(url).execute(&pool).await.unwrap();
+    }
+
+    // Feed 1 introduces both articles
+    let (ids, stats) = store_feed_items_with_stats(&pool, 1, &entries("http://x/alpha", "http://x/beta"), None, &UrlNormalizer::default(), false)
+        .await
+        .unwrap();
+    assert_eq!(ids.len(), 2);
+    assert_eq!(stats.new_articles, 2);
+    record_feed_stats(&pool, 1, &stats).await.unwrap();
+
+    // Re-fetching feed 1 is not a duplicate
+    let (_, stats) = store_feed_items_with_stats(&pool, 1, &entries("http://x/alpha", "http://x/beta"), None, &UrlNormalizer::default(), false)
+        .await
+        .unwrap();
+    assert_eq!(stats.already_seen, 2);
+    assert_eq!(stats.duplicates(), 0);
+
+    // Feed 2: same URL for alpha, syndicated copy of beta under another URL (merging off)
+    let (ids, stats) = store_feed_items_with_stats(&pool, 2, &entries("http://x/alpha", "http://y/beta"), None, &UrlNormalizer::default(), false)
+        .await
+        .unwrap();
+    assert_eq!(ids.len(), 1);
+    assert_eq!(stats.merged_by_url, 1);
+    assert_eq!(stats.hash_duplicates_unmerged, 1);
+    record_feed_stats(&pool, 2, &stats).await.unwrap();
+
+    // Feed 3 with merging on: the copy attaches to the original article
+    let (ids, stats) = store_feed_items_with_stats(&pool, 3, &entries("http://z/alpha", "http://z/beta"), None, &UrlNormalizer::default(), true)
+        .await
+        .unwrap();
+    assert!(ids.is_empty());
+    assert_eq!(stats.merged_by_hash, 2);
+    record_feed_stats(&pool, 3, &stats).await.unwrap();
+
+    // Re-fetching feed 3 merges nothing new
+    let (_, stats) = store_feed_items_with_stats(&pool, 3, &entries("http://z/alpha", "http://z/beta"), None, &UrlNormalizer::default(), true)
+        .await
+        .unwrap();
+    assert_eq!((stats.already_seen, stats.merged_by_hash), (2, 0));
+    record_feed_stats(&pool, 3, &stats).await.unwrap();
+
+    let report = dedup_report(&pool, true).await.unwrap();
+    assert_eq!(report.totals.new_articles, 2);
+    assert_eq!(report.totals.merged_by_hash, 2);
+    // Feeds 2 and 3 only carried duplicates; ties keep feed id order
+    assert_eq!(report.feeds[0].feed_id, 2);
+    assert_eq!(report.feeds[0].redundancy, 1.0);
+    assert_eq!(report.feeds.last().unwrap().feed_id, 1);
+}
+
+#[tokio::test]
+async fn test_tracking_url_variants_share_one_article() {
+    let (pool, _db) = support::migrated_db().await;
+    for url in ["http://a.example/rss", "http://b.example/rss"] {
+        sqlx::query("INSERT INTO feeds (url) VALUES (?)").bind(url).execute(&pool).await.unwrap();
+    }
+    let urls = UrlNormalizer::default();
+
+    let (ids, _) = store_feed_items_with_stats(
+        &pool,
+        1,
+        &entries("https://news.example/alpha?utm_source=rss", "https://news.example/beta"),
+        None,
+        &urls,
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(ids.len(), 2);
+
+    // Mobile host, fragment and other tracking params: same articles, new occurrences
+    let (ids, stats) = store_feed_items_with_stats(
+        &pool,
+        2,
+        &entries("https://M.news.example/alpha?fbclid=1#top", "https://www.news.example/beta?utm_medium=x"),
+        None,
+        &urls,
+        false,
+    )
+    .await
+    .unwrap();
+    assert!(ids.is_empty());
+    assert_eq!(stats.merged_by_url, 2);
+    let occurrences: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM article_occurrences WHERE feed_id = 2")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(occurrences, 2);
+
+    // A parameter outside the denylist makes it a different page
+    let (ids, _) = store_feed_items_with_stats(
+        &pool,
+        2,
+        &entries("https://news.example/alpha?page=2", "https://news.example/beta"),
+        None,
+        &urls,
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(ids.len(), 1);
+
+}
@@ -0,0 +1,61 @@
+use newscope::server::AppState;
+use rocket::http::{Header, Status};
+use serde_json::Value;
+
+mod support;
+
+#[tokio::test]
+async fn test_admin_diagnostics_dump() {
+    let dir = tempfile::tempdir().unwrap();
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice'), ('bob')",
+        "INSERT INTO llm_usage_log (operation, model, prompt_tokens, completion_tokens, success) VALUES
+            ('summarize', 'm1', 10, 5, 1), ('chat', 'm2', 20, 8, 0)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let config: common::Config = toml::from_str(&format!(
+        "[database]\npath = \"x.db\"\n[scheduler]\ntimes = []\n[admin]\ndiagnostics_dir = {:?}\n\
+         [notifications]\nwebhook_url = \"https://hooks.example/secret\"",
+        dir.path().join("dumps").display().to_string()
+    ))
+    .unwrap();
+
+    let state = AppState {
+        config: Some(newscope::config_reload::shared(config)),
+        worker: Some(newscope::worker_status::shared()),
+        ..support::app_state(pool)
+    };
+    let client = support::api_client(state).await;
+    let dump = |token: Option<&'static str>| {
+        let mut req = client.post("/api/v1/admin/diagnostics");
+        if let Some(token) = token {
+            req = req.header(Header::new("X-Admin-Token", token));
+        }
+        req.dispatch()
+    };
+
+    // Disabled until an admin token is configured
+    std::env::remove_var(newscope::auth::ADMIN_TOKEN_ENV);
+    assert_eq!(dump(Some("")).await.status(), Status::Forbidden);
+    std::env::set_var(newscope::auth::ADMIN_TOKEN_ENV, "s3cret");
+    assert_eq!(dump(None).await.status(), Status::Unauthorized);
+    assert_eq!(dump(Some("wrong")).await.status(), Status::Unauthorized);
+
+    let res = dump(Some("s3cret")).await;
+    assert_eq!(res.status(), Status::Ok);
+    let body: Value = res.into_json().await.unwrap();
+    let path = std::path::PathBuf::from(body["path"].as_str().unwrap());
+    assert_eq!(path.parent(), Some(dir.path().join("dumps").as_path()));
+
+    let snapshot: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    assert_eq!(snapshot["table_counts"]["users"], 2);
+    assert_eq!(snapshot["table_counts"]["llm_usage_log"], 2);
+    assert_eq!(snapshot["recent_llm_usage"][0]["operation"], "chat");
+    assert_eq!(snapshot["recent_llm_usage"][1]["prompt_tokens"], 10);
+    assert_eq!(snapshot["integrity_check"], serde_json::json!(["ok"]));
+    assert_eq!(snapshot["worker"]["feeds_due"], 0);
+    assert_eq!(snapshot["config"]["notifications"]["webhook_url"], "[redacted]");
+
+}
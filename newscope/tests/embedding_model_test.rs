@@ -0,0 +1,64 @@
+use newscope::processing::{
+    embed_article, embedding_configured, embedding_model, sync_embedding_model, EmbeddingText, HashingEmbedder,
+    LOCAL_EMBEDDING_MODEL,
+};
+
+mod support;
+
+fn config(llm: &str) -> common::Config {
+    toml::from_str(&format!("[database]\npath = \"x.db\"\n[scheduler]\ntimes = []\n{}", llm)).unwrap()
+}
+
+#[test]
+fn test_hashing_fallback_only_without_an_embedding_endpoint() {
+    for (llm, configured) in [
+        ("", false),
+        ("[llm]\nadapter = \"none\"\n[llm.embedding]\nmodel = \"all-minilm\"", false),
+        ("[llm]\nadapter = \"remote\"", false),
+        ("[llm]\nadapter = \"remote\"\n[llm.embedding]\nmodel = \"all-minilm\"", true),
+        ("[llm]\nadapter = \"remote\"\n[llm.remote]\nmodel = \"all-minilm\"", true),
+    ] {
+        let config = config(llm);
+        assert_eq!(embedding_configured(Some(&config)), configured, "{}", llm);
+        let expected = if configured { "all-minilm" } else { LOCAL_EMBEDDING_MODEL };
+        assert_eq!(embedding_model(Some(&config)), expected, "{}", llm);
+    }
+}
+
+#[tokio::test]
+async fn test_vectors_of_another_model_are_dropped() {
+    let (pool, _db) = support::migrated_db_with_dim(8).await;
+    for id in 1..=3 {
+        sqlx::query("INSERT INTO articles (id, canonical_url, title, content) VALUES (?, ?, 'Title', 'Body')")
+            .bind(id)
+            .bind(format!("https://example.com/{}", id))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    let hashing = HashingEmbedder::new(8);
+    for id in [1, 2] {
+        assert!(embed_article(&pool, &hashing, LOCAL_EMBEDDING_MODEL, id, 8, &EmbeddingText::default()).await.unwrap());
+    }
+    // Stored before models were recorded
+    sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (3, '[0, 0, 0, 0, 0, 0, 0, 1]')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let models = || async {
+        sqlx::query_as::<_, (i64, String)>("SELECT article_id, model FROM article_vector_models ORDER BY article_id")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+    };
+    assert_eq!(sync_embedding_model(&pool, LOCAL_EMBEDDING_MODEL).await.unwrap(), 0);
+    let local = LOCAL_EMBEDDING_MODEL.to_string();
+    assert_eq!(models().await, vec![(1, local.clone()), (2, local.clone()), (3, local)]);
+
+    // A real model is configured: the fallback vectors are recomputed
+    assert_eq!(sync_embedding_model(&pool, "all-minilm").await.unwrap(), 3);
+    assert!(models().await.is_empty());
+    assert_eq!(newscope::processing::count_missing_embeddings(&pool).await.unwrap(), 3);
+    assert_eq!(sync_embedding_model(&pool, "all-minilm").await.unwrap(), 0);
+}
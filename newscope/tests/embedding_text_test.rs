@@ -0,0 +1,36 @@
+use newscope::processing::{sync_embedding_text, EmbeddingText};
+
+mod support;
+
+#[tokio::test]
+async fn test_changed_embedding_text_clears_article_vectors() {
+    let (pool, _db) = support::migrated_db_with_dim(2).await;
+    for id in 1..=3 {
+        sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (?, '[0.5, 0.5]')")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    // Existing embeddings were built with the default composition
+    assert_eq!(sync_embedding_text(&pool, &EmbeddingText::default()).await.unwrap(), 0);
+
+    let custom = EmbeddingText {
+        template: "{title}\n{categories}\n{summary}".to_string(),
+        max_chars: 2000,
+    };
+    assert_eq!(sync_embedding_text(&pool, &custom).await.unwrap(), 3);
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vec_articles")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+
+    // Unchanged on the next start
+    sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (1, '[0.5, 0.5]')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(sync_embedding_text(&pool, &custom).await.unwrap(), 0);
+}
@@ -0,0 +1,53 @@
+use newscope::storage::FeedArticle;
+use rocket::http::Status;
+
+mod support;
+
+#[tokio::test]
+async fn test_feed_articles_listing() {
+    let (pool, _db) = support::migrated_db().await;
+
+    let (_, alice) = support::user_with_key(&pool, "alice").await;
+    let (_, bob) = support::user_with_key(&pool, "bob").await;
+    for stmt in [
+        "INSERT INTO feeds (url) VALUES ('https://a.example/rss'), ('https://b.example/rss')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1), (2, 2)",
+        "INSERT INTO articles (canonical_url, title, published_at) VALUES
+            ('https://a.example/1', 'Old', '2026-10-01T08:00:00Z'),
+            ('https://a.example/2', 'New', '2026-10-02T08:00:00Z'),
+            ('https://b.example/1', 'Other feed', '2026-10-03T08:00:00Z')",
+        "INSERT INTO article_occurrences (article_id, feed_id) VALUES (1, 1), (2, 1), (2, 1), (3, 2)",
+        r#"INSERT INTO article_summaries (article_id, headline, bullets_json) VALUES (2, 'New headline', '["point"]')"#,
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+
+    let state = support::app_state(pool.clone());
+    let client = support::api_client(state).await;
+
+    let resp = client.get("/api/v1/feeds/1/articles").header(support::api_key(&alice)).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let articles: Vec<FeedArticle> = resp.into_json().await.unwrap();
+    let titles: Vec<_> = articles.iter().map(|a| a.title.clone().unwrap()).collect();
+    assert_eq!(titles, ["New", "Old"]);
+    assert_eq!(articles[0].headline.as_deref(), Some("New headline"));
+    assert_eq!(articles[0].bullets, Some(vec!["point".to_string()]));
+    assert!(articles[1].headline.is_none());
+
+    let page: Vec<FeedArticle> = client
+        .get("/api/v1/feeds/1/articles?user_id=1&limit=1&offset=1")
+        .header(support::api_key(&alice))
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].title.as_deref(), Some("Old"));
+
+    // No user, not subscribed, or asking as someone else
+    assert_eq!(client.get("/api/v1/feeds/1/articles").dispatch().await.status(), Status::Unauthorized);
+    let as_bob = |path: &'static str| client.get(path).header(support::api_key(&bob)).dispatch();
+    assert_eq!(as_bob("/api/v1/feeds/1/articles").await.status(), Status::NotFound);
+    assert_eq!(as_bob("/api/v1/feeds/1/articles?user_id=1").await.status(), Status::Forbidden);
+}
@@ -0,0 +1,150 @@
+use newscope::ingestion::FeedPreview;
+use newscope::server::FeedCreated;
+use rocket::http::{ContentType, Header, Status};
+
+mod support;
+
+const FEED: &str = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Example News</title><link>https://example.com</link></channel></rss>"#;
+
+#[tokio::test]
+async fn test_site_url_subscribes_to_advertised_feed() {
+    let mut site = mockito::Server::new_async().await;
+    let home = site
+        .mock("GET", "/")
+        .with_header("content-type", "text/html")
+        .with_body(
+            r#"<html><head>
+                <link rel="alternate" type="application/rss+xml" href="/missing.xml">
+                <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+            </head><body>Home</body></html>"#,
+        )
+        .create_async()
+        .await;
+    let _missing = site.mock("GET", "/missing.xml").with_status(404).create_async().await;
+    let _feed = site
+        .mock("GET", "/feed.xml")
+        .with_header("content-type", "application/rss+xml")
+        .with_body(FEED)
+        .create_async()
+        .await;
+
+    let (pool, _db) = support::migrated_db().await;
+    sqlx::query("INSERT INTO users (username) VALUES ('alice')").execute(&pool).await.unwrap();
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let subscribe = |url: String| {
+        client
+            .post("/api/v1/feeds")
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", key.clone()))
+            .body(serde_json::json!({ "url": url }).to_string())
+    };
+
+    let home_url = format!("{}/", site.url());
+    let resp = subscribe(home_url.clone()).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let created: FeedCreated = resp.into_json().await.unwrap();
+
+    let (url, title, site_url): (String, Option<String>, Option<String>) =
+        sqlx::query_as("SELECT url, title, site_url FROM feeds WHERE id = ?")
+            .bind(created.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(url, format!("{}/feed.xml", site.url()));
+    assert_eq!(title.as_deref(), Some("Example News"));
+    assert_eq!(site_url.as_deref(), Some(home_url.as_str()));
+    home.assert_async().await;
+
+    // Submitting the feed URL itself finds the same feed
+    let again: FeedCreated = subscribe(url).dispatch().await.into_json().await.unwrap();
+    assert_eq!(again.subscription_id, created.subscription_id);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_preview_stores_nothing() {
+    let items: String = (1..=3)
+        .map(|i| {
+            format!(
+                "<item><title>Story {i}</title><link>https://example.com/{i}</link>\
+                 <pubDate>Mon, 0{i} Jan 2024 10:00:00 GMT</pubDate>\
+                 <description>&lt;p&gt;Body of &lt;b&gt;story&lt;/b&gt; {i}&lt;/p&gt;</description></item>"
+            )
+        })
+        .collect();
+    let feed = format!(
+        r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Example News</title>{}</channel></rss>"#,
+        items
+    );
+    let mut site = mockito::Server::new_async().await;
+    let _home = site
+        .mock("GET", "/")
+        .with_header("content-type", "text/html")
+        .with_body(r#"<html><head><link rel="alternate" type="application/rss+xml" href="/feed.xml"></head></html>"#)
+        .create_async()
+        .await;
+    let _feed = site
+        .mock("GET", "/feed.xml")
+        .with_header("content-type", "application/rss+xml")
+        .with_body(feed)
+        .create_async()
+        .await;
+    let _about = site.mock("GET", "/about").with_header("content-type", "text/html").with_body("<p>Hi</p>").create_async().await;
+    let _gone = site.mock("GET", "/gone").with_status(404).create_async().await;
+
+    let (pool, _db) = support::migrated_db().await;
+    sqlx::query("INSERT INTO users (username) VALUES ('alice')").execute(&pool).await.unwrap();
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let preview = |body: serde_json::Value| {
+        client
+            .post("/api/v1/feeds/preview")
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", key.clone()))
+            .body(body.to_string())
+    };
+
+    let resp = preview(serde_json::json!({ "url": format!("{}/feed.xml", site.url()), "limit": 2 })).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let direct: FeedPreview = resp.into_json().await.unwrap();
+    assert!(!direct.discovered);
+    assert_eq!(direct.title.as_deref(), Some("Example News"));
+    assert_eq!(direct.item_count, 3);
+    assert_eq!(direct.entries.len(), 2);
+    assert_eq!(direct.entries[0].title.as_deref(), Some("Story 1"));
+    assert_eq!(direct.entries[0].link.as_deref(), Some("https://example.com/1"));
+    assert_eq!(direct.entries[0].snippet.as_deref(), Some("Body of story 1"));
+    assert!(direct.entries[0].published.is_some());
+
+    let home_url = format!("{}/", site.url());
+    let discovered: FeedPreview = preview(serde_json::json!({ "url": home_url })).dispatch().await.into_json().await.unwrap();
+    assert!(discovered.discovered);
+    assert_eq!(discovered.feed_url, format!("{}/feed.xml", site.url()));
+    assert_eq!(discovered.site_url.as_deref(), Some(home_url.as_str()));
+    assert_eq!(discovered.entries.len(), 3);
+
+    let resp = preview(serde_json::json!({ "url": format!("{}/about", site.url()) })).dispatch().await;
+    assert_eq!(resp.status(), Status::UnprocessableEntity);
+    assert!(resp.into_string().await.unwrap().contains("no feed found"));
+    let resp = preview(serde_json::json!({ "url": format!("{}/gone", site.url()) })).dispatch().await;
+    assert_eq!(resp.status(), Status::BadGateway);
+    let resp = client
+        .post("/api/v1/feeds/preview")
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "url": home_url }).to_string())
+        .dispatch()
+        .await;
+    assert_eq!(resp.status(), Status::Unauthorized);
+
+    for table in ["feeds", "articles", "article_occurrences"] {
+        let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table)).fetch_one(&pool).await.unwrap();
+        assert_eq!(count, 0, "{}", table);
+    }
+
+    pool.close().await;
+}
@@ -0,0 +1,141 @@
+use newscope::feed_health::{feed_health_report, record_fetch, MAX_SAMPLES_PER_FEED};
+use newscope::ingestion::{fetch_and_parse_feed_measured, FetchMetrics, FetchOptions};
+
+mod support;
+
+#[tokio::test]
+async fn test_fetch_metrics_are_recorded_and_bounded() {
+    let body = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>t</title>
+<item><title>One</title><link>http://x/one</link></item>
+</channel></rss>"#;
+    let mut server_mock = mockito::Server::new_async().await;
+    let _feed = server_mock
+        .mock("GET", "/rss")
+        .with_status(200)
+        .with_body(body)
+        .create_async()
+        .await;
+
+    let (result, metrics) =
+        fetch_and_parse_feed_measured(&format!("{}/rss", server_mock.url()), &FetchOptions::default()).await;
+    assert_eq!(result.unwrap().entries.len(), 1);
+    assert_eq!(metrics.response_bytes, Some(body.len() as i64));
+    assert_eq!(metrics.http_status, Some(200));
+    assert_eq!(metrics.attempts, 1);
+
+    // Oversized bodies are rejected, not retried
+    let small = FetchOptions {
+        max_response_bytes: 64,
+        ..FetchOptions::default()
+    };
+    let (result, metrics) = fetch_and_parse_feed_measured(&format!("{}/rss", server_mock.url()), &small).await;
+    assert_eq!(result.unwrap_err().to_string(), "response exceeded 64 bytes");
+    assert_eq!(metrics.attempts, 1);
+
+    let (pool, _db) = support::migrated_db().await;
+    for url in ["http://fast.example/rss", "http://slow.example/rss"] {
+        sqlx::query("INSERT INTO feeds (url) VALUES (?)").bind(url).execute(&pool).await.unwrap();
+    }
+
+    record_fetch(&pool, 1, &metrics, None).await.unwrap();
+    for i in 0..MAX_SAMPLES_PER_FEED + 5 {
+        let sample = FetchMetrics {
+            latency_ms: 9000 + i,
+            response_bytes: Some(2000),
+            http_status: Some(200),
+            attempts: 1,
+        };
+        record_fetch(&pool, 2, &sample, None).await.unwrap();
+    }
+    let timeout = FetchMetrics {
+        latency_ms: 10_000,
+        attempts: 3,
+        ..FetchMetrics::default()
+    };
+    record_fetch(&pool, 2, &timeout, Some("network error during fetch")).await.unwrap();
+
+    let report = feed_health_report(&pool).await.unwrap();
+    assert_eq!(report.len(), 2);
+    let slow = &report[0];
+    assert_eq!(slow.feed_id, 2);
+    assert_eq!(slow.samples, MAX_SAMPLES_PER_FEED);
+    assert_eq!(slow.failures, 1);
+    assert_eq!(slow.max_latency_ms, 10_000);
+    assert_eq!(slow.last_latency_ms, 10_000);
+    assert_eq!(slow.avg_response_bytes, Some(2000.0));
+    assert_eq!(slow.last_error.as_deref(), Some("network error during fetch"));
+    assert_eq!(report[1].feed_id, 1);
+    assert_eq!(report[1].samples, 1);
+
+}
+
+#[tokio::test]
+async fn test_permanent_failures_disable_feed_until_reenabled() {
+    use newscope::feed_health::{is_permanent_failure, record_failure, record_success};
+    use rocket::http::{Header, Status};
+    
+    use serde_json::Value;
+
+    let mut server_mock = mockito::Server::new_async().await;
+    let _gone = server_mock.mock("GET", "/gone").with_status(404).create_async().await;
+    let (result, metrics) =
+        fetch_and_parse_feed_measured(&format!("{}/gone", server_mock.url()), &FetchOptions::default()).await;
+    assert!(result.is_err());
+    assert!(is_permanent_failure(metrics.http_status));
+
+    let (pool, _db) = support::migrated_db().await;
+    sqlx::query("INSERT INTO users (username) VALUES ('alice')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO feeds (url) VALUES ('http://gone.example/rss')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)").execute(&pool).await.unwrap();
+    let status = || async {
+        sqlx::query_as::<_, (Option<String>, i64)>("SELECT status, consecutive_failures FROM feeds WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+
+    // Transient failures don't count; a success resets the count
+    assert!(!record_failure(&pool, 1, false, 3).await.unwrap());
+    assert!(!record_failure(&pool, 1, true, 3).await.unwrap());
+    assert_eq!(status().await, (None, 1));
+    record_success(&pool, 1).await.unwrap();
+    assert_eq!(status().await, (None, 0));
+
+    assert!(!record_failure(&pool, 1, true, 3).await.unwrap());
+    assert!(!record_failure(&pool, 1, true, 3).await.unwrap());
+    assert!(record_failure(&pool, 1, true, 3).await.unwrap());
+    assert_eq!(status().await, (Some("disabled".to_string()), 3));
+    record_fetch(&pool, 1, &metrics, Some("HTTP 404")).await.unwrap();
+
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let auth = || Header::new("X-API-Key", key.clone());
+
+    let disabled: Vec<Value> = client
+        .get("/api/v1/feeds/disabled")
+        .header(auth())
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    assert_eq!(disabled.len(), 1);
+    assert_eq!(disabled[0]["consecutive_failures"], 3);
+    assert_eq!(disabled[0]["last_http_status"], 404);
+    assert_eq!(disabled[0]["last_error"], "HTTP 404");
+
+    assert_eq!(client.post("/api/v1/feeds/2/enable").header(auth()).dispatch().await.status(), Status::NotFound);
+    assert_eq!(client.post("/api/v1/feeds/1/enable").header(auth()).dispatch().await.status(), Status::NoContent);
+    assert_eq!(status().await, (None, 0));
+    let disabled: Vec<Value> = client
+        .get("/api/v1/feeds/disabled")
+        .header(auth())
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    assert!(disabled.is_empty());
+
+}
@@ -0,0 +1,84 @@
+use newscope::health::{check_llm, HealthReport, HealthStatus};
+use newscope::llm::remote::RemoteLlmProvider;
+use newscope::llm::{LlmProvider, LlmRequest, LlmResponse, Summary};
+use newscope::server::AppState;
+use rocket::http::Status;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+mod support;
+
+#[tokio::test]
+async fn test_health_reflects_subsystems() {
+    let (pool, _db) = support::migrated_db().await;
+
+    // Nothing listens on the discard port
+    let unreachable: Arc<dyn LlmProvider> = Arc::new(RemoteLlmProvider::new("http://127.0.0.1:9/v1", "key", "model"));
+    let worker = newscope::worker_status::shared();
+    newscope::worker_status::update(&worker, |w| w.last_loop_at = Some(chrono::Utc::now()));
+    let state = AppState {
+        interaction_llm: Some(unreachable),
+        embedding_llm: Some(Arc::new(newscope::processing::HashingEmbedder::default())),
+        worker: Some(worker),
+        ..support::app_state(pool.clone())
+    };
+    let client = support::api_client(state).await;
+
+    let res = client.get("/api/v1/health").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    let report: HealthReport = res.into_json().await.unwrap();
+    assert_eq!(report.status, HealthStatus::Degraded);
+    assert_eq!(report.checks["database"].status, HealthStatus::Ok);
+    assert_eq!(report.checks["summarization_llm"].status, HealthStatus::Disabled);
+    assert_eq!(report.checks["interaction_llm"].status, HealthStatus::Degraded);
+    // Generic: the endpoint URL and error text only go to the log
+    assert_eq!(report.checks["interaction_llm"].message.as_deref(), Some("unreachable"));
+    assert_eq!(report.checks["embedding_llm"].status, HealthStatus::Ok);
+    assert_eq!(report.checks["worker"].status, HealthStatus::Ok);
+    assert!(report.worker_last_loop_at.is_some());
+    assert_eq!(client.get("/health").dispatch().await.status(), Status::Ok);
+
+    // Without a database the service is down
+    pool.close().await;
+    let res = client.get("/api/v1/health").dispatch().await;
+    assert_eq!(res.status(), Status::ServiceUnavailable);
+    let report: HealthReport = res.into_json().await.unwrap();
+    assert_eq!(report.status, HealthStatus::Down);
+    assert_eq!(report.checks["database"].status, HealthStatus::Down);
+    let res = client.get("/health").dispatch().await;
+    assert_eq!(res.status(), Status::ServiceUnavailable);
+    assert_eq!(res.into_string().await.unwrap(), "DOWN");
+}
+
+/// Provider counting its pings.
+#[derive(Default)]
+struct CountingLlm {
+    pings: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for CountingLlm {
+    async fn generate(&self, _request: LlmRequest) -> anyhow::Result<LlmResponse> {
+        anyhow::bail!("not used")
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        anyhow::bail!("not used")
+    }
+    async fn ping(&self) -> anyhow::Result<()> {
+        self.pings.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_llm_pings_are_cached() {
+    let counting = Arc::new(CountingLlm::default());
+    let provider: Arc<dyn LlmProvider> = counting.clone();
+    for _ in 0..3 {
+        assert_eq!(check_llm(Some(&provider)).await.status, HealthStatus::Ok);
+    }
+    assert_eq!(counting.pings.load(Ordering::SeqCst), 1);
+}
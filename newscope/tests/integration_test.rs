@@ -1,9 +1,7 @@
-use common::{init_db_pool, Config};
+use common::init_db_pool;
 use newscope::server;
 use sqlx::SqlitePool;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::time::sleep;
 
 // Helper to create a test pool
 async fn setup_test_db() -> SqlitePool {
@@ -100,19 +98,6 @@ async fn test_full_pipeline() {
         .expect("count subs");
     assert_eq!(sub_count, 2, "Should have 2 subscriptions");
 
-    // 6. Run Ingestion (Simulate Worker)
-    // We can't easily run the full worker loop here without spawning, so we'll just call the logic manually
-    // or extract the logic to a testable function.
-    // Since we can't easily access private modules from integration tests unless they are pub,
-    // we might need to rely on the fact that we are in the same crate or make things pub.
-    // `mynewslens` is a binary crate, so integration tests in `tests/` can't easily access its internals
-    // unless it's also a lib.
-
-    // WORKAROUND: We'll put this test inside `mynewslens/src/main.rs` or `mynewslens/src/lib.rs` if it existed.
-    // But `mynewslens` is a binary.
-    // Let's create a separate test file in `mynewslens/src/bin/verification.rs` or similar?
-    // No, easiest is to add a test module to `mynewslens/src/main.rs` but that's not ideal for "Global Verification".
-
-    // Actually, we can use `cargo run -- --worker-only` and let it run for a bit, then check DB.
-    // That's a true integration test.
+    // 6. Ingestion itself is covered by the ingestion tests; the full worker
+    // loop only runs in the binary (`newscope worker`).
 }
@@ -0,0 +1,157 @@
+use newscope::jobs::{self, JobRunner, JOB_EMBEDDING, JOB_PERSONALIZATION, JOB_SUMMARY, MAX_ATTEMPTS};
+use rocket::http::{Header, Status};
+use serde_json::Value;
+use std::sync::Arc;
+
+mod support;
+
+#[tokio::test]
+async fn test_job_queue_retries_and_survives_restarts() {
+    let (pool, _db) = support::migrated_db().await;
+    let article_id: i64 = sqlx::query_scalar(
+        "INSERT INTO articles (canonical_url, title, content) VALUES ('https://example.com/a', 'Title', 'Body') RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // A job is not queued twice while pending
+    assert_eq!(jobs::enqueue(&pool, JOB_EMBEDDING, &[article_id]).await.unwrap(), 1);
+    assert_eq!(jobs::enqueue(&pool, JOB_EMBEDDING, &[article_id]).await.unwrap(), 0);
+    assert_eq!(jobs::enqueue(&pool, JOB_SUMMARY, &[article_id]).await.unwrap(), 1);
+
+    // No summarization LLM: the embedding job succeeds, the summary one fails
+    let config: common::Config = toml::from_str("[database]\npath = \"x.db\"\n[scheduler]\ntimes = []").unwrap();
+    let runner = JobRunner {
+        pool: pool.clone(),
+        config: newscope::config_reload::shared(config),
+        summarization_llm: None,
+        personalization_llm: None,
+        embedding_llm: Some(Arc::new(newscope::processing::HashingEmbedder::default())),
+        embedding_model: "hash".to_string(),
+        status: None,
+    };
+    assert!(runner.run_next().await.unwrap());
+    assert!(runner.run_next().await.unwrap());
+    // The failed job waits out its retry delay
+    assert!(!runner.run_next().await.unwrap());
+
+    let completed = jobs::list(&pool, Some("completed"), 10).await.unwrap();
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].job_type, JOB_EMBEDDING);
+    assert_eq!(completed[0].llm_model.as_deref(), Some("hash"));
+    assert_eq!(newscope::processing::count_missing_embeddings(&pool).await.unwrap(), 0);
+    let pending = jobs::list(&pool, Some("pending"), 10).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].attempts, 1);
+    assert!(pending[0].error_message.as_deref().unwrap().contains("no summarization LLM"));
+
+    // Retried until its attempts are used up
+    let mut later = chrono::Utc::now();
+    for attempt in 2..=MAX_ATTEMPTS {
+        later += chrono::Duration::seconds(jobs::RETRY_DELAY_SECONDS + 1);
+        let job = jobs::claim(&pool, later).await.unwrap().expect("retry claimed");
+        assert_eq!(job.attempts, attempt);
+        jobs::fail(&pool, &job, "still broken").await.unwrap();
+    }
+    later += chrono::Duration::seconds(jobs::RETRY_DELAY_SECONDS + 1);
+    assert!(jobs::claim(&pool, later).await.unwrap().is_none());
+    let failed = jobs::list(&pool, Some("failed"), 10).await.unwrap();
+    assert_eq!(failed.len(), 1);
+    assert!(failed[0].completed_at.is_some());
+
+    // A job left running by a crash goes back to pending on restart
+    jobs::enqueue(&pool, JOB_PERSONALIZATION, &[article_id]).await.unwrap();
+    let running = jobs::claim(&pool, chrono::Utc::now()).await.unwrap().expect("claimed");
+    assert_eq!(running.status, "running");
+    let stale_cutoff = chrono::Utc::now() - chrono::Duration::minutes(jobs::STALE_AFTER_MINUTES);
+    assert_eq!(jobs::requeue_stale(&pool, stale_cutoff).await.unwrap(), 0);
+    assert_eq!(jobs::requeue_stale(&pool, chrono::Utc::now()).await.unwrap(), 1);
+    let pending = jobs::list(&pool, Some("pending"), 10).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, running.id);
+
+    // GET /api/v1/jobs
+    sqlx::query("INSERT INTO users (username) VALUES ('alice')").execute(&pool).await.unwrap();
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+    let state = support::app_state(pool.clone());
+    let client = support::api_client(state).await;
+    let get = |uri: &'static str| client.get(uri).header(support::admin_token()).dispatch();
+
+    let res = get("/api/v1/jobs?status=failed").await;
+    assert_eq!(res.status(), Status::Ok);
+    let body: Vec<Value> = res.into_json().await.unwrap();
+    assert_eq!(body.len(), 1);
+    assert_eq!(body[0]["job_type"], JOB_SUMMARY);
+    assert_eq!(body[0]["attempts"], MAX_ATTEMPTS);
+
+    let body: Vec<Value> = get("/api/v1/jobs").await.into_json().await.unwrap();
+    assert_eq!(body.len(), 3);
+    assert_eq!(get("/api/v1/jobs?status=stuck").await.status(), Status::BadRequest);
+    assert_eq!(client.get("/api/v1/jobs").dispatch().await.status(), Status::Unauthorized);
+    let res = client.get("/api/v1/jobs").header(Header::new("X-API-Key", key)).dispatch().await;
+    assert_eq!(res.status(), Status::Unauthorized);
+}
+
+#[tokio::test]
+async fn test_runner_start_leaves_recent_claims_running() {
+    let (pool, _db) = support::migrated_db().await;
+    let article_id: i64 = sqlx::query_scalar(
+        "INSERT INTO articles (canonical_url, title, content) VALUES ('https://example.com/a', 'Title', 'Body') RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    // Claimed a moment ago by another runner sharing the database
+    jobs::enqueue(&pool, JOB_PERSONALIZATION, &[article_id]).await.unwrap();
+    let running = jobs::claim(&pool, chrono::Utc::now()).await.unwrap().expect("claimed");
+    // Shutdown is already requested, so this one is left for the next start
+    jobs::enqueue(&pool, JOB_EMBEDDING, &[article_id]).await.unwrap();
+
+    let config: common::Config = toml::from_str("[database]\npath = \"x.db\"\n[scheduler]\ntimes = []").unwrap();
+    let runner = JobRunner {
+        pool: pool.clone(),
+        config: newscope::config_reload::shared(config),
+        summarization_llm: None,
+        personalization_llm: None,
+        embedding_llm: Some(Arc::new(newscope::processing::HashingEmbedder::default())),
+        embedding_model: "hash".to_string(),
+        status: None,
+    };
+    let (_shutdown, stop) = tokio::sync::watch::channel(true);
+    runner.run(stop).await;
+
+    let still_running = jobs::list(&pool, Some("running"), 10).await.unwrap();
+    assert_eq!(still_running.len(), 1);
+    assert_eq!(still_running[0].id, running.id);
+    assert_eq!(still_running[0].attempts, 1);
+    assert_eq!(jobs::list(&pool, Some("pending"), 10).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_articles_without_vectors_are_queued_for_embedding() {
+    let (pool, _db) = support::migrated_db().await;
+    for (id, status) in [(1, "completed"), (2, "pending"), (3, "completed")] {
+        sqlx::query("INSERT INTO articles (id, canonical_url, title, processing_status) VALUES (?, ?, 'Title', ?)")
+            .bind(id)
+            .bind(format!("https://example.com/{}", id))
+            .bind(status)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    // Article 3's embedding gave up for good
+    jobs::enqueue(&pool, JOB_EMBEDDING, &[3]).await.unwrap();
+    sqlx::query("UPDATE processing_jobs SET status = 'failed'").execute(&pool).await.unwrap();
+
+    // With summaries, only summarized articles: the others get theirs after summarizing
+    assert_eq!(jobs::enqueue_missing_embeddings(&pool, true, 10).await.unwrap(), 1);
+    assert_eq!(jobs::enqueue_missing_embeddings(&pool, false, 10).await.unwrap(), 1);
+    let pending: Vec<i64> = jobs::list(&pool, Some("pending"), 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|j| j.entity_id)
+        .collect();
+    assert_eq!(pending, vec![2, 1]);
+}
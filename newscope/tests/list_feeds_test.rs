@@ -0,0 +1,61 @@
+use newscope::server::FeedPage;
+use rocket::http::Status;
+
+mod support;
+
+#[tokio::test]
+async fn test_list_feeds_is_per_user_and_paginated() {
+    let (pool, _db) = support::migrated_db().await;
+
+    let (_, alice) = support::user_with_key(&pool, "alice").await;
+    let (_, bob) = support::user_with_key(&pool, "bob").await;
+    for i in 1..=5 {
+        sqlx::query("INSERT INTO feeds (url) VALUES (?)")
+            .bind(format!("https://{}.example/rss", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO subscriptions (user_id, feed_id) VALUES (1, ?)")
+            .bind(i)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("INSERT INTO subscriptions (user_id, feed_id) VALUES (2, 1)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+
+    let resp = client.get("/api/v1/feeds").dispatch().await;
+    assert_eq!(resp.status(), Status::Unauthorized);
+
+    let page: FeedPage = client
+        .get("/api/v1/feeds?limit=2&offset=2")
+        .header(support::api_key(&alice))
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    assert_eq!((page.total, page.limit, page.offset), (5, 2, 2));
+    let urls: Vec<&str> = page.feeds.iter().map(|f| f.url.as_str()).collect();
+    assert_eq!(urls, ["https://3.example/rss", "https://4.example/rss"]);
+
+    // Naming yourself is fine; naming someone else is not
+    let page: FeedPage = client
+        .get("/api/v1/feeds?user_id=2&limit=1000")
+        .header(support::api_key(&bob))
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    assert_eq!((page.total, page.limit, page.feeds.len()), (1, 200, 1));
+    assert!(page.feeds.iter().all(|f| f.user_id == 2));
+    let resp = client.get("/api/v1/feeds?user_id=1").header(support::api_key(&bob)).dispatch().await;
+    assert_eq!(resp.status(), Status::Forbidden);
+
+    pool.close().await;
+}
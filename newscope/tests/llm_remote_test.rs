@@ -84,7 +84,7 @@ async fn test_remote_provider_summarize_with_mock() {
     let provider = RemoteLlmProvider::new(server.url(), "fake-api-key", "gpt-4o-mini");
 
     let result = provider
-        .summarize("Long article content here...", 200)
+        .summarize("Long article content here...", None, 200)
         .await;
 
     assert!(result.is_ok());
@@ -158,3 +158,150 @@ async fn test_remote_provider_timeout() {
     assert!(result.is_err());
     assert!(result.unwrap_err().to_string().contains("timed out"));
 }
+
+#[tokio::test]
+async fn test_remote_provider_streams_tokens() {
+    use newscope::llm::{LlmChunk, UsageMetadata};
+    use rocket::futures::StreamExt;
+
+    let mut server = mockito::Server::new_async().await;
+
+    // Comments and role-only deltas are skipped; usage arrives in a final chunk
+    let mock = server
+        .mock("POST", "/")
+        .match_body(mockito::Matcher::PartialJsonString(r#"{"stream": true}"#.to_string()))
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_body(
+            ": keep-alive\n\n\
+             data: {\"model\":\"gpt-4o-mini\",\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n\
+             data: {\"choices\":[{\"delta\":{\"content\":\"Bonjour \"}}]}\n\n\
+             data: {\"choices\":[{\"delta\":{\"content\":\"à tous\"}}]}\n\n\
+             data: {\"choices\":[],\"usage\":{\"prompt_tokens\":7,\"completion_tokens\":3,\"total_tokens\":10}}\n\n\
+             data: [DONE]\n\n",
+        )
+        .create_async()
+        .await;
+
+    let provider = RemoteLlmProvider::new(server.url(), "fake-api-key", "default-model");
+    let request = LlmRequest {
+        prompt: "Salut".to_string(),
+        max_tokens: Some(50),
+        temperature: None,
+        timeout_seconds: Some(10),
+    };
+
+    let chunks: Vec<LlmChunk> = provider
+        .generate_stream(request)
+        .await
+        .unwrap()
+        .map(|c| c.unwrap())
+        .collect()
+        .await;
+    assert_eq!(
+        chunks,
+        vec![
+            LlmChunk::Token("Bonjour ".to_string()),
+            LlmChunk::Token("à tous".to_string()),
+            LlmChunk::Done {
+                model: "gpt-4o-mini".to_string(),
+                usage: UsageMetadata { prompt_tokens: 7, completion_tokens: 3, total_tokens: 10 },
+            },
+        ]
+    );
+
+    mock.assert_async().await;
+}
+
+/// Providers without native streaming get the whole answer as one token.
+#[tokio::test]
+async fn test_default_stream_falls_back_to_generate() {
+    use newscope::llm::{LlmChunk, LlmResponse, Summary, UsageMetadata};
+    use rocket::futures::StreamExt;
+
+    struct Fixed;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for Fixed {
+        async fn generate(&self, _request: LlmRequest) -> anyhow::Result<LlmResponse> {
+            Ok(LlmResponse { content: "whole".into(), usage: UsageMetadata::default(), model: "fixed".into() })
+        }
+        async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+            anyhow::bail!("not used")
+        }
+        async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+            anyhow::bail!("not used")
+        }
+    }
+
+    let request = LlmRequest { prompt: "p".into(), max_tokens: None, temperature: None, timeout_seconds: None };
+    let chunks: Vec<LlmChunk> = Fixed.generate_stream(request).await.unwrap().map(|c| c.unwrap()).collect().await;
+    assert_eq!(
+        chunks,
+        vec![
+            LlmChunk::Token("whole".into()),
+            LlmChunk::Done { model: "fixed".into(), usage: UsageMetadata::default() },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_remote_provider_retries_transient_errors() {
+    let mut server = mockito::Server::new_async().await;
+    let ok_body = r#"{"model": "gpt-4o-mini", "choices": [{"message": {"role": "assistant", "content": "done"}}], "usage": {}}"#;
+
+    // A 429 with Retry-After, then a 503, then success
+    let rate_limited = server
+        .mock("POST", "/")
+        .with_status(429)
+        .with_header("retry-after", "1")
+        .expect(1)
+        .create_async()
+        .await;
+    let unavailable = server.mock("POST", "/").with_status(503).expect(1).create_async().await;
+    let ok = server.mock("POST", "/").with_status(200).with_body(ok_body).expect(1).create_async().await;
+
+    let provider = RemoteLlmProvider::new(server.url(), "fake-api-key", "gpt-4o-mini")
+        .with_retries(3, std::time::Duration::from_millis(10));
+    let request = LlmRequest {
+        prompt: "Test".to_string(),
+        max_tokens: None,
+        temperature: None,
+        timeout_seconds: Some(10),
+    };
+    let started = std::time::Instant::now();
+    let response = provider.generate(request).await.unwrap();
+    assert_eq!(response.content, "done");
+    // The Retry-After wait is honored instead of the 10ms backoff
+    assert!(started.elapsed() >= std::time::Duration::from_secs(1));
+
+    rate_limited.assert_async().await;
+    unavailable.assert_async().await;
+    ok.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_remote_provider_gives_up_and_fails_fast() {
+    let mut server = mockito::Server::new_async().await;
+    let provider = RemoteLlmProvider::new(server.url(), "fake-api-key", "all-minilm")
+        .with_retries(2, std::time::Duration::from_millis(10));
+
+    // Out of retries: the last error is returned
+    let down = server.mock("POST", "/embeddings").with_status(500).expect(3).create_async().await;
+    let err = provider.embed("text").await.unwrap_err();
+    assert!(err.to_string().contains("500"));
+    down.assert_async().await;
+    down.remove_async().await;
+
+    // Other client errors are not retried
+    let unauthorized = server
+        .mock("POST", "/embeddings")
+        .with_status(401)
+        .with_body("bad key")
+        .expect(1)
+        .create_async()
+        .await;
+    let err = provider.embed("text").await.unwrap_err();
+    assert!(err.to_string().contains("401"));
+    unauthorized.assert_async().await;
+}
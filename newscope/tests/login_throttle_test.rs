@@ -0,0 +1,82 @@
+use newscope::rate_limit::FailureLimiter;
+use newscope::server::AppState;
+use rocket::http::{ContentType, Status};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod support;
+
+#[tokio::test]
+async fn test_failed_logins_are_throttled() {
+    let (pool, _db) = support::migrated_db().await;
+
+    let state = AppState {
+        auth_limiter: Arc::new(FailureLimiter::new(3, Duration::from_secs(600))),
+        ..support::app_state(pool.clone())
+    };
+    let client = support::api_client(state).await;
+    let ip = |last: u8| SocketAddr::from(([10, 0, 0, last], 4000));
+    let post = |uri: &'static str, from: SocketAddr, body: String| {
+        client.post(uri).remote(from).header(ContentType::JSON).body(body).dispatch()
+    };
+    let login = |from: SocketAddr, username: &str, password: &str| {
+        post("/api/v1/login", from, format!(r#"{{"username": "{}", "password": "{}"}}"#, username, password))
+    };
+    let register = |from: SocketAddr, username: &str| {
+        post("/api/v1/register", from, format!(r#"{{"username": "{}", "password": "secret"}}"#, username))
+    };
+
+    for name in ["alice", "bob", "carol"] {
+        assert_eq!(register(ip(1), name).await.status(), Status::Ok);
+    }
+
+    // An unknown user is verified against a dummy hash, like a wrong password
+    let started = Instant::now();
+    assert_eq!(login(ip(2), "nobody", "x").await.status(), Status::Unauthorized);
+    let unknown = started.elapsed();
+    assert!(unknown >= newscope::server::FAILED_LOGIN_DELAY, "{:?}", unknown);
+    let started = Instant::now();
+    assert_eq!(login(ip(2), "carol", "x").await.status(), Status::Unauthorized);
+    assert!(unknown * 4 >= started.elapsed(), "{:?} vs {:?}", unknown, started.elapsed());
+
+    // The peer address is the key: a forged X-Real-IP doesn't escape the limit
+    for forged in ["192.0.2.1", "192.0.2.2"] {
+        let res = client
+            .post("/api/v1/login")
+            .remote(ip(2))
+            .header(rocket::http::Header::new("X-Real-IP", forged))
+            .header(ContentType::JSON)
+            .body(r#"{"username": "nobody", "password": "x"}"#)
+            .dispatch()
+            .await;
+        assert_ne!(res.status(), Status::Ok);
+    }
+    assert_eq!(login(ip(2), "dave", "x").await.status(), Status::TooManyRequests);
+
+    // A success resets the count
+    for _ in 0..2 {
+        assert_eq!(login(ip(3), "alice", "wrong").await.status(), Status::Unauthorized);
+    }
+    assert_eq!(login(ip(3), "alice", "secret").await.status(), Status::Ok);
+    for _ in 0..2 {
+        assert_eq!(login(ip(3), "alice", "wrong").await.status(), Status::Unauthorized);
+    }
+    assert_eq!(login(ip(3), "alice", "wrong").await.status(), Status::Unauthorized);
+
+    // Third failure: blocked, even with the right password, from this IP or for this user
+    let res = login(ip(3), "alice", "secret").await;
+    assert_eq!(res.status(), Status::TooManyRequests);
+    let retry_after: u64 = res.headers().get_one("Retry-After").unwrap().parse().unwrap();
+    assert!((590..=600).contains(&retry_after), "{}", retry_after);
+    assert_eq!(login(ip(3), "bob", "secret").await.status(), Status::TooManyRequests);
+    assert_eq!(login(ip(4), "alice", "secret").await.status(), Status::TooManyRequests);
+    assert_eq!(login(ip(4), "bob", "secret").await.status(), Status::Ok);
+
+    // Failed registrations (taken usernames) count against the IP
+    for _ in 0..3 {
+        assert_eq!(register(ip(5), "carol").await.status(), Status::Conflict);
+    }
+    assert_eq!(register(ip(5), "dave").await.status(), Status::TooManyRequests);
+    assert_eq!(register(ip(6), "dave").await.status(), Status::Ok);
+}
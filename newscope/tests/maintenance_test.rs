@@ -0,0 +1,148 @@
+use newscope::maintenance::{check_integrity, processing_report};
+use newscope::maintenance::PruneReport;
+use rocket::http::Status;
+
+mod support;
+
+#[tokio::test]
+async fn test_integrity_check_removes_orphans() {
+    let (pool, _db) = support::migrated_db_with_dim(4).await;
+
+    sqlx::query("INSERT INTO articles (canonical_url, title, content) VALUES ('http://example.com/1', 't', 'c')")
+        .execute(&pool)
+        .await
+        .expect("insert article");
+
+    // Orphans referencing article 999, which doesn't exist
+    let embedding: Vec<u8> = [0.1_f32, 0.2, 0.3, 0.4].iter().flat_map(|f| f.to_le_bytes()).collect();
+    sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (999, ?)")
+        .bind(embedding)
+        .execute(&pool)
+        .await
+        .expect("insert orphan vector");
+    // Simulate a manual DB edit: foreign keys are per-connection, so use a single one
+    {
+        let mut conn = pool.acquire().await.expect("acquire");
+        sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut conn).await.unwrap();
+        sqlx::query("INSERT INTO article_summaries (article_id, headline, bullets_json) VALUES (999, 'h', '[]')")
+            .execute(&mut conn)
+            .await
+            .expect("insert orphan summary");
+        sqlx::query("PRAGMA foreign_keys = ON").execute(&mut conn).await.unwrap();
+    }
+
+    let report = check_integrity(&pool, None).await.expect("integrity check");
+    assert_eq!(report.orphan_vec_articles_removed, 1);
+    assert_eq!(report.orphan_article_summaries_removed, 1);
+    assert_eq!(report.articles_missing_embeddings, 1);
+    assert_eq!(report.reembedded, 0);
+}
+
+#[tokio::test]
+async fn test_processing_report_counts_backlog_and_failures() {
+    let (pool, _db) = support::migrated_db_with_dim(4).await;
+
+    let two_hours_ago = chrono::Utc::now() - chrono::Duration::hours(2);
+    for (url, status, seen) in [
+        ("http://example.com/old", "pending", two_hours_ago),
+        ("http://example.com/new", "pending", chrono::Utc::now()),
+        ("http://example.com/done", "completed", chrono::Utc::now()),
+    ] {
+        sqlx::query("INSERT INTO articles (canonical_url, title, processing_status, first_seen_at) VALUES (?, 't', ?, ?)")
+            .bind(url)
+            .bind(status)
+            .bind(seen)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("INSERT INTO processing_jobs (job_type, entity_id, status, error_message) VALUES ('article_summary', 1, 'failed', 'timeout')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let report = processing_report(&pool).await.expect("processing report");
+    assert_eq!(report.counts.get("pending"), Some(&2));
+    assert_eq!(report.counts.get("completed"), Some(&1));
+    assert_eq!(report.samples["pending"][0].article_id, 2);
+    let age = report.oldest_pending_age_seconds.unwrap();
+    assert!((7190..7300).contains(&age), "unexpected age {}", age);
+    assert_eq!(report.recent_failures.len(), 1);
+    assert_eq!(report.recent_failures[0].error_message.as_deref(), Some("timeout"));
+}
+
+#[tokio::test]
+async fn test_prune_keeps_unread_and_recently_viewed_articles() {
+    let (pool, _db) = support::migrated_db_with_dim(4).await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice')",
+        "INSERT INTO feeds (url) VALUES ('https://example.com/rss')",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let days_ago = |days: i64| chrono::Utc::now() - chrono::Duration::days(days);
+    for (id, age_days) in [(1, 100), (2, 100), (3, 100), (4, 1)] {
+        sqlx::query("INSERT INTO articles (id, canonical_url, title, first_seen_at) VALUES (?, ?, 't', ?)")
+            .bind(id)
+            .bind(format!("https://example.com/{}", id))
+            .bind(days_ago(age_days))
+            .execute(&pool)
+            .await
+            .unwrap();
+        let embedding: Vec<u8> = [0.1_f32, 0.2, 0.3, 0.4].iter().flat_map(|f| f.to_le_bytes()).collect();
+        for stmt in [
+            "INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, 1)",
+            "INSERT INTO article_summaries (article_id, headline, bullets_json) VALUES (?, 'h', '[]')",
+            "INSERT INTO user_article_summaries (user_id, article_id, relevance_score, personalized_headline, personalized_bullets, language) VALUES (1, ?, 0.5, 'h', '[]', 'en')",
+        ] {
+            sqlx::query(stmt).bind(id).execute(&pool).await.unwrap();
+        }
+        sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (?, ?)")
+            .bind(id)
+            .bind(embedding)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    // Article 1 was read in an old session, article 3 in a recent one; 2 is unread
+    for (session_id, started) in [(1, days_ago(99)), (2, days_ago(2))] {
+        sqlx::query("INSERT INTO sessions (id, user_id, start_at) VALUES (?, 1, ?)")
+            .bind(session_id)
+            .bind(started)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("INSERT INTO user_article_views (user_id, article_id, session_id) VALUES (1, 1, 1), (1, 3, 2)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+
+    let prune = |path: &'static str| client.post(path).header(support::admin_token()).dispatch();
+    // No retention_days configured and none given
+    assert_eq!(prune("/api/v1/admin/prune").await.status(), Status::BadRequest);
+    // Would delete every article
+    assert_eq!(prune("/api/v1/admin/prune?days=0").await.status(), Status::BadRequest);
+
+    let resp = prune("/api/v1/admin/prune?days=30").await;
+    assert_eq!(resp.status(), Status::Ok);
+    let report: PruneReport = resp.into_json().await.unwrap();
+    assert_eq!(
+        report,
+        PruneReport {
+            articles: 1,
+            article_occurrences: 1,
+            article_summaries: 1,
+            user_article_summaries: 1,
+            vec_articles: 1,
+        }
+    );
+    let remaining: Vec<i64> = sqlx::query_scalar("SELECT id FROM articles ORDER BY id").fetch_all(&pool).await.unwrap();
+    assert_eq!(remaining, vec![2, 3, 4]);
+    let vectors: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM vec_articles").fetch_one(&pool).await.unwrap();
+    assert_eq!(vectors, 3);
+
+    pool.close().await;
+}
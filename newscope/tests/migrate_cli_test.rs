@@ -0,0 +1,76 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn newscope(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_newscope"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .output()
+        .expect("run newscope")
+}
+
+fn write_config(dir: &Path, db_path: &Path) -> String {
+    let path = dir.join("config.toml");
+    std::fs::write(
+        &path,
+        format!(
+            r#"
+            [database]
+            path = "{}"
+            [scheduler]
+            times = []
+            [[users]]
+            username = "alice"
+            password_hash = "$argon2id$v=19$abc"
+            "#,
+            db_path.display()
+        ),
+    )
+    .unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[tokio::test]
+async fn test_migrate_subcommand_prepares_the_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("newscope.db");
+    let config = write_config(dir.path(), &db_path);
+
+    for _ in 0..2 {
+        // Running it again on an up-to-date database is a no-op
+        let output = newscope(&["--config", &config, "--log-level", "warn", "migrate"]);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let pool = common::init_db_pool(db_path.to_str().unwrap()).await.unwrap();
+    let users: Vec<String> = sqlx::query_scalar("SELECT username FROM users").fetch_all(&pool).await.unwrap();
+    assert_eq!(users, vec!["alice".to_string()]);
+    let applied: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(applied > 0);
+    let session_title: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info('sessions') WHERE name = 'title'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(session_title, 1);
+}
+
+#[test]
+fn test_migrate_fails_with_a_non_zero_exit() {
+    let dir = tempfile::tempdir().unwrap();
+    // The database directory can't be created under a regular file
+    let blocker = dir.path().join("not_a_dir");
+    std::fs::write(&blocker, "").unwrap();
+    let config = write_config(dir.path(), &blocker.join("newscope.db"));
+
+    let output = newscope(&["migrate", "--config", &config, "--log-level", "warn"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_legacy_flags_cannot_be_combined_with_a_subcommand() {
+    let output = newscope(&["--no-worker", "migrate"]);
+    assert_eq!(output.status.code(), Some(2));
+}
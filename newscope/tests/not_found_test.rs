@@ -0,0 +1,39 @@
+use rocket::http::{ContentType, Header, Status};
+
+mod support;
+
+#[tokio::test]
+async fn test_missing_sessions_are_404() {
+    let (pool, _db) = support::migrated_db().await;
+    sqlx::query("INSERT INTO users (username) VALUES ('alice')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO users (username) VALUES ('bob')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO sessions (user_id) VALUES (1), (2)").execute(&pool).await.unwrap();
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+    let auth = || Header::new("X-API-Key", key.clone());
+
+    let state = support::app_state(pool.clone());
+    let client = support::api_client(state).await;
+
+    let get = |id: i64| client.get(format!("/api/v1/sessions/{}", id)).header(auth());
+    assert_eq!(get(1).dispatch().await.status(), Status::Ok);
+    assert_eq!(get(999).dispatch().await.status(), Status::NotFound);
+    // Another user's session is indistinguishable from a missing one
+    assert_eq!(get(2).dispatch().await.status(), Status::NotFound);
+
+    let rename = |id: i64| {
+        client
+            .put(format!("/api/v1/sessions/{}", id))
+            .header(auth())
+            .header(ContentType::JSON)
+            .body(r#"{"title":"Morning"}"#)
+    };
+    assert_eq!(rename(1).dispatch().await.status(), Status::Ok);
+    assert_eq!(rename(999).dispatch().await.status(), Status::NotFound);
+    assert_eq!(rename(2).dispatch().await.status(), Status::NotFound);
+
+    let resp = client.get("/api/v1/sessions").header(auth()).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let sessions: Vec<newscope::sessions::Session> = resp.into_json().await.unwrap();
+    assert_eq!(sessions.iter().map(|s| s.id).collect::<Vec<_>>(), [1]);
+
+}
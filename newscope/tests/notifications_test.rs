@@ -0,0 +1,60 @@
+use common::NotificationsConfig;
+use newscope::notifications::notify_high_relevance;
+
+mod support;
+
+#[tokio::test]
+async fn test_high_relevance_notification_sent_once() {
+    let mut server_mock = mockito::Server::new_async().await;
+    let hook = server_mock
+        .mock("POST", "/hook")
+        .match_body(mockito::Matcher::PartialJsonString(
+            r#"{"event":"high_relevance_article","article_id":1,"title":"Un article important pour vous"}"#.to_string(),
+        ))
+        .with_status(200)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let (pool, _db) = support::migrated_db().await;
+
+    sqlx::query("INSERT INTO users (username) VALUES ('dana')").execute(&pool).await.unwrap();
+    for url in ["http://example.com/1", "http://example.com/2"] {
+        sqlx::query("INSERT INTO articles (canonical_url, title, content) VALUES (?, 't', 'c')")
+            .bind(url)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    // Session from two days ago, long finished: user is active but not in session
+    sqlx::query(
+        "INSERT INTO sessions (user_id, start_at, duration_requested_seconds)
+         VALUES (1, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-2 days'), 600)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    for (article_id, score) in [(1_i64, 0.95_f64), (2, 0.5)] {
+        sqlx::query(
+            "INSERT INTO user_article_summaries
+                (user_id, article_id, relevance_score, is_relevant, personalized_headline, personalized_bullets, language)
+             VALUES (1, ?, ?, 1, 'Big news', '[]', 'fr')",
+        )
+        .bind(article_id)
+        .bind(score)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let cfg = NotificationsConfig {
+        webhook_url: Some(format!("{}/hook", server_mock.url())),
+        min_relevance_to_notify: Some(0.9),
+        active_within_days: Some(7),
+    };
+    assert_eq!(notify_high_relevance(&pool, &cfg).await.unwrap(), 1);
+    // Already notified: nothing new
+    assert_eq!(notify_high_relevance(&pool, &cfg).await.unwrap(), 0);
+    hook.assert_async().await;
+
+}
@@ -0,0 +1,40 @@
+mod support;
+
+#[tokio::test]
+async fn test_bootstrap_opml_is_idempotent() {
+    let (pool, db) = support::migrated_db().await;
+    let opml_path = db.path().join("bootstrap.opml");
+
+    sqlx::query("INSERT INTO feeds (url, title) VALUES ('https://known.example/rss', 'Kept title')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    std::fs::write(
+        &opml_path,
+        r#"<opml version="2.0"><body>
+  <outline text="Known" xmlUrl="https://known.example/rss"/>
+  <outline text="New" xmlUrl="https://new.example/rss"/>
+</body></opml>"#,
+    )
+    .unwrap();
+
+    assert_eq!(newscope::opml::bootstrap_feeds(&pool, &opml_path).await.unwrap(), 1);
+    assert_eq!(newscope::opml::bootstrap_feeds(&pool, &opml_path).await.unwrap(), 0);
+
+    let feeds: Vec<(String, Option<String>)> = sqlx::query_as("SELECT url, title FROM feeds ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        feeds,
+        vec![
+            ("https://known.example/rss".to_string(), Some("Kept title".to_string())),
+            ("https://new.example/rss".to_string(), Some("New".to_string())),
+        ]
+    );
+    let subscriptions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM subscriptions")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(subscriptions, 0);
+}
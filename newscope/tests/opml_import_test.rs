@@ -0,0 +1,152 @@
+use newscope::server::{FeedPage, OpmlImportResponse};
+use rocket::http::{ContentType, Header, Status};
+
+mod support;
+
+const OPML: &str = r#"<?xml version="1.0"?>
+<opml version="2.0"><head><title>Export</title></head><body>
+  <outline text="Tech">
+    <outline text="Ars" xmlUrl="https://ars.example/rss" htmlUrl="https://ars.example/"/>
+    <outline text="Linux">
+      <outline text="LWN" xmlUrl="https://lwn.example/rss"/>
+    </outline>
+  </outline>
+  <outline text="Known" xmlUrl="https://known.example/rss"/>
+  <outline text="Twice" xmlUrl="https://ars.example/rss"/>
+  <outline text="Broken" xmlUrl="not a url"/>
+</body></opml>"#;
+
+#[tokio::test]
+async fn test_opml_import_subscribes_with_categories() {
+    let (pool, _db) = support::migrated_db().await;
+
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice')",
+        "INSERT INTO feeds (url, title) VALUES ('https://known.example/rss', 'Known')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let import = |body: &str| {
+        client
+            .post("/api/v1/feeds/import")
+            .header(ContentType::XML)
+            .header(Header::new("X-API-Key", key.clone()))
+            .body(body)
+    };
+
+    let resp = client.post("/api/v1/feeds/import").body(OPML).dispatch().await;
+    assert_eq!(resp.status(), Status::Unauthorized);
+    let resp = import("<opml><body><outline xmlUrl=\"x\"></body>").dispatch().await;
+    assert_eq!(resp.status(), Status::BadRequest);
+
+    let resp = import(OPML).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let summary: OpmlImportResponse = resp.into_json().await.unwrap();
+    assert_eq!((summary.added, summary.skipped, summary.failed), (2, 2, 1));
+    assert_eq!(summary.errors, vec!["Invalid feed URL: not a url".to_string()]);
+
+    let page: FeedPage = client
+        .get("/api/v1/feeds")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    // Feeds are subscribed concurrently, so insertion order is not fixed
+    let mut categories: Vec<(&str, Option<&str>)> = page
+        .feeds
+        .iter()
+        .map(|f| (f.url.as_str(), f.category.as_deref()))
+        .collect();
+    categories.sort();
+    assert_eq!(
+        categories,
+        vec![
+            ("https://ars.example/rss", Some("Tech")),
+            ("https://known.example/rss", None),
+            ("https://lwn.example/rss", Some("Tech/Linux")),
+        ]
+    );
+    let site_url: Option<String> = sqlx::query_scalar("SELECT site_url FROM feeds WHERE url = 'https://ars.example/rss'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(site_url.as_deref(), Some("https://ars.example/"));
+
+    // Importing again changes nothing
+    let summary: OpmlImportResponse = import(OPML).dispatch().await.into_json().await.unwrap();
+    assert_eq!((summary.added, summary.skipped, summary.failed), (0, 4, 1));
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_opml_export_round_trips_through_import() {
+    let (pool, _db) = support::migrated_db().await;
+
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice'), ('bob')",
+        "INSERT INTO feeds (url, title, site_url) VALUES ('https://a.example/rss', 'A & Co', 'https://a.example/')",
+        "INSERT INTO feeds (url, title) VALUES ('https://b.example/rss', 'B')",
+        "INSERT INTO feeds (url, title) VALUES ('https://c.example/rss', 'C')",
+        "INSERT INTO subscriptions (user_id, feed_id, category) VALUES (1, 1, 'Tech/Linux'), (1, 2, NULL), (2, 3, NULL)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let (_, alice) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+    let (_, bob) = newscope::auth::create_api_key(&pool, 2, None, None).await.unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+
+    let resp = client.get("/api/v1/feeds/export").dispatch().await;
+    assert_eq!(resp.status(), Status::Unauthorized);
+    let resp = client
+        .get("/api/v1/feeds/export?user_id=2")
+        .header(Header::new("X-API-Key", alice.clone()))
+        .dispatch()
+        .await;
+    assert_eq!(resp.status(), Status::Forbidden);
+
+    let resp = client
+        .get("/api/v1/feeds/export")
+        .header(Header::new("X-API-Key", alice.clone()))
+        .dispatch()
+        .await;
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.content_type(), Some(ContentType::new("text", "x-opml")));
+    let opml = resp.into_string().await.unwrap();
+    let feeds = newscope::opml::parse_opml(opml.as_bytes()).unwrap();
+    assert_eq!(
+        feeds.iter().map(|f| f.url.as_str()).collect::<Vec<_>>(),
+        vec!["https://b.example/rss", "https://a.example/rss"]
+    );
+    assert_eq!(feeds[1].title.as_deref(), Some("A & Co"));
+    assert_eq!(feeds[1].html_url.as_deref(), Some("https://a.example/"));
+    assert_eq!(feeds[1].category.as_deref(), Some("Tech/Linux"));
+
+    // Bob imports Alice's export and ends up with the same subscriptions
+    let summary: OpmlImportResponse = client
+        .post("/api/v1/feeds/import")
+        .header(Header::new("X-API-Key", bob.clone()))
+        .body(&opml)
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    assert_eq!((summary.added, summary.skipped, summary.failed), (2, 0, 0));
+    let category: Option<String> = sqlx::query_scalar(
+        "SELECT category FROM subscriptions WHERE user_id = 2 AND feed_id = 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(category.as_deref(), Some("Tech/Linux"));
+
+    pool.close().await;
+}
@@ -0,0 +1,119 @@
+use newscope::llm::{LlmProvider, LlmRequest, LlmResponse, Summary};
+use newscope::personalize_worker::personalize_for_users;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod support;
+
+/// Slow LLM recording how many calls overlap. The first relevance evaluation
+/// fails, the second finds the article irrelevant, the others rate it 0.8.
+#[derive(Default)]
+struct SlowLlm {
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+    evaluations: AtomicUsize,
+    summaries: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for SlowLlm {
+    async fn generate(&self, request: LlmRequest) -> anyhow::Result<LlmResponse> {
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        let content = if request.prompt.starts_with("Evaluate") {
+            match self.evaluations.fetch_add(1, Ordering::SeqCst) {
+                0 => anyhow::bail!("provider unavailable"),
+                1 => r#"{"score": 0.1, "reasons": []}"#,
+                _ => r#"{"score": 0.8, "reasons": ["match"]}"#,
+            }
+        } else {
+            self.summaries.fetch_add(1, Ordering::SeqCst);
+            r#"{"headline": "Headline", "bullets": ["point"], "details": null}"#
+        };
+        Ok(LlmResponse {
+            content: content.to_string(),
+            usage: Default::default(),
+            model: "test".to_string(),
+        })
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        anyhow::bail!("not used")
+    }
+}
+
+#[tokio::test]
+async fn test_users_are_personalized_concurrently() {
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('u1'), ('u2'), ('u3'), ('u4'), ('u5'), ('u6')",
+        "INSERT INTO articles (canonical_url, title) VALUES ('https://example.com/a', 'A')",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+
+    let llm = Arc::new(SlowLlm::default());
+    let summary = Summary {
+        headline: "H".to_string(),
+        bullets: Vec::new(),
+        details: None,
+        usage: Default::default(),
+    };
+    let stored = personalize_for_users(&pool, 1, &summary, llm.clone(), "test", 3, None).await.unwrap();
+
+    // One failure and one irrelevant article don't stop the other users
+    assert_eq!(stored, 4);
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_article_summaries WHERE article_id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rows, 4);
+    // No summary is requested below the relevance threshold
+    assert_eq!(llm.evaluations.load(Ordering::SeqCst), 6);
+    assert_eq!(llm.summaries.load(Ordering::SeqCst), 4);
+    let max_in_flight = llm.max_in_flight.load(Ordering::SeqCst);
+    assert!((2..=3).contains(&max_in_flight), "{} calls at once", max_in_flight);
+}
+
+/// LLM that is down.
+struct DownLlm;
+
+#[async_trait::async_trait]
+impl LlmProvider for DownLlm {
+    async fn generate(&self, _request: LlmRequest) -> anyhow::Result<LlmResponse> {
+        anyhow::bail!("connection refused")
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        anyhow::bail!("not used")
+    }
+}
+
+#[tokio::test]
+async fn test_failing_every_user_is_an_error() {
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('u1'), ('u2')",
+        "INSERT INTO articles (canonical_url, title) VALUES ('https://example.com/a', 'A')",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let summary = Summary {
+        headline: "H".to_string(),
+        bullets: Vec::new(),
+        details: None,
+        usage: Default::default(),
+    };
+
+    // Reported, so the personalization job is retried rather than completed
+    let err = personalize_for_users(&pool, 1, &summary, Arc::new(DownLlm), "test", 2, None).await.unwrap_err();
+    assert!(format!("{:#}", err).contains("connection refused"), "{:#}", err);
+}
@@ -0,0 +1,101 @@
+use newscope::press_review::PersonalizedFeedItem;
+use rocket::http::{Header, Status};
+
+mod support;
+
+#[tokio::test]
+async fn test_personalized_feed_filters_and_pagination() {
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice'), ('bob')",
+        "INSERT INTO feeds (url, title) VALUES ('https://a.example/rss', 'Feed A'), ('https://b.example/rss', 'Feed B')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1), (2, 2)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    // (article, feed, relevance, is_relevant, categories, first seen)
+    for (id, feed_id, score, relevant, categories, seen) in [
+        (1, 1, 0.5, true, r#"["technology"]"#, "2026-10-01T08:00:00Z"),
+        (2, 1, 0.9, true, r#"["politics"]"#, "2026-10-01T09:00:00Z"),
+        (3, 1, 0.5, true, r#"["Technology","science"]"#, "2026-10-02T08:00:00Z"),
+        (4, 1, 0.1, false, r#"["sports"]"#, "2026-10-03T08:00:00Z"),
+        // Only in a feed alice doesn't subscribe to
+        (5, 2, 1.0, true, r#"["technology"]"#, "2026-10-03T08:00:00Z"),
+    ] {
+        sqlx::query("INSERT INTO articles (id, canonical_url, title, first_seen_at) VALUES (?, ?, 'Title', ?)")
+            .bind(id)
+            .bind(format!("https://example.com/{}", id))
+            .bind(seen)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, ?)")
+            .bind(id)
+            .bind(feed_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_summaries (article_id, categories) VALUES (?, ?)")
+            .bind(id)
+            .bind(categories)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries (user_id, article_id, relevance_score, is_relevant, personalized_headline, personalized_bullets, language)
+             VALUES (1, ?, ?, ?, ?, '[\"point\"]', 'en')",
+        )
+        .bind(id)
+        .bind(score)
+        .bind(relevant)
+        .bind(format!("Headline {}", id))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    sqlx::query("INSERT INTO user_article_views (user_id, article_id) VALUES (1, 2)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+
+    let state = support::app_state(pool.clone());
+    let client = support::api_client(state).await;
+    let ids = |uri: &'static str| {
+        let req = client.get(uri).header(Header::new("X-API-Key", key.clone()));
+        async move {
+            let res = req.dispatch().await;
+            assert_eq!(res.status(), Status::Ok, "{}", uri);
+            let items: Vec<PersonalizedFeedItem> = res.into_json().await.unwrap();
+            items.iter().map(|i| i.id).collect::<Vec<_>>()
+        }
+    };
+
+    // Relevance, then recency; low-relevance articles are listed too
+    assert_eq!(ids("/api/v1/feed").await, [2, 3, 1, 4]);
+    assert_eq!(ids("/api/v1/feed?unread_only=true").await, [3, 1, 4]);
+    assert_eq!(ids("/api/v1/feed?category=technology").await, [3, 1]);
+    assert_eq!(ids("/api/v1/feed?min_relevance=0.5&unread_only=true").await, [3, 1]);
+    assert_eq!(ids("/api/v1/feed?limit=2&offset=1").await, [3, 1]);
+    assert_eq!(ids("/api/v1/feed?user_id=1&category=sports").await, [4]);
+
+    let items: Vec<PersonalizedFeedItem> = client
+        .get("/api/v1/feed?limit=1")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    assert_eq!(items[0].headline, "Headline 2");
+    assert_eq!(items[0].bullets, ["point"]);
+    assert_eq!(items[0].feed_title.as_deref(), Some("Feed A"));
+    assert_eq!(items[0].categories, ["politics"]);
+    assert!(items[0].viewed);
+    assert!(items[0].is_relevant);
+
+    let other_user = client.get("/api/v1/feed?user_id=2").header(Header::new("X-API-Key", key.clone()));
+    assert_eq!(other_user.dispatch().await.status(), Status::Forbidden);
+    assert_eq!(client.get("/api/v1/feed").dispatch().await.status(), Status::Unauthorized);
+
+}
@@ -0,0 +1,90 @@
+use newscope::server::PressReviewResponse;
+use rocket::http::{ContentType, Header, Status};
+
+mod support;
+
+#[tokio::test]
+async fn test_press_review_endpoint_stores_review_in_new_session() {
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice'), ('bob')",
+        "INSERT INTO feeds (url, title) VALUES ('https://a.example/rss', 'Feed A')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    for (id, score) in [(1, 0.6), (2, 0.9)] {
+        sqlx::query(
+            "INSERT INTO articles (id, canonical_url, title, first_seen_at)
+             VALUES (?, ?, 'Title', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))",
+        )
+        .bind(id)
+        .bind(format!("https://a.example/{}", id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, 1)")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries (user_id, article_id, relevance_score, personalized_headline, personalized_bullets, language)
+             VALUES (1, ?, ?, ?, '[\"point\"]', 'en')",
+        )
+        .bind(id)
+        .bind(score)
+        .bind(format!("Headline {}", id))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+
+    // No LLM is needed: the review is assembled from stored summaries
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let review = |body: &'static str| {
+        client
+            .post("/api/v1/press-review")
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", key.clone()))
+            .body(body)
+    };
+
+    assert_eq!(
+        client.post("/api/v1/press-review").header(ContentType::JSON).body("{}").dispatch().await.status(),
+        Status::Unauthorized
+    );
+    assert_eq!(review(r#"{"user_id": 2}"#).dispatch().await.status(), Status::Forbidden);
+
+    let resp = review(r#"{"user_id": 1, "duration_seconds": 600}"#).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let body: PressReviewResponse = resp.into_json().await.unwrap();
+    assert_eq!(body.article_count, 2);
+    let headlines: Vec<&str> = body.summary.lines().filter_map(|l| l.strip_prefix("## ")).collect();
+    assert_eq!(headlines, vec!["Headline 2", "Headline 1"]);
+
+    // Stored as the digest of a new session of the caller, articles marked read
+    let (user_id, stored): (i64, String) = sqlx::query_as(
+        "SELECT s.user_id, su.summary_text FROM sessions s JOIN summaries su ON su.id = s.digest_summary_id WHERE s.id = ?",
+    )
+    .bind(body.session_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!((user_id, stored), (1, body.summary.clone()));
+    let viewed: Vec<(i64, i64)> =
+        sqlx::query_as("SELECT article_id, session_id FROM user_article_views WHERE user_id = 1 ORDER BY article_id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(viewed, vec![(1, body.session_id), (2, body.session_id)]);
+    // A Markdown digest is never replayed as websocket cards
+    assert!(newscope::press_review::recent_review(&pool, 1, 60).await.unwrap().is_none());
+
+    // Everything is read now
+    let body: PressReviewResponse = review("{}").dispatch().await.into_json().await.unwrap();
+    assert_eq!(body.article_count, 0);
+
+    pool.close().await;
+}
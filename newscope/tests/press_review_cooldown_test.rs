@@ -0,0 +1,35 @@
+use newscope::press_review::{recent_review, store_review};
+
+mod support;
+
+#[tokio::test]
+async fn test_recent_review_respects_cooldown() {
+    let (pool, _db) = support::migrated_db().await;
+
+    sqlx::query("INSERT INTO users (username) VALUES ('alice'), ('bob')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO sessions (user_id) VALUES (1), (1), (2)").execute(&pool).await.unwrap();
+
+    let review_id = store_review(&pool, 1, r#"[{"id":7,"title":"T","summary":"S"}]"#, "m").await.unwrap();
+    let digest: Option<i64> = sqlx::query_scalar("SELECT digest_summary_id FROM sessions WHERE id = 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(digest, Some(review_id));
+
+    // Reused within the cooldown, only for the same user
+    let recent = recent_review(&pool, 1, 30).await.unwrap().expect("recent review");
+    assert_eq!(recent.id, review_id);
+    assert_eq!(recent.session_id, 1);
+    assert!(recent_review(&pool, 2, 30).await.unwrap().is_none());
+    // Cooldown disabled
+    assert!(recent_review(&pool, 1, 0).await.unwrap().is_none());
+
+    // Older than the cooldown
+    sqlx::query("UPDATE summaries SET created_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-2 hours')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(recent_review(&pool, 1, 30).await.unwrap().is_none());
+    assert!(recent_review(&pool, 1, 180).await.unwrap().is_some());
+
+}
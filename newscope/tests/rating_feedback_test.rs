@@ -0,0 +1,115 @@
+use newscope::llm::{LlmProvider, LlmRequest, LlmResponse, Summary};
+use newscope::personalization::{get_user_preferences, get_user_profile};
+use newscope::personalize_worker::{learn_category_weights, learn_from_ratings, personalize_article_for_user};
+
+mod support;
+
+/// Rates every article 0.8 and returns a fixed personalized summary.
+struct FixedLlm;
+
+#[async_trait::async_trait]
+impl LlmProvider for FixedLlm {
+    async fn generate(&self, request: LlmRequest) -> anyhow::Result<LlmResponse> {
+        let content = if request.prompt.starts_with("Evaluate") {
+            r#"{"score": 0.8, "reasons": ["match"]}"#
+        } else {
+            r#"{"headline": "Headline", "bullets": ["point"], "details": null}"#
+        };
+        Ok(LlmResponse {
+            content: content.to_string(),
+            usage: Default::default(),
+            model: "test".to_string(),
+        })
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        anyhow::bail!("not used")
+    }
+}
+
+#[tokio::test]
+async fn test_ratings_adjust_category_weights_and_relevance() {
+    let (pool, _db) = support::migrated_db().await;
+
+    sqlx::query("INSERT INTO users (username) VALUES ('alice'), ('bob')").execute(&pool).await.unwrap();
+    // Alice's own weight for science, below neutral
+    sqlx::query(
+        "INSERT INTO user_preferences (user_id, preference_type, preference_key, preference_value)
+         VALUES (1, 'category_filter', 'science', -0.4)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    for (id, categories) in [
+        (1, r#"["sports"]"#),
+        (2, r#"["sports", "science"]"#),
+        (3, r#"["politics"]"#),
+        (4, r#"["politics"]"#),
+        (5, r#"["sports"]"#),
+    ] {
+        sqlx::query("INSERT INTO articles (id, canonical_url, title) VALUES (?, ?, 'Title')")
+            .bind(id)
+            .bind(format!("http://x/{}", id))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_summaries (article_id, headline, bullets_json, categories) VALUES (?, 'H', '[]', ?)")
+            .bind(id)
+            .bind(categories)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    // Alice loves sports and science, dislikes politics; article 4 is read but unrated
+    for (user_id, article_id, rating) in [(1, 1, Some(5)), (1, 2, Some(5)), (1, 3, Some(1)), (1, 4, None), (2, 3, Some(5))] {
+        sqlx::query("INSERT INTO user_article_views (user_id, article_id, rating) VALUES (?, ?, ?)")
+            .bind(user_id)
+            .bind(article_id)
+            .bind(rating)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let weights = learn_category_weights(&pool, 1, 0.5).await.unwrap();
+    // Halfway from neutral toward 1.0 / -1.0; science keeps Alice's own weight
+    assert_eq!(weights.get("sports"), Some(&0.5));
+    assert_eq!(weights.get("politics"), Some(&-0.5));
+    assert!(!weights.contains_key("science"));
+
+    // Another pass keeps converging; Bob learns independently
+    assert_eq!(learn_from_ratings(&pool, 0.5).await.unwrap(), 2);
+    let profile = get_user_profile(&pool, 1).await.unwrap();
+    assert_eq!(profile.category_weights.get("sports"), Some(&0.75));
+    assert_eq!(profile.category_weights.get("politics"), Some(&-0.75));
+    assert_eq!(profile.category_weights.get("science"), Some(&-0.4));
+    assert_eq!(profile.preferred_categories, vec!["sports".to_string()]);
+    let bob = get_user_profile(&pool, 2).await.unwrap();
+    assert_eq!(bob.category_weights.get("politics"), Some(&0.5));
+    // The preferences API shows only what the user set
+    let prefs = get_user_preferences(&pool, 1).await.unwrap();
+    assert_eq!(prefs.category_filter.into_iter().collect::<Vec<_>>(), vec![("science".to_string(), -0.4)]);
+
+    // The learned weights shape stored relevance: 0.8 * (1 + w / 2)
+    let summary = Summary {
+        headline: "H".to_string(),
+        bullets: Vec::new(),
+        details: None,
+        usage: Default::default(),
+    };
+    for article_id in [4, 5] {
+        personalize_article_for_user(&pool, article_id, &summary, &FixedLlm, "test", &profile, None)
+            .await
+            .unwrap();
+    }
+    let scores: Vec<(i64, f64)> =
+        sqlx::query_as("SELECT article_id, relevance_score FROM user_article_summaries WHERE user_id = 1 ORDER BY article_id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(scores.len(), 2);
+    assert!((scores[0].1 - 0.8 * 0.625).abs() < 1e-6, "{:?}", scores);
+    assert!((scores[1].1 - 1.0).abs() < 1e-6, "{:?}", scores);
+}
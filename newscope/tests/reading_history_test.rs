@@ -0,0 +1,121 @@
+use newscope::views::{ArticleRating, HistoryEntry};
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::asynchronous::Client;
+
+mod support;
+
+/// Database with alice (1) and bob (2), articles 1 and 2, alice's rated view of
+/// article 1 and bob's view of article 2; plus an API key for alice.
+async fn setup() -> (tempfile::TempDir, sqlx::SqlitePool, Client, String) {
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice')",
+        "INSERT INTO users (username) VALUES ('bob')",
+        "INSERT INTO articles (canonical_url, title) VALUES ('https://example.com/1', 'One')",
+        "INSERT INTO articles (canonical_url, title) VALUES ('https://example.com/2', 'Two')",
+        "INSERT INTO sessions (id, user_id) VALUES (7, 1)",
+        "INSERT INTO user_article_views (user_id, article_id, session_id, viewed_at, rating)
+         VALUES (1, 1, 7, '2020-01-01T08:00:00Z', 4)",
+        "INSERT INTO user_article_views (user_id, article_id) VALUES (2, 2)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    (_db, pool, client, key)
+}
+
+#[tokio::test]
+async fn test_mark_read_unread_and_history() {
+    let (_db, pool, client, key) = setup().await;
+    let auth = || Header::new("X-API-Key", key.clone());
+    let history = || async {
+        client
+            .get("/api/v1/history")
+            .header(auth())
+            .dispatch()
+            .await
+            .into_json::<Vec<HistoryEntry>>()
+            .await
+            .unwrap()
+    };
+
+    assert_eq!(client.post("/api/v1/articles/2/view").dispatch().await.status(), Status::Unauthorized);
+    assert_eq!(client.post("/api/v1/articles/99/view").header(auth()).dispatch().await.status(), Status::NotFound);
+    assert_eq!(client.get("/api/v1/history?user_id=2").header(auth()).dispatch().await.status(), Status::Forbidden);
+
+    // Marking read is idempotent and keeps an existing rating
+    for id in [2, 2, 1] {
+        let resp = client.post(format!("/api/v1/articles/{}/view", id)).header(auth()).dispatch().await;
+        assert_eq!(resp.status(), Status::NoContent);
+    }
+    let entries = history().await;
+    assert_eq!(entries.iter().map(|e| e.article_id).collect::<Vec<_>>(), vec![2, 1]);
+    assert_eq!(entries[0].title.as_deref(), Some("Two"));
+    assert!(entries[0].viewed_at.is_some());
+    assert_eq!((entries[1].rating, entries[1].session_id), (Some(4), Some(7)));
+
+    let resp = client.delete("/api/v1/articles/1/view").header(auth()).dispatch().await;
+    assert_eq!(resp.status(), Status::NoContent);
+    let entries = history().await;
+    assert_eq!(entries.iter().map(|e| e.article_id).collect::<Vec<_>>(), vec![2]);
+
+    // Bob's view of article 2 is untouched
+    let bob: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_article_views WHERE user_id = 2")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(bob, 1);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_rate_article() {
+    let (_db, pool, client, key) = setup().await;
+    let auth = || Header::new("X-API-Key", key.clone());
+    let rate = |id: i64, body: &'static str| {
+        client
+            .post(format!("/api/v1/articles/{}/rating", id))
+            .header(ContentType::JSON)
+            .header(auth())
+            .body(body)
+    };
+    let (client, auth) = (&client, &auth);
+    let rating = |id: i64| async move {
+        client
+            .get(format!("/api/v1/articles/{}/rating", id))
+            .header(auth())
+            .dispatch()
+            .await
+            .into_json::<ArticleRating>()
+            .await
+            .unwrap()
+            .rating
+    };
+
+    assert_eq!(client.get("/api/v1/articles/1/rating").dispatch().await.status(), Status::Unauthorized);
+    for invalid in [r#"{"rating": 0}"#, r#"{"rating": 6}"#] {
+        assert_eq!(rate(2, invalid).dispatch().await.status(), Status::BadRequest, "{}", invalid);
+    }
+    assert_eq!(rate(99, r#"{"rating": 3}"#).dispatch().await.status(), Status::NotFound);
+    assert_eq!(rating(1).await, Some(4));
+    assert_eq!(rating(2).await, None);
+
+    // Rating an unread article creates its view; rating again overwrites
+    let resp = rate(2, r#"{"rating": 2}"#).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(resp.into_json::<ArticleRating>().await.unwrap().rating, Some(2));
+    assert_eq!(rate(1, r#"{"rating": 5}"#).dispatch().await.status(), Status::Ok);
+    assert_eq!((rating(1).await, rating(2).await), (Some(5), Some(2)));
+
+    let views: Vec<(i64, Option<i64>, Option<i64>)> =
+        sqlx::query_as("SELECT article_id, session_id, rating FROM user_article_views WHERE user_id = 1 ORDER BY article_id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(views, vec![(1, Some(7), Some(5)), (2, None, Some(2))]);
+
+    pool.close().await;
+}
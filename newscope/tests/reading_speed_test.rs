@@ -0,0 +1,28 @@
+use newscope::personalization::get_user_profile;
+use newscope::reading_speed::{is_enabled, record_reading, set_enabled};
+
+mod support;
+
+#[tokio::test]
+async fn test_reading_speed_calibration_is_opt_in() {
+    let (pool, _db) = support::migrated_db().await;
+    sqlx::query("INSERT INTO users (username) VALUES ('alice')").execute(&pool).await.unwrap();
+
+    // Disabled by default: observations are ignored
+    assert!(!is_enabled(&pool, 1).await.unwrap());
+    assert_eq!(record_reading(&pool, 1, 100, 40.0).await.unwrap(), None);
+    assert_eq!(get_user_profile(&pool, 1).await.unwrap().reading_speed, 250);
+
+    set_enabled(&pool, 1, true).await.unwrap();
+    assert!(is_enabled(&pool, 1).await.unwrap());
+    // 100 words in 40s = 150 wpm; the stored speed moves a fifth of the way
+    assert_eq!(record_reading(&pool, 1, 100, 40.0).await.unwrap(), Some(230));
+    assert_eq!(get_user_profile(&pool, 1).await.unwrap().reading_speed, 230);
+    // A glance does not count
+    assert_eq!(record_reading(&pool, 1, 100, 1.0).await.unwrap(), None);
+
+    set_enabled(&pool, 1, false).await.unwrap();
+    assert!(!is_enabled(&pool, 1).await.unwrap());
+    assert_eq!(record_reading(&pool, 1, 100, 40.0).await.unwrap(), None);
+
+}
@@ -0,0 +1,33 @@
+use rocket::http::{ContentType, Status};
+use serde_json::{json, Value};
+
+mod support;
+
+#[tokio::test]
+async fn test_duplicate_username_is_a_conflict() {
+    let (pool, _db) = support::migrated_db().await;
+
+    let state = support::app_state(pool.clone());
+    let client = support::api_client(state).await;
+    let register = || {
+        client
+            .post("/api/v1/register")
+            .header(ContentType::JSON)
+            .body(r#"{"username": "alice", "password": "secret"}"#)
+            .dispatch()
+    };
+
+    let first = register().await;
+    assert_eq!(first.status(), Status::Ok);
+    let body: Value = first.into_json().await.unwrap();
+    assert_eq!(body["user_id"], 1);
+
+    let second = register().await;
+    assert_eq!(second.status(), Status::Conflict);
+    assert_eq!(second.into_json::<Value>().await.unwrap(), json!({"error": "username_taken"}));
+
+    // Other database failures are still server errors
+    sqlx::query("DROP TABLE users").execute(&pool).await.unwrap();
+    assert_eq!(register().await.status(), Status::InternalServerError);
+
+}
@@ -0,0 +1,112 @@
+use newscope::llm::{LlmProvider, LlmRequest, LlmResponse, Summary};
+use newscope::personalization::{update_user_preferences, UserPreferencesUpdate};
+use newscope::personalize_worker::personalize_for_users;
+use newscope::processing::HashingEmbedder;
+use newscope::relevance_cache::RelevanceCache;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+mod support;
+
+/// Counts relevance evaluations; rates every article 0.8.
+#[derive(Default)]
+struct CountingLlm {
+    evaluations: AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl LlmProvider for CountingLlm {
+    async fn generate(&self, request: LlmRequest) -> anyhow::Result<LlmResponse> {
+        let content = if request.prompt.starts_with("Evaluate") {
+            self.evaluations.fetch_add(1, Ordering::SeqCst);
+            r#"{"score": 0.8, "reasons": ["match"]}"#
+        } else {
+            r#"{"headline": "Headline", "bullets": ["point"], "details": null}"#
+        };
+        Ok(LlmResponse {
+            content: content.to_string(),
+            usage: Default::default(),
+            model: "test".to_string(),
+        })
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        anyhow::bail!("not used")
+    }
+}
+
+fn summary(headline: &str, bullets: &[&str]) -> Summary {
+    Summary {
+        headline: headline.to_string(),
+        bullets: bullets.iter().map(|b| b.to_string()).collect(),
+        details: None,
+        usage: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_relevance_scores_are_reused_for_unchanged_profiles() {
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        // Alice and Bob share a profile, Carol follows Rust
+        "INSERT INTO users (username) VALUES ('alice'), ('bob'), ('carol')",
+        r#"INSERT INTO user_profiles (user_id, interests) VALUES (3, '["rust"]')"#,
+        "INSERT INTO articles (canonical_url) VALUES ('https://e.x/1'), ('https://e.x/2'), ('https://e.x/3'), ('https://e.x/4')",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+
+    let config: common::Config = toml::from_str(
+        "[database]\npath = \"x.db\"\n[scheduler]\ntimes = []\n[processing]\nrelevance_cache_max_distance = 0.2",
+    )
+    .unwrap();
+    let llm = Arc::new(CountingLlm::default());
+    let embedder = HashingEmbedder::default();
+    let run = |article_id: i64, summary: Summary| {
+        let (pool, llm, config, embedder) = (&pool, llm.clone(), &config, &embedder);
+        async move {
+            let cache = RelevanceCache::for_article(pool, Some(config), article_id, &summary, Some(embedder))
+                .await
+                .expect("cache enabled");
+            let before = llm.evaluations.load(Ordering::SeqCst);
+            let stored = personalize_for_users(pool, article_id, &summary, llm.clone(), "test", 1, Some(&cache))
+                .await
+                .unwrap();
+            assert_eq!(stored, 3);
+            (llm.evaluations.load(Ordering::SeqCst) - before, cache.stats())
+        }
+    };
+    let story = ["The new release speeds up compile times", "Async traits are now stable"];
+
+    // Bob reuses Alice's evaluation
+    assert_eq!(run(1, summary("Rust 2.0 released", &story)).await, (2, (1, 3)));
+    // The same summary again (another feed's copy): no LLM call at all
+    assert_eq!(run(2, summary("Rust 2.0 released", &story)).await, (0, (3, 3)));
+    // A close rewording is matched by embedding, an unrelated story isn't
+    assert_eq!(run(3, summary("Rust 2.0 is released", &story)).await, (0, (3, 3)));
+    assert_eq!(
+        run(4, summary("Storm hits the coast", &["Thousands without power", "Schools closed"])).await,
+        (2, (1, 3))
+    );
+    // Cached scores are stored like fresh ones
+    let scores: Vec<f64> = sqlx::query_scalar("SELECT relevance_score FROM user_article_summaries WHERE article_id = 3")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(scores.len(), 3);
+    assert!(scores.iter().all(|s| (s - 0.8).abs() < 1e-6), "{:?}", scores);
+
+    // Carol's new interests are a new profile version
+    let update = UserPreferencesUpdate { interests: Some(vec!["rust".into(), "go".into()]), ..Default::default() };
+    update_user_preferences(&pool, 3, &update).await.unwrap();
+    assert_eq!(run(2, summary("Rust 2.0 released", &story)).await, (1, (2, 3)));
+
+    // Disabled by config
+    let disabled: common::Config =
+        toml::from_str("[database]\npath = \"x.db\"\n[scheduler]\ntimes = []\n[processing]\nrelevance_cache = false").unwrap();
+    let summary = summary("Rust 2.0 released", &story);
+    assert!(RelevanceCache::for_article(&pool, Some(&disabled), 1, &summary, None).await.is_none());
+
+}
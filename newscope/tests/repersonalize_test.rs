@@ -0,0 +1,92 @@
+use newscope::llm::{LlmProvider, LlmRequest, LlmResponse, Summary};
+use newscope::personalize_worker::repersonalize_user;
+use std::sync::Arc;
+
+mod support;
+
+/// Rates every article as relevant and answers personalization prompts in French.
+struct FrenchLlm;
+
+#[async_trait::async_trait]
+impl LlmProvider for FrenchLlm {
+    async fn generate(&self, request: LlmRequest) -> anyhow::Result<LlmResponse> {
+        let content = if request.prompt.starts_with("Evaluate") {
+            r#"{"score": 0.9, "reasons": ["intérêt"]}"#
+        } else {
+            r#"{"headline": "Titre en français", "bullets": ["point"], "details": null}"#
+        };
+        Ok(LlmResponse {
+            content: content.to_string(),
+            usage: Default::default(),
+            model: "test".to_string(),
+        })
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        anyhow::bail!("not used")
+    }
+}
+
+#[tokio::test]
+async fn test_repersonalize_replaces_stale_summaries() {
+    let (pool, _db) = support::migrated_db().await;
+
+    sqlx::query("INSERT INTO users (username) VALUES ('alice')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO user_profiles (user_id, language) VALUES (1, 'fr')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO feeds (url) VALUES ('http://a.example/rss'), ('http://b.example/rss')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)").execute(&pool).await.unwrap();
+    for (id, feed_id) in [(1, 1), (2, 1), (3, 2)] {
+        sqlx::query("INSERT INTO articles (id, canonical_url, title) VALUES (?, ?, 'Title')")
+            .bind(id)
+            .bind(format!("http://x/{}", id))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, ?)")
+            .bind(id)
+            .bind(feed_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_summaries (article_id, headline, bullets_json) VALUES (?, 'Headline', '[\"point\"]')")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    // Stale English summary from before the language change
+    sqlx::query(
+        "INSERT INTO user_article_summaries
+         (user_id, article_id, relevance_score, personalized_headline, personalized_bullets, language)
+         VALUES (1, 1, 0.8, 'English headline', '[]', 'en')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let report = repersonalize_user(&pool, 1, Arc::new(FrenchLlm), "test").await.unwrap();
+    assert_eq!(report.removed, 1);
+    // Article 3 comes from a feed the user does not follow
+    assert_eq!(report.evaluated, 2);
+    assert_eq!(report.personalized, 2);
+
+    let rows: Vec<(i64, String, String)> = sqlx::query_as(
+        "SELECT article_id, personalized_headline, language FROM user_article_summaries WHERE user_id = 1 ORDER BY article_id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            (1, "Titre en français".to_string(), "fr".to_string()),
+            (2, "Titre en français".to_string(), "fr".to_string()),
+        ]
+    );
+
+}
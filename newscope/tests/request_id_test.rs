@@ -0,0 +1,69 @@
+use newscope::request_id::{self, RequestIds};
+use newscope::server;
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::asynchronous::Client;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+mod support;
+
+/// Log output shared with the test.
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_request_ids_are_logged_and_echoed() {
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt().with_ansi(false).with_writer(move || writer.clone()).finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let (pool, _db) = support::migrated_db().await;
+    let state = support::app_state(pool);
+    let rocket = rocket::build()
+        .manage(state)
+        .mount("/", request_id::traced(server::api_routes()))
+        .attach(RequestIds);
+    let client = Client::untracked(rocket).await.expect("rocket client");
+    let register = |id: Option<&'static str>| {
+        let mut req = client
+            .post("/api/v1/register")
+            .header(ContentType::JSON)
+            .body(r#"{"username": "alice", "password": "secret"}"#);
+        if let Some(id) = id {
+            req = req.header(Header::new(request_id::HEADER, id));
+        }
+        req.dispatch()
+    };
+
+    // A generated id
+    let res = register(None).await;
+    assert_eq!(res.status(), Status::Ok);
+    let generated = res.headers().get_one(request_id::HEADER).unwrap().to_string();
+    assert_eq!(generated.len(), 16);
+
+    // The client's id, in the response and in what the handler logged
+    let res = register(Some("edge-42.a")).await;
+    assert_eq!(res.status(), Status::Conflict);
+    assert_eq!(res.headers().get_one(request_id::HEADER), Some("edge-42.a"));
+    let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    let line = output.lines().find(|l| l.contains("is taken")).expect("handler log");
+    assert!(line.contains("request{id=edge-42.a}"), "{}", line);
+
+    // Unusable ids are replaced
+    let res = register(Some("bad id\t")).await;
+    let replaced = res.headers().get_one(request_id::HEADER).unwrap();
+    assert_ne!(replaced, "bad id\t");
+    assert_eq!(replaced.len(), 16);
+
+}
@@ -0,0 +1,31 @@
+use newscope::ingestion::RobotsCache;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_robots_cache_fetches_once_per_site() {
+    let mut server = mockito::Server::new_async().await;
+    let robots = server
+        .mock("GET", "/robots.txt")
+        .with_status(200)
+        .with_body("User-agent: Newscope\nDisallow: /members/\n")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let cache = RobotsCache::new(Duration::from_secs(3600), Duration::from_secs(5));
+    assert!(cache.is_allowed(&format!("{}/feed.xml", server.url())).await.unwrap());
+    assert!(!cache.is_allowed(&format!("{}/members/feed.xml", server.url())).await.unwrap());
+    robots.assert_async().await;
+
+    // Expired entries are downloaded again
+    let expired = RobotsCache::new(Duration::ZERO, Duration::from_secs(5));
+    let refetch = server
+        .mock("GET", "/robots.txt")
+        .with_status(404)
+        .expect(2)
+        .create_async()
+        .await;
+    assert!(expired.is_allowed(&format!("{}/members/a", server.url())).await.unwrap());
+    assert!(expired.is_allowed(&format!("{}/members/b", server.url())).await.unwrap());
+    refetch.assert_async().await;
+}
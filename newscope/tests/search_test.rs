@@ -0,0 +1,85 @@
+use newscope::search::SearchHit;
+use newscope::server;
+use rocket::http::Status;
+
+mod support;
+
+#[tokio::test]
+async fn test_full_text_search() {
+    let (pool, _db) = support::migrated_db().await;
+
+    let (_, key) = support::user_with_key(&pool, "alice").await;
+    for stmt in [
+        "INSERT INTO feeds (url, title) VALUES ('https://a.example/rss', 'Tech Daily')",
+        "INSERT INTO feeds (url, title) VALUES ('https://b.example/rss', 'Other')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)",
+        "INSERT INTO articles (canonical_url, title, content) VALUES
+            ('https://a.example/1', 'Rust 2.0 released', 'The new edition changes the borrow checker.'),
+            ('https://a.example/2', 'Climate summit', 'Leaders discuss climate change and energy.'),
+            ('https://a.example/3', 'Change of climate at the club', 'The coach left after a bad season.'),
+            ('https://b.example/1', 'Rust belt factories', 'Unsubscribed feed.')",
+        "INSERT INTO article_occurrences (article_id, feed_id) VALUES (1, 1), (2, 1), (3, 1), (4, 2)",
+        r#"INSERT INTO article_summaries (article_id, headline, bullets_json)
+            VALUES (2, 'World leaders meet', '["Emission targets raised"]')"#,
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    // Rows written before the index existed are picked up at startup
+    sqlx::query("DELETE FROM articles_fts").execute(&pool).await.unwrap();
+    server::ensure_schema(&pool).await.expect("backfill");
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let search = |q: &str| {
+        let path = format!("/api/v1/search?q={}", urlencoding(q));
+        let (client, key) = (&client, &key);
+        async move {
+            let resp = client.get(path).header(support::api_key(key)).dispatch().await;
+            assert_eq!(resp.status(), Status::Ok);
+            resp.into_json::<Vec<SearchHit>>().await.unwrap()
+        }
+    };
+
+    // Only subscribed feeds
+    let hits = search("rust").await;
+    assert_eq!(hits.iter().map(|h| h.article_id).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(hits[0].feed_title.as_deref(), Some("Tech Daily"));
+    assert!(hits[0].snippet.contains("<mark>Rust</mark>"), "{}", hits[0].snippet);
+
+    // Words match anywhere, a phrase only in order
+    let mut ids: Vec<i64> = search("climate change").await.iter().map(|h| h.article_id).collect();
+    ids.sort();
+    assert_eq!(ids, vec![2, 3]);
+    let hits = search("\"climate change\"").await;
+    assert_eq!(hits.iter().map(|h| h.article_id).collect::<Vec<_>>(), vec![2]);
+
+    // Summary bullets are indexed, and kept in sync when summaries are written
+    assert_eq!(search("emission").await.len(), 1);
+    assert!(search("ownership").await.is_empty());
+    let summary = newscope::llm::Summary {
+        headline: "Rust 2.0 is out".to_string(),
+        bullets: vec!["Ownership rules relaxed".to_string()],
+        details: None,
+        usage: Default::default(),
+    };
+    newscope::storage::store_article_summary(&pool, 1, &summary, "test").await.unwrap();
+    assert_eq!(search("ownership").await.iter().map(|h| h.article_id).collect::<Vec<_>>(), vec![1]);
+
+    // Query syntax is not interpreted
+    assert!(search("title:rust OR").await.is_empty());
+    let resp = client.get("/api/v1/search?q=%20%22%22").header(support::api_key(&key)).dispatch().await;
+    assert_eq!(resp.status(), Status::BadRequest);
+
+    // Only the caller's own subscriptions are searched
+    assert_eq!(client.get("/api/v1/search?q=rust&user_id=1").dispatch().await.status(), Status::Unauthorized);
+    let (_, bob) = support::user_with_key(&pool, "bob").await;
+    let resp = client.get("/api/v1/search?q=rust&user_id=1").header(support::api_key(&bob)).dispatch().await;
+    assert_eq!(resp.status(), Status::Forbidden);
+    let resp = client.get("/api/v1/search?q=rust").header(support::api_key(&bob)).dispatch().await;
+    assert!(resp.into_json::<Vec<SearchHit>>().await.unwrap().is_empty());
+
+    pool.close().await;
+}
+
+fn urlencoding(q: &str) -> String {
+    q.replace(' ', "%20").replace('"', "%22").replace(':', "%3A")
+}
@@ -0,0 +1,61 @@
+use rocket::http::{ContentType, Header, Status};
+
+mod support;
+
+#[tokio::test]
+async fn test_delete_and_rename_session() {
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice'), ('bob')",
+        "INSERT INTO sessions (id, user_id) VALUES (1, 1), (2, 1), (3, 2)",
+        // Session 2 replays session 1's review
+        "INSERT INTO summaries (id, session_id, summary_text) VALUES (10, 1, 'review'), (11, 3, 'other')",
+        "UPDATE sessions SET digest_summary_id = 10 WHERE id IN (1, 2)",
+        "UPDATE sessions SET digest_summary_id = 11 WHERE id = 3",
+        "INSERT INTO chat_messages (session_id, author, message) VALUES (1, 'user', 'hi'), (2, 'user', 'hey'), (3, 'user', 'yo')",
+        "INSERT INTO session_articles (session_id, article_id, title, summary) VALUES (1, 1, 'T', 'S'), (3, 1, 'T', 'S')",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+
+    let state = support::app_state(pool.clone());
+    let client = support::api_client(state).await;
+    let auth = || Header::new("X-API-Key", key.clone());
+    let count = |sql: &'static str| {
+        let pool = pool.clone();
+        async move { sqlx::query_scalar::<_, i64>(sql).fetch_one(&pool).await.unwrap() }
+    };
+
+    let res = client
+        .patch("/api/v1/sessions/1")
+        .header(auth())
+        .header(ContentType::JSON)
+        .body(r#"{"title": "Morning"}"#)
+        .dispatch()
+        .await;
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(count("SELECT COUNT(*) FROM sessions WHERE title = 'Morning'").await, 1);
+
+    // Bob's session is not found for Alice, and is left alone
+    for req in [client.delete("/api/v1/sessions/3"), client.delete("/api/v1/sessions/99")] {
+        assert_eq!(req.header(auth()).dispatch().await.status(), Status::NotFound);
+    }
+    let rename = client.patch("/api/v1/sessions/3").header(auth()).header(ContentType::JSON).body(r#"{"title": "x"}"#);
+    assert_eq!(rename.dispatch().await.status(), Status::NotFound);
+    assert_eq!(client.delete("/api/v1/sessions/1").dispatch().await.status(), Status::Unauthorized);
+
+    assert_eq!(client.delete("/api/v1/sessions/1").header(auth()).dispatch().await.status(), Status::NoContent);
+    assert_eq!(count("SELECT COUNT(*) FROM sessions").await, 2);
+    assert_eq!(count("SELECT COUNT(*) FROM chat_messages WHERE session_id = 1").await, 0);
+    assert_eq!(count("SELECT COUNT(*) FROM session_articles WHERE session_id = 1").await, 0);
+    // Still session 2's digest, now owned by it
+    assert_eq!(count("SELECT session_id FROM summaries WHERE id = 10").await, 2);
+
+    assert_eq!(client.delete("/api/v1/sessions/2").header(auth()).dispatch().await.status(), Status::NoContent);
+    assert_eq!(client.delete("/api/v1/sessions/1").header(auth()).dispatch().await.status(), Status::NotFound);
+    assert_eq!(count("SELECT COUNT(*) FROM summaries").await, 1);
+    assert_eq!(count("SELECT COUNT(*) FROM chat_messages").await, 1);
+    assert_eq!(count("SELECT COUNT(*) FROM session_articles").await, 1);
+
+}
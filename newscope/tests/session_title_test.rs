@@ -0,0 +1,61 @@
+use newscope::llm::{LlmProvider, LlmRequest, LlmResponse, Summary};
+use newscope::sessions::title::generate_session_title;
+use newscope::sessions::{create_session, get_session, update_session_title};
+
+mod support;
+
+/// Answers with a fixed title, or fails when there is none.
+struct TitleLlm(Option<&'static str>);
+
+#[async_trait::async_trait]
+impl LlmProvider for TitleLlm {
+    async fn generate(&self, _request: LlmRequest) -> anyhow::Result<LlmResponse> {
+        let Some(title) = self.0 else {
+            anyhow::bail!("provider unavailable")
+        };
+        Ok(LlmResponse {
+            content: title.to_string(),
+            usage: Default::default(),
+            model: "test".to_string(),
+        })
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, _text: &str) -> anyhow::Result<Vec<f32>> {
+        anyhow::bail!("not used")
+    }
+}
+
+#[tokio::test]
+async fn test_sessions_are_titled_once() {
+    let (pool, _db) = support::migrated_db().await;
+    sqlx::query("INSERT INTO users (username) VALUES ('alice')").execute(&pool).await.unwrap();
+    let title_of = |id: i64| {
+        let pool = pool.clone();
+        async move { get_session(&pool, id).await.unwrap().title }
+    };
+
+    let session = create_session(&pool, 1, None).await.unwrap();
+    let llm = TitleLlm(Some("\"Elections and Energy Prices.\""));
+    let title = generate_session_title(&pool, &llm, session.id, "- Elections\n- Energy", Some("en")).await.unwrap();
+    assert_eq!(title.as_deref(), Some("Elections and Energy Prices"));
+    assert_eq!(title_of(session.id).await, title);
+    // Only once: the first exchange doesn't retitle it
+    let again = TitleLlm(Some("Something else"));
+    assert_eq!(generate_session_title(&pool, &again, session.id, "User: hi", None).await.unwrap(), None);
+    assert_eq!(title_of(session.id).await.as_deref(), Some("Elections and Energy Prices"));
+
+    // LLM failure: timestamp title
+    let failed = create_session(&pool, 1, None).await.unwrap();
+    let title = generate_session_title(&pool, &TitleLlm(None), failed.id, "User: hi", None).await.unwrap();
+    assert!(title.as_deref().unwrap().starts_with("Session of "), "{:?}", title);
+    assert_eq!(title_of(failed.id).await, title);
+
+    // A title set by the user is kept
+    let named = create_session(&pool, 1, None).await.unwrap();
+    update_session_title(&pool, named.id, "Mine").await.unwrap();
+    assert_eq!(generate_session_title(&pool, &llm, named.id, "User: hi", None).await.unwrap(), None);
+    assert_eq!(title_of(named.id).await.as_deref(), Some("Mine"));
+
+}
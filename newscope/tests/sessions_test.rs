@@ -1,4 +1,7 @@
-use newscope::sessions::{create_session, get_messages, get_session, list_sessions, store_message};
+use newscope::sessions::{
+    create_session, get_messages, get_session, get_session_articles, list_sessions, store_message,
+    store_session_article, ArticleContext,
+};
 use sqlx::sqlite::SqlitePoolOptions;
 
 async fn setup_test_db() -> sqlx::SqlitePool {
@@ -34,7 +37,8 @@ async fn setup_test_db() -> sqlx::SqlitePool {
             user_id INTEGER NOT NULL,
             start_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
             duration_requested_seconds INTEGER,
-            digest_summary_id INTEGER
+            digest_summary_id INTEGER,
+            title TEXT
         );
         "#,
     )
@@ -57,6 +61,25 @@ async fn setup_test_db() -> sqlx::SqlitePool {
     .await
     .unwrap();
 
+    sqlx::query(
+        r#"
+        CREATE TABLE session_articles (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id INTEGER NOT NULL,
+            article_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            details TEXT,
+            also_covered_by TEXT,
+            created_at TIMESTAMP DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+            UNIQUE(session_id, article_id)
+        );
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
     pool
 }
 
@@ -154,3 +177,33 @@ async fn test_multiple_sessions() {
     assert_eq!(user1_sessions.len(), 2);
     assert_eq!(user2_sessions.len(), 1);
 }
+
+#[tokio::test]
+async fn test_session_articles_are_persisted() {
+    let pool = setup_test_db().await;
+    let session = create_session(&pool, 1, Some(600)).await.unwrap();
+    let other = create_session(&pool, 1, Some(600)).await.unwrap();
+
+    let article = |id: i64, title: &str| ArticleContext {
+        id,
+        title: title.to_string(),
+        summary: format!("{} summary", title),
+        content: None,
+        also_covered_by: Vec::new(),
+    };
+    store_session_article(&pool, session.id, &article(7, "Seven")).await.unwrap();
+    let mut covered = article(3, "Three");
+    covered.content = Some("details".to_string());
+    covered.also_covered_by = vec!["Other Feed".to_string()];
+    store_session_article(&pool, session.id, &covered).await.unwrap();
+    store_session_article(&pool, other.id, &article(9, "Nine")).await.unwrap();
+    // Sent again (e.g. refined): updated in place, order kept
+    store_session_article(&pool, session.id, &article(7, "Seven bis")).await.unwrap();
+
+    let articles = get_session_articles(&pool, session.id).await.unwrap();
+    assert_eq!(articles.iter().map(|a| a.id).collect::<Vec<_>>(), vec![7, 3]);
+    assert_eq!(articles[0].title, "Seven bis");
+    assert_eq!(articles[1].content.as_deref(), Some("details"));
+    assert_eq!(articles[1].also_covered_by, vec!["Other Feed".to_string()]);
+    assert!(get_session_articles(&pool, 42).await.unwrap().is_empty());
+}
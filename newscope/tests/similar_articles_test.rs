@@ -0,0 +1,93 @@
+use newscope::processing::SimilarArticle;
+use rocket::http::Status;
+
+mod support;
+
+fn vector(v: [f32; 4]) -> Vec<u8> {
+    v.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+#[tokio::test]
+async fn test_similar_articles_by_embedding() {
+    let (pool, _db) = support::migrated_db_with_dim(4).await;
+    let (alice_id, alice) = support::user_with_key(&pool, "alice").await;
+    let (bob_id, _) = support::user_with_key(&pool, "bob").await;
+    sqlx::query("INSERT INTO feeds (url) VALUES ('https://a.example/rss'), ('https://b.example/rss')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    for (user_id, feed_id) in [(alice_id, 1), (bob_id, 2)] {
+        sqlx::query("INSERT INTO subscriptions (user_id, feed_id) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(feed_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    // Article 6 is only in Bob's feed
+    for (i, (title, feed_id)) in [
+        ("Rust 2.0", 1),
+        ("Rust compiler", 1),
+        ("Football", 1),
+        ("Cooking", 1),
+        ("Not embedded", 1),
+        ("Rust internals", 2),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let article_id = sqlx::query("INSERT INTO articles (canonical_url, title) VALUES (?, ?)")
+            .bind(format!("https://example.com/{}", i + 1))
+            .bind(title)
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, ?)")
+            .bind(article_id)
+            .bind(feed_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    for (id, v) in [
+        (1, [1.0, 0.0, 0.0, 0.0]),
+        (2, [0.9, 0.1, 0.0, 0.0]),
+        (3, [0.0, 1.0, 0.0, 0.0]),
+        (4, [0.5, 0.0, 0.5, 0.0]),
+        (6, [1.0, 0.01, 0.0, 0.0]),
+    ] {
+        sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (?, ?)")
+            .bind(id)
+            .bind(vector(v))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let get = |path: &'static str| client.get(path).header(support::api_key(&alice));
+
+    assert_eq!(client.get("/api/v1/articles/1/similar").dispatch().await.status(), Status::Unauthorized);
+
+    let resp = get("/api/v1/articles/1/similar?k=2").dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let similar: Vec<SimilarArticle> = resp.into_json().await.unwrap();
+    let ids: Vec<i64> = similar.iter().map(|s| s.article_id).collect();
+    assert_eq!(ids, [2, 4]);
+    assert_eq!(similar[0].headline.as_deref(), Some("Rust compiler"));
+    assert!(similar[0].distance < similar[1].distance);
+
+    // No embedding yet: nothing related, not an error
+    let resp = get("/api/v1/articles/5/similar").dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    assert!(resp.into_json::<Vec<SimilarArticle>>().await.unwrap().is_empty());
+
+    // Another user's article, or no such article
+    for path in ["/api/v1/articles/6/similar", "/api/v1/articles/999/similar"] {
+        assert_eq!(get(path).dispatch().await.status(), Status::NotFound);
+    }
+
+    pool.close().await;
+}
@@ -4,7 +4,7 @@
 #[tokio::test]
 async fn test_storage_module_loads() {
     // Simple test to ensure module compiles
-    assert!(true);
+    let _ = newscope::storage::store_feed_items;
 }
 
 /*
@@ -14,3 +14,86 @@ async fn test_article_deduplication() {
     // ... (complex Entry construction)
 }
 */
+
+#[tokio::test]
+async fn test_full_content_feed_skips_scraping() {
+    let mut server_mock = mockito::Server::new_async().await;
+    let page = server_mock
+        .mock("GET", "/article")
+        .with_status(200)
+        .with_body("<html><body><article>Scraped version</article></body></html>")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let db_path = format!("test_db_{}.sqlite", uuid::Uuid::new_v4());
+    let pool = common::init_db_pool(&db_path).await.expect("init pool");
+    newscope::server::ensure_schema(&pool).await.expect("ensure schema");
+    // ensure_schema's articles table predates first_seen_at (added by migrations)
+    sqlx::query("ALTER TABLE articles ADD COLUMN first_seen_at TIMESTAMP").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO feeds (url, full_content) VALUES ('http://a.example/rss', 1)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Short body that would normally trigger the scrape fallback
+    let xml = format!(
+        r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>t</title>
+<item><title>Short</title><link>{}/article</link><description>Complete, if brief.</description></item>
+</channel></rss>"#,
+        server_mock.url()
+    );
+    let entries = feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+
+    let ids = newscope::storage::store_feed_items(&pool, 1, &entries, None).await.unwrap();
+    assert_eq!(ids.len(), 1);
+    let content: String = sqlx::query_scalar("SELECT content FROM articles WHERE id = ?")
+        .bind(ids[0])
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(content, "Complete, if brief.");
+    page.assert_async().await;
+
+    std::fs::remove_file(&db_path).ok();
+}
+
+#[tokio::test]
+async fn test_article_language_is_detected() {
+    let db_path = format!("test_db_{}.sqlite", uuid::Uuid::new_v4());
+    let pool = common::init_db_pool(&db_path).await.expect("init pool");
+    newscope::server::ensure_schema(&pool).await.expect("ensure schema");
+    sqlx::query("ALTER TABLE articles ADD COLUMN first_seen_at TIMESTAMP").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO feeds (url, full_content) VALUES ('http://a.example/rss', 1)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let xml = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>t</title>
+<item><title>Le budget présenté en conseil des ministres</title><link>http://a.example/fr</link>
+<description>&lt;p&gt;Le gouvernement a présenté mercredi son projet de budget pour l'année prochaine, qui prévoit une baisse des dépenses publiques.&lt;/p&gt;</description></item>
+<item><title>Budget presented to the cabinet</title><link>http://a.example/en</link>
+<description>The government presented its budget for next year on Wednesday, which plans a reduction in public spending.</description></item>
+<item><title>OK</title><link>http://a.example/short</link><description>42</description></item>
+</channel></rss>"#;
+    let entries = feed_rs::parser::parse(xml.as_bytes()).unwrap().entries;
+    newscope::storage::store_feed_items(&pool, 1, &entries, None).await.unwrap();
+
+    let languages: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT canonical_url, language FROM articles ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        languages,
+        vec![
+            ("http://a.example/fr".to_string(), Some("fr".to_string())),
+            ("http://a.example/en".to_string(), Some("en".to_string())),
+            ("http://a.example/short".to_string(), None),
+        ]
+    );
+
+    std::fs::remove_file(&db_path).ok();
+}
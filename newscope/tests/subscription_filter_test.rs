@@ -1,23 +1,27 @@
+use newscope::press_review::{
+    catch_up_status, generate_press_review, select_review_articles, CatchUpLimit, ReviewGroupBy, ScoringWeights,
+    ReviewSelectionOptions,
+};
 use sqlx::sqlite::SqlitePoolOptions;
-use sqlx::Row;
+use sqlx::SqlitePool;
 use std::time::Duration;
 
-/// Integration test that ensures only articles coming from feeds the user is subscribed to
-/// are selected by the same SQL used by the websocket session logic.
-///
-/// The test creates a minimal in-memory SQLite schema, inserts:
-/// - two feeds (A and B)
-/// - a single user subscribed only to feed A
-/// - two articles (one occurring in feed A, one in feed B)
-/// - personalized summaries for both articles for the user (both marked relevant)
-///
-/// Then it runs the selection query used in the websocket code and asserts that only the
-/// article coming from the subscribed feed A is returned.
-#[tokio::test]
-async fn test_subscription_filter_only_subscribed_feeds() {
-    // Create in-memory SQLite pool
+fn opts(limit: i64, serendipity_fraction: f64) -> ReviewSelectionOptions {
+    ReviewSelectionOptions {
+        limit,
+        serendipity_fraction,
+        scoring: None,
+        max_age_hours: None,
+        near_duplicate_distance: None,
+    }
+}
+
+/// Minimal in-memory schema mirroring the tables the selection query touches.
+async fn setup_pool() -> SqlitePool {
+    // Create in-memory SQLite pool (single connection: each connection gets its own memory DB)
     let pool = SqlitePoolOptions::new()
-        .connect_timeout(Duration::from_secs(5))
+        .max_connections(1)
+        .acquire_timeout(Duration::from_secs(5))
         .connect("sqlite::memory:")
         .await
         .expect("Failed to create in-memory sqlite pool");
@@ -41,6 +45,36 @@ async fn test_subscription_filter_only_subscribed_feeds() {
     .await
     .unwrap();
 
+    sqlx::query(
+        r#"
+        CREATE TABLE user_profiles (
+            user_id INTEGER PRIMARY KEY,
+            language TEXT,
+            complexity_level TEXT,
+            reading_speed INTEGER,
+            interests TEXT
+        );
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE user_preferences (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL,
+            preference_type TEXT,
+            preference_key TEXT,
+            preference_value REAL
+        );
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
     sqlx::query(
         r#"
         CREATE TABLE feeds (
@@ -74,7 +108,8 @@ async fn test_subscription_filter_only_subscribed_feeds() {
         CREATE TABLE articles (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             canonical_url TEXT NOT NULL,
-            first_seen_at TEXT
+            first_seen_at TEXT,
+            language TEXT
         );
         "#,
     )
@@ -95,6 +130,19 @@ async fn test_subscription_filter_only_subscribed_feeds() {
     .await
     .unwrap();
 
+    sqlx::query(
+        r#"
+        CREATE TABLE article_summaries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            article_id INTEGER NOT NULL UNIQUE,
+            categories TEXT
+        );
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
     sqlx::query(
         r#"
         CREATE TABLE user_article_summaries (
@@ -104,6 +152,7 @@ async fn test_subscription_filter_only_subscribed_feeds() {
             personalized_headline TEXT,
             personalized_bullets TEXT,
             personalized_details TEXT,
+            language TEXT,
             relevance_score REAL,
             is_relevant INTEGER DEFAULT 0
         );
@@ -129,6 +178,24 @@ async fn test_subscription_filter_only_subscribed_feeds() {
     .await
     .unwrap();
 
+    pool
+}
+
+/// Integration test that ensures only articles coming from feeds the user is subscribed to
+/// are selected by the same SQL used by the websocket session logic.
+///
+/// The test creates a minimal in-memory SQLite schema, inserts:
+/// - two feeds (A and B)
+/// - a single user subscribed only to feed A
+/// - two articles (one occurring in feed A, one in feed B)
+/// - personalized summaries for both articles for the user (both marked relevant)
+///
+/// Then it runs the selection query used in the websocket code and asserts that only the
+/// article coming from the subscribed feed A is returned.
+#[tokio::test]
+async fn test_subscription_filter_only_subscribed_feeds() {
+    let pool = setup_pool().await;
+
     // Insert a user
     sqlx::query("INSERT INTO users (username) VALUES (?)")
         .bind("alice")
@@ -229,50 +296,20 @@ async fn test_subscription_filter_only_subscribed_feeds() {
 
     // No entries in user_article_views (uav IS NULL)
 
-    // Now run the same selection query used in websocket.rs but bound to user_id = 1 and session_id = 999
-    let estimated_limit = 10_i64;
-    let rows = sqlx::query(
-        r#"
-        SELECT
-            uas.article_id,
-            uas.personalized_headline,
-            uas.personalized_bullets,
-            uas.personalized_details,
-            uas.relevance_score,
-            a.canonical_url,
-            f.title as feed_title
-         FROM user_article_summaries uas
-         JOIN articles a ON uas.article_id = a.id
-         JOIN article_occurrences ao ON a.id = ao.article_id
-         JOIN subscriptions s ON s.feed_id = ao.feed_id AND s.user_id = ?
-         JOIN feeds f ON ao.feed_id = f.id
-         LEFT JOIN user_article_views uav ON uas.user_id = uav.user_id AND uas.article_id = uav.article_id AND uav.session_id = ?
-         WHERE uas.user_id = ?
-           AND uas.is_relevant = 1
-           AND uav.id IS NULL
-         GROUP BY uas.article_id
-         ORDER BY uas.relevance_score DESC, a.first_seen_at DESC
-         LIMIT ?
-         "#,
-    )
-    .bind(1_i64) // s.user_id
-    .bind(999_i64) // uav.session_id
-    .bind(1_i64) // uas.user_id
-    .bind(estimated_limit)
-    .fetch_all(&pool)
-    .await
-    .expect("Query failed");
+    // Now run the shared review selection for user_id = 1
+    let articles = select_review_articles(&pool, 1, &opts(10, 0.0))
+        .await
+        .expect("Query failed");
 
     // We expect only one row (the article from Feed A)
     assert_eq!(
-        rows.len(),
+        articles.len(),
         1,
         "Expected exactly one article from subscribed feed"
     );
 
-    let row = &rows[0];
-    let article_id: i64 = row.get("article_id");
-    let feed_title: String = row.get("feed_title");
+    let article_id = articles[0].id;
+    let feed_title = articles[0].feed_title.clone().unwrap_or_default();
 
     assert_eq!(
         article_id, 1,
@@ -283,3 +320,516 @@ async fn test_subscription_filter_only_subscribed_feeds() {
         "Returned article should come from Feed A"
     );
 }
+
+/// Ties on relevance and first_seen_at must resolve by article id, and an article
+/// present in several subscribed feeds must report the same feed every time.
+#[tokio::test]
+async fn test_selection_is_deterministic_on_ties() {
+    let pool = setup_pool().await;
+
+    sqlx::query("INSERT INTO users (username) VALUES ('bob')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    for (url, title) in [("http://a.example/rss", "Feed A"), ("http://b.example/rss", "Feed B")] {
+        sqlx::query("INSERT INTO feeds (url, title) VALUES (?, ?)")
+            .bind(url)
+            .bind(title)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    for feed_id in [1_i64, 2] {
+        sqlx::query("INSERT INTO subscriptions (user_id, feed_id) VALUES (1, ?)")
+            .bind(feed_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    // Three articles with identical score and timestamp, inserted so that the
+    // last one (id 3) appears in both feeds, feed B first.
+    for i in 1..=3_i64 {
+        sqlx::query("INSERT INTO articles (canonical_url, first_seen_at) VALUES (?, '2025-01-01T00:00:00Z')")
+            .bind(format!("http://example.com/{}", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries
+                (article_id, user_id, personalized_headline, personalized_bullets, relevance_score, is_relevant)
+             VALUES (?, 1, 'h', '[]', 0.5, 1)",
+        )
+        .bind(i)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    for (article_id, feed_id) in [(3_i64, 2_i64), (2, 1), (1, 1), (3, 1)] {
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, ?)")
+            .bind(article_id)
+            .bind(feed_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let articles = select_review_articles(&pool, 1, &opts(10, 0.0))
+        .await
+        .expect("Query failed");
+
+    let ids: Vec<i64> = articles.iter().map(|a| a.id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(articles[2].feed_title.as_deref(), Some("Feed A"));
+}
+
+/// Serendipity candidates are the low-relevance articles the main selection skips.
+#[tokio::test]
+async fn test_serendipity_candidates_are_below_threshold() {
+    let pool = setup_pool().await;
+
+    sqlx::query("INSERT INTO users (username) VALUES ('carol')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO feeds (url, title) VALUES ('http://a.example/rss', 'Feed A')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    for (i, relevant) in [(1_i64, 1_i64), (2, 0)] {
+        sqlx::query("INSERT INTO articles (canonical_url, first_seen_at) VALUES (?, '2025-01-01T00:00:00Z')")
+            .bind(format!("http://example.com/{}", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, 1)")
+            .bind(i)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries
+                (article_id, user_id, personalized_headline, personalized_bullets, relevance_score, is_relevant)
+             VALUES (?, 1, 'h', '[]', 0.2, ?)",
+        )
+        .bind(i)
+        .bind(relevant)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    // Two slots at 50% serendipity: one relevant pick, then one low-relevance pick
+    let articles = select_review_articles(&pool, 1, &opts(2, 0.5))
+        .await
+        .expect("Query failed");
+
+    let picks: Vec<(i64, bool)> = articles.iter().map(|a| (a.id, a.serendipity)).collect();
+    assert_eq!(picks, vec![(1, false), (2, true)]);
+}
+
+/// With recency weighting, a fresh article can overtake a slightly more relevant
+/// month-old one; without it, relevance alone decides.
+#[tokio::test]
+async fn test_recency_decay_reranks_selection() {
+    let pool = setup_pool().await;
+
+    sqlx::query("INSERT INTO users (username) VALUES ('erin')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO feeds (url, title) VALUES ('http://a.example/rss', 'Feed A')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // (id, relevance, age)
+    for (i, score, age) in [(1_i64, 0.85, "-30 days"), (2, 0.8, "-2 hours"), (3, 0.5, "-60 days")] {
+        sqlx::query(
+            "INSERT INTO articles (canonical_url, first_seen_at)
+             VALUES (?, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?))",
+        )
+        .bind(format!("http://example.com/{}", i))
+        .bind(age)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, 1)")
+            .bind(i)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries
+                (article_id, user_id, personalized_headline, personalized_bullets, relevance_score, is_relevant)
+             VALUES (?, 1, 'h', '[]', ?, 1)",
+        )
+        .bind(i)
+        .bind(score)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let ids = |articles: Vec<newscope::press_review::ScoredArticle>| articles.iter().map(|a| a.id).collect::<Vec<_>>();
+
+    let plain = select_review_articles(&pool, 1, &opts(2, 0.0)).await.unwrap();
+    assert_eq!(ids(plain), vec![1, 2]);
+
+    let scoring = ScoringWeights { w_src: 0.0, ..Default::default() };
+    let decayed = select_review_articles(&pool, 1, &opts(2, 0.0).with_scoring(Some(scoring)))
+        .await
+        .unwrap();
+    assert_eq!(ids(decayed), vec![2, 1]);
+
+    // Without the recency term, relevance decides again
+    let relevance_first = ScoringWeights { w_recency: 0.0, ..scoring };
+    let ranked = select_review_articles(&pool, 1, &opts(2, 0.0).with_scoring(Some(relevance_first)))
+        .await
+        .unwrap();
+    assert_eq!(ids(ranked), vec![1, 2]);
+}
+
+/// `w_src` favours articles from feeds the user weighs higher.
+#[tokio::test]
+async fn test_source_weight_reranks_selection() {
+    let pool = setup_pool().await;
+
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('fay')",
+        "INSERT INTO feeds (url, title) VALUES ('http://a.example/rss', 'Feed A')",
+        "INSERT INTO feeds (url, title) VALUES ('http://b.example/rss', 'Feed B')",
+        "INSERT INTO subscriptions (user_id, feed_id, weight) VALUES (1, 1, 0)",
+        // The top weight: normalized to 1.0, so w_src adds up to w_src
+        "INSERT INTO subscriptions (user_id, feed_id, weight) VALUES (1, 2, 10)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    // (id, feed, relevance), all first seen together
+    for (id, feed_id, score) in [(1_i64, 1_i64, 0.9), (2, 2, 0.7), (3, 1, 0.6)] {
+        sqlx::query("INSERT INTO articles (canonical_url, first_seen_at) VALUES (?, '2025-01-01T00:00:00Z')")
+            .bind(format!("http://example.com/{}", id))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, ?)")
+            .bind(id)
+            .bind(feed_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries
+                (article_id, user_id, personalized_headline, personalized_bullets, relevance_score, is_relevant)
+             VALUES (?, 1, 'h', '[]', ?, 1)",
+        )
+        .bind(id)
+        .bind(score)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let ids = |articles: Vec<newscope::press_review::ScoredArticle>| articles.iter().map(|a| a.id).collect::<Vec<_>>();
+    let ranked = |w_src: f64| {
+        let scoring = ScoringWeights { w_src, ..Default::default() };
+        let pool = pool.clone();
+        async move { ids(select_review_articles(&pool, 1, &opts(3, 0.0).with_scoring(Some(scoring))).await.unwrap()) }
+    };
+    assert_eq!(ranked(0.0).await, vec![1, 2, 3]);
+    assert_eq!(ranked(0.5).await, vec![2, 1, 3]);
+}
+
+/// The same story from three feeds fills one review slot; the copies are
+/// recorded on the best-scoring article and the freed slot goes to another story.
+#[tokio::test]
+async fn test_near_duplicates_are_collapsed() {
+    let pool = setup_pool().await;
+
+    // A plain table is enough: the selection only reads embeddings back
+    for stmt in [
+        "CREATE TABLE vec_articles (article_id INTEGER PRIMARY KEY, embedding BLOB)",
+        "INSERT INTO users (username) VALUES ('dana')",
+        "INSERT INTO feeds (url, title) VALUES ('http://a.example/rss', 'Feed A')",
+        "INSERT INTO feeds (url, title) VALUES ('http://b.example/rss', 'Feed B')",
+        "INSERT INTO feeds (url, title) VALUES ('http://c.example/rss', 'Feed C')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 2)",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 3)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+
+    // (id, feed, relevance, embedding): 1-3 are one wire story, 4 is different
+    let articles: [(i64, i64, f64, [f32; 3]); 4] = [
+        (1, 2, 0.9, [1.0, 0.0, 0.0]),
+        (2, 1, 0.85, [0.98, 0.1, 0.0]),
+        (3, 3, 0.8, [1.0, 0.0, 0.05]),
+        (4, 1, 0.5, [0.0, 1.0, 0.0]),
+    ];
+    for (id, feed_id, score, embedding) in articles {
+        sqlx::query("INSERT INTO articles (canonical_url, first_seen_at) VALUES (?, '2025-01-01T00:00:00Z')")
+            .bind(format!("http://example.com/{}", id))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, ?)")
+            .bind(id)
+            .bind(feed_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries
+                (article_id, user_id, personalized_headline, personalized_bullets, relevance_score, is_relevant)
+             VALUES (?, 1, 'h', '[]', ?, 1)",
+        )
+        .bind(id)
+        .bind(score)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+        sqlx::query("INSERT INTO vec_articles (article_id, embedding) VALUES (?, ?)")
+            .bind(id)
+            .bind(bytes)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let plain = select_review_articles(&pool, 1, &opts(2, 0.0)).await.unwrap();
+    assert_eq!(plain.iter().map(|a| a.id).collect::<Vec<_>>(), vec![1, 2]);
+
+    let deduped = select_review_articles(&pool, 1, &opts(2, 0.0).with_near_duplicates(Some(0.15)))
+        .await
+        .unwrap();
+    assert_eq!(deduped.iter().map(|a| a.id).collect::<Vec<_>>(), vec![1, 4]);
+    assert_eq!(deduped[0].feed_title.as_deref(), Some("Feed B"));
+    assert_eq!(deduped[0].duplicates.iter().map(|d| d.id).collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(deduped[0].also_covered_by(), vec!["Feed A".to_string(), "Feed C".to_string()]);
+    assert!(deduped[1].duplicates.is_empty());
+}
+
+/// A user back after ten days gets only the top articles of the catch-up
+/// window; older articles are left out of every review.
+#[tokio::test]
+async fn test_catch_up_limit() {
+    let pool = setup_pool().await;
+
+    for stmt in [
+        "CREATE TABLE sessions (id INTEGER PRIMARY KEY AUTOINCREMENT, user_id INTEGER NOT NULL)",
+        "CREATE TABLE summaries (id INTEGER PRIMARY KEY AUTOINCREMENT, session_id INTEGER, summary_text TEXT, created_at TEXT)",
+        "INSERT INTO users (username) VALUES ('frank')",
+        "INSERT INTO feeds (url, title) VALUES ('http://a.example/rss', 'Feed A')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)",
+        "INSERT INTO sessions (user_id) VALUES (1)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+
+    for (i, age) in [(1_i64, "-3 days"), (2, "-8 days"), (3, "-60 days")] {
+        sqlx::query(
+            "INSERT INTO articles (canonical_url, first_seen_at)
+             VALUES (?, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?))",
+        )
+        .bind(format!("http://example.com/{}", i))
+        .bind(age)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, 1)")
+            .bind(i)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries
+                (article_id, user_id, personalized_headline, personalized_bullets, relevance_score, is_relevant)
+             VALUES (?, 1, 'h', '[]', ?, 1)",
+        )
+        .bind(i)
+        .bind(1.0 - i as f64 / 10.0)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let limit = CatchUpLimit { max_age_hours: Some(14 * 24), after_hours: 48, max_articles: 1 };
+    let ids = |articles: Vec<newscope::press_review::ScoredArticle>| articles.iter().map(|a| a.id).collect::<Vec<_>>();
+
+    // Never reviewed: not catching up, but the window still applies
+    assert_eq!(catch_up_status(&pool, 1, &limit).await.unwrap(), None);
+    let selected = select_review_articles(&pool, 1, &opts(10, 0.0).with_catch_up(&limit, None)).await.unwrap();
+    assert_eq!(ids(selected), vec![1, 2]);
+
+    sqlx::query(
+        "INSERT INTO summaries (session_id, summary_text, created_at)
+         VALUES (1, 'old review', strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-10 days'))",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let catch_up = catch_up_status(&pool, 1, &limit).await.unwrap().expect("catching up");
+    assert_eq!((catch_up.away_hours / 24, catch_up.backlog), (10, 2));
+    let selected = select_review_articles(&pool, 1, &opts(10, 0.0).with_catch_up(&limit, Some(&catch_up)))
+        .await
+        .unwrap();
+    assert_eq!(ids(selected), vec![1]);
+
+    // A recent review means the user is not away
+    sqlx::query(
+        "INSERT INTO summaries (session_id, summary_text, created_at)
+         VALUES (1, 'new review', strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-1 hours'))",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    assert_eq!(catch_up_status(&pool, 1, &limit).await.unwrap(), None);
+}
+
+/// The REST digest lists exactly the articles the websocket session would show,
+/// in the same order.
+#[tokio::test]
+async fn test_press_review_uses_shared_selection() {
+    let pool = setup_pool().await;
+
+    sqlx::query("INSERT INTO users (username) VALUES ('dave')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO feeds (url, title) VALUES ('http://a.example/rss', 'Feed A')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    for (i, score, relevant) in [(1_i64, 0.6, 1_i64), (2, 0.9, 1), (3, 0.7, 1), (4, 0.1, 0)] {
+        // Recent: the digest applies the default catch-up window
+        sqlx::query("INSERT INTO articles (canonical_url, first_seen_at) VALUES (?, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))")
+            .bind(format!("http://example.com/{}", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, 1)")
+            .bind(i)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries
+                (article_id, user_id, personalized_headline, personalized_bullets, relevance_score, is_relevant)
+             VALUES (?, 1, ?, '[]', ?, ?)",
+        )
+        .bind(i)
+        .bind(format!("Headline {}", i))
+        .bind(score)
+        .bind(relevant)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    // 60s at 250 wpm sizes the review to the 3-article minimum
+    let selected = select_review_articles(&pool, 1, &ReviewSelectionOptions::for_budget(60, 250, 0.0))
+        .await
+        .unwrap();
+    let ids: Vec<i64> = selected.iter().map(|a| a.id).collect();
+    assert_eq!(ids, vec![2, 3, 1]);
+
+    let digest = generate_press_review(&pool, 1, 60, None, ReviewGroupBy::Source)
+        .await
+        .unwrap()
+        .summary;
+    let headlines: Vec<&str> = digest
+        .lines()
+        .filter_map(|l| l.strip_prefix("## "))
+        .collect();
+    assert_eq!(headlines, vec!["Headline 2", "Headline 3", "Headline 1"]);
+
+    // Grouped by topic: one section per category, in order of its best article
+    for (id, categories) in [(1, r#"["technology"]"#), (2, r#"["technology","economy"]"#), (3, r#"["politics"]"#)] {
+        sqlx::query("INSERT INTO article_summaries (article_id, categories) VALUES (?, ?)")
+            .bind(id)
+            .bind(categories)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    let digest = generate_press_review(&pool, 1, 60, None, ReviewGroupBy::Topic)
+        .await
+        .unwrap()
+        .summary;
+    let headings: Vec<&str> = digest.lines().filter(|l| l.starts_with("##")).collect();
+    assert_eq!(
+        headings,
+        vec!["## Technology", "### Headline 2", "### Headline 1", "## Politics", "### Headline 3"]
+    );
+}
+
+/// A reader who has been reading tech gets the science story first (`w_novel`),
+/// and two stories from one feed are split by another feed's (`w_src`).
+#[tokio::test]
+async fn test_novelty_and_diversity_rerank_selection() {
+    let pool = setup_pool().await;
+
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('gus')",
+        "INSERT INTO feeds (url, title) VALUES ('http://tech.example/rss', 'Tech')",
+        "INSERT INTO feeds (url, title) VALUES ('http://lab.example/rss', 'Lab')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1)",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 2)",
+        // Read this week: two tech articles
+        "INSERT INTO article_summaries (article_id, categories) VALUES (10, '[\"tech\"]'), (11, '[\"tech\"]')",
+        "INSERT INTO user_article_views (user_id, article_id, viewed_at)
+         VALUES (1, 10, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-1 day')),
+                (1, 11, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-2 days'))",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    // (id, feed, category, relevance), all first seen together
+    for (id, feed_id, category, score) in [(1_i64, 1_i64, "tech", 0.9), (2, 1, "tech", 0.88), (3, 2, "science", 0.8)] {
+        sqlx::query("INSERT INTO articles (id, canonical_url, first_seen_at) VALUES (?, ?, '2025-01-01T00:00:00Z')")
+            .bind(id)
+            .bind(format!("http://example.com/{}", id))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_occurrences (article_id, feed_id) VALUES (?, ?)")
+            .bind(id)
+            .bind(feed_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO article_summaries (article_id, categories) VALUES (?, ?)")
+            .bind(id)
+            .bind(format!("[\"{}\"]", category))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_article_summaries
+                (article_id, user_id, personalized_headline, personalized_bullets, relevance_score, is_relevant)
+             VALUES (?, 1, 'h', '[]', ?, 1)",
+        )
+        .bind(id)
+        .bind(score)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let ids = |articles: Vec<newscope::press_review::ScoredArticle>| articles.iter().map(|a| a.id).collect::<Vec<_>>();
+    let ranked = |scoring: Option<ScoringWeights>| {
+        let pool = pool.clone();
+        async move { ids(select_review_articles(&pool, 1, &opts(3, 0.0).with_scoring(scoring)).await.unwrap()) }
+    };
+    assert_eq!(ranked(None).await, vec![1, 2, 3]);
+    // Source diversity only: the second Tech story gives way to Lab
+    assert_eq!(ranked(Some(ScoringWeights { w_novel: 0.0, ..Default::default() })).await, vec![1, 3, 2]);
+    // Novelty: science has not been read lately, tech has
+    assert_eq!(ranked(Some(ScoringWeights::default())).await, vec![3, 1, 2]);
+}
@@ -0,0 +1,65 @@
+//! Fixtures shared by the integration tests: `mod support;` in a test file.
+#![allow(dead_code)]
+
+use newscope::server::{self, AppState};
+use rocket::local::asynchronous::Client;
+use sqlx::SqlitePool;
+use tempfile::TempDir;
+
+/// A database built like a deployed one: the sqlx migrations, then
+/// `ensure_schema`. The file lives in the returned directory, removed on drop.
+pub async fn migrated_db() -> (SqlitePool, TempDir) {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("newscope.db");
+    let pool = common::init_db_pool(path.to_str().unwrap()).await.expect("init pool");
+    common::run_migrations(&pool).await.expect("run migrations");
+    server::ensure_schema(&pool).await.expect("ensure schema");
+    (pool, dir)
+}
+
+/// [`migrated_db`] with the vec0 tables sized for `dim`-dimension test vectors.
+pub async fn migrated_db_with_dim(dim: usize) -> (SqlitePool, TempDir) {
+    let (pool, dir) = migrated_db().await;
+    newscope::maintenance::reset_embeddings(&pool, dim).await.expect("resize vec_articles");
+    newscope::maintenance::resize_user_vectors(&pool, dim).await.expect("resize vec_users");
+    (pool, dir)
+}
+
+/// Application state over `pool`, without configuration, LLMs or worker.
+/// Override fields with `AppState { field, ..support::app_state(pool) }`.
+pub fn app_state(pool: SqlitePool) -> AppState {
+    AppState::new(pool, None)
+}
+
+/// Client for the API routes over `state`.
+pub async fn api_client(state: AppState) -> Client {
+    let rocket = rocket::build().manage(state).mount("/", server::api_routes());
+    Client::untracked(rocket).await.expect("rocket client")
+}
+
+/// Insert a user and return their id with an API key for `X-API-Key`.
+pub async fn user_with_key(pool: &SqlitePool, username: &str) -> (i64, String) {
+    let user_id = sqlx::query("INSERT INTO users (username) VALUES (?)")
+        .bind(username)
+        .execute(pool)
+        .await
+        .expect("insert user")
+        .last_insert_rowid();
+    let (_, key) = newscope::auth::create_api_key(pool, user_id, None, None).await.expect("api key");
+    (user_id, key)
+}
+
+/// `X-API-Key` header for `key`.
+pub fn api_key(key: &str) -> rocket::http::Header<'static> {
+    rocket::http::Header::new("X-API-Key", key.to_string())
+}
+
+/// Token the admin endpoints expect once [`admin_token`] has been called.
+pub const ADMIN_TOKEN: &str = "test-admin-token";
+
+/// `X-Admin-Token` header for the admin endpoints; configures [`ADMIN_TOKEN`]
+/// as the expected token first.
+pub fn admin_token() -> rocket::http::Header<'static> {
+    std::env::set_var(newscope::auth::ADMIN_TOKEN_ENV, ADMIN_TOKEN);
+    rocket::http::Header::new("X-Admin-Token", ADMIN_TOKEN)
+}
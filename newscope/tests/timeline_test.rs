@@ -0,0 +1,20 @@
+use rocket::http::Status;
+
+mod support;
+
+#[tokio::test]
+async fn test_timeline_is_the_callers_own() {
+    let (pool, _db) = support::migrated_db().await;
+    let (alice_id, alice) = support::user_with_key(&pool, "alice").await;
+    let (_, bob) = support::user_with_key(&pool, "bob").await;
+    let client = support::api_client(support::app_state(pool.clone())).await;
+
+    let path = format!("/api/v1/timeline?q=climate&user_id={}", alice_id);
+    assert_eq!(client.get(path.clone()).dispatch().await.status(), Status::Unauthorized);
+    assert_eq!(client.get(path.clone()).header(support::api_key(&bob)).dispatch().await.status(), Status::Forbidden);
+    // Past the checks; no embedding provider to search with here
+    let resp = client.get(path).header(support::api_key(&alice)).dispatch().await;
+    assert_eq!(resp.status(), Status::ServiceUnavailable);
+
+    pool.close().await;
+}
@@ -0,0 +1,58 @@
+use newscope::server::{AppState, LoginResponse};
+use rocket::http::{ContentType, Status};
+
+mod support;
+
+#[derive(serde::Deserialize)]
+struct Claims {
+    sub: i64,
+    exp: i64,
+}
+
+fn claims(token: &str) -> Claims {
+    let key = jsonwebtoken::DecodingKey::from_secret(b"dev-secret");
+    let validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256);
+    jsonwebtoken::decode::<Claims>(token, &key, &validation).unwrap().claims
+}
+
+#[tokio::test]
+async fn test_refresh_issues_token_with_configured_ttl() {
+    let (pool, _db) = support::migrated_db().await;
+
+    let config: common::Config = toml::from_str(
+        "[database]\npath = \"x.db\"\n[scheduler]\ntimes = []\n[auth]\ntoken_ttl_hours = 2",
+    )
+    .unwrap();
+    let client = support::api_client(AppState {
+            config: Some(newscope::config_reload::shared(config)),
+            ..support::app_state(pool.clone())
+        }).await;
+    let post = |path: &'static str, body: String| client.post(path).header(ContentType::JSON).body(body);
+
+    let resp = post("/api/v1/register", r#"{"username": "alice", "password": "secret"}"#.to_string())
+        .dispatch()
+        .await;
+    assert_eq!(resp.status(), Status::Ok);
+    let login: LoginResponse = resp.into_json().await.unwrap();
+    let now = chrono::Utc::now().timestamp();
+    let exp = claims(&login.token).exp;
+    assert!((exp - now - 2 * 3600).abs() < 60, "expiry {} is not ~2h from now", exp - now);
+
+    let refresh = |token: &str| post("/api/v1/refresh", serde_json::json!({ "token": token }).to_string());
+    let resp = refresh(&login.token).dispatch().await;
+    assert_eq!(resp.status(), Status::Ok);
+    let refreshed: LoginResponse = resp.into_json().await.unwrap();
+    assert_eq!(refreshed.user_id, login.user_id);
+    assert_eq!(claims(&refreshed.token).sub, login.user_id);
+
+    assert_eq!(refresh("garbage").dispatch().await.status(), Status::Unauthorized);
+
+    // A revoked token cannot be refreshed
+    let resp = post("/api/v1/logout", serde_json::json!({ "token": login.token }).to_string())
+        .dispatch()
+        .await;
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(refresh(&login.token).dispatch().await.status(), Status::Unauthorized);
+
+    pool.close().await;
+}
@@ -0,0 +1,64 @@
+use rocket::http::{ContentType, Header, Status};
+
+mod support;
+
+#[tokio::test]
+async fn test_unsubscribe_deactivates_orphaned_feed() {
+    let (pool, _db) = support::migrated_db().await;
+
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice'), ('bob')",
+        "INSERT INTO feeds (url) VALUES ('https://a.example/rss')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1), (2, 1)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+
+    let (_, alice) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+    let (_, bob) = newscope::auth::create_api_key(&pool, 2, None, None).await.unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let unsubscribe = |id: i64, key: &str| {
+        client
+            .delete(format!("/api/v1/feeds/{}", id))
+            .header(Header::new("X-API-Key", key.to_string()))
+    };
+    let feed_status = || async {
+        sqlx::query_scalar::<_, Option<String>>("SELECT status FROM feeds WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+
+    // Bob cannot remove Alice's subscription, and unknown ids are not found
+    let resp = unsubscribe(1, &bob).dispatch().await;
+    assert_eq!(resp.status(), Status::NotFound);
+    let resp = unsubscribe(99, &alice).dispatch().await;
+    assert_eq!(resp.status(), Status::NotFound);
+    let resp = client.delete("/api/v1/feeds/1").dispatch().await;
+    assert_eq!(resp.status(), Status::Unauthorized);
+
+    let resp = unsubscribe(1, &alice).dispatch().await;
+    assert_eq!(resp.status(), Status::NoContent);
+    assert_eq!(feed_status().await, None);
+
+    // The last subscriber leaving keeps the feed but stops polling it
+    let resp = unsubscribe(2, &bob).dispatch().await;
+    assert_eq!(resp.status(), Status::NoContent);
+    assert_eq!(feed_status().await.as_deref(), Some("inactive"));
+    let resp = unsubscribe(2, &bob).dispatch().await;
+    assert_eq!(resp.status(), Status::NotFound);
+
+    // Subscribing again resumes it
+    let resp = client
+        .post("/api/v1/feeds")
+        .header(Header::new("X-API-Key", alice.clone()))
+        .header(ContentType::JSON)
+        .body(r#"{"url": "https://a.example/rss"}"#)
+        .dispatch()
+        .await;
+    assert_eq!(resp.status(), Status::Ok);
+    assert_eq!(feed_status().await, None);
+
+    pool.close().await;
+}
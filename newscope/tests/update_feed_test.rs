@@ -0,0 +1,73 @@
+use rocket::http::{ContentType, Status};
+
+mod support;
+
+#[tokio::test]
+async fn test_update_feed_only_touches_the_callers_subscription() {
+    let (pool, _db) = support::migrated_db().await;
+    let (_, alice) = support::user_with_key(&pool, "alice").await;
+    let (_, bob) = support::user_with_key(&pool, "bob").await;
+    let (_, carol) = support::user_with_key(&pool, "carol").await;
+    for stmt in [
+        "INSERT INTO feeds (url) VALUES ('https://a.example/rss')",
+        "INSERT INTO subscriptions (user_id, feed_id) VALUES (1, 1), (2, 1)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let update = |key: &str, body: &'static str| {
+        client
+            .patch("/api/v1/feeds/1")
+            .header(support::api_key(key))
+            .header(ContentType::JSON)
+            .body(body)
+            .dispatch()
+    };
+    let weights = || async {
+        sqlx::query_scalar::<_, i64>("SELECT weight FROM subscriptions ORDER BY user_id")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+    };
+    let full_content = || async {
+        sqlx::query_scalar::<_, bool>("SELECT full_content FROM feeds WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+
+    let resp = client.patch("/api/v1/feeds/1").header(ContentType::JSON).body(r#"{"weight": 3}"#).dispatch().await;
+    assert_eq!(resp.status(), Status::Unauthorized);
+    // A user_id in the body can't name someone else
+    assert_eq!(update(&bob, r#"{"user_id": 1, "weight": 3}"#).await.status(), Status::Forbidden);
+    assert_eq!(update(&carol, r#"{"weight": 3}"#).await.status(), Status::NotFound);
+    assert_eq!(update(&bob, r#"{"weight": 3}"#).await.status(), Status::NoContent);
+    assert_eq!(weights().await, vec![0, 3]);
+
+    // full_content applies to every subscriber: admin only
+    assert_eq!(update(&alice, r#"{"full_content": true}"#).await.status(), Status::Forbidden);
+    assert!(!full_content().await);
+    let resp = client
+        .patch("/api/v1/feeds/1")
+        .header(support::api_key(&alice))
+        .header(support::admin_token())
+        .header(ContentType::JSON)
+        .body(r#"{"full_content": true}"#)
+        .dispatch()
+        .await;
+    assert_eq!(resp.status(), Status::NoContent);
+    assert!(full_content().await);
+
+    // Subscribing to the existing feed doesn't change it either
+    let resp = client
+        .post("/api/v1/feeds")
+        .header(support::api_key(&carol))
+        .header(ContentType::JSON)
+        .body(r#"{"url": "https://a.example/rss", "full_content": false}"#)
+        .dispatch()
+        .await;
+    assert_eq!(resp.status(), Status::Ok);
+    assert!(full_content().await);
+
+    pool.close().await;
+}
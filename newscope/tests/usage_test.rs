@@ -0,0 +1,118 @@
+use newscope::llm::usage::{aggregate_usage, log_usage, UsageEntry};
+use newscope::llm::UsageMetadata;
+
+mod support;
+
+#[tokio::test]
+async fn test_chat_usage_is_aggregated() {
+    let (pool, _db) = support::migrated_db().await;
+
+    let usage = UsageMetadata {
+        prompt_tokens: 120,
+        completion_tokens: 30,
+        total_tokens: 150,
+    };
+    for _ in 0..2 {
+        log_usage(&pool, UsageEntry {
+            operation: "chat",
+            model: "phi4",
+            usage: Some(&usage),
+            error_message: None,
+            session_id: Some(1),
+        })
+        .await
+        .expect("log usage");
+    }
+    log_usage(&pool, UsageEntry {
+        operation: "chat",
+        model: "phi4",
+        usage: None,
+        error_message: Some("timeout"),
+        session_id: Some(1),
+    })
+    .await
+    .expect("log failure");
+
+    let rows = aggregate_usage(&pool, 1).await.expect("aggregate");
+    assert_eq!(rows.len(), 1);
+    let chat = &rows[0];
+    assert_eq!(chat.operation.as_deref(), Some("chat"));
+    assert_eq!(chat.calls, 3);
+    assert_eq!(chat.failures, 1);
+    assert_eq!(chat.prompt_tokens, 240);
+    assert_eq!(chat.completion_tokens, 60);
+
+}
+
+/// Embeds every text except those mentioning "broken".
+struct FlakyEmbedder;
+
+#[async_trait::async_trait]
+impl newscope::llm::LlmProvider for FlakyEmbedder {
+    async fn generate(&self, _request: newscope::llm::LlmRequest) -> anyhow::Result<newscope::llm::LlmResponse> {
+        anyhow::bail!("not used")
+    }
+    async fn summarize(&self, _content: &str, _language: Option<&str>, _max_tokens: usize) -> anyhow::Result<newscope::llm::Summary> {
+        anyhow::bail!("not used")
+    }
+    async fn embed(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+        if text.contains("broken") {
+            anyhow::bail!("embedding backend unavailable");
+        }
+        Ok(vec![0.5, 0.5])
+    }
+}
+
+#[tokio::test]
+async fn test_background_calls_are_logged_and_served() {
+    use rocket::http::Status;
+    
+    use std::sync::Arc;
+
+    let (pool, _db) = support::migrated_db_with_dim(2).await;
+    for (i, title) in ["Fine", "Also fine", "broken"].iter().enumerate() {
+        sqlx::query("INSERT INTO articles (canonical_url, title, content) VALUES (?, ?, 'Body')")
+            .bind(format!("https://example.com/{}", i))
+            .bind(title)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    sqlx::query("INSERT INTO llm_usage_log (operation, model, prompt_tokens, completion_tokens, created_at) VALUES ('summarize', 'old', 10, 10, '2020-01-01T00:00:00Z')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let text = newscope::processing::EmbeddingText::default();
+    let embedded = newscope::processing::process_missing_embeddings(&pool, Arc::new(FlakyEmbedder), "embedder", 10, 2, 2, &text)
+        .await
+        .unwrap();
+    assert_eq!(embedded, 2);
+    let error: String = sqlx::query_scalar("SELECT error_message FROM llm_usage_log WHERE success = 0")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(error, "embedding backend unavailable");
+
+    let state = support::app_state(pool.clone());
+    let client = support::api_client(state).await;
+    let get = |uri: &'static str| client.get(uri).header(support::admin_token()).dispatch();
+
+    let rows: Vec<newscope::llm::usage::UsageAggregate> =
+        get("/api/v1/usage?since=2021-01-01").await.into_json().await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].operation.as_deref(), Some("embed"));
+    assert_eq!(rows[0].model.as_deref(), Some("embedder"));
+    assert_eq!((rows[0].calls, rows[0].failures), (3, 1));
+
+    let rows: Vec<newscope::llm::usage::UsageAggregate> =
+        get("/api/v1/usage?since=2019-12-31T00:00:00Z").await.into_json().await.unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].total_tokens, 20);
+
+    assert_eq!(get("/api/v1/usage?since=yesterday").await.status(), Status::BadRequest);
+
+    assert_eq!(client.get("/api/v1/usage").dispatch().await.status(), Status::Unauthorized);
+
+    pool.close().await;
+}
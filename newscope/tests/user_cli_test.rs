@@ -0,0 +1,86 @@
+use argon2::password_hash::{PasswordHash, PasswordVerifier};
+use argon2::Argon2;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+fn newscope(config: &str, args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_newscope"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env_remove(newscope::server::JWT_SECRET_ENV)
+        .args(["--config", config, "--log-level", "warn"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("run newscope");
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().expect("wait for newscope")
+}
+
+fn write_config(dir: &Path, db_path: &Path) -> String {
+    let path = dir.join("config.toml");
+    std::fs::write(
+        &path,
+        format!("[database]\npath = \"{}\"\n[scheduler]\ntimes = []\n", db_path.display()),
+    )
+    .unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[tokio::test]
+async fn test_user_add_and_list() {
+    std::env::remove_var(newscope::server::JWT_SECRET_ENV);
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("newscope.db");
+    let config = write_config(dir.path(), &db_path);
+    stdout(&newscope(&config, &["migrate"], ""));
+
+    let bob = newscope(
+        &config,
+        &["user", "add", "--username", "bob", "--display-name", "Bob B", "--password", "s3cret"],
+        "",
+    );
+    let bob_token = stdout(&bob).trim().to_string();
+    // Without --password, the first line of stdin is the password
+    let carol_token = stdout(&newscope(&config, &["user", "add", "--username", "carol"], "hunter2\n"))
+        .trim()
+        .to_string();
+
+    let pool = common::init_db_pool(db_path.to_str().unwrap()).await.unwrap();
+    let bob_id: i64 = sqlx::query_scalar("SELECT id FROM users WHERE username = 'bob'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(newscope::auth::verify_jwt(&pool, &bob_token).await, Some(bob_id));
+    let (carol_id, hash): (i64, String) =
+        sqlx::query_as("SELECT id, password_hash FROM users WHERE username = 'carol'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(newscope::auth::verify_jwt(&pool, &carol_token).await, Some(carol_id));
+    let hash = PasswordHash::new(&hash).unwrap();
+    assert!(Argon2::default().verify_password(b"hunter2", &hash).is_ok());
+    let language: String = sqlx::query_scalar("SELECT language FROM user_profiles WHERE user_id = ?")
+        .bind(carol_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(language, "en");
+
+    let taken = newscope(&config, &["user", "add", "--username", "bob", "--password", "other"], "");
+    assert!(!taken.status.success());
+    assert!(taken.stdout.is_empty());
+
+    let list = stdout(&newscope(&config, &["user", "list"], ""));
+    let rows: Vec<Vec<&str>> = list.lines().map(|l| l.split('\t').collect()).collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0][..3], [bob_id.to_string().as_str(), "bob", "Bob B"]);
+    assert_eq!(rows[1][..3], [carol_id.to_string().as_str(), "carol", ""]);
+}
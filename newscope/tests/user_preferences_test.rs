@@ -0,0 +1,90 @@
+use newscope::personalization::UserPreferences;
+use rocket::http::{ContentType, Header, Status};
+
+mod support;
+
+#[tokio::test]
+async fn test_preferences_round_trip_and_validation() {
+    let (pool, _db) = support::migrated_db().await;
+    for stmt in [
+        "INSERT INTO users (username) VALUES ('alice')",
+        "INSERT INTO users (username) VALUES ('bob')",
+        "INSERT INTO user_preferences (user_id, preference_type, preference_key, preference_value) VALUES (1, 'category_filter', 'sports', 1.0)",
+        "INSERT INTO user_preferences (user_id, preference_type, preference_key, preference_value) VALUES (1, 'setting', 'auto_reading_speed', 1.0)",
+    ] {
+        sqlx::query(stmt).execute(&pool).await.unwrap();
+    }
+    let (_, key) = newscope::auth::create_api_key(&pool, 1, None, None).await.unwrap();
+
+    let client = support::api_client(support::app_state(pool.clone())).await;
+    let put = |path: &str, body: &str| {
+        client
+            .put(path.to_string())
+            .header(ContentType::JSON)
+            .header(Header::new("X-API-Key", key.clone()))
+            .body(body)
+    };
+
+    // Defaults before any profile row exists
+    let prefs: UserPreferences = client
+        .get("/api/v1/users/1/preferences")
+        .header(Header::new("X-API-Key", key.clone()))
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    assert_eq!((prefs.language.as_str(), prefs.reading_speed), ("en", 250));
+    assert_eq!(prefs.preferred_categories, vec!["sports".to_string()]);
+
+    assert_eq!(client.get("/api/v1/users/1/preferences").dispatch().await.status(), Status::Unauthorized);
+    assert_eq!(put("/api/v1/users/2/preferences", "{}").dispatch().await.status(), Status::Forbidden);
+    for invalid in [
+        r#"{"reading_speed": 20}"#,
+        r#"{"complexity_level": "expert"}"#,
+        r#"{"language": "français"}"#,
+        r#"{"interests": [" "]}"#,
+        r#"{"category_filter": {"politics": 2.0}}"#,
+        r#"{"category_filter": {"politics": -1.5}}"#,
+    ] {
+        assert_eq!(put("/api/v1/users/1/preferences", invalid).dispatch().await.status(), Status::BadRequest, "{}", invalid);
+    }
+
+    let resp = put(
+        "/api/v1/users/1/preferences",
+        r#"{"language": "fr", "reading_speed": 300, "interests": ["AI", "ai", " climate "],
+            "preferred_categories": ["technology", "science"], "category_filter": {"science": 0.5, "politics": -1.0}}"#,
+    )
+    .dispatch()
+    .await;
+    assert_eq!(resp.status(), Status::Ok);
+    let prefs: UserPreferences = resp.into_json().await.unwrap();
+    assert_eq!(prefs.language, "fr");
+    assert_eq!(prefs.complexity_level, "medium");
+    assert_eq!(prefs.reading_speed, 300);
+    assert_eq!(prefs.interests, vec!["AI".to_string(), "climate".to_string()]);
+    assert_eq!(prefs.category_filter.get("science"), Some(&0.5));
+    assert_eq!(prefs.category_filter.get("politics"), Some(&-1.0));
+    assert!(!prefs.category_filter.contains_key("sports"));
+
+    // What personalization reads back
+    let profile = newscope::personalization::get_user_profile(&pool, 1).await.unwrap();
+    let mut preferred = profile.preferred_categories.clone();
+    preferred.sort();
+    assert_eq!(preferred, vec!["science".to_string(), "technology".to_string()]);
+    assert_eq!(profile.interests, prefs.interests);
+
+    // A partial update keeps the other fields and the category weights
+    let prefs: UserPreferences = put("/api/v1/users/1/preferences", r#"{"complexity_level": "advanced"}"#)
+        .dispatch()
+        .await
+        .into_json()
+        .await
+        .unwrap();
+    assert_eq!((prefs.language.as_str(), prefs.complexity_level.as_str()), ("fr", "advanced"));
+    assert_eq!(prefs.category_filter.len(), 3);
+    // Other preference types are left alone
+    assert!(newscope::reading_speed::is_enabled(&pool, 1).await.unwrap());
+
+    pool.close().await;
+}
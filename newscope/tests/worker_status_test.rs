@@ -0,0 +1,42 @@
+use newscope::server::AppState;
+use rocket::http::Status;
+use serde_json::{json, Value};
+
+mod support;
+
+#[tokio::test]
+async fn test_worker_status_endpoint() {
+    let (pool, _db) = support::migrated_db().await;
+
+    let state = |worker| AppState {
+        worker,
+        ..support::app_state(pool.clone())
+    };
+    let get_status = |state: AppState| async {
+        let client = support::api_client(state).await;
+        let res = client.get("/api/v1/worker/status").dispatch().await;
+        assert_eq!(res.status(), Status::Ok);
+        res.into_json::<Value>().await.unwrap()
+    };
+
+    let worker = newscope::worker_status::shared();
+    let polled_at = chrono::Utc::now();
+    newscope::worker_status::update(&worker, |w| {
+        w.last_loop_at = Some(polled_at);
+        w.last_successful_poll_at = Some(polled_at);
+        w.feeds_due = 3;
+        w.processing_article_ids.extend([7, 2]);
+    });
+    let status = get_status(state(Some(worker.clone()))).await;
+    assert_eq!(status["enabled"], json!(true));
+    assert_eq!(status["feeds_due"], json!(3));
+    assert_eq!(status["processing_article_ids"], json!([2, 7]));
+    assert_eq!(status["last_successful_poll_at"], json!(polled_at));
+
+    // --no-worker
+    let status = get_status(state(None)).await;
+    assert_eq!(status["enabled"], json!(false));
+    assert_eq!(status["last_loop_at"], Value::Null);
+    assert_eq!(status["processing_article_ids"], json!([]));
+
+}